@@ -0,0 +1,177 @@
+//! Scalar multiplication with the GLV method.
+//!
+//! Some short Weierstrass curves (those with `a = 0` and a base field with cube
+//! roots of unity, such as the G1 groups of the BLS12 curves) have a cheap
+//! endomorphism `φ(x, y) = (βx, y)`, which acts on the prime-order subgroup as
+//! multiplication by a scalar `λ`. A scalar `k` then splits into `k1 + k2 λ`,
+//! with `k1` and `k2` about half as long as `k`, and `kP = k1 P + k2 φ(P)`: a
+//! multiplication needs half the doublings, and an MSM over `n` bases
+//! becomes one over `2n` bases with scalars of half the length, which needs
+//! half the windows.
+//!
+//! Outside the subgroup, `φ` is not multiplication by `λ`, and the results are
+//! wrong. So curves that opt in through
+//! [SWModelParameters::glv](crate::SWModelParameters::glv) use it for MSMs,
+//! whose bases are keys and commitments in the subgroup, but not for single
+//! multiplications, which check subgroup membership and clear cofactors too.
+//! Callers that know their point is in the subgroup can use
+//! [GLVParameters::mul].
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+use ark_std::vec::Vec;
+
+use crate::{
+    models::{
+        short_weierstrass_jacobian::{GroupAffine, GroupProjective},
+        SWModelParameters,
+    },
+    msm::VariableBaseMSM,
+    AffineCurve, ProjectiveCurve,
+};
+
+/// The endomorphism of a curve, and what it takes to split scalars for it.
+///
+/// Signed integers are stored as `(is_negative, magnitude)`.
+pub struct GLVParameters<P: SWModelParameters> {
+    /// `β`, a cube root of unity in the base field.
+    pub endo_coeff: P::BaseField,
+    /// `λ`, with `φ(P) = λP` on the prime-order subgroup.
+    pub lambda: P::ScalarField,
+    /// `[a1, b1, a2, b2]`, a short basis `(a1, b1), (a2, b2)` of the lattice of
+    /// `(a, b)` with `a + bλ = 0 mod r`.
+    pub scalar_decomp_coeffs: [(bool, u128); 4],
+    /// `[g1, g2]`, where `g1 = round(2^shift b2 / d)` and `g2 = round(-2^shift
+    /// b1 / d)`, for the determinant `d = a1 b2 - a2 b1` of the basis.
+    pub scalar_decomp_rounding: [(bool, u128); 2],
+    pub shift: u32,
+}
+
+impl<P: SWModelParameters> GLVParameters<P> {
+    /// `φ(p)`.
+    pub fn endomorphism(&self, p: &GroupAffine<P>) -> GroupAffine<P> {
+        if p.infinity {
+            *p
+        } else {
+            GroupAffine::new(p.x * &self.endo_coeff, p.y, false)
+        }
+    }
+
+    /// `(k1, k2)`, with `k = k1 + k2 λ`, and both about half as long as `r`.
+    ///
+    /// `(c1, c2)`, the rounded coordinates of `(k, 0)` in the lattice basis,
+    /// give a close lattice point `c1 (a1, b1) + c2 (a2, b2)`, and `(k1,
+    /// k2)` is what is left.
+    pub fn decompose(&self, k: &P::ScalarField) -> (P::ScalarField, P::ScalarField) {
+        let repr = k.into_repr();
+        let [a1, b1, a2, b2] = self.scalar_decomp_coeffs;
+        let [g1, g2] = self.scalar_decomp_rounding;
+        let c1 = signed::<P::ScalarField>((g1.0, mul_shift(repr.as_ref(), g1.1, self.shift)));
+        let c2 = signed::<P::ScalarField>((g2.0, mul_shift(repr.as_ref(), g2.1, self.shift)));
+        let k1 = *k - c1 * signed::<P::ScalarField>(a1) - c2 * signed::<P::ScalarField>(a2);
+        let k2 = -(c1 * signed::<P::ScalarField>(b1)) - c2 * signed::<P::ScalarField>(b2);
+        (k1, k2)
+    }
+
+    /// `kp`, for `p` in the prime-order subgroup.
+    pub fn mul(&self, p: &GroupAffine<P>, k: &P::ScalarField) -> GroupProjective<P> {
+        let (k1, k2) = self.decompose(k);
+        let (p1, k1) = short(*p, k1);
+        let (p2, k2) = short(self.endomorphism(p), k2);
+        let mut both = p1.into_projective();
+        both.add_assign_mixed(&p2);
+        let bits = k1.num_bits().max(k2.num_bits()) as usize;
+        let mut res = GroupProjective::zero();
+        for i in (0..bits).rev() {
+            res.double_in_place();
+            match (k1.get_bit(i), k2.get_bit(i)) {
+                (true, true) => res += &both,
+                (true, false) => res.add_assign_mixed(&p1),
+                (false, true) => res.add_assign_mixed(&p2),
+                (false, false) => {},
+            }
+        }
+        res
+    }
+
+    /// `\sum_i s_i g_i`, for bases in the prime-order subgroup.
+    pub fn multi_scalar_mul(
+        &self,
+        bases: &[GroupAffine<P>],
+        scalars: &[P::ScalarField],
+    ) -> GroupProjective<P> {
+        let size = ark_std::cmp::min(bases.len(), scalars.len());
+        let mut split_bases = Vec::with_capacity(2 * size);
+        let mut split_scalars = Vec::with_capacity(2 * size);
+        for (b, s) in bases.iter().zip(scalars) {
+            let (k1, k2) = self.decompose(s);
+            let (b1, k1) = short(*b, k1);
+            let (b2, k2) = short(self.endomorphism(b), k2);
+            split_bases.push(b1);
+            split_bases.push(b2);
+            split_scalars.push(k1);
+            split_scalars.push(k2);
+        }
+        let bits = split_scalars
+            .iter()
+            .map(|s| s.num_bits())
+            .max()
+            .unwrap_or(0) as usize;
+        VariableBaseMSM::multi_scalar_mul_batch_affine(&split_bases, &split_scalars, bits)
+    }
+}
+
+/// `kp` as `|k| (±p)`, where `k` is taken to be negative if its representative
+/// exceeds `r/2`.
+fn short<P: SWModelParameters>(
+    p: GroupAffine<P>,
+    k: P::ScalarField,
+) -> (GroupAffine<P>, <P::ScalarField as PrimeField>::BigInt) {
+    let neg = -k;
+    if neg.into_repr() < k.into_repr() {
+        (-p, neg.into_repr())
+    } else {
+        (p, k.into_repr())
+    }
+}
+
+fn signed<F: Field>((negative, magnitude): (bool, u128)) -> F {
+    let f = F::from(magnitude);
+    if negative {
+        -f
+    } else {
+        f
+    }
+}
+
+/// `round(k g / 2^shift)`, for `shift >= 1`, truncated to 128 bits.
+fn mul_shift(k: &[u64], g: u128, shift: u32) -> u128 {
+    let g = [g as u64, (g >> 64) as u64];
+    let mut product = vec![0u64; k.len() + 3];
+    for (i, &ki) in k.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &gj) in g.iter().enumerate() {
+            let t = product[i + j] as u128 + ki as u128 * gj as u128 + carry;
+            product[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        product[i + 2] = carry as u64;
+    }
+    // add 2^{shift - 1}, to round
+    let (limb, bit) = (((shift - 1) / 64) as usize, (shift - 1) % 64);
+    let mut carry = 1u64 << bit;
+    for l in &mut product[limb..] {
+        let (sum, overflow) = l.overflowing_add(carry);
+        *l = sum;
+        if !overflow {
+            break;
+        }
+        carry = 1;
+    }
+    let (limb, bit) = ((shift / 64) as usize, shift % 64);
+    let word = |i: usize| product.get(i).copied().unwrap_or(0) as u128;
+    let low = word(limb) | (word(limb + 1) << 64);
+    if bit == 0 {
+        low
+    } else {
+        (low >> bit) | (word(limb + 2) << (128 - bit))
+    }
+}
@@ -4,6 +4,7 @@ use core::{
     ops::{Add, AddAssign, MulAssign, Neg, Sub, SubAssign},
 };
 use num_traits::Zero;
+use zeroize::Zeroize;
 
 use ark_ff::{
     bytes::{FromBytes, ToBytes},
@@ -47,6 +48,7 @@ pub trait Group:
     + mpc_trait::MpcWire
     + CanonicalSerialize
     + CanonicalDeserialize
+    + Zeroize
 {
     type ScalarField: PrimeField;
     // type ScalarField: PrimeField + Into<<Self::ScalarField as PrimeField>::BigInt>;
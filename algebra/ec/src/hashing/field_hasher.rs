@@ -0,0 +1,79 @@
+//! Hashing to field elements: RFC 9380's `expand_message_xmd` (section 5.3.1),
+//! with SHA-256, and `hash_to_field` (section 5.2).
+use ark_ff::{Field, FpParameters, PrimeField};
+use ark_std::vec::Vec;
+use sha2::{Digest, Sha256};
+
+use super::HashToCurveError;
+
+/// The output size of SHA-256, in bytes.
+const B_IN_BYTES: usize = 32;
+/// The input block size of SHA-256, in bytes.
+const R_IN_BYTES: usize = 64;
+/// The security level, in bits, of the field elements' distance from uniform.
+const SECURITY_BITS: usize = 128;
+
+/// `len` pseudorandom bytes from `msg`, under the domain separation tag `dst`.
+///
+/// A tag of over 255 bytes is first hashed, as the RFC prescribes.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len: usize) -> Result<Vec<u8>, HashToCurveError> {
+    let ell = len.div_ceil(B_IN_BYTES);
+    if ell > 255 || len > 0xffff {
+        return Err(HashToCurveError::OutputTooLong(len));
+    }
+    let long_dst;
+    let dst = if dst.len() > 255 {
+        long_dst = Sha256::new()
+            .chain(b"H2C-OVERSIZE-DST-")
+            .chain(dst)
+            .finalize();
+        &long_dst[..]
+    } else {
+        dst
+    };
+    let dst_prime = |h: Sha256| h.chain(dst).chain([dst.len() as u8]);
+
+    let b_0 = dst_prime(
+        Sha256::new()
+            .chain([0u8; R_IN_BYTES])
+            .chain(msg)
+            .chain((len as u16).to_be_bytes())
+            .chain([0u8]),
+    )
+    .finalize();
+    let mut b_i = dst_prime(Sha256::new().chain(b_0).chain([1u8])).finalize();
+    let mut out = Vec::with_capacity(ell * B_IN_BYTES);
+    out.extend_from_slice(&b_i);
+    for i in 2..=ell {
+        let mixed: Vec<u8> = b_0.iter().zip(&b_i).map(|(a, b)| a ^ b).collect();
+        b_i = dst_prime(Sha256::new().chain(&mixed).chain([i as u8])).finalize();
+        out.extend_from_slice(&b_i);
+    }
+    out.truncate(len);
+    Ok(out)
+}
+
+/// `count` elements of `F` from `msg`, under the domain separation tag `dst`.
+///
+/// Each coordinate over the prime field comes from `ceil((log p + 128) / 8)`
+/// bytes, so that it is within `2^-128` of uniform.
+pub fn hash_to_field<F: Field>(
+    msg: &[u8],
+    dst: &[u8],
+    count: usize,
+) -> Result<Vec<F>, HashToCurveError> {
+    let m = F::extension_degree() as usize;
+    let modulus_bits = <F::BasePrimeField as PrimeField>::Params::MODULUS_BITS as usize;
+    let l = (modulus_bits + SECURITY_BITS).div_ceil(8);
+    let bytes = expand_message_xmd(msg, dst, count * m * l)?;
+    Ok(bytes
+        .chunks(m * l)
+        .map(|element| {
+            let coords: Vec<F::BasePrimeField> = element
+                .chunks(l)
+                .map(F::BasePrimeField::from_be_bytes_mod_order)
+                .collect();
+            F::from_base_prime_field_elems(&coords).unwrap()
+        })
+        .collect())
+}
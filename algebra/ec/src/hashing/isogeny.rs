@@ -0,0 +1,55 @@
+//! Isogenies between short Weierstrass curves, to take the SWU map's points to
+//! curves with `a = 0`.
+use ark_ff::{BitIteratorBE, Field, Zero};
+
+use super::swu::SWUParams;
+use crate::models::{short_weierstrass_jacobian::GroupAffine, SWModelParameters};
+
+/// A curve the SWU map can reach through an isogeny from
+/// [WBParams::IsogenousCurve]: `(x, y) -> (x_num(x) / x_den(x), y y_num(x) /
+/// y_den(x))`.
+///
+/// The coefficients of each polynomial are listed from the constant one up.
+pub trait WBParams: SWModelParameters + Sized {
+    /// The curve the SWU map maps to, with `a b != 0`.
+    type IsogenousCurve: SWUParams<BaseField = Self::BaseField, ScalarField = Self::ScalarField>;
+
+    const PHI_X_NOM: &'static [Self::BaseField];
+    const PHI_X_DEN: &'static [Self::BaseField];
+    const PHI_Y_NOM: &'static [Self::BaseField];
+    const PHI_Y_DEN: &'static [Self::BaseField];
+
+    /// The scalar, in 64-bit limbs from the least significant up (as in
+    /// `COFACTOR`), that [WBParams::clear_cofactor] multiplies by: the
+    /// cofactor, or another that maps the curve onto the subgroup, such as
+    /// the RFC's `h_eff`.
+    const H_EFF: &'static [u64] = Self::COFACTOR;
+
+    /// The image of `p`; the isogeny's kernel goes to the point at infinity.
+    fn isogeny_map(p: &GroupAffine<Self::IsogenousCurve>) -> GroupAffine<Self> {
+        if p.infinity {
+            return GroupAffine::zero();
+        }
+        let eval = |coeffs: &[Self::BaseField]| {
+            coeffs
+                .iter()
+                .rev()
+                .fold(Self::BaseField::zero(), |acc, c| acc * p.x + c)
+        };
+        let x_den = eval(Self::PHI_X_DEN);
+        let y_den = eval(Self::PHI_Y_DEN);
+        match (x_den.inverse(), y_den.inverse()) {
+            (Some(x_den), Some(y_den)) => GroupAffine::new(
+                eval(Self::PHI_X_NOM) * x_den,
+                p.y * eval(Self::PHI_Y_NOM) * y_den,
+                false,
+            ),
+            _ => GroupAffine::zero(),
+        }
+    }
+
+    /// A point of the prime-order subgroup for `p`, `H_EFF p`.
+    fn clear_cofactor(p: &GroupAffine<Self>) -> GroupAffine<Self> {
+        p.mul_bits(BitIteratorBE::new(Self::H_EFF)).into()
+    }
+}
@@ -0,0 +1,108 @@
+//! Hashing to short Weierstrass curves, as in [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380).
+//!
+//! [MapToCurveBasedHasher] implements the RFC's `hash_to_curve` with
+//! `expand_message_xmd` over SHA-256, the simplified SWU map and a cofactor
+//! clearing, for the random oracle suites (the ones whose IDs end in `_RO_`). A
+//! message and a domain separation tag go to two elements of the base field
+//! ([field_hasher]), each goes to a point by the SWU map ([swu]), and the sum
+//! of the two goes to the prime-order subgroup.
+//!
+//! The SWU map needs a curve with `a b != 0`, which the pairing-friendly
+//! curves, with `a = 0`, are not, so it maps to an isogenous curve with `a b !=
+//! 0`, and an isogeny ([isogeny]) takes the point over. Curves opt in by
+//! implementing [WBParams] (for Wahby and Boneh, who gave this construction),
+//! with the isogenous curve and the isogeny.
+//!
+//! The outputs are independent, uniformly distributed points whose discrete
+//! logs nobody knows, such as the bases of Pedersen commitments: [derive_bases]
+//! derives any number of them from a tag. The maps are not constant time, so
+//! the message should be public.
+use ark_std::{fmt, vec::Vec};
+
+use crate::models::short_weierstrass_jacobian::GroupAffine;
+
+pub mod field_hasher;
+pub mod isogeny;
+pub mod swu;
+
+pub use self::{
+    field_hasher::{expand_message_xmd, hash_to_field},
+    isogeny::WBParams,
+    swu::SWUParams,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HashToCurveError {
+    /// The SWU map needs `a b != 0`; use the curve's [WBParams] instead.
+    UnsupportedCurve,
+    /// `expand_message_xmd` gives at most 255 blocks of 32 bytes.
+    OutputTooLong(usize),
+}
+
+impl fmt::Display for HashToCurveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashToCurveError::UnsupportedCurve => {
+                write!(f, "the simplified SWU map needs a curve with a b != 0")
+            },
+            HashToCurveError::OutputTooLong(n) => {
+                write!(f, "expand_message_xmd can't give {} bytes", n)
+            },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HashToCurveError {}
+
+/// A hash to the prime-order subgroup of a curve.
+pub trait HashToCurve<P: WBParams>: Sized {
+    /// The hash with the domain separation tag `dst`.
+    fn new(dst: &[u8]) -> Result<Self, HashToCurveError>;
+
+    /// The point for `msg`.
+    fn hash(&self, msg: &[u8]) -> Result<GroupAffine<P>, HashToCurveError>;
+}
+
+/// RFC 9380's `hash_to_curve`, for a curve with [WBParams].
+///
+/// With the suite's tag (say
+/// `"MY-APP-V01-CS01-with-BLS12381G1_XMD:SHA-256_SSWU_RO_"`), a BLS12-381 hash
+/// gives the RFC's `BLS12381G1_XMD:SHA-256_SSWU_RO_` and `BLS12381G2_XMD:
+/// SHA-256_SSWU_RO_` suites, test vectors and all.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MapToCurveBasedHasher<P: WBParams> {
+    dst: Vec<u8>,
+    _params: ark_std::marker::PhantomData<P>,
+}
+
+impl<P: WBParams> HashToCurve<P> for MapToCurveBasedHasher<P> {
+    fn new(dst: &[u8]) -> Result<Self, HashToCurveError> {
+        if !swu::supported::<P::IsogenousCurve>() {
+            return Err(HashToCurveError::UnsupportedCurve);
+        }
+        Ok(Self {
+            dst: dst.to_vec(),
+            _params: ark_std::marker::PhantomData,
+        })
+    }
+
+    fn hash(&self, msg: &[u8]) -> Result<GroupAffine<P>, HashToCurveError> {
+        let u = hash_to_field::<P::BaseField>(msg, &self.dst, 2)?;
+        let q0 = P::isogeny_map(&swu::map_to_curve::<P::IsogenousCurve>(u[0]));
+        let q1 = P::isogeny_map(&swu::map_to_curve::<P::IsogenousCurve>(u[1]));
+        Ok(P::clear_cofactor(&(q0 + q1)))
+    }
+}
+
+/// `n` points hashed to the curve under the tag `domain_tag`, the `i`th from
+/// the message `i`, as 8 big-endian bytes.
+pub fn derive_bases<P: WBParams>(
+    domain_tag: &[u8],
+    n: usize,
+) -> Result<Vec<GroupAffine<P>>, HashToCurveError> {
+    let hasher = MapToCurveBasedHasher::<P>::new(domain_tag)?;
+    (0..n as u64)
+        .map(|i| hasher.hash(&i.to_be_bytes()))
+        .collect()
+}
@@ -0,0 +1,57 @@
+//! The simplified Shallue–van de Woestijne–Ulas map, RFC 9380's
+//! `map_to_curve_simple_swu` (section 6.6.2), for curves `y^2 = x^3 + ax + b`
+//! with `a b != 0`.
+use ark_ff::{Field, One, SquareRootField, Zero};
+use ark_std::vec::Vec;
+
+use crate::models::{short_weierstrass_jacobian::GroupAffine, SWModelParameters};
+
+/// A curve the SWU map can map to.
+pub trait SWUParams: SWModelParameters {
+    /// `Z`, a non-square in the base field with `Z != -1`, `x^3 + ax + b - Z`
+    /// irreducible, and `b / (Z a)` on the curve, as the RFC finds in its
+    /// appendix H.2.
+    const ZETA: Self::BaseField;
+}
+
+/// Whether the SWU map can map to `P`'s curve.
+pub(crate) fn supported<P: SWUParams>() -> bool {
+    !P::COEFF_A.is_zero() && !P::COEFF_B.is_zero()
+}
+
+/// The point for `u`; not in the prime-order subgroup.
+pub fn map_to_curve<P: SWUParams>(u: P::BaseField) -> GroupAffine<P> {
+    let (a, b, z) = (P::COEFF_A, P::COEFF_B, P::ZETA);
+    let g = |x: P::BaseField| P::add_b(&(x.square() * x + P::mul_by_a(&x)));
+
+    let zu2 = z * u.square();
+    let tv1 = (zu2.square() + zu2).inverse();
+    let x1 = match tv1 {
+        Some(tv1) => -b / a * (P::BaseField::one() + tv1),
+        // the exceptional case, u = 0 say
+        None => b / (z * a),
+    };
+    let (x, y) = match g(x1).sqrt() {
+        Some(y) => (x1, y),
+        None => {
+            let x2 = zu2 * x1;
+            // g(x2) = Z^3 u^6 g(x1) is a square if g(x1) is not
+            (x2, g(x2).sqrt().unwrap())
+        },
+    };
+    let y = if sgn0(&u) == sgn0(&y) { y } else { -y };
+    GroupAffine::new(x, y, false)
+}
+
+/// The RFC's `sgn0`: the sign of the first nonzero coordinate over the prime
+/// field, where the sign of an element of the prime field is its parity.
+pub(crate) fn sgn0<F: Field>(x: &F) -> bool {
+    let mut bytes = Vec::new();
+    x.serialize(&mut bytes).unwrap();
+    // the coordinates, each in little-endian order
+    let coord_len = bytes.len() / F::extension_degree() as usize;
+    bytes
+        .chunks(coord_len)
+        .find(|c| c.iter().any(|b| *b != 0))
+        .is_some_and(|c| c[0] & 1 == 1)
+}
@@ -38,6 +38,10 @@ pub use self::models::*;
 
 pub mod group;
 
+pub mod glv;
+
+pub mod hashing;
+
 pub mod msm;
 
 pub trait PairingEngine: Sized + 'static + Copy + Debug + Sync + Send + Eq + PartialEq {
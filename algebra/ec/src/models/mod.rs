@@ -1,5 +1,7 @@
 use ark_ff::{Field, PrimeField, SquareRootField};
 
+use crate::glv::GLVParameters;
+
 pub mod bls12;
 pub mod bn;
 pub mod bw6;
@@ -33,6 +35,15 @@ pub trait SWModelParameters: ModelParameters {
         copy += &Self::COEFF_B;
         copy
     }
+
+    /// The curve's GLV endomorphism, if it has one, to speed up MSMs; see [crate::glv].
+    #[inline(always)]
+    fn glv() -> Option<GLVParameters<Self>>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
 pub trait TEModelParameters: ModelParameters {
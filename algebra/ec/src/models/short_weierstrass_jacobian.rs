@@ -198,6 +198,7 @@ impl<P: Parameters> AffineCurve for GroupAffine<P> {
 
     #[inline]
     fn mul<S: Into<<Self::ScalarField as PrimeField>::BigInt>>(&self, by: S) -> GroupProjective<P> {
+        // not through GLV, which is only correct in the prime-order subgroup
         let bits = BitIteratorBE::new(by.into());
         self.mul_bits(bits)
     }
@@ -210,6 +211,27 @@ impl<P: Parameters> AffineCurve for GroupAffine<P> {
     fn mul_by_cofactor_inv(&self) -> Self {
         self.mul(P::COFACTOR_INV).into()
     }
+
+    /// Through GLV, if the curve has it, so the bases must be in the prime-order subgroup, as
+    /// keys and commitments are.
+    fn multi_scalar_mul(bases: &[Self], scalars: &[Self::ScalarField]) -> Self::Projective {
+        let msm_timer = ark_std::start_timer!(|| "Base MSM");
+        let product = match P::glv() {
+            Some(glv) => glv.multi_scalar_mul(bases, scalars),
+            None => {
+                let bigint_scalars = ark_std::cfg_iter!(scalars)
+                    .map(|s| s.into_repr())
+                    .collect::<Vec<_>>();
+                crate::msm::VariableBaseMSM::multi_scalar_mul_batch_affine(
+                    bases,
+                    &bigint_scalars,
+                    P::ScalarField::size_in_bits(),
+                )
+            }
+        };
+        ark_std::end_timer!(msm_timer);
+        product
+    }
 }
 
 impl<P: Parameters> Neg for GroupAffine<P> {
@@ -0,0 +1,206 @@
+//! Pippenger's method with the buckets accumulated in affine coordinates.
+//!
+//! An affine addition needs an inversion, but Montgomery's trick shares one
+//! inversion among a whole batch of independent additions, after which each
+//! costs about 6 multiplications, against 11 for the mixed additions of
+//! [VariableBaseMSM::multi_scalar_mul]. So each window sorts its points by
+//! bucket, and sums every bucket as a tree: each round adds disjoint pairs of
+//! points, in one batch over all the buckets, and halves the number of points
+//! left, until every bucket holds one.
+use ark_ff::{batch_inversion, prelude::*};
+use ark_std::vec::Vec;
+
+use crate::{
+    models::short_weierstrass_jacobian::{GroupAffine, GroupProjective},
+    ProjectiveCurve, SWModelParameters,
+};
+
+use super::VariableBaseMSM;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Below this many bases, the batches are too small to pay for their
+/// inversions.
+pub const BATCH_AFFINE_THRESHOLD: usize = 1 << 9;
+
+impl VariableBaseMSM {
+    /// Like [VariableBaseMSM::multi_scalar_mul_bounded], for short Weierstrass
+    /// curves, with the buckets summed in batches of affine additions.
+    pub fn multi_scalar_mul_batch_affine<P: SWModelParameters>(
+        bases: &[GroupAffine<P>],
+        scalars: &[<P::ScalarField as PrimeField>::BigInt],
+        num_bits: usize,
+    ) -> GroupProjective<P> {
+        let size = ark_std::cmp::min(bases.len(), scalars.len());
+        if size < BATCH_AFFINE_THRESHOLD {
+            return Self::multi_scalar_mul_bounded(bases, scalars, num_bits);
+        }
+        let scalars = &scalars[..size];
+        let bases = &bases[..size];
+
+        let c = super::ln_without_floats(size) + 2;
+        let num_bits = num_bits.max(1);
+        let zero = GroupProjective::<P>::zero();
+        let window_starts: Vec<_> = (0..num_bits).step_by(c).collect();
+
+        let window_sums: Vec<_> = ark_std::cfg_into_iter!(window_starts)
+            .map(|w_start| {
+                // \sum_d d * bucket_d, as a sum of running sums from the top
+                let mut res = zero;
+                let mut running_sum = zero;
+                accumulate(bases, scalars, w_start, c)
+                    .iter()
+                    .rev()
+                    .for_each(|b| {
+                        running_sum.add_assign_mixed(b);
+                        res += &running_sum;
+                    });
+                res
+            })
+            .collect();
+
+        // We're traversing windows from high to low.
+        let lowest = *window_sums.first().unwrap();
+        lowest
+            + &window_sums[1..]
+                .iter()
+                .rev()
+                .fold(zero, |mut total, sum_i| {
+                    total += sum_i;
+                    for _ in 0..c {
+                        total.double_in_place();
+                    }
+                    total
+                })
+    }
+}
+
+/// The buckets of the window of `c` bits from `w_start`: bucket `d - 1` sums
+/// the bases whose scalars have the digit `d` there.
+fn accumulate<P: SWModelParameters>(
+    bases: &[GroupAffine<P>],
+    scalars: &[<P::ScalarField as PrimeField>::BigInt],
+    w_start: usize,
+    c: usize,
+) -> Vec<GroupAffine<P>> {
+    let num_buckets = (1 << c) - 1;
+    let digits: Vec<usize> = scalars
+        .iter()
+        .map(|s| {
+            let mut s = *s;
+            s.divn(w_start as u32);
+            (s.as_ref()[0] % (1 << c)) as usize
+        })
+        .collect();
+
+    // Sort the points by bucket; bucket `b` takes `points[starts[b]..][..lens[b]]`.
+    let mut lens = vec![0usize; num_buckets];
+    for (&d, base) in digits.iter().zip(bases) {
+        if d != 0 && !base.infinity {
+            lens[d - 1] += 1;
+        }
+    }
+    let mut starts = Vec::with_capacity(num_buckets);
+    let mut total = 0;
+    for len in &lens {
+        starts.push(total);
+        total += len;
+    }
+    let mut points = vec![GroupAffine::<P>::zero(); total];
+    let mut next = starts.clone();
+    for (&d, base) in digits.iter().zip(bases) {
+        if d != 0 && !base.infinity {
+            points[next[d - 1]] = *base;
+            next[d - 1] += 1;
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let mut sums = Vec::new();
+    loop {
+        pairs.clear();
+        for (&start, &len) in starts.iter().zip(&lens) {
+            for j in 0..len / 2 {
+                pairs.push((start + 2 * j, start + 2 * j + 1));
+            }
+        }
+        if pairs.is_empty() {
+            break;
+        }
+        batch_add(&points, &pairs, &mut sums);
+        // The sums of bucket `b` go to the front of its points, followed by
+        // the odd one out, if any.
+        let mut results = sums.iter();
+        for (&start, len) in starts.iter().zip(&mut lens) {
+            for j in 0..*len / 2 {
+                points[start + j] = *results.next().unwrap();
+            }
+            if *len % 2 == 1 {
+                points[start + *len / 2] = points[start + *len - 1];
+            }
+            *len = (*len + 1) / 2;
+        }
+    }
+
+    starts
+        .iter()
+        .zip(&lens)
+        .map(|(&start, &len)| {
+            if len == 0 {
+                GroupAffine::zero()
+            } else {
+                points[start]
+            }
+        })
+        .collect()
+}
+
+/// `sums[i] = points[pairs[i].0] + points[pairs[i].1]`, with one inversion.
+fn batch_add<P: SWModelParameters>(
+    points: &[GroupAffine<P>],
+    pairs: &[(usize, usize)],
+    sums: &mut Vec<GroupAffine<P>>,
+) {
+    // The slope of each sum is `numerators[i] / denominators[i]`; a zero
+    // denominator marks a sum that needs no slope, which the inversion skips.
+    let mut numerators = Vec::with_capacity(pairs.len());
+    let mut denominators = Vec::with_capacity(pairs.len());
+    for &(i, j) in pairs {
+        let (p, q) = (&points[i], &points[j]);
+        let (n, d) = if p.infinity || q.infinity {
+            (P::BaseField::zero(), P::BaseField::zero())
+        } else if p.x != q.x {
+            (q.y - &p.y, q.x - &p.x)
+        } else if p.y == q.y {
+            // doubling; a zero `y` is a point of order two, whose double is
+            // zero
+            let x2 = p.x.square();
+            (x2.double() + &x2 + &P::COEFF_A, p.y.double())
+        } else {
+            (P::BaseField::zero(), P::BaseField::zero())
+        };
+        numerators.push(n);
+        denominators.push(d);
+    }
+    batch_inversion(&mut denominators);
+
+    sums.clear();
+    for ((&(i, j), n), d) in pairs.iter().zip(numerators).zip(denominators) {
+        let (p, q) = (&points[i], &points[j]);
+        let sum = if p.infinity {
+            *q
+        } else if q.infinity {
+            *p
+        } else if d.is_zero() {
+            // `p = -q`
+            GroupAffine::zero()
+        } else {
+            let slope = n * &d;
+            let x = slope.square() - &p.x - &q.x;
+            let y = slope * &(p.x - &x) - &p.y;
+            GroupAffine::new(x, y, false)
+        };
+        sums.push(sum);
+    }
+}
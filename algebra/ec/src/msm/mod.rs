@@ -1,5 +1,7 @@
+mod batch_affine;
 mod fixed_base;
 mod variable_base;
+pub use batch_affine::*;
 pub use fixed_base::*;
 pub use variable_base::*;
 
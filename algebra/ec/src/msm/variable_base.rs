@@ -12,6 +12,17 @@ impl VariableBaseMSM {
     pub fn multi_scalar_mul<G: AffineCurve>(
         bases: &[G],
         scalars: &[<G::ScalarField as PrimeField>::BigInt],
+    ) -> G::Projective {
+        let num_bits = <G::ScalarField as PrimeField>::Params::MODULUS_BITS as usize;
+        Self::multi_scalar_mul_bounded(bases, scalars, num_bits)
+    }
+
+    /// Like [VariableBaseMSM::multi_scalar_mul], for scalars known to be less than `2^num_bits`,
+    /// which saves the windows above them.
+    pub fn multi_scalar_mul_bounded<G: AffineCurve>(
+        bases: &[G],
+        scalars: &[<G::ScalarField as PrimeField>::BigInt],
+        num_bits: usize,
     ) -> G::Projective {
         let size = ark_std::cmp::min(bases.len(), scalars.len());
         let scalars = &scalars[..size];
@@ -24,7 +35,7 @@ impl VariableBaseMSM {
             super::ln_without_floats(size) + 2
         };
 
-        let num_bits = <G::ScalarField as PrimeField>::Params::MODULUS_BITS as usize;
+        let num_bits = num_bits.max(1);
         let fr_one = G::ScalarField::one().into_repr();
 
         let zero = G::Projective::zero();
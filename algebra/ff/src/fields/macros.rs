@@ -364,6 +364,43 @@ macro_rules! impl_Fp {
 
             impl_field_square_in_place!($limbs);
 
+            /// With the `simd` feature, this multiplies several elements at once, in vector
+            /// registers, when the CPU has them.
+            #[cfg(feature = "simd")]
+            fn batch_product_in_place(selfs: &mut [Self], others: &[Self]) {
+                // elements are copied to and from limb arrays this many at a time
+                const CHUNK: usize = 64;
+                assert_eq!(selfs.len(), others.len());
+                ark_std::cfg_chunks_mut!(selfs, CHUNK)
+                    .zip(ark_std::cfg_chunks!(others, CHUNK))
+                    .for_each(|(selfs, others)| {
+                        use crate::fields::simd::{digits, mul_assign_batch, Digits};
+                        let mut xs = [[0u64; $limbs]; CHUNK];
+                        let mut ys = [[0u64; $limbs]; CHUNK];
+                        for (i, (s, o)) in selfs.iter().zip(others).enumerate() {
+                            xs[i] = (s.0).0;
+                            ys[i] = (o.0).0;
+                        }
+                        let n = selfs.len();
+                        let done = mul_assign_batch::<
+                            Digits<{ digits($limbs, 29) }>,
+                            Digits<{ digits($limbs, 52) }>,
+                            $limbs,
+                        >(
+                            &mut xs[..n],
+                            &ys[..n],
+                            &P::MODULUS.0,
+                            P::INV,
+                        );
+                        for (s, x) in selfs[..done].iter_mut().zip(&xs) {
+                            (s.0).0 = *x;
+                        }
+                        for (s, o) in selfs[done..].iter_mut().zip(&others[done..]) {
+                            *s *= o;
+                        }
+                    });
+            }
+
             #[inline]
             fn inverse(&self) -> Option<Self> {
                 if self.is_zero() {
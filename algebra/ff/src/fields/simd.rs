@@ -0,0 +1,715 @@
+//! Vectorized Montgomery multiplication, for batches of prime field elements.
+//!
+//! A SIMD register holds one element per lane, as digits in 64-bit words, narrow enough that a
+//! lane's digit products, summed by column, fit their words. Carries are then propagated once per
+//! column, as Montgomery reduction reaches it, rather than once per product. Reduction takes a
+//! digit a step, and whatever is left of the `64 N` bits of `R` in the last step, so the products
+//! are the field's own, `a b R^-1`, bit for bit.
+//!
+//! The backend is picked at runtime, on x86-64: AVX-512 IFMA multiplies 52-bit digits, and
+//! AVX-512F 29-bit digits, 32 bits at a time. Each works on two registers at once, so that one's
+//! carries wait while the other multiplies. The elements left over after the last full pair of
+//! registers, or every element, on other CPUs, are for the caller to multiply as usual.
+//!
+//! AVX2 multiplies 29-bit digits too, in half AVX-512F's lanes, which is slower than multiplying
+//! 64-bit limbs one at a time, as the field does anyway: 0.4-0.7 times as fast, for Fp256 to
+//! Fp768, on an AVX-512 Xeon made to use it. So it is picked, on CPUs without AVX-512, only with
+//! the `simd-avx2` feature, with which the vector path can be tested, and measured, on them. NEON
+//! multiplies 32 bits at a time in half AVX2's lanes, so there is no NEON backend.
+#![allow(unsafe_code)]
+
+/// The most 64-bit limbs of any prime field backend, `Fp832`'s.
+const MAX_LIMBS: usize = 13;
+/// The most digits of an element, with 29-bit digits.
+const MAX_DIGITS: usize = 29;
+/// The most lanes of any backend.
+const MAX_LANES: usize = 16;
+
+/// Registers of 64-bit words, holding digits or sums of their products.
+pub(crate) trait Lanes: Copy {
+    const LANES: usize;
+    /// Low enough that the digit products of a column, and their carries, sum to less than
+    /// `2^64`, for the most digits of any element.
+    const DIGIT_BITS: u32;
+
+    unsafe fn splat(x: u64) -> Self;
+    /// The first `LANES` words of `xs`.
+    unsafe fn load(xs: &[u64]) -> Self;
+    unsafe fn store(self, out: &mut [u64]);
+    unsafe fn add(self, other: Self) -> Self;
+    unsafe fn and(self, other: Self) -> Self;
+    unsafe fn or(self, other: Self) -> Self;
+    unsafe fn shr(self, bits: u32) -> Self;
+    unsafe fn shl(self, bits: u32) -> Self;
+    /// The product of two digits, correct at least in its low `DIGIT_BITS` bits.
+    unsafe fn mul_lo(self, other: Self) -> Self;
+    /// Add the product of the digits `x` and `y` to the column `lo`, and the next one, `hi`.
+    unsafe fn mul_add(lo: Self, hi: Self, x: Self, y: Self) -> (Self, Self);
+}
+
+/// `K` digits, the number that [Reduce] is unrolled for.
+pub(crate) struct Digits<const K: usize>;
+
+/// The number of `bits`-bit digits of an element of `limbs` 64-bit limbs.
+pub(crate) const fn digits(limbs: usize, bits: usize) -> usize {
+    (64 * limbs + bits - 1) / bits
+}
+
+pub(crate) trait Reduce {
+    const K: usize;
+
+    /// `a b / 2^(DIGIT_BITS (K - 1) + last_bits) mod p`, plus `p` at most, with
+    /// `inv = -p^-1 mod 2^DIGIT_BITS`, and `last_mask` masking `last_bits`. Its digits are
+    /// normalized, and shifted up by `last_bits`: the low ones are garbage.
+    unsafe fn montgomery<L: Lanes>(
+        a: &[L; MAX_DIGITS],
+        b: &[L; MAX_DIGITS],
+        p: &[L; MAX_DIGITS],
+        inv: L,
+        last_mask: L,
+    ) -> [L; MAX_DIGITS + 2];
+}
+
+macro_rules! impl_reduce {
+    ($($k:literal),*) => {
+        $(
+            impl Reduce for Digits<$k> {
+                const K: usize = $k;
+
+                // unrolled, so that the columns stay in registers
+                #[ark_ff_asm::unroll_for_loops]
+                #[inline(always)]
+                unsafe fn montgomery<L: Lanes>(
+                    a: &[L; MAX_DIGITS],
+                    b: &[L; MAX_DIGITS],
+                    p: &[L; MAX_DIGITS],
+                    inv: L,
+                    last_mask: L,
+                ) -> [L; MAX_DIGITS + 2] {
+                    let zero = L::splat(0);
+                    let mask = L::splat((1 << L::DIGIT_BITS) - 1);
+                    // c = a b, by column
+                    let mut c = [zero; 2 * $k];
+                    for i in 0..$k {
+                        for j in 0..$k {
+                            let (lo, hi) = L::mul_add(c[i + j], c[i + j + 1], a[i], b[j]);
+                            c[i + j] = lo;
+                            c[i + j + 1] = hi;
+                        }
+                    }
+                    // c += m p, for the m that zeroes the low columns, one at a time
+                    for i in 0..$k {
+                        let m = c[i].mul_lo(inv).and(if i + 1 < $k { mask } else { last_mask });
+                        for j in 0..$k {
+                            let (lo, hi) = L::mul_add(c[i + j], c[i + j + 1], m, p[j]);
+                            c[i + j] = lo;
+                            c[i + j + 1] = hi;
+                        }
+                        if i + 1 < $k {
+                            c[i + 1] = c[i + 1].add(c[i].shr(L::DIGIT_BITS));
+                        }
+                    }
+                    // what is left, from the last column zeroed on, whose carry is still in it
+                    let mut digits = [zero; MAX_DIGITS + 2];
+                    let mut carry = zero;
+                    for e in 0..($k + 1) {
+                        let v = c[$k - 1 + e].add(carry);
+                        digits[e] = v.and(mask);
+                        carry = v.shr(L::DIGIT_BITS);
+                    }
+                    digits[$k + 1] = carry;
+                    digits
+                }
+            }
+        )*
+    };
+}
+
+// the limbs of the prime field backends, 1, 4, 5, 6, 12 and 13, in 52- and in 29-bit digits
+impl_reduce!(2, 3, 5, 7, 8, 9, 12, 14, 15, 16, 27, 29);
+
+/// Set `xs[i] = xs[i] ys[i] R^-1 mod p`, for the elements that fill whole registers of `L`,
+/// and return how many that was. The elements must be reduced, and `D` their digits.
+#[inline(always)]
+unsafe fn mul_assign<L: Lanes, D: Reduce, const N: usize>(
+    xs: &mut [[u64; N]],
+    ys: &[[u64; N]],
+    modulus: &[u64; N],
+    inv: u64,
+) -> usize {
+    let k = D::K;
+    debug_assert_eq!(k, digits(N, L::DIGIT_BITS as usize));
+    // the bits of `R` that the last step of reduction divides out
+    let last_bits = 64 * N as u32 - L::DIGIT_BITS * (k as u32 - 1);
+    let zero = L::splat(0);
+    let mask = L::splat((1 << L::DIGIT_BITS) - 1);
+    let last_mask = L::splat((1 << last_bits) - 1);
+    let inv = L::splat(inv).and(mask);
+    let mut p = [zero; MAX_DIGITS];
+    let mut p_limbs = [zero; MAX_LIMBS];
+    for (w, limb) in p_limbs.iter_mut().zip(modulus) {
+        *w = L::splat(*limb);
+    }
+    to_digits(&p_limbs[..N], &mut p[..k], mask);
+
+    let full = xs.len() - xs.len() % L::LANES;
+    let mut words = [[0u64; MAX_LANES]; MAX_LIMBS + 1];
+    for (xs, ys) in xs[..full]
+        .chunks_exact_mut(L::LANES)
+        .zip(ys.chunks_exact(L::LANES))
+    {
+        let (mut a, mut b) = ([zero; MAX_DIGITS], [zero; MAX_DIGITS]);
+        for (elems, digits) in [(&*xs, &mut a), (ys, &mut b)] {
+            let mut limbs = [zero; MAX_LIMBS];
+            for (q, limb) in limbs[..N].iter_mut().enumerate() {
+                for (w, e) in words[q].iter_mut().zip(elems) {
+                    *w = e[q];
+                }
+                *limb = L::load(&words[q]);
+            }
+            to_digits(&limbs[..N], &mut digits[..k], mask);
+        }
+
+        let digits = D::montgomery(&a, &b, &p, inv, last_mask);
+        // the quotient is below `2p`, so it fits in N limbs and one more bit, and its bits
+        // `64 q..64 q + 64` are bits `64 q + last_bits..` of the digits
+        for (q, words) in words[..=N].iter_mut().enumerate() {
+            let (lo, hi) = (64 * q as u32 + last_bits, 64 * q as u32 + last_bits + 64);
+            let mut limb = zero;
+            for (e, d) in digits[..k + 2].iter().enumerate() {
+                let at = L::DIGIT_BITS * e as u32;
+                if at + L::DIGIT_BITS <= lo || at >= hi {
+                    continue;
+                }
+                limb = limb.or(if at >= lo {
+                    d.shl(at - lo)
+                } else {
+                    d.shr(lo - at)
+                });
+            }
+            limb.store(words);
+        }
+        for (lane, x) in xs.iter_mut().enumerate() {
+            for (q, x) in x.iter_mut().enumerate() {
+                *x = words[q][lane];
+            }
+            if words[N][lane] != 0 || !less_than(x, modulus) {
+                sub_wrapping(x, modulus);
+            }
+        }
+    }
+    full
+}
+
+/// Split 64-bit limbs, lane by lane, into `digits.len()` digits.
+#[inline(always)]
+unsafe fn to_digits<L: Lanes>(limbs: &[L], digits: &mut [L], mask: L) {
+    for (j, d) in digits.iter_mut().enumerate() {
+        let at = L::DIGIT_BITS * j as u32;
+        let (q, r) = ((at / 64) as usize, at % 64);
+        let mut v = limbs[q].shr(r);
+        if r + L::DIGIT_BITS > 64 && q + 1 < limbs.len() {
+            v = v.or(limbs[q + 1].shl(64 - r));
+        }
+        *d = v.and(mask);
+    }
+}
+
+#[inline(always)]
+fn less_than<const N: usize>(x: &[u64; N], y: &[u64; N]) -> bool {
+    for (x, y) in x.iter().rev().zip(y.iter().rev()) {
+        if x != y {
+            return x < y;
+        }
+    }
+    false
+}
+
+/// `x -= y`, modulo `2^(64 N)`.
+#[inline(always)]
+fn sub_wrapping<const N: usize>(x: &mut [u64; N], y: &[u64; N]) {
+    let mut borrow = false;
+    for (x, y) in x.iter_mut().zip(y) {
+        let (d, b1) = x.overflowing_sub(*y);
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        *x = d;
+        borrow = b1 | b2;
+    }
+}
+
+/// Multiply as many of the elements as fill whole registers, on the best backend this CPU has,
+/// and return how many that was: none, on CPUs without one. `xs` and `ys` are Montgomery
+/// representations, modulo `modulus`, with `inv = -modulus^-1 mod 2^64`, and `D29` and `D52`
+/// their [Digits], of 29 and 52 bits.
+pub(crate) fn mul_assign_batch<D29: Reduce, D52: Reduce, const N: usize>(
+    xs: &mut [[u64; N]],
+    ys: &[[u64; N]],
+    modulus: &[u64; N],
+    inv: u64,
+) -> usize {
+    assert!(N <= MAX_LIMBS);
+    assert_eq!(xs.len(), ys.len());
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx512ifma") {
+            return unsafe { x86::mul_assign_ifma::<D52, N>(xs, ys, modulus, inv) };
+        }
+        if is_x86_feature_detected!("avx512f") {
+            return unsafe { x86::mul_assign_avx512::<D29, N>(xs, ys, modulus, inv) };
+        }
+        #[cfg(feature = "simd-avx2")]
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { x86::mul_assign_avx2::<D29, N>(xs, ys, modulus, inv) };
+        }
+    }
+    #[allow(unreachable_code)]
+    0
+}
+
+/// Two registers, in lockstep.
+#[derive(Clone, Copy)]
+struct Pair<L>(L, L);
+
+impl<L: Lanes> Lanes for Pair<L> {
+    const LANES: usize = 2 * L::LANES;
+    const DIGIT_BITS: u32 = L::DIGIT_BITS;
+
+    #[inline(always)]
+    unsafe fn splat(x: u64) -> Self {
+        Pair(L::splat(x), L::splat(x))
+    }
+    #[inline(always)]
+    unsafe fn load(xs: &[u64]) -> Self {
+        Pair(L::load(xs), L::load(&xs[L::LANES..]))
+    }
+    #[inline(always)]
+    unsafe fn store(self, out: &mut [u64]) {
+        self.0.store(out);
+        self.1.store(&mut out[L::LANES..]);
+    }
+    #[inline(always)]
+    unsafe fn add(self, other: Self) -> Self {
+        Pair(self.0.add(other.0), self.1.add(other.1))
+    }
+    #[inline(always)]
+    unsafe fn and(self, other: Self) -> Self {
+        Pair(self.0.and(other.0), self.1.and(other.1))
+    }
+    #[inline(always)]
+    unsafe fn or(self, other: Self) -> Self {
+        Pair(self.0.or(other.0), self.1.or(other.1))
+    }
+    #[inline(always)]
+    unsafe fn shr(self, bits: u32) -> Self {
+        Pair(self.0.shr(bits), self.1.shr(bits))
+    }
+    #[inline(always)]
+    unsafe fn shl(self, bits: u32) -> Self {
+        Pair(self.0.shl(bits), self.1.shl(bits))
+    }
+    #[inline(always)]
+    unsafe fn mul_lo(self, other: Self) -> Self {
+        Pair(self.0.mul_lo(other.0), self.1.mul_lo(other.1))
+    }
+    #[inline(always)]
+    unsafe fn mul_add(lo: Self, hi: Self, x: Self, y: Self) -> (Self, Self) {
+        let (lo0, hi0) = L::mul_add(lo.0, hi.0, x.0, y.0);
+        let (lo1, hi1) = L::mul_add(lo.1, hi.1, x.1, y.1);
+        (Pair(lo0, lo1), Pair(hi0, hi1))
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use super::{mul_assign, Lanes, Pair, Reduce};
+    use core::arch::x86_64::*;
+
+    /// 29-bit digits, multiplied 32 bits at a time, in half the lanes of [Avx512].
+    #[derive(Clone, Copy)]
+    pub(super) struct Avx2(__m256i);
+
+    impl Lanes for Avx2 {
+        const LANES: usize = 4;
+        const DIGIT_BITS: u32 = 29;
+
+        #[inline(always)]
+        unsafe fn splat(x: u64) -> Self {
+            Avx2(_mm256_set1_epi64x(x as i64))
+        }
+        #[inline(always)]
+        unsafe fn load(xs: &[u64]) -> Self {
+            Avx2(_mm256_loadu_si256(xs.as_ptr() as *const _))
+        }
+        #[inline(always)]
+        unsafe fn store(self, out: &mut [u64]) {
+            _mm256_storeu_si256(out.as_mut_ptr() as *mut _, self.0)
+        }
+        #[inline(always)]
+        unsafe fn add(self, other: Self) -> Self {
+            Avx2(_mm256_add_epi64(self.0, other.0))
+        }
+        #[inline(always)]
+        unsafe fn and(self, other: Self) -> Self {
+            Avx2(_mm256_and_si256(self.0, other.0))
+        }
+        #[inline(always)]
+        unsafe fn or(self, other: Self) -> Self {
+            Avx2(_mm256_or_si256(self.0, other.0))
+        }
+        #[inline(always)]
+        unsafe fn shr(self, bits: u32) -> Self {
+            Avx2(_mm256_srl_epi64(self.0, _mm_cvtsi32_si128(bits as i32)))
+        }
+        #[inline(always)]
+        unsafe fn shl(self, bits: u32) -> Self {
+            Avx2(_mm256_sll_epi64(self.0, _mm_cvtsi32_si128(bits as i32)))
+        }
+        #[inline(always)]
+        unsafe fn mul_lo(self, other: Self) -> Self {
+            Avx2(_mm256_mul_epu32(self.0, other.0))
+        }
+        #[inline(always)]
+        unsafe fn mul_add(lo: Self, hi: Self, x: Self, y: Self) -> (Self, Self) {
+            (lo.add(x.mul_lo(y)), hi)
+        }
+    }
+
+    /// 29-bit digits, multiplied 32 bits at a time.
+    #[derive(Clone, Copy)]
+    pub(super) struct Avx512(__m512i);
+
+    impl Lanes for Avx512 {
+        const LANES: usize = 8;
+        const DIGIT_BITS: u32 = 29;
+
+        #[inline(always)]
+        unsafe fn splat(x: u64) -> Self {
+            Avx512(_mm512_set1_epi64(x as i64))
+        }
+        #[inline(always)]
+        unsafe fn load(xs: &[u64]) -> Self {
+            Avx512(_mm512_loadu_si512(xs.as_ptr() as *const _))
+        }
+        #[inline(always)]
+        unsafe fn store(self, out: &mut [u64]) {
+            _mm512_storeu_si512(out.as_mut_ptr() as *mut _, self.0)
+        }
+        #[inline(always)]
+        unsafe fn add(self, other: Self) -> Self {
+            Avx512(_mm512_add_epi64(self.0, other.0))
+        }
+        #[inline(always)]
+        unsafe fn and(self, other: Self) -> Self {
+            Avx512(_mm512_and_si512(self.0, other.0))
+        }
+        #[inline(always)]
+        unsafe fn or(self, other: Self) -> Self {
+            Avx512(_mm512_or_si512(self.0, other.0))
+        }
+        #[inline(always)]
+        unsafe fn shr(self, bits: u32) -> Self {
+            Avx512(_mm512_srl_epi64(self.0, _mm_cvtsi32_si128(bits as i32)))
+        }
+        #[inline(always)]
+        unsafe fn shl(self, bits: u32) -> Self {
+            Avx512(_mm512_sll_epi64(self.0, _mm_cvtsi32_si128(bits as i32)))
+        }
+        #[inline(always)]
+        unsafe fn mul_lo(self, other: Self) -> Self {
+            Avx512(_mm512_mul_epu32(self.0, other.0))
+        }
+        #[inline(always)]
+        unsafe fn mul_add(lo: Self, hi: Self, x: Self, y: Self) -> (Self, Self) {
+            (lo.add(x.mul_lo(y)), hi)
+        }
+    }
+
+    /// 52-bit digits, multiplied whole, into the low and the high halves of their products.
+    #[derive(Clone, Copy)]
+    pub(super) struct Ifma(Avx512);
+
+    impl Lanes for Ifma {
+        const LANES: usize = 8;
+        const DIGIT_BITS: u32 = 52;
+
+        #[inline(always)]
+        unsafe fn splat(x: u64) -> Self {
+            Ifma(Avx512::splat(x))
+        }
+        #[inline(always)]
+        unsafe fn load(xs: &[u64]) -> Self {
+            Ifma(Avx512::load(xs))
+        }
+        #[inline(always)]
+        unsafe fn store(self, out: &mut [u64]) {
+            self.0.store(out)
+        }
+        #[inline(always)]
+        unsafe fn add(self, other: Self) -> Self {
+            Ifma(self.0.add(other.0))
+        }
+        #[inline(always)]
+        unsafe fn and(self, other: Self) -> Self {
+            Ifma(self.0.and(other.0))
+        }
+        #[inline(always)]
+        unsafe fn or(self, other: Self) -> Self {
+            Ifma(self.0.or(other.0))
+        }
+        #[inline(always)]
+        unsafe fn shr(self, bits: u32) -> Self {
+            Ifma(self.0.shr(bits))
+        }
+        #[inline(always)]
+        unsafe fn shl(self, bits: u32) -> Self {
+            Ifma(self.0.shl(bits))
+        }
+        #[inline(always)]
+        unsafe fn mul_lo(self, other: Self) -> Self {
+            let zero = _mm512_setzero_si512();
+            Ifma(Avx512(_mm512_madd52lo_epu64(zero, (self.0).0, (other.0).0)))
+        }
+        #[inline(always)]
+        unsafe fn mul_add(lo: Self, hi: Self, x: Self, y: Self) -> (Self, Self) {
+            let (x, y) = ((x.0).0, (y.0).0);
+            (
+                Ifma(Avx512(_mm512_madd52lo_epu64((lo.0).0, x, y))),
+                Ifma(Avx512(_mm512_madd52hi_epu64((hi.0).0, x, y))),
+            )
+        }
+    }
+
+    #[cfg(feature = "simd-avx2")]
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn mul_assign_avx2<D: Reduce, const N: usize>(
+        xs: &mut [[u64; N]],
+        ys: &[[u64; N]],
+        modulus: &[u64; N],
+        inv: u64,
+    ) -> usize {
+        mul_assign::<Pair<Avx2>, D, N>(xs, ys, modulus, inv)
+    }
+
+    #[target_feature(enable = "avx512f")]
+    pub(super) unsafe fn mul_assign_avx512<D: Reduce, const N: usize>(
+        xs: &mut [[u64; N]],
+        ys: &[[u64; N]],
+        modulus: &[u64; N],
+        inv: u64,
+    ) -> usize {
+        mul_assign::<Pair<Avx512>, D, N>(xs, ys, modulus, inv)
+    }
+
+    #[target_feature(enable = "avx512f,avx512ifma")]
+    pub(super) unsafe fn mul_assign_ifma<D: Reduce, const N: usize>(
+        xs: &mut [[u64; N]],
+        ys: &[[u64; N]],
+        modulus: &[u64; N],
+        inv: u64,
+    ) -> usize {
+        mul_assign::<Pair<Ifma>, D, N>(xs, ys, modulus, inv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_std::{rand::Rng, test_rng, vec, vec::Vec};
+
+    /// One lane, in plain words: the kernel, on any target, for either width of digit.
+    #[derive(Clone, Copy)]
+    struct Portable<const BITS: u32>(u64);
+
+    impl<const BITS: u32> Lanes for Portable<BITS> {
+        const LANES: usize = 1;
+        const DIGIT_BITS: u32 = BITS;
+
+        unsafe fn splat(x: u64) -> Self {
+            Portable(x)
+        }
+        unsafe fn load(xs: &[u64]) -> Self {
+            Portable(xs[0])
+        }
+        unsafe fn store(self, out: &mut [u64]) {
+            out[0] = self.0
+        }
+        unsafe fn add(self, other: Self) -> Self {
+            Portable(self.0 + other.0)
+        }
+        unsafe fn and(self, other: Self) -> Self {
+            Portable(self.0 & other.0)
+        }
+        unsafe fn or(self, other: Self) -> Self {
+            Portable(self.0 | other.0)
+        }
+        unsafe fn shr(self, bits: u32) -> Self {
+            Portable(self.0 >> bits)
+        }
+        unsafe fn shl(self, bits: u32) -> Self {
+            Portable(self.0 << bits)
+        }
+        unsafe fn mul_lo(self, other: Self) -> Self {
+            Portable(self.0.wrapping_mul(other.0))
+        }
+        unsafe fn mul_add(lo: Self, hi: Self, x: Self, y: Self) -> (Self, Self) {
+            let xy = x.0 as u128 * y.0 as u128;
+            let mask = (1 << BITS) - 1;
+            (
+                Portable(lo.0 + (xy as u64 & mask)),
+                Portable(hi.0 + (xy >> BITS) as u64),
+            )
+        }
+    }
+
+    /// `x y R^-1 mod p`, by schoolbook multiplication and then Montgomery reduction, with
+    /// 64-bit limbs.
+    fn reference<const N: usize>(x: &[u64; N], y: &[u64; N], p: &[u64; N], inv: u64) -> [u64; N] {
+        let mut r = vec![0u64; 2 * N + 1];
+        for i in 0..N {
+            let mut carry = 0u128;
+            for j in 0..N {
+                let s = r[i + j] as u128 + x[i] as u128 * y[j] as u128 + carry;
+                r[i + j] = s as u64;
+                carry = s >> 64;
+            }
+            r[i + N] = carry as u64;
+        }
+        for i in 0..N {
+            let k = r[i].wrapping_mul(inv);
+            let mut carry = 0u128;
+            for j in 0..N {
+                let s = r[i + j] as u128 + k as u128 * p[j] as u128 + carry;
+                r[i + j] = s as u64;
+                carry = s >> 64;
+            }
+            for r in &mut r[i + N..] {
+                let s = *r as u128 + carry;
+                *r = s as u64;
+                carry = s >> 64;
+            }
+        }
+        let mut out = [0u64; N];
+        out.copy_from_slice(&r[N..2 * N]);
+        if r[2 * N] != 0 || !less_than(&out, p) {
+            sub_wrapping(&mut out, p);
+        }
+        out
+    }
+
+    /// An odd modulus of `N` limbs, with its top bit set when `full`, and `-p^-1 mod 2^64`.
+    fn modulus<const N: usize, R: Rng>(rng: &mut R, full: bool) -> ([u64; N], u64) {
+        let mut p = [0u64; N];
+        rng.fill(&mut p[..]);
+        if full {
+            p[N - 1] |= 1 << 63;
+        } else {
+            p[N - 1] >>= 1;
+        }
+        p[0] |= 1;
+        // Newton's iteration doubles the correct low bits of an inverse, from 1 bit
+        let mut p_inv = 1u64;
+        for _ in 0..6 {
+            p_inv = p_inv.wrapping_mul(2u64.wrapping_sub(p[0].wrapping_mul(p_inv)));
+        }
+        (p, p_inv.wrapping_neg())
+    }
+
+    /// Reduced elements, including the extremes.
+    fn elements<const N: usize, R: Rng>(rng: &mut R, p: &[u64; N], n: usize) -> Vec<[u64; N]> {
+        let mut minus_one = *p;
+        minus_one[0] -= 1;
+        let mut one = [0u64; N];
+        one[0] = 1;
+        let mut elems = vec![[0u64; N], one, minus_one];
+        while elems.len() < n {
+            let mut x = [0u64; N];
+            rng.fill(&mut x[..]);
+            x[N - 1] >>= p[N - 1].leading_zeros();
+            if less_than(&x, p) {
+                elems.push(x);
+            }
+        }
+        elems
+    }
+
+    fn check<L: Lanes, D: Reduce, const N: usize>() {
+        let rng = &mut test_rng();
+        for &full in &[false, true] {
+            for _ in 0..4 {
+                let (p, inv) = modulus::<N, _>(rng, full);
+                let xs = elements(rng, &p, 37);
+                let mut ys = elements(rng, &p, 37);
+                ys.reverse();
+                let mut out = xs.clone();
+                let done = unsafe { mul_assign::<L, D, N>(&mut out, &ys, &p, inv) };
+                assert_eq!(done, 37 - 37 % L::LANES);
+                for i in 0..done {
+                    assert_eq!(out[i], reference(&xs[i], &ys[i], &p, inv));
+                }
+                assert_eq!(out[done..], xs[done..]);
+            }
+        }
+    }
+
+    /// Every size of prime field, in 29-bit digits.
+    fn check_29<L: Lanes>() {
+        check::<L, Digits<3>, 1>();
+        check::<L, Digits<9>, 4>();
+        check::<L, Digits<12>, 5>();
+        check::<L, Digits<14>, 6>();
+        check::<L, Digits<27>, 12>();
+        check::<L, Digits<29>, 13>();
+    }
+
+    /// Every size of prime field, in 52-bit digits.
+    fn check_52<L: Lanes>() {
+        check::<L, Digits<2>, 1>();
+        check::<L, Digits<5>, 4>();
+        check::<L, Digits<7>, 5>();
+        check::<L, Digits<8>, 6>();
+        check::<L, Digits<15>, 12>();
+        check::<L, Digits<16>, 13>();
+    }
+
+    #[test]
+    fn portable() {
+        check_29::<Portable<29>>();
+        check_52::<Portable<52>>();
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn x86() {
+        use super::x86::*;
+        if is_x86_feature_detected!("avx2") {
+            check_29::<Pair<Avx2>>();
+        }
+        if is_x86_feature_detected!("avx512f") {
+            check_29::<Pair<Avx512>>();
+        }
+        if is_x86_feature_detected!("avx512ifma") {
+            check_52::<Pair<Ifma>>();
+        }
+    }
+
+    #[test]
+    fn fields() {
+        use crate::test_field::{Fq, Fr};
+        use crate::Field;
+
+        fn check<F: Field>() {
+            let rng = &mut test_rng();
+            for n in 0..40 {
+                let xs: Vec<F> = (0..n).map(|_| F::rand(rng)).collect();
+                let ys: Vec<F> = (0..n).map(|_| F::rand(rng)).collect();
+                let mut out = xs.clone();
+                F::batch_product_in_place(&mut out, &ys);
+                for ((o, x), y) in out.iter().zip(&xs).zip(&ys) {
+                    assert_eq!(*o, *x * y);
+                }
+            }
+        }
+        check::<Fr>();
+        check::<Fq>();
+    }
+}
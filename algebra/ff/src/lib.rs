@@ -1,9 +1,9 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(unused, future_incompatible, nonstandard_style, rust_2018_idioms)]
 #![allow(clippy::op_ref, clippy::suspicious_op_assign_impl)]
-#![cfg_attr(not(feature = "asm"), forbid(unsafe_code))]
+#![cfg_attr(not(any(feature = "asm", feature = "simd")), forbid(unsafe_code))]
 #![cfg_attr(use_asm, feature(llvm_asm))]
-#![cfg_attr(feature = "asm", deny(unsafe_code))]
+#![cfg_attr(any(feature = "asm", feature = "simd"), deny(unsafe_code))]
 
 #[macro_use]
 extern crate ark_std;
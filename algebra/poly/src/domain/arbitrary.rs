@@ -0,0 +1,348 @@
+//! This module contains an `ArbitraryEvaluationDomain` for
+//! performing polynomial arithmetic over domains of any size, on
+//! fields whose multiplicative subgroups are too small, or too
+//! unevenly sized, for the radix-2 and mixed-radix domains.
+//!
+//! The domain is the geometric sequence `1, g, g^2, ..., g^(n - 1)`,
+//! where `g` is the multiplicative generator of the field, so its
+//! elements are distinct. Without a subgroup structure there is no
+//! FFT: evaluation and interpolation take `O(n^2)` field operations,
+//! through Horner's rule and the barycentric form of the Lagrange
+//! polynomials. Because the points are geometric, the barycentric
+//! weights, and the vanishing polynomial on the coset used for
+//! division, each take only `O(n)`.
+
+pub use crate::domain::utils::Elements;
+use crate::domain::{utils::compute_powers_serial, DomainCoeff, EvaluationDomain};
+use ark_ff::{batch_inversion, FftField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::{
+    cmp::max,
+    convert::TryFrom,
+    fmt,
+    io::{Read, Write},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Defines a domain of any size, over which polynomials can be evaluated
+/// and interpolated in quadratic time. Works for every field.
+#[derive(Copy, Clone, Hash, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ArbitraryEvaluationDomain<F: FftField> {
+    /// The size of the domain.
+    pub size: u64,
+    /// Size of the domain as a field element.
+    pub size_as_field_element: F,
+    /// The ratio `g` of the geometric sequence.
+    pub group_gen: F,
+    /// The offset `g^size` of the coset used for division, which is disjoint
+    /// from the domain.
+    pub coset_offset: F,
+    /// Inverse of the coset offset.
+    pub coset_offset_inv: F,
+}
+
+impl<F: FftField> fmt::Debug for ArbitraryEvaluationDomain<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Geometric domain of size {}", self.size)
+    }
+}
+
+impl<F: FftField> ArbitraryEvaluationDomain<F> {
+    /// `g^0, ..., g^size`.
+    fn powers(&self) -> Vec<F> {
+        compute_powers_serial(self.size() + 1, self.group_gen)
+    }
+
+    /// The coefficients of the vanishing polynomial, of degree `size`.
+    fn vanishing_coeffs(&self) -> Vec<F> {
+        let mut z = vec![F::one()];
+        for x in self.elements() {
+            // z * (X - x)
+            z.push(F::zero());
+            for k in (1..z.len()).rev() {
+                z[k] = z[k - 1] - x * z[k];
+            }
+            z[0] = -x * z[0];
+        }
+        z
+    }
+
+    /// The barycentric weights `w_i = 1 / prod_{j != i} (g^i - g^j)`.
+    ///
+    /// With `P_k = prod_{1 <= l <= k} (g^l - 1)` and `m = size - 1 - i`,
+    /// `1 / w_i = (-1)^m g^(i (size - 1) - i (i + 1) / 2) P_i P_m`.
+    fn weights(&self) -> Vec<F> {
+        let n = self.size();
+        let powers = self.powers();
+        // P_0, ..., P_{n - 1}
+        let mut p = Vec::with_capacity(n);
+        p.push(F::one());
+        for k in 1..n {
+            p.push(p[k - 1] * (powers[k] - F::one()));
+        }
+        let mut g_e = F::one();
+        let mut w: Vec<F> = (0..n)
+            .map(|i| {
+                let m = n - 1 - i;
+                let mut inv = g_e * p[i] * p[m];
+                if m % 2 == 1 {
+                    inv = -inv;
+                }
+                // the exponent grows by `n - 2 - i`
+                if i + 2 <= n {
+                    g_e *= powers[n - 2 - i];
+                }
+                inv
+            })
+            .collect();
+        batch_inversion(&mut w);
+        w
+    }
+
+    /// The vanishing polynomial at the `size` points of the coset,
+    /// `c g^i` for `c = g^size`.
+    ///
+    /// Each value follows from the last by
+    /// `z(g x) = g^size z(x) (x - g^-1) / (x - g^(size - 1))`.
+    fn vanishing_on_coset(&self) -> Vec<F> {
+        let n = self.size();
+        let powers = self.powers();
+        let g_inv = self.group_gen.inverse().unwrap();
+        let last = powers[n - 1];
+        let xs: Vec<F> = (0..n).map(|i| self.coset_offset * powers[i]).collect();
+        let mut dens: Vec<F> = xs.iter().map(|x| *x - last).collect();
+        batch_inversion(&mut dens);
+        let mut z = self.evaluate_vanishing_polynomial(xs[0]);
+        let mut out = Vec::with_capacity(n);
+        for i in 0..n {
+            out.push(z);
+            z *= self.coset_offset * (xs[i] - g_inv) * dens[i];
+        }
+        out
+    }
+}
+
+impl<F: FftField> EvaluationDomain<F> for ArbitraryEvaluationDomain<F> {
+    type Elements = Elements<F>;
+
+    /// Construct a domain of exactly `num_coeffs` elements, or one if it is
+    /// zero.
+    fn new(num_coeffs: usize) -> Option<Self> {
+        let size = u64::try_from(Self::compute_size_of_domain(num_coeffs)?).ok()?;
+        let group_gen = F::multiplicative_generator();
+        let coset_offset = group_gen.pow([size]);
+        Some(ArbitraryEvaluationDomain {
+            size,
+            size_as_field_element: F::from(size),
+            group_gen,
+            coset_offset,
+            coset_offset_inv: coset_offset.inverse()?,
+        })
+    }
+
+    fn compute_size_of_domain(num_coeffs: usize) -> Option<usize> {
+        Some(max(num_coeffs, 1))
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        usize::try_from(self.size).unwrap()
+    }
+
+    /// Evaluate the polynomial at every element, by Horner's rule.
+    fn fft_in_place<T: DomainCoeff<F>>(&self, coeffs: &mut Vec<T>) {
+        let points: Vec<F> = self.elements().collect();
+        let evals: Vec<T> = ark_std::cfg_iter!(points)
+            .map(|x| {
+                coeffs.iter().rev().fold(T::zero(), |mut acc, c| {
+                    acc *= *x;
+                    acc + *c
+                })
+            })
+            .collect();
+        *coeffs = evals;
+    }
+
+    /// Interpolate, as `sum_i evals[i] w_i z(X) / (X - g^i)`.
+    fn ifft_in_place<T: DomainCoeff<F>>(&self, evals: &mut Vec<T>) {
+        let n = self.size();
+        evals.resize(n, T::zero());
+        let z = self.vanishing_coeffs();
+        let weights = self.weights();
+        let mut coeffs = vec![T::zero(); n];
+        for ((x, w), y) in self.elements().zip(weights).zip(evals.iter()) {
+            // synthetic division of z by (X - x)
+            let mut q = z[n];
+            for k in (0..n).rev() {
+                let mut term = *y;
+                term *= w * q;
+                coeffs[k] += term;
+                q = z[k] + x * q;
+            }
+        }
+        *evals = coeffs;
+    }
+
+    #[inline]
+    fn coset_fft_in_place<T: DomainCoeff<F>>(&self, coeffs: &mut Vec<T>) {
+        Self::distribute_powers(coeffs, self.coset_offset);
+        self.fft_in_place(coeffs);
+    }
+
+    #[inline]
+    fn coset_ifft_in_place<T: DomainCoeff<F>>(&self, evals: &mut Vec<T>) {
+        self.ifft_in_place(evals);
+        Self::distribute_powers(evals, self.coset_offset_inv);
+    }
+
+    fn evaluate_all_lagrange_coefficients(&self, tau: F) -> Vec<F> {
+        let size = self.size();
+        let z_tau = self.evaluate_vanishing_polynomial(tau);
+        if z_tau.is_zero() {
+            self.elements()
+                .map(|x| if x == tau { F::one() } else { F::zero() })
+                .collect()
+        } else {
+            let mut u: Vec<F> = self.elements().map(|x| tau - x).collect();
+            batch_inversion(&mut u);
+            debug_assert_eq!(u.len(), size);
+            u.iter()
+                .zip(self.weights())
+                .map(|(u, w)| z_tau * w * u)
+                .collect()
+        }
+    }
+
+    fn vanishing_polynomial(&self) -> crate::univariate::SparsePolynomial<F> {
+        let coeffs = self
+            .vanishing_coeffs()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, c)| !c.is_zero())
+            .collect();
+        crate::univariate::SparsePolynomial::from_coefficients_vec(coeffs)
+    }
+
+    /// This evaluates the vanishing polynomial for this domain at tau,
+    /// `z(tau) = prod_i (tau - g^i)`.
+    fn evaluate_vanishing_polynomial(&self, tau: F) -> F {
+        self.elements().map(|x| tau - x).product()
+    }
+
+    /// Returns the `i`-th element of the domain, `g^i`.
+    fn element(&self, i: usize) -> F {
+        self.group_gen.pow(&[i as u64])
+    }
+
+    /// Return an iterator over the elements of the domain.
+    fn elements(&self) -> Elements<F> {
+        Elements {
+            cur_elem: F::one(),
+            cur_pow: 0,
+            size: self.size,
+            group_gen: self.group_gen,
+        }
+    }
+
+    /// The vanishing polynomial is not constant on the coset, so divide by
+    /// its value at each point.
+    fn divide_by_vanishing_poly_on_coset_in_place(&self, evals: &mut [F]) {
+        let mut z = self.vanishing_on_coset();
+        batch_inversion(&mut z);
+        ark_std::cfg_iter_mut!(evals)
+            .zip(z)
+            .for_each(|(eval, z)| *eval *= &z);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        polynomial::{univariate::DensePolynomial, Polynomial, UVPolynomial},
+        ArbitraryEvaluationDomain, EvaluationDomain,
+    };
+    use ark_ff::{One, Zero};
+    use ark_std::{rand::Rng, test_rng, vec::Vec};
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn vanishing_polynomial() {
+        let rng = &mut test_rng();
+        for coeffs in 0..12 {
+            let domain = ArbitraryEvaluationDomain::<Fr>::new(coeffs).unwrap();
+            let z = domain.vanishing_polynomial();
+            for point in domain.elements() {
+                assert!(z.evaluate(&point).is_zero())
+            }
+            for _ in 0..10 {
+                let point: Fr = rng.gen();
+                assert_eq!(
+                    z.evaluate(&point),
+                    domain.evaluate_vanishing_polynomial(point)
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn fft_roundtrip() {
+        let rng = &mut test_rng();
+        for size in 1..20 {
+            let domain = ArbitraryEvaluationDomain::<Fr>::new(size).unwrap();
+            let p = DensePolynomial::<Fr>::rand(size - 1, rng);
+            let evals = domain.fft(&p.coeffs);
+            for (x, e) in domain.elements().zip(&evals) {
+                assert_eq!(p.evaluate(&x), *e);
+            }
+            let mut coeffs = domain.ifft(&evals);
+            coeffs.truncate(p.coeffs.len());
+            assert_eq!(coeffs, p.coeffs);
+
+            let coset = domain.coset_fft(&p.coeffs);
+            assert_eq!(domain.coset_ifft(&coset)[..p.coeffs.len()], p.coeffs[..]);
+        }
+    }
+
+    #[test]
+    fn lagrange_coefficients() {
+        let rng = &mut test_rng();
+        for size in 1..12 {
+            let domain = ArbitraryEvaluationDomain::<Fr>::new(size).unwrap();
+            let p = DensePolynomial::<Fr>::rand(size - 1, rng);
+            let evals = domain.fft(&p.coeffs);
+            let tau: Fr = rng.gen();
+            let at_tau: Fr = domain
+                .evaluate_all_lagrange_coefficients(tau)
+                .iter()
+                .zip(&evals)
+                .map(|(l, e)| *l * e)
+                .sum();
+            assert_eq!(at_tau, p.evaluate(&tau));
+
+            let in_domain = domain.evaluate_all_lagrange_coefficients(domain.element(size - 1));
+            assert!(in_domain[size - 1].is_one());
+            assert_eq!(in_domain.iter().filter(|l| l.is_zero()).count(), size - 1);
+        }
+    }
+
+    #[test]
+    fn divide_by_vanishing_poly_on_coset() {
+        let rng = &mut test_rng();
+        for size in 1..12 {
+            let domain = ArbitraryEvaluationDomain::<Fr>::new(size).unwrap();
+            let z: DensePolynomial<Fr> = domain.vanishing_polynomial().into();
+            // h of degree < size, so that h z has degree < 2 size
+            let h = DensePolynomial::<Fr>::rand(size - 1, rng);
+            let hz = &h * &z;
+            let mut evals: Vec<Fr> = domain
+                .elements()
+                .map(|x| hz.evaluate(&(domain.coset_offset * x)))
+                .collect();
+            domain.divide_by_vanishing_poly_on_coset_in_place(&mut evals);
+            assert_eq!(domain.coset_ifft(&evals)[..h.coeffs.len()], h.coeffs[..]);
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! This module contains a `Coset` of an `EvaluationDomain`: the domain's
+//! elements, each multiplied by a fixed offset.
+//!
+//! The `coset_*` methods of `EvaluationDomain` use a single offset, the
+//! field's multiplicative generator. A `Coset` makes the offset explicit,
+//! so that several cosets of one domain (as in PLONK-style quotient
+//! computations over an extended domain), or cosets of several domains,
+//! can be kept apart.
+
+use crate::{
+    domain::{DomainCoeff, EvaluationDomain},
+    univariate::SparsePolynomial,
+    Polynomial,
+};
+use ark_ff::FftField;
+use ark_std::vec::Vec;
+
+/// The coset `offset * D` of the domain `D`.
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Debug)]
+pub struct Coset<F: FftField, D: EvaluationDomain<F>> {
+    domain: D,
+    offset: F,
+    offset_inv: F,
+}
+
+impl<F: FftField, D: EvaluationDomain<F>> Coset<F, D> {
+    /// The coset of `domain` by `offset`, unless `offset` is zero or in the
+    /// domain. For the multiplicative subgroups of the radix-2 and mixed-radix
+    /// domains, the coset is then disjoint from the domain.
+    pub fn new(domain: D, offset: F) -> Option<Self> {
+        if domain.evaluate_vanishing_polynomial(offset).is_zero() {
+            return None;
+        }
+        Some(Self {
+            domain,
+            offset,
+            offset_inv: offset.inverse()?,
+        })
+    }
+
+    /// The coset that the `coset_*` methods of `EvaluationDomain` use, by the
+    /// multiplicative generator of the field.
+    pub fn standard(domain: D) -> Option<Self> {
+        Self::new(domain, F::multiplicative_generator())
+    }
+
+    /// The underlying domain.
+    pub fn domain(&self) -> &D {
+        &self.domain
+    }
+
+    pub fn offset(&self) -> F {
+        self.offset
+    }
+
+    pub fn offset_inv(&self) -> F {
+        self.offset_inv
+    }
+
+    pub fn size(&self) -> usize {
+        self.domain.size()
+    }
+
+    /// Returns the `i`-th element of the coset.
+    pub fn element(&self, i: usize) -> F {
+        self.offset * self.domain.element(i)
+    }
+
+    /// Return an iterator over the elements of the coset.
+    pub fn elements(&self) -> impl Iterator<Item = F> {
+        let offset = self.offset;
+        self.domain.elements().map(move |x| offset * x)
+    }
+
+    /// Whether no element of the coset is in `other`.
+    pub fn is_disjoint_from<E: EvaluationDomain<F>>(&self, other: &E) -> bool {
+        self.elements()
+            .all(|x| !other.evaluate_vanishing_polynomial(x).is_zero())
+    }
+
+    /// Evaluate the polynomial with coefficients `coeffs` over the coset.
+    pub fn fft<T: DomainCoeff<F>>(&self, coeffs: &[T]) -> Vec<T> {
+        let mut coeffs = coeffs.to_vec();
+        self.fft_in_place(&mut coeffs);
+        coeffs
+    }
+
+    /// Evaluate the polynomial with coefficients `coeffs` over the coset, in
+    /// place.
+    pub fn fft_in_place<T: DomainCoeff<F>>(&self, coeffs: &mut Vec<T>) {
+        D::distribute_powers(coeffs, self.offset);
+        self.domain.fft_in_place(coeffs);
+    }
+
+    /// Interpolate the evaluations `evals` over the coset.
+    pub fn ifft<T: DomainCoeff<F>>(&self, evals: &[T]) -> Vec<T> {
+        let mut evals = evals.to_vec();
+        self.ifft_in_place(&mut evals);
+        evals
+    }
+
+    /// Interpolate the evaluations `evals` over the coset, in place.
+    pub fn ifft_in_place<T: DomainCoeff<F>>(&self, evals: &mut Vec<T>) {
+        self.domain.ifft_in_place(evals);
+        D::distribute_powers(evals, self.offset_inv);
+    }
+
+    /// The vanishing polynomial of the coset, `z_C(X) = c^n z_D(X / c)` for
+    /// the offset `c` and the domain's vanishing polynomial `z_D`, of degree
+    /// `n`. For a multiplicative subgroup, this is `X^n - c^n`.
+    pub fn vanishing_polynomial(&self) -> SparsePolynomial<F> {
+        let z = self.domain.vanishing_polynomial();
+        let n = z.degree();
+        let coeffs = z
+            .iter()
+            .map(|(k, a)| (*k, *a * self.offset.pow([(n - k) as u64])))
+            .collect();
+        SparsePolynomial::from_coefficients_vec(coeffs)
+    }
+
+    /// Evaluate the vanishing polynomial of the coset at `tau`.
+    pub fn evaluate_vanishing_polynomial(&self, tau: F) -> F {
+        let n = self.size() as u64;
+        self.offset.pow([n])
+            * self
+                .domain
+                .evaluate_vanishing_polynomial(tau * self.offset_inv)
+    }
+
+    /// Evaluate all the Lagrange polynomials of the coset at `tau`. Scaling
+    /// the points of an interpolation scales its argument, so these are the
+    /// domain's at `tau / c`.
+    pub fn evaluate_all_lagrange_coefficients(&self, tau: F) -> Vec<F> {
+        self.domain
+            .evaluate_all_lagrange_coefficients(tau * self.offset_inv)
+    }
+
+    /// The domain's vanishing polynomial, evaluated over the coset.
+    pub fn domain_vanishing_evals(&self) -> Vec<F> {
+        self.elements()
+            .map(|x| self.domain.evaluate_vanishing_polynomial(x))
+            .collect()
+    }
+
+    /// Divide evaluations over the coset by those of the domain's vanishing
+    /// polynomial, as when computing a quotient.
+    pub fn divide_by_vanishing_poly_in_place(&self, evals: &mut [F]) {
+        let mut z = self.domain_vanishing_evals();
+        ark_ff::batch_inversion(&mut z);
+        evals.iter_mut().zip(z).for_each(|(e, z)| *e *= z);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        domain::Coset,
+        polynomial::{univariate::DensePolynomial, Polynomial, UVPolynomial},
+        ArbitraryEvaluationDomain, EvaluationDomain, MixedRadixEvaluationDomain,
+        Radix2EvaluationDomain,
+    };
+    use ark_ff::{FftField, One, Zero};
+    use ark_std::{test_rng, vec::Vec};
+    use ark_test_curves::{bls12_381::Fr, bn384_small_two_adicity::Fq as BNFq};
+
+    fn check<F: FftField, D: EvaluationDomain<F>>(domain: D) {
+        let rng = &mut test_rng();
+        let n = domain.size();
+        let coset = Coset::new(domain, F::rand(rng)).unwrap();
+        let p = DensePolynomial::<F>::rand(n - 1, rng);
+
+        let evals = coset.fft(&p.coeffs);
+        let points: Vec<F> = coset.elements().collect();
+        assert_eq!(points.len(), n);
+        for (i, (x, e)) in points.iter().zip(&evals).enumerate() {
+            assert_eq!(*x, coset.element(i));
+            assert_eq!(p.evaluate(x), *e);
+        }
+        assert_eq!(coset.ifft(&evals)[..p.coeffs.len()], p.coeffs[..]);
+
+        let z = coset.vanishing_polynomial();
+        assert_eq!(z.degree(), n);
+        let tau = F::rand(rng);
+        assert_eq!(z.evaluate(&tau), coset.evaluate_vanishing_polynomial(tau));
+        for x in &points {
+            assert!(z.evaluate(x).is_zero());
+        }
+
+        let at_tau: F = coset
+            .evaluate_all_lagrange_coefficients(tau)
+            .iter()
+            .zip(&evals)
+            .map(|(l, e)| *l * e)
+            .sum();
+        assert_eq!(at_tau, p.evaluate(&tau));
+
+        // quotients: p z_D / z_D = p
+        let z_d: DensePolynomial<F> = domain.vanishing_polynomial().into();
+        let pz = &p * &z_d;
+        let mut pz_evals: Vec<F> = points.iter().map(|x| pz.evaluate(x)).collect();
+        coset.divide_by_vanishing_poly_in_place(&mut pz_evals);
+        assert_eq!(pz_evals, evals);
+
+        assert!(coset.is_disjoint_from(&domain));
+    }
+
+    #[test]
+    fn cosets() {
+        for log_n in 0..6 {
+            check(Radix2EvaluationDomain::<Fr>::new(1 << log_n).unwrap());
+        }
+        // sizes with a factor of 3 need a correct `LARGE_SUBGROUP_ROOT_OF_UNITY`, which the test
+        // curve lacks
+        for n in &[4, 8, 16] {
+            check(MixedRadixEvaluationDomain::<BNFq>::new(*n).unwrap());
+        }
+        for n in &[1, 5, 7] {
+            check(ArbitraryEvaluationDomain::<Fr>::new(*n).unwrap());
+        }
+    }
+
+    #[test]
+    fn standard_coset() {
+        let rng = &mut test_rng();
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        let coset = Coset::standard(domain).unwrap();
+        assert_eq!(coset.offset(), Fr::multiplicative_generator());
+        let p = DensePolynomial::<Fr>::rand(15, rng);
+        assert_eq!(coset.fft(&p.coeffs), domain.coset_fft(&p.coeffs));
+        let evals = coset.fft(&p.coeffs);
+        assert_eq!(coset.ifft(&evals), domain.coset_ifft(&evals));
+
+        let mut a = evals.clone();
+        let mut b = evals;
+        coset.divide_by_vanishing_poly_in_place(&mut a);
+        domain.divide_by_vanishing_poly_on_coset_in_place(&mut b);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn offsets_in_the_domain() {
+        let domain = Radix2EvaluationDomain::<Fr>::new(8).unwrap();
+        assert!(Coset::new(domain, Fr::zero()).is_none());
+        assert!(Coset::new(domain, Fr::one()).is_none());
+        assert!(Coset::new(domain, domain.element(3)).is_none());
+
+        // a coset of a subgroup by an element of a larger one can meet it
+        let large = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        let coset = Coset::new(domain, large.element(1)).unwrap();
+        assert!(coset.is_disjoint_from(&domain));
+        assert!(!coset.is_disjoint_from(&large));
+        let g = Fr::multiplicative_generator();
+        assert!(Coset::new(domain, g).unwrap().is_disjoint_from(&large));
+    }
+}
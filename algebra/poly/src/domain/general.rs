@@ -4,11 +4,14 @@
 //!
 //! It is a wrapper around specific implementations of `EvaluationDomain` that
 //! automatically chooses the most efficient implementation
-//! depending on the number of coefficients and the two-adicity of the prime.
+//! depending on the number of coefficients and the two-adicity of the prime,
+//! falling back to a quadratic-time domain of any size when the field has no
+//! large enough subgroup.
 
 pub use crate::domain::utils::Elements;
 use crate::domain::{
-    DomainCoeff, EvaluationDomain, MixedRadixEvaluationDomain, Radix2EvaluationDomain,
+    ArbitraryEvaluationDomain, DomainCoeff, EvaluationDomain, MixedRadixEvaluationDomain,
+    Radix2EvaluationDomain, Twiddles,
 };
 use ark_ff::{FftField, FftParameters};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
@@ -19,20 +22,24 @@ use ark_std::{
 
 /// Defines a domain over which finite field (I)FFTs can be performed.
 /// Generally tries to build a radix-2 domain and falls back to a mixed-radix
-/// domain if the radix-2 multiplicative subgroup is too small.
+/// domain if the radix-2 multiplicative subgroup is too small, and to an
+/// arbitrary domain if that is too.
 #[derive(Copy, Clone, Hash, Eq, PartialEq, Debug)]
 pub enum GeneralEvaluationDomain<F: FftField> {
     /// Radix-2 domain
     Radix2(Radix2EvaluationDomain<F>),
     /// Mixed-radix domain
     MixedRadix(MixedRadixEvaluationDomain<F>),
+    /// Arbitrary domain
+    Arbitrary(ArbitraryEvaluationDomain<F>),
 }
 
 macro_rules! map {
     ($self:expr, $f1:ident $(, $x:expr)*) => {
         match $self {
-            Self::Radix2(domain) => EvaluationDomain::$f1(domain, $($x)*),
-            Self::MixedRadix(domain) => EvaluationDomain::$f1(domain, $($x)*),
+            Self::Radix2(domain) => EvaluationDomain::$f1(domain $(, $x)*),
+            Self::MixedRadix(domain) => EvaluationDomain::$f1(domain $(, $x)*),
+            Self::Arbitrary(domain) => EvaluationDomain::$f1(domain $(, $x)*),
         }
     }
 }
@@ -42,12 +49,14 @@ impl<F: FftField> CanonicalSerialize for GeneralEvaluationDomain<F> {
         let type_id = match self {
             GeneralEvaluationDomain::Radix2(_) => 0u8,
             GeneralEvaluationDomain::MixedRadix(_) => 1u8,
+            GeneralEvaluationDomain::Arbitrary(_) => 2u8,
         };
         type_id.serialize(&mut writer)?;
 
         match self {
             GeneralEvaluationDomain::Radix2(domain) => domain.serialize(&mut writer),
             GeneralEvaluationDomain::MixedRadix(domain) => domain.serialize(&mut writer),
+            GeneralEvaluationDomain::Arbitrary(domain) => domain.serialize(&mut writer),
         }
     }
 
@@ -55,12 +64,14 @@ impl<F: FftField> CanonicalSerialize for GeneralEvaluationDomain<F> {
         let type_id = match self {
             GeneralEvaluationDomain::Radix2(_) => 0u8,
             GeneralEvaluationDomain::MixedRadix(_) => 1u8,
+            GeneralEvaluationDomain::Arbitrary(_) => 2u8,
         };
 
         type_id.serialized_size()
             + match self {
                 GeneralEvaluationDomain::Radix2(domain) => domain.serialized_size(),
                 GeneralEvaluationDomain::MixedRadix(domain) => domain.serialized_size(),
+                GeneralEvaluationDomain::Arbitrary(domain) => domain.serialized_size(),
             }
     }
 
@@ -68,6 +79,7 @@ impl<F: FftField> CanonicalSerialize for GeneralEvaluationDomain<F> {
         let type_id = match self {
             GeneralEvaluationDomain::Radix2(_) => 0u8,
             GeneralEvaluationDomain::MixedRadix(_) => 1u8,
+            GeneralEvaluationDomain::Arbitrary(_) => 2u8,
         };
         type_id.serialize_uncompressed(&mut writer)?;
 
@@ -76,6 +88,9 @@ impl<F: FftField> CanonicalSerialize for GeneralEvaluationDomain<F> {
             GeneralEvaluationDomain::MixedRadix(domain) => {
                 domain.serialize_uncompressed(&mut writer)
             }
+            GeneralEvaluationDomain::Arbitrary(domain) => {
+                domain.serialize_uncompressed(&mut writer)
+            }
         }
     }
 
@@ -83,12 +98,14 @@ impl<F: FftField> CanonicalSerialize for GeneralEvaluationDomain<F> {
         let type_id = match self {
             GeneralEvaluationDomain::Radix2(_) => 0u8,
             GeneralEvaluationDomain::MixedRadix(_) => 1u8,
+            GeneralEvaluationDomain::Arbitrary(_) => 2u8,
         };
         type_id.serialize_unchecked(&mut writer)?;
 
         match self {
             GeneralEvaluationDomain::Radix2(domain) => domain.serialize_unchecked(&mut writer),
             GeneralEvaluationDomain::MixedRadix(domain) => domain.serialize_unchecked(&mut writer),
+            GeneralEvaluationDomain::Arbitrary(domain) => domain.serialize_unchecked(&mut writer),
         }
     }
 
@@ -96,12 +113,14 @@ impl<F: FftField> CanonicalSerialize for GeneralEvaluationDomain<F> {
         let type_id = match self {
             GeneralEvaluationDomain::Radix2(_) => 0u8,
             GeneralEvaluationDomain::MixedRadix(_) => 1u8,
+            GeneralEvaluationDomain::Arbitrary(_) => 2u8,
         };
 
         type_id.uncompressed_size()
             + match self {
                 GeneralEvaluationDomain::Radix2(domain) => domain.uncompressed_size(),
                 GeneralEvaluationDomain::MixedRadix(domain) => domain.uncompressed_size(),
+                GeneralEvaluationDomain::Arbitrary(domain) => domain.uncompressed_size(),
             }
     }
 }
@@ -118,6 +137,10 @@ impl<F: FftField> CanonicalDeserialize for GeneralEvaluationDomain<F> {
             Ok(Self::MixedRadix(
                 MixedRadixEvaluationDomain::<F>::deserialize(&mut reader)?,
             ))
+        } else if type_id == 2u8 {
+            Ok(Self::Arbitrary(
+                ArbitraryEvaluationDomain::<F>::deserialize(&mut reader)?,
+            ))
         } else {
             Err(SerializationError::InvalidData)
         }
@@ -134,6 +157,10 @@ impl<F: FftField> CanonicalDeserialize for GeneralEvaluationDomain<F> {
             Ok(Self::MixedRadix(
                 MixedRadixEvaluationDomain::<F>::deserialize_uncompressed(&mut reader)?,
             ))
+        } else if type_id == 2u8 {
+            Ok(Self::Arbitrary(
+                ArbitraryEvaluationDomain::<F>::deserialize_uncompressed(&mut reader)?,
+            ))
         } else {
             Err(SerializationError::InvalidData)
         }
@@ -150,6 +177,10 @@ impl<F: FftField> CanonicalDeserialize for GeneralEvaluationDomain<F> {
             Ok(Self::MixedRadix(
                 MixedRadixEvaluationDomain::<F>::deserialize_unchecked(&mut reader)?,
             ))
+        } else if type_id == 2u8 {
+            Ok(Self::Arbitrary(
+                ArbitraryEvaluationDomain::<F>::deserialize_unchecked(&mut reader)?,
+            ))
         } else {
             Err(SerializationError::InvalidData)
         }
@@ -164,7 +195,8 @@ impl<F: FftField> EvaluationDomain<F> for GeneralEvaluationDomain<F> {
     ///
     /// If the field specifies a small subgroup for a mixed-radix FFT and
     /// the radix-2 FFT cannot be constructed, this method tries
-    /// constructing a mixed-radix FFT instead.
+    /// constructing a mixed-radix FFT instead. If neither can, it
+    /// constructs an arbitrary domain, without an FFT.
     fn new(num_coeffs: usize) -> Option<Self> {
         let domain = Radix2EvaluationDomain::new(num_coeffs);
         if let Some(domain) = domain {
@@ -172,12 +204,14 @@ impl<F: FftField> EvaluationDomain<F> for GeneralEvaluationDomain<F> {
         }
 
         if F::FftParams::SMALL_SUBGROUP_BASE.is_some() {
-            return Some(GeneralEvaluationDomain::MixedRadix(
-                MixedRadixEvaluationDomain::new(num_coeffs)?,
-            ));
+            if let Some(domain) = MixedRadixEvaluationDomain::new(num_coeffs) {
+                return Some(GeneralEvaluationDomain::MixedRadix(domain));
+            }
         }
 
-        None
+        Some(GeneralEvaluationDomain::Arbitrary(
+            ArbitraryEvaluationDomain::new(num_coeffs)?,
+        ))
     }
 
     fn compute_size_of_domain(num_coeffs: usize) -> Option<usize> {
@@ -187,12 +221,14 @@ impl<F: FftField> EvaluationDomain<F> for GeneralEvaluationDomain<F> {
         }
 
         if F::FftParams::SMALL_SUBGROUP_BASE.is_some() {
-            return Some(MixedRadixEvaluationDomain::<F>::compute_size_of_domain(
-                num_coeffs,
-            )?);
+            if let Some(domain_size) =
+                MixedRadixEvaluationDomain::<F>::compute_size_of_domain(num_coeffs)
+            {
+                return Some(domain_size);
+            }
         }
 
-        None
+        ArbitraryEvaluationDomain::<F>::compute_size_of_domain(num_coeffs)
     }
 
     #[inline]
@@ -220,6 +256,39 @@ impl<F: FftField> EvaluationDomain<F> for GeneralEvaluationDomain<F> {
         map!(self, coset_ifft_in_place, evals)
     }
 
+    #[inline]
+    fn twiddles(&self) -> Twiddles<F> {
+        map!(self, twiddles)
+    }
+
+    #[inline]
+    fn fft_in_place_with<T: DomainCoeff<F>>(&self, twiddles: &Twiddles<F>, coeffs: &mut Vec<T>) {
+        map!(self, fft_in_place_with, twiddles, coeffs)
+    }
+
+    #[inline]
+    fn ifft_in_place_with<T: DomainCoeff<F>>(&self, twiddles: &Twiddles<F>, evals: &mut Vec<T>) {
+        map!(self, ifft_in_place_with, twiddles, evals)
+    }
+
+    #[inline]
+    fn coset_fft_in_place_with<T: DomainCoeff<F>>(
+        &self,
+        twiddles: &Twiddles<F>,
+        coeffs: &mut Vec<T>,
+    ) {
+        map!(self, coset_fft_in_place_with, twiddles, coeffs)
+    }
+
+    #[inline]
+    fn coset_ifft_in_place_with<T: DomainCoeff<F>>(
+        &self,
+        twiddles: &Twiddles<F>,
+        evals: &mut Vec<T>,
+    ) {
+        map!(self, coset_ifft_in_place_with, twiddles, evals)
+    }
+
     #[inline]
     fn evaluate_all_lagrange_coefficients(&self, tau: F) -> Vec<F> {
         map!(self, evaluate_all_lagrange_coefficients, tau)
@@ -244,6 +313,11 @@ impl<F: FftField> EvaluationDomain<F> for GeneralEvaluationDomain<F> {
     fn elements(&self) -> GeneralElements<F> {
         GeneralElements(map!(self, elements))
     }
+
+    #[inline]
+    fn divide_by_vanishing_poly_on_coset_in_place(&self, evals: &mut [F]) {
+        map!(self, divide_by_vanishing_poly_on_coset_in_place, evals)
+    }
 }
 
 /// A generalized version of an iterator over the elements of a domain.
@@ -263,8 +337,10 @@ mod tests {
     use crate::polynomial::Polynomial;
     use crate::{EvaluationDomain, GeneralEvaluationDomain};
     use ark_ff::Zero;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
     use ark_std::rand::Rng;
     use ark_std::test_rng;
+    use ark_std::vec::Vec;
     use ark_test_curves::bls12_381::Fr;
     use ark_test_curves::bn384_small_two_adicity::Fr as BNFr;
 
@@ -307,6 +383,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn falls_back_to_arbitrary() {
+        // past 2^12 * 3^2, the largest mixed-radix domain
+        let size = (1 << 12) * 9 + 1;
+        assert_eq!(
+            GeneralEvaluationDomain::<BNFr>::compute_size_of_domain(size),
+            Some(size)
+        );
+        let domain = GeneralEvaluationDomain::<BNFr>::new(size).unwrap();
+        assert!(matches!(domain, GeneralEvaluationDomain::Arbitrary(_)));
+        assert_eq!(domain.size(), size);
+
+        let mut bytes = Vec::new();
+        domain.serialize(&mut bytes).unwrap();
+        assert_eq!(
+            GeneralEvaluationDomain::<BNFr>::deserialize(&bytes[..]).unwrap(),
+            domain
+        );
+    }
+
     #[test]
     fn size_of_elements() {
         for coeffs in 1..10 {
@@ -15,11 +15,15 @@ use ark_std::{fmt, hash, vec::Vec};
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
+pub mod arbitrary;
+pub mod coset;
 pub mod general;
 pub mod mixed_radix;
 pub mod radix2;
 pub(crate) mod utils;
 
+pub use arbitrary::ArbitraryEvaluationDomain;
+pub use coset::Coset;
 pub use general::GeneralEvaluationDomain;
 pub use mixed_radix::MixedRadixEvaluationDomain;
 pub use radix2::Radix2EvaluationDomain;
@@ -109,7 +113,7 @@ pub trait EvaluationDomain<F: FftField>:
     #[cfg(feature = "parallel")]
     fn distribute_powers_and_mul_by_const<T: DomainCoeff<F>>(coeffs: &mut [T], g: F, c: F) {
         use ark_std::cmp::max;
-        let min_parallel_chunk_size = 1024;
+        let min_parallel_chunk_size = utils::min_parallel_len::<T, F>(utils::MIN_PARALLEL_WORK);
         let num_cpus_available = rayon::current_num_threads();
         let num_elem_per_thread = max(coeffs.len() / num_cpus_available, min_parallel_chunk_size);
 
@@ -125,6 +129,12 @@ pub trait EvaluationDomain<F: FftField>:
             });
     }
 
+    /// The coset of the domain by `offset`, if `offset` is not zero or in the
+    /// domain.
+    fn coset(&self, offset: F) -> Option<Coset<F, Self>> {
+        Coset::new(*self, offset)
+    }
+
     /// Compute a FFT over a coset of the domain.
     #[inline]
     fn coset_fft<T: DomainCoeff<F>>(&self, coeffs: &[T]) -> Vec<T> {
@@ -157,6 +167,52 @@ pub trait EvaluationDomain<F: FftField>:
         Self::distribute_powers(evals, F::multiplicative_generator().inverse().unwrap());
     }
 
+    /// The roots of unity that this domain's FFTs multiply by, for the `_with`
+    /// transforms ([EvaluationDomain::fft_in_place_with] and its kin) to share,
+    /// rather than compute them afresh in each. Empty for domains whose FFTs
+    /// compute no such roots; of those here, only radix-2 domains do.
+    fn twiddles(&self) -> Twiddles<F> {
+        Twiddles {
+            size: self.size(),
+            roots: Vec::new(),
+            inv_roots: Vec::new(),
+        }
+    }
+
+    /// [EvaluationDomain::fft_in_place], with the roots of unity from
+    /// `twiddles`, which must be this domain's.
+    fn fft_in_place_with<T: DomainCoeff<F>>(&self, _twiddles: &Twiddles<F>, coeffs: &mut Vec<T>) {
+        self.fft_in_place(coeffs)
+    }
+
+    /// [EvaluationDomain::ifft_in_place], with the roots of unity from
+    /// `twiddles`, which must be this domain's.
+    fn ifft_in_place_with<T: DomainCoeff<F>>(&self, _twiddles: &Twiddles<F>, evals: &mut Vec<T>) {
+        self.ifft_in_place(evals)
+    }
+
+    /// [EvaluationDomain::coset_fft_in_place], with the roots of unity from
+    /// `twiddles`, which must be this domain's.
+    fn coset_fft_in_place_with<T: DomainCoeff<F>>(
+        &self,
+        twiddles: &Twiddles<F>,
+        coeffs: &mut Vec<T>,
+    ) {
+        Self::distribute_powers(coeffs, F::multiplicative_generator());
+        self.fft_in_place_with(twiddles, coeffs);
+    }
+
+    /// [EvaluationDomain::coset_ifft_in_place], with the roots of unity from
+    /// `twiddles`, which must be this domain's.
+    fn coset_ifft_in_place_with<T: DomainCoeff<F>>(
+        &self,
+        twiddles: &Twiddles<F>,
+        evals: &mut Vec<T>,
+    ) {
+        self.ifft_in_place_with(twiddles, evals);
+        Self::distribute_powers(evals, F::multiplicative_generator().inverse().unwrap());
+    }
+
     /// Evaluate all the lagrange polynomials defined by this domain at the
     /// point `tau`. This is computed in time O(|domain|).
     /// Then given the evaluations of a degree d polynomial P over this domain,
@@ -232,6 +288,32 @@ pub trait EvaluationDomain<F: FftField>:
     }
 }
 
+/// The roots of unity of a domain's FFTs, from [EvaluationDomain::twiddles].
+///
+/// A radix-2 domain of size `n` with generator `w` needs `w^i` and `w^-i` for
+/// `i < n / 2`, so these take `n` field elements.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Twiddles<F> {
+    /// The size of the domain.
+    pub(crate) size: usize,
+    /// `w^i`, for the FFT.
+    pub(crate) roots: Vec<F>,
+    /// `w^-i`, for the inverse FFT.
+    pub(crate) inv_roots: Vec<F>,
+}
+
+impl<F> Twiddles<F> {
+    /// The size of the domain they are for.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether there are none, as for domains that compute no roots of unity.
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+}
+
 /// Types that can be FFT-ed must implement this trait.
 pub trait DomainCoeff<F: FftField>:
     Copy
@@ -244,6 +326,13 @@ pub trait DomainCoeff<F: FftField>:
     + ark_ff::Zero
     + core::ops::MulAssign<F>
 {
+    /// What `self *= f` costs, relative to a multiplication in a plain prime field.
+    ///
+    /// The parallel FFTs hand each thread enough coefficients for a fixed amount of work, so
+    /// costlier coefficients, such as group elements or shared values, are split into smaller
+    /// chunks, and keep every thread busy. Every type gets `1` from the blanket impl; a crate
+    /// with a costlier type overrides it with an impl of its own, under `specialization`.
+    const MUL_COST: usize;
 }
 
 impl<T, F> DomainCoeff<F> for T
@@ -259,4 +348,5 @@ where
         + ark_ff::Zero
         + core::ops::MulAssign<F>,
 {
+    default const MUL_COST: usize = 1;
 }
@@ -1,10 +1,10 @@
 // The code below is a port of the excellent library of https://github.com/kwantam/fffft by Riad S. Wahby
 // to the arkworks APIs
 
-use crate::domain::utils::compute_powers_serial;
-use crate::domain::{radix2::*, DomainCoeff};
+use crate::domain::utils::{compute_powers_serial, min_parallel_len};
+use crate::domain::{radix2::*, DomainCoeff, Twiddles};
 use ark_ff::FftField;
-use ark_std::{cfg_iter_mut, vec::Vec};
+use ark_std::{borrow::Cow, cfg_iter_mut, vec::Vec};
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
@@ -18,31 +18,64 @@ enum FFTOrder {
     OI,
 }
 
+// Each transform takes its roots of unity from `twiddles` if given, and
+// computes them otherwise.
 impl<F: FftField> Radix2EvaluationDomain<F> {
-    pub(crate) fn in_order_fft_in_place<T: DomainCoeff<F>>(&self, x_s: &mut [T]) {
-        self.fft_helper_in_place(x_s, FFTOrder::II)
+    pub(crate) fn in_order_fft_in_place<T: DomainCoeff<F>>(
+        &self,
+        x_s: &mut [T],
+        twiddles: Option<&Twiddles<F>>,
+    ) {
+        self.fft_helper_in_place(x_s, FFTOrder::II, twiddles)
     }
 
-    pub(crate) fn in_order_ifft_in_place<T: DomainCoeff<F>>(&self, x_s: &mut [T]) {
-        self.ifft_helper_in_place(x_s, FFTOrder::II);
+    pub(crate) fn in_order_ifft_in_place<T: DomainCoeff<F>>(
+        &self,
+        x_s: &mut [T],
+        twiddles: Option<&Twiddles<F>>,
+    ) {
+        self.ifft_helper_in_place(x_s, FFTOrder::II, twiddles);
         ark_std::cfg_iter_mut!(x_s).for_each(|val| *val *= self.size_inv);
     }
 
-    pub(crate) fn in_order_coset_ifft_in_place<T: DomainCoeff<F>>(&self, x_s: &mut [T]) {
-        self.ifft_helper_in_place(x_s, FFTOrder::II);
+    pub(crate) fn in_order_coset_ifft_in_place<T: DomainCoeff<F>>(
+        &self,
+        x_s: &mut [T],
+        twiddles: Option<&Twiddles<F>>,
+    ) {
+        self.ifft_helper_in_place(x_s, FFTOrder::II, twiddles);
         let coset_shift = self.generator_inv;
         Self::distribute_powers_and_mul_by_const(x_s, coset_shift, self.size_inv);
     }
 
-    fn fft_helper_in_place<T: DomainCoeff<F>>(&self, x_s: &mut [T], ord: FFTOrder) {
+    /// `twiddles`, if they are for this domain.
+    pub(super) fn checked<'a>(&self, twiddles: &'a Twiddles<F>) -> &'a Twiddles<F> {
+        assert_eq!(
+            (twiddles.size, twiddles.roots.len()),
+            (self.size(), self.size() / 2),
+            "twiddles of another domain"
+        );
+        twiddles
+    }
+
+    fn fft_helper_in_place<T: DomainCoeff<F>>(
+        &self,
+        x_s: &mut [T],
+        ord: FFTOrder,
+        twiddles: Option<&Twiddles<F>>,
+    ) {
         use FFTOrder::*;
 
         let log_len = ark_std::log2(x_s.len());
+        let roots = match twiddles {
+            Some(t) => Cow::Borrowed(&t.roots[..]),
+            None => Cow::Owned(self.roots_of_unity(self.group_gen)),
+        };
 
         if ord == OI {
-            self.oi_helper(x_s, self.group_gen);
+            Self::oi_helper(x_s, &roots);
         } else {
-            self.io_helper(x_s, self.group_gen);
+            Self::io_helper(x_s, roots);
         }
 
         if ord == II {
@@ -53,19 +86,28 @@ impl<F: FftField> Radix2EvaluationDomain<F> {
     // Handles doing an IFFT with handling of being in order and out of order.
     // The results here must all be divided by |x_s|,
     // which is left up to the caller to do.
-    fn ifft_helper_in_place<T: DomainCoeff<F>>(&self, x_s: &mut [T], ord: FFTOrder) {
+    fn ifft_helper_in_place<T: DomainCoeff<F>>(
+        &self,
+        x_s: &mut [T],
+        ord: FFTOrder,
+        twiddles: Option<&Twiddles<F>>,
+    ) {
         use FFTOrder::*;
 
         let log_len = ark_std::log2(x_s.len());
+        let roots = match twiddles {
+            Some(t) => Cow::Borrowed(&t.inv_roots[..]),
+            None => Cow::Owned(self.roots_of_unity(self.group_gen_inv)),
+        };
 
         if ord == II {
             derange(x_s, log_len);
         }
 
         if ord == IO {
-            self.io_helper(x_s, self.group_gen_inv);
+            Self::io_helper(x_s, roots);
         } else {
-            self.oi_helper(x_s, self.group_gen_inv);
+            Self::oi_helper(x_s, &roots);
         }
     }
 
@@ -137,21 +179,21 @@ impl<F: FftField> Radix2EvaluationDomain<F> {
             });
     }
 
-    fn io_helper<T: DomainCoeff<F>>(&self, xi: &mut [T], root: F) {
+    fn io_helper<T: DomainCoeff<F>>(xi: &mut [T], mut roots: Cow<'_, [F]>) {
         // In the sequential case, we will keep on making the roots cache-aligned,
-        // according to the access pattern that the FFT uses.
-        // It is left as a TODO to implement this for the parallel case
-        let roots = &mut self.roots_of_unity(root);
-
-        #[cfg(not(feature = "parallel"))]
+        // according to the access pattern that the FFT uses, if they are our own
+        // to rearrange. It is left as a TODO to implement this for the parallel case
+        let align = cfg!(not(feature = "parallel")) && matches!(roots, Cow::Owned(_));
         let mut root_len = roots.len();
 
         let mut gap = xi.len() / 2;
         while gap > 0 {
             // each butterfly cluster uses 2*gap positions
             let chunk_size = 2 * gap;
-            #[cfg(feature = "parallel")]
             let nchunks = xi.len() / chunk_size;
+            // Once aligned, the index into roots is the chunk index
+            let stride = if align { 1 } else { nchunks };
+            let roots_ref = &roots[..];
 
             let butterfly_fn = |(chunk_index, (lo, hi)): (usize, (&mut T, &mut T))| {
                 let neg = *lo - *hi;
@@ -159,13 +201,7 @@ impl<F: FftField> Radix2EvaluationDomain<F> {
 
                 *hi = neg;
 
-                #[cfg(feature = "parallel")]
-                let index = nchunks * chunk_index;
-                // Due to cache aligning, the index into roots is the chunk index
-                #[cfg(not(feature = "parallel"))]
-                let index = chunk_index;
-
-                *hi *= roots[index];
+                *hi *= roots_ref[stride * chunk_index];
             };
 
             ark_std::cfg_chunks_mut!(xi, chunk_size).for_each(|cxi| {
@@ -173,8 +209,8 @@ impl<F: FftField> Radix2EvaluationDomain<F> {
                 // If the chunk is sufficiently big that parallelism helps,
                 // we parallelize the butterfly operation within the chunk.
                 //
-                // if chunk_size > MIN_CHUNK_SIZE_FOR_PARALLELIZATION
-                if gap > MIN_CHUNK_SIZE_FOR_PARALLELIZATION / 2 {
+                // if chunk_size > MIN_CHUNK_SIZE_FOR_PARALLELIZATION, for plain coefficients
+                if gap > min_parallel_len::<T, F>(MIN_CHUNK_SIZE_FOR_PARALLELIZATION) / 2 {
                     cfg_iter_mut!(lo).zip(hi).enumerate().for_each(butterfly_fn);
                 } else {
                     lo.iter_mut().zip(hi).enumerate().for_each(butterfly_fn);
@@ -191,8 +227,8 @@ impl<F: FftField> Radix2EvaluationDomain<F> {
             //
             // (Roots are already aligned in the first iteration,
             // so we only to do realignment after the first iteration.)
-            #[cfg(not(feature = "parallel"))]
-            {
+            if align {
+                let roots = roots.to_mut();
                 for i in 1..(root_len / 2) {
                     roots[i] = roots[i * 2];
                 }
@@ -202,9 +238,7 @@ impl<F: FftField> Radix2EvaluationDomain<F> {
         }
     }
 
-    fn oi_helper<T: DomainCoeff<F>>(&self, xi: &mut [T], root: F) {
-        let roots = self.roots_of_unity(root);
-
+    fn oi_helper<T: DomainCoeff<F>>(xi: &mut [T], roots: &[F]) {
         let mut gap = 1;
         while gap < xi.len() {
             let chunk_size = 2 * gap;
@@ -222,8 +256,8 @@ impl<F: FftField> Radix2EvaluationDomain<F> {
                 // If the chunk is sufficiently big that parallelism helps,
                 // we parallelize the butterfly operation within the chunk.
                 //
-                // if chunk_size > MIN_CHUNK_SIZE_FOR_PARALLELIZATION
-                if gap > MIN_CHUNK_SIZE_FOR_PARALLELIZATION / 2 {
+                // if chunk_size > MIN_CHUNK_SIZE_FOR_PARALLELIZATION, for plain coefficients
+                if gap > min_parallel_len::<T, F>(MIN_CHUNK_SIZE_FOR_PARALLELIZATION) / 2 {
                     cfg_iter_mut!(lo).zip(hi).enumerate().for_each(butterfly_fn);
                 } else {
                     lo.iter_mut().zip(hi).enumerate().for_each(butterfly_fn);
@@ -238,7 +272,8 @@ impl<F: FftField> Radix2EvaluationDomain<F> {
 // do you parallelize operations on the chunk.
 // If c > MIN_CHUNK_SIZE_FOR_PARALLELIZATION,
 // then parallelize, else be sequential.
-// This value was chosen empirically.
+// This value was chosen empirically, for coefficients whose `MUL_COST` is 1; costlier ones
+// parallelize smaller chunks.
 const MIN_CHUNK_SIZE_FOR_PARALLELIZATION: usize = 2048;
 
 // minimum size at which to parallelize.
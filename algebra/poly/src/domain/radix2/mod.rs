@@ -4,7 +4,7 @@
 //! FFTs of size at most `2^F::TWO_ADICITY`.
 
 pub use crate::domain::utils::Elements;
-use crate::domain::{DomainCoeff, EvaluationDomain};
+use crate::domain::{DomainCoeff, EvaluationDomain, Twiddles};
 use ark_ff::{FftField, FftParameters};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use ark_std::{
@@ -99,21 +99,54 @@ impl<F: FftField> EvaluationDomain<F> for Radix2EvaluationDomain<F> {
     fn fft_in_place<T: DomainCoeff<F>>(&self, coeffs: &mut Vec<T>) {
         assert!(coeffs.len() <= self.size());
         coeffs.resize(self.size(), T::zero());
-        self.in_order_fft_in_place(&mut *coeffs)
+        self.in_order_fft_in_place(&mut *coeffs, None)
     }
 
     #[inline]
     fn ifft_in_place<T: DomainCoeff<F>>(&self, evals: &mut Vec<T>) {
         assert!(evals.len() <= self.size());
         evals.resize(self.size(), T::zero());
-        self.in_order_ifft_in_place(&mut *evals);
+        self.in_order_ifft_in_place(&mut *evals, None);
     }
 
     #[inline]
     fn coset_ifft_in_place<T: DomainCoeff<F>>(&self, evals: &mut Vec<T>) {
         assert!(evals.len() <= self.size());
         evals.resize(self.size(), T::zero());
-        self.in_order_coset_ifft_in_place(&mut *evals);
+        self.in_order_coset_ifft_in_place(&mut *evals, None);
+    }
+
+    fn twiddles(&self) -> Twiddles<F> {
+        Twiddles {
+            size: self.size(),
+            roots: self.roots_of_unity(self.group_gen),
+            inv_roots: self.roots_of_unity(self.group_gen_inv),
+        }
+    }
+
+    #[inline]
+    fn fft_in_place_with<T: DomainCoeff<F>>(&self, twiddles: &Twiddles<F>, coeffs: &mut Vec<T>) {
+        assert!(coeffs.len() <= self.size());
+        coeffs.resize(self.size(), T::zero());
+        self.in_order_fft_in_place(&mut *coeffs, Some(self.checked(twiddles)))
+    }
+
+    #[inline]
+    fn ifft_in_place_with<T: DomainCoeff<F>>(&self, twiddles: &Twiddles<F>, evals: &mut Vec<T>) {
+        assert!(evals.len() <= self.size());
+        evals.resize(self.size(), T::zero());
+        self.in_order_ifft_in_place(&mut *evals, Some(self.checked(twiddles)));
+    }
+
+    #[inline]
+    fn coset_ifft_in_place_with<T: DomainCoeff<F>>(
+        &self,
+        twiddles: &Twiddles<F>,
+        evals: &mut Vec<T>,
+    ) {
+        assert!(evals.len() <= self.size());
+        evals.resize(self.size(), T::zero());
+        self.in_order_coset_ifft_in_place(&mut *evals, Some(self.checked(twiddles)));
     }
 
     fn evaluate_all_lagrange_coefficients(&self, tau: F) -> Vec<F> {
@@ -359,6 +392,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fft_with_twiddles() {
+        // The transforms give the same with the domain's twiddles as without,
+        // however many times the twiddles are reused.
+        use crate::GeneralEvaluationDomain;
+        let rng = &mut test_rng();
+        for log_domain_size in 0..8 {
+            let domain_size = 1 << log_domain_size;
+            let domain = Radix2EvaluationDomain::<Fr>::new(domain_size).unwrap();
+            let twiddles = domain.twiddles();
+            assert_eq!(twiddles.size(), domain_size);
+            let general = GeneralEvaluationDomain::<Fr>::new(domain_size).unwrap();
+            assert_eq!(general.twiddles(), twiddles);
+
+            let coeffs: Vec<Fr> = (0..domain_size).map(|_| Fr::rand(rng)).collect();
+            let (mut plain, mut cached) = (coeffs.clone(), coeffs.clone());
+            domain.fft_in_place(&mut plain);
+            domain.fft_in_place_with(&twiddles, &mut cached);
+            assert_eq!(plain, cached);
+            domain.ifft_in_place(&mut plain);
+            general.ifft_in_place_with(&twiddles, &mut cached);
+            assert_eq!(plain, cached);
+            assert_eq!(cached, coeffs);
+            domain.coset_fft_in_place(&mut plain);
+            general.coset_fft_in_place_with(&twiddles, &mut cached);
+            assert_eq!(plain, cached);
+            domain.coset_ifft_in_place(&mut plain);
+            domain.coset_ifft_in_place_with(&twiddles, &mut cached);
+            assert_eq!(plain, cached);
+            assert_eq!(cached, coeffs);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "twiddles of another domain")]
+    fn test_twiddles_of_another_domain() {
+        let domain = Radix2EvaluationDomain::<Fr>::new(8).unwrap();
+        let twiddles = Radix2EvaluationDomain::<Fr>::new(16).unwrap().twiddles();
+        domain.fft_in_place_with(&twiddles, &mut vec![Fr::one(); 8]);
+    }
+
     #[test]
     fn test_roots_of_unity() {
         // Tests that the roots of unity result is the same as domain.elements()
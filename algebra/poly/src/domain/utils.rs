@@ -9,6 +9,18 @@ use rayon::prelude::*;
 #[cfg(feature = "parallel")]
 const MIN_PARALLEL_CHUNK_SIZE: usize = 1 << 7;
 
+/// Plain field multiplications worth a thread of their own, in a parallel loop over
+/// coefficients.
+#[allow(unused)]
+pub(crate) const MIN_PARALLEL_WORK: usize = 1024;
+
+/// Fewest coefficients of type `T` to hand a thread of its own, for about `work` plain field
+/// multiplications: `work` plain coefficients, and fewer costlier ones.
+#[inline]
+pub(crate) fn min_parallel_len<T: DomainCoeff<F>, F: FftField>(work: usize) -> usize {
+    ark_std::cmp::max(work / T::MUL_COST, 1)
+}
+
 #[inline]
 pub(crate) fn bitreverse(mut n: u32, l: u32) -> u32 {
     let mut r = 0;
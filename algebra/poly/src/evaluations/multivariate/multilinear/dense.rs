@@ -1,6 +1,8 @@
 //! Multilinear polynomial represented in dense evaluation form.
 
-use crate::evaluations::multivariate::multilinear::{swap_bits, MultilinearExtension};
+use crate::evaluations::multivariate::multilinear::{
+    eq_evaluations, swap_bits, MultilinearExtension,
+};
 use ark_ff::{Field, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 use ark_std::fmt;
@@ -77,6 +79,43 @@ impl<F: Field> DenseMultilinearExtension<F> {
     pub fn iter_mut(&mut self) -> IterMut<'_, F> {
         self.evaluations.iter_mut()
     }
+
+    /// Fix the first `partial_point.len()` variables at `partial_point`, in place, as each round
+    /// of a sumcheck does.
+    ///
+    /// Each evaluation is only ever multiplied by coordinates of the point, so with a public
+    /// point, shared evaluations need no interaction.
+    pub fn fix_variables_in_place(&mut self, partial_point: &[F]) {
+        assert!(
+            partial_point.len() <= self.num_vars,
+            "invalid size of partial point"
+        );
+        for r in partial_point {
+            let half = self.evaluations.len() / 2;
+            for b in 0..half {
+                let lo = self.evaluations[b << 1];
+                let hi = self.evaluations[(b << 1) + 1];
+                self.evaluations[b] = lo + (hi - lo) * r;
+            }
+            self.evaluations.truncate(half);
+            self.num_vars -= 1;
+        }
+    }
+
+    /// The product `self(x) * other(y)`, a multilinear extension in the variables of `self`
+    /// followed by those of `other`.
+    pub fn tensor_product(&self, other: &Self) -> Self {
+        let mut evaluations = Vec::with_capacity(self.evaluations.len() * other.evaluations.len());
+        for b in &other.evaluations {
+            evaluations.extend(self.evaluations.iter().map(|a| *a * b));
+        }
+        Self::from_evaluations_vec(self.num_vars + other.num_vars, evaluations)
+    }
+
+    /// The multilinear extension of `eq(point, x)`. See [eq_evaluations].
+    pub fn eq_extension(point: &[F]) -> Self {
+        Self::from_evaluations_vec(point.len(), eq_evaluations(point))
+    }
 }
 
 impl<F: Field> MultilinearExtension<F> for DenseMultilinearExtension<F> {
@@ -110,17 +149,9 @@ impl<F: Field> MultilinearExtension<F> for DenseMultilinearExtension<F> {
             partial_point.len() <= self.num_vars,
             "invalid size of partial point"
         );
-        let mut poly = self.evaluations.to_vec();
-        let nv = self.num_vars;
-        let dim = partial_point.len();
-        // evaluate single variable of partial point from left to right
-        for i in 1..dim + 1 {
-            let r = partial_point[i - 1];
-            for b in 0..(1 << (nv - i)) {
-                poly[b] = poly[b << 1] * (F::one() - r) + poly[(b << 1) + 1] * r;
-            }
-        }
-        Self::from_evaluations_slice(nv - dim, &poly[..(1 << (nv - dim))])
+        let mut poly = self.clone();
+        poly.fix_variables_in_place(partial_point);
+        poly
     }
 
     fn to_evaluations(&self) -> Vec<F> {
@@ -269,7 +300,7 @@ impl<F: Field> Zero for DenseMultilinearExtension<F> {
 mod tests {
     use crate::DenseMultilinearExtension;
     use crate::MultilinearExtension;
-    use ark_ff::{Field, Zero};
+    use ark_ff::{Field, One, Zero};
     use ark_std::ops::Neg;
     use ark_std::vec::Vec;
     use ark_std::{test_rng, UniformRand};
@@ -306,6 +337,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn fix_variables_in_place() {
+        let mut rng = test_rng();
+        let poly = DenseMultilinearExtension::<Fr>::rand(8, &mut rng);
+        let point: Vec<_> = (0..8).map(|_| Fr::rand(&mut rng)).collect();
+        let mut folded = poly.clone();
+        for (i, r) in point.iter().enumerate() {
+            folded.fix_variables_in_place(&[*r]);
+            assert_eq!(folded.num_vars, 7 - i);
+            assert_eq!(folded, poly.fix_variables(&point[..=i]));
+        }
+        assert_eq!(folded.evaluations, vec![poly.evaluate(&point).unwrap()]);
+    }
+
+    #[test]
+    fn tensor_products() {
+        let mut rng = test_rng();
+        let p = DenseMultilinearExtension::<Fr>::rand(3, &mut rng);
+        let q = DenseMultilinearExtension::<Fr>::rand(4, &mut rng);
+        let x: Vec<_> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+        let y: Vec<_> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+        let pq = p.tensor_product(&q);
+        assert_eq!(pq.num_vars, 7);
+        let xy: Vec<_> = x.iter().chain(&y).cloned().collect();
+        assert_eq!(
+            pq.evaluate(&xy).unwrap(),
+            p.evaluate(&x).unwrap() * q.evaluate(&y).unwrap()
+        );
+
+        // eq(x, .) picks out x, on the hypercube and off it
+        let eq = DenseMultilinearExtension::eq_extension(&x);
+        let at_y = y[..3]
+            .iter()
+            .zip(&x)
+            .map(|(a, b)| *a * b + (Fr::one() - a) * (Fr::one() - b));
+        assert_eq!(eq.evaluate(&y[..3]).unwrap(), at_y.product());
+        for i in 0..8 {
+            let bits: Vec<_> = (0..3).map(|j| Fr::from(((i >> j) & 1) as u64)).collect();
+            assert_eq!(eq.evaluate(&bits).unwrap(), eq[i]);
+        }
+        let inner: Fr = eq.iter().zip(p.iter()).map(|(e, v)| *e * v).sum();
+        assert_eq!(inner, p.evaluate(&x).unwrap());
+        assert_eq!(
+            DenseMultilinearExtension::<Fr>::eq_extension(&[]).evaluations,
+            vec![Fr::one()]
+        );
+    }
+
     #[test]
     fn relabel_polynomial() {
         let mut rng = test_rng();
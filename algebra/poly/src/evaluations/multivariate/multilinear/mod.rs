@@ -61,6 +61,26 @@ pub trait MultilinearExtension<F: Field>:
     fn to_evaluations(&self) -> Vec<F>;
 }
 
+/// The evaluations over {0,1}^`point.len()` of `eq(point, x)`, the
+/// multilinear extension of the indicator of `x == point`: the tensor product
+/// of the vectors `(1 - r_i, r_i)`, for the coordinates `r_i` of `point`.
+///
+/// The value of a multilinear extension at `point` is its inner product with
+/// these, so with a public point they are the public weights for evaluating
+/// one with shared evaluations.
+pub fn eq_evaluations<F: Field>(point: &[F]) -> Vec<F> {
+    let mut eq = Vec::with_capacity(1 << point.len());
+    eq.push(F::one());
+    for (i, r) in point.iter().enumerate() {
+        for b in 0..(1 << i) {
+            let hi = eq[b] * r;
+            eq[b] -= hi;
+            eq.push(hi);
+        }
+    }
+    eq
+}
+
 /// swap the bits of `x` from position `a..a+n` to `b..b+n` and from `b..b+n` to `a..a+n` in little endian order
 pub(crate) fn swap_bits(x: usize, a: usize, b: usize, n: usize) -> usize {
     let a_bits = (x >> a) & ((1usize << n) - 1);
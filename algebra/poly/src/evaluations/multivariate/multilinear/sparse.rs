@@ -1,6 +1,6 @@
 //! multilinear polynomial represented in sparse evaluation form.
 
-use crate::evaluations::multivariate::multilinear::swap_bits;
+use crate::evaluations::multivariate::multilinear::{eq_evaluations, swap_bits};
 use crate::{DenseMultilinearExtension, MultilinearExtension};
 use ark_ff::{Field, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
@@ -89,23 +89,25 @@ impl<F: Field> SparseMultilinearExtension<F> {
         }
         DenseMultilinearExtension::from_evaluations_vec(self.num_vars, evaluations)
     }
-}
 
-/// utility: precompute f(x) = eq(g,x)
-fn precompute_eq<F: Field>(g: &[F]) -> Vec<F> {
-    let dim = g.len();
-    let mut dp = Vec::with_capacity(1 << dim);
-    dp.resize(1 << dim, F::zero());
-    dp[0] = F::one() - g[0];
-    dp[1] = g[0];
-    for i in 1..dim {
-        let dp_prev = (&dp[0..(1 << i)]).to_vec();
-        for b in 0..(1 << i) {
-            dp[b] = dp_prev[b] * (F::one() - g[i]);
-            dp[b + (1 << i)] = dp_prev[b] * g[i];
+    /// The product `self(x) * other(y)`, a multilinear extension in the variables of `self`
+    /// followed by those of `other`. Its nonzero entries are the products of theirs.
+    pub fn tensor_product(&self, other: &Self) -> Self {
+        let evaluations = other
+            .evaluations
+            .iter()
+            .flat_map(|(&j, &b)| {
+                self.evaluations
+                    .iter()
+                    .map(move |(&i, &a)| (i + (j << self.num_vars), a * b))
+            })
+            .collect();
+        Self {
+            num_vars: self.num_vars + other.num_vars,
+            evaluations,
+            zero: F::zero(),
         }
     }
-    dp
 }
 
 impl<F: Field> MultilinearExtension<F> for SparseMultilinearExtension<F> {
@@ -170,7 +172,7 @@ impl<F: Field> MultilinearExtension<F> for SparseMultilinearExtension<F> {
             };
             let focus = &point[..focus_length];
             point = &point[focus_length..];
-            let pre = precompute_eq(focus);
+            let pre = eq_evaluations(focus);
             let dim = focus.len();
             let mut result = HashMap::new();
             for src_entry in last.iter() {
@@ -401,6 +403,20 @@ mod tests {
     use ark_std::{test_rng, UniformRand};
     use ark_test_curves::bls12_381::Fr;
     /// Some sanity test to ensure random sparse polynomial make sense.
+    #[test]
+    fn tensor_products() {
+        let mut rng = test_rng();
+        let p = SparseMultilinearExtension::<Fr>::rand_with_config(5, 6, &mut rng);
+        let q = SparseMultilinearExtension::<Fr>::rand_with_config(4, 3, &mut rng);
+        let pq = p.tensor_product(&q);
+        assert_eq!(pq.evaluations.len(), 18);
+        assert_eq!(
+            pq.to_dense_multilinear_extension(),
+            p.to_dense_multilinear_extension()
+                .tensor_product(&q.to_dense_multilinear_extension())
+        );
+    }
+
     #[test]
     fn random_poly() {
         const NV: usize = 16;
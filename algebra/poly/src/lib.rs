@@ -1,6 +1,9 @@
 //! This crate implements functions for manipulating polynomials over finite
 //! fields, including FFTs.
 #![cfg_attr(not(feature = "std"), no_std)]
+// for `DomainCoeff::MUL_COST`, which the blanket impl sets and costlier coefficients override
+#![feature(specialization)]
+#![allow(incomplete_features)]
 #![warn(unused, future_incompatible, nonstandard_style, rust_2018_idioms)]
 #![forbid(unsafe_code)]
 #![allow(
@@ -21,7 +24,8 @@ pub mod evaluations;
 pub mod polynomial;
 
 pub use domain::{
-    EvaluationDomain, GeneralEvaluationDomain, MixedRadixEvaluationDomain, Radix2EvaluationDomain,
+    ArbitraryEvaluationDomain, Coset, EvaluationDomain, GeneralEvaluationDomain,
+    MixedRadixEvaluationDomain, Radix2EvaluationDomain, Twiddles,
 };
 pub use evaluations::multivariate::multilinear::{
     DenseMultilinearExtension, MultilinearExtension, SparseMultilinearExtension,
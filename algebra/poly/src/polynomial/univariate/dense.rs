@@ -61,6 +61,8 @@ impl<F: Field> Polynomial<F> for DensePolynomial<F> {
             return F::zero();
         } else if point.is_zero() {
             return self.coeffs[0];
+        } else if point.is_shared() {
+            return self.evaluate_by_powers(point);
         }
         self.internal_evaluate(point)
     }
@@ -80,6 +82,38 @@ impl<F: Field> DensePolynomial<F> {
             .rfold(F::zero(), move |result, coeff| result * point + coeff)
     }
 
+    /// Evaluation at a shared point. Horner's method multiplies by the point once per
+    /// coefficient, each a round of communication; this computes the powers of the point by
+    /// repeated doubling, a round each, and takes their inner product with the coefficients in one
+    /// more round.
+    fn evaluate_by_powers(&self, point: &F) -> F {
+        let n = self.coeffs.len();
+        // powers[i] = point^(i + 1), and top = point^powers.len()
+        let mut powers = vec![*point];
+        let mut top = *point;
+        while powers.len() + 1 < n {
+            let k = powers.len().min(n - 1 - powers.len());
+            let mut next = powers[..k].to_vec();
+            next.push(top);
+            F::batch_product_in_place(&mut next, &vec![top; k + 1]);
+            top = next.pop().unwrap();
+            powers.extend(next);
+        }
+        // public coefficients multiply locally, and the shared ones in one batch
+        let mut sum = self.coeffs[0];
+        let (mut shared, mut shared_powers) = (Vec::new(), Vec::new());
+        for (c, p) in self.coeffs[1..].iter().zip(powers) {
+            if c.is_shared() {
+                shared.push(*c);
+                shared_powers.push(p);
+            } else {
+                sum += p * c;
+            }
+        }
+        F::batch_product_in_place(&mut shared, &shared_powers);
+        shared.into_iter().fold(sum, |acc, t| acc + t)
+    }
+
     pub fn is_shared(&self) -> bool {
         let first_shared = self.coeffs.last().map(|c| c.is_shared()).unwrap_or(false);
         for c in &self.coeffs {
@@ -476,7 +510,12 @@ impl<'a, 'b, F: FftField> Mul<&'a DensePolynomial<F>> for &'b DensePolynomial<F>
             let mut self_evals = self.evaluate_over_domain_by_ref(domain);
             let other_evals = other.evaluate_over_domain_by_ref(domain);
             self_evals *= &other_evals;
-            self_evals.interpolate()
+            // shared leading zeros are not trimmed, but the degree of a product is known
+            let mut product = self_evals.interpolate();
+            product
+                .coeffs
+                .truncate(self.coeffs.len() + other.coeffs.len() - 1);
+            product
         }
     }
 }
@@ -488,7 +527,11 @@ impl<'a, 'b, F: FftField> Mul<&'a F> for &'b DensePolynomial<F> {
     #[inline]
     fn mul(self, other: &'a F) -> DensePolynomial<F> {
         let mut self_ = self.clone();
-        ark_std::cfg_iter_mut!(self_.coeffs).for_each(|c| *c *= other);
+        if other.is_shared() && self.coeffs.iter().all(|c| c.is_shared()) {
+            F::batch_product_in_place(&mut self_.coeffs, &vec![*other; self.coeffs.len()]);
+        } else {
+            ark_std::cfg_iter_mut!(self_.coeffs).for_each(|c| *c *= other);
+        }
         self_
     }
 }
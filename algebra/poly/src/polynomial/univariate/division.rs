@@ -0,0 +1,188 @@
+//! Division of polynomials whose coefficients are any `DomainCoeff`, such as
+//! group elements or secret shares, by public polynomials.
+//!
+//! Dividing by a public polynomial is linear in the dividend: each quotient
+//! coefficient is a public multiple of a remainder coefficient. So it needs
+//! no arithmetic on coefficients besides addition and scaling by the field,
+//! and never looks at their values. In particular, unlike
+//! `DenseOrSparsePolynomial::divide_with_q_and_r`, it does not trim zero
+//! coefficients, which would reveal something about a shared dividend: the
+//! quotient and remainder have lengths fixed by the lengths of the inputs.
+
+use crate::{
+    domain::DomainCoeff,
+    univariate::{DenseOrSparsePolynomial, SparsePolynomial},
+    EvaluationDomain,
+};
+use ark_ff::FftField;
+use ark_std::vec::Vec;
+
+/// Divide the polynomial with coefficients `num` by `den`, returning the
+/// quotient and remainder.
+///
+/// The quotient has `num.len() - deg(den)` coefficients (none, if `num` is
+/// shorter), and the remainder `min(num.len(), deg(den))`.
+///
+/// Panics if `den` is zero.
+pub fn divide_with_q_and_r<F: FftField, T: DomainCoeff<F>>(
+    num: &[T],
+    den: &DenseOrSparsePolynomial<'_, F>,
+) -> (Vec<T>, Vec<T>) {
+    let terms: Vec<(usize, F)> = den
+        .iter_with_index()
+        .into_iter()
+        .filter(|(_, c)| !c.is_zero())
+        .collect();
+    let (m, lead) = *terms.last().expect("Dividing by zero polynomial");
+    let lead_inv = lead.inverse().unwrap();
+    let mut rem = num.to_vec();
+    if num.len() <= m {
+        return (Vec::new(), rem);
+    }
+    let mut quotient = vec![T::zero(); num.len() - m];
+    for k in (0..quotient.len()).rev() {
+        let mut q = rem[k + m];
+        q *= lead_inv;
+        quotient[k] = q;
+        for (i, c) in &terms {
+            let mut t = q;
+            t *= *c;
+            rem[k + i] -= t;
+        }
+    }
+    rem.truncate(m);
+    (quotient, rem)
+}
+
+/// Divide the polynomial with coefficients `num` by the vanishing polynomial
+/// of `domain`, returning the quotient and remainder.
+///
+/// For a multiplicative subgroup, this takes time linear in `num.len()`.
+pub fn divide_by_vanishing_poly<F: FftField, T: DomainCoeff<F>, D: EvaluationDomain<F>>(
+    num: &[T],
+    domain: D,
+) -> (Vec<T>, Vec<T>) {
+    let z: SparsePolynomial<F> = domain.vanishing_polynomial();
+    divide_with_q_and_r(num, &z.into())
+}
+
+/// Evaluate the polynomial with coefficients `coeffs` at `point`.
+pub fn evaluate<F: FftField, T: DomainCoeff<F>>(coeffs: &[T], point: F) -> T {
+    coeffs.iter().rev().fold(T::zero(), |mut acc, c| {
+        acc *= point;
+        acc + *c
+    })
+}
+
+/// `num(point) - q(point) * den(point) - r(point)`: zero if `q` and `r` are
+/// the quotient and remainder of `num` by `den`, and, at a random point, with
+/// high probability only then.
+///
+/// With shared coefficients, this is a single shared value to open, to check
+/// a division that another party computed. Likewise, to check that a division
+/// is exact, open the remainder at a random point.
+pub fn division_residue<F: FftField, T: DomainCoeff<F>>(
+    num: &[T],
+    den: &DenseOrSparsePolynomial<'_, F>,
+    q: &[T],
+    r: &[T],
+    point: F,
+) -> T {
+    let den_at = den
+        .iter_with_index()
+        .into_iter()
+        .fold(F::zero(), |acc, (i, c)| acc + c * point.pow([i as u64]));
+    let mut q_den = evaluate(q, point);
+    q_den *= den_at;
+    evaluate(num, point) - q_den - evaluate(r, point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        univariate::DensePolynomial, ArbitraryEvaluationDomain, Polynomial, Radix2EvaluationDomain,
+        UVPolynomial,
+    };
+    use ark_ff::{UniformRand, Zero};
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Fr, G1Projective};
+
+    /// `p`, with each coefficient `c` as `c * G` for a fixed `G`: a stand-in
+    /// for coefficients whose values the divider cannot see.
+    fn in_group(p: &[Fr]) -> Vec<G1Projective> {
+        let g = G1Projective::rand(&mut test_rng());
+        p.iter()
+            .map(|c| {
+                let mut x = g;
+                x *= *c;
+                x
+            })
+            .collect()
+    }
+
+    #[test]
+    fn long_division() {
+        let rng = &mut test_rng();
+        for num_degree in 0..12 {
+            for den_degree in 0..6 {
+                let num = DensePolynomial::<Fr>::rand(num_degree, rng);
+                let den = DensePolynomial::<Fr>::rand(den_degree, rng);
+                let den = DenseOrSparsePolynomial::from(&den);
+                let (q, r) = divide_with_q_and_r(&num.coeffs, &den);
+                let (q_exp, r_exp) = DenseOrSparsePolynomial::from(&num)
+                    .divide_with_q_and_r(&den)
+                    .unwrap();
+                assert_eq!(DensePolynomial::from_coefficients_vec(q.clone()), q_exp);
+                assert_eq!(DensePolynomial::from_coefficients_vec(r.clone()), r_exp);
+                assert_eq!(q.len(), (num_degree + 1).saturating_sub(den_degree));
+                assert_eq!(r.len(), (num_degree + 1).min(den_degree));
+
+                let (gq, gr) = divide_with_q_and_r(&in_group(&num.coeffs), &den);
+                assert_eq!(gq, in_group(&q));
+                assert_eq!(gr, in_group(&r));
+
+                let z = Fr::rand(rng);
+                assert!(division_residue(&num.coeffs, &den, &q, &r, z).is_zero());
+                assert!(division_residue(&in_group(&num.coeffs), &den, &gq, &gr, z).is_zero());
+                if let Some(c) = r.first() {
+                    let mut bad = r.clone();
+                    bad[0] = *c + Fr::from(1u8);
+                    assert!(!division_residue(&num.coeffs, &den, &q, &bad, z).is_zero());
+                }
+            }
+        }
+    }
+
+    fn check_vanishing_poly_division<D: EvaluationDomain<Fr>>(domain: D) {
+        let rng = &mut test_rng();
+        let n = domain.size();
+        let z: DensePolynomial<Fr> = domain.vanishing_polynomial().into();
+        let quotient = DensePolynomial::<Fr>::rand(n + 3, rng);
+        let rem = DensePolynomial::<Fr>::rand(n - 1, rng);
+        let num = &(&quotient * &z) + &rem;
+
+        let (q, r) = divide_by_vanishing_poly(&num.coeffs, domain);
+        assert_eq!(q, quotient.coeffs);
+        assert_eq!(r, rem.coeffs);
+        let (gq, gr) = divide_by_vanishing_poly(&in_group(&num.coeffs), domain);
+        assert_eq!(gq, in_group(&q));
+        assert_eq!(gr, in_group(&r));
+
+        // an exact division leaves a remainder that vanishes everywhere
+        let (_, r) = divide_by_vanishing_poly(&(&quotient * &z).coeffs, domain);
+        assert!(evaluate(&r, Fr::rand(rng)).is_zero());
+        let x = Fr::rand(rng);
+        assert_eq!(evaluate(&num.coeffs, x), num.evaluate(&x));
+    }
+
+    #[test]
+    fn vanishing_poly_division() {
+        for &n in &[1, 4, 8, 16] {
+            check_vanishing_poly_division(Radix2EvaluationDomain::<Fr>::new(n).unwrap());
+        }
+        for &n in &[2, 5, 7] {
+            check_vanishing_poly_division(ArbitraryEvaluationDomain::<Fr>::new(n).unwrap());
+        }
+    }
+}
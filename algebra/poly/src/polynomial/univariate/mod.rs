@@ -6,6 +6,7 @@ use ark_std::{borrow::Cow, convert::TryInto, vec::Vec};
 use DenseOrSparsePolynomial::*;
 
 mod dense;
+pub mod division;
 mod sparse;
 
 pub use dense::DensePolynomial;
@@ -1,8 +1,9 @@
 #![allow(unused)]
 use ark_ec::{
+    hashing::{SWUParams, WBParams},
     AffineCurve, MontgomeryModelParameters, ProjectiveCurve, SWModelParameters, TEModelParameters,
 };
-use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use ark_ff::{BigInteger, Field, One, PrimeField, SquareRootField, UniformRand, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SWFlags, SerializationError};
 use ark_std::{io::Cursor, vec::Vec};
 
@@ -287,6 +288,70 @@ pub fn curve_tests<G: ProjectiveCurve>() {
 pub fn sw_tests<P: SWModelParameters>() {
     sw_curve_serialization_test::<P>();
     sw_from_random_bytes::<P>();
+    sw_glv_test::<P>();
+}
+
+/// Multiplications and MSMs through the GLV endomorphism, if the curve has one, agree with plain
+/// double-and-add.
+pub fn sw_glv_test<P: SWModelParameters>() {
+    use ark_ec::models::short_weierstrass_jacobian::{GroupAffine, GroupProjective};
+
+    let glv = match P::glv() {
+        Some(glv) => glv,
+        None => return,
+    };
+    let mut rng = ark_std::test_rng();
+    let g = GroupProjective::<P>::rand(&mut rng).into_affine();
+    assert_eq!(
+        glv.endomorphism(&g).into_projective(),
+        g.into_projective().mul(glv.lambda.into_repr())
+    );
+
+    let mut scalars: Vec<P::ScalarField> =
+        (0..ITERATIONS).map(|_| P::ScalarField::rand(&mut rng)).collect();
+    scalars.extend(vec![
+        P::ScalarField::zero(),
+        P::ScalarField::one(),
+        -P::ScalarField::one(),
+        glv.lambda,
+    ]);
+    let bits = <P::ScalarField as PrimeField>::size_in_bits();
+    let mut bases = Vec::new();
+    let mut expected = GroupProjective::<P>::zero();
+    for k in &scalars {
+        let (k1, k2) = glv.decompose(k);
+        assert_eq!(k1 + k2 * glv.lambda, *k);
+        for half in &[k1, k2] {
+            let short = half.into_repr().num_bits().min((-*half).into_repr().num_bits());
+            assert!(short as usize <= bits / 2 + 2);
+        }
+        let base = GroupProjective::<P>::rand(&mut rng).into_affine();
+        let product = base.into_projective().mul(k.into_repr());
+        assert_eq!(base.mul(*k), product);
+        let mut projective = base.into_projective();
+        projective *= *k;
+        assert_eq!(projective, product);
+        bases.push(base);
+        expected += &product;
+    }
+    assert_eq!(GroupAffine::multi_scalar_mul(&bases, &scalars), expected);
+    assert_eq!(
+        GroupAffine::<P>::multi_scalar_mul(&[], &[]),
+        GroupProjective::<P>::zero()
+    );
+
+    // off the prime-order subgroup, φ is not multiplication by λ, so multiplications don't use it
+    let outside = (0..ITERATIONS)
+        .filter_map(|_| GroupAffine::<P>::get_point_from_x(P::BaseField::rand(&mut rng), false))
+        .find(|p| !p.is_in_correct_subgroup_assuming_on_curve());
+    if let Some(p) = outside {
+        let product = p.into_projective().mul(glv.lambda.into_repr());
+        assert_ne!(glv.endomorphism(&p).into_projective(), product);
+        assert_eq!(p.mul(glv.lambda), product);
+        let mut projective = p.into_projective();
+        projective *= glv.lambda;
+        assert_eq!(projective, product);
+    }
 }
 
 pub fn sw_from_random_bytes<P: SWModelParameters>() {
@@ -523,3 +588,56 @@ pub fn edwards_curve_serialization_test<P: TEModelParameters>() {
         }
     }
 }
+
+/// Hashing to the curve gives points of the prime-order subgroup, through a map
+/// to the isogenous curve and an isogeny that is a homomorphism onto the curve.
+pub fn sw_hash_to_curve_test<P: WBParams>() {
+    use ark_ec::{
+        hashing::{derive_bases, swu, HashToCurve, MapToCurveBasedHasher},
+        models::short_weierstrass_jacobian::GroupAffine,
+    };
+
+    let mut rng = ark_std::test_rng();
+    let generator = GroupAffine::<P::IsogenousCurve>::prime_subgroup_generator();
+    assert!(generator.is_on_curve());
+    assert!(generator.is_in_correct_subgroup_assuming_on_curve());
+    assert!(P::IsogenousCurve::ZETA.sqrt().is_none());
+
+    let mut us: Vec<P::BaseField> = (0..ITERATIONS)
+        .map(|_| P::BaseField::rand(&mut rng))
+        .collect();
+    us.push(P::BaseField::zero());
+    for pair in us.windows(2) {
+        let (a, b) = (
+            swu::map_to_curve::<P::IsogenousCurve>(pair[0]),
+            swu::map_to_curve::<P::IsogenousCurve>(pair[1]),
+        );
+        assert!(a.is_on_curve() && b.is_on_curve());
+        assert!(P::isogeny_map(&a).is_on_curve());
+        assert_eq!(
+            P::isogeny_map(&(a + b)),
+            P::isogeny_map(&a) + P::isogeny_map(&b)
+        );
+        assert!(
+            P::isogeny_map(&generator.mul(P::ScalarField::rand(&mut rng)).into_affine())
+                .is_in_correct_subgroup_assuming_on_curve()
+        );
+    }
+
+    let hasher = MapToCurveBasedHasher::<P>::new(b"ark-algebra-test-templates").unwrap();
+    let mut points: Vec<GroupAffine<P>> = Vec::new();
+    for i in 0..ITERATIONS as u64 {
+        let p = hasher.hash(&i.to_be_bytes()).unwrap();
+        assert!(!p.is_zero() && p.is_on_curve());
+        assert!(p.is_in_correct_subgroup_assuming_on_curve());
+        assert_eq!(hasher.hash(&i.to_be_bytes()).unwrap(), p);
+        assert!(!points.contains(&p));
+        points.push(p);
+    }
+    assert_eq!(
+        derive_bases::<P>(b"ark-algebra-test-templates", ITERATIONS).unwrap(),
+        points
+    );
+    let other = MapToCurveBasedHasher::<P>::new(b"ark-algebra-test-templates 2").unwrap();
+    assert_ne!(other.hash(&[]).unwrap(), hasher.hash(&[]).unwrap());
+}
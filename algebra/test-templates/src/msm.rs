@@ -31,3 +31,41 @@ pub fn test_var_base_msm<G: AffineCurve>() {
 
     assert_eq!(naive.into_affine(), fast.into_affine());
 }
+
+/// The batch-affine MSM agrees with the naive one, including when buckets
+/// hold equal points, opposite points, or zeros.
+pub fn test_batch_affine_msm<P: ark_ec::SWModelParameters>() {
+    use ark_ec::models::short_weierstrass_jacobian::{GroupAffine, GroupProjective};
+
+    const SAMPLES: usize = 1 << 10;
+
+    let mut rng = ark_std::test_rng();
+    let bits = <P::ScalarField as PrimeField>::size_in_bits();
+    let check = |g: &[GroupAffine<P>], v: &[<P::ScalarField as PrimeField>::BigInt]| {
+        let naive = naive_var_base_msm(g, v);
+        let fast = VariableBaseMSM::multi_scalar_mul_batch_affine(g, v, bits);
+        assert_eq!(naive.into_affine(), fast.into_affine());
+    };
+
+    let v = (0..SAMPLES)
+        .map(|_| P::ScalarField::rand(&mut rng).into_repr())
+        .collect::<Vec<_>>();
+    let g = (0..SAMPLES)
+        .map(|_| GroupProjective::<P>::rand(&mut rng))
+        .collect::<Vec<_>>();
+    let mut g = ProjectiveCurve::batch_normalization_into_affine(&g);
+    check(&g, &v);
+
+    // Every bucket holds copies of one point, and has to double them.
+    let same = vec![g[0]; SAMPLES];
+    check(&same, &v);
+
+    // Every bucket holds pairs of opposite points, which cancel, and zeros.
+    let paired_scalars = v.iter().flat_map(|s| vec![*s, *s]).collect::<Vec<_>>();
+    let opposites = g.iter().flat_map(|b| vec![*b, -*b]).collect::<Vec<_>>();
+    check(&opposites, &paired_scalars);
+    for b in g.iter_mut().step_by(3) {
+        *b = GroupAffine::zero();
+    }
+    check(&g, &v);
+}
@@ -1,3 +1,4 @@
+use ark_ec::glv::GLVParameters;
 use ark_ec::models::{ModelParameters, SWModelParameters};
 use ark_ff::{field_new, Zero};
 
@@ -38,8 +39,39 @@ impl SWModelParameters for Parameters {
     fn mul_by_a(_: &Self::BaseField) -> Self::BaseField {
         Self::BaseField::zero()
     }
+
+    /// The lattice basis is (1, 1 - x^2), (x^2, 1), for the curve parameter x.
+    #[inline(always)]
+    fn glv() -> Option<GLVParameters<Self>> {
+        Some(GLVParameters {
+            endo_coeff: ENDO_COEFF,
+            lambda: LAMBDA,
+            scalar_decomp_coeffs: [
+                (false, 1),
+                (true, 91893752504881257701523279626832445440),
+                (false, 91893752504881257701523279626832445441),
+                (false, 1),
+            ],
+            scalar_decomp_rounding: [
+                (false, 3),
+                (false, 315016217318923541826197810041088902664),
+            ],
+            shift: 254,
+        })
+    }
 }
 
+/// ENDO_COEFF = a cube root of unity in Fq, with (x, y) -> (ENDO_COEFF * x, y) multiplying G1
+/// by LAMBDA
+/// = 258664426012969093929703085429980814127835149614277183275038967946009968870203535512256352201271898244626862047231
+#[rustfmt::skip]
+pub const ENDO_COEFF: Fq = field_new!(Fq, "258664426012969093929703085429980814127835149614277183275038967946009968870203535512256352201271898244626862047231");
+
+/// LAMBDA = a cube root of unity in Fr
+/// = 8444461749428370424248824938781546531284005582649182570233710176290576793600
+#[rustfmt::skip]
+pub const LAMBDA: Fr = field_new!(Fr, "8444461749428370424248824938781546531284005582649182570233710176290576793600");
+
 /// G1_GENERATOR_X =
 /// 81937999373150964239938255573465948239988671502647976594219695644855304257327692006745978603320413799295628339695
 #[rustfmt::skip]
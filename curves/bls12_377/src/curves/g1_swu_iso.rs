@@ -0,0 +1,84 @@
+//! Hashing to G1 in the manner of RFC 9380's `SSWU_RO_` suites, which define none for BLS12-377:
+//! the curve `E': y^2 = x^3 - 15x + 22` that the SWU map maps to, and a 2-isogeny from it to G1.
+//!
+//! `E'` is the codomain of the 2-isogeny from G1 with kernel `(-1, 0)`. Its dual, with kernel
+//! `(2, 0)`, lands on `y^2 = x^3 + 64`, which `(x, y) -> (x / 4, y / 8)` takes to G1. Z is the
+//! RFC's choice (appendix H.2).
+use crate::{Fq, Fr};
+use ark_ec::{
+    hashing::{SWUParams, WBParams},
+    models::{ModelParameters, SWModelParameters},
+};
+use ark_ff::field_new;
+
+use super::g1;
+
+/// The curve that [g1::Parameters] is 2-isogenous to, for the SWU map.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SwuIsoParameters;
+
+impl ModelParameters for SwuIsoParameters {
+    type BaseField = Fq;
+    type ScalarField = Fr;
+}
+
+impl SWModelParameters for SwuIsoParameters {
+    /// COEFF_A = -15
+    #[rustfmt::skip]
+    const COEFF_A: Fq = field_new!(Fq, "258664426012969094010652733694893533536393512754914660539884262666720468348340822774968888139573360124440321458162");
+
+    /// COEFF_B = 22
+    #[rustfmt::skip]
+    const COEFF_B: Fq = field_new!(Fq, "22");
+
+    /// COFACTOR, as for G1: isogenous curves have the same number of points
+    const COFACTOR: &'static [u64] = g1::Parameters::COFACTOR;
+
+    const COFACTOR_INV: Fr = g1::Parameters::COFACTOR_INV;
+
+    /// AFFINE_GENERATOR_COEFFS = COFACTOR times the point with the least x (as an integer),
+    /// and the even y
+    #[rustfmt::skip]
+    const AFFINE_GENERATOR_COEFFS: (Self::BaseField, Self::BaseField) = (
+        field_new!(Fq, "27754875533754239215852627186866881204011399617158111761889123591518884846601849427504372556748089673084404734421"),
+        field_new!(Fq, "57742739416208658582257193693937735979996224474024768207425225076216416597450391813246776935813583410945016414642"),
+    );
+}
+
+impl SWUParams for SwuIsoParameters {
+    /// ZETA = -11
+    #[rustfmt::skip]
+    const ZETA: Fq = field_new!(Fq, "258664426012969094010652733694893533536393512754914660539884262666720468348340822774968888139573360124440321458166");
+}
+
+/// `(x, y) -> ((x^2 - 2x - 3) / 4(x - 2), y (x^2 - 4x + 7) / 8(x - 2)^2)`.
+impl WBParams for g1::Parameters {
+    type IsogenousCurve = SwuIsoParameters;
+
+    #[rustfmt::skip]
+    const PHI_X_NOM: &'static [Fq] = &[
+        field_new!(Fq, "193998319509726820507989550271170150152295134566185995404913197000040351261255617081226666104680020093330241093632"),
+        field_new!(Fq, "129332213006484547005326366847446766768196756377457330269942131333360234174170411387484444069786680062220160729088"),
+        field_new!(Fq, "193998319509726820507989550271170150152295134566185995404913197000040351261255617081226666104680020093330241093633"),
+    ];
+
+    #[rustfmt::skip]
+    const PHI_X_DEN: &'static [Fq] = &[
+        field_new!(Fq, "258664426012969094010652733694893533536393512754914660539884262666720468348340822774968888139573360124440321458175"),
+        field_new!(Fq, "1"),
+    ];
+
+    #[rustfmt::skip]
+    const PHI_Y_NOM: &'static [Fq] = &[
+        field_new!(Fq, "32333053251621136751331591711861691692049189094364332567485532833340058543542602846871111017446670015555040182273"),
+        field_new!(Fq, "129332213006484547005326366847446766768196756377457330269942131333360234174170411387484444069786680062220160729088"),
+        field_new!(Fq, "226331372761347957259321141983031841844344323660550327972398729833380409804798219928097777122126690108885281275905"),
+    ];
+
+    #[rustfmt::skip]
+    const PHI_Y_DEN: &'static [Fq] = &[
+        field_new!(Fq, "4"),
+        field_new!(Fq, "258664426012969094010652733694893533536393512754914660539884262666720468348340822774968888139573360124440321458173"),
+        field_new!(Fq, "1"),
+    ];
+}
@@ -0,0 +1,188 @@
+//! Hashing to G2 in the manner of RFC 9380's `SSWU_RO_` suites, which define none for BLS12-377:
+//! the curve `E'` that the SWU map maps to, and a 23-isogeny from it to G2.
+//!
+//! G2's rational isogenies of prime degree under 23 all land on curves with `a = 0`. All 24 of
+//! degree 23 are rational, and `E'` is the codomain of one of them; its dual, composed with an
+//! isomorphism onto G2, takes `E'` back. Z is the RFC's choice (appendix H.2).
+use crate::{Fq, Fq2, Fr};
+use ark_ec::{
+    hashing::{SWUParams, WBParams},
+    models::{ModelParameters, SWModelParameters},
+};
+use ark_ff::field_new;
+
+use super::g2;
+
+/// The curve that [g2::Parameters] is 23-isogenous to, for the SWU map.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SwuIsoParameters;
+
+impl ModelParameters for SwuIsoParameters {
+    type BaseField = Fq2;
+    type ScalarField = Fr;
+}
+
+impl SWModelParameters for SwuIsoParameters {
+    /// COEFF_A = [155360543295841472923246824115803980857886035464624285170666977212960949845432365313482347006119658050172764477741, 209437903649494840229325721138772470391537581970494356535707397055878858664249303247624490440198315997390822862352]
+    #[rustfmt::skip]
+    const COEFF_A: Fq2 = field_new!(Fq2, field_new!(Fq, "155360543295841472923246824115803980857886035464624285170666977212960949845432365313482347006119658050172764477741"), field_new!(Fq, "209437903649494840229325721138772470391537581970494356535707397055878858664249303247624490440198315997390822862352"));
+
+    /// COEFF_B = [240820903982536718097684362909692926119342050659564933755990617504470329880018753877294753834755294405835007267210, 178556220617533172658793888105025913774154597692789555945405824358240117767129520715176641501973248794025606470332]
+    #[rustfmt::skip]
+    const COEFF_B: Fq2 = field_new!(Fq2, field_new!(Fq, "240820903982536718097684362909692926119342050659564933755990617504470329880018753877294753834755294405835007267210"), field_new!(Fq, "178556220617533172658793888105025913774154597692789555945405824358240117767129520715176641501973248794025606470332"));
+
+    /// COFACTOR, as for G2: isogenous curves have the same number of points
+    const COFACTOR: &'static [u64] = g2::Parameters::COFACTOR;
+
+    const COFACTOR_INV: Fr = g2::Parameters::COFACTOR_INV;
+
+    /// AFFINE_GENERATOR_COEFFS = COFACTOR times the point with the least x (as an integer),
+    /// and the even y
+    #[rustfmt::skip]
+    const AFFINE_GENERATOR_COEFFS: (Self::BaseField, Self::BaseField) = (
+        field_new!(Fq2, field_new!(Fq, "172240753518519246589993655167230813160887465033909251697685605641896213421535567445975503783897890681611116050256"), field_new!(Fq, "180723747462107919801647507524918238929074738071904768008902707175083929334478932743890033480142973784468106700734")),
+        field_new!(Fq2, field_new!(Fq, "159877028514381271259553855617051722789445680278536140408671896267048922386113772553122982885781466600839327261529"), field_new!(Fq, "55959485646876460679820664491083834209993067022766043076209731704825453822794428122600398831259783384465237255647")),
+    );
+}
+
+impl SWUParams for SwuIsoParameters {
+    /// ZETA = [0, 1]
+    #[rustfmt::skip]
+    const ZETA: Fq2 = field_new!(Fq2, field_new!(Fq, "0"), field_new!(Fq, "1"));
+}
+
+/// The dual of the 23-isogeny `G2 -> E'`, composed with an isomorphism onto G2.
+impl WBParams for g2::Parameters {
+    type IsogenousCurve = SwuIsoParameters;
+
+    #[rustfmt::skip]
+    const PHI_X_NOM: &'static [Fq2] = &[
+        field_new!(Fq2, field_new!(Fq, "76998983487740952032193865069675600319151381889822616447593125315995885462180536127101261402791462176089074113081"), field_new!(Fq, "75323755583085767572787772992962199545207080119970216848105571004698706536055308893573026021260734120547680864992")),
+        field_new!(Fq2, field_new!(Fq, "38935150531745169665491163998928135913448038228001749452824185275104079869032705884172970389631923040322153140102"), field_new!(Fq, "243151551101786373740875803049477515611225410930840906071558102221556666752714303652814796188740556828004362485079")),
+        field_new!(Fq2, field_new!(Fq, "52527019309537906482007608458338836676586928931019188693317485779369239310965716338924620956556006023475400315784"), field_new!(Fq, "11236659069882336638891525968201044878554862692993746661745836923997439819507204928092734858790396161598972999687")),
+        field_new!(Fq2, field_new!(Fq, "188267925244100607711499974198364213958252466999900055569603308600784499118817728157628047934881074223390543503573"), field_new!(Fq, "89519078436549615190380522038190798614812124625751298364321531494814154652577138928944794889461167302435787016867")),
+        field_new!(Fq2, field_new!(Fq, "25733370859382708684284554465070460172659168585036747841301992436018216369803411082094556895766278731929448169275"), field_new!(Fq, "190949406724753433579074773213220719039774365109805685718523565485400104738446770637346780142660788527540673103621")),
+        field_new!(Fq2, field_new!(Fq, "250972210241487466490067086668523725684906111708579044381229690782079863798335400195413094802586790872168091978789"), field_new!(Fq, "164058842854667019610098559246207401513418104233223145136901012062058498021657876913013806779003884376085633956278")),
+        field_new!(Fq2, field_new!(Fq, "129056665196842276411133273347077858150752493655445809624585751079142689288975561438736671964334773734931080599325"), field_new!(Fq, "44886656669410328974167739602431272034890501480374892635913322172371847454463069577442120616240570874046174388379")),
+        field_new!(Fq2, field_new!(Fq, "161443209783731994952307235434416401492518847449270637888191290245114780344372671957127208908720108823343700228458"), field_new!(Fq, "54990833508262306951011356498853476000067437929221491813852497819776242490580522705864574740187848679895919716947")),
+        field_new!(Fq2, field_new!(Fq, "229106006480917752251110970511972328844249386042101513846758706560296623692849183709255574202164419666798444729443"), field_new!(Fq, "248381859724565198986592099593377614619505751347570679268753268699155077138791624380565123633315949670064304813088")),
+        field_new!(Fq2, field_new!(Fq, "91744942130095520721369997250949921067962555923170122431317074124493719739058600673077091457172179911611952125172"), field_new!(Fq, "38477662761549129148370653454057559124472320313292517001609160462465715477656023325147053236164768454933719740406")),
+        field_new!(Fq2, field_new!(Fq, "94419312630113122713676818534686279875622673330378199315180064338865720422239713885114117712533384003306910174729"), field_new!(Fq, "62032846199837229651849142255929886111608566046121286821754307976657628959793248829470015193025977749224198285960")),
+        field_new!(Fq2, field_new!(Fq, "47797360396145692250069574193561659790443215558394932491017329560691598801570862301131234983981084721158136184762"), field_new!(Fq, "76130552139724718474822197744198069657529633620693941032592905674555045966954625305921095954082526180292621276560")),
+        field_new!(Fq2, field_new!(Fq, "66589039969853006701618750813645131268744889141712392839872564314832904521172320312196382599349266955560200192102"), field_new!(Fq, "146982896331767125664910407372426499479870993911383447309770405597177415162786582353191604502883551769029873254271")),
+        field_new!(Fq2, field_new!(Fq, "208910156465280420432836601641643229344653767453204831327015913256159598715061332385390489224279904862754521881646"), field_new!(Fq, "137721113868655011916038711252651180820936686401317873437304266623106265926058001242400546531917001165683486720915")),
+        field_new!(Fq2, field_new!(Fq, "179678290982277005777672194080658921584571484490692317046277823120209492908855593391757225218225761306771232227826"), field_new!(Fq, "92183184768209020363196051908147415146871174546684293416255229422851843827811554502319770842940936396506910787149")),
+        field_new!(Fq2, field_new!(Fq, "99165324265030375307982297452904264194914060266174024632257859899715391502513160256285144694210404578542741975549"), field_new!(Fq, "244721024944038410324630792122051254261921395215453460332284414864647628472309886243584318394778105088755919033611")),
+        field_new!(Fq2, field_new!(Fq, "141156302926087539636174913670764946694347414022894805298349942388571213429794758345924353976691656600720521326392"), field_new!(Fq, "94112784082722206514904131808433794216084449032768495931832715024068790772846435538101119399203980806173611421392")),
+        field_new!(Fq2, field_new!(Fq, "148616172676668008033558031479969804130603883695020733821067051250665125055146734194251003413742870025915833362313"), field_new!(Fq, "90350730553916567249303296427459811703754433495215013573201874554921104779578605806131274271356908835656879019139")),
+        field_new!(Fq2, field_new!(Fq, "999613249869650573962558759026358745229492366136268927961045558212931517414736959096738960173644679956124543113"), field_new!(Fq, "80431295921581828904219273625991282491985802892594342616119296642710442919308029348825636445325726396242604018364")),
+        field_new!(Fq2, field_new!(Fq, "27473782784262000429994585495493160562024577644073749544365733942172897522106941323793110684313768209367822693778"), field_new!(Fq, "15283369523124048874286763647383220622015377562045380128409586357439156309729794217874112010970498985531598716886")),
+        field_new!(Fq2, field_new!(Fq, "99075157228009293467383784914105114512439826595332350005082587827948071152668410405332637756244081977715108297788"), field_new!(Fq, "257238575076212980530030980655111261235690227674853199743988856016684299498888864448716473975801260505718562715900")),
+        field_new!(Fq2, field_new!(Fq, "12474862484774310427316332178410327932938262840319117444790651083391304605074992105914438075089169080229475984198"), field_new!(Fq, "42612364017572180428683386710934867305246886102917231255345617503176316952807239737492424357086861802669235944561")),
+        field_new!(Fq2, field_new!(Fq, "77033277424575983194423003956265825447850850561652974127182467505847538756946352777913529389114354633102222402640"), field_new!(Fq, "206187460857630375849650727606335777079293435956799469482012602226359527708320366865846663184232995420458836687784")),
+        field_new!(Fq2, field_new!(Fq, "257686488674545770403807165703608491821700153538449954828958424244540050698630649531963334687249831352703307010320"), field_new!(Fq, "0")),
+    ];
+
+    #[rustfmt::skip]
+    const PHI_X_DEN: &'static [Fq2] = &[
+        field_new!(Fq2, field_new!(Fq, "157047419571507177342639196842559063351290282684324394888866534290107337477302023582578951493341381211268432162150"), field_new!(Fq, "56521361962534792510489973199364712748593690134659372917384302186632940668561844444154360962815099096992940254367")),
+        field_new!(Fq2, field_new!(Fq, "114019423025008965307786748279913506533575191516823328999234293916957197032230540397348180778675832781605313608669"), field_new!(Fq, "14159028279172824402484981968749599618053917962584781830358858516303417122045835375500647356838116473815305870771")),
+        field_new!(Fq2, field_new!(Fq, "76341453904881414076259157602688819250563302805009206536404082432243133259764498351127172405360904684358917792186"), field_new!(Fq, "60028332596712403589058972645165966561949271010065143976052165945298946038203602219884976441694482913008185438161")),
+        field_new!(Fq2, field_new!(Fq, "84843376555979890450110303652192628576262374903655811173566833999861140448870552414422635048027059609682900297240"), field_new!(Fq, "111177969172297081838462842321904728934722415379475319297115673514279833952161958274902063499264381325800131454512")),
+        field_new!(Fq2, field_new!(Fq, "195115282444698285891644882300758510382215221322119066238243822573996361495042924376231017646183402281054469631531"), field_new!(Fq, "217406783518256607497276015112336802033859058660876575048543822855577256788205296662544967916477406593295736928505")),
+        field_new!(Fq2, field_new!(Fq, "52519539882381015979804360599386543673123147063090962125802022196271083470332720673060289401214241463122111432429"), field_new!(Fq, "218278930916566321473954769287388852372098526089726824011442984437320687495789736135442154680424133943468511074155")),
+        field_new!(Fq2, field_new!(Fq, "98642216683318715002884110193365254857647481945068822812149188085964383280004156584140231136986332610827335857099"), field_new!(Fq, "36950690316822091847066349888743474510343561851987827488702827052945075652432626630796673700438808336818569578233")),
+        field_new!(Fq2, field_new!(Fq, "210657428306573966766533405028421553584557544120482558018856250035063975216352431303919419899569600631603173336145"), field_new!(Fq, "192933248327889220877252731680705230086983862710243320031027147974312511142643023613060028305100469056341741741017")),
+        field_new!(Fq2, field_new!(Fq, "8330485216292996952558741328056841570729019324226713493519489352227750769061532811235149404182905386937248002938"), field_new!(Fq, "116677726632048016114322053181707998732931558946798504922571276341904978221062384812627532161416766277677476513562")),
+        field_new!(Fq2, field_new!(Fq, "238299652586051227184333753774743290289862090438777873378043744075704593016217750997083556938080706643417165048777"), field_new!(Fq, "49497867138991940523542690934402182487590576952093329609067312477371245736973409061967705930086634145958898459014")),
+        field_new!(Fq2, field_new!(Fq, "67028155906534651866359882386242582209363003213076941498259373239558860902288621881222668852378573161814008335742"), field_new!(Fq, "38278896096143280486971251233522888696324608672210096032451649956162106627427923765422107924107029172384630414459")),
+        field_new!(Fq2, field_new!(Fq, "249672967966819299879983232930816509400169664014273232842778789806216423660635363003462224407206972548214873650200"), field_new!(Fq, "26468168935815972408812641559653018768780986300952772783706919854269723878644153845053210235053004594924833915591")),
+        field_new!(Fq2, field_new!(Fq, "82772923854416243874378082374194228675187790142001998309894000000531933407139484357330502166887530830141433397461"), field_new!(Fq, "16636877879461386158007907453037382610601810638430527686215035304960057024494484533789208758996845621357480942670")),
+        field_new!(Fq2, field_new!(Fq, "52415440978743488669325799253260190625412515839732286301180876471974172229790627030436912931752266636057679238390"), field_new!(Fq, "107474955669831827260383634437389761684099134605459454908511865233243900987439334297993342288871675347589206822802")),
+        field_new!(Fq2, field_new!(Fq, "184574809581708775005641421101376428272376079853694297892366193369113357157178320203426442000052807263321354161962"), field_new!(Fq, "214724976766715698244949566982971313698138293238141640630214811216364915368548982362608674218387800722337789035196")),
+        field_new!(Fq2, field_new!(Fq, "45042255276922129679464720530833985596744993783082802079625531313103748689052330722128204279797720321957479045808"), field_new!(Fq, "175024556200815926155515427323373420300287016138260236505467040722779231419021921268780554119825941063268510372143")),
+        field_new!(Fq2, field_new!(Fq, "113896091637301001030011411065068710941917667441750930444556127282556756728860128847247944602361340352816471785158"), field_new!(Fq, "133919181265754093248402910029520333023901439153127550882694911375283427118581442951411482345159773094889141596794")),
+        field_new!(Fq2, field_new!(Fq, "229758360848043707847800250836965117401317867426147131385286929799725666356086949748490370857591332529075275141485"), field_new!(Fq, "98000972667982641220530438417262836347398782846484139621298916032630142749738122076048944170230570858971224488246")),
+        field_new!(Fq2, field_new!(Fq, "222820770157490982790559301892582061660984478896123393150379278214752382395655491775372224948109089915289610461541"), field_new!(Fq, "162915553953587306622153238338666445103433594561230262119570384864070376231771138903559422768873713653022585269068")),
+        field_new!(Fq2, field_new!(Fq, "196244130501094886942242977346236779819592152676311758161068181288276851247371535781344182753320014004293823352440"), field_new!(Fq, "176080805771753480410175791728764756086380883968564045814219048453499784533634211986968446067783791867727394131320")),
+        field_new!(Fq2, field_new!(Fq, "256718151069996027605763655400500462064762124215970554086945018603428518416068275256800401715356524795014399165675"), field_new!(Fq, "47980806640067118602989141140031599438338324922523927908736180297759243666196543616668096295942571611127747530535")),
+        field_new!(Fq2, field_new!(Fq, "140288873564547350177289902766336896699318444592821608517696071918234471735111443846141608928476061373945182062771"), field_new!(Fq, "175443442226480245980434018201448456124558751327847268693391995054872983049987683770982917698869965033349273945219")),
+        field_new!(Fq2, field_new!(Fq, "1"), field_new!(Fq, "0")),
+    ];
+
+    #[rustfmt::skip]
+    const PHI_Y_NOM: &'static [Fq2] = &[
+        field_new!(Fq2, field_new!(Fq, "257440297484461421942078176629806070300332629191971172150687776976697877230889018127164791209327607542030524787789"), field_new!(Fq, "243394472881174722806448472511893106017358375585053184600204040229152913261509877602420984488725637860855087985025")),
+        field_new!(Fq2, field_new!(Fq, "237182454781792824090783814818444991834153812270171952809052300370241058989450887219059488586758802633040256338935"), field_new!(Fq, "208786916162705677187638071566645792686332803730454799840626185393722831687103234520058763127575000451414833655068")),
+        field_new!(Fq2, field_new!(Fq, "221637532384254673270196247349132061295384962453072218934110147913355852593594194898633787708497283695034424034384"), field_new!(Fq, "137986954560652667417937717454847575043587218362757597973446208044372901297947134879742812822927951412063044110616")),
+        field_new!(Fq2, field_new!(Fq, "223415477868562961228434544837014769892210568287203313930314868765967332364815028807992284713699669058577331327892"), field_new!(Fq, "137070001519285277438619801703057644410504709981699358299982902901985116028180337839687250268817167290673644051247")),
+        field_new!(Fq2, field_new!(Fq, "32364873116077997685639994636081909776576328595551544578587114251241255740971873383772536015184067039036639873108"), field_new!(Fq, "220814891612899255619037393130856905217070891218579986662705971922541366785941612642307102323293039851550900114375")),
+        field_new!(Fq2, field_new!(Fq, "682236346199181704340741741358202846322967170211789053577905448750024656270842280559398003090890408784918562441"), field_new!(Fq, "23074313720456921291731617567359600927409006559286696140843097620980017455752913234162121609156499658976823482621")),
+        field_new!(Fq2, field_new!(Fq, "64190935894083913238722585846929658072116209784967677639743965116690140270688702111221879468567041893053580748264"), field_new!(Fq, "70360676047189157400140611972495633091094546442441714412234778367033387808541790705816028522985854942670492882714")),
+        field_new!(Fq2, field_new!(Fq, "198797631661449932840090583328267769093681689403211022905358497751585677534055587778301918125599448604770183849050"), field_new!(Fq, "80933055802607038943187296085616554442359273987234594110649529855680850910039503038899701072721656644515448165923")),
+        field_new!(Fq2, field_new!(Fq, "250219185915021997600860412436055712316290616101866821776163995606227086276110676154535138407101097152371098934603"), field_new!(Fq, "237174476981064400759184999599470898791458645123300619757134324899245778836548837316245751456037275493959756248573")),
+        field_new!(Fq2, field_new!(Fq, "139905261423919239227446372203418497915060508483793937762329993063849263906100003007441380966710050807933844489711"), field_new!(Fq, "93260904688291055821155590652617643597866575270684305601585360314427382022286021399474268883696600799957975325642")),
+        field_new!(Fq2, field_new!(Fq, "103922575507023897553158963406865624704787814363864834986350192408352853395057988337659361768941879483469586234852"), field_new!(Fq, "179267848423633885588448561692617769617825277207424261516017048821853203607867127334466514927212428926940579781051")),
+        field_new!(Fq2, field_new!(Fq, "103588432239144161564735692847231251208441828744231415381298978226206742373382220332691437436924568076417723368611"), field_new!(Fq, "200763071055814205057262670263717069377629393691932738159622243092596625614314156691355515576859132077262469684650")),
+        field_new!(Fq2, field_new!(Fq, "19940933808588623826431864258839840055901609035933058047178220943507491864051897358386829526198131664734312103690"), field_new!(Fq, "57682165946805062070332538506181898949378058008883740005781988235421710654882985058306319329926082614056893638956")),
+        field_new!(Fq2, field_new!(Fq, "154817261600507556775727719140559620217138187870474418714312486627679146855828518756321574035052905024937978033921"), field_new!(Fq, "29262846666465472827013663269798096107276636478707004675563773717601430131369278314493885295261830682207278561708")),
+        field_new!(Fq2, field_new!(Fq, "173743999136831649881857282098258546582493670331463835380809410405839671874105973144401102464929024326036714918320"), field_new!(Fq, "2983528477887562092652130015294390023567880047698683758528142261542478718020273052920211808940880240873311372434")),
+        field_new!(Fq2, field_new!(Fq, "128029333184484600396342122334888905307811128278432790704043148038026740763634843484042845879208160688050484543801"), field_new!(Fq, "155365484823109028788694622114813357565145221605626640362245667756796411260718759855478860889928752128157068891301")),
+        field_new!(Fq2, field_new!(Fq, "231101375695801044880848713899250032167981433011155026533813946304008141325305170553877795974877189620163921384307"), field_new!(Fq, "240054161457286484592554473584919483412547229383522539352443491170294202001094881720549287414671740833697253353417")),
+        field_new!(Fq2, field_new!(Fq, "141846396913795628081838769922194705968596393490075574303960342039330316591808277596608261360960148156601895696823"), field_new!(Fq, "61120420289025003207784136693522201723883401819496524751782901915425727708764182485064211953421816406388711627867")),
+        field_new!(Fq2, field_new!(Fq, "124602309103328524289084948804186599798052604111479797997834470519569578374435636060174824470518263905697758388884"), field_new!(Fq, "194918906349336491358672779618917502997637817531438332969065401947106663657572200535660543480981864390541727419063")),
+        field_new!(Fq2, field_new!(Fq, "238919484340096453727477913097826048229810762691080540288658779611334096856561709357774890016209986362611288673694"), field_new!(Fq, "258409134209951196234014999790930039990974447412084319691546044240334759246816134507102194093373902405554521058608")),
+        field_new!(Fq2, field_new!(Fq, "122657440193952905392053734331693795517459242305285025607985644327848369659632464678673631974559868120070490425122"), field_new!(Fq, "201165408325402757664900796529287082325445730586478298038676541596403192454881758823592993416066354824739060247971")),
+        field_new!(Fq2, field_new!(Fq, "77093945913707051261727331421895458142930033729166067893879410279527895018546337633800358269797108860352497432102"), field_new!(Fq, "53893002713710200740366380479595980611437579736137537265189550623972427243133909173322779708515869541107198093267")),
+        field_new!(Fq2, field_new!(Fq, "258131046793668174903719554718375238666713844692659107035557042430937622917484894845131342630709892827917996831880"), field_new!(Fq, "174589239660518237160555368322685365994061577081876905822431880934833102247951263029562997412782565399428850040171")),
+        field_new!(Fq2, field_new!(Fq, "135640496377285103024431252060088605726009463376013848617056158051635896046068950072889511755899610224553290227103"), field_new!(Fq, "129478614641914039967316621998656092278567979969553079507940601503148828411117983274395016630342177028223815653421")),
+        field_new!(Fq2, field_new!(Fq, "113310839339305665373754846043394910963308916385137477343285065378835588301021949586821888012770504845743768524695"), field_new!(Fq, "41929870204524621297573499056391339017942907379168665819735898403437788599059779392081017622635220984394125179971")),
+        field_new!(Fq2, field_new!(Fq, "186970281332993965695984032608335500533191567789010115831776391179551867388916328347451261129084126238608440531274"), field_new!(Fq, "40371079034269394626239813688645063859351519865909769948669819692117824670406312306889117837656095720859684863575")),
+        field_new!(Fq2, field_new!(Fq, "130229450163100228017360293900345495232845059466738635045287121698248850448224537066563948387029508022796327753122"), field_new!(Fq, "7783494594315367342918024396691411246151473515241030819899501167079656837605623925825859102811023050999562560211")),
+        field_new!(Fq2, field_new!(Fq, "224075062701282048399270159198880560301177397754104070101056143402078838586004647918015133492289414890013899863173"), field_new!(Fq, "158190086015815070235686599562810396724050337283695569363078283708529319305934905701334385914108759132100252391230")),
+        field_new!(Fq2, field_new!(Fq, "132855634599918225202814532934764077212374072979050989958159516881510054089774031987091740793127796187066114688756"), field_new!(Fq, "134524939791058615635136343643262266840901498457323872360307368185473156091028701577035080383643514702230468323495")),
+        field_new!(Fq2, field_new!(Fq, "54757351295027356157050656866114188559615724234427818125720558151350040423374559583958730747185506597597716324645"), field_new!(Fq, "206386977646970995347030329341802344857791334650150685467034166839868920497584396945469675306794585401439042598612")),
+        field_new!(Fq2, field_new!(Fq, "149362906606548241323256782293592324017905380441544496005592684335078350773595865736821683148418506858898250989328"), field_new!(Fq, "22952391745884687515644604392300808834601599308939220542603807045607571778318317615689110481610682791726379502532")),
+        field_new!(Fq2, field_new!(Fq, "250856898290042168281939426779972655322862329525519650608557106004798056838909133360640001039034364343165743489394"), field_new!(Fq, "181834671546726113777021502196904174053042190769930521040245300698299371692293019552242606070566726822094669299404")),
+        field_new!(Fq2, field_new!(Fq, "128732982707805391691687590286009895959656735484632205527369590895334628694224720856153394070390369274717255201909"), field_new!(Fq, "35939567100538423991120937339099597073553355628044718491425540377086096880833138949943816132847661451285604258610")),
+        field_new!(Fq2, field_new!(Fq, "191144230647045707590184821948778479495825928592047153194222027257046414968351470170933284561757547536684715232224"), field_new!(Fq, "0")),
+    ];
+
+    #[rustfmt::skip]
+    const PHI_Y_DEN: &'static [Fq2] = &[
+        field_new!(Fq2, field_new!(Fq, "162044972723002751760179566404628989949547778156855440715967722669301805417138913075317160160701017813580023545986"), field_new!(Fq, "145579380006244397591602844800747553786019369828170175270936386710950531200069595905414040846658650045090663046953")),
+        field_new!(Fq2, field_new!(Fq, "166456129026501659026753311298549687075263337183519615930968845376572506210124927259613985850880698280350158393318"), field_new!(Fq, "81498215874704918205918783126547624627344186527718572463335358383840174777676559058764273664562012379764971651784")),
+        field_new!(Fq2, field_new!(Fq, "95206939414382238916242050049036463925714374896204132484624609110268487313980123605586881178696437297596734724681"), field_new!(Fq, "115446946051335517832105197526615770523318859667842966646913612382780594903567602254711052737025096193109042432388")),
+        field_new!(Fq2, field_new!(Fq, "127075307127979111090665781299841633458467256700522852946522903320517625100790982559814945856002503459504661926855"), field_new!(Fq, "111553156305642244289197344157640730285695363693373703351150179199047618498984719232131423500806124783088268370959")),
+        field_new!(Fq2, field_new!(Fq, "60154323478771236104853930420110085912331152571838351622431613790156782260722719927614721070902272332774054690585"), field_new!(Fq, "165049024978173316971017445475882353808412963135707735333468368651629098000338710187836498015253165309795717712221")),
+        field_new!(Fq2, field_new!(Fq, "137308923346322382894261133190361861193545269176570350534764899873393360988668675055576152099326520303909622741912"), field_new!(Fq, "197879742424704750747093673047725713425719260261685964607290747183029132392367859197591372394151396352922204962638")),
+        field_new!(Fq2, field_new!(Fq, "100412180325350036121795258875270424455659057737661118873511736669331501945770166245200706313348903947878992507644"), field_new!(Fq, "45272466255678816090980121648725462456300855053238189437869631719238832663253532002167137043866356578044281905049")),
+        field_new!(Fq2, field_new!(Fq, "253108633665664270376599451881274956175866379231808508686106794962286467193015493760245592669462353019118527995472"), field_new!(Fq, "201712147706701341991655255194579507116094860199932413823597137310466373351439246223323917952978838193761553434505")),
+        field_new!(Fq2, field_new!(Fq, "41802784543185038151644168043152871064644702962821970960293233130005589336781094237341894717320576602535429208591"), field_new!(Fq, "218470752438172549962639390484256138038438833997532792636113863927364674593385644590883972443911713265376705211188")),
+        field_new!(Fq2, field_new!(Fq, "55805268664787638499313598605407558747776776282166821182278020009130089892399467583328996250948694194797613934921"), field_new!(Fq, "58225952689794945967616318665922517144246589168116315905878091852332200879437942278406884818248211623861097098341")),
+        field_new!(Fq2, field_new!(Fq, "12487801369230190051025937274296400371945142413187923579577974588732265710092298097703089469642348738165592442253"), field_new!(Fq, "86704883160776965870578515321510281032316534544458734742968300306879376356333234080710013436300488616379199662716")),
+        field_new!(Fq2, field_new!(Fq, "254530658569252181450166508970368505768130273324568049245094133641218289577557003182289724003112292740155870257073"), field_new!(Fq, "194828943255306009844098163267064468550316699909990780864703205670320470203456266463814861758597915634631269621758")),
+        field_new!(Fq2, field_new!(Fq, "81157773879253715467628873192401431047216174911162264850991188995339389986161386495810380492138836275321086659843"), field_new!(Fq, "241114882287034599186656640778091629719800491868794789083408621018766861667803308255045993833925405297673733846764")),
+        field_new!(Fq2, field_new!(Fq, "246567831126765942158963321383317754986876357323195760954252691470857828072220409965168738524798029119746643363400"), field_new!(Fq, "178365835860566930394950717696204812075297188132249866323197795464923793997044969884772915509842407947580908036965")),
+        field_new!(Fq2, field_new!(Fq, "178226611276254540865364111288226195708098045454278445628695057994527427850400025819413237503459776382917648544363"), field_new!(Fq, "252404454909014195258575073342289911601900144028867859094084598372904887119378730491172780980156999376811809000637")),
+        field_new!(Fq2, field_new!(Fq, "20542991590391963091899350349068493971442107457813369044416943855608964934032061798238192565153892618090512864499"), field_new!(Fq, "117556184278303841480201016157180171553757989850480018756417299827942095904765037436185867897221179525370642302666")),
+        field_new!(Fq2, field_new!(Fq, "129511653610583965993833577292285108926958100397056574646621150628038381454777671739984922459890822547879091179349"), field_new!(Fq, "194810003015006477363128037875832232721409637621967061576130142202745652716594741364250324075797776648640920631106")),
+        field_new!(Fq2, field_new!(Fq, "90756428120360570547192222735781572066790654053713599833158218403865552279545254109737092736528127004752652646998"), field_new!(Fq, "23033577190313351440777101093997531727923525842254918791787414778330130564554436865000256260999193556141359336462")),
+        field_new!(Fq2, field_new!(Fq, "189804629490397058466750044301427952079902716116354960196571320605299751535151262444874467025663277686098863547468"), field_new!(Fq, "57494040135164552958713242756898778285891163694144334720520560806422668542756779578461943594228422776332747786195")),
+        field_new!(Fq2, field_new!(Fq, "212961906298427842110789304048489575798915047713713109510979475194699345463734067441727787628602074919066925126271"), field_new!(Fq, "152770153652457147621363591192299675619247640778097746121750599000003367088977396473071110225372308835793531271889")),
+        field_new!(Fq2, field_new!(Fq, "151520745920408331667621668567087885849782038440382157473426150802036204468318959012707037537879089317937232201370"), field_new!(Fq, "115307863107042604368613714071540318895955374449004945581803459299896199282995259661803922507469482580022097243359")),
+        field_new!(Fq2, field_new!(Fq, "30482643223398315845777818190159105653130605202547439111838259308147519762208060599996218142226912213351050579267"), field_new!(Fq, "100942673601053810814498171796787724692022124907113118068937310633940445598025201518470061746186033898273766900313")),
+        field_new!(Fq2, field_new!(Fq, "52002132654050394207789694877386208767415856585629396888026796168718809443411575725735016234881122860427163004264"), field_new!(Fq, "154353186777528775159468376291778967500064790444121619452016066718473536726161079154838561764296578831274898175553")),
+        field_new!(Fq2, field_new!(Fq, "88889762921140268810800511439261249504349046390230226378224262320860231026160391465420706654580685836106432980390"), field_new!(Fq, "143356450868208715403337434168975604353431462870585801070680846376307831793280477221540975156285236557218070500276")),
+        field_new!(Fq2, field_new!(Fq, "205593292050806706203524390935864776312523742693679105631867426410105866855470791814375651189868504775446718155886"), field_new!(Fq, "73755329036087838256337826205898456228893116858905529305581778101209003268966201507109676934987841977171209595188")),
+        field_new!(Fq2, field_new!(Fq, "3131324965866595283798342650722637871912721417409175788676957365176572232130895333412621973203386397903361115430"), field_new!(Fq, "125424240238990007034802274922202705217760706859434350667276383655173238935184568623408435833546622866633190196536")),
+        field_new!(Fq2, field_new!(Fq, "167069363257309593772828211403839600080671932785065181777226289420424019742511373630687385169640438516380782978886"), field_new!(Fq, "31619019436549755736423961041287544589582782478243918383358970349475732005827894298896986080118095958306330718232")),
+        field_new!(Fq2, field_new!(Fq, "103095848083509221842195833986982924001352743758372591900823965434230226863215482065281931453920783595119926224395"), field_new!(Fq, "175143739395376932234567652115650769486810612351907648435970071012984533822145654362150043715393239464654613956406")),
+        field_new!(Fq2, field_new!(Fq, "39377293763651530875260303338894859076552230389270228319675719134824029128761545861337508854167113031541123206208"), field_new!(Fq, "116743456633241961037826292985524785771305915305540062815105510178959289111981469922114775055676823607497535947632")),
+        field_new!(Fq2, field_new!(Fq, "109460716833872767599509751766485645975861176992065598049726077557514233864976860200013462873530430984580420408708"), field_new!(Fq, "12952314086319944709645404313124334181758582182568403240196227475064036611478556623618880189664384099506346543771")),
+        field_new!(Fq2, field_new!(Fq, "181495477509268656394777215445294337997011472651102145196870305774931793226154077855926403329367703959321530197664"), field_new!(Fq, "176842693440162632140987299347650846457537932469272258543971162886441357591620671929292518020808022706568324905488")),
+        field_new!(Fq2, field_new!(Fq, "70192420972108092843658490484719793408647441199485304930096393585711425894139656446696619622514770561618510447017"), field_new!(Fq, "15767715619270401151520534797469153961649318802673838233639634626121274833293393921418923274541091262362034341422")),
+        field_new!(Fq2, field_new!(Fq, "81101097340336478260608487302058578280780910511775082506601976543991473428496754381727969322927411998697612365068"), field_new!(Fq, "133832950333235821965324660454725917418641370614313572770145861248949240400811114268989932478518267487803750188740")),
+        field_new!(Fq2, field_new!(Fq, "1"), field_new!(Fq, "0")),
+    ];
+}
@@ -5,7 +5,9 @@ use ark_ec::{
 };
 
 pub mod g1;
+pub mod g1_swu_iso;
 pub mod g2;
+pub mod g2_swu_iso;
 
 #[cfg(test)]
 mod tests;
@@ -13,8 +13,9 @@ use core::ops::{AddAssign, MulAssign};
 use crate::{g1, g2, Bls12_377, Fq, Fq12, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
 
 use ark_algebra_test_templates::{
-    curves::{curve_tests, sw_tests},
+    curves::{curve_tests, sw_hash_to_curve_test, sw_tests},
     groups::group_test,
+    msm::{test_batch_affine_msm, test_var_base_msm},
 };
 
 #[test]
@@ -24,6 +25,12 @@ fn test_g1_projective_curve() {
     sw_tests::<g1::Parameters>();
 }
 
+#[test]
+fn test_g1_msm() {
+    test_var_base_msm::<G1Affine>();
+    test_batch_affine_msm::<g1::Parameters>();
+}
+
 #[test]
 fn test_g1_projective_group() {
     let mut rng = test_rng();
@@ -120,3 +127,13 @@ fn test_g1_generator_raw() {
         x.add_assign(&Fq::one());
     }
 }
+
+#[test]
+fn test_g1_hash_to_curve() {
+    sw_hash_to_curve_test::<g1::Parameters>();
+}
+
+#[test]
+fn test_g2_hash_to_curve() {
+    sw_hash_to_curve_test::<g2::Parameters>();
+}
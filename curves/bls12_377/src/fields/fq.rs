@@ -19,6 +19,17 @@ impl FftParameters for FqParameters {
         17966377291017729567u64,
         68610905582439508u64,
     ]);
+
+    const SMALL_SUBGROUP_BASE: Option<u32> = Some(3);
+    const SMALL_SUBGROUP_BASE_ADICITY: Option<u32> = Some(1);
+    const LARGE_SUBGROUP_ROOT_OF_UNITY: Option<BigInteger> = Some(BigInteger([
+        0x5c216bb4afec1ffd,
+        0x2a5bcdac84278fd,
+        0x90d05eeb679a553b,
+        0xada6583e8792414,
+        0xb73d2f08877d8112,
+        0x4d88b866430ac5,
+    ]));
 }
 impl FpParameters for FqParameters {
     /// MODULUS = 258664426012969094010652733694893533536393512754914660539884262666720468348340822774968888139573360124440321458177
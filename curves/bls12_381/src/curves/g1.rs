@@ -1,6 +1,7 @@
 use crate::*;
 use ark_ec::{
     bls12,
+    glv::GLVParameters,
     models::{ModelParameters, SWModelParameters},
 };
 use ark_ff::{field_new, Zero};
@@ -40,8 +41,39 @@ impl SWModelParameters for Parameters {
     fn mul_by_a(_: &Self::BaseField) -> Self::BaseField {
         Self::BaseField::zero()
     }
+
+    /// The lattice basis is (1, 1 - x^2), (x^2, 1), for the curve parameter x.
+    #[inline(always)]
+    fn glv() -> Option<GLVParameters<Self>> {
+        Some(GLVParameters {
+            endo_coeff: ENDO_COEFF,
+            lambda: LAMBDA,
+            scalar_decomp_coeffs: [
+                (false, 1),
+                (true, 228988810152649578064853576960394133503),
+                (false, 228988810152649578064853576960394133504),
+                (false, 1),
+            ],
+            scalar_decomp_rounding: [
+                (false, 1),
+                (false, 126416754993536952944562482807443815308),
+            ],
+            shift: 254,
+        })
+    }
 }
 
+/// ENDO_COEFF = a cube root of unity in Fq, with (x, y) -> (ENDO_COEFF * x, y) multiplying G1
+/// by LAMBDA
+/// = 793479390729215512621379701633421447060886740281060493010456487427281649075476305620758731620350
+#[rustfmt::skip]
+pub const ENDO_COEFF: Fq = field_new!(Fq, "793479390729215512621379701633421447060886740281060493010456487427281649075476305620758731620350");
+
+/// LAMBDA = a cube root of unity in Fr
+/// = 52435875175126190479447740508185965837461563690374988244538805122978187051009
+#[rustfmt::skip]
+pub const LAMBDA: Fr = field_new!(Fr, "52435875175126190479447740508185965837461563690374988244538805122978187051009");
+
 /// G1_GENERATOR_X =
 /// 3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507
 #[rustfmt::skip]
@@ -0,0 +1,101 @@
+//! Hashing to G2 with RFC 9380's suite `BLS12381G2_XMD:SHA-256_SSWU_RO_`: the curve `E'` that the
+//! SWU map maps to, and the 3-isogeny from it to G2, from the RFC's section 8.8.2 and appendix
+//! E.3.
+use crate::{Fq, Fq2, Fr};
+use ark_ec::{
+    hashing::{SWUParams, WBParams},
+    models::{ModelParameters, SWModelParameters},
+};
+use ark_ff::field_new;
+
+use super::g2;
+
+/// The curve that [g2::Parameters] is 3-isogenous to, for the SWU map.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SwuIsoParameters;
+
+impl ModelParameters for SwuIsoParameters {
+    type BaseField = Fq2;
+    type ScalarField = Fr;
+}
+
+impl SWModelParameters for SwuIsoParameters {
+    /// COEFF_A = [0, 240]
+    #[rustfmt::skip]
+    const COEFF_A: Fq2 = field_new!(Fq2, field_new!(Fq, "0"), field_new!(Fq, "240"));
+
+    /// COEFF_B = [1012, 1012]
+    #[rustfmt::skip]
+    const COEFF_B: Fq2 = field_new!(Fq2, field_new!(Fq, "1012"), field_new!(Fq, "1012"));
+
+    /// COFACTOR, as for G2: isogenous curves have the same number of points
+    const COFACTOR: &'static [u64] = g2::Parameters::COFACTOR;
+
+    const COFACTOR_INV: Fr = g2::Parameters::COFACTOR_INV;
+
+    /// AFFINE_GENERATOR_COEFFS = COFACTOR times the point with the least x (as an integer),
+    /// and the even y
+    #[rustfmt::skip]
+    const AFFINE_GENERATOR_COEFFS: (Self::BaseField, Self::BaseField) = (
+        field_new!(Fq2, field_new!(Fq, "2595569946714414516067015540153643524656442638788025933727967960306287756885400469291119095920626560658971252184199"), field_new!(Fq, "1037079738597573406765355774006601850633656296583542639082316151670128374872040593053087014315526494961765370307992")),
+        field_new!(Fq2, field_new!(Fq, "74480082227005738379067770238572711381750951260377338410582752833319840089542203192014419701049757383464915445300"), field_new!(Fq, "702083236876097377659150492526714988680564498553784099825961638526469739667836533609722852421641401659291481334898")),
+    );
+}
+
+impl SWUParams for SwuIsoParameters {
+    /// ZETA = [-2, -1]
+    #[rustfmt::skip]
+    const ZETA: Fq2 = field_new!(Fq2, field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559785"), field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559786"));
+}
+
+/// The 3-isogeny `E' -> G2` of the RFC's appendix E.3.
+impl WBParams for g2::Parameters {
+    type IsogenousCurve = SwuIsoParameters;
+
+    #[rustfmt::skip]
+    const PHI_X_NOM: &'static [Fq2] = &[
+        field_new!(Fq2, field_new!(Fq, "889424345604814976315064405719089812568196182208668418962679585805340366775741747653930584250892369786198727235542"), field_new!(Fq, "889424345604814976315064405719089812568196182208668418962679585805340366775741747653930584250892369786198727235542")),
+        field_new!(Fq2, field_new!(Fq, "0"), field_new!(Fq, "2668273036814444928945193217157269437704588546626005256888038757416021100327225242961791752752677109358596181706522")),
+        field_new!(Fq2, field_new!(Fq, "2668273036814444928945193217157269437704588546626005256888038757416021100327225242961791752752677109358596181706526"), field_new!(Fq, "1334136518407222464472596608578634718852294273313002628444019378708010550163612621480895876376338554679298090853261")),
+        field_new!(Fq2, field_new!(Fq, "3557697382419259905260257622876359250272784728834673675850718343221361467102966990615722337003569479144794908942033"), field_new!(Fq, "0")),
+    ];
+
+    #[rustfmt::skip]
+    const PHI_X_DEN: &'static [Fq2] = &[
+        field_new!(Fq2, field_new!(Fq, "0"), field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559715")),
+        field_new!(Fq2, field_new!(Fq, "12"), field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559775")),
+        field_new!(Fq2, field_new!(Fq, "1"), field_new!(Fq, "0")),
+    ];
+
+    #[rustfmt::skip]
+    const PHI_Y_NOM: &'static [Fq2] = &[
+        field_new!(Fq2, field_new!(Fq, "3261222600550988246488569487636662646083386001431784202863158481286248011511053074731078808919938689216061999863558"), field_new!(Fq, "3261222600550988246488569487636662646083386001431784202863158481286248011511053074731078808919938689216061999863558")),
+        field_new!(Fq2, field_new!(Fq, "0"), field_new!(Fq, "889424345604814976315064405719089812568196182208668418962679585805340366775741747653930584250892369786198727235518")),
+        field_new!(Fq2, field_new!(Fq, "2668273036814444928945193217157269437704588546626005256888038757416021100327225242961791752752677109358596181706524"), field_new!(Fq, "1334136518407222464472596608578634718852294273313002628444019378708010550163612621480895876376338554679298090853263")),
+        field_new!(Fq2, field_new!(Fq, "2816510427748580758331037284777117739799287910327449993381818688383577828123182200904113516794492504322962636245776"), field_new!(Fq, "0")),
+    ];
+
+    #[rustfmt::skip]
+    const PHI_Y_DEN: &'static [Fq2] = &[
+        field_new!(Fq2, field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559355"), field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559355")),
+        field_new!(Fq2, field_new!(Fq, "0"), field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559571")),
+        field_new!(Fq2, field_new!(Fq, "18"), field_new!(Fq, "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559769")),
+        field_new!(Fq2, field_new!(Fq, "1"), field_new!(Fq, "0")),
+    ];
+
+    /// H_EFF = 3 (x^2 - 1) COFACTOR, for the curve parameter x: the RFC's h_eff, whose image is
+    /// that of the faster `clear_cofactor_bls12381_g2`
+    #[rustfmt::skip]
+    const H_EFF: &'static [u64] = &[
+        0xe8020005aaa95551,
+        0x59894c0adebbf6b4,
+        0xe954cbc06689f6a3,
+        0x2ec0ec69d7477c1a,
+        0x6d82bf015d1212b0,
+        0x329c2f178731db95,
+        0x9986ff031508ffe1,
+        0x88e2a8e9145ad768,
+        0x584c6a0ea91b3528,
+        0xbc69f08f2ee75b3,
+    ];
+}
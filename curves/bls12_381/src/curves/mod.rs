@@ -3,7 +3,9 @@ use ark_ec::bls12::{Bls12, Bls12Parameters, TwistType};
 use crate::{Fq, Fq12Parameters, Fq2Parameters, Fq6Parameters};
 
 pub mod g1;
+pub mod g1_swu_iso;
 pub mod g2;
+pub mod g2_swu_iso;
 
 #[cfg(test)]
 mod tests;
@@ -1,6 +1,7 @@
 #![allow(unused_imports)]
 use ark_ec::{models::SWModelParameters, AffineCurve, PairingEngine, ProjectiveCurve};
 use ark_ff::{
+    field_new,
     fields::{Field, FpParameters, PrimeField, SquareRootField},
     One, Zero,
 };
@@ -10,7 +11,8 @@ use ark_std::test_rng;
 use core::ops::{AddAssign, MulAssign};
 
 use crate::{g1, g2, Bls12_381, Fq, Fq12, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
-use ark_algebra_test_templates::{curves::*, groups::*};
+use ark_algebra_test_templates::{curves::*, groups::*, msm::*};
+use ark_ec::hashing::{expand_message_xmd, HashToCurve, MapToCurveBasedHasher};
 
 #[test]
 fn test_g1_projective_curve() {
@@ -19,6 +21,12 @@ fn test_g1_projective_curve() {
     sw_tests::<g1::Parameters>();
 }
 
+#[test]
+fn test_g1_msm() {
+    test_var_base_msm::<G1Affine>();
+    test_batch_affine_msm::<g1::Parameters>();
+}
+
 #[test]
 fn test_g1_projective_group() {
     let mut rng = test_rng();
@@ -115,3 +123,55 @@ fn test_g1_generator_raw() {
         x.add_assign(&Fq::one());
     }
 }
+
+/// The test vector for `expand_message_xmd` with SHA-256 in RFC 9380's appendix
+/// K.1, for the empty message.
+#[test]
+fn test_expand_message_xmd() {
+    let bytes = expand_message_xmd(b"", b"QUUX-V01-CS02-with-expander-SHA256-128", 0x20).unwrap();
+    assert_eq!(
+        bytes,
+        [
+            0x68, 0xa9, 0x85, 0xb8, 0x7e, 0xb6, 0xb4, 0x69, 0x52, 0x12, 0x89, 0x11, 0xf2, 0xa4,
+            0x41, 0x2b, 0xbc, 0x30, 0x2a, 0x9d, 0x75, 0x96, 0x67, 0xf8, 0x7f, 0x7a, 0x21, 0xd8,
+            0x03, 0xf0, 0x72, 0x35,
+        ]
+    );
+    assert!(expand_message_xmd(b"", b"", 255 * 32 + 1).is_err());
+}
+
+/// The suite `BLS12381G1_XMD:SHA-256_SSWU_RO_`, with the test vector of RFC
+/// 9380's appendix J.9.1 for the empty message.
+#[test]
+fn test_g1_hash_to_curve() {
+    sw_hash_to_curve_test::<g1::Parameters>();
+
+    let hasher = MapToCurveBasedHasher::<g1::Parameters>::new(
+        b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_",
+    )
+    .unwrap();
+    let p = hasher.hash(b"").unwrap();
+    assert_eq!(p.x, field_new!(Fq, "794311575721400831362957049303781044852006323422624111893352859557450008308620925451441746926395141598720928151969"));
+    assert_eq!(p.y, field_new!(Fq, "1343412193624222137939591894701031123123641958980729764240763391191550653712890272928110356903136085217047453540965"));
+}
+
+/// The suite `BLS12381G2_XMD:SHA-256_SSWU_RO_`, with the test vector of RFC
+/// 9380's appendix J.10.1 for the empty message.
+#[test]
+fn test_g2_hash_to_curve() {
+    sw_hash_to_curve_test::<g2::Parameters>();
+
+    let hasher = MapToCurveBasedHasher::<g2::Parameters>::new(
+        b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_",
+    )
+    .unwrap();
+    let p = hasher.hash(b"").unwrap();
+    assert_eq!(
+        p.x,
+        field_new!(Fq2, field_new!(Fq, "193548053368451749411421515628510806626565736652086807419354395577367693778571452628423727082668900187036482254730"), field_new!(Fq, "891930009643099423308102777951250899694559203647724988361022851024990473423938537113948850338098230396747396259901"))
+    );
+    assert_eq!(
+        p.y,
+        field_new!(Fq2, field_new!(Fq, "771717272055834152378281705972671257005357145478800908373659404991537354153455452961747174765859335819766715637138"), field_new!(Fq, "2810310118582126634041133454180705304393079139103252956502404531123692847658283858246402311867775854528543237781718"))
+    );
+}
@@ -0,0 +1,30 @@
+//! Timing protocols with every party run in-process.
+use mpc_test_utils::run_parties;
+
+use std::time::{Duration, Instant};
+
+/// The time for `n` parties to run `op` `iters` times on the input made by `setup`, for
+/// [criterion::Bencher::iter_custom]. Setup is not timed, and the slowest party's time is taken.
+///
+/// The parties share one machine, so interactive protocols measure computation and in-memory
+/// messaging, not a real network.
+pub fn time_parties<T, R>(
+    n: usize,
+    iters: u64,
+    init: impl Fn() + Sync,
+    setup: impl Fn() -> T + Sync,
+    op: impl Fn(&T) -> R + Sync,
+) -> Duration {
+    run_parties(n, || {
+        init();
+        let input = setup();
+        let start = Instant::now();
+        for _ in 0..iters {
+            criterion::black_box(op(&input));
+        }
+        start.elapsed()
+    })
+    .into_iter()
+    .max()
+    .unwrap()
+}
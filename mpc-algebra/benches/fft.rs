@@ -0,0 +1,45 @@
+//! FFTs over shared field elements, which are local, at a range of sizes.
+use ark_bls12_377::Fr;
+use ark_ff::UniformRand;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use mpc_algebra::{
+    share::{add::AdditiveFieldShare, field::FieldShare, gsz20::field::GszFieldShare},
+    MpcField, Reveal,
+};
+
+mod common;
+use common::time_parties;
+
+fn bench_scheme<S: FieldShare<Fr>>(c: &mut Criterion, name: &str, n: usize) {
+    let mut group = c.benchmark_group(format!("fft/{}-{}", name, n));
+    group.sample_size(10);
+    for log in &[10, 12, 14] {
+        let size = 1usize << log;
+        let setup = || {
+            let rng = &mut ark_std::test_rng();
+            let xs: Vec<Fr> = (0..size).map(|_| Fr::rand(rng)).collect();
+            let domain = Radix2EvaluationDomain::<MpcField<Fr, S>>::new(size).unwrap();
+            (domain, MpcField::<Fr, S>::king_share_batch(xs, rng))
+        };
+        group.bench_with_input(BenchmarkId::new("fft", size), &size, |b, _| {
+            b.iter_custom(|iters| {
+                time_parties(n, iters, S::init_protocol, setup, |(d, xs)| d.fft(xs))
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("ifft", size), &size, |b, _| {
+            b.iter_custom(|iters| {
+                time_parties(n, iters, S::init_protocol, setup, |(d, xs)| d.ifft(xs))
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_fft(c: &mut Criterion) {
+    bench_scheme::<AdditiveFieldShare<Fr>>(c, "additive", 2);
+    bench_scheme::<GszFieldShare<Fr>>(c, "gsz20", 3);
+}
+
+criterion_group!(benches, bench_fft);
+criterion_main!(benches);
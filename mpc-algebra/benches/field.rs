@@ -0,0 +1,131 @@
+//! Field share operations, under each scheme.
+use ark_bls12_377::Fr;
+use ark_ff::{Field, One, UniformRand};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use mpc_algebra::{
+    share::dynamic::{self, DynFieldShare, Scheme},
+    share::{
+        add::AdditiveFieldShare,
+        field::{FieldShare, SmallScalar},
+        gsz20::field::GszFieldShare,
+        spdz::*,
+    },
+    MpcField, Reveal,
+};
+
+mod common;
+use common::time_parties;
+
+const BATCH: usize = 1024;
+
+fn bench_scheme<S: FieldShare<Fr>>(c: &mut Criterion, name: &str, n: usize) {
+    bench_scheme_with::<S>(c, name, n, S::init_protocol)
+}
+
+/// [bench_scheme], with each party set up by `init`.
+fn bench_scheme_with<S: FieldShare<Fr>>(
+    c: &mut Criterion,
+    name: &str,
+    n: usize,
+    init: impl Fn() + Copy + Sync,
+) {
+    let mut group = c.benchmark_group(format!("field/{}-{}", name, n));
+    let pair = || {
+        let rng = &mut ark_std::test_rng();
+        let a = MpcField::<Fr, S>::king_share(Fr::rand(rng), rng);
+        let b = MpcField::<Fr, S>::king_share(Fr::rand(rng), rng);
+        (a, b)
+    };
+    let batch = || {
+        let rng = &mut ark_std::test_rng();
+        let xs: Vec<Fr> = (0..BATCH).map(|_| Fr::rand(rng)).collect();
+        let ys: Vec<Fr> = (0..BATCH).map(|_| Fr::rand(rng)).collect();
+        (
+            MpcField::<Fr, S>::king_share_batch(xs, rng),
+            MpcField::<Fr, S>::king_share_batch(ys, rng),
+        )
+    };
+    group.bench_function("add", |b| {
+        b.iter_custom(|iters| time_parties(n, iters, init, pair, |(x, y)| *x + *y))
+    });
+    group.bench_function("mul", |b| {
+        b.iter_custom(|iters| time_parties(n, iters, init, pair, |(x, y)| *x * *y))
+    });
+    group.bench_function("inverse", |b| {
+        b.iter_custom(|iters| time_parties(n, iters, init, pair, |(x, _)| x.inverse()))
+    });
+    group.bench_function("reveal", |b| {
+        b.iter_custom(|iters| time_parties(n, iters, init, pair, |(x, _)| x.reveal()))
+    });
+    group.bench_with_input(BenchmarkId::new("batch_product", BATCH), &BATCH, |b, _| {
+        b.iter_custom(|iters| {
+            time_parties(n, iters, init, batch, |(xs, ys)| {
+                let mut xs = xs.clone();
+                MpcField::batch_product_in_place(&mut xs, ys);
+                xs
+            })
+        })
+    });
+    group.finish();
+}
+
+/// Shares scaled by public constants as products by public values are, skipping the
+/// multiplication for `0` and `±1`, against plain [FieldShare::scale] by the same constants: those
+/// should gain from the check, and the others lose little to it. Powers of two are not skipped for
+/// fields, as their doublings cost as much as the multiplication.
+fn bench_public_scale<S: FieldShare<Fr>>(c: &mut Criterion, name: &str, n: usize) {
+    let mut group = c.benchmark_group(format!("field/{}-{}/public-scale", name, n));
+    let constants = [
+        ("one", Fr::one()),
+        ("minus-one", -Fr::one()),
+        ("two", Fr::from(2u8)),
+        ("large", Fr::rand(&mut ark_std::test_rng())),
+    ];
+    let setup = || {
+        let rng = &mut ark_std::test_rng();
+        let xs: Vec<Fr> = (0..BATCH).map(|_| Fr::rand(rng)).collect();
+        let xs = MpcField::<Fr, S>::king_share_batch(xs, rng);
+        MpcField::all_public_or_shared(xs).unwrap_err()
+    };
+    for &(constant, k) in &constants {
+        group.bench_with_input(BenchmarkId::new("checked", constant), &k, |b, k| {
+            b.iter_custom(|iters| {
+                time_parties(n, iters, S::init_protocol, setup, |xs| {
+                    let scale = |x: &S| match SmallScalar::of_unit(k) {
+                        Some(small) => *x.clone().scale_small(small),
+                        None => *x.clone().scale(k),
+                    };
+                    xs.iter().map(scale).collect::<Vec<_>>()
+                })
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("scale", constant), &k, |b, k| {
+            b.iter_custom(|iters| {
+                time_parties(n, iters, S::init_protocol, setup, |xs| {
+                    xs.iter().map(|x| *x.clone().scale(k)).collect::<Vec<_>>()
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_field(c: &mut Criterion) {
+    bench_scheme::<AdditiveFieldShare<Fr>>(c, "additive", 2);
+    bench_scheme::<AdditiveFieldShare<Fr>>(c, "additive", 3);
+    bench_scheme::<SpdzFieldShare<Fr>>(c, "spdz", 2);
+    bench_scheme::<GszFieldShare<Fr>>(c, "gsz20", 3);
+    bench_public_scale::<AdditiveFieldShare<Fr>>(c, "additive", 2);
+    bench_public_scale::<SpdzFieldShare<Fr>>(c, "spdz", 2);
+    bench_public_scale::<GszFieldShare<Fr>>(c, "gsz20", 3);
+    // the same, with the scheme chosen at run time, for the cost of the dispatch
+    for &(scheme, name, n) in &[(Scheme::Spdz, "dyn-spdz", 2), (Scheme::Gsz, "dyn-gsz20", 3)] {
+        bench_scheme_with::<DynFieldShare<Fr>>(c, name, n, move || {
+            dynamic::set_scheme(scheme);
+            DynFieldShare::<Fr>::init_protocol();
+        });
+    }
+}
+
+criterion_group!(benches, bench_field);
+criterion_main!(benches);
@@ -0,0 +1,87 @@
+//! Multi-scalar multiplications of public bases by shared scalars, with and without fixed-base
+//! tables.
+use ark_bls12_377::{Fr, G1Affine, G1Projective};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::UniformRand;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use mpc_algebra::{
+    fixed_base::{default_width, FixedBaseTable},
+    msm::ProjectiveMsm,
+    share::{
+        add::AdditiveGroupShare, field::FieldShare, group::GroupShare, gsz20::group::GszGroupShare,
+        spdz::SpdzGroupShare,
+    },
+    Reveal,
+};
+
+mod common;
+use common::time_parties;
+
+type G = G1Projective;
+
+const SIZES: [usize; 3] = [1 << 8, 1 << 10, 1 << 12];
+
+fn inputs<S: GroupShare<G>>(size: usize) -> (Vec<G>, Vec<S::FieldShare>) {
+    let rng = &mut ark_std::test_rng();
+    let bases: Vec<G> = (0..size).map(|_| G::rand(rng)).collect();
+    let scalars: Vec<Fr> = (0..size).map(|_| Fr::rand(rng)).collect();
+    (bases, S::FieldShare::king_share_batch(scalars, rng))
+}
+
+fn bench_scheme<S: GroupShare<G>>(c: &mut Criterion, name: &str, n: usize) {
+    let mut group = c.benchmark_group(format!("msm/{}-{}", name, n));
+    group.sample_size(10);
+    for &size in &SIZES {
+        group.bench_with_input(BenchmarkId::new("pippenger", size), &size, |b, &size| {
+            b.iter_custom(|iters| {
+                time_parties(
+                    n,
+                    iters,
+                    S::FieldShare::init_protocol,
+                    || inputs::<S>(size),
+                    |(bases, scalars)| S::multi_scale_pub_group(bases, scalars),
+                )
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("fixed_base", size), &size, |b, &size| {
+            b.iter_custom(|iters| {
+                time_parties(
+                    n,
+                    iters,
+                    S::FieldShare::init_protocol,
+                    || {
+                        let (bases, scalars) = inputs::<S>(size);
+                        let bases = G::batch_normalization_into_affine(&bases);
+                        (FixedBaseTable::new(&bases, default_width(size)), scalars)
+                    },
+                    |(table, scalars)| S::multi_scale_pub_group_by(scalars, |s| table.msm(s)),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_tables(c: &mut Criterion) {
+    let mut group = c.benchmark_group("msm/table");
+    group.sample_size(10);
+    let size = 1 << 10;
+    let rng = &mut ark_std::test_rng();
+    let bases: Vec<G1Affine> = (0..size).map(|_| G::rand(rng).into_affine()).collect();
+    for width in &[4, 8, 12] {
+        group.bench_with_input(BenchmarkId::new("build", width), width, |b, &w| {
+            b.iter(|| FixedBaseTable::new(&bases, w))
+        });
+    }
+    group.finish();
+}
+
+fn bench_msm(c: &mut Criterion) {
+    bench_scheme::<AdditiveGroupShare<G, ProjectiveMsm<G>>>(c, "additive", 2);
+    bench_scheme::<SpdzGroupShare<G, ProjectiveMsm<G>>>(c, "spdz", 2);
+    bench_scheme::<GszGroupShare<G, ProjectiveMsm<G>>>(c, "gsz20", 3);
+    bench_tables(c);
+}
+
+criterion_group!(benches, bench_msm);
+criterion_main!(benches);
@@ -0,0 +1,244 @@
+//! Authorization of openings, per organization.
+//!
+//! An organization that runs a party may want a say in which outputs its node helps open. Code
+//! that opens values does so within [labelled], naming the output, and before each opening of a
+//! shared [MpcField](crate::MpcField), [MpcGroup](crate::MpcGroup) or pairing value, the current
+//! session's [Authorizer] is asked to approve it. An opening it denies panics, before anything is
+//! sent, so the node never contributes its shares.
+//!
+//! [TokenPolicy] is an authorizer that approves an output only if the organization has issued an
+//! [ApprovalToken] for it: a Schnorr signature, by the organization's [PolicyKey], on the output's
+//! label and how many values may be opened under it. The node holds only the public key, so it
+//! cannot approve anything itself.
+//!
+//! As with [budget](crate::budget), openings inside protocols, of values masked by fresh
+//! randomness, need no approval, and public values are never checked.
+use ark_bls12_377::{Fr, G1Affine, G1Projective};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand};
+use ark_serialize::CanonicalSerialize;
+use lazy_static::lazy_static;
+use mpc_net::MpcMultiNet as Net;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Mutex;
+
+use crate::budget;
+
+/// An opening awaiting approval.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RevealRequest<'a> {
+    /// The innermost [labelled] output being opened, if any.
+    pub label: Option<&'a str>,
+    /// How many values are opened at once.
+    pub count: u64,
+}
+
+/// Decides which openings this party takes part in.
+pub trait Authorizer: Send {
+    /// Approve `request`, or say why not.
+    fn authorize(&mut self, request: &RevealRequest) -> Result<(), String>;
+}
+
+lazy_static! {
+    /// By session tag.
+    static ref AUTHORIZERS: Mutex<HashMap<u64, Box<dyn Authorizer>>> =
+        Mutex::new(HashMap::new());
+}
+
+thread_local! {
+    static LABELS: RefCell<Vec<String>> = RefCell::new(Vec::new());
+}
+
+/// Require `authorizer`'s approval for the openings of the current session, or with `None`, stop
+/// requiring any.
+pub fn set_authorizer(authorizer: Option<Box<dyn Authorizer>>) {
+    let mut all = AUTHORIZERS.lock().unwrap();
+    match authorizer {
+        Some(a) => {
+            all.insert(Net::session_tag(), a);
+        }
+        None => {
+            all.remove(&Net::session_tag());
+        }
+    }
+}
+
+/// Run `f`, whose openings are of the output `label`.
+pub fn labelled<T>(label: &str, f: impl FnOnce() -> T) -> T {
+    struct Guard;
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            LABELS.with(|l| l.borrow_mut().pop());
+        }
+    }
+    LABELS.with(|l| l.borrow_mut().push(label.to_owned()));
+    let _guard = Guard;
+    f()
+}
+
+/// Ask the current session's authorizer to approve opening `count` shared values.
+///
+/// Panics if it refuses.
+pub(crate) fn authorize(count: u64) {
+    if budget::is_exempt() {
+        return;
+    }
+    let mut all = AUTHORIZERS.lock().unwrap();
+    let authorizer = match all.get_mut(&Net::session_tag()) {
+        Some(a) => a,
+        None => return,
+    };
+    let label = LABELS.with(|l| l.borrow().last().cloned());
+    let request = RevealRequest {
+        label: label.as_deref(),
+        count,
+    };
+    if let Err(reason) = authorizer.authorize(&request) {
+        drop(all);
+        match label {
+            Some(l) => panic!("Opening of `{}` not authorized: {}", l, reason),
+            None => panic!("Unlabelled opening not authorized: {}", reason),
+        }
+    }
+}
+
+/// An organization's key for approving openings. Keep it away from the nodes.
+pub struct PolicyKey {
+    secret: Fr,
+}
+
+/// The public half of a [PolicyKey], which the organization's nodes hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PolicyPublicKey(pub G1Affine);
+
+/// Approval to open up to `max_openings` values labelled `label`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApprovalToken {
+    pub label: String,
+    pub max_openings: u64,
+    pub nonce: G1Affine,
+    pub s: Fr,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AclError {
+    /// The token was not signed by the policy's key.
+    BadSignature { label: String },
+}
+
+impl Display for AclError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AclError::BadSignature { label } => {
+                write!(f, "approval token for `{}` has a bad signature", label)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AclError {}
+
+fn challenge(key: &G1Affine, nonce: &G1Affine, label: &str, max_openings: u64) -> Fr {
+    let mut bytes = Vec::new();
+    key.serialize(&mut bytes).unwrap();
+    nonce.serialize(&mut bytes).unwrap();
+    bytes.extend_from_slice(&max_openings.to_le_bytes());
+    bytes.extend_from_slice(label.as_bytes());
+    Fr::from_le_bytes_mod_order(&Sha256::digest(&bytes))
+}
+
+impl PolicyKey {
+    pub fn generate<R: Rng>(rng: &mut R) -> Self {
+        Self {
+            secret: Fr::rand(rng),
+        }
+    }
+
+    pub fn public(&self) -> PolicyPublicKey {
+        PolicyPublicKey(
+            G1Affine::prime_subgroup_generator()
+                .mul(self.secret)
+                .into_affine(),
+        )
+    }
+
+    /// Approve opening up to `max_openings` values labelled `label`.
+    pub fn approve<R: Rng>(&self, label: &str, max_openings: u64, rng: &mut R) -> ApprovalToken {
+        let k = Fr::rand(rng);
+        let nonce = G1Affine::prime_subgroup_generator().mul(k).into_affine();
+        let e = challenge(&self.public().0, &nonce, label, max_openings);
+        ApprovalToken {
+            label: label.to_owned(),
+            max_openings,
+            nonce,
+            s: k + e * self.secret,
+        }
+    }
+}
+
+impl PolicyPublicKey {
+    /// Whether this key signed `token`.
+    pub fn verify(&self, token: &ApprovalToken) -> bool {
+        let e = challenge(&self.0, &token.nonce, &token.label, token.max_openings);
+        let lhs = G1Affine::prime_subgroup_generator().mul(token.s);
+        let rhs: G1Projective = token.nonce.into_projective() + self.0.mul(e);
+        lhs == rhs
+    }
+}
+
+/// Approves labelled openings covered by an organization's [ApprovalToken]s, each up to its
+/// count. Unlabelled openings are refused.
+pub struct TokenPolicy {
+    key: PolicyPublicKey,
+    /// Openings left, by label.
+    allowed: HashMap<String, u64>,
+}
+
+impl TokenPolicy {
+    pub fn new(key: PolicyPublicKey) -> Self {
+        Self {
+            key,
+            allowed: HashMap::new(),
+        }
+    }
+
+    /// Accept `token`, if the organization signed it, adding to what its label may open.
+    pub fn present(&mut self, token: &ApprovalToken) -> Result<(), AclError> {
+        if !self.key.verify(token) {
+            return Err(AclError::BadSignature {
+                label: token.label.clone(),
+            });
+        }
+        *self.allowed.entry(token.label.clone()).or_insert(0) += token.max_openings;
+        Ok(())
+    }
+
+    /// How many more values labelled `label` may be opened.
+    pub fn remaining(&self, label: &str) -> u64 {
+        self.allowed.get(label).copied().unwrap_or(0)
+    }
+}
+
+impl Authorizer for TokenPolicy {
+    fn authorize(&mut self, request: &RevealRequest) -> Result<(), String> {
+        let label = request
+            .label
+            .ok_or_else(|| "no approval token covers unlabelled openings".to_owned())?;
+        match self.allowed.get_mut(label) {
+            Some(left) if *left >= request.count => {
+                *left -= request.count;
+                Ok(())
+            }
+            Some(left) => Err(format!(
+                "{} value(s) approved, but {} requested",
+                left, request.count
+            )),
+            None => Err("no approval token".to_owned()),
+        }
+    }
+}
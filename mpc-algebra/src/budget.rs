@@ -0,0 +1,161 @@
+//! A limit on how many shared values a session opens.
+//!
+//! Every opening of a shared [MpcField](crate::MpcField), [MpcGroup](crate::MpcGroup) or pairing
+//! value, through [Reveal::reveal](crate::Reveal::reveal) or
+//! [MpcWire::publicize](mpc_trait::MpcWire::publicize), is charged to the current session. An
+//! application that knows how many values it means to open can set a [RevealBudget], so that a
+//! bug which opens more (say, a reveal in a loop) is caught rather than leaking its inputs.
+//!
+//! Openings inside protocols, of values masked by fresh randomness, are not charged. Public
+//! values are free.
+//!
+//! The charge is made before the opening, and every party opens the same values, so when the
+//! budget runs out all parties stop at the same point, before anything is revealed.
+use lazy_static::lazy_static;
+use log::warn;
+use mpc_net::MpcMultiNet as Net;
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// What to do when a session opens more values than its budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnExceed {
+    /// Panic, before opening the value.
+    Abort,
+    /// Log a warning, once, and carry on.
+    Warn,
+}
+
+/// How many shared values a session may open.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RevealBudget {
+    pub limit: u64,
+    pub on_exceed: OnExceed,
+}
+
+impl RevealBudget {
+    /// Allow `limit` openings, then abort.
+    pub fn abort_after(limit: u64) -> Self {
+        Self {
+            limit,
+            on_exceed: OnExceed::Abort,
+        }
+    }
+
+    /// Allow `limit` openings, then warn.
+    pub fn warn_after(limit: u64) -> Self {
+        Self {
+            limit,
+            on_exceed: OnExceed::Warn,
+        }
+    }
+}
+
+struct Account {
+    budget: RevealBudget,
+    spent: u64,
+    warned: bool,
+}
+
+lazy_static! {
+    /// By session tag.
+    static ref ACCOUNTS: Mutex<HashMap<u64, Account>> = Mutex::new(HashMap::new());
+}
+
+thread_local! {
+    static EXEMPT: Cell<usize> = Cell::new(0);
+}
+
+/// Limit the openings of the current session, or with `None`, lift the limit. Either way, the
+/// count starts again from zero.
+pub fn set_reveal_budget(budget: Option<RevealBudget>) {
+    let mut accounts = ACCOUNTS.lock().unwrap();
+    let tag = Net::session_tag();
+    match budget {
+        Some(budget) => {
+            accounts.insert(
+                tag,
+                Account {
+                    budget,
+                    spent: 0,
+                    warned: false,
+                },
+            );
+        }
+        None => {
+            accounts.remove(&tag);
+        }
+    }
+}
+
+/// The current session's budget, if any.
+pub fn reveal_budget() -> Option<RevealBudget> {
+    let accounts = ACCOUNTS.lock().unwrap();
+    accounts.get(&Net::session_tag()).map(|a| a.budget)
+}
+
+/// How many shared values the current session has opened since its budget was set.
+///
+/// Openings are only counted while there is a budget.
+pub fn revealed() -> u64 {
+    let accounts = ACCOUNTS.lock().unwrap();
+    accounts.get(&Net::session_tag()).map_or(0, |a| a.spent)
+}
+
+/// Run `f` without charging its openings to the budget.
+///
+/// For protocols that open values masked by fresh randomness, which reveal nothing.
+pub fn exempt<T>(f: impl FnOnce() -> T) -> T {
+    struct Guard;
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            EXEMPT.with(|e| e.set(e.get() - 1));
+        }
+    }
+    EXEMPT.with(|e| e.set(e.get() + 1));
+    let _guard = Guard;
+    f()
+}
+
+/// Whether this thread is within [exempt].
+pub(crate) fn is_exempt() -> bool {
+    EXEMPT.with(|e| e.get()) > 0
+}
+
+/// Charge the opening of `n` shared values to the current session.
+pub(crate) fn charge(n: u64) {
+    if is_exempt() {
+        return;
+    }
+    let mut accounts = ACCOUNTS.lock().unwrap();
+    let account = match accounts.get_mut(&Net::session_tag()) {
+        Some(a) => a,
+        None => return,
+    };
+    let budget = account.budget;
+    let spent = account.spent + n;
+    if spent > budget.limit {
+        match budget.on_exceed {
+            OnExceed::Abort => {
+                drop(accounts);
+                panic!(
+                    "Reveal budget exceeded: opening {} more value(s) after {} of {}",
+                    n,
+                    spent - n,
+                    budget.limit
+                );
+            }
+            OnExceed::Warn if !account.warned => {
+                account.warned = true;
+                warn!(
+                    "Reveal budget exceeded: {} value(s) opened, of {}",
+                    spent, budget.limit
+                );
+            }
+            OnExceed::Warn => {}
+        }
+    }
+    account.spent = spent;
+}
@@ -1,58 +1,200 @@
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use digest::Digest;
-use rand::RngCore;
+use lazy_static::lazy_static;
 use sha2::Sha256;
 use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::sync::Mutex;
 
 use mpc_net::two as net_two;
 
-use mpc_net::MpcNet;
+use mpc_net::{MpcMultiNet, MpcNet};
+
+/// The default for [max_chunk].
+pub const DEFAULT_MAX_CHUNK: usize = 1 << 16;
+
+lazy_static! {
+    /// By session tag.
+    static ref MAX_CHUNKS: Mutex<HashMap<u64, usize>> = Mutex::new(HashMap::new());
+    /// By session tag.
+    static ref POINT_ENCODINGS: Mutex<HashMap<u64, PointEncoding>> = Mutex::new(HashMap::new());
+}
+
+/// How curve points are serialized, in messages and in outputs such as proofs.
+///
+/// A compressed point is half the size of an uncompressed one, but reading it takes a square
+/// root, and checking that it is in the prime-order subgroup. Uncompressed points are the faster
+/// choice on a LAN, and compressed ones on a WAN. Field elements serialize the same either way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointEncoding {
+    Compressed,
+    Uncompressed,
+}
+
+impl Default for PointEncoding {
+    fn default() -> Self {
+        PointEncoding::Compressed
+    }
+}
+
+impl FromStr for PointEncoding {
+    type Err = String;
+
+    /// Parse `compressed` or `uncompressed`.
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "compressed" => Ok(PointEncoding::Compressed),
+            "uncompressed" => Ok(PointEncoding::Uncompressed),
+            _ => Err(format!("unknown point encoding: {}", s)),
+        }
+    }
+}
+
+impl Display for PointEncoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PointEncoding::Compressed => write!(f, "compressed"),
+            PointEncoding::Uncompressed => write!(f, "uncompressed"),
+        }
+    }
+}
+
+impl PointEncoding {
+    /// Serialize `x` with this encoding.
+    pub fn serialize<T: CanonicalSerialize + ?Sized>(self, x: &T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        match self {
+            PointEncoding::Compressed => x.serialize(&mut bytes),
+            PointEncoding::Uncompressed => x.serialize_uncompressed(&mut bytes),
+        }
+        .unwrap();
+        bytes
+    }
+
+    /// Deserialize a `T` serialized with this encoding, checking it either way.
+    pub fn deserialize<T: CanonicalDeserialize>(
+        self,
+        bytes: &[u8],
+    ) -> Result<T, SerializationError> {
+        match self {
+            PointEncoding::Compressed => T::deserialize(bytes),
+            PointEncoding::Uncompressed => T::deserialize_uncompressed(bytes),
+        }
+    }
+}
+
+/// Serialize points with `encoding` in the current session, or with `None`, with the default
+/// ([PointEncoding::Compressed]). All parties must agree on it.
+pub fn set_point_encoding(encoding: Option<PointEncoding>) {
+    let mut encodings = POINT_ENCODINGS.lock().unwrap();
+    let tag = MpcMultiNet::session_tag();
+    match encoding {
+        Some(e) => {
+            encodings.insert(tag, e);
+        }
+        None => {
+            encodings.remove(&tag);
+        }
+    }
+}
+
+/// How the current session serializes points.
+pub fn point_encoding() -> PointEncoding {
+    let encodings = POINT_ENCODINGS.lock().unwrap();
+    encodings
+        .get(&MpcMultiNet::session_tag())
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Send vectors of more than `max` elements in messages of at most `max` each, in the current
+/// session, or with `None`, of at most [DEFAULT_MAX_CHUNK]. All parties must agree on it.
+pub fn set_max_chunk(max: Option<usize>) {
+    let mut chunks = MAX_CHUNKS.lock().unwrap();
+    let tag = MpcMultiNet::session_tag();
+    match max {
+        Some(max) => {
+            assert!(max > 0, "chunks must hold at least one element");
+            chunks.insert(tag, max);
+        }
+        None => {
+            chunks.remove(&tag);
+        }
+    }
+}
+
+/// The most elements the current session sends in one message of a vector.
+pub fn max_chunk() -> usize {
+    let chunks = MAX_CHUNKS.lock().unwrap();
+    chunks
+        .get(&MpcMultiNet::session_tag())
+        .copied()
+        .unwrap_or(DEFAULT_MAX_CHUNK)
+}
+
+/// `xs` in [max_chunk]-sized pieces; at least one, so that an empty vector is still a round.
+fn chunks<T>(xs: &[T]) -> impl Iterator<Item = &[T]> {
+    let max = max_chunk();
+    let n = (xs.len() + max - 1) / max;
+    (0..n.max(1)).map(move |i| &xs[(i * max).min(xs.len())..((i + 1) * max).min(xs.len())])
+}
+
+fn to_bytes<T: CanonicalSerialize + ?Sized>(x: &T) -> Vec<u8> {
+    point_encoding().serialize(x)
+}
+
+fn from_bytes<T: CanonicalDeserialize>(bytes: &[u8]) -> T {
+    point_encoding().deserialize(bytes).unwrap()
+}
 
 pub trait MpcSerNet: MpcNet {
     #[inline]
     fn broadcast<T: CanonicalDeserialize + CanonicalSerialize>(out: &T) -> Vec<T> {
-        let mut bytes_out = Vec::new();
-        out.serialize(&mut bytes_out).unwrap();
+        let bytes_out = to_bytes(out);
         let bytes_in = Self::broadcast_bytes(&bytes_out);
         bytes_in
             .into_iter()
-            .map(|b| T::deserialize(&b[..]).unwrap())
+            .map(|b| from_bytes(&b[..]))
             .collect()
     }
 
     #[inline]
     fn send_to_king<T: CanonicalDeserialize + CanonicalSerialize>(out: &T) -> Option<Vec<T>> {
-        let mut bytes_out = Vec::new();
-        out.serialize(&mut bytes_out).unwrap();
-        Self::send_bytes_to_king(&bytes_out).map(|bytes_in| {
+        Self::send_to(0, out)
+    }
+
+    #[inline]
+    fn recv_from_king<T: CanonicalDeserialize + CanonicalSerialize>(out: Option<Vec<T>>) -> T {
+        Self::recv_from(0, out)
+    }
+
+    #[inline]
+    fn send_to<T: CanonicalDeserialize + CanonicalSerialize>(king: usize, out: &T) -> Option<Vec<T>> {
+        let bytes_out = to_bytes(out);
+        Self::send_bytes_to(king, &bytes_out).map(|bytes_in| {
             bytes_in
                 .into_iter()
-                .map(|b| T::deserialize(&b[..]).unwrap())
+                .map(|b| from_bytes(&b[..]))
                 .collect()
         })
     }
 
     #[inline]
-    fn recv_from_king<T: CanonicalDeserialize + CanonicalSerialize>(out: Option<Vec<T>>) -> T {
-        let bytes_in = Self::recv_bytes_from_king(out.map(|outs| {
-            outs.iter()
-                .map(|out| {
-                    let mut bytes_out = Vec::new();
-                    out.serialize(&mut bytes_out).unwrap();
-                    bytes_out
-                })
-                .collect()
+    fn recv_from<T: CanonicalDeserialize + CanonicalSerialize>(king: usize, out: Option<Vec<T>>) -> T {
+        let bytes_in = Self::recv_bytes_from(king, out.map(|outs| {
+            outs.iter().map(|out| to_bytes(out)).collect()
         }));
-        T::deserialize(&bytes_in[..]).unwrap()
+        from_bytes(&bytes_in[..])
     }
 
     #[inline]
     fn atomic_broadcast<T: CanonicalDeserialize + CanonicalSerialize>(out: &T) -> Vec<T> {
-        let mut bytes_out = Vec::new();
-        out.serialize(&mut bytes_out).unwrap();
+        let mut bytes_out = to_bytes(out);
         let ser_len = bytes_out.len();
         bytes_out.resize(ser_len + COMMIT_RAND_BYTES, 0);
-        rand::thread_rng().fill_bytes(&mut bytes_out[ser_len..]);
+        crate::seed::fill_bytes(b"commit", &mut bytes_out[ser_len..]);
         let commitment = CommitHash::new().chain(&bytes_out).finalize();
         // exchange commitments
         let all_commits = Self::broadcast_bytes(&commitment[..]);
@@ -70,7 +212,7 @@ pub trait MpcSerNet: MpcNet {
         }
         all_data
             .into_iter()
-            .map(|d| T::deserialize(&d[..ser_len]).unwrap())
+            .map(|d| from_bytes(&d[..ser_len]))
             .collect()
     }
 
@@ -79,6 +221,127 @@ pub trait MpcSerNet: MpcNet {
         let king_response = Self::send_to_king(x).map(f);
         Self::recv_from_king(king_response)
     }
+
+    /// Like [MpcSerNet::broadcast], for vectors, which go in messages of at most [max_chunk]
+    /// elements. All parties must send vectors of the same length.
+    fn broadcast_vec<T: CanonicalDeserialize + CanonicalSerialize>(out: &[T]) -> Vec<Vec<T>> {
+        let mut all: Vec<Vec<T>> = (0..Self::n_parties()).map(|_| Vec::new()).collect();
+        for chunk in chunks(out) {
+            let bytes_in = Self::broadcast_bytes(&to_bytes(chunk));
+            for (a, b) in all.iter_mut().zip(bytes_in) {
+                a.extend(from_bytes::<Vec<T>>(&b[..]));
+            }
+        }
+        all
+    }
+
+    /// Like [MpcSerNet::send_to], for vectors, which go in messages of at most [max_chunk]
+    /// elements. All parties must send vectors of the same length.
+    ///
+    /// The chunks go in one [round](MpcNet::round), since only the king waits for them.
+    fn send_vec_to<T: CanonicalDeserialize + CanonicalSerialize>(
+        king: usize,
+        out: &[T],
+    ) -> Option<Vec<Vec<T>>> {
+        Self::round(|| {
+            let mut all: Option<Vec<Vec<T>>> = None;
+            for chunk in chunks(out) {
+                if let Some(bytes_in) = Self::send_bytes_to(king, &to_bytes(chunk)) {
+                    let all =
+                        all.get_or_insert_with(|| bytes_in.iter().map(|_| Vec::new()).collect());
+                    for (a, b) in all.iter_mut().zip(bytes_in) {
+                        a.extend(from_bytes::<Vec<T>>(&b[..]));
+                    }
+                }
+            }
+            all
+        })
+    }
+
+    /// Like [MpcSerNet::recv_from], for vectors, which go in messages of at most [max_chunk]
+    /// elements. The king must send every party a vector of the same length, and the others
+    /// learn it from the last, short, message.
+    ///
+    /// The king sends the chunks in one [round](MpcNet::round).
+    fn recv_vec_from<T: CanonicalDeserialize + CanonicalSerialize>(
+        king: usize,
+        out: Option<Vec<Vec<T>>>,
+    ) -> Vec<T> {
+        let max = max_chunk();
+        let mut mine = Vec::new();
+        match out {
+            Some(outs) => Self::round(|| {
+                let len = outs[0].len();
+                let mut start = 0;
+                loop {
+                    let end = (start + max).min(len);
+                    let bytes_out = outs.iter().map(|o| to_bytes(&o[start..end])).collect();
+                    let bytes_in = Self::recv_bytes_from(king, Some(bytes_out));
+                    mine.extend(from_bytes::<Vec<T>>(&bytes_in[..]));
+                    // a full last chunk is followed by an empty one, to mark the end
+                    if end - start < max {
+                        break;
+                    }
+                    start = end;
+                }
+            }),
+            None => loop {
+                let bytes_in = Self::recv_bytes_from(king, None);
+                let chunk = from_bytes::<Vec<T>>(&bytes_in[..]);
+                let last = chunk.len() < max;
+                mine.extend(chunk);
+                if last {
+                    break;
+                }
+            },
+        }
+        mine
+    }
+
+    /// Like [MpcSerNet::atomic_broadcast], for vectors, which go in messages of at most
+    /// [max_chunk] elements. The commitment covers all of them.
+    fn atomic_broadcast_vec<T: CanonicalDeserialize + CanonicalSerialize>(
+        out: &[T],
+    ) -> Vec<Vec<T>> {
+        let chunks_out: Vec<Vec<u8>> = chunks(out).map(|c| to_bytes(c)).collect();
+        let mut rand = vec![0u8; COMMIT_RAND_BYTES];
+        crate::seed::fill_bytes(b"commit", &mut rand);
+        let commit = |chunks: &[Vec<u8>], rand: &[u8]| {
+            let mut h = CommitHash::new();
+            for c in chunks {
+                h.update(c);
+            }
+            h.chain(rand).finalize()
+        };
+        let commitment = commit(&chunks_out, &rand);
+        // exchange commitments
+        let all_commits = Self::broadcast_bytes(&commitment[..]);
+        // exchange the data, then the randomness
+        let n = Self::n_parties();
+        let mut all_chunks: Vec<Vec<Vec<u8>>> = vec![Vec::new(); n];
+        for c in &chunks_out {
+            for (a, b) in all_chunks.iter_mut().zip(Self::broadcast_bytes(c)) {
+                a.push(b);
+            }
+        }
+        let all_rand = Self::broadcast_bytes(&rand);
+        let self_id = Self::party_id();
+        for i in 0..n {
+            if i != self_id {
+                // check other commitment
+                assert_eq!(&all_commits[i][..], &commit(&all_chunks[i], &all_rand[i])[..]);
+            }
+        }
+        all_chunks
+            .into_iter()
+            .map(|chunks| {
+                chunks
+                    .iter()
+                    .flat_map(|c| from_bytes::<Vec<T>>(&c[..]))
+                    .collect()
+            })
+            .collect()
+    }
 }
 
 impl<N: MpcNet> MpcSerNet for N {}
@@ -93,10 +356,9 @@ type CommitHash = Sha256;
 
 #[inline]
 pub fn exchange<F: CanonicalSerialize + CanonicalDeserialize>(f: &F) -> F {
-    let mut bytes_out = Vec::new();
-    f.serialize(&mut bytes_out).unwrap();
+    let bytes_out = to_bytes(f);
     let bytes_in = net_two::exchange_bytes(&bytes_out).unwrap();
-    F::deserialize(&bytes_in[..]).unwrap()
+    from_bytes(&bytes_in[..])
 }
 
 #[inline]
@@ -105,11 +367,10 @@ pub fn exchange<F: CanonicalSerialize + CanonicalDeserialize>(f: &F) -> F {
 /// Ensures that if both parties get a value, each party chose its value independently of the
 /// other.
 pub fn atomic_exchange<F: CanonicalSerialize + CanonicalDeserialize>(f: &F) -> F {
-    let mut bytes_out = Vec::new();
-    f.serialize(&mut bytes_out).unwrap();
+    let mut bytes_out = to_bytes(f);
     let ser_len = bytes_out.len();
     bytes_out.resize(ser_len + COMMIT_RAND_BYTES, 0);
-    rand::thread_rng().fill_bytes(&mut bytes_out[ser_len..]);
+    crate::seed::fill_bytes(b"commit", &mut bytes_out[ser_len..]);
     let commitment = CommitHash::new().chain(&bytes_out).finalize();
     // exchange commitments
     let other_commitment = net_two::exchange_bytes(&commitment[..]).unwrap();
@@ -121,7 +382,7 @@ pub fn atomic_exchange<F: CanonicalSerialize + CanonicalDeserialize>(f: &F) -> F
         &CommitHash::new().chain(&other_bytes).finalize()[..]
     );
     // parse data
-    F::deserialize(&other_bytes[..ser_len]).unwrap()
+    from_bytes(&other_bytes[..ser_len])
 }
 
 #[inline]
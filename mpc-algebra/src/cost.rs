@@ -0,0 +1,63 @@
+//! Counts of what the protocols consume, beyond the bytes that the network's
+//! [Stats](mpc_net::Stats) count.
+//!
+//! Every multiplication triple drawn from the dealer's sources (for field, group and pairing
+//! products), or from another crate's source that reports it with [drew_triples], is counted, and
+//! so is every shared value opened through [Reveal::reveal](crate::Reveal::reveal) or
+//! [MpcWire::publicize](mpc_trait::MpcWire::publicize), including the masked openings inside
+//! protocols, which [budget](crate::budget) does not charge. Schemes that multiply without
+//! triples, as GSZ does, draw none.
+//!
+//! The counts are per thread. A session's triples are drawn, and its values opened, on the thread
+//! that runs it, so the difference of two [costs] there is what the code between them cost.
+use std::cell::Cell;
+
+/// Triples drawn and values opened, by this thread, since it started or was [reset].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Costs {
+    pub triples: u64,
+    pub openings: u64,
+}
+
+impl Costs {
+    /// What was consumed after `earlier`.
+    pub fn since(self, earlier: Costs) -> Costs {
+        Costs {
+            triples: self.triples.saturating_sub(earlier.triples),
+            openings: self.openings.saturating_sub(earlier.openings),
+        }
+    }
+}
+
+thread_local! {
+    static COSTS: Cell<Costs> = Cell::new(Costs::default());
+}
+
+/// This thread's counts so far.
+pub fn costs() -> Costs {
+    COSTS.with(|c| c.get())
+}
+
+/// Start this thread's counts again from zero.
+pub fn reset() {
+    COSTS.with(|c| c.set(Costs::default()));
+}
+
+fn add(triples: u64, openings: u64) {
+    COSTS.with(|c| {
+        let mut costs = c.get();
+        costs.triples += triples;
+        costs.openings += openings;
+        c.set(costs);
+    });
+}
+
+/// Count `n` triples drawn.
+pub fn drew_triples(n: u64) {
+    add(n, 0);
+}
+
+/// Count `n` values opened.
+pub(crate) fn opened(n: u64) {
+    add(0, n);
+}
@@ -0,0 +1,53 @@
+//! Comparisons of secret values that do not branch on them.
+//!
+//! The checks of an opening (a MAC check, a consistency check of the parties' shares) compare
+//! values that are secret until the check passes. Comparing them with `==` or `is_zero` stops at
+//! the first differing limb, so the time a check takes leaks where the values differ. These
+//! helpers compare canonical serializations with [subtle] instead, and a check of a batch folds
+//! all of its comparisons into one [Choice] before it branches, once, on the verdict.
+//!
+//! Serializing is as constant-time as arkworks makes it: field elements serialize their limbs
+//! without branching, but curve points are normalized first. The verdict itself is public, as is
+//! which check failed once it has.
+use ark_ff::{One, Zero};
+use ark_serialize::CanonicalSerialize;
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
+
+fn to_bytes<T: CanonicalSerialize + ?Sized>(x: &T) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(x.serialized_size());
+    x.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+/// Whether `a` and `b` are equal byte strings. Only their lengths may be compared early.
+pub fn bytes_eq(a: &[u8], b: &[u8]) -> Choice {
+    a.ct_eq(b)
+}
+
+/// Whether `a == b`, comparing their serializations.
+pub fn eq<T: CanonicalSerialize + ?Sized>(a: &T, b: &T) -> Choice {
+    let (mut a, mut b) = (to_bytes(a), to_bytes(b));
+    let equal = bytes_eq(&a, &b);
+    a.zeroize();
+    b.zeroize();
+    equal
+}
+
+/// Whether `x` is zero.
+pub fn is_zero<T: CanonicalSerialize + Zero>(x: &T) -> Choice {
+    eq(x, &T::zero())
+}
+
+/// Whether `x` is one.
+pub fn is_one<T: CanonicalSerialize + One>(x: &T) -> Choice {
+    eq(x, &T::one())
+}
+
+/// Whether every one of `choices` holds, looking at each of them.
+pub fn all(choices: impl IntoIterator<Item = Choice>) -> bool {
+    choices
+        .into_iter()
+        .fold(Choice::from(1), |acc, c| acc & c)
+        .into()
+}
@@ -0,0 +1,85 @@
+//! Compacting vectors with repeated entries.
+//!
+//! Circuits with heavy wire reuse feed the same shared value into MSMs and openings many times
+//! over. Compacting such a vector to its distinct entries shrinks the work, and the result can be
+//! expanded back afterwards.
+//!
+//! There are two ways to decide what counts as a repeat:
+//!
+//! * By share value ([Compacted::by_value]). Each party compacts differently (e.g., under additive
+//!   sharing, every non-king share of a public value is zero), so this is only sound for local,
+//!   linear computations, like an MSM with public bases.
+//! * By a key all parties agree on, such as a wire index ([Compacted::by_key]). Every party
+//!   compacts the same way, so this is also sound before an opening or any other exchange.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::Zero;
+
+/// A vector reduced to its distinct entries, with the position of each original entry.
+#[derive(Clone, Debug)]
+pub struct Compacted<T> {
+    /// The distinct entries, in order of first appearance.
+    pub unique: Vec<T>,
+    /// `unique[index[i]]` is the original `i`th entry.
+    pub index: Vec<usize>,
+}
+
+impl<T: Clone> Compacted<T> {
+    /// Compact `xs`, treating entries with equal keys as repeats.
+    pub fn by_key<K: Hash + Eq>(xs: &[T], key: impl Fn(&T) -> K) -> Self {
+        let mut positions: HashMap<K, usize> = HashMap::with_capacity(xs.len());
+        let mut unique = Vec::new();
+        let index = xs
+            .iter()
+            .map(|x| {
+                *positions.entry(key(x)).or_insert_with(|| {
+                    unique.push(x.clone());
+                    unique.len() - 1
+                })
+            })
+            .collect();
+        Self { unique, index }
+    }
+
+    /// Compact `xs`, treating equal entries as repeats. See the module docs for when this is sound.
+    pub fn by_value(xs: &[T]) -> Self
+    where
+        T: Hash + Eq,
+    {
+        Self::by_key(xs, |x| x.clone())
+    }
+
+    /// The number of original entries.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Whether there were no entries at all.
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Whether at least `1/denominator` of the entries were repeats.
+    pub fn saves_at_least(&self, denominator: usize) -> bool {
+        (self.len() - self.unique.len()) * denominator >= self.len()
+    }
+
+    /// Given the results for the unique entries, the results for the original ones.
+    pub fn expand<U: Clone>(&self, unique_results: &[U]) -> Vec<U> {
+        assert_eq!(unique_results.len(), self.unique.len());
+        self.index.iter().map(|&i| unique_results[i].clone()).collect()
+    }
+
+    /// Sum the `weights` that go with repeated entries, so that
+    /// `sum_i weights[i] * xs[i] = sum_j summed[j] * unique[j]`.
+    pub fn sum_bases<G: AffineCurve>(&self, weights: &[G]) -> Vec<G> {
+        assert_eq!(weights.len(), self.len());
+        let mut sums = vec![G::Projective::zero(); self.unique.len()];
+        for (w, &i) in weights.iter().zip(&self.index) {
+            sums[i].add_assign_mixed(w);
+        }
+        G::Projective::batch_normalization_into_affine(&sums)
+    }
+}
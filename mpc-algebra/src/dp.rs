@@ -0,0 +1,239 @@
+//! Differentially private release of shared values.
+//!
+//! The parties jointly sample discrete noise as shared values, add it to a shared aggregate, and
+//! only then open the sum (or use it as a public input). No party learns the noise, so each
+//! release is differentially private with respect to the inputs behind the aggregate.
+//!
+//! The noise is two-sided geometric (the discrete Laplace distribution): the difference of two
+//! geometric variables. A geometric variable's binary digits are independent biased bits
+//! (["Our Data, Ourselves"](https://www.iacr.org/archive/eurocrypt2006/40040493/40040493.pdf),
+//! section 5.2), so it is assembled from biased bits, and each biased bit is a comparison of
+//! uniformly random shared bits against a public threshold.
+//!
+//! The randomness is [MpcField]'s [UniformRand], so it is only as good as the share type's: for
+//! GSZ shares, that means activating PRSS.
+use ark_ff::{Field, One, PrimeField, SquareRootField, UniformRand, Zero};
+use rand::Rng;
+
+use crate::budget;
+use crate::share::field::FieldShare;
+use crate::share::randomness::bit_from_square;
+use crate::wire::field::{MpcField, MpcMulScratch};
+
+/// Default number of bits to which the bias of each noise digit is rounded.
+pub const DEFAULT_PRECISION: u32 = 40;
+
+/// `n` shared bits, each uniformly random. Every party must call this.
+///
+/// Each bit is derived from a random shared `r`, by [bit_from_square]: `r^2` is opened, which
+/// reveals nothing about the sign of `r`. Costs one multiplication and one opening per bit, all
+/// batched.
+pub fn random_bits<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+    n: usize,
+    rng: &mut R,
+) -> Vec<MpcField<F, S>> {
+    let mut bits = Vec::with_capacity(n);
+    while bits.len() < n {
+        let rs: Vec<MpcField<F, S>> = (bits.len()..n).map(|_| MpcField::rand(rng)).collect();
+        let mut squares = rs.clone();
+        MpcField::batch_product_in_place(&mut squares, &rs);
+        // r^2 hides r's sign, which is all the bit depends on, so it is not charged
+        let squares = budget::exempt(|| MpcField::open_batch(squares));
+        // an r = 0 is drawn again
+        bits.extend(rs.into_iter().zip(squares).filter_map(|(r, square)| {
+            let (a, b) = bit_from_square(&square)?;
+            Some(r * MpcField::Public(a) + MpcField::Public(b))
+        }));
+    }
+    bits
+}
+
+/// Shared bits, the `i`th of which is `1` with probability `thresholds[i] / 2^precision`.
+///
+/// Each is the comparison `u < thresholds[i]` of a random `precision`-bit shared `u` against the
+/// public threshold, so this costs `precision - 1` rounds of multiplications.
+pub fn biased_bits<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+    thresholds: &[u64],
+    precision: u32,
+    rng: &mut R,
+) -> Vec<MpcField<F, S>> {
+    assert!(precision >= 1 && precision < 64, "precision out of range");
+    assert!(
+        thresholds.iter().all(|t| *t <= 1 << precision),
+        "threshold exceeds 2^precision"
+    );
+    let m = precision as usize;
+    let n = thresholds.len();
+    let one = MpcField::<F, S>::one();
+    // digit j of each threshold, most significant first
+    let digit = |t: u64, j: usize| (t >> (m - 1 - j)) & 1 == 1;
+    // u[j][i] is digit j of the ith random number
+    let u: Vec<Vec<MpcField<F, S>>> = {
+        let mut all = random_bits(n * m, rng).into_iter();
+        (0..m).map(|_| all.by_ref().take(n).collect()).collect()
+    };
+    // u < t iff at the first digit where they differ, t has a one and u a zero. Those events are
+    // disjoint, so the comparison is their sum. `prefix` is whether all digits so far agree.
+    let mut out: Vec<MpcField<F, S>> = thresholds
+        .iter()
+        .map(|t| {
+            if *t == 1 << precision {
+                one
+            } else {
+                MpcField::zero()
+            }
+        })
+        .collect();
+    let mut prefix: Option<Vec<MpcField<F, S>>> = None;
+    let mut scratch = MpcMulScratch::default();
+    for j in 0..m {
+        let agrees: Vec<MpcField<F, S>> = thresholds
+            .iter()
+            .zip(&u[j])
+            .map(|(t, u)| if digit(*t, j) { *u } else { one - *u })
+            .collect();
+        let below: Vec<MpcField<F, S>> = u[j].iter().map(|u| one - *u).collect();
+        let first_below = match prefix.take() {
+            None => {
+                prefix = Some(agrees);
+                below
+            }
+            Some(p) => {
+                // both products of this digit in one round
+                let mut products = below;
+                products.extend(agrees);
+                let mut ps = p.clone();
+                ps.extend(p);
+                MpcField::batch_product_in_place_with(&mut products, &ps, &mut scratch);
+                prefix = Some(products.split_off(n));
+                products
+            }
+        };
+        for ((o, t), b) in out.iter_mut().zip(thresholds).zip(first_below) {
+            if *t < 1 << precision && digit(*t, j) {
+                *o += b;
+            }
+        }
+    }
+    out
+}
+
+/// Two-sided geometric noise: `Pr[z] ∝ alpha^|z|`.
+///
+/// Adding it to a value of sensitivity `Δ` is `ε`-differentially private for
+/// `alpha = exp(-ε / Δ)`. Each side is truncated to `magnitude_bits` bits, chosen so that the
+/// truncated tail has mass below `2^-precision`, and the bias of each digit is rounded to
+/// `precision` bits.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiscreteLaplace {
+    alpha: f64,
+    precision: u32,
+}
+
+impl DiscreteLaplace {
+    /// Noise with ratio `alpha` between the probabilities of adjacent values.
+    pub fn new(alpha: f64) -> Self {
+        assert!(alpha > 0.0 && alpha < 1.0, "alpha must be in (0, 1)");
+        Self {
+            alpha,
+            precision: DEFAULT_PRECISION,
+        }
+    }
+
+    /// Noise for `epsilon`-differential privacy of a value with the given `sensitivity`.
+    pub fn for_privacy(epsilon: f64, sensitivity: u64) -> Self {
+        assert!(epsilon > 0.0 && sensitivity > 0);
+        Self::new((-epsilon / sensitivity as f64).exp())
+    }
+
+    /// Round the bias of each digit to `precision` bits, instead of [DEFAULT_PRECISION].
+    pub fn with_precision(self, precision: u32) -> Self {
+        assert!(
+            precision >= 1 && precision <= 52,
+            "precision must be at most the f64 mantissa"
+        );
+        Self { precision, ..self }
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+
+    /// The number of bits of each side's magnitude: the least `k` with
+    /// `alpha^(2^k) <= 2^-precision`.
+    pub fn magnitude_bits(&self) -> u32 {
+        let needed = self.precision as f64 * std::f64::consts::LN_2 / -self.alpha.ln();
+        (needed.log2().ceil().max(0.0) as u32).min(62)
+    }
+
+    /// The thresholds of the digits of one side, least significant first: digit `j` is one with
+    /// probability `alpha^(2^j) / (1 + alpha^(2^j))`.
+    fn thresholds(&self) -> Vec<u64> {
+        let scale = (1u64 << self.precision) as f64;
+        (0..self.magnitude_bits())
+            .map(|j| {
+                let a = self.alpha.powf((1u64 << j) as f64);
+                (a / (1.0 + a) * scale).round() as u64
+            })
+            .collect()
+    }
+
+    /// `count` shared samples.
+    ///
+    /// Negative samples are represented by their negations in the field.
+    pub fn sample<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+        &self,
+        count: usize,
+        rng: &mut R,
+    ) -> Vec<MpcField<F, S>> {
+        let digit_thresholds = self.thresholds();
+        let k = digit_thresholds.len();
+        if k == 0 {
+            return vec![MpcField::zero(); count];
+        }
+        // two sides for each sample, k digits for each side
+        let thresholds: Vec<u64> = (0..2 * count)
+            .flat_map(|_| digit_thresholds.iter().cloned())
+            .collect();
+        let digits = biased_bits::<F, S, R>(&thresholds, self.precision, rng);
+        let sides: Vec<MpcField<F, S>> = digits
+            .chunks(k)
+            .map(|ds| {
+                let mut weight = F::one();
+                let mut side = MpcField::zero();
+                for d in ds {
+                    side += *d * MpcField::Public(weight);
+                    weight.double_in_place();
+                }
+                side
+            })
+            .collect();
+        sides.chunks(2).map(|s| s[0] - s[1]).collect()
+    }
+
+    /// Add independent noise to each of `values`.
+    pub fn add_noise<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+        &self,
+        values: &mut [MpcField<F, S>],
+        rng: &mut R,
+    ) {
+        let noise = self.sample::<F, S, R>(values.len(), rng);
+        for (v, n) in values.iter_mut().zip(noise) {
+            *v += n;
+        }
+    }
+
+    /// Add noise to each of `values`, then open them.
+    pub fn noisy_reveal<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+        &self,
+        mut values: Vec<MpcField<F, S>>,
+        rng: &mut R,
+    ) -> Vec<F> {
+        self.add_noise(&mut values, rng);
+        MpcField::open_batch(values)
+    }
+}
@@ -0,0 +1,305 @@
+//! Fixed-point encoding of real numbers as field elements.
+//!
+//! A real `x` is encoded as the integer `round(x * 2^frac_bits)`, with negative integers
+//! represented by their negations in the field. Sums of encodings encode sums. A product of two
+//! encodings has `2 * frac_bits` fractional bits, so decode it with a [FixedPoint] of twice the
+//! precision.
+//!
+//! Encoding is exact up to the final rounding, which follows [Rounding]: floats are decomposed
+//! into their exact binary values, and decimal strings are parsed as exact decimals, so that
+//! `"0.1"` encodes as the rounding of one tenth rather than of the float nearest to it.
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use rand::Rng;
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+use crate::reveal::Reveal;
+use crate::share::field::FieldShare;
+use crate::wire::field::MpcField;
+
+/// How to round a real number that lies between two encodable values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// To the nearest, with ties to the even one, like IEEE arithmetic.
+    NearestEven,
+    /// Toward zero, like truncation.
+    TowardZero,
+    /// Toward negative infinity.
+    Floor,
+    /// Toward positive infinity.
+    Ceil,
+}
+
+/// Why a number could not be encoded or decoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FixedPointError {
+    /// NaN or an infinity.
+    NotFinite,
+    /// The scaled magnitude does not fit in 128 bits, or in half the field.
+    Overflow,
+    /// A malformed decimal string.
+    Syntax(String),
+}
+
+impl Display for FixedPointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FixedPointError::NotFinite => write!(f, "not a finite number"),
+            FixedPointError::Overflow => write!(f, "out of fixed-point range"),
+            FixedPointError::Syntax(s) => write!(f, "malformed decimal: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for FixedPointError {}
+
+/// A fixed-point encoding with `frac_bits` fractional bits.
+///
+/// A value is encodable if its scaled magnitude fits in 128 bits and is below half the field size,
+/// so that the sign survives decoding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedPoint {
+    frac_bits: u32,
+    rounding: Rounding,
+}
+
+impl FixedPoint {
+    /// An encoding with `frac_bits` fractional bits, rounding to nearest.
+    pub fn new(frac_bits: u32) -> Self {
+        assert!(frac_bits <= 64, "at most 64 fractional bits");
+        Self {
+            frac_bits,
+            rounding: Rounding::NearestEven,
+        }
+    }
+
+    pub fn with_rounding(self, rounding: Rounding) -> Self {
+        Self { rounding, ..self }
+    }
+
+    pub fn frac_bits(&self) -> u32 {
+        self.frac_bits
+    }
+
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    /// The distance between adjacent encodable values.
+    pub fn resolution(&self) -> f64 {
+        0.5f64.powi(self.frac_bits as i32)
+    }
+
+    /// Round `q + r/d` per [Self::rounding], for `0 <= r < d`. `exact` is whether `r = 0`, and
+    /// `half` compares `r` with `d/2`.
+    fn round(&self, negative: bool, q: u128, exact: bool, half: Ordering) -> Option<u128> {
+        if exact {
+            return Some(q);
+        }
+        let up = match self.rounding {
+            Rounding::NearestEven => {
+                half == Ordering::Greater || (half == Ordering::Equal && q & 1 == 1)
+            }
+            Rounding::TowardZero => false,
+            Rounding::Floor => negative,
+            Rounding::Ceil => !negative,
+        };
+        if up {
+            q.checked_add(1)
+        } else {
+            Some(q)
+        }
+    }
+
+    /// `±magnitude`, if it is in range.
+    fn to_field<F: PrimeField>(negative: bool, magnitude: u128) -> Result<F, FixedPointError> {
+        let bits = 128 - magnitude.leading_zeros();
+        if bits >= F::Params::CAPACITY {
+            return Err(FixedPointError::Overflow);
+        }
+        let x = F::from(magnitude);
+        Ok(if negative { -x } else { x })
+    }
+
+    /// Encode `x`.
+    pub fn encode_f64<F: PrimeField>(&self, x: f64) -> Result<F, FixedPointError> {
+        if !x.is_finite() {
+            return Err(FixedPointError::NotFinite);
+        }
+        // x = ±m * 2^e exactly
+        let bits = x.to_bits();
+        let negative = bits >> 63 == 1;
+        let biased_exp = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = bits & ((1 << 52) - 1);
+        let (m, e) = if biased_exp == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | (1 << 52), biased_exp - 1075)
+        };
+        let shift = e + self.frac_bits as i32;
+        let magnitude = if shift >= 0 {
+            if 64 - m.leading_zeros() as i32 + shift > 128 {
+                return Err(FixedPointError::Overflow);
+            }
+            Some((m as u128) << shift)
+        } else if shift > -64 {
+            let s = -shift as u32;
+            let r = m & ((1 << s) - 1);
+            self.round(negative, (m >> s) as u128, r == 0, r.cmp(&(1 << (s - 1))))
+        } else {
+            // m < 2^53, so what is shifted out is below half
+            self.round(negative, 0, m == 0, Ordering::Less)
+        };
+        Self::to_field(negative, magnitude.ok_or(FixedPointError::Overflow)?)
+    }
+
+    /// Encode the decimal number `s`, such as `-12.5`, `.75`, or `6.02e23`.
+    ///
+    /// At most 38 significant digits are kept exactly; any beyond that are an error unless zero.
+    pub fn encode_decimal<F: PrimeField>(&self, s: &str) -> Result<F, FixedPointError> {
+        let syntax = || FixedPointError::Syntax(s.to_owned());
+        let t = s.trim();
+        let (negative, t) = match t.as_bytes().first() {
+            Some(b'-') => (true, &t[1..]),
+            Some(b'+') => (false, &t[1..]),
+            _ => (false, t),
+        };
+        let (mantissa, exp) = match t.find(|c| c == 'e' || c == 'E') {
+            Some(i) => (&t[..i], t[i + 1..].parse::<i32>().map_err(|_| syntax())?),
+            None => (t, 0),
+        };
+        let (int, frac) = match mantissa.find('.') {
+            Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+            None => (mantissa, ""),
+        };
+        if int.len() + frac.len() == 0
+            || !int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit())
+        {
+            return Err(syntax());
+        }
+        let frac = frac.trim_end_matches('0');
+        // s = ±n * 10^k
+        let mut n: u128 = 0;
+        for c in int.chars().chain(frac.chars()) {
+            n = n
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(c.to_digit(10).unwrap() as u128))
+                .ok_or(FixedPointError::Overflow)?;
+        }
+        let k = exp
+            .checked_sub(frac.len() as i32)
+            .ok_or(FixedPointError::Overflow)?;
+        let magnitude = if n == 0 {
+            Some(0)
+        } else if k >= 0 {
+            10u128
+                .checked_pow(k as u32)
+                .and_then(|p| n.checked_mul(p))
+                .filter(|n| n.leading_zeros() >= self.frac_bits)
+                .map(|n| n << self.frac_bits)
+        } else {
+            if n.leading_zeros() < self.frac_bits {
+                return Err(FixedPointError::Overflow);
+            }
+            let n = n << self.frac_bits;
+            match 10u128.checked_pow(k.unsigned_abs()) {
+                Some(d) => {
+                    let r = n % d;
+                    self.round(negative, n / d, r == 0, r.cmp(&(d - r)))
+                }
+                // d > 10^38 > 2 n
+                None => self.round(negative, 0, false, Ordering::Less),
+            }
+        };
+        Self::to_field(negative, magnitude.ok_or(FixedPointError::Overflow)?)
+    }
+
+    /// Split `x` into its sign and magnitude, reading the upper half of the field as negative.
+    fn signed<F: PrimeField>(x: F) -> (bool, F::BigInt) {
+        if x.into_repr() > F::modulus_minus_one_div_two() {
+            (true, (-x).into_repr())
+        } else {
+            (false, x.into_repr())
+        }
+    }
+
+    /// The magnitude, if it fits in 128 bits.
+    fn small<B: BigInteger>(magnitude: &B) -> Option<u128> {
+        let limbs = magnitude.as_ref();
+        if limbs.iter().skip(2).any(|l| *l != 0) {
+            return None;
+        }
+        Some(limbs[0] as u128 | (limbs.get(1).cloned().unwrap_or(0) as u128) << 64)
+    }
+
+    /// Decode `x` to the nearest float (with ties to even, if the magnitude fits in 128 bits).
+    pub fn decode_f64<F: PrimeField>(&self, x: F) -> f64 {
+        let (negative, magnitude) = Self::signed(x);
+        let m = match Self::small(&magnitude) {
+            Some(m) => m as f64,
+            None => magnitude
+                .as_ref()
+                .iter()
+                .rev()
+                .fold(0.0, |acc, l| acc * 2f64.powi(64) + *l as f64),
+        };
+        let y = m * self.resolution();
+        if negative {
+            -y
+        } else {
+            y
+        }
+    }
+
+    /// Decode `x` exactly, as a decimal with no trailing zeros.
+    ///
+    /// Fails if the magnitude does not fit in 128 bits, which is outside the encodable range.
+    pub fn decode_decimal<F: PrimeField>(&self, x: F) -> Result<String, FixedPointError> {
+        let (negative, magnitude) = Self::signed(x);
+        let m = Self::small(&magnitude).ok_or(FixedPointError::Overflow)?;
+        let mut out = if negative && m != 0 {
+            String::from("-")
+        } else {
+            String::new()
+        };
+        let (int, mut frac) = match self.frac_bits {
+            0 => (m, 0),
+            b => (m >> b, m & ((1 << b) - 1)),
+        };
+        out.push_str(&int.to_string());
+        if frac != 0 {
+            out.push('.');
+            // each digit is exact, since 2^-b has b decimal digits
+            while frac != 0 {
+                frac *= 10;
+                out.push(char::from(b'0' + (frac >> self.frac_bits) as u8));
+                frac &= (1 << self.frac_bits) - 1;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Have the king encode and share `xs`.
+    ///
+    /// The other parties' `xs` are ignored, but must have the same length as the king's.
+    pub fn king_share_f64s<F: PrimeField, S: FieldShare<F>, R: Rng>(
+        &self,
+        xs: &[f64],
+        rng: &mut R,
+    ) -> Result<Vec<MpcField<F, S>>, FixedPointError> {
+        let encoded = xs
+            .iter()
+            .map(|x| self.encode_f64(*x))
+            .collect::<Result<Vec<F>, _>>()?;
+        Ok(MpcField::king_share_batch(encoded, rng))
+    }
+
+    /// Open `xs` and decode them.
+    pub fn reveal_f64s<F: PrimeField, S: FieldShare<F>>(
+        &self,
+        xs: Vec<MpcField<F, S>>,
+    ) -> Vec<f64> {
+        MpcField::open_batch(xs)
+            .into_iter().map(|x| self.decode_f64(x)).collect()
+    }
+}
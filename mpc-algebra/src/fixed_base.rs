@@ -0,0 +1,138 @@
+//! Multi-scalar multiplication over fixed bases, with precomputed tables.
+//!
+//! A prover multiplies the same bases (those of its proving key) by new scalars in every proof.
+//! A [FixedBaseTable] stores, for each base `g`, the points `g, 2^w g, 2^{2w} g, ...` for a window
+//! width `w`. An MSM then splits each scalar into `w`-bit digits and sorts the matching points into
+//! buckets by digit: it needs one addition per base and digit, and no doublings at all, at the cost
+//! of about `256 / w` stored points per base. Wider windows store fewer points, but cost `2^{w+1}`
+//! more additions to sum the buckets; [default_width] balances the two.
+//!
+//! [FixedBaseMsm] lifts this to the MPC curves: the bases must be public, and the scalars may be
+//! shared, in which case each party runs the MSM on its own shares.
+use ark_ec::models::{
+    short_weierstrass_jacobian as sw, twisted_edwards_extended as te, SWModelParameters,
+    TEModelParameters,
+};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{FpParameters, PrimeField, Zero};
+
+/// Points to multiply a fixed list of bases by, with windows of a fixed width.
+#[derive(Clone, Debug)]
+pub struct FixedBaseTable<G: AffineCurve> {
+    width: usize,
+    /// Digits per scalar.
+    windows: usize,
+    /// For each base `g`, `g, 2^w g, 2^{2w} g, ...`.
+    shifted: Vec<G>,
+}
+
+/// A window width suited to `n` bases: about `log2(n)`.
+pub fn default_width(n: usize) -> usize {
+    let log = (usize::BITS - n.leading_zeros()) as usize;
+    log.max(4).min(16)
+}
+
+impl<G: AffineCurve> FixedBaseTable<G> {
+    /// Tables for `bases`, with windows of `width` bits, between 1 and 24.
+    pub fn new(bases: &[G], width: usize) -> Self {
+        assert!(
+            width >= 1 && width <= 24,
+            "window width {} not in 1..=24",
+            width
+        );
+        let bits = <G::ScalarField as PrimeField>::Params::MODULUS_BITS as usize;
+        let windows = (bits + width - 1) / width;
+        let mut shifted = Vec::with_capacity(bases.len() * windows);
+        for b in bases {
+            let mut g = b.into_projective();
+            for _ in 0..windows {
+                shifted.push(g);
+                for _ in 0..width {
+                    g.double_in_place();
+                }
+            }
+        }
+        Self {
+            width,
+            windows,
+            shifted: G::Projective::batch_normalization_into_affine(&shifted),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of bases.
+    pub fn len(&self) -> usize {
+        self.shifted.len() / self.windows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shifted.is_empty()
+    }
+
+    /// `\sum_i s_i g_i`, over the first `scalars.len()` bases (or all of them, if there are fewer).
+    pub fn msm(&self, scalars: &[G::ScalarField]) -> G::Projective {
+        let mut buckets = vec![G::Projective::zero(); (1 << self.width) - 1];
+        for (s, points) in scalars.iter().zip(self.shifted.chunks(self.windows)) {
+            let repr = s.into_repr();
+            let limbs = repr.as_ref();
+            for (k, p) in points.iter().enumerate() {
+                let d = digit(limbs, k * self.width, self.width);
+                if d != 0 {
+                    buckets[d - 1].add_assign_mixed(p);
+                }
+            }
+        }
+        // \sum_d d * bucket_d, as a sum of running sums from the top
+        let mut running = G::Projective::zero();
+        let mut acc = G::Projective::zero();
+        for b in buckets.into_iter().rev() {
+            running += &b;
+            acc += &running;
+        }
+        acc
+    }
+}
+
+/// The `width` bits of `limbs` from bit `start`.
+fn digit(limbs: &[u64], start: usize, width: usize) -> usize {
+    let (limb, offset) = (start / 64, start % 64);
+    let mut d = limbs.get(limb).map_or(0, |l| l >> offset);
+    if offset + width > 64 {
+        d |= limbs.get(limb + 1).map_or(0, |l| l << (64 - offset));
+    }
+    (d & ((1u64 << width) - 1)) as usize
+}
+
+/// Curves whose MSMs over fixed bases can use a precomputed table.
+pub trait FixedBaseMsm: AffineCurve {
+    type Table: Clone + Send + Sync;
+
+    /// A table for `bases`, with windows of `width` bits.
+    fn fixed_base_table(bases: &[Self], width: usize) -> Self::Table;
+
+    /// `\sum_i s_i g_i`, for the bases `g_i` of `table`.
+    fn fixed_base_msm(table: &Self::Table, scalars: &[Self::ScalarField]) -> Self::Projective;
+}
+
+impl<P: SWModelParameters> FixedBaseMsm for sw::GroupAffine<P> {
+    type Table = FixedBaseTable<Self>;
+    fn fixed_base_table(bases: &[Self], width: usize) -> Self::Table {
+        FixedBaseTable::new(bases, width)
+    }
+    fn fixed_base_msm(table: &Self::Table, scalars: &[Self::ScalarField]) -> Self::Projective {
+        table.msm(scalars)
+    }
+}
+
+impl<P: TEModelParameters> FixedBaseMsm for te::GroupAffine<P> {
+    type Table = FixedBaseTable<Self>;
+    fn fixed_base_table(bases: &[Self], width: usize) -> Self::Table {
+        FixedBaseTable::new(bases, width)
+    }
+    fn fixed_base_msm(table: &Self::Table, scalars: &[Self::ScalarField]) -> Self::Projective {
+        table.msm(scalars)
+    }
+}
@@ -0,0 +1,244 @@
+//! Boolean circuits, as a garbler and an evaluator both build them.
+//!
+//! A [CircuitBuilder] folds constants as it goes, so that `x & 0` or `x ^ 1` cost no gate, and
+//! words are slices of wires, least significant bit first.
+use num_bigint::BigUint;
+
+/// A wire of a circuit: the output of an input, a constant or a gate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Wire(pub(crate) usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Node {
+    /// The next input of the party.
+    Input(usize),
+    Const(bool),
+    Xor(Wire, Wire),
+    And(Wire, Wire),
+    Not(Wire),
+}
+
+/// A boolean circuit over inputs of two parties, in topological order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Circuit {
+    pub(crate) nodes: Vec<Node>,
+    pub(crate) outputs: Vec<Wire>,
+}
+
+impl Circuit {
+    /// The number of inputs of `party`.
+    pub fn num_inputs(&self, party: usize) -> usize {
+        self.nodes
+            .iter()
+            .filter(|n| **n == Node::Input(party))
+            .count()
+    }
+
+    pub fn num_outputs(&self) -> usize {
+        self.outputs.len()
+    }
+
+    /// The number of AND gates: the others are free to garble.
+    pub fn num_and_gates(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|n| matches!(n, Node::And(..)))
+            .count()
+    }
+
+    /// Evaluate the circuit in the clear, on the inputs of both parties.
+    pub fn evaluate(&self, inputs: [&[bool]; 2]) -> Vec<bool> {
+        let mut next = [inputs[0].iter(), inputs[1].iter()];
+        let mut values: Vec<bool> = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let v = match *node {
+                Node::Input(party) => *next[party].next().expect("too few inputs"),
+                Node::Const(b) => b,
+                Node::Xor(a, b) => values[a.0] ^ values[b.0],
+                Node::And(a, b) => values[a.0] & values[b.0],
+                Node::Not(a) => !values[a.0],
+            };
+            values.push(v);
+        }
+        assert!(
+            next[0].next().is_none() && next[1].next().is_none(),
+            "too many inputs"
+        );
+        self.outputs.iter().map(|w| values[w.0]).collect()
+    }
+}
+
+/// Builds a [Circuit].
+#[derive(Clone, Debug, Default)]
+pub struct CircuitBuilder {
+    nodes: Vec<Node>,
+}
+
+impl CircuitBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(&mut self, node: Node) -> Wire {
+        self.nodes.push(node);
+        Wire(self.nodes.len() - 1)
+    }
+
+    fn constant_value(&self, w: Wire) -> Option<bool> {
+        match self.nodes[w.0] {
+            Node::Const(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// The next input of `party`, 0 or 1.
+    pub fn input(&mut self, party: usize) -> Wire {
+        assert!(party < 2, "circuits have two parties");
+        self.push(Node::Input(party))
+    }
+
+    /// The next `n` inputs of `party`.
+    pub fn inputs(&mut self, party: usize, n: usize) -> Vec<Wire> {
+        (0..n).map(|_| self.input(party)).collect()
+    }
+
+    pub fn constant(&mut self, b: bool) -> Wire {
+        self.push(Node::Const(b))
+    }
+
+    pub fn xor(&mut self, a: Wire, b: Wire) -> Wire {
+        match (self.constant_value(a), self.constant_value(b)) {
+            (Some(x), Some(y)) => self.constant(x ^ y),
+            (Some(x), None) => self.xor_const(b, x),
+            (None, Some(y)) => self.xor_const(a, y),
+            (None, None) if a == b => self.constant(false),
+            (None, None) => self.push(Node::Xor(a, b)),
+        }
+    }
+
+    fn xor_const(&mut self, a: Wire, b: bool) -> Wire {
+        if b {
+            self.not(a)
+        } else {
+            a
+        }
+    }
+
+    pub fn and(&mut self, a: Wire, b: Wire) -> Wire {
+        match (self.constant_value(a), self.constant_value(b)) {
+            (Some(x), Some(y)) => self.constant(x & y),
+            (Some(x), None) => self.and_const(b, x),
+            (None, Some(y)) => self.and_const(a, y),
+            (None, None) if a == b => a,
+            (None, None) => self.push(Node::And(a, b)),
+        }
+    }
+
+    fn and_const(&mut self, a: Wire, b: bool) -> Wire {
+        if b {
+            a
+        } else {
+            self.constant(false)
+        }
+    }
+
+    pub fn not(&mut self, a: Wire) -> Wire {
+        match self.nodes[a.0] {
+            Node::Const(b) => self.constant(!b),
+            Node::Not(b) => b,
+            _ => self.push(Node::Not(a)),
+        }
+    }
+
+    pub fn or(&mut self, a: Wire, b: Wire) -> Wire {
+        let (na, nb) = (self.not(a), self.not(b));
+        let nor = self.and(na, nb);
+        self.not(nor)
+    }
+
+    /// `b` if `s`, else `a`.
+    pub fn mux(&mut self, s: Wire, a: Wire, b: Wire) -> Wire {
+        let d = self.xor(a, b);
+        let d = self.and(s, d);
+        self.xor(a, d)
+    }
+
+    /// The low `n` bits of `value`.
+    pub fn constant_word(&mut self, value: &BigUint, n: usize) -> Vec<Wire> {
+        (0..n).map(|i| self.constant(value.bit(i as u64))).collect()
+    }
+
+    /// `a + b`, one bit longer than the longer of the two. One AND gate per bit.
+    pub fn add(&mut self, a: &[Wire], b: &[Wire]) -> Vec<Wire> {
+        let n = a.len().max(b.len());
+        let zero = self.constant(false);
+        let mut carry = zero;
+        let mut out = Vec::with_capacity(n + 1);
+        for i in 0..n {
+            let x = a.get(i).copied().unwrap_or(zero);
+            let y = b.get(i).copied().unwrap_or(zero);
+            let (s, c) = self.full_add(x, y, carry);
+            out.push(s);
+            carry = c;
+        }
+        out.push(carry);
+        out
+    }
+
+    /// The sum and carry of three bits, with one AND gate.
+    fn full_add(&mut self, a: Wire, b: Wire, c: Wire) -> (Wire, Wire) {
+        let ac = self.xor(a, c);
+        let bc = self.xor(b, c);
+        let sum = self.xor(ac, b);
+        let both = self.and(ac, bc);
+        (sum, self.xor(both, c))
+    }
+
+    /// `a - b` modulo `2^n`, for words of `n` bits, and whether `a < b`. One AND gate per bit.
+    pub fn sub(&mut self, a: &[Wire], b: &[Wire]) -> (Vec<Wire>, Wire) {
+        assert_eq!(a.len(), b.len(), "words of different lengths");
+        // a + !b + 1, whose carry out is a >= b
+        let mut carry = self.constant(true);
+        let mut out = Vec::with_capacity(a.len());
+        for (x, y) in a.iter().zip(b) {
+            let ny = self.not(*y);
+            let (s, c) = self.full_add(*x, ny, carry);
+            out.push(s);
+            carry = c;
+        }
+        let below = self.not(carry);
+        (out, below)
+    }
+
+    /// Whether `a < b`, for words of the same length.
+    pub fn less_than(&mut self, a: &[Wire], b: &[Wire]) -> Wire {
+        self.sub(a, b).1
+    }
+
+    /// `b` if `s`, else `a`, bit by bit.
+    pub fn mux_word(&mut self, s: Wire, a: &[Wire], b: &[Wire]) -> Vec<Wire> {
+        assert_eq!(a.len(), b.len(), "words of different lengths");
+        a.iter().zip(b).map(|(x, y)| self.mux(s, *x, *y)).collect()
+    }
+
+    /// `a + b` modulo `modulus`, for `a, b < modulus` of as many bits as the modulus.
+    pub fn add_mod(&mut self, a: &[Wire], b: &[Wire], modulus: &BigUint) -> Vec<Wire> {
+        let n = modulus.bits() as usize;
+        assert!(
+            a.len() == n && b.len() == n,
+            "words of a different length than the modulus"
+        );
+        let sum = self.add(a, b);
+        let m = self.constant_word(modulus, n + 1);
+        let (reduced, below) = self.sub(&sum, &m);
+        self.mux_word(below, &reduced[..n], &sum[..n])
+    }
+
+    /// The circuit computing `outputs`.
+    pub fn finish(self, outputs: Vec<Wire>) -> Circuit {
+        Circuit {
+            nodes: self.nodes,
+            outputs,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Garbling with free XOR, point-and-permute and half gates
+//! ([Zahur, Rosulek and Evans](https://eprint.iacr.org/2014/756)).
+//!
+//! Every wire has a label for `0`, and the label for `1` is that XOR a global `delta`, whose low
+//! bit is set. The low bit of a label is its permute bit, so the evaluator's labels' low bits are
+//! XOR shares, with the garbler's zero labels' low bits, of the wires' values. XOR and NOT gates
+//! cost nothing; an AND gate costs two labels, and four hashes to garble.
+use std::convert::TryInto;
+
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use super::circuit::{Circuit, Node};
+
+pub(crate) type Label = u128;
+
+fn hash(label: Label, tweak: usize) -> Label {
+    let digest = Sha256::new()
+        .chain(&label.to_le_bytes())
+        .chain(&(tweak as u64).to_le_bytes())
+        .finalize();
+    u128::from_le_bytes(digest[..16].try_into().unwrap())
+}
+
+pub(crate) fn lsb(label: Label) -> bool {
+    label & 1 == 1
+}
+
+/// `label` if `b`, else zero.
+fn select(b: bool, label: Label) -> Label {
+    if b {
+        label
+    } else {
+        0
+    }
+}
+
+/// A garbled circuit, as its garbler keeps it.
+pub(crate) struct Garbling {
+    pub delta: Label,
+    /// The label for `0` of every wire.
+    pub zeros: Vec<Label>,
+    /// Two labels for each AND gate, in order.
+    pub tables: Vec<[Label; 2]>,
+}
+
+impl Garbling {
+    pub fn new<R: Rng>(circuit: &Circuit, rng: &mut R) -> Self {
+        let delta = rng.gen::<Label>() | 1;
+        let mut zeros: Vec<Label> = Vec::with_capacity(circuit.nodes.len());
+        let mut tables = Vec::new();
+        for (i, node) in circuit.nodes.iter().enumerate() {
+            let zero = match *node {
+                Node::Input(_) | Node::Const(_) => rng.gen(),
+                Node::Xor(a, b) => zeros[a.0] ^ zeros[b.0],
+                Node::Not(a) => zeros[a.0] ^ delta,
+                Node::And(a, b) => {
+                    let (a0, b0) = (zeros[a.0], zeros[b.0]);
+                    let (pa, pb) = (lsb(a0), lsb(b0));
+                    let (j, k) = (2 * i, 2 * i + 1);
+                    // the garbler's half gate, for which it knows b's permute bit
+                    let ha = hash(a0, j);
+                    let generator = ha ^ hash(a0 ^ delta, j) ^ select(pb, delta);
+                    let wg = ha ^ select(pa, generator);
+                    // the evaluator's half gate, for which it knows b's value XOR its permute bit
+                    let hb = hash(b0, k);
+                    let evaluator = hb ^ hash(b0 ^ delta, k) ^ a0;
+                    let we = hb ^ select(pb, evaluator ^ a0);
+                    tables.push([generator, evaluator]);
+                    wg ^ we
+                }
+            };
+            zeros.push(zero);
+        }
+        Self {
+            delta,
+            zeros,
+            tables,
+        }
+    }
+
+    /// The label of wire `i` for `b`.
+    pub fn label(&self, i: usize, b: bool) -> Label {
+        self.zeros[i] ^ select(b, self.delta)
+    }
+}
+
+/// The label of every wire, given the tables and the labels of the inputs and constants, in the
+/// order of the circuit's nodes.
+pub(crate) fn evaluate(
+    circuit: &Circuit,
+    tables: &[[Label; 2]],
+    sources: impl IntoIterator<Item = Label>,
+) -> Vec<Label> {
+    let mut sources = sources.into_iter();
+    let mut tables = tables.iter();
+    let mut labels: Vec<Label> = Vec::with_capacity(circuit.nodes.len());
+    for (i, node) in circuit.nodes.iter().enumerate() {
+        let label = match *node {
+            Node::Input(_) | Node::Const(_) => sources.next().expect("too few input labels"),
+            Node::Xor(a, b) => labels[a.0] ^ labels[b.0],
+            Node::Not(a) => labels[a.0],
+            Node::And(a, b) => {
+                let [generator, evaluator] = *tables.next().expect("too few garbled gates");
+                let (a, b) = (labels[a.0], labels[b.0]);
+                let wg = hash(a, 2 * i) ^ select(lsb(a), generator);
+                let we = hash(b, 2 * i + 1) ^ select(lsb(b), evaluator ^ a);
+                wg ^ we
+            }
+        };
+        labels.push(label);
+    }
+    labels
+}
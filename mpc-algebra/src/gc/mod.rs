@@ -0,0 +1,215 @@
+//! A garbled-circuit engine for two-party sessions.
+//!
+//! Shares multiply cheaply, but comparisons, bit twiddling and hashes such as SHA-256 are boolean
+//! circuits, and evaluating those on shared bits takes a round per layer of AND gates. A garbled
+//! circuit takes four messages however deep the circuit is, at the price of sending two labels per
+//! AND gate: on a high-latency link, it wins for deep circuits.
+//!
+//! Party [GARBLER] garbles a [Circuit], and party [EVALUATOR] evaluates it (Yao's protocol),
+//! receiving the labels of its inputs by oblivious transfer. Like the honest-but-curious shares,
+//! it is secure against semi-honest parties only, and it converts to and from those shares at the
+//! boundary: [field_inputs] and [field_input_bits] bring shared field elements into a circuit, and
+//! [to_field_bits] brings its XOR-shared outputs back. [apply] does both around a circuit.
+use ark_ff::{BigInteger, Field, FpParameters, PrimeField, Zero};
+use num_bigint::BigUint;
+use rand::Rng;
+
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+
+use crate::share::add::AdditiveFieldShare;
+use crate::wire::field::MpcField;
+use crate::Reveal;
+
+pub mod circuit;
+mod garble;
+mod ot;
+
+pub use circuit::{Circuit, CircuitBuilder, Wire};
+
+use circuit::Node;
+use garble::{lsb, Garbling, Label};
+
+/// The party that garbles.
+pub const GARBLER: usize = 0;
+/// The party that evaluates.
+pub const EVALUATOR: usize = 1;
+
+/// Send `bytes` to the other party, which must [receive_bytes] them.
+fn send_bytes(bytes: Vec<u8>) {
+    // a king's messages all have the same length, its own included
+    Net::recv_bytes_from(Net::party_id(), Some(vec![bytes.clone(), bytes]));
+}
+
+fn receive_bytes() -> Vec<u8> {
+    Net::recv_bytes_from(1 - Net::party_id(), None)
+}
+
+fn labels_from_bytes(bytes: &[u8]) -> Vec<Label> {
+    bytes
+        .chunks(16)
+        .map(|c| {
+            let mut b = [0u8; 16];
+            b.copy_from_slice(c);
+            u128::from_le_bytes(b)
+        })
+        .collect()
+}
+
+/// Run `circuit` on this party's `inputs`, and return this party's XOR shares of its outputs.
+///
+/// Both parties must pass the same circuit. The garbler's shares are the permute bits of its
+/// output labels, so it sends nothing about them.
+pub fn run_shared<R: Rng>(circuit: &Circuit, inputs: &[bool], rng: &mut R) -> Vec<bool> {
+    assert_eq!(Net::n_parties(), 2, "garbled circuits need two parties");
+    let me = Net::party_id();
+    assert_eq!(
+        inputs.len(),
+        circuit.num_inputs(me),
+        "wrong number of inputs"
+    );
+    if me == GARBLER {
+        let garbling = Garbling::new(circuit, rng);
+        let mut mine = inputs.iter();
+        let mut message = Vec::with_capacity(32 * garbling.tables.len());
+        for table in &garbling.tables {
+            message.extend_from_slice(&table[0].to_le_bytes());
+            message.extend_from_slice(&table[1].to_le_bytes());
+        }
+        let mut pairs = Vec::new();
+        for (i, node) in circuit.nodes.iter().enumerate() {
+            match *node {
+                Node::Input(GARBLER) => {
+                    let label = garbling.label(i, *mine.next().unwrap());
+                    message.extend_from_slice(&label.to_le_bytes());
+                }
+                Node::Const(b) => message.extend_from_slice(&garbling.label(i, b).to_le_bytes()),
+                Node::Input(_) => pairs.push((garbling.label(i, false), garbling.label(i, true))),
+                _ => {}
+            }
+        }
+        send_bytes(message);
+        ot::send(&pairs, rng);
+        circuit
+            .outputs
+            .iter()
+            .map(|w| lsb(garbling.zeros[w.0]))
+            .collect()
+    } else {
+        let labels = labels_from_bytes(&receive_bytes());
+        let (tables, garbler) = labels.split_at(2 * circuit.num_and_gates());
+        let tables: Vec<[Label; 2]> = tables.chunks(2).map(|t| [t[0], t[1]]).collect();
+        let mut garbler = garbler.iter();
+        let mut mine = ot::receive(inputs, rng).into_iter();
+        let sources: Vec<Label> = circuit
+            .nodes
+            .iter()
+            .filter_map(|node| match *node {
+                Node::Input(GARBLER) | Node::Const(_) => garbler.next().copied(),
+                Node::Input(_) => mine.next(),
+                _ => None,
+            })
+            .collect();
+        let labels = garble::evaluate(circuit, &tables, sources);
+        circuit.outputs.iter().map(|w| lsb(labels[w.0])).collect()
+    }
+}
+
+/// Like [run_shared], but open the outputs to both parties.
+pub fn run<R: Rng>(circuit: &Circuit, inputs: &[bool], rng: &mut R) -> Vec<bool> {
+    let shares = run_shared(circuit, inputs, rng);
+    let bytes: Vec<u8> = shares.iter().map(|b| *b as u8).collect();
+    let all = Net::broadcast_bytes(&bytes);
+    all[0].iter().zip(&all[1]).map(|(a, b)| a != b).collect()
+}
+
+fn modulus<F: PrimeField>() -> BigUint {
+    BigUint::from_bytes_le(&F::Params::MODULUS.to_bytes_le())
+}
+
+/// Wires for the values of `n` shared elements of `F`, least significant bit first.
+///
+/// Each party inputs the bits of its shares, as [field_input_bits] gives them, and the circuit
+/// adds them modulo `F`'s modulus.
+pub fn field_inputs<F: PrimeField>(builder: &mut CircuitBuilder, n: usize) -> Vec<Vec<Wire>> {
+    let modulus = modulus::<F>();
+    let bits = modulus.bits() as usize;
+    (0..n)
+        .map(|_| {
+            let a = builder.inputs(GARBLER, bits);
+            let b = builder.inputs(EVALUATOR, bits);
+            builder.add_mod(&a, &b, &modulus)
+        })
+        .collect()
+}
+
+/// This party's inputs for [field_inputs]: the bits of its shares of `xs`.
+pub fn field_input_bits<F: PrimeField>(xs: &[MpcField<F, AdditiveFieldShare<F>>]) -> Vec<bool> {
+    let bits = F::Params::MODULUS_BITS as usize;
+    let me = Net::party_id();
+    xs.iter()
+        .flat_map(|x| {
+            let share = match x {
+                MpcField::Shared(s) => s.val,
+                MpcField::Public(x) if me == GARBLER => *x,
+                MpcField::Public(_) => F::zero(),
+            };
+            share.into_repr().to_bits_le().into_iter().take(bits)
+        })
+        .collect()
+}
+
+/// Shared bits, from XOR shares of them, such as [run_shared]'s outputs.
+///
+/// A bit is `b0 + b1 - 2 b0 b1`, for the shares `b0` and `b1` of the two parties, so this costs one
+/// round of multiplications.
+pub fn to_field_bits<F: PrimeField>(shares: &[bool]) -> Vec<MpcField<F, AdditiveFieldShare<F>>> {
+    let me = Net::party_id();
+    let own = |party: usize| -> Vec<MpcField<F, AdditiveFieldShare<F>>> {
+        shares
+            .iter()
+            .map(|b| {
+                let mine = if me == party {
+                    F::from(*b as u64)
+                } else {
+                    F::zero()
+                };
+                MpcField::from_add_shared(mine)
+            })
+            .collect()
+    };
+    let (mut products, b1) = (own(GARBLER), own(EVALUATOR));
+    let sums: Vec<_> = products.iter().zip(&b1).map(|(a, b)| *a + *b).collect();
+    MpcField::batch_product_in_place(&mut products, &b1);
+    sums.into_iter()
+        .zip(products)
+        .map(|(s, p)| s - p.double())
+        .collect()
+}
+
+/// Apply a boolean function to shared field elements with a garbled circuit.
+///
+/// `build` maps the bits of the values of `xs` to output words, least significant bit first, and
+/// each word comes back as a shared field element.
+pub fn apply<F: PrimeField, R: Rng>(
+    xs: &[MpcField<F, AdditiveFieldShare<F>>],
+    build: impl FnOnce(&mut CircuitBuilder, &[Vec<Wire>]) -> Vec<Vec<Wire>>,
+    rng: &mut R,
+) -> Vec<MpcField<F, AdditiveFieldShare<F>>> {
+    let mut builder = CircuitBuilder::new();
+    let inputs = field_inputs::<F>(&mut builder, xs.len());
+    let words = build(&mut builder, &inputs);
+    let lens: Vec<usize> = words.iter().map(|w| w.len()).collect();
+    let circuit = builder.finish(words.into_iter().flatten().collect());
+    let bits = to_field_bits::<F>(&run_shared(&circuit, &field_input_bits(xs), rng));
+    let mut bits = bits.into_iter();
+    lens.into_iter()
+        .map(|len| {
+            bits.by_ref()
+                .take(len)
+                .enumerate()
+                .fold(MpcField::zero(), |acc, (i, b)| {
+                    acc + b * MpcField::Public(F::from(2u64).pow([i as u64]))
+                })
+        })
+        .collect()
+}
@@ -0,0 +1,86 @@
+//! Oblivious transfer of labels, by the "simplest OT" of
+//! [Chou and Orlandi](https://eprint.iacr.org/2015/267), over BLS12-377's G1.
+//!
+//! The sender publishes `A = aG`; for each transfer, the receiver, choosing `c`, answers
+//! `B = bG + cA`. The sender encrypts its two labels under hashes of `aB` and `a(B - A)`, and the
+//! receiver can compute only the one of them that is `bA`. Three messages in all, for any number
+//! of transfers, and secure against semi-honest parties.
+use std::convert::TryInto;
+
+use ark_bls12_377::{Fr, G1Affine, G1Projective};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use super::garble::Label;
+use super::{receive_bytes, send_bytes};
+
+/// The key of the `i`th transfer with the shared point `p`.
+fn key(i: usize, p: &G1Projective) -> Label {
+    let mut bytes = Vec::new();
+    p.into_affine().serialize(&mut bytes).unwrap();
+    let digest = Sha256::new()
+        .chain(&(i as u64).to_le_bytes())
+        .chain(&bytes)
+        .finalize();
+    u128::from_le_bytes(digest[..16].try_into().unwrap())
+}
+
+fn to_bytes<T: CanonicalSerialize>(x: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    x.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+/// Send one label of each pair to the other party, which [receive]s the one it chooses, and
+/// learns nothing about the other.
+pub(crate) fn send<R: Rng>(pairs: &[(Label, Label)], rng: &mut R) {
+    let a = Fr::rand(rng).into_repr();
+    let big_a = G1Projective::prime_subgroup_generator().mul(a);
+    send_bytes(to_bytes(&big_a.into_affine()));
+    let bs = Vec::<G1Affine>::deserialize(&receive_bytes()[..]).unwrap();
+    assert_eq!(bs.len(), pairs.len(), "wrong number of choices");
+    let a_big_a = big_a.mul(a);
+    let mut out = Vec::with_capacity(32 * pairs.len());
+    for (i, ((m0, m1), b)) in pairs.iter().zip(bs).enumerate() {
+        let ab = b.mul(a);
+        out.extend_from_slice(&(m0 ^ key(i, &ab)).to_le_bytes());
+        out.extend_from_slice(&(m1 ^ key(i, &(ab - a_big_a))).to_le_bytes());
+    }
+    send_bytes(out);
+}
+
+/// Receive the labels that the other party [send]s, choosing from each pair the second one if
+/// `choices` says so.
+pub(crate) fn receive<R: Rng>(choices: &[bool], rng: &mut R) -> Vec<Label> {
+    let big_a = G1Affine::deserialize(&receive_bytes()[..]).unwrap();
+    let g = G1Projective::prime_subgroup_generator();
+    let bs: Vec<_> = choices.iter().map(|_| Fr::rand(rng).into_repr()).collect();
+    let points: Vec<G1Projective> = bs
+        .iter()
+        .zip(choices)
+        .map(|(b, c)| {
+            let p = g.mul(b);
+            if *c {
+                p.add_mixed(&big_a)
+            } else {
+                p
+            }
+        })
+        .collect();
+    send_bytes(to_bytes(&G1Projective::batch_normalization_into_affine(
+        &points,
+    )));
+    let sealed = receive_bytes();
+    assert_eq!(sealed.len(), 32 * choices.len(), "wrong number of labels");
+    bs.iter()
+        .zip(choices)
+        .enumerate()
+        .map(|(i, (b, c))| {
+            let at = 32 * i + if *c { 16 } else { 0 };
+            u128::from_le_bytes(sealed[at..at + 16].try_into().unwrap()) ^ key(i, &big_a.mul(*b))
+        })
+        .collect()
+}
@@ -0,0 +1,291 @@
+//! A development mode that flags openings which may leak private inputs.
+//!
+//! Once [enable]d for a session, each shared [MpcField](crate::MpcField) and
+//! [MpcGroup](crate::MpcGroup) carries a [Provenance]: the private inputs it was computed from,
+//! and whether fresh shared randomness went into it. When such a value is opened, it is flagged
+//! if it depends on inputs but carries no randomness, and either on fewer inputs than
+//! [LeakCheck::min_inputs] or on a single party's inputs alone. Opening `x + 1`, or a product of
+//! one party's inputs, reveals those inputs to everyone; opening `x + r` for a random `r` does not.
+//!
+//! Flags are logged as warnings and kept as [Finding]s. They are hints for circuit authors, not
+//! proofs: an intended output is flagged just like an accident, and the analysis can't tell how
+//! much a value really hides.
+//!
+//! Provenance is kept in a table keyed by this party's share of each value, so it is a heuristic.
+//! Values whose shares happen to coincide share an entry, and each party reaches its own findings.
+//! Values whose share is zero are not tracked at all. With the placeholder multiplication triples,
+//! that includes every party's share of a product, except the king's, so read the king's findings.
+//! Only the operations of the wire types are tracked: a value computed on raw shares has no
+//! provenance, and is never flagged.
+use lazy_static::lazy_static;
+use log::warn;
+use mpc_net::MpcMultiNet as Net;
+
+use std::any::TypeId;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{self, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Most inputs to list in a [Provenance]; beyond this, only their number is kept.
+const MAX_INPUTS: usize = 64;
+
+/// Settings for the analysis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LeakCheck {
+    /// Unmasked openings must depend on at least this many inputs.
+    pub min_inputs: usize,
+}
+
+impl Default for LeakCheck {
+    fn default() -> Self {
+        Self { min_inputs: 2 }
+    }
+}
+
+/// A private input: a value shared by `owner`, or (with no owner) one assembled from all the
+/// parties' additive shares.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Input {
+    pub owner: Option<usize>,
+    /// Inputs are numbered in the order they are shared, from `0`.
+    pub index: u64,
+}
+
+impl Display for Input {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.owner {
+            Some(p) => write!(f, "input #{} (party {})", self.index, p),
+            None => write!(f, "input #{} (joint)", self.index),
+        }
+    }
+}
+
+/// Where a shared value came from.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Provenance {
+    /// The inputs it depends on, up to a limit.
+    pub inputs: BTreeSet<Input>,
+    /// How many inputs it depends on, including those not listed.
+    pub n_inputs: usize,
+    /// Whether shared randomness went into it.
+    pub masked: bool,
+}
+
+impl Provenance {
+    fn input(input: Input) -> Self {
+        Self {
+            inputs: std::iter::once(input).collect(),
+            n_inputs: 1,
+            masked: false,
+        }
+    }
+
+    fn random() -> Self {
+        Self {
+            masked: true,
+            ..Self::default()
+        }
+    }
+
+    /// The provenance of a value computed from values of provenance `self` and `other`.
+    fn join(mut self, other: &Self) -> Self {
+        let complete = self.n_inputs == self.inputs.len() && other.n_inputs == other.inputs.len();
+        self.inputs.extend(other.inputs.iter().cloned());
+        self.n_inputs = if complete {
+            self.inputs.len()
+        } else {
+            // some inputs weren't listed, so we can't tell which are common
+            self.n_inputs.max(other.n_inputs).max(self.inputs.len())
+        };
+        while self.inputs.len() > MAX_INPUTS {
+            let last = *self.inputs.iter().next_back().unwrap();
+            self.inputs.remove(&last);
+        }
+        self.masked |= other.masked;
+        self
+    }
+
+    /// The party whose inputs this depends on, if it depends on only one party's.
+    fn sole_owner(&self) -> Option<usize> {
+        let mut owners = self.inputs.iter().map(|i| i.owner);
+        let first = owners.next()??;
+        if self.n_inputs == self.inputs.len() && owners.all(|o| o == Some(first)) {
+            Some(first)
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for Provenance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let listed: Vec<String> = self.inputs.iter().map(|i| i.to_string()).collect();
+        write!(f, "{}", listed.join(", "))?;
+        if self.n_inputs > self.inputs.len() {
+            write!(f, " and {} more", self.n_inputs - self.inputs.len())?;
+        }
+        if self.masked {
+            write!(f, ", masked")?;
+        }
+        Ok(())
+    }
+}
+
+/// Why an opening was flagged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reason {
+    /// It depends on this many inputs, fewer than [LeakCheck::min_inputs].
+    FewInputs(usize),
+    /// It depends on this party's inputs alone.
+    SingleOwner(usize),
+}
+
+/// An opening that was flagged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub provenance: Provenance,
+    pub reason: Reason,
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.reason {
+            Reason::FewInputs(n) => write!(f, "opened an unmasked value of only {} input(s)", n)?,
+            Reason::SingleOwner(p) => {
+                write!(f, "opened an unmasked value of party {}'s inputs alone", p)?
+            }
+        }
+        write!(f, ": {}", self.provenance)
+    }
+}
+
+#[derive(Default)]
+struct Tracker {
+    config: LeakCheck,
+    next_input: u64,
+    /// By (type of share, share).
+    values: HashMap<u64, Provenance>,
+    findings: Vec<Finding>,
+}
+
+lazy_static! {
+    /// By session tag.
+    static ref TRACKERS: Mutex<HashMap<u64, Tracker>> = Mutex::new(HashMap::new());
+}
+
+/// How many sessions have the analysis on, so that the others can skip it cheaply.
+static ENABLED: AtomicUsize = AtomicUsize::new(0);
+
+/// Turn the analysis on for the current session, forgetting any earlier findings.
+///
+/// Values shared before this have no provenance.
+pub fn enable(config: LeakCheck) {
+    let tracker = Tracker {
+        config,
+        ..Tracker::default()
+    };
+    if TRACKERS
+        .lock()
+        .unwrap()
+        .insert(Net::session_tag(), tracker)
+        .is_none()
+    {
+        ENABLED.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Turn the analysis off for the current session, returning its findings.
+pub fn disable() -> Vec<Finding> {
+    match TRACKERS.lock().unwrap().remove(&Net::session_tag()) {
+        Some(t) => {
+            ENABLED.fetch_sub(1, Ordering::Relaxed);
+            t.findings
+        }
+        None => Vec::new(),
+    }
+}
+
+/// Whether the analysis is on for the current session.
+#[inline]
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed) > 0 && with_tracker(|_| ()).is_some()
+}
+
+/// The current session's findings so far.
+pub fn findings() -> Vec<Finding> {
+    with_tracker(|t| t.findings.clone()).unwrap_or_default()
+}
+
+fn with_tracker<T>(f: impl FnOnce(&mut Tracker) -> T) -> Option<T> {
+    if ENABLED.load(Ordering::Relaxed) == 0 {
+        return None;
+    }
+    let tag = Net::session_tag();
+    TRACKERS.lock().unwrap().get_mut(&tag).map(f)
+}
+
+fn key<S: Hash + 'static>(share: &S) -> u64 {
+    let mut h = DefaultHasher::new();
+    TypeId::of::<S>().hash(&mut h);
+    share.hash(&mut h);
+    h.finish()
+}
+
+/// The provenance of `share`, if it is known.
+pub(crate) fn lookup<S: Hash + 'static>(share: &S) -> Option<Provenance> {
+    with_tracker(|t| t.values.get(&key(share)).cloned()).flatten()
+}
+
+/// Record that `share` has provenance `p`.
+pub(crate) fn record<S: Hash + 'static>(share: &S, p: Provenance) {
+    with_tracker(|t| t.values.insert(key(share), p));
+}
+
+/// Record that `share` is a new input.
+pub(crate) fn record_input<S: Hash + 'static>(share: &S, owner: Option<usize>) {
+    with_tracker(|t| {
+        let index = t.next_input;
+        t.next_input += 1;
+        t.values
+            .insert(key(share), Provenance::input(Input { owner, index }));
+    });
+}
+
+/// Record that `share` is fresh randomness.
+pub(crate) fn record_random<S: Hash + 'static>(share: &S) {
+    record(share, Provenance::random())
+}
+
+/// The provenance of a value computed from values of provenances `a` and `b`.
+pub(crate) fn join(a: Option<Provenance>, b: Option<Provenance>) -> Option<Provenance> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.join(&b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Check a value of provenance `p` before it is opened.
+pub(crate) fn check_open(p: Option<Provenance>) {
+    let p = match p {
+        Some(p) if !p.masked && p.n_inputs > 0 => p,
+        _ => return,
+    };
+    with_tracker(|t| {
+        let reason = if p.n_inputs < t.config.min_inputs {
+            Reason::FewInputs(p.n_inputs)
+        } else if let Some(owner) = p.sole_owner() {
+            Reason::SingleOwner(owner)
+        } else {
+            return;
+        };
+        let finding = Finding {
+            provenance: p,
+            reason,
+        };
+        warn!("Possible leak: {}", finding);
+        t.findings.push(finding);
+    });
+}
@@ -1,21 +1,41 @@
 #![macro_use]
 #![feature(associated_type_defaults)]
+// for the costlier DomainCoeff::MUL_COST of shared coefficients
+#![feature(specialization)]
+#![allow(incomplete_features)]
 
 pub mod reveal;
 pub use reveal::*;
+pub mod acl;
+pub mod budget;
 pub mod channel;
 pub mod com;
+pub mod cost;
+pub mod ct;
+pub mod dedup;
+pub mod dp;
+pub mod fixed;
+pub mod fixed_base;
+pub mod gc;
 pub mod group;
+pub mod leak;
+pub mod nonnative;
+pub mod seed;
 pub mod share;
+pub mod shared_rng;
+pub use shared_rng::SharedRng;
 pub use share::*;
+pub mod transcript;
+pub use transcript::MpcTranscript;
 pub mod wire;
 pub use wire::*;
 
 pub mod honest_but_curious {
+    use ark_ec::ModelParameters;
     use super::{
         share::add::*,
         share::msm::NaiveMsm,
-        wire::{field, group, pairing},
+        wire::{edwards, field, group, pairing},
     };
     pub type MpcField<F> = field::MpcField<F, AdditiveFieldShare<F>>;
     pub type MpcGroup<G> = group::MpcGroup<G, AdditiveGroupShare<G, NaiveMsm<G>>>;
@@ -26,13 +46,18 @@ pub mod honest_but_curious {
     pub type MpcG1Prep<E> = pairing::MpcG1Prep<E, AdditivePairingShare<E>>;
     pub type MpcG2Prep<E> = pairing::MpcG2Prep<E, AdditivePairingShare<E>>;
     pub type MpcPairingEngine<E> = pairing::MpcPairingEngine<E, AdditivePairingShare<E>>;
+    pub type MpcEdwardsAffine<P> =
+        edwards::MpcEdwardsAffine<P, AdditiveFieldShare<<P as ModelParameters>::BaseField>>;
+    pub type MpcEdwardsProjective<P> =
+        edwards::MpcEdwardsProjective<P, AdditiveFieldShare<<P as ModelParameters>::BaseField>>;
 }
 
 pub mod malicious_majority {
+    use ark_ec::ModelParameters;
     use super::{
         share::msm::NaiveMsm,
         share::spdz::*,
-        wire::{field, group, pairing},
+        wire::{edwards, field, group, pairing},
     };
     pub type MpcField<F> = field::MpcField<F, SpdzFieldShare<F>>;
     pub type MpcGroup<G> = group::MpcGroup<G, SpdzGroupShare<G, NaiveMsm<G>>>;
@@ -43,16 +68,25 @@ pub mod malicious_majority {
     pub type MpcG1Prep<E> = pairing::MpcG1Prep<E, SpdzPairingShare<E>>;
     pub type MpcG2Prep<E> = pairing::MpcG2Prep<E, SpdzPairingShare<E>>;
     pub type MpcPairingEngine<E> = pairing::MpcPairingEngine<E, SpdzPairingShare<E>>;
+    pub type MpcEdwardsAffine<P> =
+        edwards::MpcEdwardsAffine<P, SpdzFieldShare<<P as ModelParameters>::BaseField>>;
+    pub type MpcEdwardsProjective<P> =
+        edwards::MpcEdwardsProjective<P, SpdzFieldShare<<P as ModelParameters>::BaseField>>;
 }
 
 pub mod honest_majority {
+    use ark_ec::ModelParameters;
     use super::{
         share::msm::NaiveMsm,
         share::gsz20::{field::GszFieldShare, group::GszGroupShare},
-        wire::{field, group},
+        wire::{edwards, field, group},
     };
     pub type MpcField<F> = field::MpcField<F, GszFieldShare<F>>;
     pub type MpcGroup<G> = group::MpcGroup<G, GszGroupShare<G, NaiveMsm<G>>>;
+    pub type MpcEdwardsAffine<P> =
+        edwards::MpcEdwardsAffine<P, GszFieldShare<<P as ModelParameters>::BaseField>>;
+    pub type MpcEdwardsProjective<P> =
+        edwards::MpcEdwardsProjective<P, GszFieldShare<<P as ModelParameters>::BaseField>>;
     // pub type MpcG1Affine<E> = pairing::MpcG1Affine<E, GszPairingShare<E>>;
     // pub type MpcG2Affine<E> = pairing::MpcG2Affine<E, GszPairingShare<E>>;
     // pub type MpcG1Projective<E> = pairing::MpcG1Projective<E, GszPairingShare<E>>;
@@ -61,3 +95,19 @@ pub mod honest_majority {
     // pub type MpcG2Prep<E> = pairing::MpcG2Prep<E, GszPairingShare<E>>;
     // pub type MpcPairingEngine<E> = pairing::MpcPairingEngine<E, GszPairingShare<E>>;
 }
+
+/// Any of the above, as chosen at run time with [share::dynamic::set_scheme].
+pub mod any_scheme {
+    use super::{
+        share::dynamic::*,
+        wire::{field, pairing},
+    };
+    pub type MpcField<F> = field::MpcField<F, DynFieldShare<F>>;
+    pub type MpcG1Affine<E> = pairing::MpcG1Affine<E, DynPairingShare<E>>;
+    pub type MpcG2Affine<E> = pairing::MpcG2Affine<E, DynPairingShare<E>>;
+    pub type MpcG1Projective<E> = pairing::MpcG1Projective<E, DynPairingShare<E>>;
+    pub type MpcG2Projective<E> = pairing::MpcG2Projective<E, DynPairingShare<E>>;
+    pub type MpcG1Prep<E> = pairing::MpcG1Prep<E, DynPairingShare<E>>;
+    pub type MpcG2Prep<E> = pairing::MpcG2Prep<E, DynPairingShare<E>>;
+    pub type MpcPairingEngine<E> = pairing::MpcPairingEngine<E, DynPairingShare<E>>;
+}
@@ -0,0 +1,922 @@
+//! Witnesses for non-native field arithmetic, computed on shared values.
+//!
+//! A circuit over one field that does arithmetic modulo another prime `p` (say, checking an
+//! ECDSA signature over secp256k1 inside a BLS12-377 circuit) represents each element by limbs,
+//! and for each product `a * b` asks the prover for the quotient `q` and remainder `r` of
+//! `a * b = q * p + r`, and for the carries that show two limb representations equal. Computing
+//! those needs comparisons and divisions of the values, which shares do not support: this module
+//! builds them from shared random bits.
+//!
+//! Everything rests on one step. To find the low bits of a shared `x < 2^k`, mask it with a random
+//! shared `r` of `k + σ` bits, open `c = x + r`, and compare the low bits of the public `c` with
+//! those of `r`, one bit per round, for `σ = STATISTICAL_SECURITY`. The opening is statistically
+//! close to uniform whatever `x` is, so `k + σ` bits must fit in the native field: about 210 bits,
+//! for BLS12-377.
+//!
+//! Limbs are least significant first. ark-nonnative-field's gadgets put the most significant
+//! first, so reverse them before allocating witnesses.
+//!
+//! Every function batches across its inputs, so its round count does not grow with their number,
+//! but rounds do add up: reducing products modulo a 256-bit `p` with 64-bit limbs takes a few
+//! thousand. Batch as many products as the circuit allows into each call.
+//!
+//! When `p` is the modulus of a field `T` that the parties already share elements of (BLS12-377's
+//! base field, say, in a circuit over its scalar field), [NonNativeParams::share_limbs] turns those
+//! shares into shared limbs, and [NonNative] wraps them for [ToConstraintField], so that a circuit
+//! can take them as witnesses or absorb them into a sponge.
+use ark_ff::bytes::ToBytes;
+use ark_ff::{
+    BigInteger, Field, FpParameters, One, PrimeField, SquareRootField, ToConstraintField, Zero,
+};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use num_bigint::BigUint;
+use rand::Rng;
+
+use std::io::{self, Write};
+
+use crate::dp::random_bits;
+use crate::share::field::FieldShare;
+use crate::wire::field::MpcField;
+use crate::{budget, seed, Reveal};
+
+/// The bits of each mask beyond those of the value it hides. The distance between an opened
+/// masked value and a uniform one is at most `2^-STATISTICAL_SECURITY`.
+pub const STATISTICAL_SECURITY: usize = 40;
+
+fn to_field<F: PrimeField>(x: &BigUint) -> F {
+    F::from_le_bytes_mod_order(&x.to_bytes_le())
+}
+
+fn to_biguint<F: PrimeField>(x: F) -> BigUint {
+    BigUint::from_bytes_le(&x.into_repr().to_bytes_le())
+}
+
+fn modulus_of<F: PrimeField>() -> BigUint {
+    BigUint::from_bytes_le(&F::Params::MODULUS.to_bytes_le())
+}
+
+fn pow2<F: Field>(i: usize) -> F {
+    F::from(2u64).pow([i as u64])
+}
+
+/// The number of bits of `n`.
+fn bits_of(n: usize) -> usize {
+    (usize::BITS - n.leading_zeros()) as usize
+}
+
+/// Panic unless values below `2^(k + σ + 1)` can be opened without wrapping around the modulus.
+fn check_bound<F: PrimeField>(k: usize) {
+    assert!(
+        k + STATISTICAL_SECURITY + 1 < F::Params::MODULUS_BITS as usize,
+        "{}-bit values are too large to mask in a {}-bit field",
+        k,
+        F::Params::MODULUS_BITS
+    );
+}
+
+/// The low `m` bits of `x`, least significant first, as public values.
+fn public_bits<F: PrimeField, S: FieldShare<F>>(x: &BigUint, m: usize) -> Vec<MpcField<F, S>> {
+    (0..m)
+        .map(|i| MpcField::Public(F::from(x.bit(i as u64) as u64)))
+        .collect()
+}
+
+/// `Σ xs[i] 2^(i * width)`.
+fn compose<F: PrimeField, S: FieldShare<F>>(xs: &[MpcField<F, S>], width: usize) -> MpcField<F, S> {
+    xs.iter().enumerate().fold(MpcField::zero(), |acc, (i, x)| {
+        acc + *x * MpcField::Public(pow2::<F>(i * width))
+    })
+}
+
+/// `Σ_{i + j = t} xs[i] ys[j]` for each `t`, where `ys` is public, so each product is local.
+fn convolve<F: PrimeField, S: FieldShare<F>>(
+    xs: &[MpcField<F, S>],
+    ys: &[F],
+) -> Vec<MpcField<F, S>> {
+    let mut out = vec![MpcField::zero(); xs.len() + ys.len() - 1];
+    for (i, x) in xs.iter().enumerate() {
+        for (j, y) in ys.iter().enumerate() {
+            out[i + j] += *x * MpcField::Public(*y);
+        }
+    }
+    out
+}
+
+/// Split each of `bits` into consecutive groups of `width`, and compose each group.
+fn group<F: PrimeField, S: FieldShare<F>>(
+    bits: &[MpcField<F, S>],
+    width: usize,
+) -> Vec<MpcField<F, S>> {
+    bits.chunks(width).map(|c| compose(c, 1)).collect()
+}
+
+/// For each pair of bit strings of equal length, least significant first, whether the first is
+/// less than the second, when compared on their lowest `1, 2, ...` bits.
+///
+/// In each pair of bits, one must be public and the other shared. Costs one round of
+/// multiplications per bit after the first.
+fn prefix_less_than<F: PrimeField, S: FieldShare<F>>(
+    a: &[Vec<MpcField<F, S>>],
+    b: &[Vec<MpcField<F, S>>],
+) -> Vec<Vec<MpcField<F, S>>> {
+    assert_eq!(a.len(), b.len());
+    let m = a.first().map_or(0, |a| a.len());
+    assert!(a.iter().chain(b).all(|x| x.len() == m));
+    let one = MpcField::<F, S>::one();
+    let mut out: Vec<Vec<MpcField<F, S>>> = a.iter().map(|_| Vec::with_capacity(m)).collect();
+    for i in 0..m {
+        // a < b on bits 0..=i iff a_i < b_i, or a_i = b_i and a < b on bits 0..i
+        let below: Vec<_> = a.iter().zip(b).map(|(a, b)| (one - a[i]) * b[i]).collect();
+        if i == 0 {
+            for (o, below) in out.iter_mut().zip(below) {
+                o.push(below);
+            }
+            continue;
+        }
+        let mut equal: Vec<_> = a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| one - a[i] - b[i] + (a[i] * b[i]).double())
+            .collect();
+        let lower: Vec<_> = out.iter().map(|o| o[i - 1]).collect();
+        MpcField::batch_product_in_place(&mut equal, &lower);
+        for ((o, below), equal) in out.iter_mut().zip(below).zip(equal) {
+            o.push(below + equal);
+        }
+    }
+    out
+}
+
+/// Whether each `a < b`, for bit strings as in [prefix_less_than].
+fn less_than<F: PrimeField, S: FieldShare<F>>(
+    a: &[Vec<MpcField<F, S>>],
+    b: &[Vec<MpcField<F, S>>],
+) -> Vec<MpcField<F, S>> {
+    prefix_less_than(a, b)
+        .into_iter()
+        .map(|p| p.last().copied().unwrap_or_else(MpcField::zero))
+        .collect()
+}
+
+/// Open `x + r` for each `x < 2^k`, where `r` is a fresh random shared number of `k + σ` bits.
+///
+/// Returns the openings, and the bits of each `r`.
+fn masked_open<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+    xs: &[MpcField<F, S>],
+    k: usize,
+    rng: &mut R,
+) -> (Vec<BigUint>, Vec<Vec<MpcField<F, S>>>) {
+    check_bound::<F>(k);
+    let width = k + STATISTICAL_SECURITY;
+    let mut all = random_bits::<F, S, R>(xs.len() * width, rng).into_iter();
+    let masks: Vec<Vec<_>> = xs
+        .iter()
+        .map(|_| all.by_ref().take(width).collect())
+        .collect();
+    let masked = xs
+        .iter()
+        .zip(&masks)
+        .map(|(x, r)| *x + compose(r, 1))
+        .collect();
+    let opened = budget::exempt(|| MpcField::open_batch(masked));
+    (opened.into_iter().map(to_biguint).collect(), masks)
+}
+
+/// The bits of each shared `x < 2^k`, least significant first.
+///
+/// Costs `k` rounds of multiplications, after drawing the masks.
+pub fn bit_decompose<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+    xs: &[MpcField<F, S>],
+    k: usize,
+    rng: &mut R,
+) -> Vec<Vec<MpcField<F, S>>> {
+    if xs.is_empty() || k == 0 {
+        return xs.iter().map(|_| Vec::new()).collect();
+    }
+    let (c, r) = masked_open(xs, k, rng);
+    let c_bits: Vec<_> = c.iter().map(|c| public_bits::<F, S>(c, k)).collect();
+    let r_low: Vec<_> = r.iter().map(|r| r[..k].to_vec()).collect();
+    // With borrow_i = [c mod 2^i < r mod 2^i], x mod 2^i = c mod 2^i - r mod 2^i + 2^i borrow_i,
+    // so bit i of x is c_i - r_i + 2 borrow_(i+1) - borrow_i.
+    let borrows = prefix_less_than(&c_bits, &r_low);
+    c_bits
+        .iter()
+        .zip(&r_low)
+        .zip(&borrows)
+        .map(|((c, r), borrow)| {
+            (0..k)
+                .map(|i| {
+                    let bit = c[i] - r[i] + borrow[i].double();
+                    if i == 0 {
+                        bit
+                    } else {
+                        bit - borrow[i - 1]
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The `limb_bits`-bit limbs of each shared `x < 2^k`, least significant first.
+///
+/// Costs as much as [bit_decompose].
+pub fn decompose_limbs<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+    xs: &[MpcField<F, S>],
+    k: usize,
+    limb_bits: usize,
+    rng: &mut R,
+) -> Vec<Vec<MpcField<F, S>>> {
+    assert!(limb_bits > 0, "limbs of no bits");
+    bit_decompose(xs, k, rng)
+        .iter()
+        .map(|bits| group(bits, limb_bits))
+        .collect()
+}
+
+/// `(x mod 2^m, x >> m)` for each shared `x < 2^k`, for `0 < m <= k`.
+///
+/// Costs `m` rounds of multiplications, after drawing the masks.
+pub fn split<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+    xs: &[MpcField<F, S>],
+    k: usize,
+    m: usize,
+    rng: &mut R,
+) -> (Vec<MpcField<F, S>>, Vec<MpcField<F, S>>) {
+    assert!(
+        0 < m && m <= k,
+        "cannot split {}-bit values at bit {}",
+        k,
+        m
+    );
+    if xs.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+    let (c, r) = masked_open(xs, k, rng);
+    let c_bits: Vec<_> = c.iter().map(|c| public_bits::<F, S>(c, m)).collect();
+    let r_low: Vec<_> = r.iter().map(|r| r[..m].to_vec()).collect();
+    let borrows = less_than(&c_bits, &r_low);
+    let shift = pow2::<F>(m);
+    let shift_inv = shift.inverse().unwrap();
+    let low: Vec<_> = c
+        .iter()
+        .zip(&r_low)
+        .zip(borrows)
+        .map(|((c, r), borrow)| {
+            let c_low = c % (BigUint::one() << m);
+            MpcField::Public(to_field(&c_low)) - compose(r, 1) + borrow * MpcField::Public(shift)
+        })
+        .collect();
+    let high = xs
+        .iter()
+        .zip(&low)
+        .map(|(x, l)| (*x - *l) * MpcField::Public(shift_inv))
+        .collect();
+    (low, high)
+}
+
+/// The quotient and remainder of each shared `x < 2^k` by the public `modulus`, which must be
+/// smaller than the native field.
+///
+/// The mask's low part must be uniform below the modulus, so it is drawn by rejection: each draw
+/// is compared with the modulus, and only whether it was accepted is opened. Costs about
+/// `2 log(modulus)` rounds of multiplications.
+pub fn reduce<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+    xs: &[MpcField<F, S>],
+    k: usize,
+    modulus: &BigUint,
+    rng: &mut R,
+) -> (Vec<MpcField<F, S>>, Vec<MpcField<F, S>>) {
+    assert!(!modulus.is_zero(), "reduction modulo zero");
+    assert!(
+        *modulus < modulus_of::<F>(),
+        "modulus is not smaller than the native field"
+    );
+    let l = modulus.bits() as usize;
+    // the mask is below 2^(max(k, l) + σ + 1), so the masked value below twice that
+    check_bound::<F>(k.max(l) + 1);
+    if xs.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+    let n = xs.len();
+    let modulus_bits: Vec<MpcField<F, S>> = public_bits(modulus, l);
+
+    // low parts of the masks, uniform in [0, modulus)
+    let mut lows: Vec<Vec<MpcField<F, S>>> = Vec::with_capacity(n);
+    while lows.len() < n {
+        let mut all = random_bits::<F, S, R>((n - lows.len()) * l, rng).into_iter();
+        let draws: Vec<Vec<_>> = (lows.len()..n)
+            .map(|_| all.by_ref().take(l).collect())
+            .collect();
+        let bounds = vec![modulus_bits.clone(); draws.len()];
+        let accepted = budget::exempt(|| MpcField::open_batch(less_than(&draws, &bounds)));
+        lows.extend(
+            draws
+                .into_iter()
+                .zip(accepted)
+                .filter(|(_, a)| a.is_one())
+                .map(|(d, _)| d),
+        );
+    }
+    // high parts, so that each mask is at least 2^(k + σ)
+    let high_bits = k + STATISTICAL_SECURITY + 1 - l.min(k);
+    let mut all = random_bits::<F, S, R>(n * high_bits, rng).into_iter();
+    let modulus_f: F = to_field(modulus);
+    let masked = xs
+        .iter()
+        .zip(&lows)
+        .map(|(x, low)| {
+            let high: Vec<_> = all.by_ref().take(high_bits).collect();
+            *x + compose(low, 1) + compose(&high, 1) * MpcField::Public(modulus_f)
+        })
+        .collect();
+    let c = budget::exempt(|| MpcField::open_batch(masked));
+    let c: Vec<BigUint> = c.into_iter().map(to_biguint).collect();
+
+    // x mod m = (c mod m) - low + m [c mod m < low]
+    let c_low: Vec<BigUint> = c.iter().map(|c| c % modulus).collect();
+    let c_bits: Vec<_> = c_low.iter().map(|c| public_bits::<F, S>(c, l)).collect();
+    let wraps = less_than(&c_bits, &lows);
+    let modulus_inv = modulus_f.inverse().unwrap();
+    let remainders: Vec<_> = c_low
+        .iter()
+        .zip(&lows)
+        .zip(wraps)
+        .map(|((c, low), wrap)| {
+            MpcField::Public(to_field(c)) - compose(low, 1) + wrap * MpcField::Public(modulus_f)
+        })
+        .collect();
+    let quotients = xs
+        .iter()
+        .zip(&remainders)
+        .map(|(x, r)| (*x - *r) * MpcField::Public(modulus_inv))
+        .collect();
+    (quotients, remainders)
+}
+
+/// Carry each number, given by limbs of at most `bound_bits` bits that are `limb_bits` apart,
+/// into `out_limbs` limbs of `limb_bits` bits: the number modulo `2^(limb_bits * out_limbs)`.
+///
+/// Limbs may be public, but not negative. Costs `limb_bits` rounds of multiplications per output
+/// limb.
+pub fn normalize_limbs<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+    limbs: &[Vec<MpcField<F, S>>],
+    limb_bits: usize,
+    bound_bits: usize,
+    out_limbs: usize,
+    rng: &mut R,
+) -> Vec<Vec<MpcField<F, S>>> {
+    assert!(bound_bits >= limb_bits, "limbs are wider than their bound");
+    let mut out: Vec<Vec<MpcField<F, S>>> = limbs
+        .iter()
+        .map(|_| Vec::with_capacity(out_limbs))
+        .collect();
+    let mut carries = vec![MpcField::zero(); limbs.len()];
+    for t in 0..out_limbs {
+        // each carry is below 2^(bound_bits + 1 - limb_bits), so each sum below 2^(bound_bits + 1)
+        let sums: Vec<_> = limbs
+            .iter()
+            .zip(&carries)
+            .map(|(l, c)| l.get(t).copied().unwrap_or_else(MpcField::zero) + *c)
+            .collect();
+        let (low, high) = split(&sums, bound_bits + 1, limb_bits, rng);
+        for (o, l) in out.iter_mut().zip(low) {
+            o.push(l);
+        }
+        carries = high;
+    }
+    out
+}
+
+/// Like [normalize_limbs], for limbs of absolute value below `2^bound_bits`, which may be
+/// negative, of a number that is not.
+///
+/// A multiple of `2^(limb_bits * out_limbs)` is spread over the limbs first, which leaves the
+/// result unchanged, but makes every limb positive.
+fn normalize_signed_limbs<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+    limbs: &[Vec<MpcField<F, S>>],
+    limb_bits: usize,
+    bound_bits: usize,
+    out_limbs: usize,
+    rng: &mut R,
+) -> Vec<Vec<MpcField<F, S>>> {
+    let offset = pow2::<F>(bound_bits);
+    let carried = offset * pow2::<F>(limb_bits);
+    let positive: Vec<Vec<_>> = limbs
+        .iter()
+        .map(|l| {
+            (0..out_limbs)
+                .map(|t| {
+                    let limb = l.get(t).copied().unwrap_or_else(MpcField::zero);
+                    let limb = limb + MpcField::Public(carried);
+                    if t == 0 {
+                        limb
+                    } else {
+                        limb - MpcField::Public(offset)
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    normalize_limbs(
+        &positive,
+        limb_bits,
+        bound_bits + limb_bits + 1,
+        out_limbs,
+        rng,
+    )
+}
+
+/// A quotient and remainder modulo a [NonNativeParams]' modulus, as normalized limbs.
+#[derive(Clone, Debug)]
+pub struct Reduction<F: Field, S: FieldShare<F>> {
+    pub quotient: Vec<MpcField<F, S>>,
+    pub remainder: Vec<MpcField<F, S>>,
+}
+
+/// How elements modulo a non-native `modulus` are split into limbs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NonNativeParams {
+    modulus: BigUint,
+    limb_bits: usize,
+    num_limbs: usize,
+}
+
+impl NonNativeParams {
+    /// Elements modulo `modulus`, as limbs of `limb_bits` bits.
+    ///
+    /// Reducing needs about `3 * limb_bits + log(num_limbs) + σ` bits of native field: for
+    /// BLS12-377's scalar field, limbs of up to 64 bits.
+    pub fn new(modulus: BigUint, limb_bits: usize) -> Self {
+        assert!(modulus > BigUint::one(), "modulus below 2");
+        assert!(limb_bits > 0, "limbs of no bits");
+        let num_limbs = (modulus.bits() as usize + limb_bits - 1) / limb_bits;
+        Self {
+            modulus,
+            limb_bits,
+            num_limbs,
+        }
+    }
+
+    /// Elements of `T`, as limbs of `limb_bits` bits.
+    pub fn for_field<T: PrimeField>(limb_bits: usize) -> Self {
+        Self::new(modulus_of::<T>(), limb_bits)
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    pub fn limb_bits(&self) -> usize {
+        self.limb_bits
+    }
+
+    pub fn num_limbs(&self) -> usize {
+        self.num_limbs
+    }
+
+    fn modulus_bits(&self) -> usize {
+        self.modulus.bits() as usize
+    }
+
+    /// The limbs of `x`, which must fit in [NonNativeParams::num_limbs] of them.
+    pub fn to_limbs<F: PrimeField>(&self, x: &BigUint) -> Vec<F> {
+        self.to_n_limbs(x, self.num_limbs)
+    }
+
+    fn to_n_limbs<F: PrimeField>(&self, x: &BigUint, n: usize) -> Vec<F> {
+        assert!(
+            x.bits() as usize <= n * self.limb_bits,
+            "value does not fit in {} limbs",
+            n
+        );
+        let mask = (BigUint::one() << self.limb_bits) - 1u8;
+        (0..n)
+            .map(|i| to_field(&((x >> (i * self.limb_bits)) & &mask)))
+            .collect()
+    }
+
+    /// The number that `limbs` represent, whether or not they are normalized.
+    pub fn from_limbs<F: PrimeField>(&self, limbs: &[F]) -> BigUint {
+        limbs.iter().rev().fold(BigUint::zero(), |acc, l| {
+            (acc << self.limb_bits) + to_biguint(*l)
+        })
+    }
+
+    /// The normalized limbs of each element of `T`, whose modulus this must be for: public limbs
+    /// for a public element, shared ones for a shared element.
+    ///
+    /// Shares of `T` are not shares of limbs, so a shared `x` is masked. Each party draws a random
+    /// `r_i` of `T`, and shares both it and the limbs of `modulus - r_i`. The parties open
+    /// `c = x + Σ r_i`, which is uniform whatever `x` is. Then `c + Σ (modulus - r_i)` is `x` plus
+    /// a multiple of the modulus, below `n + 1` moduli for `n` parties, and is reduced as by
+    /// [NonNativeParams::reduce], which it costs about as much as.
+    ///
+    /// Nothing checks that a party shared the limbs of its own `r_i`, and a dishonest one can
+    /// shift the result, so a conversion is only as sound as the parties are honest-but-curious.
+    pub fn share_limbs<T, TS, F, S, R>(
+        &self,
+        xs: &[MpcField<T, TS>],
+        rng: &mut R,
+    ) -> Vec<Vec<MpcField<F, S>>>
+    where
+        T: PrimeField,
+        TS: FieldShare<T>,
+        F: PrimeField + SquareRootField,
+        S: FieldShare<F>,
+        R: Rng,
+    {
+        assert!(
+            self.modulus == modulus_of::<T>(),
+            "these limbs are not for this field"
+        );
+        let shared: Vec<_> = xs
+            .iter()
+            .filter(|x| matches!(x, MpcField::Shared(_)))
+            .copied()
+            .collect();
+        let mut unmasked = self.unmask(&shared, rng).into_iter();
+        xs.iter()
+            .map(|x| match x {
+                MpcField::Public(x) => self
+                    .to_limbs(&to_biguint(*x))
+                    .into_iter()
+                    .map(MpcField::Public)
+                    .collect(),
+                MpcField::Shared(_) => unmasked.next().unwrap(),
+            })
+            .collect()
+    }
+
+    /// [NonNativeParams::share_limbs], for shared elements only.
+    fn unmask<T, TS, F, S, R>(
+        &self,
+        xs: &[MpcField<T, TS>],
+        rng: &mut R,
+    ) -> Vec<Vec<MpcField<F, S>>>
+    where
+        T: PrimeField,
+        TS: FieldShare<T>,
+        F: PrimeField + SquareRootField,
+        S: FieldShare<F>,
+        R: Rng,
+    {
+        if xs.is_empty() {
+            return Vec::new();
+        }
+        let parties = Net::n_parties();
+        assert!(
+            self.modulus > BigUint::from(parties + 1),
+            "modulus too small to mask"
+        );
+        let (w, n) = (self.limb_bits, self.num_limbs);
+        let rs: Vec<T> = xs.iter().map(|_| T::rand(rng)).collect();
+        let complements: Vec<F> = rs
+            .iter()
+            .flat_map(|r| self.to_limbs::<F>(&(&self.modulus - to_biguint(*r))))
+            .collect();
+        let mut masks = vec![MpcField::<T, TS>::zero(); xs.len()];
+        let mut sums = vec![MpcField::<F, S>::zero(); xs.len() * n];
+        for i in 0..parties {
+            let r_i = MpcField::party_share_batch(i, rs.clone(), rng);
+            for (mask, r) in masks.iter_mut().zip(r_i) {
+                *mask += r;
+            }
+            let c_i = MpcField::party_share_batch(i, complements.clone(), rng);
+            for (sum, c) in sums.iter_mut().zip(c_i) {
+                *sum += c;
+            }
+        }
+        let masked = xs.iter().zip(&masks).map(|(x, r)| *x + *r).collect();
+        let opened = budget::exempt(|| MpcField::open_batch(masked));
+
+        // each limb of c + Σ (modulus - r_i) is below (parties + 1) 2^w, and the number below
+        // (parties + 1) moduli
+        let bound = w + bits_of(parties);
+        let wide = (self.modulus_bits() + bits_of(parties) + w - 1) / w;
+        let unreduced: Vec<Vec<_>> = opened
+            .iter()
+            .zip(sums.chunks(n))
+            .map(|(c, sum)| {
+                let c: Vec<F> = self.to_limbs(&to_biguint(*c));
+                (0..wide)
+                    .map(|t| match (c.get(t), sum.get(t)) {
+                        (Some(c), Some(s)) => *s + MpcField::Public(*c),
+                        _ => MpcField::zero(),
+                    })
+                    .collect()
+            })
+            .collect();
+        let unreduced = normalize_limbs(&unreduced, w, bound, wide, rng);
+        self.reduce(&unreduced, rng)
+            .into_iter()
+            .map(|r| r.remainder)
+            .collect()
+    }
+
+    /// The bits of each element of `T`, least significant first, as many as the modulus has:
+    /// the bits of [NonNativeParams::share_limbs]' limbs, for the witnesses of a gadget's bits or
+    /// bytes. Costs `limb_bits` more rounds of multiplications.
+    pub fn share_bits<T, TS, F, S, R>(
+        &self,
+        xs: &[MpcField<T, TS>],
+        rng: &mut R,
+    ) -> Vec<Vec<MpcField<F, S>>>
+    where
+        T: PrimeField,
+        TS: FieldShare<T>,
+        F: PrimeField + SquareRootField,
+        S: FieldShare<F>,
+        R: Rng,
+    {
+        let limbs = self.share_limbs(xs, rng);
+        let flat: Vec<_> = limbs.iter().flatten().copied().collect();
+        let mut bits = bit_decompose(&flat, self.limb_bits, rng).into_iter();
+        limbs
+            .iter()
+            .map(|l| {
+                bits.by_ref()
+                    .take(l.len())
+                    .flatten()
+                    .take(self.modulus_bits())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Normalized `limbs`, packed as densely as `F` allows: as many consecutive limbs into each
+    /// element as its capacity holds, least significant first. This is linear, so it is local.
+    pub fn pack<F: PrimeField, S: FieldShare<F>>(
+        &self,
+        limbs: &[MpcField<F, S>],
+    ) -> Vec<MpcField<F, S>> {
+        let per = F::Params::CAPACITY as usize / self.limb_bits;
+        assert!(
+            per > 0,
+            "{}-bit limbs do not fit in a {}-bit field",
+            self.limb_bits,
+            F::Params::CAPACITY
+        );
+        limbs
+            .chunks(per)
+            .map(|c| compose(c, self.limb_bits))
+            .collect()
+    }
+
+    /// The quotient and remainder of each product `a[i] * b[i]` by the modulus, where `a[i]`
+    /// and `b[i]` are normalized limbs of reduced elements.
+    pub fn mul<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+        &self,
+        a: &[Vec<MpcField<F, S>>],
+        b: &[Vec<MpcField<F, S>>],
+        rng: &mut R,
+    ) -> Vec<Reduction<F, S>> {
+        assert_eq!(a.len(), b.len());
+        let n = self.num_limbs;
+        assert!(
+            a.iter().chain(b).all(|x| x.len() == n),
+            "expected {} limbs",
+            n
+        );
+        // every limb product of every pair, in one round
+        let mut lhs = Vec::with_capacity(a.len() * n * n);
+        let mut rhs = Vec::with_capacity(a.len() * n * n);
+        for (a, b) in a.iter().zip(b) {
+            for x in a {
+                lhs.extend(std::iter::repeat(*x).take(n));
+                rhs.extend_from_slice(b);
+            }
+        }
+        MpcField::batch_product_in_place(&mut lhs, &rhs);
+        let products: Vec<Vec<_>> = lhs
+            .chunks(n * n)
+            .map(|p| {
+                let mut limbs = vec![MpcField::zero(); 2 * n - 1];
+                for (ij, y) in p.iter().enumerate() {
+                    limbs[ij / n + ij % n] += *y;
+                }
+                limbs
+            })
+            .collect();
+        let bound = 2 * self.limb_bits + bits_of(n);
+        let products = normalize_limbs(&products, self.limb_bits, bound, 2 * n, rng);
+        self.reduce(&products, rng)
+    }
+
+    /// The quotient and remainder of each number by the modulus, given normalized limbs of a
+    /// number below the modulus squared, such as the product of two reduced elements.
+    ///
+    /// This is Barrett reduction: the quotient is estimated from the top bits of the number, to
+    /// within two, and corrected by comparing what remains with the modulus.
+    pub fn reduce<F: PrimeField + SquareRootField, S: FieldShare<F>, R: Rng>(
+        &self,
+        xs: &[Vec<MpcField<F, S>>],
+        rng: &mut R,
+    ) -> Vec<Reduction<F, S>> {
+        if xs.is_empty() {
+            return Vec::new();
+        }
+        let w = self.limb_bits;
+        let bits = self.modulus_bits();
+        let n = self.num_limbs;
+        let zero = MpcField::<F, S>::zero();
+
+        // the bits of each number
+        let flat: Vec<_> = xs.iter().flatten().copied().collect();
+        let mut flat_bits = bit_decompose(&flat, w, rng).into_iter();
+        let x_bits: Vec<Vec<_>> = xs
+            .iter()
+            .map(|x| {
+                let mut b: Vec<_> = flat_bits.by_ref().take(x.len()).flatten().collect();
+                b.resize(2 * bits, zero);
+                b
+            })
+            .collect();
+
+        // estimate q = ((x >> (bits - 1)) * mu) >> (bits + 1), for mu = 2^(2 bits) / modulus
+        let mu = (BigUint::one() << (2 * bits)) / &self.modulus;
+        let mu_limbs: Vec<F> = self.to_n_limbs(&mu, (bits + 2 + w - 1) / w);
+        let scaled: Vec<Vec<_>> = x_bits
+            .iter()
+            .map(|b| convolve(&group(&b[bits - 1..2 * bits], w), &mu_limbs))
+            .collect();
+        let bound = 2 * w + bits_of(mu_limbs.len());
+        let scaled_limbs = (2 * bits + 3 + w - 1) / w;
+        let scaled = normalize_limbs(&scaled, w, bound, scaled_limbs, rng);
+        let flat: Vec<_> = scaled.iter().flatten().copied().collect();
+        let mut flat_bits = bit_decompose(&flat, w, rng).into_iter();
+        let estimates: Vec<Vec<_>> = scaled
+            .iter()
+            .map(|s| {
+                let b: Vec<_> = flat_bits.by_ref().take(s.len()).flatten().collect();
+                // the estimate is at most the quotient, which is below the modulus
+                group(&b[bits + 1..2 * bits + 1], w)
+            })
+            .collect();
+
+        // x - estimate * modulus is below 3 modulus, so below 2^(bits + 2)
+        let wide = (bits + 2 + w - 1) / w;
+        let modulus_limbs: Vec<F> = self.to_limbs(&self.modulus);
+        let rests: Vec<Vec<_>> = xs
+            .iter()
+            .zip(&estimates)
+            .map(|(x, q)| {
+                let qm = convolve(q, &modulus_limbs);
+                (0..wide)
+                    .map(|t| {
+                        let x_t = x.get(t).copied().unwrap_or(zero);
+                        x_t - qm.get(t).copied().unwrap_or(zero)
+                    })
+                    .collect()
+            })
+            .collect();
+        let rests = normalize_signed_limbs(&rests, w, 2 * w + bits_of(n) + 1, wide, rng);
+
+        // subtract the modulus once for each of `modulus` and `2 modulus` that the rest exceeds
+        let flat: Vec<_> = rests.iter().flatten().copied().collect();
+        let mut flat_bits = bit_decompose(&flat, w, rng).into_iter();
+        let mut rest_bits = Vec::with_capacity(2 * xs.len());
+        for r in &rests {
+            let b: Vec<_> = flat_bits
+                .by_ref()
+                .take(r.len())
+                .flatten()
+                .take(bits + 2)
+                .collect();
+            rest_bits.push(b.clone());
+            rest_bits.push(b);
+        }
+        let two_modulus = &self.modulus << 1;
+        let bounds: Vec<Vec<_>> = (0..xs.len())
+            .flat_map(|_| {
+                vec![
+                    public_bits(&self.modulus, bits + 2),
+                    public_bits(&two_modulus, bits + 2),
+                ]
+            })
+            .collect();
+        let below = less_than(&rest_bits, &bounds);
+        let excess: Vec<_> = below
+            .chunks(2)
+            .map(|b| MpcField::Public(F::from(2u64)) - b[0] - b[1])
+            .collect();
+
+        let remainders: Vec<Vec<_>> = rests
+            .iter()
+            .zip(&excess)
+            .map(|(r, e)| {
+                r.iter()
+                    .enumerate()
+                    .map(|(t, r)| {
+                        let m_t = modulus_limbs.get(t).copied().unwrap_or_else(F::zero);
+                        *r - *e * MpcField::Public(m_t)
+                    })
+                    .collect()
+            })
+            .collect();
+        let remainders = normalize_signed_limbs(&remainders, w, w + 2, n, rng);
+        let quotients: Vec<Vec<_>> = estimates
+            .into_iter()
+            .zip(&excess)
+            .map(|(mut q, e)| {
+                q[0] += *e;
+                q
+            })
+            .collect();
+        let quotients = normalize_limbs(&quotients, w, w + 1, n, rng);
+        quotients
+            .into_iter()
+            .zip(remainders)
+            .map(|(quotient, remainder)| Reduction {
+                quotient,
+                remainder,
+            })
+            .collect()
+    }
+}
+
+/// An element of a field `T`, public or shared, as a circuit over another field represents it:
+/// by limbs of `limb_bits` bits.
+///
+/// By [ToConstraintField], it gives its limbs, as [NonNativeParams::share_limbs] does. A slice of
+/// elements converts in one batch, which takes as many rounds as a single one, and every party
+/// must convert the same elements in the same order. By [ToBytes], it gives the bytes of a public
+/// value, as `T` does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NonNative<T: PrimeField, S: FieldShare<T>> {
+    pub value: MpcField<T, S>,
+    pub limb_bits: usize,
+}
+
+impl<T: PrimeField, S: FieldShare<T>> NonNative<T, S> {
+    pub fn new(value: MpcField<T, S>, limb_bits: usize) -> Self {
+        Self { value, limb_bits }
+    }
+}
+
+/// The limbs of every element of `xs`, which must have limbs of the same width.
+fn limbs_of<T, TS, F, S>(xs: &[NonNative<T, TS>]) -> Vec<Vec<MpcField<F, S>>>
+where
+    T: PrimeField,
+    TS: FieldShare<T>,
+    F: PrimeField + SquareRootField,
+    S: FieldShare<F>,
+{
+    let limb_bits = match xs.first() {
+        Some(x) => x.limb_bits,
+        None => return Vec::new(),
+    };
+    assert!(
+        xs.iter().all(|x| x.limb_bits == limb_bits),
+        "elements with limbs of different widths"
+    );
+    let values: Vec<_> = xs.iter().map(|x| x.value).collect();
+    NonNativeParams::for_field::<T>(limb_bits)
+        .share_limbs(&values, &mut seed::rng(b"non-native limbs"))
+}
+
+impl<T, TS, F, S> ToConstraintField<MpcField<F, S>> for [NonNative<T, TS>]
+where
+    T: PrimeField,
+    TS: FieldShare<T>,
+    F: PrimeField + SquareRootField,
+    S: FieldShare<F>,
+{
+    fn to_field_elements(&self) -> Option<Vec<MpcField<F, S>>> {
+        Some(limbs_of(self).into_iter().flatten().collect())
+    }
+}
+
+impl<T, TS, F, S> ToConstraintField<MpcField<F, S>> for NonNative<T, TS>
+where
+    T: PrimeField,
+    TS: FieldShare<T>,
+    F: PrimeField + SquareRootField,
+    S: FieldShare<F>,
+{
+    fn to_field_elements(&self) -> Option<Vec<MpcField<F, S>>> {
+        std::slice::from_ref(self).to_field_elements()
+    }
+}
+
+impl<T: PrimeField, S: FieldShare<T>> ToBytes for NonNative<T, S> {
+    fn write<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.value.write(writer)
+    }
+}
+
+/// What a sponge over `F` absorbs for `xs`: their limbs, all packed together by
+/// [NonNativeParams::pack], so that it absorbs fewer elements than by [ToConstraintField].
+pub fn absorb<T, TS, F, S>(xs: &[NonNative<T, TS>]) -> Vec<MpcField<F, S>>
+where
+    T: PrimeField,
+    TS: FieldShare<T>,
+    F: PrimeField + SquareRootField,
+    S: FieldShare<F>,
+{
+    let limbs: Vec<_> = limbs_of(xs).into_iter().flatten().collect();
+    match xs.first() {
+        Some(x) => NonNativeParams::for_field::<T>(x.limb_bits).pack(&limbs),
+        None => Vec::new(),
+    }
+}
@@ -35,6 +35,16 @@ pub trait Reveal: Sized {
     fn king_share_batch<R: Rng>(bs: Vec<Self::Base>, rng: &mut R) -> Vec<Self> {
         bs.into_iter().map(|b| Self::king_share(b, rng)).collect()
     }
+    /// Have party `owner` share their `b` values, and send shares to all parties.
+    ///
+    /// The other parties' `bs` are ignored.
+    fn party_share_batch<R: Rng>(owner: usize, bs: Vec<Self::Base>, rng: &mut R) -> Vec<Self> {
+        if owner == 0 {
+            Self::king_share_batch(bs, rng)
+        } else {
+            unimplemented!("No party share for {}", std::any::type_name::<Self>())
+        }
+    }
     /// Initialize the network protocol associated with this sharing system, if it is not
     /// initialized.
     fn init_protocol() {}
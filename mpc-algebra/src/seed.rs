@@ -0,0 +1,77 @@
+//! A deterministic mode, for regression tests.
+//!
+//! Most randomness comes from the rngs that callers pass in, but some is drawn locally, from the
+//! OS: the openings of commitments in [MpcSerNet::atomic_broadcast](crate::channel::MpcSerNet),
+//! the seeds of the GSZ20 random-sharing protocol, and the coins of
+//! [MpcTranscript::coin_flip](crate::transcript::MpcTranscript::coin_flip). Once a session
+//! [set_seed]s, those are derived from the seed instead, and callers can draw their own rngs from
+//! it with [rng]. Two runs with the same seed, parties, and inputs then send the same messages and
+//! produce byte-identical proofs, which golden-file tests can check.
+//!
+//! The derived randomness is predictable, so this is for testing only.
+use lazy_static::lazy_static;
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use sha2::{Digest, Sha256};
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+struct Stream {
+    seed: [u8; 32],
+    /// How many rngs have been drawn.
+    drawn: u64,
+}
+
+lazy_static! {
+    /// By session tag.
+    static ref STREAMS: Mutex<HashMap<u64, Stream>> = Mutex::new(HashMap::new());
+}
+
+/// Derive the current session's randomness from `seed`, or with `None`, from the OS again.
+pub fn set_seed(seed: Option<[u8; 32]>) {
+    let mut streams = STREAMS.lock().unwrap();
+    let tag = Net::session_tag();
+    match seed {
+        Some(seed) => {
+            streams.insert(tag, Stream { seed, drawn: 0 });
+        }
+        None => {
+            streams.remove(&tag);
+        }
+    }
+}
+
+/// The current session's seed, if it has one.
+pub fn seed() -> Option<[u8; 32]> {
+    let streams = STREAMS.lock().unwrap();
+    streams.get(&Net::session_tag()).map(|s| s.seed)
+}
+
+/// A fresh rng, private to this party.
+///
+/// With a seed, it is derived from the seed, this party's id, `label`, and how many rngs were
+/// drawn before it, so successive rngs differ, but the `i`th is the same from run to run.
+/// Without, it is seeded from the OS.
+pub fn rng(label: &[u8]) -> StdRng {
+    let mut streams = STREAMS.lock().unwrap();
+    match streams.get_mut(&Net::session_tag()) {
+        Some(s) => {
+            let seed = Sha256::new()
+                .chain(&s.seed)
+                .chain(&(Net::party_id() as u64).to_le_bytes())
+                .chain(&s.drawn.to_le_bytes())
+                .chain(label)
+                .finalize();
+            s.drawn += 1;
+            StdRng::from_seed(seed.into())
+        }
+        None => StdRng::from_rng(rand::thread_rng()).unwrap(),
+    }
+}
+
+/// Fill `bytes` with randomness from a fresh [rng].
+pub(crate) fn fill_bytes(label: &[u8], bytes: &mut [u8]) {
+    rng(label).fill_bytes(bytes)
+}
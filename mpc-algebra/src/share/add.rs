@@ -18,12 +18,14 @@ use std::fmt::{self, Debug, Display, Formatter};
 use std::hash::Hash;
 use std::io::{self, Read, Write};
 use std::marker::PhantomData;
+use zeroize::Zeroize;
 
 use mpc_net::{MpcNet, MpcMultiNet as Net};
 use crate::channel::MpcSerNet;
 
 use super::field::{
-    DenseOrSparsePolynomial, DensePolynomial, ExtFieldShare, FieldShare, SparsePolynomial,
+    DenseOrSparsePolynomial, DensePolynomial, ExtFieldShare, FieldShare, MulScratch, SmallScalar,
+    SparsePolynomial,
 };
 use super::group::GroupShare;
 use super::pairing::{AffProjShare, PairingShare};
@@ -36,6 +38,12 @@ pub struct AdditiveFieldShare<T> {
     pub val: T,
 }
 
+impl<T: Zeroize> Zeroize for AdditiveFieldShare<T> {
+    fn zeroize(&mut self) {
+        self.val.zeroize();
+    }
+}
+
 impl<F: Field> AdditiveFieldShare<F> {
     fn poly_share<'a>(
         p: DenseOrSparsePolynomial<Self>,
@@ -103,24 +111,32 @@ impl<F: Field> Reveal for AdditiveFieldShare<F> {
         Self::from_add_shared(Net::recv_from_king( if Net::am_king() { Some(r) } else { None }))
     }
     fn king_share_batch<R: Rng>(f: Vec<Self::Base>, rng: &mut R) -> Vec<Self> {
-        let mut rs: Vec<Vec<Self::Base>> =
-            (0..(Net::n_parties()-1)).map(|_| {
-            (0..f.len()).map(|_| {
-                F::rand(rng)
-            }).collect()
-        }).collect();
-        let final_shares: Vec<Self::Base> = (0..rs[0].len()).map(|i| {
-            f[i] - &rs.iter().map(|r| &r[i]).sum()
-        }).collect();
-        rs.push(final_shares);
-        Net::recv_from_king(if Net::am_king() { Some(rs) } else {None}).into_iter().map(Self::from_add_shared).collect()
+        Self::party_share_batch(0, f, rng)
+    }
+    fn party_share_batch<R: Rng>(owner: usize, f: Vec<Self::Base>, rng: &mut R) -> Vec<Self> {
+        let shares = if Net::party_id() == owner {
+            let mut rs: Vec<Vec<Self::Base>> =
+                (0..(Net::n_parties()-1)).map(|_| {
+                (0..f.len()).map(|_| {
+                    F::rand(rng)
+                }).collect()
+            }).collect();
+            let final_shares: Vec<Self::Base> = (0..f.len()).map(|i| {
+                f[i] - &rs.iter().map(|r| &r[i]).sum()
+            }).collect();
+            rs.push(final_shares);
+            Some(rs)
+        } else {
+            None
+        };
+        Net::recv_vec_from(owner, shares).into_iter().map(Self::from_add_shared).collect()
     }
 }
 
 impl<F: Field> FieldShare<F> for AdditiveFieldShare<F> {
     fn batch_open(selfs: impl IntoIterator<Item = Self>) -> Vec<F> {
         let self_vec: Vec<F> = selfs.into_iter().map(|s| s.val).collect();
-        let all_vals = Net::broadcast(&self_vec);
+        let all_vals = Net::broadcast_vec(&self_vec);
         (0..self_vec.len()).map(|i| all_vals.iter().map(|v| &v[i]).sum()).collect()
     }
     fn add(&mut self, other: &Self) -> &mut Self {
@@ -172,6 +188,12 @@ pub struct AdditiveGroupShare<T, M> {
     _phants: PhantomData<M>,
 }
 
+impl<T: Zeroize, M> Zeroize for AdditiveGroupShare<T, M> {
+    fn zeroize(&mut self) {
+        self.val.zeroize();
+    }
+}
+
 impl<G: Group, M> Reveal for AdditiveGroupShare<G, M> {
     type Base = G;
 
@@ -206,11 +228,11 @@ impl<G: Group, M> Reveal for AdditiveGroupShare<G, M> {
                 Self::Base::rand(rng)
             }).collect()
         }).collect();
-        let final_shares: Vec<Self::Base> = (0..rs[0].len()).map(|i| {
+        let final_shares: Vec<Self::Base> = (0..f.len()).map(|i| {
             f[i] - &rs.iter().map(|r| &r[i]).sum()
         }).collect();
         rs.push(final_shares);
-        Net::recv_from_king(if Net::am_king() { Some(rs) } else {None}).into_iter().map(Self::from_add_shared).collect()
+        Net::recv_vec_from(0, if Net::am_king() { Some(rs) } else {None}).into_iter().map(Self::from_add_shared).collect()
     }
 }
 
@@ -219,7 +241,7 @@ impl<G: Group, M: Msm<G, G::ScalarField>> GroupShare<G> for AdditiveGroupShare<G
 
     fn batch_open(selfs: impl IntoIterator<Item = Self>) -> Vec<G> {
         let self_vec: Vec<G> = selfs.into_iter().map(|s| s.val).collect();
-        let all_vals = Net::broadcast(&self_vec);
+        let all_vals = Net::broadcast_vec(&self_vec);
         (0..self_vec.len()).map(|i| all_vals.iter().map(|v| &v[i]).sum()).collect()
     }
 
@@ -257,6 +279,19 @@ impl<G: Group, M: Msm<G, G::ScalarField>> GroupShare<G> for AdditiveGroupShare<G
         let scalars: Vec<G::ScalarField> = scalars.into_iter().map(|s| s.val.clone()).collect();
         Self::from_add_shared(M::msm(bases, &scalars))
     }
+
+    fn multi_scale_pub_group_by(
+        scalars: &[Self::FieldShare],
+        msm: impl Fn(&[G::ScalarField]) -> G,
+    ) -> Self {
+        let scalars: Vec<G::ScalarField> = scalars.iter().map(|s| s.val).collect();
+        Self::from_add_shared(msm(&scalars))
+    }
+
+    fn msm_pub_scalar_shared_base(bases: &[Self], scalars: &[G::ScalarField]) -> Self {
+        let bases: Vec<G> = bases.iter().map(|b| b.val).collect();
+        Self::from_add_shared(M::msm(&bases, scalars))
+    }
 }
 
 macro_rules! impl_basics {
@@ -282,11 +317,11 @@ macro_rules! impl_basics {
             }
         }
         impl<T: $bound> CanonicalSerialize for $share<T> {
-            fn serialize<W: Write>(&self, _writer: W) -> Result<(), SerializationError> {
-                unimplemented!("serialize")
+            fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+                self.val.serialize(writer)
             }
             fn serialized_size(&self) -> usize {
-                unimplemented!("serialized_size")
+                self.val.serialized_size()
             }
         }
         impl<T: $bound> CanonicalSerializeWithFlags for $share<T> {
@@ -303,8 +338,10 @@ macro_rules! impl_basics {
             }
         }
         impl<T: $bound> CanonicalDeserialize for $share<T> {
-            fn deserialize<R: Read>(_reader: R) -> Result<Self, SerializationError> {
-                unimplemented!("deserialize")
+            fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+                Ok(Self {
+                    val: T::deserialize(reader)?,
+                })
             }
         }
         impl<T: $bound> CanonicalDeserializeWithFlags for $share<T> {
@@ -344,11 +381,20 @@ macro_rules! impl_basics_2_param {
             }
         }
         impl<T: $bound, M> CanonicalSerialize for $share<T, M> {
-            fn serialize<W: Write>(&self, _writer: W) -> Result<(), SerializationError> {
-                unimplemented!("serialize")
+            fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+                self.val.serialize(writer)
             }
             fn serialized_size(&self) -> usize {
-                unimplemented!("serialized_size")
+                self.val.serialized_size()
+            }
+            fn serialize_uncompressed<W: Write>(
+                &self,
+                writer: W,
+            ) -> Result<(), SerializationError> {
+                self.val.serialize_uncompressed(writer)
+            }
+            fn uncompressed_size(&self) -> usize {
+                self.val.uncompressed_size()
             }
         }
         impl<T: $bound, M> CanonicalSerializeWithFlags for $share<T, M> {
@@ -365,8 +411,17 @@ macro_rules! impl_basics_2_param {
             }
         }
         impl<T: $bound, M> CanonicalDeserialize for $share<T, M> {
-            fn deserialize<R: Read>(_reader: R) -> Result<Self, SerializationError> {
-                unimplemented!("deserialize")
+            fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+                Ok(Self {
+                    val: T::deserialize(reader)?,
+                    _phants: PhantomData,
+                })
+            }
+            fn deserialize_uncompressed<R: Read>(reader: R) -> Result<Self, SerializationError> {
+                Ok(Self {
+                    val: T::deserialize_uncompressed(reader)?,
+                    _phants: PhantomData,
+                })
             }
         }
         impl<T: $bound, M> CanonicalDeserializeWithFlags for $share<T, M> {
@@ -408,6 +463,12 @@ pub struct MulFieldShare<T> {
     pub val: T,
 }
 
+impl<T: Zeroize> Zeroize for MulFieldShare<T> {
+    fn zeroize(&mut self) {
+        self.val.zeroize();
+    }
+}
+
 impl<F: Field> Reveal for MulFieldShare<F> {
     type Base = F;
 
@@ -433,7 +494,7 @@ impl<F: Field> FieldShare<F> for MulFieldShare<F> {
     }
     fn batch_open(selfs: impl IntoIterator<Item = Self>) -> Vec<F> {
         let self_vec: Vec<F> = selfs.into_iter().map(|s| s.val).collect();
-        let all_vals = Net::broadcast(&self_vec);
+        let all_vals = Net::broadcast_vec(&self_vec);
         (0..self_vec.len()).map(|i| all_vals.iter().map(|v| &v[i]).product()).collect()
     }
 
@@ -441,6 +502,10 @@ impl<F: Field> FieldShare<F> for MulFieldShare<F> {
         unimplemented!("add for MulFieldShare")
     }
 
+    fn scale_small(&mut self, c: SmallScalar) -> &mut Self {
+        self.scale(&c.value())
+    }
+
     fn scale(&mut self, other: &F) -> &mut Self {
         if Net::am_king() {
             self.val *= other;
@@ -469,6 +534,17 @@ impl<F: Field> FieldShare<F> for MulFieldShare<F> {
         xs
     }
 
+    fn batch_mul_in_place<S: BeaverSource<Self, Self, Self>>(
+        selfs: &mut [Self],
+        others: &[Self],
+        _source: &mut S,
+        _scratch: &mut MulScratch<F, Self>,
+    ) {
+        for (x, y) in selfs.iter_mut().zip(others.iter()) {
+            x.val *= y.val;
+        }
+    }
+
     fn inv<S: BeaverSource<Self, Self, Self>>(mut self, _source: &mut S) -> Self {
         self.val = self.val.inverse().unwrap();
         self
@@ -515,6 +591,14 @@ macro_rules! groups_share {
                 g.map_homo(|s| s.into())
             }
 
+            fn sh_batch_proj_to_aff(gs: &[Self::ProjectiveShare]) -> Vec<Self::AffineShare> {
+                let vals: Vec<E::$proj> = gs.iter().map(|g| g.val).collect();
+                E::$proj::batch_normalization_into_affine(&vals)
+                    .into_iter()
+                    .map(Reveal::from_add_shared)
+                    .collect()
+            }
+
             fn add_sh_proj_sh_aff(
                 mut a: Self::ProjectiveShare,
                 o: &Self::AffineShare,
@@ -0,0 +1,180 @@
+//! Identifiable abort for SPDZ: when a MAC check fails, find out which party cheated.
+//!
+//! A failed MAC check normally tells the parties only that someone sent a bad share. With
+//! [set_identifiable_abort], each opening starts with every party committing to its shares and
+//! MAC shares, once for each other party (with randomness of its own for each), before anyone
+//! sees the others' shares. If the MAC check then fails, the parties open those commitments, and
+//! each party judges every party by what it was sent: a party is to blame if its shares do not
+//! match its commitment, if its MAC shares are not its shares times the MAC key, or if its part of
+//! the MAC check does not follow from them. The parties then abort with a [MacCheckFailure]
+//! naming the culprits.
+//!
+//! This costs a round of commitments per opening, and a round of openings after a failure.
+//!
+//! Judging a party on its own works because the MAC key is public in this prototype (see
+//! [mac](super::spdz::mac)), so every party's MAC share is its share times the key. With a secret
+//! key, identifying the cheater would need a MAC per pair of parties, as in BDOZ.
+//!
+//! Only [SpdzFieldShare](super::spdz::SpdzFieldShare) and
+//! [SpdzGroupShare](super::spdz::SpdzGroupShare) openings are covered; those of multiplicative
+//! shares (in the pairing's target group) still abort without a culprit.
+use ark_ff::{Field, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use digest::Digest;
+use lazy_static::lazy_static;
+use log::error;
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use sha2::Sha256;
+
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::ops::Sub;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Mutex;
+
+use super::spdz::{mac, mac_share_of};
+use crate::channel::MpcSerNet;
+use crate::ct;
+
+lazy_static! {
+    /// By session tag.
+    static ref IDENTIFIABLE: Mutex<HashSet<u64>> = Mutex::new(HashSet::new());
+}
+
+/// Randomness bytes per commitment.
+const RAND_BYTES: usize = 32;
+/// Bytes per commitment.
+const COMMIT_BYTES: usize = 32;
+
+/// Identify the cheater when a MAC check fails in the current session, at the cost of extra
+/// communication for every opening. All parties must agree on it.
+pub fn set_identifiable_abort(on: bool) {
+    let mut sessions = IDENTIFIABLE.lock().unwrap();
+    let tag = Net::session_tag();
+    if on {
+        sessions.insert(tag);
+    } else {
+        sessions.remove(&tag);
+    }
+}
+
+/// Whether the current session identifies the cheater when a MAC check fails.
+pub fn identifiable_abort() -> bool {
+    let sessions = IDENTIFIABLE.lock().unwrap();
+    sessions.contains(&Net::session_tag())
+}
+
+/// A failed MAC check, with the parties to blame for it.
+///
+/// In identifiable-abort mode, a failed MAC check unwinds with this as the payload. Use
+/// [catch_mac_failure] to get it back as a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MacCheckFailure {
+    /// The parties whose openings were inconsistent, in order. Empty if every party opened
+    /// consistently, but the MAC check failed anyway.
+    pub culprits: Vec<usize>,
+}
+
+impl Display for MacCheckFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.culprits[..] {
+            [] => write!(f, "MAC check failed, with no party to blame"),
+            [p] => write!(f, "MAC check failed: party {} sent inconsistent shares", p),
+            ps => write!(
+                f,
+                "MAC check failed: parties {:?} sent inconsistent shares",
+                ps
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MacCheckFailure {}
+
+/// Run `f`, returning the failure if a MAC check fails in identifiable-abort mode during it.
+///
+/// Other panics pass through unchanged.
+pub fn catch_mac_failure<T>(f: impl FnOnce() -> T) -> Result<T, MacCheckFailure> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        match payload.downcast::<MacCheckFailure>() {
+            Ok(failure) => *failure,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    })
+}
+
+fn to_bytes<T: CanonicalSerialize + ?Sized>(x: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    x.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+fn commit(opening: &[u8], rand: &[u8]) -> Vec<u8> {
+    Sha256::new().chain(opening).chain(rand).finalize().to_vec()
+}
+
+/// Open values of which we hold `shares`, with MAC shares `macs`, committing to both first, and
+/// identify the culprits if the MAC check fails. `scale` multiplies a value by a key.
+pub(crate) fn open<T, K>(shares: Vec<T>, macs: Vec<T>, scale: impl Fn(&T, K) -> T) -> Vec<T>
+where
+    T: CanonicalSerialize + CanonicalDeserialize + Clone + Zero + Sub<Output = T>,
+    K: Field,
+{
+    let n_parties = Net::n_parties();
+    let me = Net::party_id();
+    let opening = to_bytes(&(shares.clone(), macs.clone()));
+    let mut rands = vec![0u8; n_parties * RAND_BYTES];
+    crate::seed::fill_bytes(b"blame", &mut rands);
+    let commits: Vec<u8> = rands
+        .chunks(RAND_BYTES)
+        .flat_map(|rand| commit(&opening, rand))
+        .collect();
+    let all_commits = Net::broadcast_bytes(&commits);
+
+    // _Pragmatic MPC_ 6.6.2, as in the plain opening
+    let all_shares = Net::broadcast_vec(&shares);
+    let vals: Vec<T> = (0..shares.len())
+        .map(|i| all_shares.iter().fold(T::zero(), |x, s| x + s[i].clone()))
+        .collect();
+    let dx_ts: Vec<T> = vals
+        .iter()
+        .zip(&macs)
+        .map(|(val, mac)| scale(val, mac_share_of::<K>(me)) - mac.clone())
+        .collect();
+    let all_dx_ts = Net::atomic_broadcast_vec(&dx_ts);
+    let passed = ct::all((0..vals.len()).map(|i| {
+        ct::is_zero(&all_dx_ts.iter().fold(T::zero(), |x, d| x + d[i].clone()))
+    }));
+    if passed {
+        return vals;
+    }
+
+    // open the commitments, and judge every party by them
+    let all_macs = Net::broadcast_vec(&macs);
+    let all_rands = Net::broadcast_bytes(&rands);
+    let key = mac::<K>();
+    let culprits: Vec<usize> = (0..n_parties)
+        .filter(|&p| {
+            let opening = to_bytes(&(all_shares[p].clone(), all_macs[p].clone()));
+            let mine = me * RAND_BYTES..(me + 1) * RAND_BYTES;
+            let committed = ct::bytes_eq(
+                &all_commits[p][me * COMMIT_BYTES..(me + 1) * COMMIT_BYTES],
+                &commit(&opening, &all_rands[p][mine]),
+            );
+            let macs_match = all_shares[p]
+                .iter()
+                .zip(&all_macs[p])
+                .map(|(share, mac)| ct::eq(&scale(share, key), mac));
+            let key_share = mac_share_of::<K>(p);
+            let checks_follow = vals
+                .iter()
+                .zip(&all_macs[p])
+                .zip(&all_dx_ts[p])
+                .map(|((val, mac), dx_t)| ct::eq(&(scale(val, key_share) - mac.clone()), dx_t));
+            !ct::all(std::iter::once(committed).chain(macs_match).chain(checks_follow))
+        })
+        .collect();
+    let failure = MacCheckFailure { culprits };
+    error!("{}", failure);
+    panic::resume_unwind(Box::new(failure))
+}
@@ -0,0 +1,122 @@
+//! A trusted dealer, for experiments only: INSECURE.
+//!
+//! The king plays the dealer. It draws every item of preprocessing in the clear (the `x`, `y` and
+//! `xy` of each triple, each random bit, and so on), and sends each party its shares, which go
+//! into that party's [Preprocessed] store. It is quick, and it works for any number of parties
+//! and any scheme, but the dealer learns every secret the material will ever mask: whoever runs
+//! it can read every party's witness. Stores it stocks are noted as such, and [Preprocessed::open]
+//! warns when it opens one.
+//!
+//! For two parties, `mpc-offline` generates triples without a dealer.
+use ark_ff::prelude::*;
+use log::info;
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use rand::Rng;
+
+use super::field::FieldShare;
+use super::spdz::mac_share_of;
+use super::store::{Preprocessed, PreprocessingStore, Stock, StoreError};
+use crate::channel::MpcSerNet;
+
+/// The store note that says who made the material.
+pub const SOURCE: &str = "source";
+/// The store note that says whether the material is fit for production.
+pub const INSECURE: &str = "insecure-for-production";
+/// The store note with this party's share of the SPDZ MAC key, in hex.
+pub const MAC_KEY_SHARE: &str = "mac-key-share";
+
+/// Items dealt in one round of messages.
+const CHUNK: usize = 1 << 14;
+
+/// Deal `counts` of each kind of material into every party's `pre`, and note the stores as
+/// stocked by a trusted dealer.
+///
+/// All parties call it with the same `counts`. Only the king draws from `rng`.
+pub fn deal<F: Field, S: FieldShare<F>, St: PreprocessingStore, R: Rng>(
+    pre: &Preprocessed<F, S, St>,
+    counts: Stock,
+    rng: &mut R,
+) -> Result<(), StoreError> {
+    note_dealt(pre.store())?;
+    for n in chunks(counts.triples) {
+        let xs = draw(n, rng, F::rand);
+        let ys = draw(n, rng, F::rand);
+        let zs = xs.iter().zip(&ys).map(|(x, y)| *x * y).collect();
+        pre.add_triples(&share(xs, rng), &share(ys, rng), &share(zs, rng))?;
+    }
+    for n in chunks(counts.inv_pairs) {
+        let rs = draw(n, rng, |rng| loop {
+            let r = F::rand(rng);
+            if !r.is_zero() {
+                break r;
+            }
+        });
+        // the other parties' placeholders are zero
+        let r_invs = rs.iter().map(|r| r.inverse().unwrap_or_else(F::zero)).collect();
+        pre.add_inv_pairs(&share(rs, rng), &share(r_invs, rng))?;
+    }
+    for n in chunks(counts.shares) {
+        pre.add_shares(&share(draw(n, rng, F::rand), rng))?;
+    }
+    for n in chunks(counts.bits) {
+        pre.add_bits(&share(draw(n, rng, |rng| F::from(rng.gen::<bool>())), rng))?;
+    }
+    for n in chunks(counts.zeros) {
+        pre.add_zeros(&share(vec![F::zero(); n], rng))?;
+    }
+    info!("Dealt {}", counts);
+    Ok(())
+}
+
+/// Deal each party its share of the SPDZ MAC key over `F`, note it in `store`, and return it.
+///
+/// The key is fixed and public in this prototype, so the dealer cannot pick a fresh one: it
+/// sends each party the share that the online phase will use, which each party checks, so that
+/// material dealt for one key is never used under another.
+pub fn deal_mac_key<F: PrimeField, St: PreprocessingStore>(store: &St) -> Result<F, StoreError> {
+    let shares = if Net::am_king() {
+        Some((0..Net::n_parties()).map(mac_share_of::<F>).collect())
+    } else {
+        None
+    };
+    let key_share: F = Net::recv_from_king(shares);
+    assert_eq!(
+        key_share,
+        mac_share_of(Net::party_id()),
+        "the dealer's MAC key share differs from this party's"
+    );
+    let mut bytes = Vec::new();
+    key_share.serialize(&mut bytes)?;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    store.set_metadata(MAC_KEY_SHARE, &hex)?;
+    Ok(key_share)
+}
+
+/// Whether `store` holds material from a trusted dealer.
+pub fn is_dealt<St: PreprocessingStore>(store: &St) -> Result<bool, StoreError> {
+    Ok(store.metadata(INSECURE)?.as_deref() == Some("true"))
+}
+
+/// Mark `store` as stocked by a trusted dealer.
+fn note_dealt<St: PreprocessingStore>(store: &St) -> Result<(), StoreError> {
+    store.set_metadata(SOURCE, "trusted dealer")?;
+    store.set_metadata(INSECURE, "true")
+}
+
+/// `n` values from `f` at the king, and placeholders elsewhere.
+fn draw<F: Field, R: Rng>(n: usize, rng: &mut R, mut f: impl FnMut(&mut R) -> F) -> Vec<F> {
+    if Net::am_king() {
+        (0..n).map(|_| f(rng)).collect()
+    } else {
+        vec![F::zero(); n]
+    }
+}
+
+fn share<F: Field, S: FieldShare<F>, R: Rng>(values: Vec<F>, rng: &mut R) -> Vec<S> {
+    S::king_share_batch(values, rng)
+}
+
+/// The sizes of the rounds that deal `n` items.
+fn chunks(n: usize) -> impl Iterator<Item = usize> {
+    (0..n).step_by(CHUNK).map(move |i| CHUNK.min(n - i))
+}
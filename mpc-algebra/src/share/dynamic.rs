@@ -0,0 +1,915 @@
+//! Shares whose scheme is chosen at run time.
+//!
+//! Everything in this crate is generic over its share types, and so is everything built on it: a
+//! program that lets its user choose the scheme has to be instantiated once for each. With
+//! [DynPairingShare] it is instantiated once. Its shares are [DynShare]s, which each hold a share
+//! of one scheme and forward every operation to it. A share made from nothing (by
+//! [Reveal::from_public], [Reveal::king_share], deserialization, ...) is of the scheme that
+//! [set_scheme] chose for the session, so the parties must agree on it, as they would on a type.
+//!
+//! The cost is a match per operation, and a lookup of the session's scheme per share made from
+//! nothing. Batches are unwrapped into the scheme's own shares, so batched operations run as
+//! fast as they do without the wrapper, less a copy.
+//!
+//! Combining shares of different schemes panics. Multiplications draw their triples as
+//! [MpcField](crate::MpcField) does, from each scheme's own dummy source, not from the source they
+//! are given, whose triples would have to be unwrapped first.
+use ark_ec::{group::Group, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::bytes::{FromBytes, ToBytes};
+use ark_ff::prelude::*;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalDeserializeWithFlags, CanonicalSerialize,
+    CanonicalSerializeWithFlags, Flags, SerializationError,
+};
+use derivative::Derivative;
+use lazy_static::lazy_static;
+use mpc_net::MpcMultiNet as Net;
+use rand::Rng;
+use zeroize::Zeroize;
+
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use super::add::AdditivePairingShare;
+use super::field::{
+    DenseOrSparsePolynomial, DensePolynomial, ExtFieldShare, FieldShare, MulScratch, SmallScalar,
+};
+use super::group::GroupShare;
+use super::gsz20::GszPairingShare;
+use super::pairing::{AffProjShare, PairingShare};
+use super::spdz::SpdzPairingShare;
+use super::BeaverSource;
+use crate::wire::field::DummyFieldTripleSource;
+use crate::wire::group::DummyGroupTripleSource;
+use crate::Reveal;
+
+/// A sharing scheme, chosen at run time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Scheme {
+    /// Additive shares, secure against honest-but-curious parties.
+    Hbc,
+    /// SPDZ shares, secure against a malicious majority.
+    Spdz,
+    /// GSZ20 (Shamir) shares, secure against a malicious minority.
+    Gsz,
+}
+
+impl Scheme {
+    pub const ALL: [Scheme; 3] = [Scheme::Hbc, Scheme::Spdz, Scheme::Gsz];
+}
+
+impl Display for Scheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Scheme::Hbc => "hbc",
+            Scheme::Spdz => "spdz",
+            Scheme::Gsz => "gsz",
+        })
+    }
+}
+
+/// A name that is not a [Scheme].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownScheme(pub String);
+
+impl Display for UnknownScheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown sharing scheme {:?}: expected hbc, spdz, or gsz (or shamir)",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for UnknownScheme {}
+
+impl FromStr for Scheme {
+    type Err = UnknownScheme;
+    fn from_str(s: &str) -> Result<Self, UnknownScheme> {
+        match s.to_ascii_lowercase().as_str() {
+            "hbc" | "additive" => Ok(Scheme::Hbc),
+            "spdz" => Ok(Scheme::Spdz),
+            "gsz" | "shamir" => Ok(Scheme::Gsz),
+            _ => Err(UnknownScheme(s.to_owned())),
+        }
+    }
+}
+
+lazy_static! {
+    /// By session tag.
+    static ref SCHEMES: Mutex<HashMap<u64, Scheme>> = Mutex::new(HashMap::new());
+}
+
+/// Make [DynShare]s of `scheme` in the current session. All parties must agree on it, and set it
+/// before any share is made.
+pub fn set_scheme(scheme: Scheme) {
+    SCHEMES.lock().unwrap().insert(Net::session_tag(), scheme);
+}
+
+/// The scheme of the [DynShare]s made in the current session.
+///
+/// Panics if [set_scheme] has not been called.
+pub fn scheme() -> Scheme {
+    let schemes = SCHEMES.lock().unwrap();
+    match schemes.get(&Net::session_tag()) {
+        Some(scheme) => *scheme,
+        None => panic!("No sharing scheme set for this session: call dynamic::set_scheme"),
+    }
+}
+
+/// A share of one of the schemes: `H` for [Scheme::Hbc], `S` for [Scheme::Spdz], `G` for
+/// [Scheme::Gsz].
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DynShare<H, S, G> {
+    Hbc(H),
+    Spdz(S),
+    Gsz(G),
+}
+
+pub type DynFieldShare<F> = DynShare<
+    super::add::AdditiveFieldShare<F>,
+    super::spdz::SpdzFieldShare<F>,
+    super::gsz20::GszFieldShare<F>,
+>;
+
+/// `$body`, with `$x` bound to the share that `$e` holds, whatever its scheme.
+macro_rules! each {
+    ($e:expr, $x:ident => $body:expr) => {
+        match $e {
+            DynShare::Hbc($x) => $body,
+            DynShare::Spdz($x) => $body,
+            DynShare::Gsz($x) => $body,
+        }
+    };
+}
+
+/// As [each], with the result wrapped in the same scheme.
+macro_rules! each_wrap {
+    ($e:expr, $x:ident => $body:expr) => {
+        match $e {
+            DynShare::Hbc($x) => DynShare::Hbc($body),
+            DynShare::Spdz($x) => DynShare::Spdz($body),
+            DynShare::Gsz($x) => DynShare::Gsz($body),
+        }
+    };
+}
+
+/// As [each], for two shares, which must be of the same scheme.
+macro_rules! each_pair {
+    ($a:expr, $b:expr, ($x:pat, $y:pat) => $body:expr) => {
+        match ($a, $b) {
+            (DynShare::Hbc($x), DynShare::Hbc($y)) => DynShare::Hbc($body),
+            (DynShare::Spdz($x), DynShare::Spdz($y)) => DynShare::Spdz($body),
+            (DynShare::Gsz($x), DynShare::Gsz($y)) => DynShare::Gsz($body),
+            (a, b) => mixed(a.scheme(), b.scheme()),
+        }
+    };
+}
+
+/// `$H::$f(args)`, `$S::$f(args)` or `$G::$f(args)`, by the session's scheme, wrapped.
+macro_rules! fresh {
+    ($H:ident, $S:ident, $G:ident, $f:ident($($arg:expr),*)) => {
+        match scheme() {
+            Scheme::Hbc => DynShare::Hbc($H::$f($($arg),*)),
+            Scheme::Spdz => DynShare::Spdz($S::$f($($arg),*)),
+            Scheme::Gsz => DynShare::Gsz($G::$f($($arg),*)),
+        }
+    };
+}
+
+fn mixed(a: Scheme, b: Scheme) -> ! {
+    panic!("Combined a {} share with a {} share", a, b)
+}
+
+impl<H, S, G> DynShare<H, S, G> {
+    pub fn scheme(&self) -> Scheme {
+        match self {
+            DynShare::Hbc(_) => Scheme::Hbc,
+            DynShare::Spdz(_) => Scheme::Spdz,
+            DynShare::Gsz(_) => Scheme::Gsz,
+        }
+    }
+}
+
+/// A batch of shares of one scheme.
+enum Batch<H, S, G> {
+    Hbc(Vec<H>),
+    Spdz(Vec<S>),
+    Gsz(Vec<G>),
+}
+
+impl<H, S, G> Batch<H, S, G> {
+    /// Unwrap `xs`, which must all be of one scheme; the session's, if there are none.
+    fn of(xs: impl IntoIterator<Item = DynShare<H, S, G>>) -> Self {
+        let mut xs = xs.into_iter().peekable();
+        let batch_scheme = match xs.peek() {
+            Some(x) => x.scheme(),
+            None => scheme(),
+        };
+        match batch_scheme {
+            Scheme::Hbc => Batch::Hbc(
+                xs.map(|x| match x {
+                    DynShare::Hbc(x) => x,
+                    x => mixed(batch_scheme, x.scheme()),
+                })
+                .collect(),
+            ),
+            Scheme::Spdz => Batch::Spdz(
+                xs.map(|x| match x {
+                    DynShare::Spdz(x) => x,
+                    x => mixed(batch_scheme, x.scheme()),
+                })
+                .collect(),
+            ),
+            Scheme::Gsz => Batch::Gsz(
+                xs.map(|x| match x {
+                    DynShare::Gsz(x) => x,
+                    x => mixed(batch_scheme, x.scheme()),
+                })
+                .collect(),
+            ),
+        }
+    }
+
+    fn scheme(&self) -> Scheme {
+        match self {
+            Batch::Hbc(_) => Scheme::Hbc,
+            Batch::Spdz(_) => Scheme::Spdz,
+            Batch::Gsz(_) => Scheme::Gsz,
+        }
+    }
+
+    fn wrap(self) -> Vec<DynShare<H, S, G>> {
+        match self {
+            Batch::Hbc(xs) => xs.into_iter().map(DynShare::Hbc).collect(),
+            Batch::Spdz(xs) => xs.into_iter().map(DynShare::Spdz).collect(),
+            Batch::Gsz(xs) => xs.into_iter().map(DynShare::Gsz).collect(),
+        }
+    }
+}
+
+/// `$body`, with `$xs` bound to the shares of a [Batch], whatever their scheme.
+macro_rules! each_batch {
+    ($e:expr, $xs:ident => $body:expr) => {
+        match $e {
+            Batch::Hbc($xs) => $body,
+            Batch::Spdz($xs) => $body,
+            Batch::Gsz($xs) => $body,
+        }
+    };
+}
+
+/// As [each_batch], for two batches, which must be of the same scheme, with the resulting
+/// shares wrapped.
+macro_rules! each_batch_pair {
+    ($a:expr, $b:expr, ($xs:ident, $ys:ident) => $body:expr) => {
+        match ($a, $b) {
+            (Batch::Hbc($xs), Batch::Hbc($ys)) => Batch::Hbc($body).wrap(),
+            (Batch::Spdz($xs), Batch::Spdz($ys)) => Batch::Spdz($body).wrap(),
+            (Batch::Gsz($xs), Batch::Gsz($ys)) => Batch::Gsz($body).wrap(),
+            (a, b) => mixed(a.scheme(), b.scheme()),
+        }
+    };
+}
+
+impl<H: Display, S: Display, G: Display> Display for DynShare<H, S, G> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        each!(self, x => Display::fmt(x, f))
+    }
+}
+
+impl<H: Debug, S: Debug, G: Debug> Debug for DynShare<H, S, G> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        each!(self, x => Debug::fmt(x, f))
+    }
+}
+
+impl<H: Zeroize, S: Zeroize, G: Zeroize> Zeroize for DynShare<H, S, G> {
+    fn zeroize(&mut self) {
+        each!(self, x => x.zeroize())
+    }
+}
+
+// A share is encoded as its scheme encodes it, without a tag: the parties agree on the scheme.
+
+impl<H: ToBytes, S: ToBytes, G: ToBytes> ToBytes for DynShare<H, S, G> {
+    fn write<W: Write>(&self, writer: W) -> io::Result<()> {
+        each!(self, x => x.write(writer))
+    }
+}
+
+impl<H: FromBytes, S: FromBytes, G: FromBytes> FromBytes for DynShare<H, S, G> {
+    fn read<R: Read>(reader: R) -> io::Result<Self> {
+        Ok(match scheme() {
+            Scheme::Hbc => DynShare::Hbc(H::read(reader)?),
+            Scheme::Spdz => DynShare::Spdz(S::read(reader)?),
+            Scheme::Gsz => DynShare::Gsz(G::read(reader)?),
+        })
+    }
+}
+
+impl<H, S, G> CanonicalSerialize for DynShare<H, S, G>
+where
+    H: CanonicalSerialize,
+    S: CanonicalSerialize,
+    G: CanonicalSerialize,
+{
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        each!(self, x => x.serialize(writer))
+    }
+    fn serialized_size(&self) -> usize {
+        each!(self, x => x.serialized_size())
+    }
+    fn serialize_uncompressed<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        each!(self, x => x.serialize_uncompressed(writer))
+    }
+    fn uncompressed_size(&self) -> usize {
+        each!(self, x => x.uncompressed_size())
+    }
+}
+
+impl<H, S, G> CanonicalSerializeWithFlags for DynShare<H, S, G>
+where
+    H: CanonicalSerializeWithFlags,
+    S: CanonicalSerializeWithFlags,
+    G: CanonicalSerializeWithFlags,
+{
+    fn serialize_with_flags<W: Write, F: Flags>(
+        &self,
+        writer: W,
+        flags: F,
+    ) -> Result<(), SerializationError> {
+        each!(self, x => x.serialize_with_flags(writer, flags))
+    }
+    fn serialized_size_with_flags<F: Flags>(&self) -> usize {
+        each!(self, x => x.serialized_size_with_flags::<F>())
+    }
+}
+
+impl<H, S, G> CanonicalDeserialize for DynShare<H, S, G>
+where
+    H: CanonicalDeserialize,
+    S: CanonicalDeserialize,
+    G: CanonicalDeserialize,
+{
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(match scheme() {
+            Scheme::Hbc => DynShare::Hbc(H::deserialize(reader)?),
+            Scheme::Spdz => DynShare::Spdz(S::deserialize(reader)?),
+            Scheme::Gsz => DynShare::Gsz(G::deserialize(reader)?),
+        })
+    }
+    fn deserialize_uncompressed<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(match scheme() {
+            Scheme::Hbc => DynShare::Hbc(H::deserialize_uncompressed(reader)?),
+            Scheme::Spdz => DynShare::Spdz(S::deserialize_uncompressed(reader)?),
+            Scheme::Gsz => DynShare::Gsz(G::deserialize_uncompressed(reader)?),
+        })
+    }
+}
+
+impl<H, S, G> CanonicalDeserializeWithFlags for DynShare<H, S, G>
+where
+    H: CanonicalDeserializeWithFlags,
+    S: CanonicalDeserializeWithFlags,
+    G: CanonicalDeserializeWithFlags,
+{
+    fn deserialize_with_flags<R: Read, F: Flags>(
+        reader: R,
+    ) -> Result<(Self, F), SerializationError> {
+        Ok(match scheme() {
+            Scheme::Hbc => {
+                let (x, flags) = H::deserialize_with_flags(reader)?;
+                (DynShare::Hbc(x), flags)
+            }
+            Scheme::Spdz => {
+                let (x, flags) = S::deserialize_with_flags(reader)?;
+                (DynShare::Spdz(x), flags)
+            }
+            Scheme::Gsz => {
+                let (x, flags) = G::deserialize_with_flags(reader)?;
+                (DynShare::Gsz(x), flags)
+            }
+        })
+    }
+}
+
+impl<H: UniformRand, S: UniformRand, G: UniformRand> UniformRand for DynShare<H, S, G> {
+    fn rand<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        fresh!(H, S, G, rand(rng))
+    }
+}
+
+impl<H, S, G> Reveal for DynShare<H, S, G>
+where
+    H: Reveal,
+    S: Reveal<Base = H::Base>,
+    G: Reveal<Base = H::Base>,
+{
+    type Base = H::Base;
+
+    fn reveal(self) -> H::Base {
+        each!(self, x => x.reveal())
+    }
+    fn from_add_shared(b: H::Base) -> Self {
+        fresh!(H, S, G, from_add_shared(b))
+    }
+    fn from_public(b: H::Base) -> Self {
+        fresh!(H, S, G, from_public(b))
+    }
+    fn unwrap_as_public(self) -> H::Base {
+        each!(self, x => x.unwrap_as_public())
+    }
+    fn king_share<R: Rng>(b: H::Base, rng: &mut R) -> Self {
+        fresh!(H, S, G, king_share(b, rng))
+    }
+    fn king_share_batch<R: Rng>(bs: Vec<H::Base>, rng: &mut R) -> Vec<Self> {
+        match scheme() {
+            Scheme::Hbc => Batch::Hbc(H::king_share_batch(bs, rng)).wrap(),
+            Scheme::Spdz => Batch::Spdz(S::king_share_batch(bs, rng)).wrap(),
+            Scheme::Gsz => Batch::Gsz(G::king_share_batch(bs, rng)).wrap(),
+        }
+    }
+    fn party_share_batch<R: Rng>(owner: usize, bs: Vec<H::Base>, rng: &mut R) -> Vec<Self> {
+        match scheme() {
+            Scheme::Hbc => Batch::Hbc(H::party_share_batch(owner, bs, rng)).wrap(),
+            Scheme::Spdz => Batch::Spdz(S::party_share_batch(owner, bs, rng)).wrap(),
+            Scheme::Gsz => Batch::Gsz(G::party_share_batch(owner, bs, rng)).wrap(),
+        }
+    }
+    fn init_protocol() {
+        match scheme() {
+            Scheme::Hbc => H::init_protocol(),
+            Scheme::Spdz => S::init_protocol(),
+            Scheme::Gsz => G::init_protocol(),
+        }
+    }
+    fn deinit_protocol() {
+        match scheme() {
+            Scheme::Hbc => H::deinit_protocol(),
+            Scheme::Spdz => S::deinit_protocol(),
+            Scheme::Gsz => G::deinit_protocol(),
+        }
+    }
+}
+
+/// A polynomial of [DynShare]s, as one of its scheme's shares.
+fn poly_batch<H, S, G>(p: DenseOrSparsePolynomial<DynShare<H, S, G>>) -> PolyBatch<H, S, G> {
+    match p {
+        Ok(coeffs) => match Batch::of(coeffs) {
+            Batch::Hbc(c) => PolyBatch::Hbc(Ok(c)),
+            Batch::Spdz(c) => PolyBatch::Spdz(Ok(c)),
+            Batch::Gsz(c) => PolyBatch::Gsz(Ok(c)),
+        },
+        Err(terms) => {
+            let (degrees, coeffs): (Vec<usize>, Vec<_>) = terms.into_iter().unzip();
+            match Batch::of(coeffs) {
+                Batch::Hbc(c) => PolyBatch::Hbc(Err(degrees.into_iter().zip(c).collect())),
+                Batch::Spdz(c) => PolyBatch::Spdz(Err(degrees.into_iter().zip(c).collect())),
+                Batch::Gsz(c) => PolyBatch::Gsz(Err(degrees.into_iter().zip(c).collect())),
+            }
+        }
+    }
+}
+
+enum PolyBatch<H, S, G> {
+    Hbc(DenseOrSparsePolynomial<H>),
+    Spdz(DenseOrSparsePolynomial<S>),
+    Gsz(DenseOrSparsePolynomial<G>),
+}
+
+impl<F, H, S, G> FieldShare<F> for DynShare<H, S, G>
+where
+    F: Field,
+    H: FieldShare<F>,
+    S: FieldShare<F>,
+    G: FieldShare<F>,
+{
+    fn open(&self) -> F {
+        each!(self, x => x.open())
+    }
+
+    fn map_homo<FF: Field, SS: FieldShare<FF>, Fun: Fn(F) -> FF>(self, f: Fun) -> SS {
+        each!(self, x => x.map_homo(f))
+    }
+
+    fn batch_open(selfs: impl IntoIterator<Item = Self>) -> Vec<F> {
+        each_batch!(Batch::of(selfs), xs => FieldShare::batch_open(xs))
+    }
+
+    fn add(&mut self, other: &Self) -> &mut Self {
+        *self = each_pair!(*self, other, (mut x, y) => *x.add(y));
+        self
+    }
+
+    fn sub(&mut self, other: &Self) -> &mut Self {
+        *self = each_pair!(*self, other, (mut x, y) => *x.sub(y));
+        self
+    }
+
+    fn neg(&mut self) -> &mut Self {
+        each!(&mut *self, x => { x.neg(); });
+        self
+    }
+
+    fn shift(&mut self, other: &F) -> &mut Self {
+        each!(&mut *self, x => { x.shift(other); });
+        self
+    }
+
+    fn scale(&mut self, other: &F) -> &mut Self {
+        each!(&mut *self, x => { x.scale(other); });
+        self
+    }
+
+    fn scale_small(&mut self, c: SmallScalar) -> &mut Self {
+        each!(&mut *self, x => { x.scale_small(c); });
+        self
+    }
+
+    fn mul<Src: BeaverSource<Self, Self, Self>>(self, other: Self, _source: &mut Src) -> Self {
+        each_pair!(self, other, (x, y) => x.mul(y, &mut DummyFieldTripleSource::default()))
+    }
+
+    fn batch_mul<Src: BeaverSource<Self, Self, Self>>(
+        xs: Vec<Self>,
+        ys: Vec<Self>,
+        _source: &mut Src,
+    ) -> Vec<Self> {
+        each_batch_pair!(Batch::of(xs), Batch::of(ys), (xs, ys) => {
+            FieldShare::batch_mul(xs, ys, &mut DummyFieldTripleSource::default())
+        })
+    }
+
+    fn batch_mul_in_place<Src: BeaverSource<Self, Self, Self>>(
+        selfs: &mut [Self],
+        others: &[Self],
+        source: &mut Src,
+        _scratch: &mut MulScratch<F, Self>,
+    ) {
+        let products = Self::batch_mul(selfs.to_vec(), others.to_vec(), source);
+        selfs.copy_from_slice(&products);
+    }
+
+    fn inv<Src: BeaverSource<Self, Self, Self>>(self, _source: &mut Src) -> Self {
+        each_wrap!(self, x => x.inv(&mut DummyFieldTripleSource::default()))
+    }
+
+    fn batch_inv<Src: BeaverSource<Self, Self, Self>>(
+        xs: Vec<Self>,
+        _source: &mut Src,
+    ) -> Vec<Self> {
+        match Batch::of(xs) {
+            Batch::Hbc(xs) => {
+                Batch::Hbc(H::batch_inv(xs, &mut DummyFieldTripleSource::default())).wrap()
+            }
+            Batch::Spdz(xs) => {
+                Batch::Spdz(S::batch_inv(xs, &mut DummyFieldTripleSource::default())).wrap()
+            }
+            Batch::Gsz(xs) => {
+                Batch::Gsz(G::batch_inv(xs, &mut DummyFieldTripleSource::default())).wrap()
+            }
+        }
+    }
+
+    fn div<Src: BeaverSource<Self, Self, Self>>(self, other: Self, _source: &mut Src) -> Self {
+        each_pair!(self, other, (x, y) => x.div(y, &mut DummyFieldTripleSource::default()))
+    }
+
+    fn batch_div<Src: BeaverSource<Self, Self, Self>>(
+        xs: Vec<Self>,
+        ys: Vec<Self>,
+        _source: &mut Src,
+    ) -> Vec<Self> {
+        each_batch_pair!(Batch::of(xs), Batch::of(ys), (xs, ys) => {
+            FieldShare::batch_div(xs, ys, &mut DummyFieldTripleSource::default())
+        })
+    }
+
+    fn partial_products<Src: BeaverSource<Self, Self, Self>>(
+        x: Vec<Self>,
+        _src: &mut Src,
+    ) -> Vec<Self> {
+        match Batch::of(x) {
+            Batch::Hbc(x) => Batch::Hbc(H::partial_products(
+                x,
+                &mut DummyFieldTripleSource::default(),
+            ))
+            .wrap(),
+            Batch::Spdz(x) => Batch::Spdz(S::partial_products(
+                x,
+                &mut DummyFieldTripleSource::default(),
+            ))
+            .wrap(),
+            Batch::Gsz(x) => Batch::Gsz(G::partial_products(
+                x,
+                &mut DummyFieldTripleSource::default(),
+            ))
+            .wrap(),
+        }
+    }
+
+    fn univariate_div_qr<'a>(
+        num: DenseOrSparsePolynomial<Self>,
+        den: DenseOrSparsePolynomial<F>,
+    ) -> Option<(DensePolynomial<Self>, DensePolynomial<Self>)> {
+        fn wrap<T, U>((q, r): (Vec<T>, Vec<T>), f: fn(T) -> U) -> (Vec<U>, Vec<U>) {
+            (
+                q.into_iter().map(f).collect(),
+                r.into_iter().map(f).collect(),
+            )
+        }
+        match poly_batch(num) {
+            PolyBatch::Hbc(num) => H::univariate_div_qr(num, den).map(|qr| wrap(qr, DynShare::Hbc)),
+            PolyBatch::Spdz(num) => {
+                S::univariate_div_qr(num, den).map(|qr| wrap(qr, DynShare::Spdz))
+            }
+            PolyBatch::Gsz(num) => G::univariate_div_qr(num, den).map(|qr| wrap(qr, DynShare::Gsz)),
+        }
+    }
+}
+
+impl<Gr, H, S, G> GroupShare<Gr> for DynShare<H, S, G>
+where
+    Gr: Group,
+    H: GroupShare<Gr>,
+    S: GroupShare<Gr>,
+    G: GroupShare<Gr>,
+{
+    type FieldShare = DynShare<H::FieldShare, S::FieldShare, G::FieldShare>;
+
+    fn open(&self) -> Gr {
+        each!(self, x => x.open())
+    }
+
+    fn map_homo<G2: Group, S2: GroupShare<G2>, Fun: Fn(Gr) -> G2>(self, f: Fun) -> S2 {
+        each!(self, x => x.map_homo(f))
+    }
+
+    fn batch_open(selfs: impl IntoIterator<Item = Self>) -> Vec<Gr> {
+        each_batch!(Batch::of(selfs), xs => GroupShare::batch_open(xs))
+    }
+
+    fn add(&mut self, other: &Self) -> &mut Self {
+        *self = each_pair!(*self, other, (mut x, y) => *x.add(y));
+        self
+    }
+
+    fn sub(&mut self, other: &Self) -> &mut Self {
+        *self = each_pair!(*self, other, (mut x, y) => *x.sub(y));
+        self
+    }
+
+    fn neg(&mut self) -> &mut Self {
+        each!(&mut *self, x => { x.neg(); });
+        self
+    }
+
+    fn scale_pub_scalar(&mut self, scalar: &Gr::ScalarField) -> &mut Self {
+        each!(&mut *self, x => { x.scale_pub_scalar(scalar); });
+        self
+    }
+
+    fn scale_small(&mut self, c: SmallScalar) -> &mut Self {
+        each!(&mut *self, x => { x.scale_small(c); });
+        self
+    }
+
+    fn scale_pub_group(base: Gr, scalar: &Self::FieldShare) -> Self {
+        each_wrap!(scalar, s => GroupShare::scale_pub_group(base, s))
+    }
+
+    fn shift(&mut self, other: &Gr) -> &mut Self {
+        each!(&mut *self, x => { x.shift(other); });
+        self
+    }
+
+    fn scale<Src: BeaverSource<Self, Self::FieldShare, Self>>(
+        self,
+        other: Self::FieldShare,
+        _source: &mut Src,
+    ) -> Self {
+        match (self, other) {
+            (DynShare::Hbc(x), DynShare::Hbc(y)) => {
+                DynShare::Hbc(x.scale(y, &mut DummyGroupTripleSource::default()))
+            }
+            (DynShare::Spdz(x), DynShare::Spdz(y)) => {
+                DynShare::Spdz(x.scale(y, &mut DummyGroupTripleSource::default()))
+            }
+            (DynShare::Gsz(x), DynShare::Gsz(y)) => {
+                DynShare::Gsz(x.scale(y, &mut DummyGroupTripleSource::default()))
+            }
+            (a, b) => mixed(a.scheme(), b.scheme()),
+        }
+    }
+
+    fn multi_scale_pub_group(bases: &[Gr], scalars: &[Self::FieldShare]) -> Self {
+        match Batch::of(scalars.iter().cloned()) {
+            Batch::Hbc(s) => DynShare::Hbc(H::multi_scale_pub_group(bases, &s)),
+            Batch::Spdz(s) => DynShare::Spdz(S::multi_scale_pub_group(bases, &s)),
+            Batch::Gsz(s) => DynShare::Gsz(G::multi_scale_pub_group(bases, &s)),
+        }
+    }
+
+    fn multi_scale_pub_group_by(
+        scalars: &[Self::FieldShare],
+        msm: impl Fn(&[Gr::ScalarField]) -> Gr,
+    ) -> Self {
+        match Batch::of(scalars.iter().cloned()) {
+            Batch::Hbc(s) => DynShare::Hbc(H::multi_scale_pub_group_by(&s, msm)),
+            Batch::Spdz(s) => DynShare::Spdz(S::multi_scale_pub_group_by(&s, msm)),
+            Batch::Gsz(s) => DynShare::Gsz(G::multi_scale_pub_group_by(&s, msm)),
+        }
+    }
+
+    fn msm_pub_scalar_shared_base(bases: &[Self], scalars: &[Gr::ScalarField]) -> Self {
+        match Batch::of(bases.iter().cloned()) {
+            Batch::Hbc(b) => DynShare::Hbc(H::msm_pub_scalar_shared_base(&b, scalars)),
+            Batch::Spdz(b) => DynShare::Spdz(S::msm_pub_scalar_shared_base(&b, scalars)),
+            Batch::Gsz(b) => DynShare::Gsz(G::msm_pub_scalar_shared_base(&b, scalars)),
+        }
+    }
+}
+
+/// The [ExtFieldShare] of [DynShare]s of `H`'s, `S`'s and `G`'s.
+#[derive(Derivative)]
+#[derivative(
+    Default(bound = ""),
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct DynExtFieldShare<H, S, G>(PhantomData<(H, S, G)>);
+
+impl<F, H, S, G> ExtFieldShare<F> for DynExtFieldShare<H, S, G>
+where
+    F: Field,
+    H: ExtFieldShare<F>,
+    S: ExtFieldShare<F>,
+    G: ExtFieldShare<F>,
+{
+    type Base = DynShare<H::Base, S::Base, G::Base>;
+    type Ext = DynShare<H::Ext, S::Ext, G::Ext>;
+}
+
+/// The [AffProjShare] of [DynShare]s of `H`'s, `S`'s and `G`'s.
+pub struct DynAffProjShare<H, S, G>(PhantomData<(H, S, G)>);
+
+impl<Fr, A, P, H, S, G> AffProjShare<Fr, A, P> for DynAffProjShare<H, S, G>
+where
+    Fr: Field,
+    A: AffineCurve<ScalarField = Fr> + Group,
+    P: ProjectiveCurve<Affine = A>,
+    H: AffProjShare<Fr, A, P>,
+    S: AffProjShare<Fr, A, P>,
+    G: AffProjShare<Fr, A, P>,
+{
+    type FrShare = DynShare<H::FrShare, S::FrShare, G::FrShare>;
+    type AffineShare = DynShare<H::AffineShare, S::AffineShare, G::AffineShare>;
+    type ProjectiveShare = DynShare<H::ProjectiveShare, S::ProjectiveShare, G::ProjectiveShare>;
+
+    fn sh_aff_to_proj(g: Self::AffineShare) -> Self::ProjectiveShare {
+        match g {
+            DynShare::Hbc(g) => DynShare::Hbc(H::sh_aff_to_proj(g)),
+            DynShare::Spdz(g) => DynShare::Spdz(S::sh_aff_to_proj(g)),
+            DynShare::Gsz(g) => DynShare::Gsz(G::sh_aff_to_proj(g)),
+        }
+    }
+
+    fn sh_proj_to_aff(g: Self::ProjectiveShare) -> Self::AffineShare {
+        match g {
+            DynShare::Hbc(g) => DynShare::Hbc(H::sh_proj_to_aff(g)),
+            DynShare::Spdz(g) => DynShare::Spdz(S::sh_proj_to_aff(g)),
+            DynShare::Gsz(g) => DynShare::Gsz(G::sh_proj_to_aff(g)),
+        }
+    }
+
+    fn sh_batch_proj_to_aff(gs: &[Self::ProjectiveShare]) -> Vec<Self::AffineShare> {
+        match Batch::of(gs.iter().cloned()) {
+            Batch::Hbc(gs) => Batch::Hbc(H::sh_batch_proj_to_aff(&gs)).wrap(),
+            Batch::Spdz(gs) => Batch::Spdz(S::sh_batch_proj_to_aff(&gs)).wrap(),
+            Batch::Gsz(gs) => Batch::Gsz(G::sh_batch_proj_to_aff(&gs)).wrap(),
+        }
+    }
+
+    fn add_sh_proj_sh_aff(
+        a: Self::ProjectiveShare,
+        o: &Self::AffineShare,
+    ) -> Self::ProjectiveShare {
+        match (a, o) {
+            (DynShare::Hbc(a), DynShare::Hbc(o)) => DynShare::Hbc(H::add_sh_proj_sh_aff(a, o)),
+            (DynShare::Spdz(a), DynShare::Spdz(o)) => DynShare::Spdz(S::add_sh_proj_sh_aff(a, o)),
+            (DynShare::Gsz(a), DynShare::Gsz(o)) => DynShare::Gsz(G::add_sh_proj_sh_aff(a, o)),
+            (a, o) => mixed(a.scheme(), o.scheme()),
+        }
+    }
+
+    fn add_sh_proj_pub_aff(a: Self::ProjectiveShare, o: &A) -> Self::ProjectiveShare {
+        match a {
+            DynShare::Hbc(a) => DynShare::Hbc(H::add_sh_proj_pub_aff(a, o)),
+            DynShare::Spdz(a) => DynShare::Spdz(S::add_sh_proj_pub_aff(a, o)),
+            DynShare::Gsz(a) => DynShare::Gsz(G::add_sh_proj_pub_aff(a, o)),
+        }
+    }
+
+    fn add_pub_proj_sh_aff(a: &P, o: Self::AffineShare) -> Self::ProjectiveShare {
+        match o {
+            DynShare::Hbc(o) => DynShare::Hbc(H::add_pub_proj_sh_aff(a, o)),
+            DynShare::Spdz(o) => DynShare::Spdz(S::add_pub_proj_sh_aff(a, o)),
+            DynShare::Gsz(o) => DynShare::Gsz(G::add_pub_proj_sh_aff(a, o)),
+        }
+    }
+}
+
+type Hbc<E> = AdditivePairingShare<E>;
+type Spdz<E> = SpdzPairingShare<E>;
+type Gsz<E> = GszPairingShare<E>;
+
+/// The share of `T` in each scheme's [PairingShare], as a [DynShare].
+macro_rules! dyn_assoc {
+    ($E:ident, $T:ident) => {
+        DynShare<
+            <Hbc<$E> as PairingShare<$E>>::$T,
+            <Spdz<$E> as PairingShare<$E>>::$T,
+            <Gsz<$E> as PairingShare<$E>>::$T,
+        >
+    };
+}
+
+/// Pairing shares of the scheme chosen with [set_scheme].
+#[derive(Debug, Derivative)]
+#[derivative(
+    Default(bound = ""),
+    Clone(bound = ""),
+    Copy(bound = ""),
+    PartialEq(bound = "E::G1Affine: PartialEq"),
+    Eq(bound = "E::G1Affine: Eq"),
+    Hash(bound = "E::G1Affine: std::hash::Hash")
+)]
+pub struct DynPairingShare<E: PairingEngine>(pub PhantomData<E>);
+
+impl<E: PairingEngine> PairingShare<E> for DynPairingShare<E> {
+    type FrShare = dyn_assoc!(E, FrShare);
+    type FqShare = dyn_assoc!(E, FqShare);
+    type FqeShare = DynExtFieldShare<
+        <Hbc<E> as PairingShare<E>>::FqeShare,
+        <Spdz<E> as PairingShare<E>>::FqeShare,
+        <Gsz<E> as PairingShare<E>>::FqeShare,
+    >;
+    type FqkShare = DynExtFieldShare<
+        <Hbc<E> as PairingShare<E>>::FqkShare,
+        <Spdz<E> as PairingShare<E>>::FqkShare,
+        <Gsz<E> as PairingShare<E>>::FqkShare,
+    >;
+    type G1AffineShare = dyn_assoc!(E, G1AffineShare);
+    type G2AffineShare = dyn_assoc!(E, G2AffineShare);
+    type G1ProjectiveShare = dyn_assoc!(E, G1ProjectiveShare);
+    type G2ProjectiveShare = dyn_assoc!(E, G2ProjectiveShare);
+    type G1 = DynAffProjShare<
+        <Hbc<E> as PairingShare<E>>::G1,
+        <Spdz<E> as PairingShare<E>>::G1,
+        <Gsz<E> as PairingShare<E>>::G1,
+    >;
+    type G2 = DynAffProjShare<
+        <Hbc<E> as PairingShare<E>>::G2,
+        <Spdz<E> as PairingShare<E>>::G2,
+        <Gsz<E> as PairingShare<E>>::G2,
+    >;
+
+    fn pairing_sh_g1(
+        p: Self::G1AffineShare,
+        q: &E::G2Affine,
+    ) -> <Self::FqkShare as ExtFieldShare<E::Fqk>>::Ext {
+        match p {
+            DynShare::Hbc(p) => DynShare::Hbc(Hbc::<E>::pairing_sh_g1(p, q)),
+            DynShare::Spdz(p) => DynShare::Spdz(Spdz::<E>::pairing_sh_g1(p, q)),
+            DynShare::Gsz(p) => DynShare::Gsz(Gsz::<E>::pairing_sh_g1(p, q)),
+        }
+    }
+
+    fn pairing_sh_g2(
+        p: &E::G1Affine,
+        q: Self::G2AffineShare,
+    ) -> <Self::FqkShare as ExtFieldShare<E::Fqk>>::Ext {
+        match q {
+            DynShare::Hbc(q) => DynShare::Hbc(Hbc::<E>::pairing_sh_g2(p, q)),
+            DynShare::Spdz(q) => DynShare::Spdz(Spdz::<E>::pairing_sh_g2(p, q)),
+            DynShare::Gsz(q) => DynShare::Gsz(Gsz::<E>::pairing_sh_g2(p, q)),
+        }
+    }
+
+    fn pow_gt_sh(g: &E::Fqk, exp: Self::FrShare) -> <Self::FqkShare as ExtFieldShare<E::Fqk>>::Ext {
+        match exp {
+            DynShare::Hbc(exp) => DynShare::Hbc(Hbc::<E>::pow_gt_sh(g, exp)),
+            DynShare::Spdz(exp) => DynShare::Spdz(Spdz::<E>::pow_gt_sh(g, exp)),
+            DynShare::Gsz(exp) => DynShare::Gsz(Gsz::<E>::pow_gt_sh(g, exp)),
+        }
+    }
+}
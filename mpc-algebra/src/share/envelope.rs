@@ -0,0 +1,301 @@
+//! A tagged, versioned envelope for persisted shares.
+//!
+//! A share on its own says nothing about the scheme, curve or session that made it, so a file of
+//! SPDZ shares of BLS12-377 scalars reads just as happily as GSZ shares of BLS12-381 ones, or as
+//! another party's shares. An envelope heads the payload with what it is and whose it is, and
+//! [read] refuses anything that does not match exactly:
+//!
+//! * the magic bytes `MPCSHARE` and a [FORMAT_VERSION],
+//! * the [PayloadKind],
+//! * the payload's Rust type name, which names the share scheme and the field or curve,
+//! * the party id and the number of parties,
+//! * the payload's length, which must be exactly what the payload deserializes from.
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+
+use std::any::type_name;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+
+const MAGIC: [u8; 8] = *b"MPCSHARE";
+
+/// The version of the envelope format that this crate writes, and the only one it reads.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// What an envelope holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayloadKind {
+    /// Shares of inputs or intermediate values.
+    Shares,
+    /// Preprocessed multiplication triples.
+    Triples,
+    /// Preprocessed random shares and bits.
+    Randomness,
+    /// A party's shares of part of a proof.
+    ProofFragment,
+}
+
+impl PayloadKind {
+    fn to_byte(self) -> u8 {
+        match self {
+            PayloadKind::Shares => 0,
+            PayloadKind::Triples => 1,
+            PayloadKind::Randomness => 2,
+            PayloadKind::ProofFragment => 3,
+        }
+    }
+
+    fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            0 => Some(PayloadKind::Shares),
+            1 => Some(PayloadKind::Triples),
+            2 => Some(PayloadKind::Randomness),
+            3 => Some(PayloadKind::ProofFragment),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum EnvelopeError {
+    /// The data does not start with the envelope's magic bytes.
+    NotAnEnvelope,
+    UnsupportedVersion(u32),
+    UnknownKind(u8),
+    WrongKind {
+        expected: PayloadKind,
+        found: PayloadKind,
+    },
+    /// The payload is of another type: another scheme, field or curve.
+    WrongType {
+        expected: String,
+        found: String,
+    },
+    /// The envelope was made for a different party or party count.
+    WrongParty {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    /// The payload is shorter or longer than its header says, or than its type reads.
+    BadLength {
+        declared: u64,
+        read: u64,
+    },
+    Io(io::Error),
+    Serialization(SerializationError),
+}
+
+impl Display for EnvelopeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeError::NotAnEnvelope => write!(f, "not a share envelope"),
+            EnvelopeError::UnsupportedVersion(v) => write!(
+                f,
+                "share envelope version {} is not supported (expected {})",
+                v, FORMAT_VERSION
+            ),
+            EnvelopeError::UnknownKind(k) => write!(f, "unknown share envelope kind {}", k),
+            EnvelopeError::WrongKind { expected, found } => write!(
+                f,
+                "share envelope holds {:?}, but {:?} were expected",
+                found, expected
+            ),
+            EnvelopeError::WrongType { expected, found } => write!(
+                f,
+                "share envelope holds {}, but {} was expected",
+                found, expected
+            ),
+            EnvelopeError::WrongParty { expected, found } => write!(
+                f,
+                "share envelope is for party {} of {}, but this is party {} of {}",
+                found.0, found.1, expected.0, expected.1
+            ),
+            EnvelopeError::BadLength { declared, read } => write!(
+                f,
+                "share envelope declares {} payload bytes, but {} were read",
+                declared, read
+            ),
+            EnvelopeError::Io(e) => write!(f, "share envelope I/O: {}", e),
+            EnvelopeError::Serialization(e) => write!(f, "share envelope encoding: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
+
+impl From<io::Error> for EnvelopeError {
+    fn from(e: io::Error) -> Self {
+        EnvelopeError::Io(e)
+    }
+}
+
+impl From<SerializationError> for EnvelopeError {
+    fn from(e: SerializationError) -> Self {
+        match e {
+            SerializationError::IoError(e) => EnvelopeError::Io(e),
+            e => EnvelopeError::Serialization(e),
+        }
+    }
+}
+
+/// The header of an envelope.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub version: u32,
+    pub kind: PayloadKind,
+    pub type_name: String,
+    pub party_id: usize,
+    pub n_parties: usize,
+    pub payload_len: u64,
+}
+
+impl Header {
+    /// The header for this party's `payload`.
+    pub fn new<T: CanonicalSerialize>(kind: PayloadKind, payload: &T) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            kind,
+            type_name: type_name::<T>().to_owned(),
+            party_id: Net::party_id(),
+            n_parties: Net::n_parties(),
+            payload_len: payload.serialized_size() as u64,
+        }
+    }
+
+    fn write<W: Write>(&self, mut w: W) -> Result<(), EnvelopeError> {
+        w.write_all(&MAGIC)?;
+        self.version.serialize(&mut w)?;
+        self.kind.to_byte().serialize(&mut w)?;
+        self.type_name.serialize(&mut w)?;
+        (self.party_id as u64).serialize(&mut w)?;
+        (self.n_parties as u64).serialize(&mut w)?;
+        self.payload_len.serialize(&mut w)?;
+        Ok(())
+    }
+
+    /// Read a header, checking only that it is one, of a version this crate reads.
+    pub fn read<R: Read>(mut r: R) -> Result<Self, EnvelopeError> {
+        let mut magic = [0u8; 8];
+        r.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(EnvelopeError::NotAnEnvelope);
+        }
+        let version = u32::deserialize(&mut r)?;
+        if version != FORMAT_VERSION {
+            return Err(EnvelopeError::UnsupportedVersion(version));
+        }
+        let kind = u8::deserialize(&mut r)?;
+        let kind = PayloadKind::from_byte(kind).ok_or(EnvelopeError::UnknownKind(kind))?;
+        Ok(Self {
+            version,
+            kind,
+            type_name: String::deserialize(&mut r)?,
+            party_id: u64::deserialize(&mut r)? as usize,
+            n_parties: u64::deserialize(&mut r)? as usize,
+            payload_len: u64::deserialize(&mut r)?,
+        })
+    }
+
+    /// Check that this header is for a `T` of `kind`, belonging to this party.
+    pub fn check<T>(&self, kind: PayloadKind) -> Result<(), EnvelopeError> {
+        if self.kind != kind {
+            return Err(EnvelopeError::WrongKind {
+                expected: kind,
+                found: self.kind,
+            });
+        }
+        if self.type_name != type_name::<T>() {
+            return Err(EnvelopeError::WrongType {
+                expected: type_name::<T>().to_owned(),
+                found: self.type_name.clone(),
+            });
+        }
+        let expected = (Net::party_id(), Net::n_parties());
+        if (self.party_id, self.n_parties) != expected {
+            return Err(EnvelopeError::WrongParty {
+                expected,
+                found: (self.party_id, self.n_parties),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Counts the bytes read through it.
+struct Counting<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R: Read> Read for Counting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+/// Write this party's `payload` in an envelope.
+pub fn write<T: CanonicalSerialize, W: Write>(
+    kind: PayloadKind,
+    payload: &T,
+    mut w: W,
+) -> Result<(), EnvelopeError> {
+    Header::new(kind, payload).write(&mut w)?;
+    payload.serialize(&mut w)?;
+    Ok(())
+}
+
+/// Read a `T` of `kind` from an envelope, checking that it belongs to this party, and that nothing
+/// follows it.
+pub fn read<T: CanonicalDeserialize, R: Read>(
+    kind: PayloadKind,
+    mut r: R,
+) -> Result<T, EnvelopeError> {
+    let header = Header::read(&mut r)?;
+    header.check::<T>(kind)?;
+    let mut payload = Counting {
+        inner: r.by_ref().take(header.payload_len),
+        count: 0,
+    };
+    let value = T::deserialize(&mut payload).map_err(|e| match e {
+        SerializationError::IoError(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+            EnvelopeError::BadLength {
+                declared: header.payload_len,
+                read: payload.count,
+            }
+        }
+        e => e.into(),
+    })?;
+    let read = payload.count;
+    if read != header.payload_len || r.read(&mut [0u8; 1])? != 0 {
+        return Err(EnvelopeError::BadLength {
+            declared: header.payload_len,
+            read,
+        });
+    }
+    Ok(value)
+}
+
+/// [write] to a file.
+pub fn save<T: CanonicalSerialize>(
+    kind: PayloadKind,
+    payload: &T,
+    path: impl AsRef<Path>,
+) -> Result<(), EnvelopeError> {
+    let mut w = BufWriter::new(File::create(path)?);
+    write(kind, payload, &mut w)?;
+    w.flush()?;
+    Ok(())
+}
+
+/// [read] from a file.
+pub fn load<T: CanonicalDeserialize>(
+    kind: PayloadKind,
+    path: impl AsRef<Path>,
+) -> Result<T, EnvelopeError> {
+    read(kind, BufReader::new(File::open(path)?))
+}
@@ -6,9 +6,12 @@ use ark_serialize::{
 };
 //use ark_poly::univariate::{DensePolynomial,DenseOrSparsePolynomial};
 use core::ops::*;
+use derivative::Derivative;
 use std::cmp::Ord;
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
+use std::marker::PhantomData;
+use zeroize::Zeroize;
 
 use super::BeaverSource;
 use crate::Reveal;
@@ -30,6 +33,7 @@ pub trait FieldShare<F: Field>:
     + UniformRand
     + ToBytes
     + FromBytes
+    + Zeroize
     + 'static
     + Reveal<Base = F>
 {
@@ -63,6 +67,29 @@ pub trait FieldShare<F: Field>:
 
     fn scale(&mut self, other: &F) -> &mut Self;
 
+    /// Multiply by a small public constant, using only additions.
+    ///
+    /// Shares that cannot add (e.g. multiplicative ones) should fall back to [FieldShare::scale].
+    fn scale_small(&mut self, c: SmallScalar) -> &mut Self {
+        let t = *self;
+        match c {
+            SmallScalar::Zero => self.sub(&t),
+            SmallScalar::One => self,
+            SmallScalar::MinusOne => self.sub(&t).sub(&t),
+            SmallScalar::Pow2(k) => {
+                for _ in 0..k {
+                    let t = *self;
+                    self.add(&t);
+                }
+                self
+            }
+            SmallScalar::NegPow2(k) => {
+                self.sub(&t).sub(&t);
+                self.scale_small(SmallScalar::Pow2(k))
+            }
+        }
+    }
+
     fn mul<S: BeaverSource<Self, Self, Self>>(self, other: Self, source: &mut S) -> Self {
         let (mut x, mut y, z) = source.triple();
         //println!("Triple:\n *{}\n *{}\n *{}", x, y, z);
@@ -95,54 +122,65 @@ pub trait FieldShare<F: Field>:
     }
 
     fn batch_mul<S: BeaverSource<Self, Self, Self>>(
-        xs: Vec<Self>,
+        mut xs: Vec<Self>,
         ys: Vec<Self>,
         source: &mut S,
     ) -> Vec<Self> {
-        let ss = xs;
-        let os = ys;
-        let (xs, ys, zs) = source.triples(ss.len());
+        Self::batch_mul_in_place(&mut xs, &ys, source, &mut MulScratch::default());
+        xs
+    }
+
+    /// Set `selfs[i] *= others[i]`, using `scratch` for all intermediate buffers.
+    ///
+    /// Reusing one `scratch` across many calls avoids allocating on each batch.
+    fn batch_mul_in_place<S: BeaverSource<Self, Self, Self>>(
+        selfs: &mut [Self],
+        others: &[Self],
+        source: &mut S,
+        scratch: &mut MulScratch<F, Self>,
+    ) {
+        let n = selfs.len();
+        assert_eq!(n, others.len());
+        let MulScratch { xs, ys, zs, masked, .. } = scratch;
+        source.triples_into(n, xs, ys, zs);
         // output: z - open(s + x)y - open(o + y)x + open(s + x)open(o + y)
         //         xy - sy - xy - ox - yx + so + sy + xo + xy
         //         so
-        let sxs = Self::batch_open(ss.into_iter().zip(xs.iter()).map(|(mut s, x)| {
-            s.add(x);
-            s
-        }));
-        let oys = Self::batch_open(os.into_iter().zip(ys.iter()).map(|(mut o, y)| {
-            o.add(y);
-            o
-        }));
-        zs.into_iter()
-            .zip(ys.into_iter())
-            .zip(xs.into_iter())
-            .enumerate()
-            .map(|(i, ((mut z, mut y), mut x))| {
-                z.sub(y.scale(&sxs[i]))
-                    .sub(x.scale(&oys[i]))
-                    .shift(&(sxs[i] * oys[i]));
-                z
-            })
-            .collect()
+        masked.clear();
+        masked.reserve(2 * n);
+        masked.extend(selfs.iter().zip(xs.iter()).map(|(s, x)| *s.clone().add(x)));
+        masked.extend(others.iter().zip(ys.iter()).map(|(o, y)| *o.clone().add(y)));
+        // one round for both openings
+        let opened = Self::batch_open(masked.iter().cloned());
+        let (sxs, oys) = opened.split_at(n);
+        for i in 0..n {
+            let mut z = zs[i];
+            z.sub(ys[i].scale(&sxs[i]))
+                .sub(xs[i].scale(&oys[i]))
+                .shift(&(sxs[i] * oys[i]));
+            selfs[i] = z;
+        }
     }
 
+    /// With a random `r` from an inverse pair, `r / open(r * self)`.
     fn inv<S: BeaverSource<Self, Self, Self>>(self, source: &mut S) -> Self {
-        let (x, mut y) = source.inv_pair();
-        let xa = x.mul(self, source).open().inverse().unwrap();
-        *y.scale(&xa)
+        let (mut r, _) = source.inv_pair();
+        let ra = r.mul(self, source).open().inverse().unwrap();
+        *r.scale(&ra)
     }
 
     fn batch_inv<S: BeaverSource<Self, Self, Self>>(xs: Vec<Self>, source: &mut S) -> Vec<Self> {
-        let (bs, cs) = source.inv_pairs(xs.len());
-        cs.into_iter()
+        let (rs, _) = source.inv_pairs(xs.len());
+        rs.clone()
+            .into_iter()
             .zip(
-                Self::batch_open(Self::batch_mul(xs, bs, source))
+                Self::batch_open(Self::batch_mul(xs, rs, source))
                     .into_iter()
                     .map(|i| i.inverse().unwrap()),
             )
-            .map(|(mut c, i)| {
-                c.scale(&i);
-                c
+            .map(|(mut r, i)| {
+                r.scale(&i);
+                r
             })
             .collect()
     }
@@ -193,6 +231,89 @@ pub trait FieldShare<F: Field>:
 
 }
 
+/// Scratch space for [FieldShare::batch_mul_in_place].
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct MulScratch<F: Field, S: FieldShare<F>> {
+    xs: Vec<S>,
+    ys: Vec<S>,
+    zs: Vec<S>,
+    masked: Vec<S>,
+    _field: PhantomData<F>,
+}
+
+/// A public scalar whose product with a share can be computed without a full multiplication.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SmallScalar {
+    Zero,
+    One,
+    MinusOne,
+    /// `2^k`, for `1 <= k <= SmallScalar::MAX_POW2`.
+    Pow2(u32),
+    /// `-2^k`, for `1 <= k <= SmallScalar::MAX_POW2`.
+    NegPow2(u32),
+}
+
+impl SmallScalar {
+    /// The largest power of two that is recognized.
+    ///
+    /// Each doubling costs an addition, so beyond this a multiplication is cheaper than the
+    /// doublings plus the check.
+    pub const MAX_POW2: u32 = 4;
+
+    /// Classify `x`, or `None` if it isn't small.
+    ///
+    /// Only group scaling (`MpcGroup *= MpcField`) uses the powers of two: there a doubling is far
+    /// cheaper than a scalar multiplication. Field scaling uses [SmallScalar::of_unit].
+    #[inline]
+    pub fn of<F: Field>(x: &F) -> Option<Self> {
+        if let Some(unit) = Self::of_unit(x) {
+            return Some(unit);
+        }
+        let neg_x = -*x;
+        let mut p = F::one();
+        for k in 1..=Self::MAX_POW2 {
+            p.double_in_place();
+            if *x == p {
+                return Some(SmallScalar::Pow2(k));
+            }
+            if neg_x == p {
+                return Some(SmallScalar::NegPow2(k));
+            }
+        }
+        None
+    }
+
+    /// Classify `x` only if it is `0` or `±1`, or `None`.
+    ///
+    /// For field shares, these are the only constants worth the check: a doubling costs about as
+    /// much as the multiplication it would save (see `cargo bench --bench field public-scale`).
+    #[inline]
+    pub fn of_unit<F: Field>(x: &F) -> Option<Self> {
+        if x.is_zero() {
+            Some(SmallScalar::Zero)
+        } else if x.is_one() {
+            Some(SmallScalar::One)
+        } else if (-*x).is_one() {
+            Some(SmallScalar::MinusOne)
+        } else {
+            None
+        }
+    }
+
+    /// The value of this constant in `F`.
+    pub fn value<F: Field>(self) -> F {
+        let pow2 = |k: u32| (0..k).fold(F::one(), |p, _| p.double());
+        match self {
+            SmallScalar::Zero => F::zero(),
+            SmallScalar::One => F::one(),
+            SmallScalar::MinusOne => -F::one(),
+            SmallScalar::Pow2(k) => pow2(k),
+            SmallScalar::NegPow2(k) => -pow2(k),
+        }
+    }
+}
+
 pub type DensePolynomial<T> = Vec<T>;
 pub type SparsePolynomial<T> = Vec<(usize, T)>;
 pub type DenseOrSparsePolynomial<T> = Result<DensePolynomial<T>, SparsePolynomial<T>>;
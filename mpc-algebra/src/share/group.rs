@@ -9,8 +9,9 @@ use ark_std::{end_timer, start_timer};
 use core::ops::*;
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
+use zeroize::Zeroize;
 
-use super::field::FieldShare;
+use super::field::{FieldShare, SmallScalar};
 use super::BeaverSource;
 use crate::Reveal;
 
@@ -31,6 +32,7 @@ pub trait GroupShare<G: Group>:
     + UniformRand
     + ToBytes
     + FromBytes
+    + Zeroize
     + 'static
     + Reveal<Base = G>
 {
@@ -63,6 +65,27 @@ pub trait GroupShare<G: Group>:
 
     fn scale_pub_scalar(&mut self, scalar: &G::ScalarField) -> &mut Self;
 
+    /// Multiply by a small public scalar, using only additions.
+    fn scale_small(&mut self, c: SmallScalar) -> &mut Self {
+        let t = *self;
+        match c {
+            SmallScalar::Zero => self.sub(&t),
+            SmallScalar::One => self,
+            SmallScalar::MinusOne => self.sub(&t).sub(&t),
+            SmallScalar::Pow2(k) => {
+                for _ in 0..k {
+                    let t = *self;
+                    self.add(&t);
+                }
+                self
+            }
+            SmallScalar::NegPow2(k) => {
+                self.sub(&t).sub(&t);
+                self.scale_small(SmallScalar::Pow2(k))
+            }
+        }
+    }
+
     fn scale_pub_group(base: G, scalar: &Self::FieldShare) -> Self;
 
     fn shift(&mut self, other: &G) -> &mut Self;
@@ -120,4 +143,30 @@ pub trait GroupShare<G: Group>:
                 acc
             })
     }
+
+    /// Like [GroupShare::multi_scale_pub_group], with the MSM over the (public) bases done by
+    /// `msm`, which must be linear in the scalars. For bases with precomputed tables.
+    fn multi_scale_pub_group_by(
+        scalars: &[Self::FieldShare],
+        msm: impl Fn(&[G::ScalarField]) -> G,
+    ) -> Self;
+
+    /// Compute \sum_i (s_i * g_i)
+    /// where the s_i are public and the g_i are shared.
+    ///
+    /// Sharing is linear, so this needs no communication.
+    fn msm_pub_scalar_shared_base(bases: &[Self], scalars: &[G::ScalarField]) -> Self {
+        bases
+            .iter()
+            .zip(scalars.iter())
+            .map(|(g, s)| {
+                let mut g = *g;
+                g.scale_pub_scalar(s);
+                g
+            })
+            .fold(Self::from_public(G::zero()), |mut acc, n| {
+                acc.add(&n);
+                acc
+            })
+    }
 }
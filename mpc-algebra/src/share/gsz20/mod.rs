@@ -18,6 +18,7 @@ macro_rules! dd {
 }
 
 use crate::channel::MpcSerNet;
+use crate::ct;
 use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
 use ark_ff::{
     bytes::{FromBytes, ToBytes},
@@ -35,7 +36,6 @@ use ark_serialize::{
 use ark_std::{end_timer, start_timer};
 use mpc_net::{MpcMultiNet as Net, MpcNet};
 
-use once_cell::sync::OnceCell;
 use std::any::{Any, TypeId};
 use std::borrow::Cow;
 use std::cmp::Ord;
@@ -50,9 +50,11 @@ use derivative::Derivative;
 use lazy_static::lazy_static;
 use log::debug;
 use rand::Rng;
+use zeroize::Zeroize;
 
 use super::field::{
-    DenseOrSparsePolynomial, DensePolynomial, ExtFieldShare, FieldShare, SparsePolynomial,
+    DenseOrSparsePolynomial, DensePolynomial, ExtFieldShare, FieldShare, MulScratch, SmallScalar,
+    SparsePolynomial,
 };
 use super::BeaverSource;
 use crate::msm::Msm;
@@ -60,29 +62,18 @@ use crate::share::pairing::{AffProjShare, PairingShare};
 use crate::Reveal;
 
 lazy_static! {
-    static ref TYPE_LISTS: Mutex<HashMap<TypeId, Vec<Box<dyn Any + Send>>>> =
+    /// Evaluation domains for Shamir shares, by field and number of parties (sessions may differ
+    /// in the latter).
+    static ref SHARE_DOMAINS: Mutex<HashMap<(TypeId, usize), &'static (dyn Any + Send + Sync)>> =
         Mutex::new(HashMap::new());
-    static ref SHARE_DOMAIN: OnceCell<Box<dyn Any + Send + Sync>> = OnceCell::new();
 }
 
+/// Values awaiting a check are kept with the session, so they go when it does.
 fn take_types<T: Any + Send>() -> Vec<T> {
-    let mut lists = TYPE_LISTS.lock().unwrap();
-    let type_id = TypeId::of::<T>();
-    let list = lists.remove(&type_id).unwrap_or_else(|| Vec::new());
-    list.into_iter()
-        .map(|x| *x.downcast::<T>().unwrap())
-        .collect()
+    Net::with_local(std::mem::take::<Vec<T>>)
 }
 fn add_type<T: Any + Send>(t: T) {
-    let mut lists = TYPE_LISTS.lock().unwrap();
-    let type_id = TypeId::of::<T>();
-    use std::collections::hash_map::Entry;
-    match lists.entry(type_id) {
-        Entry::Occupied(o) => o.into_mut().push(Box::new(t)),
-        Entry::Vacant(v) => {
-            v.insert(vec![Box::new(t)]);
-        }
-    };
+    Net::with_local(|list: &mut Vec<T>| list.push(t))
 }
 fn add_types<T: Any + Send>(ts: Vec<T>) {
     for t in ts {
@@ -90,18 +81,23 @@ fn add_types<T: Any + Send>(ts: Vec<T>) {
     }
 }
 
+pub mod prss;
+
 /// Malicious degree
 pub fn t() -> usize {
     (Net::n_parties() - 1) / 2
 }
 
 pub fn domain<F: FftField>() -> &'static MixedRadixEvaluationDomain<F> {
-    SHARE_DOMAIN.get_or_init(|| {
-        let d = MixedRadixEvaluationDomain::<F>::new(Net::n_parties()).unwrap();
-        assert_eq!(d.size(), Net::n_parties(),
-            "Attempted to build an evaluation domain of size {}, but could only get one of size {}.\nThis domain is needed in order to support Shamir shares for this many parties", Net::n_parties(), d.size(), );
-        Box::new(d)
-    }).downcast_ref().unwrap()
+    let n = Net::n_parties();
+    let mut domains = SHARE_DOMAINS.lock().unwrap();
+    let d = *domains.entry((TypeId::of::<F>(), n)).or_insert_with(|| {
+        let d = MixedRadixEvaluationDomain::<F>::new(n).unwrap();
+        assert_eq!(d.size(), n,
+            "Attempted to build an evaluation domain of size {}, but could only get one of size {}.\nThis domain is needed in order to support Shamir shares for this many parties", n, d.size(), );
+        Box::leak(Box::new(d))
+    });
+    d.downcast_ref().unwrap()
 }
 
 pub mod field {
@@ -117,6 +113,12 @@ pub mod field {
         pub degree: usize,
     }
 
+    impl<F: Field> Zeroize for GszFieldShare<F> {
+        fn zeroize(&mut self) {
+            self.val.zeroize();
+        }
+    }
+
     impl<T: FftField> Display for GszFieldShare<T> {
         fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
             write!(f, "{}", self.val)
@@ -138,11 +140,12 @@ pub mod field {
         }
     }
     impl<T: FftField> CanonicalSerialize for GszFieldShare<T> {
-        fn serialize<W: Write>(&self, _writer: W) -> Result<(), SerializationError> {
-            unimplemented!("serialize")
+        fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+            self.val.serialize(&mut writer)?;
+            (self.degree as u64).serialize(writer)
         }
         fn serialized_size(&self) -> usize {
-            unimplemented!("serialized_size")
+            self.val.serialized_size() + 8
         }
     }
     impl<T: FftField> CanonicalSerializeWithFlags for GszFieldShare<T> {
@@ -159,8 +162,11 @@ pub mod field {
         }
     }
     impl<T: FftField> CanonicalDeserialize for GszFieldShare<T> {
-        fn deserialize<R: Read>(_reader: R) -> Result<Self, SerializationError> {
-            unimplemented!("deserialize")
+        fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+            Ok(Self {
+                val: T::deserialize(&mut reader)?,
+                degree: u64::deserialize(reader)? as usize,
+            })
         }
     }
     impl<T: FftField> CanonicalDeserializeWithFlags for GszFieldShare<T> {
@@ -199,17 +205,29 @@ pub mod field {
                 degree: t(),
             }
         }
-        fn king_share_batch<R: Rng>(f: Vec<Self::Base>, _rng: &mut R) -> Vec<Self> {
-            let fs = vec![f; Net::n_parties()];
-            let king_fs = Net::recv_from_king(if Net::am_king() { Some(fs) } else { None });
-            king_fs
+        fn king_share_batch<R: Rng>(f: Vec<Self::Base>, rng: &mut R) -> Vec<Self> {
+            Self::party_share_batch(0, f, rng)
+        }
+        fn party_share_batch<R: Rng>(owner: usize, f: Vec<Self::Base>, _rng: &mut R) -> Vec<Self> {
+            let fs = if Net::party_id() == owner {
+                Some(vec![f; Net::n_parties()])
+            } else {
+                None
+            };
+            Net::recv_vec_from(owner, fs)
                 .into_iter()
-                .map(|king_f| Self {
-                    val: king_f,
+                .map(|owner_f| Self {
+                    val: owner_f,
                     degree: t(),
                 })
                 .collect()
         }
+        fn init_protocol() {
+            prss::init()
+        }
+        fn deinit_protocol() {
+            prss::deinit()
+        }
     }
     impl<F: FftField> GszFieldShare<F> {
         fn poly_share<'a>(
@@ -260,6 +278,7 @@ pub mod field {
     impl<F: FftField> FieldShare<F> for GszFieldShare<F> {
         fn add(&mut self, other: &Self) -> &mut Self {
             self.val += other.val;
+            self.degree = std::cmp::max(self.degree, other.degree);
             self
         }
 
@@ -275,6 +294,7 @@ pub mod field {
 
         fn sub(&mut self, other: &Self) -> &mut Self {
             self.val -= other.val;
+            self.degree = std::cmp::max(self.degree, other.degree);
             self
         }
 
@@ -287,7 +307,7 @@ pub mod field {
             let (self_vec, mut deg_vec): (Vec<F>, Vec<usize>) =
                 selfs.into_iter().map(|s| (s.val, s.degree)).unzip();
             let timer = start_timer!(|| format!("Batch open: {}", self_vec.len()));
-            let mut all_vals = Net::broadcast(&self_vec);
+            let mut all_vals = Net::broadcast_vec(&self_vec);
             let mut out = Vec::new();
             while all_vals[0].len() > 0 {
                 let vals: Vec<F> = all_vals.iter_mut().map(|v| v.pop().unwrap()).collect();
@@ -314,6 +334,16 @@ pub mod field {
             batch_mult(xs, &ys, true)
         }
 
+        fn batch_mul_in_place<S: BeaverSource<Self, Self, Self>>(
+            selfs: &mut [Self],
+            others: &[Self],
+            _source: &mut S,
+            _scratch: &mut MulScratch<F, Self>,
+        ) {
+            let products = batch_mult(selfs.to_vec(), others, true);
+            selfs.copy_from_slice(&products);
+        }
+
         fn inv<S: super::BeaverSource<Self, Self, Self>>(self, _source: &mut S) -> Self {
             let mut r = rand::<F>();
             let self_r = self.mul(r, _source);
@@ -377,35 +407,38 @@ pub mod field {
 
     /// Yields a t-share of a random r.
     ///
-    /// Stubbed b/c it can be pre-processed.
+    /// Computed locally by PRSS, if it is active. Otherwise, stubbed b/c it can be pre-processed.
     ///
     /// Protocol 3.
-    pub fn rand<F: Field>() -> GszFieldShare<F> {
+    pub fn rand<F: FftField>() -> GszFieldShare<F> {
         GszFieldShare {
-            val: F::one(),
+            val: prss::rand_share().unwrap_or_else(F::one),
             degree: t(),
         }
     }
 
     /// Yields two shares of a random `r`, one of degree t, one of degree 2t
     ///
-    /// Stubbed b/c it can be pre-processed.
+    /// Computed locally by PRSS, if it is active: the 2t-share is the t-share plus a 2t-share of
+    /// zero. Otherwise, stubbed b/c it can be pre-processed.
     ///
     /// Protocol 4.
-    pub fn double_rand<F: Field>() -> (GszFieldShare<F>, GszFieldShare<F>) {
+    pub fn double_rand<F: FftField>() -> (GszFieldShare<F>, GszFieldShare<F>) {
+        let r = prss::rand_share::<F>().unwrap_or_else(F::one);
+        let z = prss::zero_share::<F>().unwrap_or_else(F::zero);
         (
             GszFieldShare {
-                val: F::one(),
+                val: r,
                 degree: t(),
             },
             GszFieldShare {
-                val: F::one(),
+                val: r + z,
                 degree: 2 * t(),
             },
         )
     }
 
-    pub fn batch_double_rand<F: Field>(n: usize) -> (Vec<GszFieldShare<F>>, Vec<GszFieldShare<F>>) {
+    pub fn batch_double_rand<F: FftField>(n: usize) -> (Vec<GszFieldShare<F>>, Vec<GszFieldShare<F>>) {
         (0..n).map(|_| double_rand()).unzip()
     }
 
@@ -445,14 +478,17 @@ pub mod field {
         domain.ifft_in_place(&mut shares);
         //end_timer!(ifft_timer);
         //let eval_timer = start_timer!(|| "polyeval");
+        // check the high coefficients all at once, before anything branches on them
+        if !ct::all(shares.iter().skip(d + 1).map(ct::is_zero)) {
+            let p = ark_poly::univariate::DensePolynomial::from_coefficients_vec(shares);
+            panic!(
+                "Polynomial\n{:?}\nhas degree {} (> degree bound {})",
+                p,
+                p.degree(),
+                d
+            );
+        }
         let p = ark_poly::univariate::DensePolynomial::from_coefficients_vec(shares);
-        assert!(
-            p.degree() <= d,
-            "Polynomial\n{:?}\nhas degree {} (> degree bound {})",
-            p,
-            p.degree(),
-            d
-        );
         let r = p.evaluate(&F::zero());
         //end_timer!(eval_timer);
         r
@@ -462,7 +498,7 @@ pub mod field {
     /// * A share `share`
     /// * A function over plain data, `f`
     ///
-    /// 1. Opens the share to King.
+    /// 1. Opens the share to King (rotating among the parties).
     /// 2. King performs the function.
     /// 3. King reshares the result.
     pub fn king_compute<F: FftField, Func: FnOnce(F) -> F>(
@@ -470,14 +506,15 @@ pub mod field {
         new_degree: usize,
         f: Func,
     ) -> GszFieldShare<F> {
-        let king_answer = Net::send_to_king(&share.val).map(|shares| {
+        let king = Net::next_king();
+        let king_answer = Net::send_to(king, &share.val).map(|shares| {
             let n = shares.len();
             let value = open_degree_vec(shares, share.degree);
             let output = f(value);
             // TODO: randomize
             vec![output; n]
         });
-        let from_king = Net::recv_from_king(king_answer);
+        let from_king = Net::recv_from(king, king_answer);
         GszFieldShare {
             degree: new_degree,
             val: from_king,
@@ -488,7 +525,7 @@ pub mod field {
     /// * Shares `share`
     /// * A function over plain data, `f`
     ///
-    /// 1. Opens the share to King.
+    /// 1. Opens the share to King (rotating among the parties).
     /// 2. King performs the function.
     /// 3. King reshares the result.
     pub fn batch_king_compute<F: FftField, Func: Fn(F) -> F>(
@@ -497,7 +534,8 @@ pub mod field {
         f: Func,
     ) -> Vec<GszFieldShare<F>> {
         let values: Vec<F> = shares.iter().map(|s| s.val).collect();
-        let king_answer = Net::send_to_king(&values).map(|all_shares| {
+        let king = Net::next_king();
+        let king_answer = Net::send_vec_to(king, &values).map(|all_shares| {
             let kc_timer = start_timer!(|| format!("King computation"));
             let n = all_shares.len();
             let mut outputs = vec![Vec::new(); n];
@@ -513,7 +551,7 @@ pub mod field {
             end_timer!(kc_timer);
             outputs
         });
-        let from_king = Net::recv_from_king(king_answer);
+        let from_king = Net::recv_vec_from(king, king_answer);
         from_king
             .into_iter()
             .map(|from_king| GszFieldShare {
@@ -541,10 +579,10 @@ pub mod field {
         let (r, r2) = double_rand::<F>();
         let mut x_cp = x.clone();
         x_cp.val *= y.val;
-        x_cp.degree *= 2;
+        x_cp.degree = std::cmp::max(x.degree + y.degree, r2.degree);
         x_cp.val += r2.val;
         // king just reduces the sharing degree
-        let mut shift_res = king_compute(&x_cp, x_cp.degree / 2, |r| r);
+        let mut shift_res = king_compute(&x_cp, r.degree, |r| r);
         shift_res.val -= r.val;
         if queue_check {
             let triple = GszFieldTriple(x, y.clone(), shift_res);
@@ -570,12 +608,12 @@ pub mod field {
         for ((x, y), r2) in x_cp.iter_mut().zip(y).zip(r2) {
             assert_eq!(x.degree, d);
             x.val *= y.val;
-            x.degree *= 2;
+            x.degree = std::cmp::max(x.degree + y.degree, r2.degree);
             x.val += r2.val;
         }
         // king just reduces the sharing degree
         let kc_timer = start_timer!(|| format!("King compute wrapper"));
-        let mut shift_res = batch_king_compute(&x_cp, x_cp[0].degree / 2, |r| r);
+        let mut shift_res = batch_king_compute(&x_cp, t(), |r| r);
         end_timer!(kc_timer);
         for (shift_res, r) in shift_res.iter_mut().zip(r) {
             shift_res.val -= r.val;
@@ -801,8 +839,9 @@ pub mod field {
         }
         let (r, r2) = double_rand::<F>();
         acc += r2.val;
+        let degree = std::cmp::max(degree, r2.degree);
         let acc_share = GszFieldShare { val: acc, degree };
-        let mut shifted_result = king_compute(&acc_share, degree / 2, |r| r);
+        let mut shifted_result = king_compute(&acc_share, r.degree, |r| r);
         shifted_result.sub(&r);
         shifted_result
     }
@@ -837,6 +876,12 @@ pub mod group {
         pub degree: usize,
         pub _phants: PhantomData<M>,
     }
+
+    impl<T: Zeroize, M> Zeroize for GszGroupShare<T, M> {
+        fn zeroize(&mut self) {
+            self.val.zeroize();
+        }
+    }
     impl<T: Group, M> Display for GszGroupShare<T, M> {
         fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
             write!(f, "{}", self.val)
@@ -858,11 +903,22 @@ pub mod group {
         }
     }
     impl<T: Group, M> CanonicalSerialize for GszGroupShare<T, M> {
-        fn serialize<W: Write>(&self, _writer: W) -> Result<(), SerializationError> {
-            unimplemented!("serialize")
+        fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+            self.val.serialize(&mut writer)?;
+            (self.degree as u64).serialize(writer)
         }
         fn serialized_size(&self) -> usize {
-            unimplemented!("serialized_size")
+            self.val.serialized_size() + 8
+        }
+        fn serialize_uncompressed<W: Write>(
+            &self,
+            mut writer: W,
+        ) -> Result<(), SerializationError> {
+            self.val.serialize_uncompressed(&mut writer)?;
+            (self.degree as u64).serialize(writer)
+        }
+        fn uncompressed_size(&self) -> usize {
+            self.val.uncompressed_size() + 8
         }
     }
     impl<T: Group, M> CanonicalSerializeWithFlags for GszGroupShare<T, M> {
@@ -879,8 +935,19 @@ pub mod group {
         }
     }
     impl<T: Group, M> CanonicalDeserialize for GszGroupShare<T, M> {
-        fn deserialize<R: Read>(_reader: R) -> Result<Self, SerializationError> {
-            unimplemented!("deserialize")
+        fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+            Ok(Self {
+                val: T::deserialize(&mut reader)?,
+                degree: u64::deserialize(reader)? as usize,
+                _phants: PhantomData,
+            })
+        }
+        fn deserialize_uncompressed<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+            Ok(Self {
+                val: T::deserialize_uncompressed(&mut reader)?,
+                degree: u64::deserialize(reader)? as usize,
+                _phants: PhantomData,
+            })
         }
     }
     impl<T: Group, M> CanonicalDeserializeWithFlags for GszGroupShare<T, M> {
@@ -927,7 +994,7 @@ pub mod group {
         }
         fn king_share_batch<R: Rng>(f: Vec<Self::Base>, _rng: &mut R) -> Vec<Self> {
             let fs = vec![f; Net::n_parties()];
-            let king_fs = Net::recv_from_king(if Net::am_king() { Some(fs) } else { None });
+            let king_fs = Net::recv_vec_from(0, if Net::am_king() { Some(fs) } else { None });
             king_fs
                 .into_iter()
                 .map(|king_f| Self {
@@ -944,11 +1011,13 @@ pub mod group {
 
         fn add(&mut self, other: &Self) -> &mut Self {
             self.val += &other.val;
+            self.degree = std::cmp::max(self.degree, other.degree);
             self
         }
 
         fn sub(&mut self, other: &Self) -> &mut Self {
             self.val -= &other.val;
+            self.degree = std::cmp::max(self.degree, other.degree);
             self
         }
 
@@ -980,12 +1049,8 @@ pub mod group {
         }
 
         fn multi_scale_pub_group(bases: &[G], scalars: &[Self::FieldShare]) -> Self {
-            let degree = if scalars.len() > 0 {
-                scalars[0].degree
-            } else {
-                0
-            };
-            assert!(scalars.iter().all(|s| s.degree == degree));
+            // public scalars (of degree 0) may be mixed in, as with a proof's public inputs
+            let degree = scalars.iter().map(|s| s.degree).max().unwrap_or(0);
             let s_t = start_timer!(|| "Collecting scalar shares");
             let scalars: Vec<G::ScalarField> = scalars.into_iter().map(|s| s.val.clone()).collect();
             end_timer!(s_t);
@@ -998,6 +1063,30 @@ pub mod group {
                 _phants: Default::default(),
             }
         }
+
+        fn multi_scale_pub_group_by(
+            scalars: &[Self::FieldShare],
+            msm: impl Fn(&[G::ScalarField]) -> G,
+        ) -> Self {
+            let degree = scalars.iter().map(|s| s.degree).max().unwrap_or(0);
+            let scalars: Vec<G::ScalarField> = scalars.iter().map(|s| s.val).collect();
+            Self {
+                val: msm(&scalars),
+                degree,
+                _phants: Default::default(),
+            }
+        }
+
+        fn msm_pub_scalar_shared_base(bases: &[Self], scalars: &[G::ScalarField]) -> Self {
+            let degree = if bases.len() > 0 { bases[0].degree } else { 0 };
+            assert!(bases.iter().all(|b| b.degree == degree));
+            let bases: Vec<G> = bases.iter().map(|b| b.val).collect();
+            Self {
+                val: M::msm(&bases, scalars),
+                degree,
+                _phants: Default::default(),
+            }
+        }
     }
 
     /// Yields a t-share of a random r.
@@ -1058,8 +1147,8 @@ pub mod group {
                 let mut coeff = G::zero();
                 // 1/N * w^{-ij}
                 let mut w_inv_ij = n_inv;
-                for _j in 0..n {
-                    coeff += shares[i].mul(&w_inv_ij);
+                for j in 0..n {
+                    coeff += shares[j].mul(&w_inv_ij);
                     w_inv_ij *= &w_inv_i;
                 }
                 w_inv_i *= &w_inv;
@@ -1067,13 +1156,11 @@ pub mod group {
             })
             .collect();
         assert_eq!(coeffs.len(), n);
-        for i in d + 1..n {
-            assert!(
-                coeffs[i].is_zero(),
+        if !ct::all(coeffs[d + 1..].iter().map(ct::is_zero)) {
+            let i = (d + 1..n).find(|&i| !coeffs[i].is_zero()).unwrap();
+            panic!(
                 "Non-identity coeffs {} ({}), when expecting a degree <= {} poly",
-                i,
-                coeffs[i],
-                d
+                i, coeffs[i], d
             );
         }
         coeffs[0]
@@ -1084,7 +1171,7 @@ pub mod group {
     /// * A function over plain data, `f`
     ///   * which also outputs a sharing degree.
     ///
-    /// 1. Opens the share to King.
+    /// 1. Opens the share to King (rotating among the parties).
     /// 2. King performs the function.
     /// 3. King reshares the result.
     pub fn king_compute<G: Group, M, Func: FnOnce(G) -> G>(
@@ -1092,14 +1179,15 @@ pub mod group {
         new_degree: usize,
         f: Func,
     ) -> GszGroupShare<G, M> {
-        let king_answer = Net::send_to_king(&share.val).map(|shares| {
+        let king = Net::next_king();
+        let king_answer = Net::send_to(king, &share.val).map(|shares| {
             let n = shares.len();
             let value = open_degree_vec(shares, share.degree);
             let output = f(value);
             // TODO: randomize
             vec![output; n]
         });
-        let from_king = Net::recv_from_king(king_answer);
+        let from_king = Net::recv_from(king, king_answer);
         GszGroupShare {
             degree: new_degree,
             val: from_king,
@@ -1117,10 +1205,10 @@ pub mod group {
         let mut y_cp = y.clone();
         let (r, r2) = double_rand::<G, M>();
         y_cp.val *= x.val;
-        y_cp.degree *= 2;
+        y_cp.degree = std::cmp::max(x.degree + y.degree, r2.degree);
         y_cp.val += r2.val;
         // king just reduces the sharing degree
-        let mut shift_res = king_compute(&y_cp, x.degree / 2, |r| r);
+        let mut shift_res = king_compute(&y_cp, r.degree, |r| r);
         shift_res.val -= r.val;
         if queue_check {
             let t = GszGroupTriple(x.clone(), y, shift_res);
@@ -1144,12 +1232,13 @@ pub mod group {
         }
         let (r, r2) = double_rand::<G, M>();
         acc += r2.val;
+        let degree = std::cmp::max(degree, r2.degree);
         let acc_share = GszGroupShare {
             val: acc,
             degree,
             _phants: Default::default(),
         };
-        let mut shifted_result = king_compute(&acc_share, degree / 2, |r| r);
+        let mut shifted_result = king_compute(&acc_share, r.degree, |r| r);
         shifted_result.sub(&r);
         shifted_result
     }
@@ -1421,6 +1510,19 @@ macro_rules! groups_share {
                 }
             }
 
+            fn sh_batch_proj_to_aff(gs: &[Self::ProjectiveShare]) -> Vec<Self::AffineShare> {
+                let vals: Vec<E::$proj> = gs.iter().map(|g| g.val).collect();
+                E::$proj::batch_normalization_into_affine(&vals)
+                    .into_iter()
+                    .zip(gs)
+                    .map(|(val, g)| GszGroupShare {
+                        val,
+                        degree: g.degree,
+                        _phants: Default::default(),
+                    })
+                    .collect()
+            }
+
             fn add_sh_proj_sh_aff(
                 mut a: Self::ProjectiveShare,
                 o: &Self::AffineShare,
@@ -1476,6 +1578,12 @@ pub mod mul_field {
         pub _phants: PhantomData<S>,
     }
 
+    impl<T: Zeroize, S> Zeroize for MulFieldShare<T, S> {
+        fn zeroize(&mut self) {
+            self.val.zeroize();
+        }
+    }
+
     macro_rules! impl_basics_2_param {
         ($share:ident, $bound:ident) => {
             impl<T: $bound, M> Display for $share<T, M> {
@@ -1572,6 +1680,10 @@ pub mod mul_field {
             unimplemented!("add for MulFieldShare")
         }
 
+        fn scale_small(&mut self, c: SmallScalar) -> &mut Self {
+            self.scale(&c.value())
+        }
+
         fn scale(&mut self, other: &F) -> &mut Self {
             self.val *= other;
             self
@@ -1631,8 +1743,8 @@ pub mod mul_field {
                 let mut coeff = F::one();
                 // 1/N * w^{-ij}
                 let mut w_inv_ij = n_inv;
-                for _j in 0..n {
-                    coeff *= shares[i].pow(&w_inv_ij.into_repr());
+                for j in 0..n {
+                    coeff *= shares[j].pow(&w_inv_ij.into_repr());
                     w_inv_ij *= &w_inv_i;
                 }
                 w_inv_i *= &w_inv;
@@ -1640,13 +1752,11 @@ pub mod mul_field {
             })
             .collect();
         assert_eq!(coeffs.len(), n);
-        for i in d + 1..n {
-            assert!(
-                coeffs[i].is_one(),
+        if !ct::all(coeffs[d + 1..].iter().map(ct::is_one)) {
+            let i = (d + 1..n).find(|&i| !coeffs[i].is_one()).unwrap();
+            panic!(
                 "Non-one coeffs {} ({}), when expecting a degree <= {} poly",
-                i,
-                coeffs[i],
-                d
+                i, coeffs[i], d
             );
         }
         coeffs[0]
@@ -1788,4 +1898,35 @@ impl<E: PairingEngine> PairingShare<E> for GszPairingShare<E> {
     type G2ProjectiveShare = GszGroupShare<E::G2Projective, msm::GszG2ProjectiveMsm<E>>;
     type G1 = GszG1Share<E>;
     type G2 = GszG2Share<E>;
+
+    // the pairing of a Shamir share is a share in the exponent, of the same degree
+    fn pairing_sh_g1(
+        p: Self::G1AffineShare,
+        q: &E::G2Affine,
+    ) -> mul_field::MulFieldShare<E::Fqk, E::Fr> {
+        mul_field::MulFieldShare {
+            val: E::pairing(p.val, *q),
+            degree: p.degree,
+            _phants: PhantomData::default(),
+        }
+    }
+
+    fn pairing_sh_g2(
+        p: &E::G1Affine,
+        q: Self::G2AffineShare,
+    ) -> mul_field::MulFieldShare<E::Fqk, E::Fr> {
+        mul_field::MulFieldShare {
+            val: E::pairing(*p, q.val),
+            degree: q.degree,
+            _phants: PhantomData::default(),
+        }
+    }
+
+    fn pow_gt_sh(g: &E::Fqk, exp: Self::FrShare) -> mul_field::MulFieldShare<E::Fqk, E::Fr> {
+        mul_field::MulFieldShare {
+            val: g.pow(exp.val.into_repr()),
+            degree: exp.degree,
+            _phants: PhantomData::default(),
+        }
+    }
 }
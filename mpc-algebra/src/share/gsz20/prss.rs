@@ -0,0 +1,181 @@
+//! Pseudorandom secret sharing (PRSS), following ["Share Conversion, Pseudorandom
+//! Secret-Sharing and Applications to Secure Computation"](https://ia.cr/2004/200) by Cramer,
+//! Damgård and Ishai.
+//!
+//! Every set `A` of `n - t` parties holds a PRG seed `k_A`, established once at [init]. Afterwards,
+//! random degree-`t` sharings and degree-`2t` sharings of zero are computed locally, with no
+//! communication: each party expands the seeds it holds and weights them by a polynomial that is
+//! `1` at zero and vanishes on the parties outside `A`.
+//!
+//! Until [init] is called, [rand_share] and [zero_share] return `None`, and the callers in this
+//! module keep using their pre-processing stubs.
+//!
+//! There are `n choose t` seeds, so this is only practical for small numbers of parties.
+//!
+//! Each network session (see [mpc_net::MpcSession]) has its own seeds and counter.
+use super::*;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sha2::{Digest, Sha256};
+
+type Seed = [u8; 32];
+
+struct PrssState {
+    /// The sets we belong to, with their seeds.
+    sets: Vec<(Vec<usize>, Seed)>,
+    /// Number of values drawn so far; all parties draw in the same order.
+    counter: u64,
+}
+
+impl Drop for PrssState {
+    fn drop(&mut self) {
+        for (_, seed) in &mut self.sets {
+            seed.zeroize();
+        }
+    }
+}
+
+lazy_static! {
+    /// By session tag.
+    static ref PRSS: Mutex<HashMap<u64, PrssState>> = Mutex::new(HashMap::new());
+}
+
+/// All subsets of `0..n` of size `k`, in lexicographic order.
+fn subsets(n: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut out = Vec::new();
+    let mut cur = Vec::with_capacity(k);
+    fn go(start: usize, n: usize, k: usize, cur: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+        if cur.len() == k {
+            out.push(cur.clone());
+            return;
+        }
+        for i in start..n {
+            cur.push(i);
+            go(i + 1, n, k, cur, out);
+            cur.pop();
+        }
+    }
+    go(0, n, k, &mut cur, &mut out);
+    out
+}
+
+/// Establish the seeds.
+///
+/// The least member of each set picks its seed and sends it to the other members. All parties
+/// must call this together; later calls are no-ops until [deinit].
+pub fn init() {
+    if is_active() {
+        return;
+    }
+    let n = Net::n_parties();
+    let me = Net::party_id();
+    let all_sets = subsets(n, n - t());
+    let mut sets = Vec::new();
+    for leader in 0..n {
+        let led: Vec<&Vec<usize>> = all_sets.iter().filter(|a| a[0] == leader).collect();
+        let king_answer = Net::send_to(leader, &Vec::<u8>::new()).map(|_| {
+            let mut rng = crate::seed::rng(b"prss");
+            // messages must have equal lengths, so non-members get a blank in place of the seed
+            let mut per_party: Vec<Vec<Vec<u8>>> = vec![Vec::new(); n];
+            for a in &led {
+                let seed: Seed = rng.gen();
+                for (j, msg) in per_party.iter_mut().enumerate() {
+                    if a.contains(&j) {
+                        msg.push(seed.to_vec());
+                    } else {
+                        msg.push(Seed::default().to_vec());
+                    }
+                }
+            }
+            per_party
+        });
+        let seeds: Vec<Vec<u8>> = Net::recv_from(leader, king_answer);
+        for (a, seed) in led.into_iter().zip(seeds).filter(|(a, _)| a.contains(&me)) {
+            let mut s = Seed::default();
+            s.copy_from_slice(&seed);
+            sets.push((a.clone(), s));
+        }
+    }
+    PRSS.lock()
+        .unwrap()
+        .insert(Net::session_tag(), PrssState { sets, counter: 0 });
+}
+
+/// Forget the seeds.
+pub fn deinit() {
+    PRSS.lock().unwrap().remove(&Net::session_tag());
+}
+
+/// `f_A(x_me)`, where `f_A` has degree `t`, `f_A(0) = 1`, and `f_A` vanishes at the points of
+/// the parties outside `a`.
+fn weight<F: FftField>(a: &[usize]) -> F {
+    let d = domain::<F>();
+    let me = d.element(Net::party_id());
+    (0..Net::n_parties())
+        .filter(|j| !a.contains(j))
+        .map(|j| {
+            let x_j = d.element(j);
+            (x_j - me) * x_j.inverse().unwrap()
+        })
+        .product()
+}
+
+/// Whether [init] has been run.
+pub fn is_active() -> bool {
+    PRSS.lock().unwrap().contains_key(&Net::session_tag())
+}
+
+/// Draw the next value: for each of our sets, a PRG for that set's seed.
+///
+/// `None` if PRSS is not active.
+fn draw<T>(f: impl FnOnce(Vec<(&[usize], StdRng)>) -> T) -> Option<T> {
+    let tag = Net::session_tag();
+    let mut states = PRSS.lock().unwrap();
+    let state = states.get_mut(&tag)?;
+    let counter = state.counter;
+    state.counter += 1;
+    let rngs = state
+        .sets
+        .iter()
+        .map(|(a, seed)| {
+            let key = Sha256::new()
+                .chain(&seed[..])
+                .chain(&counter.to_le_bytes())
+                .finalize();
+            let mut s = Seed::default();
+            s.copy_from_slice(&key);
+            (&a[..], StdRng::from_seed(s))
+        })
+        .collect();
+    Some(f(rngs))
+}
+
+/// Our share of a random value, shared with degree `t`, or `None` if PRSS is not active.
+pub fn rand_share<F: FftField>() -> Option<F> {
+    draw(|rngs| {
+        rngs.into_iter()
+            .map(|(a, mut rng)| F::rand(&mut rng) * weight::<F>(a))
+            .sum()
+    })
+}
+
+/// Our share of zero, shared with degree `2t`, or `None` if PRSS is not active.
+pub fn zero_share<F: FftField>() -> Option<F> {
+    let x_me = domain::<F>().element(Net::party_id());
+    draw(|rngs| {
+        rngs.into_iter()
+            .map(|(a, mut rng)| {
+                // sum_l r_l * x^l * f_A(x), for l in 1..=t
+                let w = weight::<F>(a);
+                let mut x_l = F::one();
+                let mut acc = F::zero();
+                for _ in 0..t() {
+                    x_l *= x_me;
+                    acc += F::rand(&mut rng) * x_l;
+                }
+                acc * w
+            })
+            .sum()
+    })
+}
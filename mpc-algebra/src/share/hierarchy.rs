@@ -0,0 +1,341 @@
+//! Hierarchical sharing, for federations of committees.
+//!
+//! The parties are split into [Committees], say one per organization, each of several servers. A
+//! value is shared additively among the committees, and each committee's share is in turn Shamir
+//! shared among its members, with the committee's own threshold `t`. So a committee can open its
+//! share with any `t + 1` of its members, tolerating the loss of the rest, while learning nothing
+//! about the value from its share alone; and a coalition learns nothing unless it holds more than
+//! `t` members of every committee.
+//!
+//! A [HierFieldShare] supports the linear operations locally, and opening. For anything else,
+//! [HierFieldShare::to_additive] turns it, without communication, into an [AdditiveFieldShare]
+//! among all parties, for the honest-but-curious protocols; [HierFieldShare::from_additive] turns
+//! the result back.
+use ark_ff::Field;
+use lazy_static::lazy_static;
+use rand::Rng;
+
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+use std::sync::Mutex;
+
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+
+use super::add::AdditiveFieldShare;
+use crate::channel::MpcSerNet;
+use crate::Reveal;
+
+/// How the parties are split into committees: committee `0` is the first parties, and so on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Committees {
+    sizes: Vec<usize>,
+    thresholds: Vec<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HierarchyError {
+    /// Fewer than `t + 1` of the committee's members have a share.
+    TooFew {
+        committee: usize,
+        needed: usize,
+        present: usize,
+    },
+    /// The shares of the committee do not lie on a polynomial of degree `t`: some member's share
+    /// is wrong.
+    Inconsistent { committee: usize, member: usize },
+}
+
+impl Display for HierarchyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HierarchyError::TooFew {
+                committee,
+                needed,
+                present,
+            } => write!(
+                f,
+                "committee {} needs {} shares to open, but has {}",
+                committee, needed, present
+            ),
+            HierarchyError::Inconsistent { committee, member } => write!(
+                f,
+                "the share of member {} of committee {} is inconsistent with the others",
+                member, committee
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HierarchyError {}
+
+impl Committees {
+    /// Committees of `sizes[c]` members, which tolerate the loss of all but `thresholds[c] + 1`.
+    pub fn new(sizes: Vec<usize>, thresholds: Vec<usize>) -> Self {
+        assert!(!sizes.is_empty(), "no committees");
+        assert_eq!(sizes.len(), thresholds.len(), "one threshold per committee");
+        for (c, (s, t)) in sizes.iter().zip(&thresholds).enumerate() {
+            assert!(
+                t < s,
+                "the threshold of committee {} must be below its {} members",
+                c,
+                s
+            );
+        }
+        Self { sizes, thresholds }
+    }
+
+    /// `committees` committees of `members` members each, with threshold `threshold`.
+    pub fn uniform(committees: usize, members: usize, threshold: usize) -> Self {
+        Self::new(vec![members; committees], vec![threshold; committees])
+    }
+
+    /// The number of committees.
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    pub fn n_parties(&self) -> usize {
+        self.sizes.iter().sum()
+    }
+
+    pub fn threshold(&self, committee: usize) -> usize {
+        self.thresholds[committee]
+    }
+
+    /// The parties in `committee`.
+    pub fn members(&self, committee: usize) -> Range<usize> {
+        let start = self.sizes[..committee].iter().sum();
+        start..start + self.sizes[committee]
+    }
+
+    /// The committee of `party`, and its index there.
+    pub fn committee_of(&self, party: usize) -> (usize, usize) {
+        let mut start = 0;
+        for (c, s) in self.sizes.iter().enumerate() {
+            if party < start + s {
+                return (c, party - start);
+            }
+            start += s;
+        }
+        panic!("party {} is in no committee", party)
+    }
+
+    /// Open `committee`'s share from those of its members, `None` for a member that is missing.
+    ///
+    /// Any `t + 1` shares determine it; the others are checked against them.
+    pub fn reconstruct<F: Field>(
+        &self,
+        committee: usize,
+        shares: &[Option<F>],
+    ) -> Result<F, HierarchyError> {
+        assert_eq!(shares.len(), self.sizes[committee]);
+        let needed = self.thresholds[committee] + 1;
+        // (member, point, share)
+        let present: Vec<(usize, F, F)> = shares
+            .iter()
+            .enumerate()
+            .filter_map(|(i, s)| s.map(|s| (i, point::<F>(i), s)))
+            .collect();
+        if present.len() < needed {
+            return Err(HierarchyError::TooFew {
+                committee,
+                needed,
+                present: present.len(),
+            });
+        }
+        let (basis, rest) = present.split_at(needed);
+        let xs: Vec<F> = basis.iter().map(|(_, x, _)| *x).collect();
+        let at = |x: F| -> F {
+            lagrange_at(&xs, x)
+                .into_iter()
+                .zip(basis)
+                .map(|(l, (_, _, s))| l * s)
+                .sum()
+        };
+        for (member, x, s) in rest {
+            if at(*x) != *s {
+                return Err(HierarchyError::Inconsistent {
+                    committee,
+                    member: *member,
+                });
+            }
+        }
+        Ok(at(F::zero()))
+    }
+}
+
+lazy_static! {
+    /// By session tag.
+    static ref COMMITTEES: Mutex<HashMap<u64, Committees>> = Mutex::new(HashMap::new());
+}
+
+/// Split the parties of the current session into `committees`, or with `None`, forget the split.
+///
+/// Panics if the committees do not hold exactly the session's parties.
+pub fn set_committees(committees: Option<Committees>) {
+    let mut all = COMMITTEES.lock().unwrap();
+    match committees {
+        Some(c) => {
+            assert_eq!(
+                c.n_parties(),
+                Net::n_parties(),
+                "the committees must hold exactly the session's parties"
+            );
+            all.insert(Net::session_tag(), c);
+        }
+        None => {
+            all.remove(&Net::session_tag());
+        }
+    }
+}
+
+/// The committees of the current session.
+///
+/// Panics if none were set.
+pub fn committees() -> Committees {
+    COMMITTEES
+        .lock()
+        .unwrap()
+        .get(&Net::session_tag())
+        .cloned()
+        .expect("no committees set for this session")
+}
+
+/// The Shamir evaluation point of the `i`th member of a committee.
+fn point<F: Field>(i: usize) -> F {
+    F::from(i as u64 + 1)
+}
+
+/// The Lagrange basis on `xs`, evaluated at `x`.
+fn lagrange_at<F: Field>(xs: &[F], x: F) -> Vec<F> {
+    xs.iter()
+        .enumerate()
+        .map(|(i, xi)| {
+            let (num, den) = xs
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold((F::one(), F::one()), |(num, den), (_, xj)| {
+                    (num * (x - xj), den * (*xi - xj))
+                });
+            num * den.inverse().unwrap()
+        })
+        .collect()
+}
+
+/// A share of a share: this party's Shamir share of its committee's additive share.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HierFieldShare<F> {
+    pub val: F,
+}
+
+impl<F: Field> HierFieldShare<F> {
+    /// Share `values`, which only `owner`'s are used, among the committees of the session.
+    pub fn party_share_batch<R: Rng>(owner: usize, values: Vec<F>, rng: &mut R) -> Vec<Self> {
+        let shares = if Net::party_id() == owner {
+            let committees = committees();
+            let mut out = vec![Vec::with_capacity(values.len()); committees.n_parties()];
+            for v in &values {
+                let mut rest = *v;
+                for c in 0..committees.len() {
+                    let additive = if c + 1 == committees.len() {
+                        rest
+                    } else {
+                        F::rand(rng)
+                    };
+                    rest -= additive;
+                    // a random polynomial of degree `t` through `additive` at zero
+                    let coeffs: Vec<F> = std::iter::once(additive)
+                        .chain((0..committees.threshold(c)).map(|_| F::rand(rng)))
+                        .collect();
+                    for (i, p) in committees.members(c).enumerate() {
+                        let x = point::<F>(i);
+                        out[p].push(coeffs.iter().rev().fold(F::zero(), |acc, a| acc * x + a));
+                    }
+                }
+            }
+            Some(out)
+        } else {
+            None
+        };
+        Net::recv_vec_from(owner, shares)
+            .into_iter()
+            .map(|val| Self { val })
+            .collect()
+    }
+
+    /// Open shares, from all members of every committee.
+    ///
+    /// Panics, naming the committee, if a committee's shares are inconsistent.
+    pub fn batch_open(selfs: impl IntoIterator<Item = Self>) -> Vec<F> {
+        let mine: Vec<F> = selfs.into_iter().map(|s| s.val).collect();
+        let all = Net::broadcast_vec(&mine);
+        let committees = committees();
+        (0..mine.len())
+            .map(|i| {
+                (0..committees.len())
+                    .map(|c| {
+                        let shares: Vec<Option<F>> =
+                            committees.members(c).map(|p| Some(all[p][i])).collect();
+                        committees
+                            .reconstruct(c, &shares)
+                            .unwrap_or_else(|e| panic!("{}", e))
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    pub fn open(self) -> F {
+        Self::batch_open(vec![self]).pop().unwrap()
+    }
+
+    pub fn add(&mut self, other: &Self) -> &mut Self {
+        self.val += other.val;
+        self
+    }
+
+    pub fn sub(&mut self, other: &Self) -> &mut Self {
+        self.val -= other.val;
+        self
+    }
+
+    pub fn scale(&mut self, other: &F) -> &mut Self {
+        self.val *= other;
+        self
+    }
+
+    /// Add a public value, to the share of the first committee.
+    pub fn shift(&mut self, other: &F) -> &mut Self {
+        if committees().committee_of(Net::party_id()).0 == 0 {
+            self.val += other;
+        }
+        self
+    }
+
+    /// An additive share of the same value among all the parties, without communication.
+    pub fn to_additive(self) -> AdditiveFieldShare<F> {
+        let committees = committees();
+        let (c, i) = committees.committee_of(Net::party_id());
+        let xs: Vec<F> = (0..committees.members(c).len()).map(point).collect();
+        AdditiveFieldShare::from_add_shared(self.val * lagrange_at(&xs, F::zero())[i])
+    }
+
+    /// Hierarchical shares of the same values as `shares`, additive among all the parties.
+    ///
+    /// Each party shares its own, in turn, so this takes one exchange per party.
+    pub fn from_additive<R: Rng>(shares: Vec<AdditiveFieldShare<F>>, rng: &mut R) -> Vec<Self> {
+        let mine: Vec<F> = shares.into_iter().map(|s| s.val).collect();
+        let mut out = vec![Self { val: F::zero() }; mine.len()];
+        for owner in 0..Net::n_parties() {
+            for (o, s) in out
+                .iter_mut()
+                .zip(Self::party_share_batch(owner, mine.clone(), rng))
+            {
+                o.add(&s);
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,134 @@
+//! Fresh sharings of zero, and masks for opening shared values without revealing them.
+//!
+//! A protocol that must open some function of a secret `x` opens `c = x + r` instead, for a fresh
+//! random sharing `r`: [mask] does this, and the opening is uniform whatever `x` is. Whatever the
+//! parties then compute from `c` in public, [unmask] turns back into a sharing, by subtracting
+//! `r`. A fresh sharing of zero, added to a sharing, gives the same value with shares that say
+//! nothing about where they came from: [rerandomize].
+//!
+//! Both come from a [MaskSource]: a [RandomnessPool], for randomness preprocessed with
+//! [RandomnessPool::generate] and [RandomnessPool::generate_zeros], or [Prss], for GSZ20 shares,
+//! which computes them locally from the seeds of pseudorandom secret sharing.
+use ark_ff::{prelude::*, FftField};
+
+use super::field::FieldShare;
+use super::gsz20::{prss, t, GszFieldShare};
+use super::randomness::{RandomnessError, RandomnessPool};
+
+pub trait MaskSource<F: Field, S: FieldShare<F>> {
+    /// A fresh sharing of a uniformly random field element, to mask a value with.
+    fn mask_share(&mut self) -> Result<S, RandomnessError>;
+    /// A fresh sharing of zero.
+    fn zero_share(&mut self) -> Result<S, RandomnessError>;
+    fn mask_shares(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        (0..n).map(|_| self.mask_share()).collect()
+    }
+    fn zero_shares(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        (0..n).map(|_| self.zero_share()).collect()
+    }
+}
+
+/// The sharing that masked a value, to [unmask] with.
+///
+/// It is wiped when dropped: anyone who learns it and the opening learns the value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mask<F: Field, S: FieldShare<F>> {
+    r: S,
+    _field: std::marker::PhantomData<F>,
+}
+
+impl<F: Field, S: FieldShare<F>> Mask<F, S> {
+    /// Our share of the mask.
+    pub fn share(&self) -> &S {
+        &self.r
+    }
+}
+
+impl<F: Field, S: FieldShare<F>> Drop for Mask<F, S> {
+    fn drop(&mut self) {
+        self.r.zeroize();
+    }
+}
+
+/// Open `x + r`, for a fresh random sharing `r` from `source`, and return the opening with `r`.
+///
+/// Costs one opening.
+pub fn mask<F: Field, S: FieldShare<F>, M: MaskSource<F, S>>(
+    x: S,
+    source: &mut M,
+) -> Result<(F, Mask<F, S>), RandomnessError> {
+    let (mut opened, mut masks) = batch_mask(vec![x], source)?;
+    Ok((opened.remove(0), masks.remove(0)))
+}
+
+/// [mask] each of `xs`, with one round of openings for all of them.
+pub fn batch_mask<F: Field, S: FieldShare<F>, M: MaskSource<F, S>>(
+    xs: Vec<S>,
+    source: &mut M,
+) -> Result<(Vec<F>, Vec<Mask<F, S>>), RandomnessError> {
+    let rs = source.mask_shares(xs.len())?;
+    let opened = S::batch_open(xs.into_iter().zip(&rs).map(|(mut x, r)| *x.add(r)));
+    let masks = rs
+        .into_iter()
+        .map(|r| Mask {
+            r,
+            _field: Default::default(),
+        })
+        .collect();
+    Ok((opened, masks))
+}
+
+/// A sharing of `c - r`, for a public `c` and the mask `r`, with no communication.
+///
+/// With the opening of [mask] as `c`, this is a sharing of the masked value again.
+pub fn unmask<F: Field, S: FieldShare<F>>(c: F, mask: &Mask<F, S>) -> S {
+    let mut x = mask.r;
+    *x.neg().shift(&c)
+}
+
+/// A fresh sharing of the value of `x`, from a sharing of zero from `source`, with no
+/// communication.
+pub fn rerandomize<F: Field, S: FieldShare<F>, M: MaskSource<F, S>>(
+    mut x: S,
+    source: &mut M,
+) -> Result<S, RandomnessError> {
+    Ok(*x.add(&source.zero_share()?))
+}
+
+impl<F: Field, S: FieldShare<F>> MaskSource<F, S> for RandomnessPool<F, S> {
+    fn mask_share(&mut self) -> Result<S, RandomnessError> {
+        Ok(self.take_shares(1)?[0])
+    }
+    fn zero_share(&mut self) -> Result<S, RandomnessError> {
+        Ok(self.take_zeros(1)?[0])
+    }
+    fn mask_shares(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        self.take_shares(n)
+    }
+    fn zero_shares(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        self.take_zeros(n)
+    }
+}
+
+/// Masks and sharings of zero for GSZ20 shares, computed locally from the PRSS seeds that
+/// [GszFieldShare]'s `init_protocol` establishes, so with neither preprocessing nor
+/// communication.
+///
+/// Its sharings of zero have degree `2t`, and so does any share that one [rerandomize]s: opening
+/// it takes `2t + 1` parties. All parties must draw from it in the same order.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Prss;
+
+impl<F: FftField> MaskSource<F, GszFieldShare<F>> for Prss {
+    fn mask_share(&mut self) -> Result<GszFieldShare<F>, RandomnessError> {
+        let val = prss::rand_share().ok_or(RandomnessError::NoPrss)?;
+        Ok(GszFieldShare { val, degree: t() })
+    }
+    fn zero_share(&mut self) -> Result<GszFieldShare<F>, RandomnessError> {
+        let val = prss::zero_share().ok_or(RandomnessError::NoPrss)?;
+        Ok(GszFieldShare {
+            val,
+            degree: 2 * t(),
+        })
+    }
+}
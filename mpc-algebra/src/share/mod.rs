@@ -8,8 +8,19 @@ pub mod msm;
 pub mod add;
 pub use add::*;
 pub mod spdz;
+pub mod blame;
 pub use spdz::*;
 pub mod gsz20;
+pub mod hierarchy;
+pub mod randomness;
+pub use randomness::*;
+pub mod store;
+pub mod dealer;
+pub mod masking;
+pub use masking::*;
+pub mod envelope;
+pub mod dynamic;
+pub use dynamic::{DynFieldShare, DynPairingShare, DynShare, Scheme};
 pub use gsz20::*;
 
 use std::marker::PhantomData;
@@ -29,6 +40,22 @@ pub trait BeaverSource<A, B, C>: Clone {
         }
         (xs, ys, zs)
     }
+    /// Like [BeaverSource::triples], but writes into existing buffers, which are cleared first.
+    fn triples_into(&mut self, n: usize, xs: &mut Vec<A>, ys: &mut Vec<B>, zs: &mut Vec<C>) {
+        xs.clear();
+        ys.clear();
+        zs.clear();
+        xs.reserve(n);
+        ys.reserve(n);
+        zs.reserve(n);
+        for _ in 0..n {
+            let (x, y, z) = self.triple();
+            xs.push(x);
+            ys.push(y);
+            zs.push(z);
+        }
+    }
+    /// Sharings of a random `r`, and of `1 / r`.
     fn inv_pair(&mut self) -> (B, B);
     fn inv_pairs(&mut self, n: usize) -> (Vec<B>, Vec<B>) {
         let mut xs = Vec::new();
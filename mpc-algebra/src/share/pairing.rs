@@ -1,10 +1,11 @@
 use ark_ec::{group::Group, AffineCurve, PairingEngine, ProjectiveCurve};
-use ark_ff::Field;
+use ark_ff::{Field, PrimeField};
 
 use std::fmt::Debug;
 
 use super::field::{ExtFieldShare, FieldShare};
 use super::group::GroupShare;
+use crate::Reveal;
 
 pub trait AffProjShare<
     Fr: Field,
@@ -17,6 +18,11 @@ pub trait AffProjShare<
     type ProjectiveShare: GroupShare<P, FieldShare = Self::FrShare>;
     fn sh_aff_to_proj(g: Self::AffineShare) -> Self::ProjectiveShare;
     fn sh_proj_to_aff(g: Self::ProjectiveShare) -> Self::AffineShare;
+    /// [sh_proj_to_aff](AffProjShare::sh_proj_to_aff) for a batch. A party's shares are points of
+    /// its own, so it can normalize them all locally, with one inversion.
+    fn sh_batch_proj_to_aff(gs: &[Self::ProjectiveShare]) -> Vec<Self::AffineShare> {
+        gs.iter().map(|g| Self::sh_proj_to_aff(*g)).collect()
+    }
     fn add_sh_proj_sh_aff(
         _a: Self::ProjectiveShare,
         _o: &Self::AffineShare,
@@ -60,4 +66,35 @@ pub trait PairingShare<E: PairingEngine>:
         AffineShare = Self::G2AffineShare,
         ProjectiveShare = Self::G2ProjectiveShare,
     >;
+
+    /// The pairing of a shared `G1` point with a public `G2` point, shared in `GT`.
+    ///
+    /// The pairing is linear in `p`, so each party pairs its own share, locally, and the results
+    /// are shares of the pairing in the multiplicative group.
+    fn pairing_sh_g1(
+        p: Self::G1AffineShare,
+        q: &E::G2Affine,
+    ) -> <Self::FqkShare as ExtFieldShare<E::Fqk>>::Ext {
+        Reveal::from_add_shared(E::pairing(p.unwrap_as_public(), *q))
+    }
+
+    /// The pairing of a public `G1` point with a shared `G2` point, shared in `GT`; see
+    /// [PairingShare::pairing_sh_g1].
+    fn pairing_sh_g2(
+        p: &E::G1Affine,
+        q: Self::G2AffineShare,
+    ) -> <Self::FqkShare as ExtFieldShare<E::Fqk>>::Ext {
+        Reveal::from_add_shared(E::pairing(*p, q.unwrap_as_public()))
+    }
+
+    /// A public `GT` element raised to a shared scalar, shared in `GT`.
+    ///
+    /// Exponentiation is linear in the exponent, so each party raises the element to its own
+    /// share, locally.
+    fn pow_gt_sh(
+        g: &E::Fqk,
+        exp: Self::FrShare,
+    ) -> <Self::FqkShare as ExtFieldShare<E::Fqk>>::Ext {
+        Reveal::from_add_shared(g.pow(exp.unwrap_as_public().into_repr()))
+    }
 }
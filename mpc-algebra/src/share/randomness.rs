@@ -0,0 +1,264 @@
+//! Preprocessed shared randomness.
+//!
+//! Protocols for masking, inversion and bit decomposition consume sharings of random field
+//! elements and random bits, and re-randomizing shares consumes sharings of zero. A
+//! [RandomnessPool] holds these, generated ahead of time with [RandomnessPool::generate] and
+//! [RandomnessPool::generate_zeros], and kept in one file per party. When the pool runs dry,
+//! requests fail with [RandomnessError::Exhausted], rather than falling back to some weaker
+//! source.
+use ark_ff::prelude::*;
+use ark_serialize::SerializationError;
+use derivative::Derivative;
+use rand::Rng;
+use zeroize::Zeroize;
+
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use super::envelope::{self, EnvelopeError, PayloadKind};
+use super::field::FieldShare;
+use super::BeaverSource;
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+
+pub trait RandomnessSource<F: Field, S: FieldShare<F>> {
+    /// A sharing of a uniformly random field element.
+    fn random_share(&mut self) -> Result<S, RandomnessError>;
+    /// A sharing of a uniformly random bit.
+    fn random_bit(&mut self) -> Result<S, RandomnessError>;
+    fn random_shares(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        (0..n).map(|_| self.random_share()).collect()
+    }
+    fn random_bits(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        (0..n).map(|_| self.random_bit()).collect()
+    }
+}
+
+/// For a shared random `r` whose square has been opened, the public `(a, b)` with which `a r + b`
+/// is a uniformly random shared bit, `(r / sqrt(r^2) + 1) / 2`.
+///
+/// `r^2` hides the sign of `r`, which is all the bit depends on. This is `None` when `r = 0`,
+/// which happens with negligible probability; such an `r` should be drawn again.
+pub fn bit_from_square<F: SquareRootField>(square: &F) -> Option<(F, F)> {
+    let root_inv = square.sqrt()?.inverse()?;
+    let half = F::from(2u8).inverse().expect("characteristic 2");
+    Some((root_inv * half, half))
+}
+
+#[derive(Debug)]
+pub enum RandomnessError {
+    /// The pool holds fewer items of `kind` than were requested.
+    Exhausted {
+        kind: &'static str,
+        requested: usize,
+        available: usize,
+    },
+    /// The pool file is not a pool of these shares for this party.
+    Envelope(EnvelopeError),
+    /// PRSS has no seeds for this session: the GSZ20 protocol is not initialized.
+    NoPrss,
+    Io(io::Error),
+    Serialization(SerializationError),
+}
+
+impl Display for RandomnessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RandomnessError::Exhausted {
+                kind,
+                requested,
+                available,
+            } => write!(
+                f,
+                "randomness pool exhausted: requested {} {}, but only {} remain",
+                requested, kind, available
+            ),
+            RandomnessError::Envelope(e) => write!(f, "randomness pool file: {}", e),
+            RandomnessError::NoPrss => write!(f, "no PRSS seeds: the GSZ20 protocol is not set up"),
+            RandomnessError::Io(e) => write!(f, "randomness pool I/O: {}", e),
+            RandomnessError::Serialization(e) => write!(f, "randomness pool encoding: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RandomnessError {}
+
+impl From<io::Error> for RandomnessError {
+    fn from(e: io::Error) -> Self {
+        RandomnessError::Io(e)
+    }
+}
+
+impl From<EnvelopeError> for RandomnessError {
+    fn from(e: EnvelopeError) -> Self {
+        RandomnessError::Envelope(e)
+    }
+}
+
+impl From<SerializationError> for RandomnessError {
+    fn from(e: SerializationError) -> Self {
+        RandomnessError::Serialization(e)
+    }
+}
+
+/// One party's stock of random shares, random bit shares, and sharings of zero.
+///
+/// All parties must draw from their pools in the same order. The pool wipes the shares it still
+/// holds when it is dropped.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct RandomnessPool<F: Field, S: FieldShare<F>> {
+    party_id: usize,
+    n_parties: usize,
+    shares: Vec<S>,
+    bits: Vec<S>,
+    zeros: Vec<S>,
+    _field: PhantomData<F>,
+}
+
+impl<F: Field, S: FieldShare<F>> RandomnessPool<F, S> {
+    /// An empty pool for this party.
+    pub fn empty() -> Self {
+        Self {
+            party_id: Net::party_id(),
+            n_parties: Net::n_parties(),
+            shares: Vec::new(),
+            bits: Vec::new(),
+            zeros: Vec::new(),
+            _field: PhantomData,
+        }
+    }
+
+    /// Jointly generate `n_shares` random shares and `n_bits` random bits.
+    ///
+    /// This is the offline phase: all parties run it together, before their inputs are known.
+    /// Each bit is derived from a random share `r` by [bit_from_square], which costs one
+    /// multiplication and two openings per bit.
+    pub fn generate<R: Rng, B: BeaverSource<S, S, S>>(
+        n_shares: usize,
+        n_bits: usize,
+        triples: &mut B,
+        rng: &mut R,
+    ) -> Self
+    where
+        F: SquareRootField,
+    {
+        let mut pool = Self::empty();
+        pool.shares = (0..n_shares).map(|_| S::rand(rng)).collect();
+        while pool.bits.len() < n_bits {
+            let needed = n_bits - pool.bits.len();
+            let rs: Vec<S> = (0..needed).map(|_| S::rand(rng)).collect();
+            let squares = S::batch_open(S::batch_mul(rs.clone(), rs.clone(), triples));
+            for (mut r, sq) in rs.into_iter().zip(squares) {
+                if let Some((a, b)) = bit_from_square(&sq) {
+                    r.scale(&a).shift(&b);
+                    pool.bits.push(r);
+                }
+            }
+        }
+        pool
+    }
+
+    /// Jointly add `n_zeros` fresh sharings of zero.
+    ///
+    /// Each is a random sharing minus its opening, so together they cost one opening.
+    pub fn generate_zeros<R: Rng>(&mut self, n_zeros: usize, rng: &mut R) {
+        let rs: Vec<S> = (0..n_zeros).map(|_| S::rand(rng)).collect();
+        let opened = S::batch_open(rs.clone());
+        self.zeros
+            .extend(rs.into_iter().zip(opened).map(|(mut r, c)| *r.shift(&-c)));
+    }
+
+    /// Number of random shares remaining.
+    pub fn shares_left(&self) -> usize {
+        self.shares.len()
+    }
+
+    /// Number of random bits remaining.
+    pub fn bits_left(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Number of sharings of zero remaining.
+    pub fn zeros_left(&self) -> usize {
+        self.zeros.len()
+    }
+
+    /// The conventional file for `party_id`'s pool within `dir`.
+    pub fn party_path(dir: impl AsRef<Path>, party_id: usize) -> PathBuf {
+        dir.as_ref().join(format!("randomness.{}", party_id))
+    }
+
+    /// Write the unused part of the pool to `path`.
+    ///
+    /// Save after drawing from a loaded pool, so that no randomness is ever used twice.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RandomnessError> {
+        let mut payload = (self.shares.clone(), self.bits.clone(), self.zeros.clone());
+        let saved = envelope::save(PayloadKind::Randomness, &payload, path);
+        payload.0.zeroize();
+        payload.1.zeroize();
+        payload.2.zeroize();
+        Ok(saved?)
+    }
+
+    /// Read a pool from `path`, checking that it holds shares of this type for this party.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RandomnessError> {
+        let (shares, bits, zeros) = envelope::load(PayloadKind::Randomness, path)?;
+        Ok(Self {
+            shares,
+            bits,
+            zeros,
+            ..Self::empty()
+        })
+    }
+
+    fn take(
+        from: &mut Vec<S>,
+        n: usize,
+        kind: &'static str,
+    ) -> Result<Vec<S>, RandomnessError> {
+        if from.len() < n {
+            return Err(RandomnessError::Exhausted {
+                kind,
+                requested: n,
+                available: from.len(),
+            });
+        }
+        let mut taken = from.split_off(from.len() - n);
+        taken.reverse();
+        Ok(taken)
+    }
+
+    pub(crate) fn take_shares(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        Self::take(&mut self.shares, n, "shares")
+    }
+
+    pub(crate) fn take_zeros(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        Self::take(&mut self.zeros, n, "zeros")
+    }
+}
+
+impl<F: Field, S: FieldShare<F>> Drop for RandomnessPool<F, S> {
+    fn drop(&mut self) {
+        // with the spare capacity, which holds the shares already taken
+        self.shares.zeroize();
+        self.bits.zeroize();
+        self.zeros.zeroize();
+    }
+}
+
+impl<F: Field, S: FieldShare<F>> RandomnessSource<F, S> for RandomnessPool<F, S> {
+    fn random_share(&mut self) -> Result<S, RandomnessError> {
+        Ok(self.take_shares(1)?[0])
+    }
+    fn random_bit(&mut self) -> Result<S, RandomnessError> {
+        Ok(Self::take(&mut self.bits, 1, "bits")?[0])
+    }
+    fn random_shares(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        self.take_shares(n)
+    }
+    fn random_bits(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        Self::take(&mut self.bits, n, "bits")
+    }
+}
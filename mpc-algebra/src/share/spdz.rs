@@ -15,12 +15,15 @@ use std::fmt::{self, Debug, Display, Formatter};
 use std::hash::Hash;
 use std::io::{self, Read, Write};
 use std::marker::PhantomData;
+use zeroize::Zeroize;
 
 use mpc_net::{MpcNet, MpcMultiNet as Net};
 use crate::channel::{can_cheat, MpcSerNet};
+use crate::ct;
 
 use super::add::{AdditiveFieldShare, AdditiveGroupShare, MulFieldShare};
-use super::field::{DenseOrSparsePolynomial, DensePolynomial, ExtFieldShare, FieldShare};
+use super::blame;
+use super::field::{DenseOrSparsePolynomial, DensePolynomial, ExtFieldShare, FieldShare, MulScratch, SmallScalar};
 use super::group::GroupShare;
 use super::msm::*;
 use super::pairing::{AffProjShare, PairingShare};
@@ -29,7 +32,13 @@ use crate::Reveal;
 
 #[inline]
 pub fn mac_share<F: Field>() -> F {
-    if Net::am_king() {
+    mac_share_of(Net::party_id())
+}
+
+/// `party`'s share of the MAC key, which, like the key, is public in this prototype.
+#[inline]
+pub fn mac_share_of<F: Field>(party: usize) -> F {
+    if party == 0 {
         F::one()
     } else {
         F::zero()
@@ -52,6 +61,13 @@ pub struct SpdzFieldShare<T> {
     mac: AdditiveFieldShare<T>,
 }
 
+impl<T: Zeroize> Zeroize for SpdzFieldShare<T> {
+    fn zeroize(&mut self) {
+        self.sh.zeroize();
+        self.mac.zeroize();
+    }
+}
+
 macro_rules! impl_basics_spdz {
     ($share:ident, $bound:ident) => {
         impl<T: $bound> Display for $share<T> {
@@ -75,11 +91,12 @@ macro_rules! impl_basics_spdz {
             }
         }
         impl<T: $bound> CanonicalSerialize for $share<T> {
-            fn serialize<W: Write>(&self, _writer: W) -> Result<(), SerializationError> {
-                unimplemented!("serialize")
+            fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+                self.sh.serialize(&mut writer)?;
+                self.mac.serialize(writer)
             }
             fn serialized_size(&self) -> usize {
-                unimplemented!("serialized_size")
+                self.sh.serialized_size() + self.mac.serialized_size()
             }
         }
         impl<T: $bound> CanonicalSerializeWithFlags for $share<T> {
@@ -96,8 +113,11 @@ macro_rules! impl_basics_spdz {
             }
         }
         impl<T: $bound> CanonicalDeserialize for $share<T> {
-            fn deserialize<R: Read>(_reader: R) -> Result<Self, SerializationError> {
-                unimplemented!("deserialize")
+            fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+                Ok(Self {
+                    sh: CanonicalDeserialize::deserialize(&mut reader)?,
+                    mac: CanonicalDeserialize::deserialize(reader)?,
+                })
             }
         }
         impl<T: $bound> CanonicalDeserializeWithFlags for $share<T> {
@@ -120,13 +140,16 @@ impl<F: Field> Reveal for SpdzFieldShare<F> {
     type Base = F;
 
     fn reveal(self) -> F {
+        if blame::identifiable_abort() {
+            return blame::open(vec![self.sh.val], vec![self.mac.val], |x, k: F| *x * k)[0];
+        }
         let vals: Vec<F> = Net::broadcast(&self.sh.val);
         // _Pragmatic MPC_ 6.6.2
         let x: F = vals.iter().sum();
         let dx_t: F = mac_share::<F>() * x - self.mac.val;
         let all_dx_ts: Vec<F> = Net::atomic_broadcast(&dx_t);
         let sum: F = all_dx_ts.iter().sum();
-        assert!(sum.is_zero());
+        assert!(bool::from(ct::is_zero(&sum)));
         x
     }
     fn from_public(f: F) -> Self {
@@ -141,6 +164,10 @@ impl<F: Field> Reveal for SpdzFieldShare<F> {
             mac: Reveal::from_add_shared(f * mac::<F>()),
         }
     }
+    /// Our additive share of the value, without checking its MAC.
+    fn unwrap_as_public(self) -> F {
+        self.sh.val
+    }
     fn king_share<R: Rng>(f: Self::Base, rng: &mut R) -> Self {
         let mut r: Vec<F> = (0..(Net::n_parties()-1)).map(|_| F::rand(rng)).collect();
         let sum_r: F = r.iter().sum();
@@ -148,17 +175,25 @@ impl<F: Field> Reveal for SpdzFieldShare<F> {
         Self::from_add_shared(Net::recv_from_king( if Net::am_king() { Some(r) } else { None }))
     }
     fn king_share_batch<R: Rng>(f: Vec<Self::Base>, rng: &mut R) -> Vec<Self> {
-        let mut rs: Vec<Vec<Self::Base>> =
-            (0..(Net::n_parties()-1)).map(|_| {
-            (0..f.len()).map(|_| {
-                F::rand(rng)
-            }).collect()
-        }).collect();
-        let final_shares: Vec<Self::Base> = (0..rs[0].len()).map(|i| {
-            f[i] - &rs.iter().map(|r| &r[i]).sum()
-        }).collect();
-        rs.push(final_shares);
-        Net::recv_from_king(if Net::am_king() { Some(rs) } else {None}).into_iter().map(Self::from_add_shared).collect()
+        Self::party_share_batch(0, f, rng)
+    }
+    fn party_share_batch<R: Rng>(owner: usize, f: Vec<Self::Base>, rng: &mut R) -> Vec<Self> {
+        let shares = if Net::party_id() == owner {
+            let mut rs: Vec<Vec<Self::Base>> =
+                (0..(Net::n_parties()-1)).map(|_| {
+                (0..f.len()).map(|_| {
+                    F::rand(rng)
+                }).collect()
+            }).collect();
+            let final_shares: Vec<Self::Base> = (0..f.len()).map(|i| {
+                f[i] - &rs.iter().map(|r| &r[i]).sum()
+            }).collect();
+            rs.push(final_shares);
+            Some(rs)
+        } else {
+            None
+        };
+        Net::recv_vec_from(owner, shares).into_iter().map(Self::from_add_shared).collect()
     }
 }
 
@@ -166,8 +201,11 @@ impl<F: Field> FieldShare<F> for SpdzFieldShare<F> {
     fn batch_open(selfs: impl IntoIterator<Item = Self>) -> Vec<F> {
         let (s_vals, macs): (Vec<F>, Vec<F>) =
             selfs.into_iter().map(|s| (s.sh.val, s.mac.val)).unzip();
+        if blame::identifiable_abort() {
+            return blame::open(s_vals, macs, |x, k: F| *x * k);
+        }
         let n = s_vals.len();
-        let all_vals = Net::broadcast(&s_vals);
+        let all_vals = Net::broadcast_vec(&s_vals);
         let vals: Vec<F> =
             (0..n).map(|i| all_vals.iter().map(|v| &v[i]).sum()).collect();
         let dx_ts: Vec<F> =
@@ -176,11 +214,11 @@ impl<F: Field> FieldShare<F> for SpdzFieldShare<F> {
             .zip(vals.iter())
             .map(|(mac, val)| mac_share::<F>() * val - mac)
             .collect();
-        let all_dx_ts: Vec<Vec<F>> = Net::atomic_broadcast(&dx_ts);
-        for i in 0..n {
+        let all_dx_ts: Vec<Vec<F>> = Net::atomic_broadcast_vec(&dx_ts);
+        assert!(ct::all((0..n).map(|i| {
             let sum: F = all_dx_ts.iter().map(|dx_ts| &dx_ts[i]).sum();
-            assert!(sum.is_zero());
-        }
+            ct::is_zero(&sum)
+        })));
         vals
     }
     fn add(&mut self, other: &Self) -> &mut Self {
@@ -251,15 +289,28 @@ impl<F: Field> FieldShare<F> for SpdzFieldShare<F> {
     Ord(bound = "T: Ord"),
     Hash(bound = "T: Hash")
 )]
+/// A share of a group element, with a share of its MAC in the exponent: the element scaled by the
+/// MAC key. Openings check the MAC as they do for [SpdzFieldShare]s, and the MACs of MSMs of shared
+/// scalars are the MSMs of the scalars' MACs.
 pub struct SpdzGroupShare<T, M> {
     sh: AdditiveGroupShare<T, M>,
     mac: AdditiveGroupShare<T, M>,
 }
 
+impl<T: Zeroize, M> Zeroize for SpdzGroupShare<T, M> {
+    fn zeroize(&mut self) {
+        self.sh.zeroize();
+        self.mac.zeroize();
+    }
+}
+
 impl<G: Group, M> Reveal for SpdzGroupShare<G, M> {
     type Base = G;
 
     fn reveal(self) -> G {
+        if blame::identifiable_abort() {
+            return blame::open(vec![self.sh.val], vec![self.mac.val], |x: &G, k| x.mul(&k))[0];
+        }
         let vals: Vec<G> = Net::broadcast(&self.sh.val);
         // _Pragmatic MPC_ 6.6.2
         let x: G = vals.iter().sum();
@@ -270,7 +321,7 @@ impl<G: Group, M> Reveal for SpdzGroupShare<G, M> {
         };
         let all_dx_ts: Vec<G> = Net::atomic_broadcast(&dx_t);
         let sum: G = all_dx_ts.iter().sum();
-        assert!(sum.is_zero());
+        assert!(bool::from(ct::is_zero(&sum)));
         x
     }
     fn from_public(f: G) -> Self {
@@ -293,6 +344,10 @@ impl<G: Group, M> Reveal for SpdzGroupShare<G, M> {
             }),
         }
     }
+    /// Our additive share of the value, without checking its MAC.
+    fn unwrap_as_public(self) -> G {
+        self.sh.val
+    }
     fn king_share<R: Rng>(f: Self::Base, rng: &mut R) -> Self {
         let mut r: Vec<G> = (0..(Net::n_parties()-1)).map(|_| G::rand(rng)).collect();
         let sum_r: G = r.iter().sum();
@@ -306,15 +361,15 @@ impl<G: Group, M> Reveal for SpdzGroupShare<G, M> {
                 Self::Base::rand(rng)
             }).collect()
         }).collect();
-        let final_shares: Vec<Self::Base> = (0..rs[0].len()).map(|i| {
+        let final_shares: Vec<Self::Base> = (0..f.len()).map(|i| {
             f[i] - &rs.iter().map(|r| &r[i]).sum()
         }).collect();
         rs.push(final_shares);
-        Net::recv_from_king(if Net::am_king() { Some(rs) } else {None}).into_iter().map(Self::from_add_shared).collect()
+        Net::recv_vec_from(0, if Net::am_king() { Some(rs) } else {None}).into_iter().map(Self::from_add_shared).collect()
     }
 }
 macro_rules! impl_spdz_basics_2_param {
-    ($share:ident, $bound:ident) => {
+    ($share:ident, $bound:ident $(, $phants:ident)?) => {
         impl<T: $bound, M> Display for $share<T, M> {
             fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
                 write!(f, "{}", self.sh.val)
@@ -336,11 +391,22 @@ macro_rules! impl_spdz_basics_2_param {
             }
         }
         impl<T: $bound, M> CanonicalSerialize for $share<T, M> {
-            fn serialize<W: Write>(&self, _writer: W) -> Result<(), SerializationError> {
-                unimplemented!("serialize")
+            fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+                self.sh.serialize(&mut writer)?;
+                self.mac.serialize(writer)
             }
             fn serialized_size(&self) -> usize {
-                unimplemented!("serialized_size")
+                self.sh.serialized_size() + self.mac.serialized_size()
+            }
+            fn serialize_uncompressed<W: Write>(
+                &self,
+                mut writer: W,
+            ) -> Result<(), SerializationError> {
+                self.sh.serialize_uncompressed(&mut writer)?;
+                self.mac.serialize_uncompressed(writer)
+            }
+            fn uncompressed_size(&self) -> usize {
+                self.sh.uncompressed_size() + self.mac.uncompressed_size()
             }
         }
         impl<T: $bound, M> CanonicalSerializeWithFlags for $share<T, M> {
@@ -357,8 +423,21 @@ macro_rules! impl_spdz_basics_2_param {
             }
         }
         impl<T: $bound, M> CanonicalDeserialize for $share<T, M> {
-            fn deserialize<R: Read>(_reader: R) -> Result<Self, SerializationError> {
-                unimplemented!("deserialize")
+            fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+                Ok(Self {
+                    sh: CanonicalDeserialize::deserialize(&mut reader)?,
+                    mac: CanonicalDeserialize::deserialize(reader)?,
+                    $($phants: PhantomData,)?
+                })
+            }
+            fn deserialize_uncompressed<R: Read>(
+                mut reader: R,
+            ) -> Result<Self, SerializationError> {
+                Ok(Self {
+                    sh: CanonicalDeserialize::deserialize_uncompressed(&mut reader)?,
+                    mac: CanonicalDeserialize::deserialize_uncompressed(reader)?,
+                    $($phants: PhantomData,)?
+                })
             }
         }
         impl<T: $bound, M> CanonicalDeserializeWithFlags for $share<T, M> {
@@ -385,8 +464,11 @@ impl<G: Group, M: Msm<G, G::ScalarField>> GroupShare<G> for SpdzGroupShare<G, M>
     fn batch_open(selfs: impl IntoIterator<Item = Self>) -> Vec<G> {
         let (s_vals, macs): (Vec<G>, Vec<G>) =
             selfs.into_iter().map(|s| (s.sh.val, s.mac.val)).unzip();
+        if blame::identifiable_abort() {
+            return blame::open(s_vals, macs, |x: &G, k| x.mul(&k));
+        }
         let n = s_vals.len();
-        let all_vals = Net::broadcast(&s_vals);
+        let all_vals = Net::broadcast_vec(&s_vals);
         let vals: Vec<G> =
             (0..n).map(|i| all_vals.iter().map(|v| &v[i]).sum()).collect();
         let dx_ts: Vec<G> =
@@ -395,11 +477,11 @@ impl<G: Group, M: Msm<G, G::ScalarField>> GroupShare<G> for SpdzGroupShare<G, M>
             .zip(vals.iter())
             .map(|(mac, val)| val.mul(&mac_share::<G::ScalarField>()) - mac)
             .collect();
-        let all_dx_ts: Vec<Vec<G>> = Net::atomic_broadcast(&dx_ts);
-        for i in 0..n {
+        let all_dx_ts: Vec<Vec<G>> = Net::atomic_broadcast_vec(&dx_ts);
+        assert!(ct::all((0..n).map(|i| {
             let sum: G = all_dx_ts.iter().map(|dx_ts| &dx_ts[i]).sum();
-            assert!(sum.is_zero());
-        }
+            ct::is_zero(&sum)
+        })));
         vals
     }
 
@@ -439,11 +521,30 @@ impl<G: Group, M: Msm<G, G::ScalarField>> GroupShare<G> for SpdzGroupShare<G, M>
 
     fn multi_scale_pub_group(bases: &[G], scalars: &[Self::FieldShare]) -> Self {
         let shares: Vec<G::ScalarField> = scalars.into_iter().map(|s| s.sh.val.clone()).collect();
-        let macs: Vec<G::ScalarField> = scalars.into_iter().map(|s| s.sh.val.clone()).collect();
+        let macs: Vec<G::ScalarField> = scalars.into_iter().map(|s| s.mac.val.clone()).collect();
         let sh = AdditiveGroupShare::from_add_shared(M::msm(bases, &shares));
         let mac = AdditiveGroupShare::from_add_shared(M::msm(bases, &macs));
         Self { sh, mac }
     }
+
+    fn multi_scale_pub_group_by(
+        scalars: &[Self::FieldShare],
+        msm: impl Fn(&[G::ScalarField]) -> G,
+    ) -> Self {
+        let shares: Vec<G::ScalarField> = scalars.iter().map(|s| s.sh.val).collect();
+        let macs: Vec<G::ScalarField> = scalars.iter().map(|s| s.mac.val).collect();
+        let sh = AdditiveGroupShare::from_add_shared(msm(&shares));
+        let mac = AdditiveGroupShare::from_add_shared(msm(&macs));
+        Self { sh, mac }
+    }
+
+    fn msm_pub_scalar_shared_base(bases: &[Self], scalars: &[G::ScalarField]) -> Self {
+        let shares: Vec<_> = bases.iter().map(|b| b.sh).collect();
+        let macs: Vec<_> = bases.iter().map(|b| b.mac).collect();
+        let sh = AdditiveGroupShare::msm_pub_scalar_shared_base(&shares, scalars);
+        let mac = AdditiveGroupShare::msm_pub_scalar_shared_base(&macs, scalars);
+        Self { sh, mac }
+    }
 }
 
 #[derive(Derivative)]
@@ -461,7 +562,14 @@ pub struct SpdzMulFieldShare<T, S> {
     mac: MulFieldShare<T>,
     _phants: PhantomData<S>,
 }
-impl_spdz_basics_2_param!(SpdzMulFieldShare, Field);
+
+impl<T: Zeroize, S> Zeroize for SpdzMulFieldShare<T, S> {
+    fn zeroize(&mut self) {
+        self.sh.zeroize();
+        self.mac.zeroize();
+    }
+}
+impl_spdz_basics_2_param!(SpdzMulFieldShare, Field, _phants);
 
 impl<F: Field, S: PrimeField> Reveal for SpdzMulFieldShare<F, S> {
     type Base = F;
@@ -473,7 +581,7 @@ impl<F: Field, S: PrimeField> Reveal for SpdzMulFieldShare<F, S> {
         let dx_t: F = x.pow(&mac_share::<S>().into_repr()) / self.mac.val;
         let all_dx_ts: Vec<F> = Net::atomic_broadcast(&dx_t);
         let prod: F = all_dx_ts.iter().product();
-        assert!(prod.is_one());
+        assert!(bool::from(ct::is_one(&prod)));
         x
     }
     fn from_public(f: F) -> Self {
@@ -490,6 +598,10 @@ impl<F: Field, S: PrimeField> Reveal for SpdzMulFieldShare<F, S> {
             _phants: PhantomData::default(),
         }
     }
+    /// Our multiplicative share of the value, without checking its MAC.
+    fn unwrap_as_public(self) -> F {
+        self.sh.val
+    }
 }
 
 impl<F: Field, S: PrimeField> FieldShare<F> for SpdzMulFieldShare<F, S> {
@@ -497,6 +609,10 @@ impl<F: Field, S: PrimeField> FieldShare<F> for SpdzMulFieldShare<F, S> {
         unimplemented!("add for SpdzMulFieldShare")
     }
 
+    fn scale_small(&mut self, c: SmallScalar) -> &mut Self {
+        self.scale(&c.value())
+    }
+
     fn scale(&mut self, other: &F) -> &mut Self {
         if Net::am_king() {
             self.sh.scale(other);
@@ -510,9 +626,11 @@ impl<F: Field, S: PrimeField> FieldShare<F> for SpdzMulFieldShare<F, S> {
     }
 
     fn mul<S2: BeaverSource<Self, Self, Self>>(self, other: Self, _source: &mut S2) -> Self {
-        self.sh.mul(other.sh, &mut PanicBeaverSource::default());
-        self.mac.mul(other.mac, &mut PanicBeaverSource::default());
-        self
+        Self {
+            sh: self.sh.mul(other.sh, &mut PanicBeaverSource::default()),
+            mac: self.mac.mul(other.mac, &mut PanicBeaverSource::default()),
+            _phants: PhantomData::default(),
+        }
     }
 
     fn batch_mul<S2: BeaverSource<Self, Self, Self>>(
@@ -521,12 +639,24 @@ impl<F: Field, S: PrimeField> FieldShare<F> for SpdzMulFieldShare<F, S> {
         _source: &mut S2,
     ) -> Vec<Self> {
         for (x, y) in xs.iter_mut().zip(ys.iter()) {
-            x.sh.mul(y.sh, &mut PanicBeaverSource::default());
-            x.mac.mul(y.mac, &mut PanicBeaverSource::default());
+            x.sh = x.sh.mul(y.sh, &mut PanicBeaverSource::default());
+            x.mac = x.mac.mul(y.mac, &mut PanicBeaverSource::default());
         }
         xs
     }
 
+    fn batch_mul_in_place<S2: BeaverSource<Self, Self, Self>>(
+        selfs: &mut [Self],
+        others: &[Self],
+        _source: &mut S2,
+        _scratch: &mut MulScratch<F, Self>,
+    ) {
+        for (x, y) in selfs.iter_mut().zip(others.iter()) {
+            x.sh = x.sh.mul(y.sh, &mut PanicBeaverSource::default());
+            x.mac = x.mac.mul(y.mac, &mut PanicBeaverSource::default());
+        }
+    }
+
     fn inv<S2: BeaverSource<Self, Self, Self>>(self, _source: &mut S2) -> Self {
         Self {
             sh: self.sh.inv(&mut PanicBeaverSource::default()),
@@ -595,6 +725,24 @@ macro_rules! groups_share {
                 }
             }
 
+            fn sh_batch_proj_to_aff(gs: &[Self::ProjectiveShare]) -> Vec<Self::AffineShare> {
+                // the shares, then their MACs, in one batch
+                let vals: Vec<E::$proj> = gs
+                    .iter()
+                    .map(|g| g.sh.val)
+                    .chain(gs.iter().map(|g| g.mac.val))
+                    .collect();
+                let affine = E::$proj::batch_normalization_into_affine(&vals);
+                let (shs, macs) = affine.split_at(gs.len());
+                shs.iter()
+                    .zip(macs)
+                    .map(|(sh, mac)| SpdzGroupShare {
+                        sh: Reveal::from_add_shared(*sh),
+                        mac: Reveal::from_add_shared(*mac),
+                    })
+                    .collect()
+            }
+
             fn add_sh_proj_sh_aff(
                 mut a: Self::ProjectiveShare,
                 o: &Self::AffineShare,
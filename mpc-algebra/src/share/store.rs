@@ -0,0 +1,574 @@
+//! Durable stores of preprocessing material.
+//!
+//! A [RandomnessPool](super::RandomnessPool) lives in one file, which is rewritten whole after
+//! every draw. A production prover, drawing many proofs' worth of triples and random shares from
+//! a large stock, keeps it in a [PreprocessingStore] instead: a key-value store, such as the
+//! embedded [SledStore] (with the `sled` feature), or a [MemoryStore] for tests.
+//!
+//! A [Preprocessed] reads and writes a store's items as shares of one type, and serves them as a
+//! [BeaverSource], a [RandomnessSource] and a [MaskSource]. It removes items from the store,
+//! durably, before it hands them out: a prover that crashes mid-proof loses what that proof drew,
+//! but never uses an item twice. Like a pool, all parties must draw from their stores in the same order, which is
+//! the order the items were added in.
+use ark_ff::prelude::*;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use derivative::Derivative;
+use log::{debug, warn};
+use zeroize::Zeroize;
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::envelope::{self, EnvelopeError, PayloadKind};
+use super::field::FieldShare;
+use super::masking::MaskSource;
+use super::randomness::{RandomnessError, RandomnessSource};
+use super::BeaverSource;
+use crate::cost;
+
+/// A kind of preprocessing material.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Material {
+    /// Multiplication triples.
+    Triples,
+    /// Sharings of a random element and its inverse.
+    InvPairs,
+    /// Sharings of uniformly random field elements.
+    Shares,
+    /// Sharings of uniformly random bits.
+    Bits,
+    /// Sharings of zero.
+    Zeros,
+}
+
+impl Material {
+    pub const ALL: [Material; 5] = [
+        Material::Triples,
+        Material::InvPairs,
+        Material::Shares,
+        Material::Bits,
+        Material::Zeros,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Material::Triples => "triples",
+            Material::InvPairs => "inverse pairs",
+            Material::Shares => "shares",
+            Material::Bits => "bits",
+            Material::Zeros => "zeros",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum StoreError {
+    /// The store holds fewer items of `kind` than were requested.
+    Exhausted {
+        kind: &'static str,
+        requested: usize,
+        available: usize,
+    },
+    /// The store holds shares of another type, or another party's.
+    Envelope(EnvelopeError),
+    Serialization(SerializationError),
+    /// The backend failed.
+    Backend(String),
+}
+
+impl Display for StoreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Exhausted {
+                kind,
+                requested,
+                available,
+            } => write!(
+                f,
+                "preprocessing store exhausted: requested {} {}, but only {} remain",
+                requested, kind, available
+            ),
+            StoreError::Envelope(e) => write!(f, "preprocessing store: {}", e),
+            StoreError::Serialization(e) => write!(f, "preprocessing store encoding: {}", e),
+            StoreError::Backend(e) => write!(f, "preprocessing store backend: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<EnvelopeError> for StoreError {
+    fn from(e: EnvelopeError) -> Self {
+        StoreError::Envelope(e)
+    }
+}
+
+impl From<SerializationError> for StoreError {
+    fn from(e: SerializationError) -> Self {
+        StoreError::Serialization(e)
+    }
+}
+
+impl From<StoreError> for RandomnessError {
+    fn from(e: StoreError) -> Self {
+        match e {
+            StoreError::Exhausted {
+                kind,
+                requested,
+                available,
+            } => RandomnessError::Exhausted {
+                kind,
+                requested,
+                available,
+            },
+            StoreError::Envelope(e) => RandomnessError::Envelope(e),
+            StoreError::Serialization(e) => RandomnessError::Serialization(e),
+            StoreError::Backend(e) => RandomnessError::Io(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+}
+
+/// A durable queue of encoded items of each [Material], a header that says whose they are, and
+/// named notes on where they came from.
+pub trait PreprocessingStore {
+    /// Append `items` to the stock of `kind`, durably.
+    fn put(&self, kind: Material, items: &[Vec<u8>]) -> Result<(), StoreError>;
+    /// Remove the oldest `n` items of `kind`, durably, and return them. If fewer remain, remove
+    /// nothing, and fail with [StoreError::Exhausted].
+    fn take(&self, kind: Material, n: usize) -> Result<Vec<Vec<u8>>, StoreError>;
+    /// How many items of `kind` remain.
+    fn remaining(&self, kind: Material) -> Result<usize, StoreError>;
+    fn header(&self) -> Result<Option<Vec<u8>>, StoreError>;
+    fn set_header(&self, header: &[u8]) -> Result<(), StoreError>;
+    /// The note under `key`, if any.
+    fn metadata(&self, key: &str) -> Result<Option<String>, StoreError>;
+    /// Set the note under `key`, durably.
+    fn set_metadata(&self, key: &str, value: &str) -> Result<(), StoreError>;
+}
+
+/// A store in memory, which does not survive the process.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    items: Mutex<HashMap<Material, VecDeque<Vec<u8>>>>,
+    header: Mutex<Option<Vec<u8>>>,
+    metadata: Mutex<HashMap<String, String>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PreprocessingStore for MemoryStore {
+    fn put(&self, kind: Material, items: &[Vec<u8>]) -> Result<(), StoreError> {
+        let mut all = self.items.lock().unwrap();
+        all.entry(kind).or_default().extend(items.iter().cloned());
+        Ok(())
+    }
+
+    fn take(&self, kind: Material, n: usize) -> Result<Vec<Vec<u8>>, StoreError> {
+        let mut all = self.items.lock().unwrap();
+        let queue = all.entry(kind).or_default();
+        if queue.len() < n {
+            return Err(StoreError::Exhausted {
+                kind: kind.name(),
+                requested: n,
+                available: queue.len(),
+            });
+        }
+        Ok(queue.drain(..n).collect())
+    }
+
+    fn remaining(&self, kind: Material) -> Result<usize, StoreError> {
+        Ok(self.items.lock().unwrap().get(&kind).map_or(0, VecDeque::len))
+    }
+
+    fn header(&self) -> Result<Option<Vec<u8>>, StoreError> {
+        Ok(self.header.lock().unwrap().clone())
+    }
+
+    fn set_header(&self, header: &[u8]) -> Result<(), StoreError> {
+        *self.header.lock().unwrap() = Some(header.to_vec());
+        Ok(())
+    }
+
+    fn metadata(&self, key: &str) -> Result<Option<String>, StoreError> {
+        Ok(self.metadata.lock().unwrap().get(key).cloned())
+    }
+
+    fn set_metadata(&self, key: &str, value: &str) -> Result<(), StoreError> {
+        self.metadata.lock().unwrap().insert(key.to_owned(), value.to_owned());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sled")]
+pub use self::sled_store::SledStore;
+
+#[cfg(feature = "sled")]
+mod sled_store {
+    use super::*;
+    use sled::transaction::{abort, TransactionError};
+    use std::convert::TryInto;
+    use std::path::Path;
+
+    const HEADER: &[u8] = b"header";
+    const HEAD: &[u8] = b"head";
+    const TAIL: &[u8] = b"tail";
+    const METADATA: &[u8] = b"metadata";
+
+    /// A store in an embedded [sled] database, with a tree per [Material].
+    ///
+    /// Each tree holds its items under the big-endian index of each, from `head` (the oldest
+    /// left) to `tail` (one past the newest). A draw removes its items and advances `head` in one
+    /// transaction, and flushes it to disk before returning.
+    pub struct SledStore {
+        db: sled::Db,
+    }
+
+    impl From<sled::Error> for StoreError {
+        fn from(e: sled::Error) -> Self {
+            StoreError::Backend(e.to_string())
+        }
+    }
+
+    impl<E: Into<StoreError>> From<TransactionError<E>> for StoreError {
+        fn from(e: TransactionError<E>) -> Self {
+            match e {
+                TransactionError::Abort(e) => e.into(),
+                TransactionError::Storage(e) => e.into(),
+            }
+        }
+    }
+
+    fn item_key(i: u64) -> [u8; 9] {
+        let mut key = [b'i'; 9];
+        key[1..].copy_from_slice(&i.to_be_bytes());
+        key
+    }
+
+    fn counter(v: Option<sled::IVec>) -> u64 {
+        v.map_or(0, |v| u64::from_be_bytes(v.as_ref().try_into().unwrap()))
+    }
+
+    impl SledStore {
+        /// Open the store at `path`, creating it if there is none.
+        pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+            Ok(Self {
+                db: sled::open(path)?,
+            })
+        }
+
+        fn tree(&self, kind: Material) -> Result<sled::Tree, StoreError> {
+            Ok(self.db.open_tree(kind.name())?)
+        }
+    }
+
+    impl PreprocessingStore for SledStore {
+        fn put(&self, kind: Material, items: &[Vec<u8>]) -> Result<(), StoreError> {
+            self.tree(kind)?
+                .transaction::<_, _, StoreError>(|t| {
+                    let tail = counter(t.get(TAIL)?);
+                    for (i, item) in items.iter().enumerate() {
+                        t.insert(&item_key(tail + i as u64)[..], &item[..])?;
+                    }
+                    t.insert(TAIL, &(tail + items.len() as u64).to_be_bytes()[..])?;
+                    Ok(())
+                })?;
+            self.db.flush()?;
+            Ok(())
+        }
+
+        fn take(&self, kind: Material, n: usize) -> Result<Vec<Vec<u8>>, StoreError> {
+            let taken = self.tree(kind)?.transaction(|t| {
+                let (head, tail) = (counter(t.get(HEAD)?), counter(t.get(TAIL)?));
+                let available = (tail - head) as usize;
+                if available < n {
+                    return abort(StoreError::Exhausted {
+                        kind: kind.name(),
+                        requested: n,
+                        available,
+                    });
+                }
+                let mut taken = Vec::with_capacity(n);
+                for i in head..head + n as u64 {
+                    let item = t.remove(&item_key(i)[..])?;
+                    taken.push(item.expect("an item between head and tail").to_vec());
+                }
+                t.insert(HEAD, &(head + n as u64).to_be_bytes()[..])?;
+                Ok(taken)
+            })?;
+            // only items that are gone for good are handed out
+            self.db.flush()?;
+            Ok(taken)
+        }
+
+        fn remaining(&self, kind: Material) -> Result<usize, StoreError> {
+            let tree = self.tree(kind)?;
+            Ok((counter(tree.get(TAIL)?) - counter(tree.get(HEAD)?)) as usize)
+        }
+
+        fn header(&self) -> Result<Option<Vec<u8>>, StoreError> {
+            Ok(self.db.get(HEADER)?.map(|h| h.to_vec()))
+        }
+
+        fn set_header(&self, header: &[u8]) -> Result<(), StoreError> {
+            self.db.insert(HEADER, header)?;
+            self.db.flush()?;
+            Ok(())
+        }
+
+        fn metadata(&self, key: &str) -> Result<Option<String>, StoreError> {
+            let value = self.db.open_tree(METADATA)?.get(key)?;
+            Ok(value.map(|v| String::from_utf8_lossy(&v).into_owned()))
+        }
+
+        fn set_metadata(&self, key: &str, value: &str) -> Result<(), StoreError> {
+            self.db.open_tree(METADATA)?.insert(key, value.as_bytes())?;
+            self.db.flush()?;
+            Ok(())
+        }
+    }
+}
+
+/// How many items of each [Material] a store holds, or how many were drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stock {
+    pub triples: usize,
+    pub inv_pairs: usize,
+    pub shares: usize,
+    pub bits: usize,
+    pub zeros: usize,
+}
+
+impl Stock {
+    pub fn get(&self, kind: Material) -> usize {
+        match kind {
+            Material::Triples => self.triples,
+            Material::InvPairs => self.inv_pairs,
+            Material::Shares => self.shares,
+            Material::Bits => self.bits,
+            Material::Zeros => self.zeros,
+        }
+    }
+
+    fn get_mut(&mut self, kind: Material) -> &mut usize {
+        match kind {
+            Material::Triples => &mut self.triples,
+            Material::InvPairs => &mut self.inv_pairs,
+            Material::Shares => &mut self.shares,
+            Material::Bits => &mut self.bits,
+            Material::Zeros => &mut self.zeros,
+        }
+    }
+}
+
+impl Display for Stock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} triples, {} inverse pairs, {} shares, {} bits, {} zeros",
+            self.triples, self.inv_pairs, self.shares, self.bits, self.zeros
+        )
+    }
+}
+
+/// A store's items, as shares of type `S`, for this party.
+///
+/// Clones draw from the same store.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct Preprocessed<F: Field, S: FieldShare<F>, St: PreprocessingStore> {
+    store: Arc<St>,
+    /// Items drawn through this, and its clones, by [Material::ALL] order.
+    taken: Arc<[AtomicU64; 5]>,
+    _share: PhantomData<(F, S)>,
+}
+
+impl<F: Field, S: FieldShare<F>, St: PreprocessingStore> Preprocessed<F, S, St> {
+    /// Use `store` for this party's shares of type `S`.
+    ///
+    /// A new store is marked as such. A used one must already be, or this fails with
+    /// [StoreError::Envelope]. Opening one stocked by a [trusted dealer](super::dealer) logs a
+    /// warning.
+    pub fn open(store: St) -> Result<Self, StoreError> {
+        match store.header()? {
+            Some(header) => envelope::read::<Vec<S>, _>(PayloadKind::Randomness, &header[..])
+                .map(|_| ())?,
+            None => {
+                let mut header = Vec::new();
+                envelope::write(PayloadKind::Randomness, &Vec::<S>::new(), &mut header)?;
+                store.set_header(&header)?;
+            }
+        }
+        if super::dealer::is_dealt(&store)? {
+            warn!("This preprocessing came from a trusted dealer: it is insecure for production");
+        }
+        Ok(Self {
+            store: Arc::new(store),
+            taken: Arc::new(Default::default()),
+            _share: PhantomData,
+        })
+    }
+
+    pub fn store(&self) -> &St {
+        &self.store
+    }
+
+    fn put<T: CanonicalSerialize>(&self, kind: Material, items: &[T]) -> Result<(), StoreError> {
+        let mut encoded = items
+            .iter()
+            .map(|item| {
+                let mut bytes = Vec::with_capacity(item.serialized_size());
+                item.serialize(&mut bytes).map(|_| bytes)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let put = self.store.put(kind, &encoded);
+        encoded.zeroize();
+        put
+    }
+
+    fn take<T: CanonicalDeserialize>(&self, kind: Material, n: usize) -> Result<Vec<T>, StoreError> {
+        let mut encoded = self.store.take(kind, n)?;
+        let i = Material::ALL.iter().position(|k| *k == kind).unwrap();
+        self.taken[i].fetch_add(n as u64, Ordering::Relaxed);
+        debug!("Took {} {} from the preprocessing store", n, kind.name());
+        let items = encoded
+            .iter()
+            .map(|bytes| T::deserialize(&bytes[..]))
+            .collect::<Result<Vec<_>, _>>();
+        encoded.zeroize();
+        Ok(items?)
+    }
+
+    /// Add triples, which the parties generated together, in the same order at each.
+    pub fn add_triples(&self, xs: &[S], ys: &[S], zs: &[S]) -> Result<(), StoreError> {
+        assert!(xs.len() == ys.len() && ys.len() == zs.len());
+        let triples: Vec<(S, S, S)> = (0..xs.len()).map(|i| (xs[i], ys[i], zs[i])).collect();
+        self.put(Material::Triples, &triples)
+    }
+
+    /// Add pairs of a random element and its inverse.
+    pub fn add_inv_pairs(&self, rs: &[S], r_invs: &[S]) -> Result<(), StoreError> {
+        assert_eq!(rs.len(), r_invs.len());
+        let pairs: Vec<(S, S)> = rs.iter().cloned().zip(r_invs.iter().cloned()).collect();
+        self.put(Material::InvPairs, &pairs)
+    }
+
+    pub fn add_shares(&self, shares: &[S]) -> Result<(), StoreError> {
+        self.put(Material::Shares, shares)
+    }
+
+    pub fn add_bits(&self, bits: &[S]) -> Result<(), StoreError> {
+        self.put(Material::Bits, bits)
+    }
+
+    pub fn add_zeros(&self, zeros: &[S]) -> Result<(), StoreError> {
+        self.put(Material::Zeros, zeros)
+    }
+
+    /// Draw `n` triples.
+    pub fn take_triples(&self, n: usize) -> Result<(Vec<S>, Vec<S>, Vec<S>), StoreError> {
+        let triples: Vec<(S, S, S)> = self.take(Material::Triples, n)?;
+        cost::drew_triples(n as u64);
+        let mut xs = Vec::with_capacity(n);
+        let mut ys = Vec::with_capacity(n);
+        let mut zs = Vec::with_capacity(n);
+        for (x, y, z) in triples {
+            xs.push(x);
+            ys.push(y);
+            zs.push(z);
+        }
+        Ok((xs, ys, zs))
+    }
+
+    pub fn take_inv_pairs(&self, n: usize) -> Result<(Vec<S>, Vec<S>), StoreError> {
+        let pairs: Vec<(S, S)> = self.take(Material::InvPairs, n)?;
+        Ok(pairs.into_iter().unzip())
+    }
+
+    pub fn take_zeros(&self, n: usize) -> Result<Vec<S>, StoreError> {
+        self.take(Material::Zeros, n)
+    }
+
+    /// What the store holds.
+    pub fn stock(&self) -> Result<Stock, StoreError> {
+        let mut stock = Stock::default();
+        for kind in Material::ALL.iter() {
+            *stock.get_mut(*kind) = self.store.remaining(*kind)?;
+        }
+        Ok(stock)
+    }
+
+    /// What was drawn through this, and its clones, since [Preprocessed::open].
+    pub fn taken(&self) -> Stock {
+        let mut taken = Stock::default();
+        for (kind, n) in Material::ALL.iter().zip(self.taken.iter()) {
+            *taken.get_mut(*kind) = n.load(Ordering::Relaxed) as usize;
+        }
+        taken
+    }
+}
+
+/// Panics when the store runs out, or fails, as a [BeaverSource] cannot report it.
+impl<F: Field, S: FieldShare<F>, St: PreprocessingStore> BeaverSource<S, S, S>
+    for Preprocessed<F, S, St>
+{
+    fn triple(&mut self) -> (S, S, S) {
+        let (mut xs, mut ys, mut zs) = self.triples(1);
+        (xs.pop().unwrap(), ys.pop().unwrap(), zs.pop().unwrap())
+    }
+    fn triples(&mut self, n: usize) -> (Vec<S>, Vec<S>, Vec<S>) {
+        self.take_triples(n).unwrap_or_else(|e| panic!("{}", e))
+    }
+    fn triples_into(&mut self, n: usize, xs: &mut Vec<S>, ys: &mut Vec<S>, zs: &mut Vec<S>) {
+        let (x, y, z) = self.triples(n);
+        *xs = x;
+        *ys = y;
+        *zs = z;
+    }
+    fn inv_pair(&mut self) -> (S, S) {
+        let (mut rs, mut r_invs) = self.inv_pairs(1);
+        (rs.pop().unwrap(), r_invs.pop().unwrap())
+    }
+    fn inv_pairs(&mut self, n: usize) -> (Vec<S>, Vec<S>) {
+        self.take_inv_pairs(n).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl<F: Field, S: FieldShare<F>, St: PreprocessingStore> RandomnessSource<F, S>
+    for Preprocessed<F, S, St>
+{
+    fn random_share(&mut self) -> Result<S, RandomnessError> {
+        Ok(self.random_shares(1)?[0])
+    }
+    fn random_bit(&mut self) -> Result<S, RandomnessError> {
+        Ok(self.random_bits(1)?[0])
+    }
+    fn random_shares(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        Ok(self.take(Material::Shares, n)?)
+    }
+    fn random_bits(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        Ok(self.take(Material::Bits, n)?)
+    }
+}
+
+impl<F: Field, S: FieldShare<F>, St: PreprocessingStore> MaskSource<F, S> for Preprocessed<F, S, St> {
+    fn mask_share(&mut self) -> Result<S, RandomnessError> {
+        Ok(self.mask_shares(1)?[0])
+    }
+    fn zero_share(&mut self) -> Result<S, RandomnessError> {
+        Ok(self.zero_shares(1)?[0])
+    }
+    fn mask_shares(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        Ok(self.take(Material::Shares, n)?)
+    }
+    fn zero_shares(&mut self, n: usize) -> Result<Vec<S>, RandomnessError> {
+        Ok(self.take_zeros(n)?)
+    }
+}
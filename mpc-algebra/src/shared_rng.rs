@@ -0,0 +1,110 @@
+//! Public randomness that all parties agree on.
+//!
+//! A value drawn with [PubUniformRand](ark_ff::PubUniformRand) is public, so every party must draw
+//! the same one. Drawn from each party's own rng, they only agree if the parties happen to seed
+//! their rngs alike, as tests do with `test_rng`. A [SharedRng] is seeded from the session's joint
+//! seed instead. At [setup], each party commits to a random contribution, then all open theirs,
+//! and the seed is a hash of all of them: it is uniform as long as one party is honest. From then
+//! on, the parties draw the same [SharedRng]s, in the same order, without talking.
+//!
+//! The joint seed is fixed at setup, so a [SharedRng]'s output is known from then on. It is for
+//! public coins that the parties must agree on, not for challenges that must be unpredictable
+//! until a prover's messages are fixed: those come from an [MpcTranscript](crate::MpcTranscript).
+use lazy_static::lazy_static;
+use mpc_net::MpcMultiNet as Net;
+use rand::rngs::StdRng;
+use rand::{CryptoRng, Error, Rng, RngCore, SeedableRng};
+use sha2::{Digest, Sha256};
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::channel::MpcSerNet;
+
+struct Stream {
+    seed: [u8; 32],
+    /// How many rngs have been drawn.
+    drawn: u64,
+}
+
+lazy_static! {
+    /// By session tag.
+    static ref STREAMS: Mutex<HashMap<u64, Stream>> = Mutex::new(HashMap::new());
+}
+
+/// Agree on a joint seed for the current session, replacing any earlier one. Every party must
+/// call this, at the same point.
+///
+/// Costs two broadcast rounds. The contributions come from [seed::rng](crate::seed::rng), so a
+/// seeded session agrees on the same joint seed from run to run.
+pub fn setup() {
+    let contribution: [u8; 32] = crate::seed::rng(b"shared rng").gen();
+    let mut h = Sha256::new();
+    for c in Net::atomic_broadcast(&contribution.to_vec()) {
+        h.update(&c);
+    }
+    let stream = Stream {
+        seed: h.finalize().into(),
+        drawn: 0,
+    };
+    STREAMS.lock().unwrap().insert(Net::session_tag(), stream);
+}
+
+/// Whether the current session has a joint seed.
+pub fn is_set_up() -> bool {
+    STREAMS.lock().unwrap().contains_key(&Net::session_tag())
+}
+
+/// Forget the current session's joint seed.
+pub fn clear() {
+    STREAMS.lock().unwrap().remove(&Net::session_tag());
+}
+
+/// An rng that every party of the session draws alike.
+pub struct SharedRng {
+    rng: StdRng,
+}
+
+impl SharedRng {
+    /// A fresh rng, derived from the session's joint seed, `label`, and how many were drawn
+    /// before it, so that the `i`th is the same at every party.
+    ///
+    /// If the session has no joint seed yet, this runs [setup] first, which every party must then
+    /// do at the same point.
+    pub fn new(label: &[u8]) -> Self {
+        if !is_set_up() {
+            setup();
+        }
+        let mut streams = STREAMS.lock().unwrap();
+        let s = streams.get_mut(&Net::session_tag()).unwrap();
+        let seed = Sha256::new()
+            .chain(&s.seed)
+            .chain(&s.drawn.to_le_bytes())
+            .chain(label)
+            .finalize();
+        s.drawn += 1;
+        Self {
+            rng: StdRng::from_seed(seed.into()),
+        }
+    }
+}
+
+impl RngCore for SharedRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for SharedRng {}
@@ -0,0 +1,212 @@
+//! Fiat-Shamir transcripts that all parties keep in lockstep.
+//!
+//! Every party absorbs the same public messages (typically commitments, once revealed), in the same
+//! order, so every party derives the same challenges without talking. No party can bias a
+//! challenge, since it is a hash of values that were fixed before it was drawn.
+//!
+//! When there is nothing binding to hash yet, [MpcTranscript::coin_flip] derives a challenge from
+//! a commit-then-open coin flip instead.
+//!
+//! The tables a prover needs for a challenge (its powers, or the Lagrange basis evaluated at it)
+//! would otherwise be computed between drawing the challenge and starting the next round, on the
+//! critical path. Whatever part of them does not depend on the challenge can instead be
+//! [speculate]d on another thread before the round that fixes the challenge, overlapping the
+//! round's communication, and finished by [MpcTranscript::challenge_with].
+use ark_ff::{batch_inversion, FftField, Field, One, PrimeField, PubUniformRand};
+use ark_poly::EvaluationDomain;
+use ark_serialize::CanonicalSerialize;
+use merlin::Transcript;
+use mpc_trait::MpcWire;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use std::panic;
+use std::thread::{self, JoinHandle};
+
+use crate::channel::MpcSerNet;
+use crate::share::field::FieldShare;
+use crate::wire::field::MpcField;
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+
+pub struct MpcTranscript {
+    t: Transcript,
+}
+
+impl MpcTranscript {
+    /// Start a transcript for the protocol `label`.
+    pub fn new(label: &'static [u8]) -> Self {
+        Self {
+            t: Transcript::new(label),
+        }
+    }
+
+    /// Absorb a public value.
+    ///
+    /// Panics if `value` is still shared: its shares differ between parties, so the parties'
+    /// challenges would diverge.
+    pub fn append<T: CanonicalSerialize + MpcWire>(&mut self, label: &'static [u8], value: &T) {
+        assert!(
+            !value.is_shared(),
+            "cannot absorb a shared value into the transcript; reveal it first"
+        );
+        let mut bytes = Vec::new();
+        value.serialize(&mut bytes).unwrap();
+        self.t.append_message(label, &bytes);
+    }
+
+    /// Absorb raw bytes, which must be the same at every party.
+    pub fn append_bytes(&mut self, label: &'static [u8], bytes: &[u8]) {
+        self.t.append_message(label, bytes);
+    }
+
+    fn challenge_rng(&mut self, label: &'static [u8]) -> StdRng {
+        let mut seed = [0u8; 32];
+        self.t.challenge_bytes(label, &mut seed);
+        StdRng::from_seed(seed)
+    }
+
+    /// Derive a challenge from everything absorbed so far.
+    pub fn challenge<T: PubUniformRand>(&mut self, label: &'static [u8]) -> T {
+        T::pub_rand(&mut self.challenge_rng(label))
+    }
+
+    /// Derive a challenge, as by [MpcTranscript::challenge], and finish its tables from the
+    /// result of `speculation`.
+    pub fn challenge_with<T: PubUniformRand + Copy, P, R>(
+        &mut self,
+        label: &'static [u8],
+        speculation: Speculation<P>,
+        finish: impl FnOnce(P, T) -> R,
+    ) -> (T, R) {
+        let c = self.challenge(label);
+        (c, finish(speculation.join(), c))
+    }
+
+    /// Derive a challenge by jointly flipping coins.
+    ///
+    /// Each party commits to a random seed, then all open theirs; the seeds are absorbed and the
+    /// challenge derived as by [MpcTranscript::challenge]. It is uniform as long as one party is
+    /// honest, at the cost of two broadcast rounds.
+    pub fn coin_flip<T: PubUniformRand>(&mut self, label: &'static [u8]) -> T {
+        let seed: [u8; 32] = crate::seed::rng(b"coin").gen();
+        for s in Net::atomic_broadcast(&seed.to_vec()) {
+            self.t.append_message(b"coin", &s);
+        }
+        self.challenge(label)
+    }
+
+    /// Check that all parties have absorbed the same messages.
+    ///
+    /// Costs one broadcast. Panics on disagreement, which means a party absorbed a value that was
+    /// not actually public.
+    pub fn check_agreement(&mut self) {
+        let mut digest = [0u8; 32];
+        self.t.challenge_bytes(b"agreement", &mut digest);
+        let all = Net::broadcast_bytes(&digest);
+        for (i, d) in all.iter().enumerate() {
+            assert_eq!(&d[..], &digest[..], "transcript of party {} diverged", i);
+        }
+    }
+}
+
+/// Work towards a challenge's tables, running on another thread.
+///
+/// It must not use the network: only the thread that runs the protocol may.
+#[must_use = "the speculated tables are only used once joined"]
+pub struct Speculation<P> {
+    handle: JoinHandle<P>,
+}
+
+/// Start `prepare` on another thread, typically just before the round whose messages fix the next
+/// challenge.
+pub fn speculate<P: Send + 'static>(
+    prepare: impl FnOnce() -> P + Send + 'static,
+) -> Speculation<P> {
+    Speculation {
+        handle: thread::spawn(prepare),
+    }
+}
+
+impl<P> Speculation<P> {
+    /// Wait for the result. A panic in `prepare` is resumed here.
+    pub fn join(self) -> P {
+        self.handle
+            .join()
+            .unwrap_or_else(|e| panic::resume_unwind(e))
+    }
+}
+
+/// The Lagrange basis of a domain, ready to be evaluated at a challenge.
+///
+/// The domain's elements do not depend on the challenge, so are [speculate]d; evaluating at the
+/// challenge then costs one batch inversion. A challenge that must stay hidden can be evaluated
+/// at while shared, with [LagrangeTable::evaluate_shared].
+pub struct LagrangeTable<F> {
+    size_inv: F,
+    elements: Vec<F>,
+}
+
+impl<F: FftField> LagrangeTable<F> {
+    pub fn new<D: EvaluationDomain<F>>(domain: D) -> Self {
+        Self {
+            size_inv: domain.size_as_field_element().inverse().unwrap(),
+            elements: domain.elements().collect(),
+        }
+    }
+
+    /// Speculate the table for `domain`.
+    pub fn speculate<D: EvaluationDomain<F> + Send + 'static>(domain: D) -> Speculation<Self> {
+        speculate(move || Self::new(domain))
+    }
+
+    /// The evaluations at `z` of the Lagrange basis polynomials, in the order of the domain's
+    /// elements.
+    pub fn evaluate(&self, z: F) -> Vec<F> {
+        let n = self.elements.len() as u64;
+        let vanishing = z.pow(&[n]) - F::one();
+        if vanishing.is_zero() {
+            return self
+                .elements
+                .iter()
+                .map(|w| if *w == z { F::one() } else { F::zero() })
+                .collect();
+        }
+        // L_i(z) = w^i (z^n - 1) / (n (z - w^i))
+        let mut denominators: Vec<F> = self.elements.iter().map(|w| z - w).collect();
+        batch_inversion(&mut denominators);
+        let scale = vanishing * self.size_inv;
+        self.elements
+            .iter()
+            .zip(denominators)
+            .map(|(w, d)| *w * d * scale)
+            .collect()
+    }
+}
+
+impl<F: PrimeField> LagrangeTable<F> {
+    /// The evaluations at a shared `z` of the Lagrange basis polynomials, for protocols in which
+    /// no party may learn the point.
+    ///
+    /// Costs `log n` rounds of multiplications for `z^n`, then one batch division. `z` must not be
+    /// in the domain, which a random point is with overwhelming probability: if it is, one of the
+    /// divisors is zero, and the division panics on opening it.
+    pub fn evaluate_shared<S: FieldShare<F>>(&self, z: MpcField<F, S>) -> Vec<MpcField<F, S>> {
+        if let MpcField::Public(z) = z {
+            return self.evaluate(z).into_iter().map(MpcField::Public).collect();
+        }
+        let n = self.elements.len() as u64;
+        let scale = (z.pow(&[n]) - MpcField::one()) * MpcField::Public(self.size_inv);
+        let mut numerators: Vec<_> = self
+            .elements
+            .iter()
+            .map(|w| scale * MpcField::Public(*w))
+            .collect();
+        let denominators: Vec<_> = self
+            .elements
+            .iter()
+            .map(|w| z - MpcField::Public(*w))
+            .collect();
+        MpcField::batch_division_in_place(&mut numerators, &denominators);
+        numerators
+    }
+}
@@ -0,0 +1,265 @@
+//! [ToConstraintField] for the MPC wrapper types, so that gadgets can absorb commitments and other
+//! MPC values into a circuit over [MpcField].
+//!
+//! [MpcField] has it already, as a prime field, and so has
+//! [MpcEdwardsAffine](super::MpcEdwardsAffine), through its coordinates. Here, an [MpcExtField]
+//! gives its coefficients over the base prime field, and an [MpcGroup] of a curve that has
+//! [SharedCoordinates] gives the elements that the plain point would: `x` and `y` on a twisted
+//! Edwards curve, and `x`, `y` and an infinity flag on a short Weierstrass one. The pairing
+//! wrappers give those of their [MpcGroup]. A public value gives public elements, without
+//! communication. A shared one gives shared elements, and every party must convert it, in the
+//! same order.
+//!
+//! A shared value's coordinates are not linear in its shares, so they are found by masking. Each
+//! party draws a random `r_i`, and shares both it and the coordinates of `r_i G`. The parties open
+//! `P + R`, for the sum `R` of the `r_i G`, which is uniform whatever `P` is. They add up the
+//! coordinates of `R` with shared point additions, and subtract them from the public `P + R` the
+//! same way. An extension field element is masked alike, with its coefficients for coordinates.
+//!
+//! A shared point costs a few rounds per party on a twisted Edwards curve. On a short Weierstrass
+//! curve, whose addition has exceptions, the infinity flag takes an equality test, which costs a
+//! multiplication per bit of the base field: several hundred rounds. Either way, the result is
+//! wrong with negligible probability, when the random summands collide.
+//!
+//! Nothing checks that a party shared the coordinates of its own `r_i G`, and a dishonest one can
+//! shift the result, so a conversion is only as sound as the parties are honest-but-curious.
+use ark_ec::models::{
+    short_weierstrass_jacobian as sw, twisted_edwards_extended as te, SWModelParameters,
+    TEModelParameters,
+};
+use ark_ec::{group::Group, AffineCurve, PairingEngine};
+use ark_ff::{
+    BigInteger, Field, FpParameters, One, PrimeField, ToConstraintField, UniformRand, Zero,
+};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+
+use super::edwards::MpcEdwardsAffine;
+use super::field::MpcField;
+use super::group::MpcGroup;
+use super::pairing::{MpcExtField, MpcG1Affine, MpcG1Projective, MpcG2Affine, MpcG2Projective};
+use crate::share::field::{ExtFieldShare, FieldShare};
+use crate::share::group::GroupShare;
+use crate::share::pairing::PairingShare;
+use crate::{seed, Reveal};
+
+/// A curve whose shared points [MpcGroup] can turn into shared coordinates.
+pub trait SharedCoordinates:
+    Group + ToConstraintField<<Self as SharedCoordinates>::Coordinate>
+{
+    /// The field of the coordinates.
+    type Coordinate: PrimeField;
+
+    /// The elements that [ToConstraintField] gives for the value of `point`, shared. Every party
+    /// must call this.
+    fn shared_coordinates<S, FS>(point: S) -> Vec<MpcField<Self::Coordinate, FS>>
+    where
+        S: GroupShare<Self>,
+        FS: FieldShare<Self::Coordinate>;
+}
+
+/// Open `point + R`, for a random `R` to which each party adds a summand `r_i G`, and share the
+/// `coordinates` of each summand.
+fn open_masked<G, S, F, FS>(
+    point: S,
+    generator: G,
+    coordinates: impl Fn(G) -> Vec<F>,
+) -> (G, Vec<Vec<MpcField<F, FS>>>)
+where
+    G: Group,
+    S: GroupShare<G>,
+    F: PrimeField,
+    FS: FieldShare<F>,
+{
+    let rng = &mut seed::rng(b"to_field_elements mask");
+    let r = G::ScalarField::rand(rng);
+    let mine = coordinates(Group::mul(&generator, &r));
+    let mut mask = MpcGroup::<G, S>::Public(G::zero());
+    let mut summands = Vec::with_capacity(Net::n_parties());
+    for i in 0..Net::n_parties() {
+        let r_i = MpcField::<G::ScalarField, S::FieldShare>::party_share_batch(i, vec![r], rng);
+        mask += MpcGroup::Public(generator) * r_i[0];
+        summands.push(MpcField::party_share_batch(i, mine.clone(), rng));
+    }
+    ((MpcGroup::Shared(point) + mask).reveal(), summands)
+}
+
+fn te_coordinates<P, G, S, FS>(point: S) -> Vec<MpcField<P::BaseField, FS>>
+where
+    P: TEModelParameters,
+    P::BaseField: PrimeField,
+    G: Group + From<te::GroupAffine<P>> + Into<te::GroupAffine<P>>,
+    S: GroupShare<G>,
+    FS: FieldShare<P::BaseField>,
+{
+    let generator = te::GroupAffine::<P>::prime_subgroup_generator().into();
+    let (opened, summands) = open_masked::<G, S, _, FS>(point, generator, |g| {
+        let g = g.into();
+        vec![g.x, g.y]
+    });
+    // the addition law is complete, so nothing can go wrong here
+    let mask = summands
+        .iter()
+        .fold(MpcEdwardsAffine::<P, FS>::zero(), |sum, xy| {
+            sum + &MpcEdwardsAffine::new(xy[0], xy[1])
+        });
+    let p = MpcEdwardsAffine::<P, FS>::from_public(opened.into()) - &mask;
+    vec![p.x, p.y]
+}
+
+fn sw_coordinates<P, G, S, FS>(point: S) -> Vec<MpcField<P::BaseField, FS>>
+where
+    P: SWModelParameters,
+    P::BaseField: PrimeField,
+    G: Group + From<sw::GroupAffine<P>> + Into<sw::GroupAffine<P>>,
+    S: GroupShare<G>,
+    FS: FieldShare<P::BaseField>,
+{
+    let generator = sw::GroupAffine::<P>::prime_subgroup_generator().into();
+    let (opened, summands) = open_masked::<G, S, _, FS>(point, generator, |g| {
+        let g = g.into();
+        vec![g.x, g.y]
+    });
+    // R, from its summands, whose x coordinates differ except with negligible probability
+    let (mut x_r, mut y_r) = (summands[0][0], summands[0][1]);
+    for xy in &summands[1..] {
+        let l = (xy[1] - y_r) / (xy[0] - x_r);
+        let x = l * l - x_r - xy[0];
+        y_r = l * (x_r - x) - y_r;
+        x_r = x;
+    }
+
+    // P = Q - R, for the public Q = P + R, which is not zero except with negligible probability.
+    // Q and R share an x coordinate exactly when P is zero (or, negligibly, -2R); then the
+    // formula below divides by one instead, and its result is discarded.
+    let q: sw::GroupAffine<P> = opened.into();
+    let (x_q, y_q) = (MpcField::Public(q.x), MpcField::Public(q.y));
+    let z = x_r - x_q;
+    let mut p_minus_one = <P::BaseField as PrimeField>::Params::MODULUS;
+    p_minus_one.sub_noborrow(&1u64.into());
+    let infinity = MpcField::one() - z.pow(p_minus_one);
+    let l = -(y_r + y_q) / (z + infinity);
+    let x = l * l - x_q - x_r;
+    let y = l * (x_q - x) - y_q;
+    let finite = MpcField::one() - infinity;
+    // the zero point is (0, 1), as in the plain curve
+    vec![finite * x, finite * y + infinity, infinity]
+}
+
+macro_rules! impl_shared_coordinates {
+    ($model:ident, $params:ident, $coordinates:ident) => {
+        impl<P> SharedCoordinates for $model::GroupAffine<P>
+        where
+            P: $params,
+            P::BaseField: PrimeField,
+        {
+            type Coordinate = P::BaseField;
+
+            fn shared_coordinates<S, FS>(point: S) -> Vec<MpcField<P::BaseField, FS>>
+            where
+                S: GroupShare<Self>,
+                FS: FieldShare<P::BaseField>,
+            {
+                $coordinates::<P, Self, S, FS>(point)
+            }
+        }
+
+        impl<P> SharedCoordinates for $model::GroupProjective<P>
+        where
+            P: $params,
+            P::BaseField: PrimeField,
+        {
+            type Coordinate = P::BaseField;
+
+            fn shared_coordinates<S, FS>(point: S) -> Vec<MpcField<P::BaseField, FS>>
+            where
+                S: GroupShare<Self>,
+                FS: FieldShare<P::BaseField>,
+            {
+                $coordinates::<P, Self, S, FS>(point)
+            }
+        }
+    };
+}
+
+impl_shared_coordinates!(te, TEModelParameters, te_coordinates);
+impl_shared_coordinates!(sw, SWModelParameters, sw_coordinates);
+
+impl<G, S, FS> ToConstraintField<MpcField<G::Coordinate, FS>> for MpcGroup<G, S>
+where
+    G: SharedCoordinates,
+    S: GroupShare<G>,
+    FS: FieldShare<G::Coordinate>,
+{
+    fn to_field_elements(&self) -> Option<Vec<MpcField<G::Coordinate, FS>>> {
+        match self {
+            MpcGroup::Public(g) => Some(
+                g.to_field_elements()?
+                    .into_iter()
+                    .map(MpcField::Public)
+                    .collect(),
+            ),
+            MpcGroup::Shared(s) => Some(G::shared_coordinates(*s)),
+        }
+    }
+}
+
+macro_rules! impl_pairing_curve_coordinates {
+    ($wrap:ident, $curve:ident, $share:ident) => {
+        impl<E, PS, CF> ToConstraintField<CF> for $wrap<E, PS>
+        where
+            E: PairingEngine,
+            PS: PairingShare<E>,
+            CF: Field,
+            MpcGroup<E::$curve, PS::$share>: ToConstraintField<CF>,
+        {
+            fn to_field_elements(&self) -> Option<Vec<CF>> {
+                self.val.to_field_elements()
+            }
+        }
+    };
+}
+
+impl_pairing_curve_coordinates!(MpcG1Affine, G1Affine, G1AffineShare);
+impl_pairing_curve_coordinates!(MpcG1Projective, G1Projective, G1ProjectiveShare);
+impl_pairing_curve_coordinates!(MpcG2Affine, G2Affine, G2AffineShare);
+impl_pairing_curve_coordinates!(MpcG2Projective, G2Projective, G2ProjectiveShare);
+
+impl<F, FS> ToConstraintField<MpcField<F::BasePrimeField, FS::Base>> for MpcExtField<F, FS>
+where
+    F: Field + ToConstraintField<F::BasePrimeField>,
+    FS: ExtFieldShare<F>,
+{
+    fn to_field_elements(&self) -> Option<Vec<MpcField<F::BasePrimeField, FS::Base>>> {
+        let x = match self.val {
+            MpcField::Public(x) => {
+                return Some(
+                    x.to_field_elements()?
+                        .into_iter()
+                        .map(MpcField::Public)
+                        .collect(),
+                )
+            }
+            MpcField::Shared(_) => self.val,
+        };
+        let rng = &mut seed::rng(b"to_field_elements mask");
+        let r = F::rand(rng);
+        let mine = r.to_field_elements()?;
+        let mut mask = MpcField::<F, FS::Ext>::zero();
+        let mut coefficients = vec![MpcField::zero(); mine.len()];
+        for i in 0..Net::n_parties() {
+            mask += MpcField::party_share_batch(i, vec![r], rng)[0];
+            let r_i = MpcField::party_share_batch(i, mine.clone(), rng);
+            for (c, r_c) in coefficients.iter_mut().zip(r_i) {
+                *c += r_c;
+            }
+        }
+        let opened = (x + mask).reveal().to_field_elements()?;
+        Some(
+            opened
+                .into_iter()
+                .zip(coefficients)
+                .map(|(c, r)| MpcField::Public(c) - r)
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,129 @@
+//! Twisted Edwards curves with shared coordinates.
+//!
+//! An embedded curve, such as Edwards-BLS12 (`ark-ed-on-bls12-377`), has a base field that is the
+//! scalar field of a pairing curve, so a circuit over that field can do arithmetic on its points
+//! cheaply. For a collaborative proof, the circuit's field is an [MpcField], and so must be the
+//! coordinates of the points in its witness: [MpcEdwardsParameters] is the curve `P` over
+//! [MpcField], so that [MpcEdwardsAffine] points can hold shared coordinates, and be allocated as
+//! witnesses by the `r1cs-std` gadgets for twisted Edwards curves.
+//!
+//! Shared points in their own right, for protocols outside circuits, are
+//! [MpcGroup](super::MpcGroup)s of the plain curve.
+use ark_ec::models::{
+    twisted_edwards_extended as te, ModelParameters, MontgomeryModelParameters, TEModelParameters,
+};
+use ark_ff::PrimeField;
+use derivative::Derivative;
+use rand::Rng;
+
+use std::marker::PhantomData;
+
+use super::field::MpcField;
+use crate::share::field::FieldShare;
+use crate::Reveal;
+
+/// The twisted Edwards curve `P`, over the [MpcField] of its base field with shares `S`.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Default(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = ""),
+    Debug(bound = "")
+)]
+pub struct MpcEdwardsParameters<P, S> {
+    _params: PhantomData<(P, S)>,
+}
+
+pub type MpcEdwardsAffine<P, S> = te::GroupAffine<MpcEdwardsParameters<P, S>>;
+pub type MpcEdwardsProjective<P, S> = te::GroupProjective<MpcEdwardsParameters<P, S>>;
+
+impl<P, S> ModelParameters for MpcEdwardsParameters<P, S>
+where
+    P: TEModelParameters,
+    P::BaseField: PrimeField,
+    S: FieldShare<P::BaseField>,
+{
+    type BaseField = MpcField<P::BaseField, S>;
+    type ScalarField = P::ScalarField;
+}
+
+impl<P, S> TEModelParameters for MpcEdwardsParameters<P, S>
+where
+    P: TEModelParameters,
+    P::BaseField: PrimeField,
+    S: FieldShare<P::BaseField>,
+{
+    const COEFF_A: Self::BaseField = MpcField::Public(<P as TEModelParameters>::COEFF_A);
+    const COEFF_D: Self::BaseField = MpcField::Public(P::COEFF_D);
+    const COFACTOR: &'static [u64] = P::COFACTOR;
+    const COFACTOR_INV: Self::ScalarField = P::COFACTOR_INV;
+    const AFFINE_GENERATOR_COEFFS: (Self::BaseField, Self::BaseField) = (
+        MpcField::Public(P::AFFINE_GENERATOR_COEFFS.0),
+        MpcField::Public(P::AFFINE_GENERATOR_COEFFS.1),
+    );
+
+    type MontgomeryModelParameters = Self;
+
+    #[inline(always)]
+    fn mul_by_a(elem: &Self::BaseField) -> Self::BaseField {
+        // `a` is public, and usually small, like -1
+        match elem {
+            MpcField::Public(x) => MpcField::Public(P::mul_by_a(x)),
+            MpcField::Shared(_) => *elem * <Self as TEModelParameters>::COEFF_A,
+        }
+    }
+}
+
+impl<P, S> MontgomeryModelParameters for MpcEdwardsParameters<P, S>
+where
+    P: TEModelParameters,
+    P::BaseField: PrimeField,
+    S: FieldShare<P::BaseField>,
+{
+    const COEFF_A: Self::BaseField =
+        MpcField::Public(<P::MontgomeryModelParameters as MontgomeryModelParameters>::COEFF_A);
+    const COEFF_B: Self::BaseField = MpcField::Public(P::MontgomeryModelParameters::COEFF_B);
+
+    type TEModelParameters = Self;
+}
+
+/// A point's coordinates are shared separately, so a shared point is only known to be on the
+/// curve once revealed.
+impl<P, S> Reveal for MpcEdwardsAffine<P, S>
+where
+    P: TEModelParameters,
+    P::BaseField: PrimeField,
+    S: FieldShare<P::BaseField>,
+{
+    type Base = te::GroupAffine<P>;
+
+    fn reveal(self) -> Self::Base {
+        let xy = vec![self.x, self.y].reveal();
+        te::GroupAffine::new(xy[0], xy[1])
+    }
+    fn from_add_shared(b: Self::Base) -> Self {
+        Self::new(MpcField::from_add_shared(b.x), MpcField::from_add_shared(b.y))
+    }
+    fn from_public(b: Self::Base) -> Self {
+        Self::new(MpcField::from_public(b.x), MpcField::from_public(b.y))
+    }
+    fn unwrap_as_public(self) -> Self::Base {
+        te::GroupAffine::new(self.x.unwrap_as_public(), self.y.unwrap_as_public())
+    }
+    fn king_share<R: Rng>(b: Self::Base, rng: &mut R) -> Self {
+        Self::king_share_batch(vec![b], rng).pop().unwrap()
+    }
+    fn king_share_batch<R: Rng>(bs: Vec<Self::Base>, rng: &mut R) -> Vec<Self> {
+        Self::party_share_batch(0, bs, rng)
+    }
+    fn party_share_batch<R: Rng>(owner: usize, bs: Vec<Self::Base>, rng: &mut R) -> Vec<Self> {
+        let coords = bs.iter().flat_map(|b| vec![b.x, b.y]).collect();
+        let coords = MpcField::party_share_batch(owner, coords, rng);
+        coords
+            .chunks(2)
+            .map(|xy| Self::new(xy[0], xy[1]))
+            .collect()
+    }
+}
@@ -5,7 +5,8 @@ use zeroize::Zeroize;
 
 use ark_ff::bytes::{FromBytes, ToBytes};
 use ark_ff::prelude::*;
-use ark_ff::{poly_stub, FftField};
+use ark_ff::{poly_stub, FftField, LegendreSymbol};
+use ark_poly::domain::DomainCoeff;
 use ark_serialize::{
     CanonicalDeserialize, CanonicalDeserializeWithFlags, CanonicalSerialize,
     CanonicalSerializeWithFlags, Flags, SerializationError,
@@ -18,9 +19,13 @@ use std::iter::{Product, Sum};
 use std::marker::PhantomData;
 use std::ops::*;
 
-use super::super::share::field::FieldShare;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::super::share::field::{FieldShare, MulScratch, SmallScalar};
 use super::super::share::BeaverSource;
-use crate::Reveal;
+use super::guarded_open;
+use crate::{cost, leak, seed, Reveal};
 use mpc_net::{MpcNet, MpcMultiNet as Net};
 
 #[derive(Clone, Copy, Hash, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -41,6 +46,7 @@ pub struct DummyFieldTripleSource<T, S> {
 impl<T: Field, S: FieldShare<T>> BeaverSource<S, S, S> for DummyFieldTripleSource<T, S> {
     #[inline]
     fn triple(&mut self) -> (S, S, S) {
+        cost::drew_triples(1);
         (
             S::from_add_shared(if Net::am_king() {
                 T::one()
@@ -79,12 +85,43 @@ impl<T: Field, S: FieldShare<T>> BeaverSource<S, S, S> for DummyFieldTripleSourc
 impl<T: Field, S: FieldShare<T>> MpcField<T, S> {
     #[inline]
     pub fn inv(self) -> Option<Self> {
-        match self {
+        let p = self.provenance();
+        let inv = match self {
             Self::Public(x) => x.inverse().map(MpcField::Public),
+            Self::Shared(x) if Net::is_single_party() => x
+                .unwrap_as_public()
+                .inverse()
+                .map(|i| MpcField::Shared(S::from_public(i))),
             Self::Shared(x) => Some(MpcField::Shared(
                 x.inv(&mut DummyFieldTripleSource::default()),
             )),
+        };
+        if let Some(i) = &inv {
+            i.set_provenance(p);
         }
+        inv
+    }
+    /// The provenances of `selfs[i] op others[i]`, if the leak analysis is on.
+    fn joint_provenances(selfs: &[Self], others: &[Self]) -> Option<Vec<Option<leak::Provenance>>> {
+        if !leak::enabled() {
+            return None;
+        }
+        let ps = selfs
+            .iter()
+            .zip(others)
+            .map(|(a, b)| leak::join(a.provenance(), b.provenance()))
+            .collect();
+        Some(ps)
+    }
+    /// Wrap `owner`'s freshly shared inputs.
+    fn inputs(owner: usize, shares: Vec<S>) -> Vec<Self> {
+        shares
+            .into_iter()
+            .map(|s| {
+                leak::record_input(&s, Some(owner));
+                Self::Shared(s)
+            })
+            .collect()
     }
     pub fn all_public_or_shared(v: impl IntoIterator<Item = Self>) -> Result<Vec<T>, Vec<S>> {
         let mut out_a = Vec::new();
@@ -103,10 +140,245 @@ impl<T: Field, S: FieldShare<T>> MpcField<T, S> {
             Err(out_b)
         }
     }
+    /// Open `xs` to every party as one batch, with the checks of [Reveal::reveal]. Every party
+    /// must call this.
+    pub fn open_batch(xs: Vec<Self>) -> Vec<T> {
+        let ps = xs.iter().map(|x| x.provenance()).collect();
+        match Self::all_public_or_shared(xs) {
+            Ok(public) => public,
+            Err(shares) if shares.is_empty() => Vec::new(),
+            Err(shares) => guarded_open(ps, || S::batch_open(shares)),
+        }
+    }
+    /// Set `selfs[i] *= others[i]`, keeping all intermediate buffers in `scratch`.
+    ///
+    /// Callers that multiply in a loop should reuse one `scratch`, so that the shares are not
+    /// re-allocated on each batch.
+    pub fn batch_product_in_place_with(
+        selfs: &mut [Self],
+        others: &[Self],
+        scratch: &mut MpcMulScratch<T, S>,
+    ) {
+        assert_eq!(selfs.len(), others.len());
+        if selfs.is_empty() {
+            return;
+        }
+        let selfs_shared = selfs[0].is_shared();
+        let others_shared = others[0].is_shared();
+        assert!(
+            selfs.iter().all(|s| s.is_shared() == selfs_shared),
+            "Selfs heterogenously shared!"
+        );
+        assert!(
+            others.iter().all(|s| s.is_shared() == others_shared),
+            "others heterogenously shared!"
+        );
+        let ps = Self::joint_provenances(selfs, others);
+        if selfs_shared && others_shared && !Net::is_single_party() {
+            let MpcMulScratch { sshares, oshares, inner } = scratch;
+            let unwrap = |s: &Self| match s {
+                Self::Shared(s) => *s,
+                Self::Public(_) => unreachable!(),
+            };
+            sshares.clear();
+            sshares.extend(selfs.iter().map(unwrap));
+            oshares.clear();
+            oshares.extend(others.iter().map(unwrap));
+            S::batch_mul_in_place(
+                sshares,
+                oshares,
+                &mut DummyFieldTripleSource::default(),
+                inner,
+            );
+            for (self_, new) in selfs.iter_mut().zip(sshares.iter()) {
+                *self_ = Self::Shared(*new);
+            }
+        } else if selfs_shared && others_shared {
+            // a single party's products of shares still count costs, which are per thread
+            for (a, b) in selfs.iter_mut().zip(others) {
+                *a *= b;
+            }
+        } else {
+            ark_std::cfg_iter_mut!(selfs)
+                .zip(ark_std::cfg_iter!(others))
+                .for_each(|(a, b)| *a *= b);
+        }
+        if let Some(ps) = ps {
+            for (a, p) in selfs.iter().zip(ps) {
+                a.set_provenance(p);
+            }
+        }
+    }
+}
+
+/// Invert every element of `v`, as [ark_ff::batch_inversion] does for public fields, opening the
+/// shared ones, each times a random mask, in one batch, rather than one at a time. Public zeros
+/// are left as they are, but shared elements must be nonzero, as for [Field::inverse]: a masked
+/// zero opens as zero, and has no inverse.
+pub fn batch_inverse<T: Field, S: FieldShare<T>>(v: &mut [MpcField<T, S>]) {
+    let single = Net::is_single_party();
+    let (mut public, mut shares) = (Vec::new(), Vec::new());
+    for x in v.iter() {
+        match x {
+            MpcField::Public(x) => public.push(*x),
+            MpcField::Shared(s) if single => public.push(s.unwrap_as_public()),
+            MpcField::Shared(s) => shares.push(*s),
+        }
+    }
+    ark_ff::batch_inversion(&mut public);
+    let shares = if shares.is_empty() {
+        shares
+    } else {
+        S::batch_inv(shares, &mut DummyFieldTripleSource::default())
+    };
+    let (mut public, mut shares) = (public.into_iter(), shares.into_iter());
+    for x in v.iter_mut() {
+        let p = x.provenance();
+        *x = match x {
+            MpcField::Public(_) => MpcField::Public(public.next().unwrap()),
+            MpcField::Shared(_) if single => {
+                MpcField::Shared(S::from_public(public.next().unwrap()))
+            }
+            MpcField::Shared(_) => MpcField::Shared(shares.next().unwrap()),
+        };
+        x.set_provenance(p);
+    }
+}
+
+/// Open `x r²` for each shared `x`, with a fresh random shared `r`, and return the openings with
+/// the `r`s. An opening is zero exactly when `x` is, and otherwise a uniform element of `x`'s
+/// coset of squares, so it tells only `x`'s Legendre symbol.
+///
+/// Costs two rounds of multiplications and one of openings. The result is wrong with negligible
+/// probability, when an `r` is zero.
+fn open_square_masked<T: Field, S: FieldShare<T>>(xs: Vec<S>) -> (Vec<T>, Vec<S>) {
+    let rng = &mut seed::rng(b"square mask");
+    let rs: Vec<S> = (0..xs.len()).map(|_| S::rand(rng)).collect();
+    let source = &mut DummyFieldTripleSource::default();
+    let squares = S::batch_mul(rs.clone(), rs.clone(), source);
+    let opened = S::batch_open(S::batch_mul(xs, squares, source));
+    (opened, rs)
+}
+
+/// The Legendre symbol of every element of `v`, opening the shared ones, each times a random
+/// square, in one batch. For a shared element, the symbol is all that is revealed.
+pub fn batch_legendre<T: SquareRootField, S: FieldShare<T>>(
+    v: &[MpcField<T, S>],
+) -> Vec<LegendreSymbol> {
+    let single = Net::is_single_party();
+    let shares: Vec<S> = v
+        .iter()
+        .filter_map(|x| match x {
+            MpcField::Shared(s) if !single => Some(*s),
+            _ => None,
+        })
+        .collect();
+    let mut opened = if shares.is_empty() {
+        Vec::new()
+    } else {
+        open_square_masked(shares).0
+    }
+    .into_iter();
+    v.iter()
+        .map(|x| match x {
+            MpcField::Public(x) => x.legendre(),
+            MpcField::Shared(s) if single => s.unwrap_as_public().legendre(),
+            MpcField::Shared(_) => opened.next().unwrap().legendre(),
+        })
+        .collect()
+}
+
+/// A square root of every element of `v`, or `None` for those that are not squares.
+///
+/// A public element's root is [SquareRootField::sqrt]'s. A shared element `x` is opened as `x r²`,
+/// for a random shared `r`, in one batch for all of them; the root of a square is then
+/// `sqrt(x r²) / r`, shared, which is either of `x`'s two roots, at random. Whether `x` is a
+/// square is revealed, as the `None` does anyway, but nothing else.
+pub fn batch_sqrt<T: SquareRootField, S: FieldShare<T>>(
+    v: &[MpcField<T, S>],
+) -> Vec<Option<MpcField<T, S>>> {
+    let single = Net::is_single_party();
+    let shares: Vec<S> = v
+        .iter()
+        .filter_map(|x| match x {
+            MpcField::Shared(s) if !single => Some(*s),
+            _ => None,
+        })
+        .collect();
+    let mut roots = if shares.is_empty() {
+        Vec::new()
+    } else {
+        let (opened, rs) = open_square_masked(shares);
+        let roots: Vec<Option<T>> = opened.iter().map(|y| y.sqrt()).collect();
+        // divide by the masks of the squares only
+        let (squares, rs): (Vec<T>, Vec<S>) = roots
+            .iter()
+            .zip(rs)
+            .filter_map(|(root, r)| root.filter(|y| !y.is_zero()).map(|y| (y, r)))
+            .unzip();
+        let inverses = if rs.is_empty() {
+            rs
+        } else {
+            S::batch_inv(rs, &mut DummyFieldTripleSource::default())
+        };
+        let mut scaled = squares
+            .into_iter()
+            .zip(inverses)
+            .map(|(y, mut r_inv)| *r_inv.scale(&y));
+        roots
+            .into_iter()
+            .map(|root| {
+                root.map(|y| {
+                    if y.is_zero() {
+                        S::from_public(T::zero())
+                    } else {
+                        scaled.next().unwrap()
+                    }
+                })
+            })
+            .collect()
+    }
+    .into_iter();
+    v.iter()
+        .map(|x| {
+            let root = match x {
+                MpcField::Public(x) => x.sqrt().map(MpcField::Public),
+                MpcField::Shared(s) if single => s
+                    .unwrap_as_public()
+                    .sqrt()
+                    .map(|r| MpcField::Shared(S::from_public(r))),
+                MpcField::Shared(_) => roots.next().unwrap().map(MpcField::Shared),
+            };
+            if let Some(r) = &root {
+                r.set_provenance(x.provenance());
+            }
+            root
+        })
+        .collect()
+}
+
+/// Scratch space for [MpcField::batch_product_in_place_with].
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct MpcMulScratch<T: Field, S: FieldShare<T>> {
+    sshares: Vec<S>,
+    oshares: Vec<S>,
+    inner: MulScratch<T, S>,
+}
+/// Scale a share by a public constant, skipping the multiplication if the constant is `0` or `±1`.
+///
+/// Constraint matrices are dominated by `±1` coefficients, so this is the common case.
+#[inline]
+fn scale_share<T: Field, S: FieldShare<T>>(share: &mut S, c: &T) {
+    match SmallScalar::of_unit(c) {
+        Some(small) => share.scale_small(small),
+        None => share.scale(c),
+    };
 }
 impl<'a, T: Field, S: FieldShare<T>> MulAssign<&'a MpcField<T, S>> for MpcField<T, S> {
     #[inline]
     fn mul_assign(&mut self, other: &Self) {
+        let p = leak::join(self.provenance(), other.provenance());
         match self {
             // for some reason, a two-stage match (rather than a tuple match) avoids moving
             // self
@@ -116,20 +388,25 @@ impl<'a, T: Field, S: FieldShare<T>> MulAssign<&'a MpcField<T, S>> for MpcField<
                 }
                 MpcField::Shared(y) => {
                     let mut t = *y;
-                    t.scale(x);
+                    scale_share(&mut t, x);
                     *self = MpcField::Shared(t);
                 }
             },
             MpcField::Shared(x) => match other {
                 MpcField::Public(y) => {
-                    x.scale(y);
+                    scale_share(x, y);
                 }
                 MpcField::Shared(y) => {
-                    let t = x.mul(*y, &mut DummyFieldTripleSource::default());
+                    let t = if Net::is_single_party() {
+                        S::from_public(x.unwrap_as_public() * y.unwrap_as_public())
+                    } else {
+                        x.mul(*y, &mut DummyFieldTripleSource::default())
+                    };
                     *self = MpcField::Shared(t);
                 }
             },
         }
+        self.set_provenance(p);
     }
 }
 impl<T: Field, S: FieldShare<T>> One for MpcField<T, S> {
@@ -154,6 +431,7 @@ impl<'a, T: Field, S: FieldShare<T> + 'a> Product<&'a MpcField<T, S>> for MpcFie
 impl<'a, T: Field, S: FieldShare<T>> DivAssign<&'a MpcField<T, S>> for MpcField<T, S> {
     #[inline]
     fn div_assign(&mut self, other: &Self) {
+        let p = leak::join(self.provenance(), other.provenance());
         match self {
             // for some reason, a two-stage match (rather than a tuple match) avoids moving
             // self
@@ -162,8 +440,13 @@ impl<'a, T: Field, S: FieldShare<T>> DivAssign<&'a MpcField<T, S>> for MpcField<
                     *x /= y;
                 }
                 MpcField::Shared(y) => {
-                    let mut t = y.inv(&mut DummyFieldTripleSource::default());
-                    t.scale(&x);
+                    let t = if Net::is_single_party() {
+                        S::from_public(*x / y.unwrap_as_public())
+                    } else {
+                        let mut t = y.inv(&mut DummyFieldTripleSource::default());
+                        t.scale(&x);
+                        t
+                    };
                     *self = MpcField::Shared(t);
                 }
             },
@@ -171,12 +454,16 @@ impl<'a, T: Field, S: FieldShare<T>> DivAssign<&'a MpcField<T, S>> for MpcField<
                 MpcField::Public(y) => {
                     x.scale(&y.inverse().unwrap());
                 }
+                MpcField::Shared(y) if Net::is_single_party() => {
+                    *x = S::from_public(x.unwrap_as_public() / y.unwrap_as_public());
+                }
                 MpcField::Shared(y) => {
                     let src = &mut DummyFieldTripleSource::default();
                     *x = x.div(*y, src);
                 }
             },
         }
+        self.set_provenance(p);
     }
 }
 
@@ -220,9 +507,10 @@ impl_ref_ops!(
 impl<T: Field, S: FieldShare<T>> MpcWire for MpcField<T, S> {
     #[inline]
     fn publicize(&mut self) {
+        let p = self.provenance();
         match self {
             MpcField::Shared(s) => {
-                *self = MpcField::Public(s.open());
+                *self = MpcField::Public(guarded_open(vec![p], || s.open()));
             }
             _ => {}
         }
@@ -249,8 +537,9 @@ impl<T: Field, S: FieldShare<T>> Reveal for MpcField<T, S> {
     type Base = T;
     #[inline]
     fn reveal(self) -> Self::Base {
+        let p = self.provenance();
         let result = match self {
-            Self::Shared(s) => s.reveal(),
+            Self::Shared(s) => guarded_open(vec![p], || s.reveal()),
             Self::Public(s) => s,
         };
         super::macros::check_eq(result.clone());
@@ -262,7 +551,9 @@ impl<T: Field, S: FieldShare<T>> Reveal for MpcField<T, S> {
     }
     #[inline]
     fn from_add_shared(b: Self::Base) -> Self {
-        MpcField::Shared(S::from_add_shared(b))
+        let s = S::from_add_shared(b);
+        leak::record_input(&s, None);
+        MpcField::Shared(s)
     }
     #[inline]
     fn unwrap_as_public(self) -> Self::Base {
@@ -273,11 +564,17 @@ impl<T: Field, S: FieldShare<T>> Reveal for MpcField<T, S> {
     }
     #[inline]
     fn king_share<R: Rng>(f: Self::Base, rng: &mut R) -> Self {
-        Self::Shared(S::king_share(f, rng))
+        let s = S::king_share(f, rng);
+        leak::record_input(&s, Some(0));
+        Self::Shared(s)
     }
     #[inline]
     fn king_share_batch<R: Rng>(f: Vec<Self::Base>, rng: &mut R) -> Vec<Self> {
-        S::king_share_batch(f, rng).into_iter().map(Self::Shared).collect()
+        Self::inputs(0, S::king_share_batch(f, rng))
+    }
+    #[inline]
+    fn party_share_batch<R: Rng>(owner: usize, f: Vec<Self::Base>, rng: &mut R) -> Vec<Self> {
+        Self::inputs(owner, S::party_share_batch(owner, f, rng))
     }
     fn init_protocol() {
         S::init_protocol()
@@ -356,40 +653,7 @@ impl<F: PrimeField, S: FieldShare<F>> Field for MpcField<F, S> {
     }
 
     fn batch_product_in_place(selfs: &mut [Self], others: &[Self]) {
-        let selfs_shared = selfs[0].is_shared();
-        let others_shared = others[0].is_shared();
-        assert!(
-            selfs.iter().all(|s| s.is_shared() == selfs_shared),
-            "Selfs heterogenously shared!"
-        );
-        assert!(
-            others.iter().all(|s| s.is_shared() == others_shared),
-            "others heterogenously shared!"
-        );
-        if selfs_shared && others_shared {
-            let sshares = selfs
-                .iter()
-                .map(|s| match s {
-                    Self::Shared(s) => s.clone(),
-                    Self::Public(_) => unreachable!(),
-                })
-                .collect();
-            let oshares = others
-                .iter()
-                .map(|s| match s {
-                    Self::Shared(s) => s.clone(),
-                    Self::Public(_) => unreachable!(),
-                })
-                .collect();
-            let nshares = S::batch_mul(sshares, oshares, &mut DummyFieldTripleSource::default());
-            for (self_, new) in selfs.iter_mut().zip(nshares.into_iter()) {
-                *self_ = Self::Shared(new);
-            }
-        } else {
-            for (a, b) in ark_std::cfg_iter_mut!(selfs).zip(others.iter()) {
-                *a *= b;
-            }
-        }
+        Self::batch_product_in_place_with(selfs, others, &mut MpcMulScratch::default())
     }
     fn batch_division_in_place(selfs: &mut [Self], others: &[Self]) {
         let selfs_shared = selfs[0].is_shared();
@@ -402,7 +666,8 @@ impl<F: PrimeField, S: FieldShare<F>> Field for MpcField<F, S> {
             others.iter().all(|s| s.is_shared() == others_shared),
             "others heterogenously shared!"
         );
-        if selfs_shared && others_shared {
+        let ps = Self::joint_provenances(selfs, others);
+        if selfs_shared && others_shared && !Net::is_single_party() {
             let sshares = selfs
                 .iter()
                 .map(|s| match s {
@@ -421,9 +686,18 @@ impl<F: PrimeField, S: FieldShare<F>> Field for MpcField<F, S> {
             for (self_, new) in selfs.iter_mut().zip(nshares.into_iter()) {
                 *self_ = Self::Shared(new);
             }
+        } else if selfs_shared && others_shared {
+            for (a, b) in selfs.iter_mut().zip(others) {
+                *a /= b;
+            }
         } else {
-            for (a, b) in ark_std::cfg_iter_mut!(selfs).zip(others.iter()) {
-                *a *= b;
+            ark_std::cfg_iter_mut!(selfs)
+                .zip(ark_std::cfg_iter!(others))
+                .for_each(|(a, b)| *a /= b);
+        }
+        if let Some(ps) = ps {
+            for (a, p) in selfs.iter().zip(ps) {
+                a.set_provenance(p);
             }
         }
     }
@@ -433,7 +707,7 @@ impl<F: PrimeField, S: FieldShare<F>> Field for MpcField<F, S> {
             selfs.iter().all(|s| s.is_shared() == selfs_shared),
             "Selfs heterogenously shared!"
         );
-        if selfs_shared {
+        if selfs_shared && !Net::is_single_party() {
             let sshares = selfs
                 .iter()
                 .map(|s| match s {
@@ -497,6 +771,12 @@ impl<F: PrimeField, S: FieldShare<F>> Field for MpcField<F, S> {
     }
 }
 
+/// A twiddle factor is public, so multiplying by one scales each share locally, which costs a few
+/// plain multiplications (more with a MAC to scale too).
+impl<F: PrimeField, S: FieldShare<F>> DomainCoeff<MpcField<F, S>> for MpcField<F, S> {
+    const MUL_COST: usize = 4;
+}
+
 impl<F: PrimeField, S: FieldShare<F>> FftField for MpcField<F, S> {
     type FftParams = F::FftParams;
     #[inline]
@@ -529,18 +809,23 @@ impl<F: PrimeField, S: FieldShare<F>> PrimeField for MpcField<F, S> {
     }
 }
 
-impl<F: PrimeField, S: FieldShare<F>> SquareRootField for MpcField<F, S> {
+/// For a shared element, by [batch_legendre] and [batch_sqrt], which mask it before opening; every
+/// party must call these.
+impl<F: PrimeField + SquareRootField, S: FieldShare<F>> SquareRootField for MpcField<F, S> {
     #[inline]
-    fn legendre(&self) -> ark_ff::LegendreSymbol {
-        todo!()
+    fn legendre(&self) -> LegendreSymbol {
+        batch_legendre(std::slice::from_ref(self)).remove(0)
     }
     #[inline]
     fn sqrt(&self) -> Option<Self> {
-        todo!()
+        batch_sqrt(std::slice::from_ref(self)).remove(0)
     }
     #[inline]
     fn sqrt_in_place(&mut self) -> Option<&mut Self> {
-        todo!()
+        self.sqrt().map(|r| {
+            *self = r;
+            self
+        })
     }
 }
 
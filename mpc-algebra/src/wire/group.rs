@@ -6,6 +6,7 @@ use zeroize::Zeroize;
 use ark_ec::group::Group;
 use ark_ff::bytes::{FromBytes, ToBytes};
 use ark_ff::prelude::*;
+use ark_poly::domain::DomainCoeff;
 use ark_serialize::{
     CanonicalDeserialize, CanonicalDeserializeWithFlags, CanonicalSerialize,
     CanonicalSerializeWithFlags, Flags, SerializationError,
@@ -18,11 +19,13 @@ use std::iter::Sum;
 use std::marker::PhantomData;
 use std::ops::*;
 
+use super::super::share::field::SmallScalar;
 use super::super::share::group::GroupShare;
 use super::super::share::BeaverSource;
 use super::field::MpcField;
 use mpc_net::{MpcNet, MpcMultiNet as Net};
-use crate::Reveal;
+use super::guarded_open;
+use crate::{cost, leak, Reveal};
 
 #[derive(Clone, Copy, Hash, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum MpcGroup<G: Group, S: GroupShare<G>> {
@@ -44,6 +47,7 @@ impl<T: Group, S: GroupShare<T>> BeaverSource<S, S::FieldShare, S>
 {
     #[inline]
     fn triple(&mut self) -> (S, S::FieldShare, S) {
+        cost::drew_triples(1);
         (
             S::from_add_shared(T::zero()),
             <S::FieldShare as Reveal>::from_add_shared(if Net::am_king() {
@@ -77,9 +81,10 @@ impl_ref_ops!(Sub, SubAssign, sub, sub_assign, Group, GroupShare, MpcGroup);
 impl<T: Group, S: GroupShare<T>> MpcWire for MpcGroup<T, S> {
     #[inline]
     fn publicize(&mut self) {
+        let p = self.provenance();
         match self {
             MpcGroup::Shared(s) => {
-                *self = MpcGroup::Public(s.reveal());
+                *self = MpcGroup::Public(guarded_open(vec![p], || s.reveal()));
             }
             _ => {}
         }
@@ -106,8 +111,9 @@ impl<T: Group, S: GroupShare<T>> Reveal for MpcGroup<T, S> {
     type Base = T;
     #[inline]
     fn reveal(self) -> Self::Base {
+        let p = self.provenance();
         let result = match self {
-            Self::Shared(s) => s.reveal(),
+            Self::Shared(s) => guarded_open(vec![p], || s.reveal()),
             Self::Public(s) => s,
         };
         super::macros::check_eq(result.clone());
@@ -119,7 +125,9 @@ impl<T: Group, S: GroupShare<T>> Reveal for MpcGroup<T, S> {
     }
     #[inline]
     fn from_add_shared(b: Self::Base) -> Self {
-        Self::Shared(S::from_add_shared(b))
+        let s = S::from_add_shared(b);
+        leak::record_input(&s, None);
+        Self::Shared(s)
     }
     #[inline]
     fn unwrap_as_public(self) -> Self::Base {
@@ -130,11 +138,19 @@ impl<T: Group, S: GroupShare<T>> Reveal for MpcGroup<T, S> {
     }
     #[inline]
     fn king_share<R: Rng>(f: Self::Base, rng: &mut R) -> Self {
-        Self::Shared(S::king_share(f, rng))
+        let s = S::king_share(f, rng);
+        leak::record_input(&s, Some(0));
+        Self::Shared(s)
     }
     #[inline]
     fn king_share_batch<R: Rng>(f: Vec<Self::Base>, rng: &mut R) -> Vec<Self> {
-        S::king_share_batch(f, rng).into_iter().map(Self::Shared).collect()
+        S::king_share_batch(f, rng)
+            .into_iter()
+            .map(|s| {
+                leak::record_input(&s, Some(0));
+                Self::Shared(s)
+            })
+            .collect()
     }
     fn init_protocol() {
         S::init_protocol()
@@ -163,6 +179,14 @@ impl<'a, T: Group, S: GroupShare<T>> Mul<&'a MpcField<T::ScalarField, S::FieldSh
         self
     }
 }
+/// Multiplying by a field element is a scalar multiplication, of thousands of plain field
+/// multiplications.
+impl<T: Group, S: GroupShare<T>> DomainCoeff<MpcField<T::ScalarField, S::FieldShare>>
+    for MpcGroup<T, S>
+{
+    const MUL_COST: usize = 4096;
+}
+
 impl<T: Group, S: GroupShare<T>> MulAssign<MpcField<T::ScalarField, S::FieldShare>>
     for MpcGroup<T, S>
 {
@@ -176,13 +200,22 @@ impl<'a, T: Group, S: GroupShare<T>> MulAssign<&'a MpcField<T::ScalarField, S::F
 {
     #[inline]
     fn mul_assign(&mut self, other: &MpcField<T::ScalarField, S::FieldShare>) {
+        let p = leak::join(self.provenance(), other.provenance());
         match self {
             // for some reason, a two-stage match (rather than a tuple match) avoids moving
             // self
             MpcGroup::Public(x) => match other {
-                MpcField::Public(y) => {
-                    *x *= *y;
-                }
+                MpcField::Public(y) => match SmallScalar::of(y) {
+                    Some(SmallScalar::Zero) => *x = T::zero(),
+                    Some(SmallScalar::One) => {}
+                    Some(SmallScalar::MinusOne) => *x = -*x,
+                    Some(SmallScalar::Pow2(k)) => {
+                        for _ in 0..k {
+                            x.double_in_place();
+                        }
+                    }
+                    _ => *x *= *y,
+                },
                 MpcField::Shared(y) => {
                     let t = MpcGroup::Shared(S::scale_pub_group(*x, &y));
                     *self = t;
@@ -190,14 +223,26 @@ impl<'a, T: Group, S: GroupShare<T>> MulAssign<&'a MpcField<T::ScalarField, S::F
             },
             MpcGroup::Shared(x) => match other {
                 MpcField::Public(y) => {
-                    x.scale_pub_scalar(y);
+                    // group scalar multiplication is expensive, so small scalars are well worth
+                    // special-casing
+                    match SmallScalar::of(y) {
+                        Some(small) => x.scale_small(small),
+                        None => x.scale_pub_scalar(y),
+                    };
                 }
                 MpcField::Shared(y) => {
-                    let t = x.scale(*y, &mut DummyGroupTripleSource::default());
+                    let t = if Net::is_single_party() {
+                        let mut t = x.unwrap_as_public();
+                        t *= y.unwrap_as_public();
+                        S::from_public(t)
+                    } else {
+                        x.scale(*y, &mut DummyGroupTripleSource::default())
+                    };
                     *x = t;
                 }
             },
         }
+        self.set_provenance(p);
     }
 }
 
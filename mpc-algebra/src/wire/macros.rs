@@ -52,6 +52,25 @@ macro_rules! impl_basics_2 {
             pub fn from_public(t: T) -> Self {
                 Self::new(t, false)
             }
+            /// Where this value came from, if the [leak](crate::leak) analysis is on and knows.
+            ///
+            /// Values whose share is zero here are untracked, since so many are.
+            #[inline]
+            pub fn provenance(&self) -> Option<crate::leak::Provenance> {
+                match self {
+                    Self::Shared(s) if !s.unwrap_as_public().is_zero() => crate::leak::lookup(s),
+                    _ => None,
+                }
+            }
+            #[inline]
+            pub(crate) fn set_provenance(&self, p: Option<crate::leak::Provenance>) {
+                match (self, p) {
+                    (Self::Shared(s), Some(p)) if !s.unwrap_as_public().is_zero() => {
+                        crate::leak::record(s, p)
+                    }
+                    _ => {}
+                }
+            }
             #[inline]
             pub fn map<TT: $bound, SS: $share<TT>, FT: Fn(T) -> TT, FS: Fn(S) -> SS>(
                 self,
@@ -80,9 +99,10 @@ macro_rules! impl_basics_2 {
                 }
             }
         }
+        // a public value, as written above
         impl<T: $bound, S: $share<T>> FromBytes for $wrap<T, S> {
-            fn read<R: Read>(_reader: R) -> io::Result<Self> {
-                unimplemented!("read")
+            fn read<R: Read>(reader: R) -> io::Result<Self> {
+                T::read(reader).map(Self::Public)
             }
         }
         impl<T: $bound, S: $share<T>> CanonicalSerialize for $wrap<T, S> {
@@ -98,6 +118,21 @@ macro_rules! impl_basics_2 {
                     Self::Shared(_) => unimplemented!("serialized_size share: {}", self),
                 }
             }
+            fn serialize_uncompressed<W: Write>(
+                &self,
+                writer: W,
+            ) -> Result<(), SerializationError> {
+                match self {
+                    Self::Public(v) => v.serialize_uncompressed(writer),
+                    Self::Shared(_) => unimplemented!("serialize share: {}", self),
+                }
+            }
+            fn uncompressed_size(&self) -> usize {
+                match self {
+                    Self::Public(v) => v.uncompressed_size(),
+                    Self::Shared(_) => unimplemented!("serialized_size share: {}", self),
+                }
+            }
         }
         // NB: CanonicalSerializeWithFlags is unimplemented for Group.
         impl<T: $bound, S: $share<T>> CanonicalSerializeWithFlags for $wrap<T, S> {
@@ -127,9 +162,12 @@ macro_rules! impl_basics_2 {
         }
         impl<T: $bound, S: $share<T>> UniformRand for $wrap<T, S> {
             fn rand<R: Rng + ?Sized>(rng: &mut R) -> Self {
-                Self::Shared(<S as UniformRand>::rand(rng))
+                let s = <S as UniformRand>::rand(rng);
+                crate::leak::record_random(&s);
+                Self::Shared(s)
             }
         }
+        // every party must draw from the same rng, such as a SharedRng
         impl<T: $bound, S: $share<T>> PubUniformRand for $wrap<T, S> {
             fn pub_rand<R: Rng + ?Sized>(rng: &mut R) -> Self {
                 Self::Public(<T as PubUniformRand>::pub_rand(rng))
@@ -150,6 +188,7 @@ macro_rules! impl_basics_2 {
         impl<'a, T: $bound, S: $share<T>> AddAssign<&'a $wrap<T, S>> for $wrap<T, S> {
             #[inline]
             fn add_assign(&mut self, other: &Self) {
+                let p = crate::leak::join(self.provenance(), other.provenance());
                 match self {
                     // for some reason, a two-stage match (rather than a tuple match) avoids moving
                     // self
@@ -172,6 +211,7 @@ macro_rules! impl_basics_2 {
                         }
                     },
                 }
+                self.set_provenance(p);
             }
         }
         impl<T: $bound, S: $share<T>> Sum for $wrap<T, S> {
@@ -190,18 +230,22 @@ macro_rules! impl_basics_2 {
             type Output = Self;
             #[inline]
             fn neg(self) -> Self::Output {
-                match self {
+                let p = self.provenance();
+                let out = match self {
                     $wrap::Public(x) => $wrap::Public(-x),
                     $wrap::Shared(mut x) => $wrap::Shared({
                         x.neg();
                         x
                     }),
-                }
+                };
+                out.set_provenance(p);
+                out
             }
         }
         impl<'a, T: $bound, S: $share<T>> SubAssign<&'a $wrap<T, S>> for $wrap<T, S> {
             #[inline]
             fn sub_assign(&mut self, other: &Self) {
+                let p = crate::leak::join(self.provenance(), other.provenance());
                 match self {
                     // for some reason, a two-stage match (rather than a tuple match) avoids moving
                     // self
@@ -224,6 +268,7 @@ macro_rules! impl_basics_2 {
                         }
                     },
                 }
+                self.set_provenance(p);
             }
         }
         impl<T: $bound, S: $share<T>> Zero for $wrap<T, S> {
@@ -245,6 +290,11 @@ macro_rules! impl_basics_2 {
         impl<T: $bound, S: $share<T>> Zeroize for $wrap<T, S> {
             #[inline]
             fn zeroize(&mut self) {
+                // wipe the value in place, before the variant changes
+                match self {
+                    $wrap::Public(x) => x.zeroize(),
+                    $wrap::Shared(s) => s.zeroize(),
+                }
                 *self = $wrap::Public(T::zero());
             }
         }
@@ -1,7 +1,31 @@
+use crate::leak::{self, Provenance};
+use crate::{acl, budget, cost};
+
 pub mod macros;
 pub mod field;
 pub use field::*;
 pub mod group;
 pub use group::*;
+pub mod edwards;
+pub use edwards::*;
 pub mod pairing;
 pub use pairing::*;
+pub mod typed;
+pub use typed::*;
+pub mod persist;
+pub use persist::*;
+pub mod constraint_field;
+pub use constraint_field::*;
+
+/// Open shared values with `open`, once they pass the checks every opening must: the leak check,
+/// on each value's provenance, then the access policy and the reveal budget, which count them.
+pub(crate) fn guarded_open<T>(provenances: Vec<Option<Provenance>>, open: impl FnOnce() -> T) -> T {
+    let n = provenances.len() as u64;
+    for p in provenances {
+        leak::check_open(p);
+    }
+    acl::authorize(n);
+    budget::charge(n);
+    cost::opened(n);
+    open()
+}
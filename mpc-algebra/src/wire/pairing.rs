@@ -28,7 +28,9 @@ use super::super::share::pairing::{AffProjShare, PairingShare};
 use super::super::share::BeaverSource;
 use super::field::MpcField;
 use super::group::MpcGroup;
-use crate::Reveal;
+use crate::dedup::Compacted;
+use crate::fixed_base::{FixedBaseMsm, FixedBaseTable};
+use crate::{budget, cost, Reveal};
 
 #[derive(Derivative)]
 #[derivative(Default(bound = ""), Clone(bound = ""), Copy(bound = ""))]
@@ -48,6 +50,7 @@ impl<E: PairingEngine, S: PairingShare<E>>
         MpcG2Projective<E, S>,
         MpcExtField<E::Fqk, S::FqkShare>,
     ) {
+        cost::drew_triples(1);
         let g1 = E::G1Projective::zero();
         let g2 = E::G2Projective::zero();
         (
@@ -204,10 +207,8 @@ impl<E: PairingEngine, PS: PairingShare<E>> PairingEngine for MpcPairingEngine<E
             let source = &mut DummyPairingTripleSource::default();
             // x * y = z
             let (x, y, z) = source.triple();
-            // x + a
-            let xa = (a + x).reveal();
-            // y + b
-            let yb = (b + y).reveal();
+            // x + a and y + b are masked, so opening them costs no budget
+            let (xa, yb) = budget::exempt(|| ((a + x).reveal(), (b + y).reveal()));
             let xayb: MpcExtField<E::Fqk, PS::FqkShare> =
                 MpcExtField::wrap(MpcField::Public(E::pairing(xa, yb)));
             let xay: MpcExtField<E::Fqk, PS::FqkShare> = MpcExtField::wrap(MpcField::Shared(
@@ -223,12 +224,99 @@ impl<E: PairingEngine, PS: PairingShare<E>> PairingEngine for MpcPairingEngine<E
                 )),
             ));
             z / xay / xyb * xayb
+        } else if let MpcGroup::Shared(sa) = a.val {
+            // linear in the shared side, so each party pairs its share: nothing is opened
+            let b: E::G2Affine = b.reveal().into();
+            MpcExtField::wrap(MpcField::Shared(PS::pairing_sh_g1(
+                PS::G1::sh_proj_to_aff(sa),
+                &b,
+            )))
+        } else if let MpcGroup::Shared(sb) = b.val {
+            let a: E::G1Affine = a.reveal().into();
+            MpcExtField::wrap(MpcField::Shared(PS::pairing_sh_g2(
+                &a,
+                PS::G2::sh_proj_to_aff(sb),
+            )))
         } else {
             MpcExtField::wrap(MpcField::Public(E::pairing(a.reveal(), b.reveal())))
         }
     }
 }
 
+impl<E: PairingEngine, PS: PairingShare<E>> MpcPairingEngine<E, PS> {
+    /// The product of the pairings of `pairs`, either side of which may be shared.
+    ///
+    /// The public pairs share one final exponentiation; a pair with one shared side is paired
+    /// locally; only a pair with both sides shared costs a round.
+    pub fn product_of_shared_pairings(
+        pairs: &[(MpcG1Affine<E, PS>, MpcG2Affine<E, PS>)],
+    ) -> MpcExtField<E::Fqk, PS::FqkShare> {
+        let (public, shared): (Vec<(_, _)>, Vec<(_, _)>) = pairs
+            .iter()
+            .partition(|(p, q)| !p.is_shared() && !q.is_shared());
+        let prepared: Vec<(E::G1Prepared, E::G2Prepared)> = public
+            .into_iter()
+            .map(|(p, q)| (p.reveal().into(), q.reveal().into()))
+            .collect();
+        let public = MpcExtField::wrap(MpcField::Public(E::product_of_pairings(&prepared)));
+        shared
+            .into_iter()
+            .fold(public, |acc, (p, q)| acc * Self::pairing(p, q))
+    }
+
+    /// `g` raised to `exp`, either or both of which may be shared.
+    ///
+    /// With one side shared, each party exponentiates its share: nothing is sent. With both
+    /// shared, a triple `(x, y, x^y)`, for `x` random in `GT` and `y` random, costs a
+    /// multiplication of shared scalars, and then `g x` and `exp + y` are opened, which are
+    /// masked.
+    pub fn pow_gt<R: Rng>(
+        g: MpcExtField<E::Fqk, PS::FqkShare>,
+        exp: MpcField<E::Fr, PS::FrShare>,
+        rng: &mut R,
+    ) -> MpcExtField<E::Fqk, PS::FqkShare> {
+        match (g.val, exp) {
+            (MpcField::Public(g), MpcField::Public(e)) => {
+                MpcExtField::from_public(g.pow(e.into_repr()))
+            }
+            (MpcField::Shared(_), MpcField::Public(e)) => g.pow(e.into_repr()),
+            (MpcField::Public(g), MpcField::Shared(e)) => {
+                MpcExtField::wrap(MpcField::Shared(PS::pow_gt_sh(&g, e)))
+            }
+            (MpcField::Shared(_), MpcField::Shared(_)) => {
+                let generator = E::pairing(
+                    E::G1Projective::prime_subgroup_generator(),
+                    E::G2Projective::prime_subgroup_generator(),
+                );
+                let (x, y) = (MpcField::rand(rng), MpcField::rand(rng));
+                let xy = x * y;
+                let generator = MpcExtField::from_public(generator);
+                let x = Self::pow_gt(generator, x, rng);
+                let z = Self::pow_gt(generator, xy, rng);
+                // g = d / x and exp = e - y, so g^exp = d^e / d^y / x^e * x^y
+                let (d, e) = budget::exempt(|| ((g * x).reveal(), (exp + y).reveal()));
+                let d_y = Self::pow_gt(MpcExtField::from_public(d), y, rng);
+                MpcExtField::from_public(d.pow(e.into_repr())) / d_y / x.pow(e.into_repr()) * z
+            }
+        }
+    }
+
+    /// Whether `a` and `b`, either of which may be shared, are equal. Only the verdict is
+    /// opened: `(a / b)^r`, for a random shared `r`, which is one if they are equal, and uniformly
+    /// random in `GT` otherwise.
+    pub fn gt_eq<R: Rng>(
+        a: MpcExtField<E::Fqk, PS::FqkShare>,
+        b: MpcExtField<E::Fqk, PS::FqkShare>,
+        rng: &mut R,
+    ) -> bool {
+        if !a.is_shared() && !b.is_shared() {
+            return a == b;
+        }
+        let r = MpcField::rand(rng);
+        Self::pow_gt(a / b, r, rng).reveal().is_one()
+    }
+}
+
 macro_rules! impl_pairing_mpc_wrapper {
     ($wrapped:ident, $bound1:ident, $bound2:ident, $base:ident, $share:ident, $wrap:ident) => {
         impl<E: $bound1, PS: $bound2<E>> Display for $wrap<E, PS> {
@@ -744,14 +832,43 @@ macro_rules! impl_aff_proj {
                 todo!("AffineCurve::mul_by_cofactor_inv")
             }
             fn multi_scalar_mul(bases: &[Self], scalars: &[Self::ScalarField]) -> Self::Projective {
+                let _mem = mpc_net::mem::phase("msm");
+                if bases.iter().any(|b| b.is_shared()) {
+                    // shared bases (such as commitments) and public scalars: scale the shares
+                    let pub_scalars = match MpcField::all_public_or_shared(scalars.iter().cloned()) {
+                        Ok(s) => s,
+                        Err(_) => panic!("MSM of shared bases by shared scalars"),
+                    };
+                    let shares: Vec<PS::$share_aff> = bases
+                        .iter()
+                        .map(|b| match b.val {
+                            MpcGroup::Shared(s) => s,
+                            MpcGroup::Public(g) => <PS::$share_aff as Reveal>::from_public(g),
+                        })
+                        .collect();
+                    return $w_pro {
+                        val: MpcGroup::Shared(PS::$g_name::sh_aff_to_proj(
+                            <PS::$share_aff as GroupShare<E::$aff>>::msm_pub_scalar_shared_base(
+                                &shares,
+                                &pub_scalars,
+                            ),
+                        )),
+                    };
+                }
                 let b = {
-                    assert!(bases.iter().all(|b| !b.is_shared()));
-                    let scalars_shared = scalars.first().map(|s| s.is_shared()).unwrap_or(true);
-                    assert!(scalars.iter().all(|b| scalars_shared == b.is_shared()));
+                    // a witness can hold public wires, such as those computed from public
+                    // inputs, among its shared ones: treat them as shared
+                    let scalars_shared = scalars.iter().any(|s| s.is_shared());
+                    let scalars = scalars.iter().map(|s| match s {
+                        MpcField::Public(x) if scalars_shared => {
+                            MpcField::Shared(<PS::FrShare as Reveal>::from_public(*x))
+                        }
+                        s => *s,
+                    });
                     let bases =
                         MpcGroup::all_public_or_shared(bases.into_iter().map(|i| i.val.clone()))
                             .unwrap();
-                    match MpcField::all_public_or_shared(scalars.into_iter().cloned()) {
+                    match MpcField::all_public_or_shared(scalars) {
                         Ok(pub_scalars) => {
                             let t = start_timer!(|| "MSM inner");
                             let r = $w_pro {
@@ -781,6 +898,14 @@ macro_rules! impl_aff_proj {
                         }
                         Err(priv_scalars) => {
                             let t = start_timer!(|| "MSM inner");
+                            // repeated wires: sum their bases, and scale by each share once
+                            let n = std::cmp::min(bases.len(), priv_scalars.len());
+                            let compacted = Compacted::by_value(&priv_scalars[..n]);
+                            let (bases, priv_scalars) = if compacted.saves_at_least(8) {
+                                (compacted.sum_bases(&bases[..n]), compacted.unique)
+                            } else {
+                                (bases, priv_scalars)
+                            };
                             let r = $w_pro {
                                 val: MpcGroup::Shared(PS::$g_name::sh_aff_to_proj(
                                     <PS::$share_aff as GroupShare<E::$aff>>::multi_scale_pub_group(
@@ -811,6 +936,30 @@ macro_rules! impl_aff_proj {
                 (*self * other.into()).into()
             }
         }
+        impl<E: PairingEngine, PS: PairingShare<E>> FixedBaseMsm for $w_aff<E, PS> {
+            type Table = FixedBaseTable<E::$aff>;
+            fn fixed_base_table(bases: &[Self], width: usize) -> Self::Table {
+                let bases = MpcGroup::all_public_or_shared(bases.iter().map(|b| b.val.clone()))
+                    .unwrap_or_else(|_| panic!("fixed-base tables need public bases"));
+                FixedBaseTable::new(&bases, width)
+            }
+            fn fixed_base_msm(table: &Self::Table, scalars: &[Self::ScalarField]) -> Self::Projective {
+                let _mem = mpc_net::mem::phase("msm");
+                let val = match MpcField::all_public_or_shared(scalars.iter().cloned()) {
+                    // like multi_scalar_mul, give a (trivially) shared result
+                    Ok(pub_scalars) => <PS::$share_proj as Reveal>::from_public(table.msm(&pub_scalars)),
+                    Err(priv_scalars) => {
+                        <PS::$share_proj as GroupShare<E::$pro>>::multi_scale_pub_group_by(
+                            &priv_scalars,
+                            |s| table.msm(s),
+                        )
+                    }
+                };
+                $w_pro {
+                    val: MpcGroup::Shared(val),
+                }
+            }
+        }
         impl<E: PairingEngine, PS: PairingShare<E>> ProjectiveCurve for $w_pro<E, PS> {
             type ScalarField = MpcField<E::Fr, PS::FrShare>;
             const COFACTOR: &'static [u64] = E::$aff::COFACTOR;
@@ -820,11 +969,36 @@ macro_rules! impl_aff_proj {
             fn prime_subgroup_generator() -> Self {
                 Self::from_public(E::$pro::prime_subgroup_generator())
             }
-            fn batch_normalization(_elems: &mut [Self]) {
-                //TODO: wrong?
+            fn batch_normalization(elems: &mut [Self]) {
+                // shared points are normalized share by share, with no communication, since each
+                // party's shares are points of its own
+                let (mut public, mut shared) = (Vec::new(), Vec::new());
+                for e in elems.iter() {
+                    match &e.val {
+                        MpcGroup::Public(g) => public.push(*g),
+                        MpcGroup::Shared(s) => shared.push(*s),
+                    }
+                }
+                E::$pro::batch_normalization(&mut public);
+                let shared = PS::$g_name::sh_batch_proj_to_aff(&shared);
+                let (mut public, mut shared) = (public.into_iter(), shared.into_iter());
+                for e in elems.iter_mut() {
+                    let p = e.val.provenance();
+                    e.val = match e.val {
+                        MpcGroup::Public(_) => MpcGroup::Public(public.next().unwrap()),
+                        MpcGroup::Shared(_) => MpcGroup::Shared(PS::$g_name::sh_aff_to_proj(
+                            shared.next().unwrap(),
+                        )),
+                    };
+                    e.val.set_provenance(p);
+                }
             }
+            /// Whether this party's share, if shared, is normalized.
             fn is_normalized(&self) -> bool {
-                todo!("ProjectiveCurve::is_normalized")
+                match &self.val {
+                    MpcGroup::Public(g) => g.is_normalized(),
+                    MpcGroup::Shared(s) => s.unwrap_as_public().is_normalized(),
+                }
             }
             fn double_in_place(&mut self) -> &mut Self {
                 self.val.double_in_place();
@@ -0,0 +1,131 @@
+//! Writing MPC values, shares and all, to disk and reading them back.
+//!
+//! The wrappers' canonical serialization is only for public values, which it writes exactly as
+//! their plain counterparts (so that a transcript absorbs them the same way). Persisted values
+//! may be either, so [Persist] writes a tag first: public, or this party's share. [Persisted]
+//! adapts it to [CanonicalSerialize], to put such values in an
+//! [envelope](crate::share::envelope).
+use ark_ec::group::Group;
+use ark_ec::PairingEngine;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+
+use std::io::{Read, Write};
+
+use super::field::MpcField;
+use super::group::MpcGroup;
+use super::pairing::{MpcG1Projective, MpcG2Projective};
+use crate::share::field::FieldShare;
+use crate::share::group::GroupShare;
+use crate::share::pairing::PairingShare;
+
+const PUBLIC: u8 = 0;
+const SHARED: u8 = 1;
+
+/// A value that can be written to disk, whether public or shared, and read back by the same
+/// party.
+pub trait Persist: Sized {
+    fn persist<W: Write>(&self, w: W) -> Result<(), SerializationError>;
+    fn restore<R: Read>(r: R) -> Result<Self, SerializationError>;
+}
+
+impl<F: Field, S: FieldShare<F>> Persist for MpcField<F, S> {
+    fn persist<W: Write>(&self, mut w: W) -> Result<(), SerializationError> {
+        match self {
+            MpcField::Public(x) => {
+                PUBLIC.serialize(&mut w)?;
+                x.serialize(w)
+            }
+            MpcField::Shared(s) => {
+                SHARED.serialize(&mut w)?;
+                s.serialize(w)
+            }
+        }
+    }
+    fn restore<R: Read>(mut r: R) -> Result<Self, SerializationError> {
+        match u8::deserialize(&mut r)? {
+            PUBLIC => Ok(MpcField::Public(F::deserialize(r)?)),
+            SHARED => Ok(MpcField::Shared(S::deserialize(r)?)),
+            _ => Err(SerializationError::InvalidData),
+        }
+    }
+}
+
+impl<G: Group, S: GroupShare<G>> Persist for MpcGroup<G, S> {
+    fn persist<W: Write>(&self, mut w: W) -> Result<(), SerializationError> {
+        match self {
+            MpcGroup::Public(x) => {
+                PUBLIC.serialize(&mut w)?;
+                x.serialize(w)
+            }
+            MpcGroup::Shared(s) => {
+                SHARED.serialize(&mut w)?;
+                s.serialize(w)
+            }
+        }
+    }
+    fn restore<R: Read>(mut r: R) -> Result<Self, SerializationError> {
+        match u8::deserialize(&mut r)? {
+            PUBLIC => Ok(MpcGroup::Public(G::deserialize(r)?)),
+            SHARED => Ok(MpcGroup::Shared(S::deserialize(r)?)),
+            _ => Err(SerializationError::InvalidData),
+        }
+    }
+}
+
+impl<E: PairingEngine, PS: PairingShare<E>> Persist for MpcG1Projective<E, PS> {
+    fn persist<W: Write>(&self, w: W) -> Result<(), SerializationError> {
+        self.val.persist(w)
+    }
+    fn restore<R: Read>(r: R) -> Result<Self, SerializationError> {
+        Ok(Self {
+            val: Persist::restore(r)?,
+        })
+    }
+}
+
+impl<E: PairingEngine, PS: PairingShare<E>> Persist for MpcG2Projective<E, PS> {
+    fn persist<W: Write>(&self, w: W) -> Result<(), SerializationError> {
+        self.val.persist(w)
+    }
+    fn restore<R: Read>(r: R) -> Result<Self, SerializationError> {
+        Ok(Self {
+            val: Persist::restore(r)?,
+        })
+    }
+}
+
+impl<T: Persist> Persist for Vec<T> {
+    fn persist<W: Write>(&self, mut w: W) -> Result<(), SerializationError> {
+        (self.len() as u64).serialize(&mut w)?;
+        for x in self {
+            x.persist(&mut w)?;
+        }
+        Ok(())
+    }
+    fn restore<R: Read>(mut r: R) -> Result<Self, SerializationError> {
+        let len = u64::deserialize(&mut r)?;
+        (0..len).map(|_| T::restore(&mut r)).collect()
+    }
+}
+
+/// A [Persist] value, serialized as [Persist] writes it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Persisted<T>(pub T);
+
+impl<T: Persist> CanonicalSerialize for Persisted<T> {
+    fn serialize<W: Write>(&self, w: W) -> Result<(), SerializationError> {
+        self.0.persist(w)
+    }
+    fn serialized_size(&self) -> usize {
+        let mut bytes = Vec::new();
+        self.0.persist(&mut bytes).unwrap();
+        bytes.len()
+    }
+}
+
+impl<T: Persist> CanonicalDeserialize for Persisted<T> {
+    fn deserialize<R: Read>(r: R) -> Result<Self, SerializationError> {
+        Ok(Persisted(T::restore(r)?))
+    }
+}
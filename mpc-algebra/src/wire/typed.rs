@@ -0,0 +1,228 @@
+//! Field values whose sharing is part of their type.
+//!
+//! An [MpcField] may be public or shared, and quietly becomes one or the other: a public constant
+//! can stand where a secret was meant, and [Reveal::reveal] opens whatever it is given. [Shared]
+//! and [Public] make the distinction static. Arithmetic between them is free (it never reveals
+//! anything, and a result that involves a shared value is shared), but crossing over takes a
+//! deliberate call: [Shared::open] to publish a secret, [Shared::from_public] to treat a public
+//! value as a secret one, and [Shared::into_mpc] or [Public::into_mpc] to hand either to code
+//! written for [MpcField].
+use ark_ff::{Field, UniformRand};
+use derivative::Derivative;
+use rand::Rng;
+
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use std::marker::PhantomData;
+use std::ops::*;
+
+use super::field::MpcField;
+use crate::share::field::FieldShare;
+use crate::Reveal;
+
+/// A field value that is secret-shared.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = "")
+)]
+pub struct Shared<F: Field, S: FieldShare<F>> {
+    share: S,
+    _field: PhantomData<F>,
+}
+
+/// A field value that every party knows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Public<F: Field>(F);
+
+impl<F: Field, S: FieldShare<F>> Shared<F, S> {
+    fn wrap(share: S) -> Self {
+        Self {
+            share,
+            _field: PhantomData,
+        }
+    }
+
+    /// Have party `owner` share `value`. The other parties' `value`s are ignored.
+    pub fn input<R: Rng>(owner: usize, value: F, rng: &mut R) -> Self {
+        Self::input_batch(owner, vec![value], rng).pop().unwrap()
+    }
+
+    /// Have party `owner` share `values`. The other parties' `values` are ignored, but must be
+    /// as many.
+    pub fn input_batch<R: Rng>(owner: usize, values: Vec<F>, rng: &mut R) -> Vec<Self> {
+        MpcField::<F, S>::party_share_batch(owner, values, rng)
+            .into_iter()
+            .map(|x| Self::try_from(x).ok().unwrap())
+            .collect()
+    }
+
+    /// The value of which `value` is this party's additive share.
+    pub fn from_add_shared(value: F) -> Self {
+        Self::try_from(MpcField::<F, S>::from_add_shared(value))
+            .ok()
+            .unwrap()
+    }
+
+    /// Treat a public value as a shared one. It stays known to everyone.
+    pub fn from_public(value: Public<F>) -> Self {
+        Self::wrap(S::from_public(value.0))
+    }
+
+    /// Open the value to every party. Every party must call this.
+    pub fn open(self) -> Public<F> {
+        Public(self.into_mpc().reveal())
+    }
+
+    /// Open many values at once. Every party must call this.
+    pub fn open_batch(values: Vec<Self>) -> Vec<Public<F>> {
+        MpcField::open_batch(values.into_iter().map(Self::into_mpc).collect())
+            .into_iter()
+            .map(Public)
+            .collect()
+    }
+
+    /// This party's share.
+    pub fn share(&self) -> &S {
+        &self.share
+    }
+
+    /// The same value, as an [MpcField].
+    pub fn into_mpc(self) -> MpcField<F, S> {
+        MpcField::Shared(self.share)
+    }
+}
+
+impl<F: Field> Public<F> {
+    pub fn new(value: F) -> Self {
+        Public(value)
+    }
+
+    pub fn value(self) -> F {
+        self.0
+    }
+
+    /// The same value, as an [MpcField].
+    pub fn into_mpc<S: FieldShare<F>>(self) -> MpcField<F, S> {
+        MpcField::Public(self.0)
+    }
+}
+
+/// Fails, returning the value, if it is public.
+impl<F: Field, S: FieldShare<F>> TryFrom<MpcField<F, S>> for Shared<F, S> {
+    type Error = F;
+    fn try_from(x: MpcField<F, S>) -> Result<Self, F> {
+        match x {
+            MpcField::Shared(s) => Ok(Self::wrap(s)),
+            MpcField::Public(f) => Err(f),
+        }
+    }
+}
+
+/// Fails, returning the share, if it is shared.
+impl<F: Field, S: FieldShare<F>> TryFrom<MpcField<F, S>> for Public<F> {
+    type Error = S;
+    fn try_from(x: MpcField<F, S>) -> Result<Self, S> {
+        match x {
+            MpcField::Public(f) => Ok(Public(f)),
+            MpcField::Shared(s) => Err(s),
+        }
+    }
+}
+
+impl<F: Field, S: FieldShare<F>> From<Shared<F, S>> for MpcField<F, S> {
+    fn from(x: Shared<F, S>) -> Self {
+        x.into_mpc()
+    }
+}
+
+impl<F: Field, S: FieldShare<F>> UniformRand for Shared<F, S> {
+    fn rand<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::try_from(MpcField::<F, S>::rand(rng)).ok().unwrap()
+    }
+}
+
+impl<F: Field, S: FieldShare<F>> Display for Shared<F, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (shared)", self.share)
+    }
+}
+
+impl<F: Field> Display for Public<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (public)", self.0)
+    }
+}
+
+impl<F: Field, S: FieldShare<F>> Neg for Shared<F, S> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::try_from(-self.into_mpc()).ok().unwrap()
+    }
+}
+
+impl<F: Field> Neg for Public<F> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Public(-self.0)
+    }
+}
+
+macro_rules! impl_typed_op {
+    ($op:ident, $opfn:ident, $assop:ident, $assopfn:ident) => {
+        impl<F: Field, S: FieldShare<F>> $op<Shared<F, S>> for Shared<F, S> {
+            type Output = Shared<F, S>;
+            fn $opfn(self, other: Shared<F, S>) -> Shared<F, S> {
+                Shared::try_from(self.into_mpc().$opfn(other.into_mpc()))
+                    .ok()
+                    .unwrap()
+            }
+        }
+        impl<F: Field, S: FieldShare<F>> $op<Public<F>> for Shared<F, S> {
+            type Output = Shared<F, S>;
+            fn $opfn(self, other: Public<F>) -> Shared<F, S> {
+                Shared::try_from(self.into_mpc().$opfn(other.into_mpc()))
+                    .ok()
+                    .unwrap()
+            }
+        }
+        impl<F: Field, S: FieldShare<F>> $op<Shared<F, S>> for Public<F> {
+            type Output = Shared<F, S>;
+            fn $opfn(self, other: Shared<F, S>) -> Shared<F, S> {
+                Shared::try_from(self.into_mpc::<S>().$opfn(other.into_mpc()))
+                    .ok()
+                    .unwrap()
+            }
+        }
+        impl<F: Field> $op<Public<F>> for Public<F> {
+            type Output = Public<F>;
+            fn $opfn(self, other: Public<F>) -> Public<F> {
+                Public(self.0.$opfn(other.0))
+            }
+        }
+        impl<F: Field, S: FieldShare<F>> $assop<Shared<F, S>> for Shared<F, S> {
+            fn $assopfn(&mut self, other: Shared<F, S>) {
+                *self = (*self).$opfn(other);
+            }
+        }
+        impl<F: Field, S: FieldShare<F>> $assop<Public<F>> for Shared<F, S> {
+            fn $assopfn(&mut self, other: Public<F>) {
+                *self = (*self).$opfn(other);
+            }
+        }
+        impl<F: Field> $assop<Public<F>> for Public<F> {
+            fn $assopfn(&mut self, other: Public<F>) {
+                self.0.$assopfn(other.0);
+            }
+        }
+    };
+}
+
+impl_typed_op!(Add, add, AddAssign, add_assign);
+impl_typed_op!(Sub, sub, SubAssign, sub_assign);
+impl_typed_op!(Mul, mul, MulAssign, mul_assign);
+impl_typed_op!(Div, div, DivAssign, div_assign);
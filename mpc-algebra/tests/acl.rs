@@ -0,0 +1,102 @@
+//! Approval of openings by an organization's policy key, with every party run in-process.
+use ark_bls12_377::Fr;
+use ark_ff::UniformRand;
+use mpc_algebra::acl::{self, AclError, PolicyKey, TokenPolicy};
+use mpc_algebra::budget;
+use mpc_algebra::honest_but_curious::MpcField;
+use mpc_algebra::Reveal;
+use mpc_test_utils::run_parties;
+use mpc_trait::MpcWire;
+
+fn shared_field(n: usize) -> Vec<MpcField<Fr>> {
+    let rng = &mut ark_std::test_rng();
+    (0..n)
+        .map(|_| MpcField::king_share(Fr::rand(rng), rng))
+        .collect()
+}
+
+/// A policy holding `tokens`, each a label and count, approved by the organization's key.
+fn policy(tokens: &[(&str, u64)]) -> TokenPolicy {
+    let rng = &mut ark_std::test_rng();
+    let key = PolicyKey::generate(rng);
+    let mut policy = TokenPolicy::new(key.public());
+    for (label, n) in tokens {
+        policy.present(&key.approve(label, *n, rng)).unwrap();
+    }
+    policy
+}
+
+#[test]
+fn approved_openings() {
+    let opened = run_parties(2, || {
+        acl::set_authorizer(Some(Box::new(policy(&[("proof", 3)]))));
+        let xs = shared_field(3);
+        let mut opened = acl::labelled("proof", || xs[..2].to_vec().reveal());
+        let mut last = xs[2];
+        acl::labelled("proof", || last.publicize());
+        opened.push(last.reveal());
+        // masked openings need no approval
+        budget::exempt(|| shared_field(4).reveal());
+        // and nor do public values
+        MpcField::<Fr>::from_public(Fr::from(1u64)).reveal();
+        acl::set_authorizer(None);
+        assert_eq!(shared_field(3).reveal(), opened);
+        opened
+    });
+    assert_eq!(opened[0], opened[1]);
+}
+
+#[test]
+#[should_panic(expected = "Unlabelled opening not authorized")]
+fn refuses_unlabelled() {
+    run_parties(2, || {
+        acl::set_authorizer(Some(Box::new(policy(&[("proof", 3)]))));
+        shared_field(1).reveal();
+    });
+}
+
+#[test]
+#[should_panic(
+    expected = "Opening of `proof` not authorized: 0 value(s) approved, but 1 requested"
+)]
+fn refuses_past_approval() {
+    run_parties(2, || {
+        acl::set_authorizer(Some(Box::new(policy(&[("proof", 2)]))));
+        acl::labelled("proof", || shared_field(3).reveal());
+    });
+}
+
+#[test]
+#[should_panic(expected = "Opening of `witness` not authorized: no approval token")]
+fn refuses_other_labels() {
+    run_parties(2, || {
+        acl::set_authorizer(Some(Box::new(policy(&[("proof", 2)]))));
+        acl::labelled("witness", || shared_field(1).reveal());
+    });
+}
+
+#[test]
+fn rejects_forged_tokens() {
+    let rng = &mut ark_std::test_rng();
+    let key = PolicyKey::generate(rng);
+    let other = PolicyKey::generate(rng);
+    let mut policy = TokenPolicy::new(key.public());
+    assert_eq!(
+        policy.present(&other.approve("proof", 1, rng)),
+        Err(AclError::BadSignature {
+            label: "proof".to_owned()
+        })
+    );
+    let mut token = key.approve("proof", 1, rng);
+    token.max_openings = 1000;
+    assert!(policy.present(&token).is_err());
+    token.max_openings = 1;
+    token.label = "witness".to_owned();
+    assert!(policy.present(&token).is_err());
+    assert_eq!(policy.remaining("proof"), 0);
+    assert_eq!(policy.remaining("witness"), 0);
+
+    policy.present(&key.approve("proof", 2, rng)).unwrap();
+    policy.present(&key.approve("proof", 3, rng)).unwrap();
+    assert_eq!(policy.remaining("proof"), 5);
+}
@@ -0,0 +1,76 @@
+//! Identifiable abort for SPDZ, with every party run in-process.
+use ark_bls12_377::{Fr, G1Projective};
+use ark_ec::ProjectiveCurve;
+use ark_ff::{One, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mpc_algebra::malicious_majority::{MpcField, MpcGroup};
+use mpc_algebra::share::blame::{self, MacCheckFailure};
+use mpc_algebra::{FieldShare, GroupShare, Reveal};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_test_utils::run_parties;
+
+use std::ops::Add;
+
+/// `share`, but with `delta` added to its value and not to its MAC.
+fn corrupt<S, T>(share: &S, delta: T) -> S
+where
+    S: CanonicalSerialize + CanonicalDeserialize,
+    T: CanonicalSerialize + CanonicalDeserialize + Add<Output = T>,
+{
+    let mut bytes = Vec::new();
+    share.serialize(&mut bytes).unwrap();
+    let (val, mac) = <(T, T)>::deserialize(&bytes[..]).unwrap();
+    let mut corrupted = Vec::new();
+    (val + delta, mac).serialize(&mut corrupted).unwrap();
+    S::deserialize(&corrupted[..]).unwrap()
+}
+
+#[test]
+fn opens_honestly() {
+    let ok = run_parties(3, || {
+        blame::set_identifiable_abort(true);
+        let rng = &mut ark_std::test_rng();
+        let (x, y) = (Fr::rand(rng), Fr::rand(rng));
+        let a = MpcField::<Fr>::king_share(x, rng);
+        let b = MpcField::<Fr>::king_share(y, rng);
+        let g_pub = G1Projective::rand(rng);
+        let g = MpcGroup::<G1Projective>::king_share(g_pub, rng);
+        let pubs: Vec<Fr> = (0..10).map(|_| Fr::rand(rng)).collect();
+        let shared = MpcField::<Fr>::king_share_batch(pubs.clone(), rng);
+        let shares = MpcField::all_public_or_shared(shared).unwrap_err();
+        (a * b).reveal() == x * y && g.reveal() == g_pub && FieldShare::batch_open(shares) == pubs
+    });
+    assert_eq!(ok, vec![true; 3]);
+}
+
+#[test]
+fn blames_the_cheater() {
+    let blamed = run_parties(3, || {
+        blame::set_identifiable_abort(true);
+        let rng = &mut ark_std::test_rng();
+        let a = match MpcField::<Fr>::king_share(Fr::rand(rng), rng) {
+            MpcField::Shared(s) if Net::party_id() == 1 => MpcField::Shared(corrupt(&s, Fr::one())),
+            a => a,
+        };
+        blame::catch_mac_failure(|| a.reveal()).unwrap_err()
+    });
+    let failure = MacCheckFailure { culprits: vec![1] };
+    assert_eq!(blamed, vec![failure; 3]);
+}
+
+#[test]
+fn blames_in_batches() {
+    let blamed = run_parties(4, || {
+        blame::set_identifiable_abort(true);
+        let rng = &mut ark_std::test_rng();
+        let pubs: Vec<G1Projective> = (0..5).map(|_| G1Projective::rand(rng)).collect();
+        let shared = MpcGroup::<G1Projective>::king_share_batch(pubs, rng);
+        let mut shares = MpcGroup::all_public_or_shared(shared).unwrap_err();
+        if Net::party_id() == 2 {
+            shares[3] = corrupt(&shares[3], G1Projective::prime_subgroup_generator());
+        }
+        blame::catch_mac_failure(|| GroupShare::batch_open(shares)).unwrap_err()
+    });
+    let failure = MacCheckFailure { culprits: vec![2] };
+    assert_eq!(blamed, vec![failure; 4]);
+}
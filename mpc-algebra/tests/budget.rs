@@ -0,0 +1,78 @@
+//! Reveal budgets, with every party run in-process.
+use ark_bls12_377::{Fr, G1Projective};
+use ark_ff::UniformRand;
+use mpc_algebra::budget::{self, RevealBudget};
+use mpc_algebra::dp;
+use mpc_algebra::honest_but_curious::{MpcField, MpcGroup};
+use mpc_algebra::Reveal;
+use mpc_test_utils::run_parties;
+use mpc_trait::MpcWire;
+
+fn shared_field(n: usize) -> Vec<MpcField<Fr>> {
+    let rng = &mut ark_std::test_rng();
+    (0..n)
+        .map(|_| MpcField::king_share(Fr::rand(rng), rng))
+        .collect()
+}
+
+#[test]
+fn counts_openings() {
+    let counts = run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        budget::set_reveal_budget(Some(RevealBudget::abort_after(5)));
+        let xs = shared_field(3);
+        xs.reveal();
+        let mut g = MpcGroup::<G1Projective>::king_share(G1Projective::rand(rng), rng);
+        g.publicize();
+        // public values are free
+        let _ = g.reveal();
+        MpcField::<Fr>::from_public(Fr::rand(rng)).reveal();
+        // and so are masked ones
+        budget::exempt(|| shared_field(10).reveal());
+        budget::revealed()
+    });
+    assert_eq!(counts, vec![4, 4]);
+}
+
+#[test]
+#[should_panic(expected = "Reveal budget exceeded")]
+fn aborts_past_limit() {
+    run_parties(3, || {
+        budget::set_reveal_budget(Some(RevealBudget::abort_after(2)));
+        shared_field(3).reveal();
+    });
+}
+
+#[test]
+fn warns_past_limit() {
+    let counts = run_parties(2, || {
+        budget::set_reveal_budget(Some(RevealBudget::warn_after(2)));
+        shared_field(3).reveal();
+        budget::revealed()
+    });
+    assert_eq!(counts, vec![3, 3]);
+}
+
+#[test]
+fn unlimited_by_default() {
+    let counts = run_parties(2, || {
+        shared_field(3).reveal();
+        budget::set_reveal_budget(Some(RevealBudget::abort_after(1)));
+        budget::set_reveal_budget(None);
+        shared_field(3).reveal();
+        (budget::reveal_budget(), budget::revealed())
+    });
+    assert_eq!(counts, vec![(None, 0), (None, 0)]);
+}
+
+#[test]
+fn masked_openings_inside_protocols_are_free() {
+    let counts = run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        budget::set_reveal_budget(Some(RevealBudget::abort_after(0)));
+        let bits: Vec<MpcField<Fr>> = dp::random_bits(4, rng);
+        assert_eq!(bits.len(), 4);
+        budget::revealed()
+    });
+    assert_eq!(counts, vec![0, 0]);
+}
@@ -0,0 +1,103 @@
+//! Vectors exchanged in chunks, with every party run in-process.
+use ark_bls12_377::{Fr, G1Projective};
+use ark_ff::UniformRand;
+use mpc_algebra::channel::{self, MpcSerNet};
+use mpc_algebra::{honest_but_curious, honest_majority, malicious_majority, Reveal};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_test_utils::{run_parties, run_parties_with, test_config};
+
+fn values<T: UniformRand>(n: usize) -> Vec<T> {
+    let rng = &mut ark_std::test_rng();
+    (0..n).map(|_| T::rand(rng)).collect()
+}
+
+#[test]
+fn splits_and_reassembles() {
+    for &len in &[0, 1, 3, 9, 10] {
+        let outs = run_parties(3, move || {
+            channel::set_max_chunk(Some(3));
+            let mine: Vec<u64> = (0..len)
+                .map(|i| (Net::party_id() * 100 + i) as u64)
+                .collect();
+            let all = Net::broadcast_vec(&mine);
+            let committed = Net::atomic_broadcast_vec(&mine);
+            let at_king = Net::send_vec_to(1, &mine);
+            let back = Net::recv_vec_from(1, at_king.map(|all| all.into_iter().rev().collect()));
+            (all, committed, back)
+        });
+        for (id, (all, committed, back)) in outs.into_iter().enumerate() {
+            let expected: Vec<Vec<u64>> = (0..3)
+                .map(|p| (0..len).map(|i| (p * 100 + i) as u64).collect())
+                .collect();
+            assert_eq!(all, expected);
+            assert_eq!(committed, expected);
+            assert_eq!(back, expected[2 - id]);
+        }
+    }
+}
+
+#[test]
+fn one_message_per_chunk() {
+    let broadcasts = run_parties(2, || {
+        channel::set_max_chunk(Some(4));
+        let before = Net::stats().broadcasts;
+        Net::broadcast_vec(&vec![0u64; 10]);
+        Net::stats().broadcasts - before
+    });
+    assert_eq!(broadcasts, vec![3, 3]);
+}
+
+#[test]
+fn chunks_to_the_king_share_writes_when_batching() {
+    let writes = |batch: bool| {
+        let config = mpc_net::NetConfig {
+            batch,
+            ..test_config()
+        };
+        run_parties_with(2, config, || {
+            channel::set_max_chunk(Some(4));
+            let before = Net::stats().writes;
+            let at_king = Net::send_vec_to(0, &vec![0u64; 10]);
+            Net::recv_vec_from(0, at_king);
+            Net::stats().writes - before
+        })
+    };
+    // party 1 writes only to the king, and the king only back, three chunks each
+    assert!(writes(false).into_iter().all(|w| w >= 3));
+    assert_eq!(writes(true), vec![1, 1]);
+}
+
+#[test]
+fn default_chunk() {
+    let chunks = run_parties(2, || {
+        channel::set_max_chunk(Some(5));
+        channel::set_max_chunk(None);
+        channel::max_chunk()
+    });
+    assert_eq!(chunks, vec![channel::DEFAULT_MAX_CHUNK; 2]);
+}
+
+macro_rules! opens_in_chunks {
+    ($name:ident, $mod:ident, $n:expr) => {
+        #[test]
+        fn $name() {
+            let fs = values::<Fr>(11);
+            let gs = values::<G1Projective>(7);
+            let (fs2, gs2) = (fs.clone(), gs.clone());
+            let outs = run_parties($n, move || {
+                channel::set_max_chunk(Some(2));
+                let rng = &mut ark_std::test_rng();
+                let f = $mod::MpcField::king_share_batch(fs2.clone(), rng);
+                let g = $mod::MpcGroup::king_share_batch(gs2.clone(), rng);
+                (f.reveal(), g.reveal())
+            });
+            for out in outs {
+                assert_eq!(out, (fs.clone(), gs.clone()));
+            }
+        }
+    };
+}
+
+opens_in_chunks!(hbc_opens_in_chunks, honest_but_curious, 2);
+opens_in_chunks!(spdz_opens_in_chunks, malicious_majority, 2);
+opens_in_chunks!(gsz_opens_in_chunks, honest_majority, 3);
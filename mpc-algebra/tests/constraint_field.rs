@@ -0,0 +1,114 @@
+//! Shared values as shared constraint-field elements, with every party run in-process.
+use ark_bls12_377::{Bls12_377, Fq, Fq2, G1Projective};
+use ark_ec::ProjectiveCurve;
+use ark_ed_on_bls12_377::EdwardsProjective;
+use ark_ff::{ToConstraintField, UniformRand};
+use mpc_algebra::share::msm::NaiveMsm;
+use mpc_algebra::{
+    AdditiveExtFieldShare, AdditiveFieldShare, AdditiveGroupShare, FieldShare, GroupShare,
+    GszFieldShare, GszGroupShare, MpcExtField, MpcField, MpcG1Affine, MpcGroup, Reveal,
+    SharedCoordinates, SpdzFieldShare, SpdzGroupShare, SpdzPairingShare,
+};
+use mpc_test_utils::run_parties;
+use mpc_trait::MpcWire;
+
+/// A random point and zero, converted shared by party 0 and public, give the plain points'
+/// elements.
+fn check<G, S, FS>(n: usize)
+where
+    G: SharedCoordinates,
+    S: GroupShare<G>,
+    FS: FieldShare<G::Coordinate>,
+{
+    let points = vec![G::rand(&mut ark_std::test_rng()), G::zero()];
+    let expected: Vec<_> = points
+        .iter()
+        .map(|p| p.to_field_elements().unwrap())
+        .collect();
+    let outs = run_parties(n, || {
+        let rng = &mut ark_std::test_rng();
+        let shared = MpcGroup::<G, S>::king_share_batch(points.clone(), rng);
+        shared
+            .into_iter()
+            .zip(&points)
+            .map(|(s, p)| {
+                let public: Vec<MpcField<_, FS>> =
+                    MpcGroup::<G, S>::Public(*p).to_field_elements().unwrap();
+                assert!(public.iter().all(|x| !x.is_shared()));
+                let coordinates: Vec<MpcField<_, FS>> = s.to_field_elements().unwrap();
+                assert!(coordinates.iter().all(|x| x.is_shared()));
+                (public.reveal(), coordinates.reveal())
+            })
+            .collect::<Vec<_>>()
+    });
+    for out in outs {
+        for ((public, shared), expected) in out.into_iter().zip(&expected) {
+            assert_eq!(&public, expected);
+            assert_eq!(&shared, expected);
+        }
+    }
+}
+
+type Add<G> = AdditiveGroupShare<G, NaiveMsm<G>>;
+type Spdz<G> = SpdzGroupShare<G, NaiveMsm<G>>;
+type Gsz<G> = GszGroupShare<G, NaiveMsm<G>>;
+
+#[test]
+fn edwards_additive() {
+    check::<EdwardsProjective, Add<_>, AdditiveFieldShare<_>>(3);
+}
+
+#[test]
+fn edwards_spdz() {
+    check::<EdwardsProjective, Spdz<_>, SpdzFieldShare<_>>(2);
+}
+
+#[test]
+fn bls12_g1_additive() {
+    check::<G1Projective, Add<_>, AdditiveFieldShare<_>>(2);
+}
+
+#[test]
+fn bls12_g1_spdz() {
+    check::<G1Projective, Spdz<_>, SpdzFieldShare<_>>(2);
+}
+
+#[test]
+fn bls12_g1_gsz() {
+    check::<G1Projective, Gsz<_>, GszFieldShare<_>>(3);
+}
+
+#[test]
+fn pairing_wrapper() {
+    let p = G1Projective::rand(&mut ark_std::test_rng()).into_affine();
+    let outs = run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        let shared = MpcG1Affine::<Bls12_377, SpdzPairingShare<_>>::king_share(p, rng);
+        let coordinates: Vec<MpcField<Fq, SpdzFieldShare<Fq>>> =
+            shared.to_field_elements().unwrap();
+        coordinates.reveal()
+    });
+    for out in outs {
+        assert_eq!(out, p.to_field_elements().unwrap());
+    }
+}
+
+#[test]
+fn extension_field() {
+    let x = Fq2::rand(&mut ark_std::test_rng());
+    let outs = run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        type X = MpcExtField<Fq2, AdditiveExtFieldShare<Fq2>>;
+        let coefficients: Vec<MpcField<Fq, AdditiveFieldShare<Fq>>> =
+            X::king_share(x, rng).to_field_elements().unwrap();
+        assert!(coefficients.iter().all(|c| c.is_shared()));
+        let public: Vec<MpcField<Fq, AdditiveFieldShare<Fq>>> =
+            X::from_public(x).to_field_elements().unwrap();
+        (public.reveal(), coefficients.reveal())
+    });
+    let expected: Vec<Fq> = x.to_field_elements().unwrap();
+    for (public, shared) in outs {
+        assert_eq!(public, expected);
+        assert_eq!(shared, expected);
+    }
+}
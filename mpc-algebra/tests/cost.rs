@@ -0,0 +1,54 @@
+//! Counts of the triples drawn and values opened, with every party run in-process.
+use ark_bls12_377::{Fr, G1Projective};
+use ark_ff::{Field, UniformRand};
+use mpc_algebra::cost::{self, Costs};
+use mpc_algebra::share::msm::NaiveMsm;
+use mpc_algebra::{AdditiveFieldShare, AdditiveGroupShare, MpcField, MpcGroup, Reveal};
+use mpc_test_utils::run_parties;
+
+type F = MpcField<Fr, AdditiveFieldShare<Fr>>;
+type G = MpcGroup<G1Projective, AdditiveGroupShare<G1Projective, NaiveMsm<G1Projective>>>;
+
+#[test]
+fn counts() {
+    let outs = run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        let (x, y) = (
+            F::king_share(Fr::rand(rng), rng),
+            F::king_share(Fr::rand(rng), rng),
+        );
+        let g = G::king_share(G1Projective::rand(rng), rng);
+        cost::reset();
+        let start = cost::costs();
+        let xy = x * y;
+        let mut products = vec![x; 3];
+        F::batch_product_in_place(&mut products, &[y; 3]);
+        let gx = g * x;
+        let after_products = cost::costs().since(start);
+        // public operands draw no triples
+        let _ = x * F::from_public(Fr::from(2u8));
+        let _ = (
+            xy.reveal(),
+            products.reveal(),
+            gx.reveal(),
+            F::from_public(Fr::from(1u8)).reveal(),
+        );
+        (after_products, cost::costs())
+    });
+    for (products, total) in outs {
+        assert_eq!(
+            products,
+            Costs {
+                triples: 5,
+                openings: 0
+            }
+        );
+        assert_eq!(
+            total,
+            Costs {
+                triples: 5,
+                openings: 5
+            }
+        );
+    }
+}
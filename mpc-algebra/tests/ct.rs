@@ -0,0 +1,41 @@
+//! Constant-time comparisons, and wiping shares.
+use ark_bls12_377::{Fr, G1Projective};
+use ark_ff::{One, UniformRand, Zero};
+use mpc_algebra::ct;
+use mpc_algebra::malicious_majority::{MpcField, MpcGroup};
+use mpc_algebra::Reveal;
+use mpc_test_utils::run_parties;
+use zeroize::Zeroize;
+
+#[test]
+fn compares() {
+    let rng = &mut ark_std::test_rng();
+    let (x, y) = (Fr::rand(rng), Fr::rand(rng));
+    assert!(bool::from(ct::eq(&x, &x)));
+    assert!(!bool::from(ct::eq(&x, &y)));
+    assert!(bool::from(ct::is_zero(&Fr::zero())));
+    assert!(!bool::from(ct::is_zero(&x)));
+    assert!(bool::from(ct::is_one(&Fr::one())));
+    assert!(bool::from(ct::is_zero(&G1Projective::zero())));
+    assert!(!bool::from(ct::is_zero(&G1Projective::rand(rng))));
+    assert!(!bool::from(ct::bytes_eq(b"ab", b"abc")));
+    assert!(ct::all(vec![ct::eq(&x, &x), ct::is_one(&Fr::one())]));
+    assert!(!ct::all(vec![ct::eq(&x, &x), ct::eq(&x, &y)]));
+    assert!(ct::all(vec![]));
+}
+
+#[test]
+fn wipes_shares() {
+    let wiped = run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        let mut a = MpcField::<Fr>::king_share(Fr::rand(rng), rng);
+        let mut g = MpcGroup::<G1Projective>::king_share(G1Projective::rand(rng), rng);
+        a.zeroize();
+        g.zeroize();
+        (a, g)
+    });
+    for (a, g) in wiped {
+        assert_eq!(a, MpcField::from_public(Fr::zero()));
+        assert_eq!(g, MpcGroup::from_public(G1Projective::zero()));
+    }
+}
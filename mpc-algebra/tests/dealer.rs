@@ -0,0 +1,67 @@
+//! Material from the trusted dealer, with every party run in-process.
+use ark_bls12_377::Fr;
+use ark_ff::{One, Zero};
+use mpc_algebra::share::dealer;
+use mpc_algebra::share::store::{MemoryStore, PreprocessingStore, Preprocessed, Stock};
+use mpc_algebra::{AdditiveFieldShare, FieldShare, GszFieldShare, RandomnessSource, SpdzFieldShare};
+use mpc_test_utils::run_parties;
+
+fn check_dealt<S: FieldShare<Fr>>() {
+    let rng = &mut ark_std::test_rng();
+    let mut pre = Preprocessed::<Fr, S, _>::open(MemoryStore::new()).unwrap();
+    assert!(!dealer::is_dealt(pre.store()).unwrap());
+    let counts = Stock {
+        triples: 3,
+        inv_pairs: 2,
+        shares: 2,
+        bits: 8,
+        zeros: 1,
+    };
+    dealer::deal(&pre, counts, rng).unwrap();
+    assert_eq!(pre.stock().unwrap(), counts);
+    assert!(dealer::is_dealt(pre.store()).unwrap());
+    assert_eq!(
+        pre.store().metadata(dealer::SOURCE).unwrap().as_deref(),
+        Some("trusted dealer")
+    );
+
+    let (xs, ys, zs) = pre.take_triples(3).unwrap();
+    let (xs, ys, zs) = (S::batch_open(xs), S::batch_open(ys), S::batch_open(zs));
+    for ((x, y), z) in xs.iter().zip(&ys).zip(&zs) {
+        assert_eq!(*x * y, *z);
+    }
+    assert_ne!(xs[0], xs[1]);
+    let (rs, r_invs) = pre.take_inv_pairs(2).unwrap();
+    for (r, r_inv) in S::batch_open(rs).into_iter().zip(S::batch_open(r_invs)) {
+        assert!((r * r_inv).is_one());
+    }
+    assert!(S::batch_open(pre.take_zeros(1).unwrap())[0].is_zero());
+    let bits = S::batch_open(pre.random_bits(8).unwrap());
+    assert!(bits.iter().all(|b| b.is_zero() || b.is_one()));
+    assert!(bits.iter().any(|b| b.is_one()));
+}
+
+#[test]
+fn additive_dealt() {
+    run_parties(3, check_dealt::<AdditiveFieldShare<Fr>>);
+}
+
+#[test]
+fn spdz_dealt() {
+    run_parties(2, check_dealt::<SpdzFieldShare<Fr>>);
+}
+
+#[test]
+fn gsz_dealt() {
+    run_parties(3, check_dealt::<GszFieldShare<Fr>>);
+}
+
+#[test]
+fn mac_key_share_noted() {
+    run_parties(2, || {
+        let store = MemoryStore::new();
+        let share: Fr = dealer::deal_mac_key(&store).unwrap();
+        assert_eq!(share, mpc_algebra::mac_share::<Fr>());
+        assert_eq!(store.metadata(dealer::MAC_KEY_SHARE).unwrap().unwrap().len(), 64);
+    });
+}
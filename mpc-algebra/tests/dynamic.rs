@@ -0,0 +1,75 @@
+//! Shares of a scheme chosen at run time, with every party run in-process.
+use ark_bls12_377::{Bls12_377, Fr, G1Projective, G2Projective};
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mpc_algebra::any_scheme::{MpcField, MpcG1Affine, MpcG2Affine, MpcPairingEngine};
+use mpc_algebra::share::dynamic::{self, DynFieldShare, Scheme};
+use mpc_algebra::Reveal;
+use mpc_test_utils::run_parties;
+
+type E = Bls12_377;
+
+/// The same on every party.
+fn inputs() -> (
+    Vec<Fr>,
+    <E as PairingEngine>::G1Affine,
+    <E as PairingEngine>::G2Affine,
+) {
+    let rng = &mut ark_std::test_rng();
+    let xs = (0..4).map(|_| Fr::rand(rng)).collect();
+    let p = G1Projective::rand(rng).into_affine();
+    let q = G2Projective::rand(rng).into_affine();
+    (xs, p, q)
+}
+
+/// Products, quotients and a pairing, of shares of `scheme`, opened.
+fn compute(scheme: Scheme) -> (Fr, Vec<Fr>, <E as PairingEngine>::Fqk) {
+    dynamic::set_scheme(scheme);
+    DynFieldShare::<Fr>::init_protocol();
+    let rng = &mut ark_std::test_rng();
+    let (xs, p, q) = inputs();
+    let shared = MpcField::<Fr>::king_share_batch(xs, rng);
+    let product = (shared[0] * shared[1] + shared[2]) / shared[3];
+    let mut quotients = shared.clone();
+    quotients.rotate_left(1);
+    MpcField::batch_division_in_place(&mut quotients, &shared);
+
+    let p = MpcG1Affine::<E>::king_share(p, rng);
+    let gt = MpcPairingEngine::<E>::pairing(p, MpcG2Affine::from_public(q));
+
+    // a share is encoded as its scheme encodes it
+    if let MpcField::Shared(s) = shared[0] {
+        assert_eq!(s.scheme(), scheme);
+        let mut bytes = Vec::new();
+        s.serialize(&mut bytes).unwrap();
+        assert_eq!(DynFieldShare::<Fr>::deserialize(&bytes[..]).unwrap(), s);
+    }
+    let out = (product.reveal(), quotients.reveal(), gt.reveal());
+    DynFieldShare::<Fr>::deinit_protocol();
+    out
+}
+
+#[test]
+fn every_scheme() {
+    let (xs, p, q) = inputs();
+    let expected = (
+        (xs[0] * xs[1] + xs[2]) / xs[3],
+        (0..4).map(|i| xs[(i + 1) % 4] / xs[i]).collect::<Vec<_>>(),
+        E::pairing(p, q),
+    );
+    for (scheme, parties) in [(Scheme::Hbc, 2), (Scheme::Spdz, 2), (Scheme::Gsz, 3)] {
+        for out in run_parties(parties, || compute(scheme)) {
+            assert_eq!(out, expected, "{}", scheme);
+        }
+    }
+}
+
+#[test]
+fn scheme_names() {
+    for scheme in Scheme::ALL.iter() {
+        assert_eq!(scheme.to_string().parse::<Scheme>(), Ok(*scheme));
+    }
+    assert_eq!("Shamir".parse::<Scheme>(), Ok(Scheme::Gsz));
+    assert!("replicated".parse::<Scheme>().is_err());
+}
@@ -0,0 +1,70 @@
+//! Arithmetic on the Edwards-BLS12 embedded curve, with every party run in-process.
+use ark_ec::{group::Group, ProjectiveCurve};
+use ark_ed_on_bls12_377::{EdwardsAffine, EdwardsParameters, EdwardsProjective, Fr};
+use ark_ff::UniformRand;
+use mpc_algebra::Reveal;
+use mpc_test_utils::run_parties;
+
+fn points(n: usize) -> Vec<EdwardsAffine> {
+    let rng = &mut ark_std::test_rng();
+    (0..n)
+        .map(|_| EdwardsProjective::rand(rng).into_affine())
+        .collect()
+}
+
+macro_rules! edwards_tests {
+    ($mod:ident, $n:expr) => {
+        mod $mod {
+            use super::*;
+            use mpc_algebra::$mod::MpcEdwardsAffine;
+
+            #[test]
+            fn shared_coordinates_add() {
+                let ps = points(3);
+                let ps2 = ps.clone();
+                let outs = run_parties($n, move || {
+                    let rng = &mut ark_std::test_rng();
+                    let s =
+                        MpcEdwardsAffine::<EdwardsParameters>::king_share_batch(ps2.clone(), rng);
+                    let public = MpcEdwardsAffine::<EdwardsParameters>::from_public(ps2[2]);
+                    (
+                        (s[0] + &s[1]).reveal(),
+                        (s[0] + &s[0]).reveal(),
+                        (s[1] + &public).reveal(),
+                    )
+                });
+                for out in outs {
+                    assert_eq!(out, (ps[0] + ps[1], ps[0] + ps[0], ps[1] + ps[2]));
+                }
+            }
+        }
+    };
+}
+
+edwards_tests!(honest_but_curious, 2);
+edwards_tests!(malicious_majority, 2);
+edwards_tests!(honest_majority, 3);
+
+// The scalar field of Edwards-BLS12 has no evaluation domain of size 3, which GSZ20 shares need.
+macro_rules! group_tests {
+    ($name:ident, $mod:ident, $n:expr) => {
+        #[test]
+        fn $name() {
+            use mpc_algebra::$mod::{MpcField, MpcGroup};
+            let rng = &mut ark_std::test_rng();
+            let (p, k) = (EdwardsProjective::rand(rng), Fr::rand(rng));
+            let outs = run_parties($n, move || {
+                let rng = &mut ark_std::test_rng();
+                let sp = MpcGroup::<EdwardsProjective>::king_share(p, rng);
+                let sk = MpcField::<Fr>::king_share(k, rng);
+                (sp * sk).reveal()
+            });
+            for out in outs {
+                assert_eq!(out, Group::mul(&p, &k));
+            }
+        }
+    };
+}
+
+group_tests!(hbc_shared_group_scalar_mul, honest_but_curious, 2);
+group_tests!(spdz_shared_group_scalar_mul, malicious_majority, 2);
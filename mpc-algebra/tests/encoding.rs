@@ -0,0 +1,73 @@
+//! Points exchanged compressed or uncompressed, with every party run in-process.
+use ark_bls12_377::{Fr, G1Projective, G2Projective};
+use ark_ff::UniformRand;
+use mpc_algebra::channel::{self, MpcSerNet, PointEncoding};
+use mpc_algebra::{honest_but_curious, honest_majority, malicious_majority, Reveal};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_test_utils::run_parties;
+
+fn values<T: UniformRand>(n: usize) -> Vec<T> {
+    let rng = &mut ark_std::test_rng();
+    (0..n).map(|_| T::rand(rng)).collect()
+}
+
+#[test]
+fn round_trips() {
+    let gs = values::<G2Projective>(3);
+    for &e in &[PointEncoding::Compressed, PointEncoding::Uncompressed] {
+        assert_eq!(e.deserialize::<Vec<G2Projective>>(&e.serialize(&gs)).unwrap(), gs);
+        assert_eq!(e.to_string().parse::<PointEncoding>(), Ok(e));
+    }
+    let compressed = PointEncoding::Compressed.serialize(&gs);
+    let uncompressed = PointEncoding::Uncompressed.serialize(&gs);
+    assert_eq!(uncompressed.len() - 8, 2 * (compressed.len() - 8));
+    assert!(PointEncoding::Uncompressed
+        .deserialize::<Vec<G2Projective>>(&compressed)
+        .is_err());
+    assert!("packed".parse::<PointEncoding>().is_err());
+}
+
+#[test]
+fn uncompressed_doubles_the_traffic() {
+    let gs = values::<G1Projective>(4);
+    let sent = run_parties(2, move || {
+        let mut sent = Vec::new();
+        for &e in &[PointEncoding::Compressed, PointEncoding::Uncompressed] {
+            channel::set_point_encoding(Some(e));
+            let before = Net::stats().bytes_sent;
+            assert_eq!(Net::broadcast_vec(&gs), vec![gs.clone(); 2]);
+            sent.push(Net::stats().bytes_sent - before);
+        }
+        channel::set_point_encoding(None);
+        assert_eq!(channel::point_encoding(), PointEncoding::Compressed);
+        sent
+    });
+    for s in sent {
+        assert_eq!(s[1] - 8, 2 * (s[0] - 8));
+    }
+}
+
+macro_rules! opens_uncompressed {
+    ($name:ident, $mod:ident, $n:expr) => {
+        #[test]
+        fn $name() {
+            let fs = values::<Fr>(5);
+            let gs = values::<G1Projective>(5);
+            let (fs2, gs2) = (fs.clone(), gs.clone());
+            let outs = run_parties($n, move || {
+                channel::set_point_encoding(Some(PointEncoding::Uncompressed));
+                let rng = &mut ark_std::test_rng();
+                let f = $mod::MpcField::king_share_batch(fs2.clone(), rng);
+                let g = $mod::MpcGroup::king_share_batch(gs2.clone(), rng);
+                (f.reveal(), g.reveal())
+            });
+            for out in outs {
+                assert_eq!(out, (fs.clone(), gs.clone()));
+            }
+        }
+    };
+}
+
+opens_uncompressed!(hbc_opens_uncompressed, honest_but_curious, 2);
+opens_uncompressed!(spdz_opens_uncompressed, malicious_majority, 2);
+opens_uncompressed!(gsz_opens_uncompressed, honest_majority, 3);
@@ -0,0 +1,135 @@
+//! Persisted shares in tagged envelopes, with every party run in-process.
+use ark_bls12_377::{Fr, G1Projective};
+use ark_ff::{One, UniformRand, Zero};
+use ark_serialize::CanonicalSerialize;
+use mpc_algebra::{
+    msm::NaiveMsm,
+    share::{
+        add::AdditiveFieldShare,
+        envelope::{self, EnvelopeError, Header, PayloadKind, FORMAT_VERSION},
+        gsz20::field::GszFieldShare,
+        spdz::{SpdzFieldShare, SpdzGroupShare},
+        FieldShare, RandomnessError, RandomnessPool, RandomnessSource,
+    },
+    DummyFieldTripleSource, Reveal,
+};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_test_utils::run_parties;
+
+fn envelope_of<T: CanonicalSerialize>(kind: PayloadKind, payload: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    envelope::write(kind, payload, &mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn round_trips() {
+    run_parties(3, || {
+        let rng = &mut ark_std::test_rng();
+        // every party draws the same values, before sharing draws differently on each
+        let xs: Vec<Fr> = (0..5).map(|_| Fr::rand(rng)).collect();
+        let g = G1Projective::rand(rng);
+
+        let spdz = SpdzFieldShare::king_share_batch(xs.clone(), rng);
+        let bytes = envelope_of(PayloadKind::Shares, &spdz);
+        let read: Vec<SpdzFieldShare<Fr>> =
+            envelope::read(PayloadKind::Shares, &bytes[..]).unwrap();
+        assert_eq!(read, spdz);
+        assert_eq!(SpdzFieldShare::batch_open(read), xs);
+
+        let group = SpdzGroupShare::<G1Projective, NaiveMsm<G1Projective>>::king_share(g, rng);
+        let bytes = envelope_of(PayloadKind::ProofFragment, &group);
+        let read: SpdzGroupShare<G1Projective, NaiveMsm<G1Projective>> =
+            envelope::read(PayloadKind::ProofFragment, &bytes[..]).unwrap();
+        assert_eq!(read.reveal(), g);
+
+        GszFieldShare::<Fr>::init_protocol();
+        let gsz = GszFieldShare::king_share_batch(xs.clone(), rng);
+        let bytes = envelope_of(PayloadKind::Shares, &gsz);
+        let read: Vec<GszFieldShare<Fr>> = envelope::read(PayloadKind::Shares, &bytes[..]).unwrap();
+        assert_eq!(GszFieldShare::batch_open(read), xs);
+        GszFieldShare::<Fr>::deinit_protocol();
+
+        let header = Header::read(&bytes[..]).unwrap();
+        assert_eq!(header.version, FORMAT_VERSION);
+        assert_eq!(header.kind, PayloadKind::Shares);
+        assert_eq!(header.party_id, Net::party_id());
+        assert_eq!(header.n_parties, 3);
+    });
+}
+
+#[test]
+fn strict_validation() {
+    run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        let xs: Vec<Fr> = (0..5).map(|_| Fr::rand(rng)).collect();
+        let shares = AdditiveFieldShare::king_share_batch(xs, rng);
+        let bytes = envelope_of(PayloadKind::Shares, &shares);
+        let read = |bytes: &[u8]| -> Result<Vec<AdditiveFieldShare<Fr>>, EnvelopeError> {
+            envelope::read(PayloadKind::Shares, bytes)
+        };
+        assert_eq!(read(&bytes).unwrap(), shares);
+
+        assert!(matches!(
+            envelope::read::<Vec<AdditiveFieldShare<Fr>>, _>(PayloadKind::Triples, &bytes[..]),
+            Err(EnvelopeError::WrongKind { .. })
+        ));
+        // the same bytes, but as shares of another scheme
+        assert!(matches!(
+            envelope::read::<Vec<SpdzFieldShare<Fr>>, _>(PayloadKind::Shares, &bytes[..]),
+            Err(EnvelopeError::WrongType { .. })
+        ));
+        let mut other = bytes.clone();
+        other[0] ^= 1;
+        assert!(matches!(read(&other), Err(EnvelopeError::NotAnEnvelope)));
+        let mut other = bytes.clone();
+        other[8] = 2;
+        assert!(matches!(
+            read(&other),
+            Err(EnvelopeError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            read(&bytes[..bytes.len() - 1]),
+            Err(EnvelopeError::BadLength { .. })
+        ));
+        let mut other = bytes.clone();
+        other.push(0);
+        assert!(matches!(read(&other), Err(EnvelopeError::BadLength { .. })));
+
+        // another party's file
+        let theirs = Net::broadcast_bytes(&bytes);
+        assert!(matches!(
+            read(&theirs[1 - Net::party_id()]),
+            Err(EnvelopeError::WrongParty { .. })
+        ));
+    });
+}
+
+#[test]
+fn randomness_pools() {
+    type Pool = RandomnessPool<Fr, AdditiveFieldShare<Fr>>;
+    let dir = std::env::temp_dir().join(format!("mpc-envelope-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        let path = Pool::party_path(&dir, Net::party_id());
+        let mut triples = DummyFieldTripleSource::default();
+        let mut pool = Pool::generate(4, 3, &mut triples, rng);
+        let first = pool.random_share().unwrap();
+        pool.save(&path).unwrap();
+
+        let mut loaded = Pool::load(&path).unwrap();
+        assert_eq!((loaded.shares_left(), loaded.bits_left()), (3, 3));
+        let rest = loaded.random_shares(3).unwrap();
+        assert_eq!(rest, pool.random_shares(3).unwrap());
+        assert!(!rest.contains(&first));
+        for bit in AdditiveFieldShare::batch_open(loaded.random_bits(3).unwrap()) {
+            assert!(bit == Fr::zero() || bit == Fr::one());
+        }
+        assert!(matches!(
+            RandomnessPool::<Fr, SpdzFieldShare<Fr>>::load(&path),
+            Err(RandomnessError::Envelope(EnvelopeError::WrongType { .. }))
+        ));
+    });
+    std::fs::remove_dir_all(&dir).unwrap();
+}
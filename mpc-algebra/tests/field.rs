@@ -0,0 +1,222 @@
+//! Field protocols, with every party run in-process.
+use ark_bls12_377::Fr;
+use ark_ff::{FftField, Field, LegendreSymbol, One, SquareRootField, UniformRand, Zero};
+use mpc_algebra::{
+    channel::MpcSerNet,
+    share::{
+        add::AdditiveFieldShare,
+        field::{FieldShare, SmallScalar},
+        gsz20::field::GszFieldShare,
+        spdz::*,
+    },
+    batch_inverse, batch_legendre, batch_sqrt, MpcField, MpcMulScratch, Reveal,
+};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_test_utils::run_parties;
+use mpc_trait::MpcWire;
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+fn test_reveal<S: FieldShare<Fr>>() {
+    let rng = &mut ark_std::test_rng();
+    for _ in 0..10 {
+        let a_pub = Fr::rand(rng);
+        let a = MpcField::<Fr, S>::king_share(a_pub, rng);
+        assert!(a.is_shared());
+        assert_eq!(a.reveal(), a_pub);
+    }
+    let pubs: Vec<Fr> = (0..100).map(|_| Fr::rand(rng)).collect();
+    let shared = MpcField::<Fr, S>::king_share_batch(pubs.clone(), rng);
+    let shares = MpcField::all_public_or_shared(shared).unwrap_err();
+    assert_eq!(S::batch_open(shares), pubs);
+}
+
+fn test_mul<S: FieldShare<Fr>>() {
+    let rng = &mut ark_std::test_rng();
+    for _ in 0..10 {
+        let a_pub = Fr::rand(rng);
+        let b_pub = Fr::rand(rng);
+        let a = MpcField::<Fr, S>::king_share(a_pub, rng);
+        let b = MpcField::<Fr, S>::king_share(b_pub, rng);
+        assert_eq!((a * b).reveal(), a_pub * b_pub);
+        assert_eq!((a * MpcField::from_public(b_pub)).reveal(), a_pub * b_pub);
+        assert_eq!((a + b).reveal(), a_pub + b_pub);
+        assert_eq!((a / b).reveal(), a_pub / b_pub);
+    }
+
+    // public factors that skip the multiplication, and ones that don't
+    let a_pub = Fr::rand(rng);
+    let a = MpcField::<Fr, S>::king_share(a_pub, rng);
+    for k in [Fr::zero(), Fr::one(), -Fr::one(), Fr::from(2u8), -Fr::from(16u8)] {
+        assert_eq!((a * MpcField::from_public(k)).reveal(), a_pub * k);
+    }
+
+    let size = 100;
+    let a_pubs: Vec<Fr> = (0..size).map(|_| Fr::rand(rng)).collect();
+    let b_pubs: Vec<Fr> = (0..size).map(|_| Fr::rand(rng)).collect();
+    let mut a = MpcField::<Fr, S>::king_share_batch(a_pubs.clone(), rng);
+    let b = MpcField::<Fr, S>::king_share_batch(b_pubs.clone(), rng);
+    MpcField::batch_product_in_place(&mut a, &b);
+    for i in 0..size {
+        assert_eq!(a[i].reveal(), a_pubs[i] * b_pubs[i]);
+    }
+
+    // one scratch, reused by batches that shrink and grow, and by public ones; sharing draws
+    // from `rng` on the king only, so the values come from their own generator
+    let values = &mut ark_std::test_rng();
+    let mut scratch = MpcMulScratch::default();
+    for &(len, shared) in &[(50, true), (3, true), (7, false), (80, true)] {
+        let a_pubs: Vec<Fr> = (0..len).map(|_| Fr::rand(values)).collect();
+        let b_pubs: Vec<Fr> = (0..len).map(|_| Fr::rand(values)).collect();
+        let mut a = MpcField::<Fr, S>::king_share_batch(a_pubs.clone(), rng);
+        let b = if shared {
+            MpcField::<Fr, S>::king_share_batch(b_pubs.clone(), rng)
+        } else {
+            b_pubs.iter().map(|b| MpcField::from_public(*b)).collect()
+        };
+        MpcField::batch_product_in_place_with(&mut a, &b, &mut scratch);
+        let products: Vec<Fr> = a_pubs.iter().zip(&b_pubs).map(|(a, b)| *a * b).collect();
+        assert_eq!(a.reveal(), products);
+    }
+}
+
+fn test_inv<S: FieldShare<Fr>>() {
+    let rng = &mut ark_std::test_rng();
+    let a_pub = Fr::rand(rng);
+    let a = MpcField::<Fr, S>::king_share(a_pub, rng);
+    assert_eq!(a.inverse().unwrap().reveal(), a_pub.inverse().unwrap());
+    assert_eq!((a * a.inverse().unwrap()).reveal(), Fr::one());
+
+    // shared and public elements, with a public zero, which is left as it is
+    let pubs: Vec<Fr> = (0..20).map(|_| Fr::rand(rng)).collect();
+    let mut v = MpcField::<Fr, S>::king_share_batch(pubs.clone(), rng);
+    v[3] = MpcField::from_public(pubs[3]);
+    v[7] = MpcField::from_public(Fr::zero());
+    batch_inverse(&mut v);
+    for (i, (x, p)) in v.into_iter().zip(pubs).enumerate() {
+        match i {
+            3 => assert_eq!(x, MpcField::from_public(p.inverse().unwrap())),
+            7 => assert_eq!(x, MpcField::from_public(Fr::zero())),
+            _ => assert_eq!(x.reveal(), p.inverse().unwrap()),
+        }
+    }
+
+    // batches divided by public divisors, and, in a single party, by shared ones, are divided
+    // locally, and must divide rather than multiply
+    let values = &mut ark_std::test_rng();
+    let xs: Vec<Fr> = (0..20).map(|_| Fr::rand(values)).collect();
+    let ys: Vec<Fr> = (0..20).map(|_| Fr::rand(values)).collect();
+    let quotients: Vec<Fr> = xs.iter().zip(&ys).map(|(x, y)| *x / y).collect();
+    let mut v = MpcField::<Fr, S>::king_share_batch(xs.clone(), rng);
+    let public: Vec<_> = ys.iter().map(|y| MpcField::from_public(*y)).collect();
+    MpcField::batch_division_in_place(&mut v, &public);
+    assert_eq!(v.reveal(), quotients);
+    let mut v = MpcField::<Fr, S>::king_share_batch(xs, rng);
+    MpcField::batch_division_in_place(&mut v, &MpcField::king_share_batch(ys, rng));
+    assert_eq!(v.reveal(), quotients);
+}
+
+fn test_sqrt<S: FieldShare<Fr>>() {
+    let rng = &mut ark_std::test_rng();
+    let a_pub = Fr::rand(rng).square();
+    let a = MpcField::<Fr, S>::king_share(a_pub, rng);
+    assert_eq!(a.legendre(), LegendreSymbol::QuadraticResidue);
+    assert_eq!(a.sqrt().unwrap().square().reveal(), a_pub);
+
+    // squares, non-squares (a generator of the multiplicative group is not a square) and zero,
+    // shared and public
+    let pubs: Vec<Fr> = (0..20)
+        .map(|i| match i % 3 {
+            0 => Fr::rand(rng).square(),
+            1 => Fr::rand(rng).square() * Fr::multiplicative_generator(),
+            _ => Fr::zero(),
+        })
+        .collect();
+    let mut v = MpcField::<Fr, S>::king_share_batch(pubs.clone(), rng);
+    v[3] = MpcField::from_public(pubs[3]);
+    v[4] = MpcField::from_public(pubs[4]);
+    let symbols = batch_legendre(&v);
+    let roots = batch_sqrt(&v);
+    for (i, ((s, r), p)) in symbols.into_iter().zip(roots).zip(pubs).enumerate() {
+        assert_eq!(s, p.legendre());
+        match r {
+            Some(r) => {
+                assert_eq!(r.is_shared(), ![3, 4].contains(&i));
+                assert_eq!(r.square().reveal(), p);
+            }
+            None => assert_eq!(s, LegendreSymbol::QuadraticNonResidue),
+        }
+    }
+}
+
+fn test_all<S: FieldShare<Fr>>(n: usize) {
+    run_parties(n, || {
+        S::init_protocol();
+        test_reveal::<S>();
+        test_mul::<S>();
+        test_inv::<S>();
+        test_sqrt::<S>();
+        S::deinit_protocol();
+    });
+}
+
+#[test]
+fn additive() {
+    test_all::<AdditiveFieldShare<Fr>>(2);
+    test_all::<AdditiveFieldShare<Fr>>(3);
+}
+
+#[test]
+fn spdz() {
+    test_all::<SpdzFieldShare<Fr>>(2);
+    test_all::<SpdzFieldShare<Fr>>(3);
+}
+
+#[test]
+fn gsz20() {
+    test_all::<GszFieldShare<Fr>>(3);
+    test_all::<GszFieldShare<Fr>>(4);
+}
+
+#[test]
+fn single_party() {
+    test_all::<AdditiveFieldShare<Fr>>(1);
+    test_all::<SpdzFieldShare<Fr>>(1);
+}
+
+#[test]
+fn small_scalars() {
+    let units = [Fr::zero(), Fr::one(), -Fr::one()];
+    let powers = [Fr::from(2u8), -Fr::from(2u8), Fr::from(16u8), -Fr::from(16u8)];
+    for k in units.iter().chain(&powers) {
+        assert_eq!(SmallScalar::of(k).unwrap().value::<Fr>(), *k);
+    }
+    for k in &units {
+        assert_eq!(SmallScalar::of_unit(k), SmallScalar::of(k));
+    }
+    for k in powers.iter().chain(&[Fr::from(32u8), Fr::from(3u8)]) {
+        assert_eq!(SmallScalar::of_unit(k), None);
+    }
+    assert_eq!(SmallScalar::of(&Fr::from(32u8)), None);
+}
+
+/// One party lies about its share when opening; the others' MAC check must catch it.
+#[test]
+fn spdz_mac_check() {
+    let caught = run_parties(3, || {
+        let rng = &mut ark_std::test_rng();
+        let a = MpcField::<Fr, SpdzFieldShare<Fr>>::king_share(Fr::rand(rng), rng);
+        if Net::party_id() == 1 {
+            // Run the opening by hand. The MAC key is public in this prototype, so our MAC share
+            // is our share of the value.
+            let sh = a.unwrap_as_public();
+            let x: Fr = Net::broadcast(&(sh + Fr::one())).iter().sum();
+            let dx_t: Fr = mac_share::<Fr>() * x - sh;
+            Net::atomic_broadcast(&dx_t);
+            false
+        } else {
+            catch_unwind(AssertUnwindSafe(|| a.reveal())).is_err()
+        }
+    });
+    assert_eq!(caught, vec![true, false, true]);
+}
@@ -0,0 +1,84 @@
+//! Fixed-point encoding, and shared fixed-point values with every party run in-process.
+use ark_bls12_377::Fr;
+use mpc_algebra::budget::{self, RevealBudget};
+use mpc_algebra::fixed::{FixedPoint, FixedPointError, Rounding};
+use mpc_algebra::honest_but_curious::MpcField;
+use mpc_test_utils::run_parties;
+
+#[test]
+fn round_trips() {
+    let fp = FixedPoint::new(16);
+    for x in [0.0, 1.0, 0.5, 3.25, 12345.0625, 1e9] {
+        let e: Fr = fp.encode_f64(x).unwrap();
+        assert_eq!(fp.decode_f64(e), x);
+    }
+    let e: Fr = fp.encode_decimal("2.75").unwrap();
+    assert_eq!(fp.decode_decimal(e).unwrap(), "2.75");
+}
+
+#[test]
+fn negative_values() {
+    let fp = FixedPoint::new(8);
+    let a: Fr = fp.encode_f64(-1.5).unwrap();
+    let b: Fr = fp.encode_f64(0.25).unwrap();
+    assert_eq!(a, -fp.encode_f64::<Fr>(1.5).unwrap());
+    assert_eq!(fp.decode_f64(a), -1.5);
+    // sums of encodings encode sums, across zero
+    assert_eq!(fp.decode_f64(a + b), -1.25);
+    assert_eq!(fp.decode_decimal(a + b).unwrap(), "-1.25");
+    let c: Fr = fp.encode_decimal("-0.125").unwrap();
+    assert_eq!(fp.decode_f64(c), -0.125);
+}
+
+#[test]
+fn rounds() {
+    let x = 1.0 + 1.0 / 512.0;
+    let y = -x;
+    let nearest = FixedPoint::new(8);
+    assert_eq!(nearest.decode_f64(nearest.encode_f64::<Fr>(x).unwrap()), 1.0);
+    let ceil = nearest.with_rounding(Rounding::Ceil);
+    assert_eq!(ceil.decode_f64(ceil.encode_f64::<Fr>(x).unwrap()), 1.00390625);
+    let floor = nearest.with_rounding(Rounding::Floor);
+    assert_eq!(floor.decode_f64(floor.encode_f64::<Fr>(y).unwrap()), -1.00390625);
+}
+
+#[test]
+fn rejects_unencodable() {
+    let fp = FixedPoint::new(16);
+    assert_eq!(fp.encode_f64::<Fr>(f64::NAN), Err(FixedPointError::NotFinite));
+    assert_eq!(fp.encode_f64::<Fr>(1e300), Err(FixedPointError::Overflow));
+    assert!(matches!(
+        fp.encode_decimal::<Fr>("1.2.3"),
+        Err(FixedPointError::Syntax(_))
+    ));
+}
+
+#[test]
+fn shared_round_trip() {
+    let xs = [-2.5, 0.0, 7.125, -0.0078125];
+    let outs = run_parties(3, move || {
+        let rng = &mut ark_std::test_rng();
+        let fp = FixedPoint::new(32);
+        let shared: Vec<MpcField<Fr>> = fp.king_share_f64s(&xs, rng).unwrap();
+        let sum = shared.iter().fold(MpcField::<Fr>::from(0u64), |a, b| a + b);
+        let mut opened = fp.reveal_f64s(shared);
+        opened.extend(fp.reveal_f64s(vec![sum]));
+        opened
+    });
+    for out in outs {
+        assert_eq!(out[..4], xs);
+        assert_eq!(out[4], xs.iter().sum::<f64>());
+    }
+}
+
+#[test]
+#[should_panic(expected = "Reveal budget exceeded")]
+fn openings_are_charged() {
+    run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        let fp = FixedPoint::new(16);
+        budget::set_reveal_budget(Some(RevealBudget::abort_after(2)));
+        let shared: Vec<MpcField<Fr>> = fp.king_share_f64s(&[1.0, 2.0, 3.0], rng).unwrap();
+        fp.reveal_f64s(shared);
+    });
+}
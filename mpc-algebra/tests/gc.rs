@@ -0,0 +1,82 @@
+//! Garbled circuits between two parties, with both run in-process.
+use ark_bls12_377::Fr;
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use mpc_algebra::gc::{self, CircuitBuilder, Wire};
+use mpc_algebra::honest_but_curious::MpcField;
+use mpc_algebra::Reveal;
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_test_utils::run_parties;
+use rand::Rng;
+
+fn bits(x: u64, n: usize) -> Vec<bool> {
+    (0..n).map(|i| (x >> i) & 1 == 1).collect()
+}
+
+#[test]
+fn garbled_matches_plain() {
+    run_parties(2, || {
+        // every party draws the same inputs, and uses its own
+        let rng = &mut ark_std::test_rng();
+        let (x, y): (u32, u32) = (rng.gen(), rng.gen());
+
+        let mut b = CircuitBuilder::new();
+        let xs = b.inputs(gc::GARBLER, 32);
+        let ys = b.inputs(gc::EVALUATOR, 32);
+        let below = b.less_than(&xs, &ys);
+        let max = b.mux_word(below, &xs, &ys);
+        // a deep circuit: 8 * x + y, by repeated addition
+        let mut acc = ys.clone();
+        for _ in 0..8 {
+            acc = b.add(&acc, &xs)[..32].to_vec();
+        }
+        let any: Wire = xs.iter().fold(b.constant(false), |a, x| b.or(a, *x));
+        let mut outputs = vec![below, any];
+        outputs.extend(max);
+        outputs.extend(acc);
+        let circuit = b.finish(outputs);
+
+        let mine = if Net::party_id() == gc::GARBLER { x } else { y };
+        let out = gc::run(&circuit, &bits(mine as u64, 32), rng);
+        let plain = [bits(x as u64, 32), bits(y as u64, 32)];
+        assert_eq!(out, circuit.evaluate([&plain[0], &plain[1]]));
+        assert_eq!(out[0], x < y);
+        assert_eq!(out[2..34].to_vec(), bits(x.max(y) as u64, 32));
+        let expected = y.wrapping_add(x.wrapping_mul(8));
+        assert_eq!(out[34..].to_vec(), bits(expected as u64, 32));
+
+        // the outputs are XOR shared, and nothing is sent after the circuit runs
+        let shares = gc::run_shared(&circuit, &bits(mine as u64, 32), rng);
+        let all = Net::broadcast_bytes(&shares.iter().map(|b| *b as u8).collect::<Vec<_>>());
+        let opened: Vec<bool> = all[0].iter().zip(&all[1]).map(|(a, b)| a != b).collect();
+        assert_eq!(opened, out);
+    });
+}
+
+#[test]
+fn shared_field_elements() {
+    run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        let plain = [Fr::rand(rng), Fr::rand(rng)];
+        let xs: Vec<MpcField<Fr>> = plain
+            .iter()
+            .map(|x| MpcField::king_share(*x, rng))
+            .collect();
+        let outs = gc::apply(
+            &xs,
+            |b, v| {
+                let low: Vec<Wire> = v[0][..64]
+                    .iter()
+                    .zip(&v[1][..64])
+                    .map(|(x, y)| b.xor(*x, *y))
+                    .collect();
+                vec![vec![b.less_than(&v[0], &v[1])], low]
+            },
+            rng,
+        )
+        .reveal();
+        assert_eq!(outs[0], Fr::from((plain[0] < plain[1]) as u64));
+        let low = |x: Fr| x.into_repr().as_ref()[0];
+        assert_eq!(outs[1], Fr::from(low(plain[0]) ^ low(plain[1])));
+        assert!(outs[1].into_repr().to_bits_le()[64..].iter().all(|b| !b));
+    });
+}
@@ -0,0 +1,130 @@
+//! Hierarchical sharing among committees, with every party run in-process.
+use ark_bls12_377::Fr;
+use ark_ff::UniformRand;
+use mpc_algebra::channel::MpcSerNet;
+use mpc_algebra::honest_but_curious::MpcField;
+use mpc_algebra::share::hierarchy::{
+    committees, set_committees, Committees, HierFieldShare, HierarchyError,
+};
+use mpc_algebra::Reveal;
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_test_utils::run_parties;
+
+fn values(n: usize) -> Vec<Fr> {
+    let rng = &mut ark_std::test_rng();
+    (0..n).map(|_| Fr::rand(rng)).collect()
+}
+
+#[test]
+fn opens_linear_combinations() {
+    let outs = run_parties(9, || {
+        let rng = &mut ark_std::test_rng();
+        set_committees(Some(Committees::uniform(3, 3, 1)));
+        let xs = HierFieldShare::party_share_batch(4, values(2), rng);
+        let mut y = xs[0];
+        y.scale(&Fr::from(3u64)).sub(&xs[1]).shift(&Fr::from(7u64));
+        let mut opened = HierFieldShare::batch_open(xs);
+        opened.push(y.open());
+        opened
+    });
+    let v = values(2);
+    for opened in outs {
+        assert_eq!(
+            opened,
+            vec![v[0], v[1], v[0] * Fr::from(3u64) - v[1] + Fr::from(7u64)]
+        );
+    }
+}
+
+#[test]
+fn committee_share_alone_is_random() {
+    let outs = run_parties(6, || {
+        let rng = &mut ark_std::test_rng();
+        set_committees(Some(Committees::new(vec![2, 4], vec![1, 2])));
+        let x = HierFieldShare::party_share_batch(0, vec![Fr::from(5u64)], rng)[0];
+        let all = Net::broadcast(&x.val);
+        let c = committees();
+        let first: Vec<_> = c.members(0).map(|p| Some(all[p])).collect();
+        let second: Vec<_> = c.members(1).map(|p| Some(all[p])).collect();
+        (
+            c.reconstruct(0, &first).unwrap(),
+            c.reconstruct(1, &second).unwrap(),
+        )
+    });
+    let (a, b) = outs[0];
+    assert_ne!(a, Fr::from(5u64));
+    assert_eq!(a + b, Fr::from(5u64));
+}
+
+#[test]
+fn tolerates_missing_members() {
+    let c = Committees::uniform(2, 4, 1);
+    // 2 + 3x, at 1..=4
+    let shares: Vec<Fr> = [5u64, 8, 11, 14].iter().map(|&s| Fr::from(s)).collect();
+    let with = |present: &[usize]| -> Vec<Option<Fr>> {
+        (0..4)
+            .map(|i| {
+                if present.contains(&i) {
+                    Some(shares[i])
+                } else {
+                    None
+                }
+            })
+            .collect()
+    };
+    assert_eq!(c.reconstruct(1, &with(&[1, 3])), Ok(Fr::from(2u64)));
+    assert_eq!(c.reconstruct(1, &with(&[0, 1, 2, 3])), Ok(Fr::from(2u64)));
+    assert_eq!(
+        c.reconstruct(1, &with(&[2])),
+        Err(HierarchyError::TooFew {
+            committee: 1,
+            needed: 2,
+            present: 1
+        })
+    );
+    let mut bad = with(&[0, 1, 2]);
+    bad[2] = Some(Fr::from(12u64));
+    assert_eq!(
+        c.reconstruct(1, &bad),
+        Err(HierarchyError::Inconsistent {
+            committee: 1,
+            member: 2
+        })
+    );
+}
+
+#[test]
+fn multiplies_as_additive() {
+    let outs = run_parties(9, || {
+        let rng = &mut ark_std::test_rng();
+        set_committees(Some(Committees::new(vec![3, 2, 4], vec![1, 0, 2])));
+        let xs = HierFieldShare::party_share_batch(8, values(2), rng);
+        let a = MpcField::Shared(xs[0].to_additive());
+        let b = MpcField::Shared(xs[1].to_additive());
+        let product = match a * b {
+            MpcField::Shared(s) => s,
+            MpcField::Public(_) => unreachable!(),
+        };
+        let back = HierFieldShare::from_additive(vec![product], rng);
+        ((a * b).reveal(), back[0].open())
+    });
+    let v = values(2);
+    for (direct, back) in outs {
+        assert_eq!(direct, v[0] * v[1]);
+        assert_eq!(back, v[0] * v[1]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "inconsistent")]
+fn detects_a_bad_share() {
+    run_parties(4, || {
+        let rng = &mut ark_std::test_rng();
+        set_committees(Some(Committees::uniform(1, 4, 1)));
+        let mut x = HierFieldShare::party_share_batch(0, vec![Fr::from(1u64)], rng)[0];
+        if Net::party_id() == 3 {
+            x.shift(&Fr::from(1u64));
+        }
+        x.open();
+    });
+}
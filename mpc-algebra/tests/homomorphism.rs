@@ -0,0 +1,305 @@
+//! Random arithmetic circuits evaluated on shares open to the plain result, under every scheme.
+//!
+//! Each case draws inputs (biased towards edge values such as 0, 1 and -1), shares some of them
+//! and makes the rest public, evaluates a random circuit over them both on the shares and in the
+//! clear, and compares every wire. All parties run in-process.
+use ark_bls12_377::{Fr, G1Projective};
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, One, PrimeField, Zero};
+use mpc_algebra::{
+    msm::NaiveMsm,
+    share::{
+        add::{AdditiveFieldShare, AdditiveGroupShare},
+        field::FieldShare,
+        group::GroupShare,
+        gsz20::{field::GszFieldShare, group::GszGroupShare},
+        spdz::{SpdzFieldShare, SpdzGroupShare},
+    },
+    MpcField, MpcGroup, Reveal,
+};
+use mpc_test_utils::run_parties;
+use proptest::prelude::*;
+
+type G = G1Projective;
+
+/// Field values, with the edges of the field well represented.
+fn field_value() -> impl Strategy<Value = Fr> {
+    let half = Fr::from(2u8).inverse().unwrap();
+    prop_oneof![
+        Just(Fr::zero()),
+        Just(Fr::one()),
+        Just(-Fr::one()),
+        Just(Fr::from(2u8)),
+        // (p + 1) / 2 and (p - 1) / 2
+        Just(half),
+        Just(-half),
+        // 2^k, which scalar multiplication special-cases
+        (1u32..64).prop_map(|k| Fr::from(2u8).pow(&[k as u64])),
+        any::<u64>().prop_map(Fr::from),
+        any::<[u8; 32]>().prop_map(|b| Fr::from_le_bytes_mod_order(&b)),
+        any::<[u8; 32]>().prop_map(|b| -Fr::from_le_bytes_mod_order(&b)),
+    ]
+}
+
+/// An input: its value, and whether it is shared (rather than public).
+fn input<T: std::fmt::Debug + Clone>(
+    value: impl Strategy<Value = T>,
+) -> impl Strategy<Value = (T, bool)> {
+    (value, prop::bool::weighted(0.75))
+}
+
+/// A gate, by the indices of the wires it reads; the wire it writes comes next.
+#[derive(Clone, Debug)]
+enum Gate {
+    Add(usize, usize),
+    Sub(usize, usize),
+    Mul(usize, usize),
+    Neg(usize),
+    Square(usize),
+    /// Skipped if the divisor is zero.
+    Div(usize, usize),
+    Const(Fr),
+}
+
+fn gate() -> impl Strategy<Value = Gate> {
+    let i = any::<usize>;
+    prop_oneof![
+        1 => (i(), i()).prop_map(|(a, b)| Gate::Add(a, b)),
+        1 => (i(), i()).prop_map(|(a, b)| Gate::Sub(a, b)),
+        3 => (i(), i()).prop_map(|(a, b)| Gate::Mul(a, b)),
+        1 => i().prop_map(Gate::Neg),
+        1 => i().prop_map(Gate::Square),
+        1 => (i(), i()).prop_map(|(a, b)| Gate::Div(a, b)),
+        1 => field_value().prop_map(Gate::Const),
+    ]
+}
+
+/// Evaluate `gates` over `inputs`, returning every wire. Wire indices wrap around.
+///
+/// `lift` makes constants, and `is_zero(wires, i)` tells whether wire `i` is zero, for division.
+fn eval_field<T: Field>(
+    inputs: Vec<T>,
+    gates: &[Gate],
+    lift: impl Fn(Fr) -> T,
+    is_zero: impl Fn(&[T], usize) -> bool,
+) -> Vec<T> {
+    let mut w = inputs;
+    for g in gates {
+        let n = w.len();
+        let out = match *g {
+            Gate::Add(a, b) => w[a % n] + w[b % n],
+            Gate::Sub(a, b) => w[a % n] - w[b % n],
+            Gate::Mul(a, b) => w[a % n] * w[b % n],
+            Gate::Neg(a) => -w[a % n],
+            Gate::Square(a) => w[a % n].square(),
+            Gate::Div(a, b) if !is_zero(&w, b % n) => w[a % n] / w[b % n],
+            Gate::Div(a, _) => w[a % n],
+            Gate::Const(c) => lift(c),
+        };
+        w.push(out);
+    }
+    w
+}
+
+fn check_field<S: FieldShare<Fr>>(n_parties: usize, inputs: &[(Fr, bool)], gates: &[Gate]) {
+    let plain: Vec<Fr> = inputs.iter().map(|(x, _)| *x).collect();
+    let expected = eval_field(plain, gates, |c| c, |w, i| w[i].is_zero());
+    let revealed = run_parties(n_parties, || {
+        S::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let shared: Vec<MpcField<Fr, S>> = inputs
+            .iter()
+            .map(|&(x, shared)| {
+                if shared {
+                    MpcField::king_share(x, rng)
+                } else {
+                    MpcField::from_public(x)
+                }
+            })
+            .collect();
+        // which divisors are zero is known from the plain run
+        let out = eval_field(shared, gates, MpcField::from_public, |_, i| {
+            expected[i].is_zero()
+        })
+        .reveal();
+        S::deinit_protocol();
+        out
+    });
+    for r in revealed {
+        assert_eq!(r, expected);
+    }
+}
+
+/// A group gate: wires are group elements, and `scalars` holds field inputs.
+#[derive(Clone, Debug)]
+enum GroupGate {
+    Add(usize, usize),
+    Sub(usize, usize),
+    Neg(usize),
+    Double(usize),
+    /// Multiply by a scalar input, which may be shared.
+    Scale(usize, usize),
+}
+
+fn group_gate() -> impl Strategy<Value = GroupGate> {
+    let i = any::<usize>;
+    prop_oneof![
+        1 => (i(), i()).prop_map(|(a, b)| GroupGate::Add(a, b)),
+        1 => (i(), i()).prop_map(|(a, b)| GroupGate::Sub(a, b)),
+        1 => i().prop_map(GroupGate::Neg),
+        1 => i().prop_map(GroupGate::Double),
+        3 => (i(), i()).prop_map(|(a, s)| GroupGate::Scale(a, s)),
+    ]
+}
+
+fn eval_group<T, F>(points: Vec<T>, scalars: &[F], gates: &[GroupGate]) -> Vec<T>
+where
+    T: Clone + std::ops::Add<Output = T> + std::ops::Sub<Output = T> + std::ops::Neg<Output = T>,
+    for<'a> T: std::ops::Mul<&'a F, Output = T>,
+{
+    let mut w = points;
+    for g in gates {
+        let n = w.len();
+        let out = match *g {
+            GroupGate::Add(a, b) => w[a % n].clone() + w[b % n].clone(),
+            GroupGate::Sub(a, b) => w[a % n].clone() - w[b % n].clone(),
+            GroupGate::Neg(a) => -w[a % n].clone(),
+            GroupGate::Double(a) => w[a % n].clone() + w[a % n].clone(),
+            GroupGate::Scale(a, s) => w[a % n].clone() * &scalars[s % scalars.len()],
+        };
+        w.push(out);
+    }
+    w
+}
+
+/// A plain group element, for scaling by reference like the shared one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Plain(G);
+
+impl std::ops::Add for Plain {
+    type Output = Self;
+    fn add(self, o: Self) -> Self {
+        Plain(self.0 + o.0)
+    }
+}
+impl std::ops::Sub for Plain {
+    type Output = Self;
+    fn sub(self, o: Self) -> Self {
+        Plain(self.0 - o.0)
+    }
+}
+impl std::ops::Neg for Plain {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Plain(-self.0)
+    }
+}
+impl<'a> std::ops::Mul<&'a Fr> for Plain {
+    type Output = Self;
+    fn mul(self, s: &Fr) -> Self {
+        Plain(self.0.mul(s.into_repr()))
+    }
+}
+
+fn check_group<S>(
+    n_parties: usize,
+    points: &[(Fr, bool)],
+    scalars: &[(Fr, bool)],
+    gates: &[GroupGate],
+) where
+    S: GroupShare<G>,
+{
+    let g = G::prime_subgroup_generator();
+    // points are given by their discrete logs, so that zero and small multiples turn up
+    let plain_points: Vec<Plain> = points
+        .iter()
+        .map(|(x, _)| Plain(g.mul(x.into_repr())))
+        .collect();
+    let plain_scalars: Vec<Fr> = scalars.iter().map(|(x, _)| *x).collect();
+    let expected: Vec<G> = eval_group(plain_points.clone(), &plain_scalars, gates)
+        .into_iter()
+        .map(|p| p.0)
+        .collect();
+    let revealed = run_parties(n_parties, || {
+        S::FieldShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let ps: Vec<MpcGroup<G, S>> = points
+            .iter()
+            .zip(&plain_points)
+            .map(|(&(_, shared), p)| {
+                if shared {
+                    MpcGroup::king_share(p.0, rng)
+                } else {
+                    MpcGroup::from_public(p.0)
+                }
+            })
+            .collect();
+        let ss: Vec<MpcField<Fr, S::FieldShare>> = scalars
+            .iter()
+            .map(|&(x, shared)| {
+                if shared {
+                    MpcField::king_share(x, rng)
+                } else {
+                    MpcField::from_public(x)
+                }
+            })
+            .collect();
+        let out = eval_group(ps, &ss, gates).reveal();
+        S::FieldShare::deinit_protocol();
+        out
+    });
+    for r in revealed {
+        assert_eq!(r, expected);
+    }
+}
+
+fn field_case() -> impl Strategy<Value = (Vec<(Fr, bool)>, Vec<Gate>)> {
+    (
+        prop::collection::vec(input(field_value()), 1..5),
+        prop::collection::vec(gate(), 1..12),
+    )
+}
+
+fn group_case() -> impl Strategy<Value = (Vec<(Fr, bool)>, Vec<(Fr, bool)>, Vec<GroupGate>)> {
+    (
+        prop::collection::vec(input(field_value()), 1..4),
+        prop::collection::vec(input(field_value()), 1..4),
+        prop::collection::vec(group_gate(), 1..8),
+    )
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn additive_field((inputs, gates) in field_case()) {
+        check_field::<AdditiveFieldShare<Fr>>(2, &inputs, &gates);
+        check_field::<AdditiveFieldShare<Fr>>(3, &inputs, &gates);
+    }
+
+    #[test]
+    fn spdz_field((inputs, gates) in field_case()) {
+        check_field::<SpdzFieldShare<Fr>>(2, &inputs, &gates);
+        check_field::<SpdzFieldShare<Fr>>(3, &inputs, &gates);
+    }
+
+    #[test]
+    fn gsz20_field((inputs, gates) in field_case()) {
+        check_field::<GszFieldShare<Fr>>(3, &inputs, &gates);
+    }
+
+    #[test]
+    fn additive_group((points, scalars, gates) in group_case()) {
+        check_group::<AdditiveGroupShare<G, NaiveMsm<G>>>(2, &points, &scalars, &gates);
+    }
+
+    #[test]
+    fn spdz_group((points, scalars, gates) in group_case()) {
+        check_group::<SpdzGroupShare<G, NaiveMsm<G>>>(2, &points, &scalars, &gates);
+    }
+
+    #[test]
+    fn gsz20_group((points, scalars, gates) in group_case()) {
+        check_group::<GszGroupShare<G, NaiveMsm<G>>>(3, &points, &scalars, &gates);
+    }
+}
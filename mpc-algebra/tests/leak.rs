@@ -0,0 +1,73 @@
+//! The leak analysis, with every party run in-process.
+use ark_bls12_377::Fr;
+use ark_ff::{One, UniformRand};
+use mpc_algebra::honest_but_curious::MpcField;
+use mpc_algebra::leak::{self, LeakCheck, Reason};
+use mpc_algebra::Reveal;
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_test_utils::run_parties;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Open the values that `f` computes from party 0's `a` and `b`, and party 1's `c`, and return
+/// the reasons for which the king flagged them.
+///
+/// With the placeholder multiplication triples, other parties' shares of products are zero, and
+/// so untracked.
+fn flags(
+    f: impl Fn(MpcField<Fr>, MpcField<Fr>, MpcField<Fr>) -> MpcField<Fr> + Sync,
+) -> Vec<Reason> {
+    let all = run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        // each party shares with its own randomness, or the shares could coincide
+        let share_rng = &mut StdRng::seed_from_u64(Net::party_id() as u64);
+        leak::enable(LeakCheck::default());
+        let ab = MpcField::party_share_batch(0, vec![Fr::rand(rng), Fr::rand(rng)], share_rng);
+        let c = MpcField::party_share_batch(1, vec![Fr::rand(rng)], share_rng);
+        f(ab[0], ab[1], c[0]).reveal();
+        leak::disable()
+            .into_iter()
+            .map(|finding| finding.reason)
+            .collect::<Vec<_>>()
+    });
+    all[0].clone()
+}
+
+#[test]
+fn flags_single_inputs() {
+    assert_eq!(flags(|a, _, _| a), vec![Reason::FewInputs(1)]);
+    assert_eq!(
+        flags(|a, _, _| a * a + MpcField::one()),
+        vec![Reason::FewInputs(1)]
+    );
+    assert_eq!(flags(|_, _, c| -c), vec![Reason::FewInputs(1)]);
+}
+
+#[test]
+fn flags_single_owners() {
+    assert_eq!(flags(|a, b, _| a * b), vec![Reason::SingleOwner(0)]);
+    assert_eq!(flags(|a, b, _| a / b - a), vec![Reason::SingleOwner(0)]);
+}
+
+#[test]
+fn passes_mixed_and_masked() {
+    assert_eq!(flags(|a, _, c| a + c), vec![]);
+    assert_eq!(flags(|a, b, c| a * b * c), vec![]);
+    assert_eq!(
+        flags(|a, _, _| a + MpcField::rand(&mut ark_std::test_rng())),
+        vec![]
+    );
+    // public values carry nothing
+    assert_eq!(flags(|_, _, _| MpcField::from_public(Fr::one())), vec![]);
+}
+
+#[test]
+fn off_by_default() {
+    let findings = run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        let a = MpcField::<Fr>::king_share(Fr::rand(rng), rng);
+        (a + MpcField::one()).reveal();
+        (leak::enabled(), leak::findings())
+    });
+    assert_eq!(findings, vec![(false, vec![]), (false, vec![])]);
+}
@@ -0,0 +1,76 @@
+//! Sharings of zero and masks, from preprocessing and from PRSS, with every party run in-process.
+use ark_bls12_377::Fr;
+use ark_ff::{UniformRand, Zero};
+use mpc_algebra::{
+    batch_mask, mask, rerandomize, unmask, AdditiveFieldShare, DummyFieldTripleSource, FieldShare,
+    GszFieldShare, MaskSource, Prss, RandomnessError, RandomnessPool, Reveal, SpdzFieldShare,
+};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_test_utils::run_parties;
+
+/// Mask, unmask and re-randomize with `source`, which must hold at least 3 masks and 2 zeros.
+fn check<S: FieldShare<Fr>, M: MaskSource<Fr, S>>(source: &mut M) {
+    let rng = &mut ark_std::test_rng();
+    let x = Fr::rand(rng);
+    let shared = S::king_share(x, rng);
+
+    let (c, m) = mask(shared, source).unwrap();
+    assert_ne!(c, x);
+    assert_eq!(unmask(c, &m).reveal(), x);
+    // anything computed from the opening, in public, unmasks to the same function of x
+    assert_eq!(unmask(c + Fr::from(5u8), &m).reveal(), x + Fr::from(5u8));
+
+    let ys: Vec<Fr> = (0..2).map(|_| Fr::rand(rng)).collect();
+    let (cs, ms) = batch_mask(S::king_share_batch(ys.clone(), rng), source).unwrap();
+    for ((c, m), y) in cs.into_iter().zip(&ms).zip(ys) {
+        assert_eq!(unmask(c, m).reveal(), y);
+    }
+
+    let zero = source.zero_share().unwrap();
+    assert!(!zero.unwrap_as_public().is_zero());
+    assert!(zero.reveal().is_zero());
+    let again = rerandomize(shared, source).unwrap();
+    assert_ne!(again.unwrap_as_public(), shared.unwrap_as_public());
+    assert_eq!(again.reveal(), x);
+}
+
+fn check_pool<S: FieldShare<Fr>>() {
+    let rng = &mut ark_std::test_rng();
+    let mut pool =
+        RandomnessPool::<Fr, S>::generate(3, 0, &mut DummyFieldTripleSource::default(), rng);
+    pool.generate_zeros(2, rng);
+    assert_eq!(pool.zeros_left(), 2);
+    check::<S, _>(&mut pool);
+    assert_eq!((pool.shares_left(), pool.zeros_left()), (0, 0));
+    assert!(matches!(
+        pool.zero_share(),
+        Err(RandomnessError::Exhausted { kind: "zeros", .. })
+    ));
+}
+
+#[test]
+fn additive_pool() {
+    run_parties(3, check_pool::<AdditiveFieldShare<Fr>>);
+}
+
+#[test]
+fn spdz_pool() {
+    run_parties(2, check_pool::<SpdzFieldShare<Fr>>);
+}
+
+#[test]
+fn gsz_prss() {
+    run_parties(3, || {
+        assert!(matches!(
+            MaskSource::<Fr, GszFieldShare<Fr>>::zero_share(&mut Prss),
+            Err(RandomnessError::NoPrss)
+        ));
+        GszFieldShare::<Fr>::init_protocol();
+        check::<GszFieldShare<Fr>, _>(&mut Prss);
+        // PRSS's zeros differ from draw to draw
+        let zeros = MaskSource::<Fr, GszFieldShare<Fr>>::zero_shares(&mut Prss, 2).unwrap();
+        assert_ne!(zeros[0], zeros[1]);
+        assert_eq!(zeros[0].degree, 2 * (Net::n_parties() - 1) / 2);
+        GszFieldShare::<Fr>::deinit_protocol();
+    });
+}
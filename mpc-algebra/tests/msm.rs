@@ -0,0 +1,184 @@
+//! Multi-scalar multiplications of shared bases by public scalars, and over fixed-base tables, with
+//! every party run in-process.
+use ark_bls12_377::{Bls12_377, Fr, G1Affine, G1Projective};
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand};
+use mpc_algebra::{
+    channel::MpcSerNet,
+    fixed_base::{FixedBaseMsm, FixedBaseTable},
+    msm::NaiveMsm,
+    share::{
+        add::{AdditiveGroupShare, AdditivePairingShare},
+        group::GroupShare,
+        gsz20::{group::GszGroupShare, GszPairingShare},
+        pairing::PairingShare,
+        spdz::{mac_share, SpdzFieldShare, SpdzGroupShare, SpdzPairingShare},
+    },
+    MpcField, MpcPairingEngine, Reveal,
+};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_test_utils::run_parties;
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+const SIZE: usize = 20;
+
+fn test_share<S: GroupShare<G1Projective>>(n: usize) {
+    run_parties(n, || {
+        S::FieldShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let bases: Vec<G1Projective> = (0..SIZE).map(|_| G1Projective::rand(rng)).collect();
+        let scalars: Vec<Fr> = (0..SIZE).map(|_| Fr::rand(rng)).collect();
+        let expected: G1Projective = bases
+            .iter()
+            .zip(&scalars)
+            .map(|(b, s)| b.mul(s.into_repr()))
+            .sum();
+        let shared = S::king_share_batch(bases, rng);
+        assert_eq!(
+            S::msm_pub_scalar_shared_base(&shared, &scalars).reveal(),
+            expected
+        );
+        assert_eq!(
+            S::msm_pub_scalar_shared_base(&[], &[]).reveal(),
+            G1Projective::default()
+        );
+        // and public bases with shared scalars
+        let bases: Vec<G1Projective> = (0..SIZE).map(|_| G1Projective::rand(rng)).collect();
+        let expected: G1Projective = bases
+            .iter()
+            .zip(&scalars)
+            .map(|(b, s)| b.mul(s.into_repr()))
+            .sum();
+        let shared = S::FieldShare::king_share_batch(scalars.clone(), rng);
+        assert_eq!(S::multi_scale_pub_group(&bases, &shared).reveal(), expected);
+        let by = S::multi_scale_pub_group_by(&shared, |xs| {
+            bases
+                .iter()
+                .zip(xs)
+                .map(|(b, x)| b.mul(x.into_repr()))
+                .sum()
+        });
+        assert_eq!(S::batch_open(vec![by]), vec![expected]);
+        S::FieldShare::deinit_protocol();
+    });
+}
+
+fn test_wire<PS: PairingShare<Bls12_377>>(n: usize) {
+    type ME<PS> = MpcPairingEngine<Bls12_377, PS>;
+    run_parties(n, || {
+        PS::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let bases: Vec<G1Affine> = (0..SIZE)
+            .map(|_| G1Projective::rand(rng).into_affine())
+            .collect();
+        let scalars: Vec<Fr> = (0..SIZE).map(|_| Fr::rand(rng)).collect();
+        let expected = G1Affine::multi_scalar_mul(&bases, &scalars);
+        // one base is public, and the rest shared
+        let mut shared: Vec<<ME<PS> as PairingEngine>::G1Affine> = bases[1..]
+            .iter()
+            .map(|b| Reveal::king_share(*b, rng))
+            .collect();
+        shared.insert(0, Reveal::from_public(bases[0]));
+        let scalars: Vec<MpcField<Fr, PS::FrShare>> =
+            scalars.into_iter().map(MpcField::from_public).collect();
+        let msm = <ME<PS> as PairingEngine>::G1Affine::multi_scalar_mul(&shared, &scalars);
+        assert_eq!(msm.reveal(), expected);
+        PS::FrShare::deinit_protocol();
+    });
+}
+
+/// Tables of every width agree with the plain MSM, on edge scalars as well as random ones.
+#[test]
+fn fixed_base_plain() {
+    let rng = &mut ark_std::test_rng();
+    let bases: Vec<G1Affine> = (0..SIZE)
+        .map(|_| G1Projective::rand(rng).into_affine())
+        .collect();
+    let mut scalars: Vec<Fr> = (0..SIZE - 3).map(|_| Fr::rand(rng)).collect();
+    scalars.extend(vec![Fr::from(0u8), Fr::from(1u8), -Fr::from(1u8)]);
+    let expected = G1Affine::multi_scalar_mul(&bases, &scalars);
+    for width in &[1, 3, 4, 7, 8, 13, 16] {
+        let table = FixedBaseTable::new(&bases, *width);
+        assert_eq!(table.len(), SIZE);
+        assert_eq!(table.msm(&scalars), expected, "width {}", width);
+        assert_eq!(
+            table.msm(&scalars[..5]),
+            G1Affine::multi_scalar_mul(&bases[..5], &scalars[..5])
+        );
+    }
+}
+
+fn test_fixed_base_wire<PS: PairingShare<Bls12_377>>(n: usize) {
+    type ME<PS> = MpcPairingEngine<Bls12_377, PS>;
+    type A<PS> = <ME<PS> as PairingEngine>::G1Affine;
+    run_parties(n, || {
+        PS::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let bases: Vec<G1Affine> = (0..SIZE)
+            .map(|_| G1Projective::rand(rng).into_affine())
+            .collect();
+        let scalars: Vec<Fr> = (0..SIZE).map(|_| Fr::rand(rng)).collect();
+        let expected = G1Affine::multi_scalar_mul(&bases, &scalars);
+        let mpc_bases: Vec<A<PS>> = bases.iter().map(|b| Reveal::from_public(*b)).collect();
+        let table = A::<PS>::fixed_base_table(&mpc_bases, 5);
+        let shared = MpcField::king_share_batch(scalars.clone(), rng);
+        let public: Vec<MpcField<Fr, PS::FrShare>> =
+            scalars.into_iter().map(MpcField::from_public).collect();
+        assert_eq!(A::<PS>::fixed_base_msm(&table, &shared).reveal(), expected);
+        assert_eq!(A::<PS>::fixed_base_msm(&table, &public).reveal(), expected);
+        PS::FrShare::deinit_protocol();
+    });
+}
+
+#[test]
+fn additive() {
+    // a single party holds the whole value as its share
+    test_share::<AdditiveGroupShare<G1Projective, NaiveMsm<G1Projective>>>(1);
+    test_share::<AdditiveGroupShare<G1Projective, NaiveMsm<G1Projective>>>(2);
+    test_share::<AdditiveGroupShare<G1Projective, NaiveMsm<G1Projective>>>(3);
+    test_wire::<AdditivePairingShare<Bls12_377>>(2);
+    test_fixed_base_wire::<AdditivePairingShare<Bls12_377>>(2);
+    test_fixed_base_wire::<AdditivePairingShare<Bls12_377>>(3);
+}
+
+#[test]
+fn spdz() {
+    test_share::<SpdzGroupShare<G1Projective, NaiveMsm<G1Projective>>>(1);
+    test_share::<SpdzGroupShare<G1Projective, NaiveMsm<G1Projective>>>(2);
+    test_wire::<SpdzPairingShare<Bls12_377>>(3);
+    test_fixed_base_wire::<SpdzPairingShare<Bls12_377>>(2);
+}
+
+/// One party lies about its share of an MSM when opening; the others' MAC check must catch it.
+#[test]
+fn spdz_group_mac_check() {
+    type S = SpdzGroupShare<G1Projective, NaiveMsm<G1Projective>>;
+    let caught = run_parties(3, || {
+        let rng = &mut ark_std::test_rng();
+        let bases: Vec<G1Projective> = (0..SIZE).map(|_| G1Projective::rand(rng)).collect();
+        let scalars: Vec<Fr> = (0..SIZE).map(|_| Fr::rand(rng)).collect();
+        let shared = SpdzFieldShare::king_share_batch(scalars, rng);
+        let a = S::multi_scale_pub_group(&bases, &shared);
+        if Net::party_id() == 1 {
+            // Run the opening by hand. The MAC key is public in this prototype, so our MAC share
+            // is our share of the value.
+            let sh = a.unwrap_as_public();
+            let lie = sh + G1Projective::prime_subgroup_generator();
+            let x: G1Projective = Net::broadcast(&lie).iter().sum();
+            let dx_t = x.mul(mac_share::<Fr>().into_repr()) - sh;
+            Net::atomic_broadcast(&dx_t);
+            false
+        } else {
+            catch_unwind(AssertUnwindSafe(|| a.reveal())).is_err()
+        }
+    });
+    assert_eq!(caught, vec![true, false, true]);
+}
+
+#[test]
+fn gsz20() {
+    test_share::<GszGroupShare<G1Projective, NaiveMsm<G1Projective>>>(3);
+    test_wire::<GszPairingShare<Bls12_377>>(3);
+    test_fixed_base_wire::<GszPairingShare<Bls12_377>>(3);
+}
@@ -0,0 +1,73 @@
+//! Multilinear extensions with shared evaluations, with every party run in-process.
+use ark_bls12_377::Fr;
+use ark_ff::{UniformRand, Zero};
+use ark_poly::{
+    evaluations::multivariate::multilinear::eq_evaluations, DenseMultilinearExtension,
+    MultilinearExtension, SparseMultilinearExtension,
+};
+use mpc_algebra::honest_but_curious::MpcField;
+use mpc_algebra::Reveal;
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_test_utils::run_parties;
+
+type S = MpcField<Fr>;
+
+fn public(xs: &[Fr]) -> Vec<S> {
+    xs.iter().map(|x| S::from_public(*x)).collect()
+}
+
+#[test]
+fn shared_evaluations() {
+    run_parties(3, || {
+        // every party draws the same values
+        let rng = &mut ark_std::test_rng();
+        let plain = DenseMultilinearExtension::<Fr>::rand(5, rng);
+        let point: Vec<Fr> = (0..5).map(|_| Fr::rand(rng)).collect();
+        let shared = DenseMultilinearExtension::from_evaluations_vec(
+            5,
+            plain.iter().map(|v| S::king_share(*v, rng)).collect(),
+        );
+        let sent = Net::stats().bytes_sent;
+
+        // folding at a public point, as a sumcheck does, and tensor products with public
+        // polynomials, are local
+        let mut folded = shared.clone();
+        folded.fix_variables_in_place(&public(&point[..2]));
+        let eq = DenseMultilinearExtension::eq_extension(&public(&point[2..]));
+        let product = folded.tensor_product(&eq);
+        let weights = public(&eq_evaluations(&point));
+        let inner = shared
+            .iter()
+            .zip(&weights)
+            .fold(S::zero(), |acc, (v, w)| acc + *v * w);
+        assert_eq!(Net::stats().bytes_sent, sent);
+
+        assert_eq!(
+            folded.evaluations.reveal(),
+            plain.fix_variables(&point[..2]).evaluations
+        );
+        let eq_plain = DenseMultilinearExtension::eq_extension(&point[2..]);
+        assert_eq!(
+            product.evaluations.reveal(),
+            plain
+                .fix_variables(&point[..2])
+                .tensor_product(&eq_plain)
+                .evaluations
+        );
+        let value = plain.evaluate(&point).unwrap();
+        assert_eq!(inner.reveal(), value);
+        assert_eq!(shared.evaluate(&public(&point)).unwrap().reveal(), value);
+
+        let sparse_plain = SparseMultilinearExtension::<Fr>::rand_with_config(5, 4, rng);
+        let entries: Vec<(usize, S)> = sparse_plain
+            .evaluations
+            .iter()
+            .map(|(i, v)| (*i, S::king_share(*v, rng)))
+            .collect();
+        let sparse = SparseMultilinearExtension::from_evaluations(5, &entries);
+        assert_eq!(
+            sparse.evaluate(&public(&point)).unwrap().reveal(),
+            sparse_plain.evaluate(&point).unwrap()
+        );
+    });
+}
@@ -0,0 +1,182 @@
+//! Non-native arithmetic witnesses computed on shared values, with every party run in-process.
+use ark_bls12_377::{Fq, Fr};
+use ark_ff::{to_bytes, BigInteger, FromBytes, PrimeField, ToConstraintField, UniformRand};
+use mpc_algebra::honest_but_curious::MpcField;
+use mpc_algebra::nonnative::{
+    absorb, bit_decompose, decompose_limbs, reduce, split, NonNative, NonNativeParams,
+};
+use mpc_algebra::Reveal;
+use mpc_test_utils::run_parties;
+use num_bigint::BigUint;
+use rand::Rng;
+
+type S = MpcField<Fr>;
+type Q = MpcField<Fq>;
+
+fn big(x: Fr) -> BigUint {
+    BigUint::from_bytes_le(&x.into_repr().to_bytes_le())
+}
+
+fn field(x: &BigUint) -> Fr {
+    Fr::from_le_bytes_mod_order(&x.to_bytes_le())
+}
+
+/// A value below `modulus`.
+fn below<R: Rng>(modulus: &BigUint, rng: &mut R) -> BigUint {
+    let bytes: Vec<u8> = (0..modulus.bits() / 8 + 9).map(|_| rng.gen()).collect();
+    BigUint::from_bytes_le(&bytes) % modulus
+}
+
+#[test]
+fn decomposition() {
+    run_parties(3, || {
+        // every party draws the same values
+        let rng = &mut ark_std::test_rng();
+        let plain: Vec<u128> = (0..4).map(|_| rng.gen::<u128>() >> 28).collect();
+        let xs: Vec<S> = plain
+            .iter()
+            .map(|x| S::king_share(Fr::from(*x), rng))
+            .collect();
+
+        let bits = bit_decompose(&xs, 100, rng).reveal();
+        let limbs = decompose_limbs(&xs, 100, 32, rng).reveal();
+        let (low, high) = split(&xs, 100, 37, rng);
+        let (low, high) = (low.reveal(), high.reveal());
+        for (i, x) in plain.iter().enumerate() {
+            let expected: Vec<Fr> = (0..100).map(|j| Fr::from((x >> j) & 1)).collect();
+            assert_eq!(bits[i], expected);
+            let expected: Vec<Fr> = (0..4)
+                .map(|j| Fr::from((x >> (32 * j)) & 0xffff_ffff))
+                .collect();
+            assert_eq!(limbs[i], expected);
+            assert_eq!(low[i], Fr::from(x & ((1 << 37) - 1)));
+            assert_eq!(high[i], Fr::from(x >> 37));
+        }
+    });
+}
+
+#[test]
+fn reduction_by_public_modulus() {
+    run_parties(3, || {
+        let rng = &mut ark_std::test_rng();
+        let moduli = [
+            BigUint::from(3u8),
+            BigUint::from(1_000_003u32),
+            BigUint::from(u128::MAX >> 31) - 7u8,
+        ];
+        for modulus in &moduli {
+            let plain: Vec<BigUint> = (0..3)
+                .map(|_| below(&(BigUint::from(1u8) << 110), rng))
+                .collect();
+            let xs: Vec<S> = plain.iter().map(|x| S::king_share(field(x), rng)).collect();
+            let (q, r) = reduce(&xs, 110, modulus, rng);
+            let (q, r) = (q.reveal(), r.reveal());
+            for (i, x) in plain.iter().enumerate() {
+                assert_eq!(big(q[i]), x / modulus);
+                assert_eq!(big(r[i]), x % modulus);
+            }
+        }
+    });
+}
+
+fn check_products(params: &NonNativeParams) {
+    run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        let p = params.modulus();
+        let mut plain: Vec<(BigUint, BigUint)> =
+            (0..1).map(|_| (below(p, rng), below(p, rng))).collect();
+        // the largest product
+        plain.push((p - 1u8, p - 1u8));
+        let share = |x: &BigUint, rng: &mut _| -> Vec<S> {
+            let limbs: Vec<Fr> = params.to_limbs(x);
+            limbs.into_iter().map(|l| S::king_share(l, rng)).collect()
+        };
+        let (a, b): (Vec<_>, Vec<_>) = plain
+            .iter()
+            .map(|(a, b)| (share(a, rng), share(b, rng)))
+            .unzip();
+
+        let reductions = params.mul(&a, &b, rng);
+        for ((a, b), reduction) in plain.iter().zip(reductions) {
+            let q = reduction.quotient.reveal();
+            let r = reduction.remainder.reveal();
+            assert_eq!(q.len(), params.num_limbs());
+            assert_eq!(r.len(), params.num_limbs());
+            for limb in q.iter().chain(&r) {
+                assert!(big(*limb).bits() as usize <= params.limb_bits());
+            }
+            assert_eq!(params.from_limbs(&q), a * b / p);
+            assert_eq!(params.from_limbs(&r), a * b % p);
+        }
+    });
+}
+
+#[test]
+fn secp256k1_products() {
+    let p = BigUint::parse_bytes(
+        b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+        16,
+    )
+    .unwrap();
+    check_products(&NonNativeParams::new(p, 64));
+}
+
+#[test]
+fn base_field_products() {
+    check_products(&NonNativeParams::for_field::<Fq>(56));
+}
+
+#[test]
+fn base_field_limbs() {
+    let params = NonNativeParams::for_field::<Fq>(56);
+    let rng = &mut ark_std::test_rng();
+    let mut plain: Vec<Fq> = (0..2).map(|_| Fq::rand(rng)).collect();
+    plain.push(Fq::from(0u8));
+    plain.push(-Fq::from(1u8));
+    run_parties(3, || {
+        let rng = &mut ark_std::test_rng();
+        let shared: Vec<_> = Q::king_share_batch(plain.clone(), rng)
+            .into_iter()
+            .map(|x| NonNative::new(x, 56))
+            .collect();
+        let public: Vec<_> = plain
+            .iter()
+            .map(|x| NonNative::new(Q::Public(*x), 56))
+            .collect();
+
+        let limbs: Vec<S> = shared.to_field_elements().unwrap();
+        let expected: Vec<Fr> = plain
+            .iter()
+            .flat_map(|x| params.to_limbs(&BigUint::from_bytes_le(&to_bytes![x].unwrap())))
+            .collect();
+        assert_eq!(limbs.clone().reveal(), expected);
+        let public_limbs: Vec<S> = public.to_field_elements().unwrap();
+        assert_eq!(public_limbs.reveal(), expected);
+
+        let packed: Vec<S> = absorb(&shared);
+        let expected_packed: Vec<S> = absorb(&public);
+        assert!(packed.len() < limbs.len());
+        assert_eq!(packed.reveal(), expected_packed.reveal());
+
+        let values: Vec<_> = shared.iter().map(|x| x.value).collect();
+        let bits: Vec<Vec<S>> = params.share_bits(&values, rng);
+        for (x, bits) in plain.iter().zip(bits) {
+            let expected: Vec<Fr> = x
+                .into_repr()
+                .to_bits_le()
+                .into_iter()
+                .take(377)
+                .map(Fr::from)
+                .collect();
+            assert_eq!(bits.reveal(), expected);
+        }
+    });
+}
+
+#[test]
+fn public_bytes() {
+    let x = Fq::rand(&mut ark_std::test_rng());
+    let bytes = to_bytes![NonNative::new(Q::Public(x), 56)].unwrap();
+    assert_eq!(bytes, to_bytes![x].unwrap());
+    assert_eq!(Q::read(&bytes[..]).unwrap(), Q::Public(x));
+}
@@ -0,0 +1,175 @@
+//! Pairings of shared points, with every party run in-process.
+use ark_bls12_377::Fr;
+use ark_bls12_377::{Bls12_377, G1Projective, G2Projective};
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, PrimeField, UniformRand};
+use mpc_algebra::budget::{self, RevealBudget};
+use mpc_algebra::share::{
+    add::AdditivePairingShare, gsz20::GszPairingShare, pairing::PairingShare,
+    spdz::SpdzPairingShare,
+};
+use mpc_algebra::{
+    MpcField, MpcG1Affine, MpcG1Projective, MpcG2Affine, MpcPairingEngine, Reveal,
+};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_test_utils::run_parties;
+use mpc_trait::MpcWire;
+
+type G1 = <Bls12_377 as PairingEngine>::G1Affine;
+type G2 = <Bls12_377 as PairingEngine>::G2Affine;
+type Fqk = <Bls12_377 as PairingEngine>::Fqk;
+
+fn points() -> (Vec<G1>, Vec<G2>) {
+    let rng = &mut ark_std::test_rng();
+    (
+        (0..3)
+            .map(|_| G1Projective::rand(rng).into_affine())
+            .collect(),
+        (0..3)
+            .map(|_| G2Projective::rand(rng).into_affine())
+            .collect(),
+    )
+}
+
+/// `e(p0, q0)`, `e(p1, q1)`, with one side shared, and the product of all three pairings, with
+/// the last both shared if `both_shared`.
+fn shared_pairings<PS: PairingShare<Bls12_377>>(both_shared: bool) -> Vec<Fqk> {
+    PS::FrShare::init_protocol();
+    let rng = &mut ark_std::test_rng();
+    let (ps, qs) = points();
+    let p0 = MpcG1Affine::<Bls12_377, PS>::king_share(ps[0], rng);
+    let q1 = MpcG2Affine::<Bls12_377, PS>::king_share(qs[1], rng);
+    let (p2, q2) = if both_shared {
+        (
+            MpcG1Affine::king_share(ps[2], rng),
+            MpcG2Affine::king_share(qs[2], rng),
+        )
+    } else {
+        (
+            MpcG1Affine::from_public(ps[2]),
+            MpcG2Affine::from_public(qs[2]),
+        )
+    };
+    // only the results are opened
+    budget::set_reveal_budget(Some(RevealBudget::abort_after(3)));
+    let a = MpcPairingEngine::<Bls12_377, PS>::pairing(p0, MpcG2Affine::from_public(qs[0]));
+    let b = MpcPairingEngine::<Bls12_377, PS>::pairing(MpcG1Affine::from_public(ps[1]), q1);
+    assert!(a.is_shared() && b.is_shared());
+    let c = MpcPairingEngine::<Bls12_377, PS>::product_of_shared_pairings(&[
+        (p0, MpcG2Affine::from_public(qs[0])),
+        (MpcG1Affine::from_public(ps[1]), q1),
+        (p2, q2),
+    ]);
+    let outs = vec![a.reveal(), b.reveal(), c.reveal()];
+    budget::set_reveal_budget(None);
+    PS::FrShare::deinit_protocol();
+    outs
+}
+
+fn check(outs: Vec<Vec<Fqk>>) {
+    let (ps, qs) = points();
+    let e: Vec<Fqk> = (0..3).map(|i| Bls12_377::pairing(ps[i], qs[i])).collect();
+    for out in outs {
+        assert_eq!(out, vec![e[0], e[1], e[0] * e[1] * e[2]]);
+    }
+}
+
+#[test]
+fn additive() {
+    check(run_parties(2, || {
+        let mut outs = shared_pairings::<AdditivePairingShare<Bls12_377>>(false);
+        outs.truncate(2);
+        outs.extend(shared_pairings::<AdditivePairingShare<Bls12_377>>(true).pop());
+        outs
+    }));
+}
+
+#[test]
+fn spdz() {
+    check(run_parties(2, || {
+        shared_pairings::<SpdzPairingShare<Bls12_377>>(true)
+    }));
+}
+
+#[test]
+fn gsz() {
+    check(run_parties(3, || {
+        shared_pairings::<GszPairingShare<Bls12_377>>(false)
+    }));
+}
+
+/// Exponentiations of public and shared `GT` elements by public and shared scalars, and equality
+/// tests that open only their verdicts.
+fn gt_protocols<PS: PairingShare<Bls12_377>>() {
+    type E<PS> = MpcPairingEngine<Bls12_377, PS>;
+    PS::FrShare::init_protocol();
+    let rng = &mut ark_std::test_rng();
+    let (ps, qs) = points();
+    let s = Fr::rand(rng);
+    let g = Bls12_377::pairing(ps[0], qs[0]);
+    let expected = g.pow(s.into_repr());
+
+    let p0 = MpcG1Affine::<Bls12_377, PS>::king_share(ps[0], rng);
+    let q0 = MpcG2Affine::<Bls12_377, PS>::king_share(qs[0], rng);
+    let shared_s = MpcField::<Fr, PS::FrShare>::king_share(s, rng);
+    let public_s = MpcField::<Fr, PS::FrShare>::from_public(s);
+    let shared_g = E::<PS>::pairing(p0, MpcG2Affine::from_public(qs[0]));
+    let public_g = Reveal::from_public(g);
+    for (g, s) in [
+        (public_g, shared_s),
+        (shared_g, public_s),
+        (shared_g, shared_s),
+    ] {
+        assert_eq!(E::<PS>::pow_gt(g, s, rng).reveal(), expected);
+    }
+
+    // the same pairing, shared two ways, and another one
+    let other_g = E::<PS>::pairing(MpcG1Affine::from_public(ps[0]), q0);
+    let different = E::<PS>::pairing(p0, MpcG2Affine::from_public(qs[1]));
+    budget::set_reveal_budget(Some(RevealBudget::abort_after(2)));
+    assert!(E::<PS>::gt_eq(shared_g, other_g, rng));
+    assert!(!E::<PS>::gt_eq(shared_g, different, rng));
+    budget::set_reveal_budget(None);
+    assert!(E::<PS>::gt_eq(shared_g, public_g, rng));
+    PS::FrShare::deinit_protocol();
+}
+
+#[test]
+fn gt() {
+    run_parties(2, gt_protocols::<AdditivePairingShare<Bls12_377>>);
+    run_parties(2, gt_protocols::<SpdzPairingShare<Bls12_377>>);
+    run_parties(3, gt_protocols::<GszPairingShare<Bls12_377>>);
+}
+
+/// Shared and public points, normalized in one batch, locally.
+fn normalization<PS: PairingShare<Bls12_377>>() {
+    PS::FrShare::init_protocol();
+    let rng = &mut ark_std::test_rng();
+    let pubs: Vec<G1Projective> = (0..6).map(|_| G1Projective::rand(rng)).collect();
+    let mut points: Vec<MpcG1Projective<Bls12_377, PS>> = pubs
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            if i % 3 == 0 {
+                MpcG1Projective::from_public(*p)
+            } else {
+                MpcG1Projective::king_share(*p, rng)
+            }
+        })
+        .collect();
+    let sent = Net::stats().bytes_sent;
+    ProjectiveCurve::batch_normalization(&mut points);
+    assert_eq!(Net::stats().bytes_sent, sent);
+    assert!(points.iter().all(|p| p.is_normalized()));
+    for (p, q) in points.into_iter().zip(pubs) {
+        assert_eq!(p.reveal(), q);
+    }
+    PS::FrShare::deinit_protocol();
+}
+
+#[test]
+fn batch_normalization() {
+    run_parties(2, normalization::<AdditivePairingShare<Bls12_377>>);
+    run_parties(2, normalization::<SpdzPairingShare<Bls12_377>>);
+    run_parties(3, normalization::<GszPairingShare<Bls12_377>>);
+}
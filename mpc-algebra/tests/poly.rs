@@ -0,0 +1,150 @@
+//! Dense polynomials with shared coefficients, with every party run in-process.
+use ark_bls12_377::Fr;
+use ark_ff::{Field, UniformRand};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, GeneralEvaluationDomain, Polynomial,
+    UVPolynomial,
+};
+use mpc_algebra::honest_but_curious::MpcField;
+use mpc_algebra::Reveal;
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_test_utils::run_parties;
+
+type S = MpcField<Fr>;
+
+fn share<R: rand::Rng>(p: &DensePolynomial<Fr>, rng: &mut R) -> DensePolynomial<S> {
+    DensePolynomial::from_coefficients_vec(
+        p.coeffs.iter().map(|c| S::king_share(*c, rng)).collect(),
+    )
+}
+
+/// The number of messages this party takes part in while running `f`.
+fn messages<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let count = || {
+        let s = Net::stats();
+        s.broadcasts + s.to_king + s.from_king
+    };
+    let before = count();
+    let out = f();
+    (out, count() - before)
+}
+
+#[test]
+fn shared_arithmetic() {
+    run_parties(3, || {
+        // every party draws the same values
+        let rng = &mut ark_std::test_rng();
+        let (a, b) = (
+            DensePolynomial::<Fr>::rand(20, rng),
+            DensePolynomial::<Fr>::rand(12, rng),
+        );
+        let (sa, sb) = (share(&a, rng), share(&b, rng));
+        let pb = DensePolynomial::from_coefficients_vec(
+            b.coeffs.iter().map(|c| S::from_public(*c)).collect(),
+        );
+
+        assert_eq!((&sa + &sb).reveal(), &a + &b);
+        assert_eq!((&sa - &sb).reveal(), &a - &b);
+        assert_eq!((-sa.clone()).reveal(), -a.clone());
+        // a product of shared polynomials has its degree, though its leading zeros are shared
+        let product = &sa * &sb;
+        assert_eq!(product.degree(), 32);
+        assert_eq!(product.reveal(), &a * &b);
+        // and a product with a public one is local
+        let (product, sent) = messages(|| &sa * &pb);
+        assert_eq!(sent, 0);
+        assert_eq!(product.reveal(), &a * &b);
+
+        let x = Fr::rand(rng);
+        let sx = S::king_share(x, rng);
+        assert_eq!((&sa * &sx).reveal(), &a * &x);
+        let mut sum = sa.clone();
+        sum += (sx, &sb);
+        let mut plain = a.clone();
+        plain += (x, &b);
+        assert_eq!(sum.reveal(), plain);
+
+        let domain = GeneralEvaluationDomain::<S>::new(16).unwrap();
+        let plain_domain = GeneralEvaluationDomain::<Fr>::new(16).unwrap();
+        let vanishing = sa.mul_by_vanishing_poly(domain);
+        assert_eq!(
+            vanishing.clone().reveal(),
+            a.mul_by_vanishing_poly(plain_domain)
+        );
+        let (q, r) = vanishing.divide_by_vanishing_poly(domain).unwrap();
+        assert_eq!(q.reveal(), a);
+        assert!(r.reveal().coeffs.iter().all(|c| *c == Fr::from(0u64)));
+    });
+}
+
+#[test]
+fn shared_evaluation() {
+    run_parties(3, || {
+        let rng = &mut ark_std::test_rng();
+        let a = DensePolynomial::<Fr>::rand(63, rng);
+        let sa = share(&a, rng);
+        let x = Fr::rand(rng);
+        let sx = S::king_share(x, rng);
+
+        let (y, sent) = messages(|| sa.evaluate(&S::from_public(x)));
+        assert_eq!(sent, 0);
+        assert_eq!(y.reveal(), a.evaluate(&x));
+
+        // at a shared point, in a round per doubling of the powers and one for their sum,
+        // rather than one per coefficient
+        let (_, per_round) = messages(|| S::batch_product_in_place(&mut [sx, sx], &[sx, sx]));
+        let (y, sent) = messages(|| sa.evaluate(&sx));
+        assert_eq!(sent, 7 * per_round);
+        assert_eq!(y.reveal(), a.evaluate(&x));
+
+        // public coefficients, and shared ones of mixed sharing
+        let pa = DensePolynomial::from_coefficients_vec(
+            a.coeffs.iter().map(|c| S::from_public(*c)).collect(),
+        );
+        assert_eq!(pa.evaluate(&sx).reveal(), a.evaluate(&x));
+        let mixed = &share(&DensePolynomial::rand(10, rng), rng) + &pa;
+        let plain = mixed.clone().reveal();
+        assert_eq!(mixed.evaluate(&sx).reveal(), plain.evaluate(&x));
+    });
+}
+
+/// Shared coefficients, and above all shared group elements, cost more to scale than plain ones,
+/// so the parallel FFTs give each thread fewer of them.
+#[test]
+fn shared_coefficients_cost_more() {
+    use ark_bls12_377::G1Projective;
+    use ark_poly::domain::DomainCoeff;
+    use mpc_algebra::honest_but_curious::MpcGroup;
+    assert_eq!(<Fr as DomainCoeff<Fr>>::MUL_COST, 1);
+    assert!(<S as DomainCoeff<S>>::MUL_COST > 1);
+    assert!(<MpcGroup<G1Projective> as DomainCoeff<S>>::MUL_COST >= 1024);
+}
+
+/// FFTs of shared coefficients, long enough that, under the `parallel` feature, their butterflies
+/// are split across threads, match those of the opened values.
+#[test]
+fn shared_fft() {
+    use ark_bls12_377::G1Projective;
+    use mpc_algebra::honest_but_curious::MpcGroup;
+    run_parties(3, || {
+        let rng = &mut ark_std::test_rng();
+        let domain = GeneralEvaluationDomain::<S>::new(1024).unwrap();
+        let plain_domain = GeneralEvaluationDomain::<Fr>::new(1024).unwrap();
+        // only the king draws from `rng` while sharing, so every value is drawn first
+        let xs: Vec<Fr> = (0..1024).map(|_| Fr::rand(rng)).collect();
+        let gs: Vec<G1Projective> = (0..32).map(|_| G1Projective::rand(rng)).collect();
+        let shared = S::king_share_batch(xs.clone(), rng);
+        let (evals, sent) = messages(|| domain.fft(&shared));
+        assert_eq!(sent, 0);
+        assert_eq!(evals.clone().reveal(), plain_domain.fft(&xs));
+        assert_eq!(domain.ifft(&evals).reveal(), xs);
+
+        // group elements cost enough that every butterfly is split
+        let domain = GeneralEvaluationDomain::<S>::new(32).unwrap();
+        let plain_domain = GeneralEvaluationDomain::<Fr>::new(32).unwrap();
+        let shared = MpcGroup::king_share_batch(gs.clone(), rng);
+        let evals = domain.fft(&shared);
+        assert_eq!(evals.clone().reveal(), plain_domain.fft(&gs));
+        assert_eq!(domain.ifft(&evals).reveal(), gs);
+    });
+}
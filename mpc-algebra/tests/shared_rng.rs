@@ -0,0 +1,56 @@
+//! Public randomness from the session's joint seed, with every party run in-process.
+use ark_bls12_377::Fr;
+use ark_ff::PubUniformRand;
+use mpc_algebra::honest_but_curious::MpcField;
+use mpc_algebra::{seed, shared_rng, SharedRng};
+use mpc_test_utils::run_parties;
+use mpc_trait::MpcWire;
+use rand::RngCore;
+
+#[test]
+fn parties_agree() {
+    let draws = run_parties(3, || {
+        // drawn lazily, then again after a fresh setup
+        let first = (0..2)
+            .map(|_| SharedRng::new(b"test").next_u64())
+            .collect::<Vec<_>>();
+        shared_rng::setup();
+        let x = MpcField::<Fr>::pub_rand(&mut SharedRng::new(b"test"));
+        assert!(!x.is_shared());
+        (first, x)
+    });
+    assert!(draws.iter().all(|d| d == &draws[0]));
+    let (first, _) = &draws[0];
+    assert_ne!(first[0], first[1]);
+}
+
+#[test]
+fn seeded_sessions_repeat() {
+    let run = |s: u8| {
+        run_parties(2, move || {
+            seed::set_seed(Some([s; 32]));
+            shared_rng::setup();
+            let x = SharedRng::new(b"test").next_u64();
+            shared_rng::clear();
+            seed::set_seed(None);
+            assert!(!shared_rng::is_set_up());
+            x
+        })
+    };
+    assert_eq!(run(1), run(1));
+    assert_ne!(run(1), run(2));
+}
+
+#[test]
+fn private_rngs_differ_between_parties() {
+    // a witness owner masks its values with one of these, so no other party may draw the same
+    let draws = run_parties(3, || {
+        seed::set_seed(Some([7; 32]));
+        let x = seed::rng(b"witness").next_u64();
+        seed::set_seed(None);
+        x
+    });
+    assert_ne!(draws[0], draws[1]);
+    assert_ne!(draws[1], draws[2]);
+    assert_ne!(draws[0], draws[2]);
+}
@@ -0,0 +1,164 @@
+//! Preprocessing stores, with every party run in-process.
+use ark_bls12_377::Fr;
+use ark_ff::{UniformRand, Zero};
+use mpc_algebra::envelope::EnvelopeError;
+use mpc_algebra::share::store::{MemoryStore, PreprocessingStore, Preprocessed, Stock, StoreError};
+use mpc_algebra::{
+    AdditiveFieldShare, FieldShare, MaskSource, RandomnessError, RandomnessSource, SpdzFieldShare,
+};
+use mpc_test_utils::run_parties;
+
+/// `n` triples, shared by the king.
+fn triples<S: FieldShare<Fr>>(n: usize) -> (Vec<S>, Vec<S>, Vec<S>) {
+    let rng = &mut ark_std::test_rng();
+    let xs: Vec<Fr> = (0..n).map(|_| Fr::rand(rng)).collect();
+    let ys: Vec<Fr> = (0..n).map(|_| Fr::rand(rng)).collect();
+    let zs: Vec<Fr> = xs.iter().zip(&ys).map(|(x, y)| *x * y).collect();
+    (
+        S::king_share_batch(xs, rng),
+        S::king_share_batch(ys, rng),
+        S::king_share_batch(zs, rng),
+    )
+}
+
+fn check_memory<S: FieldShare<Fr>>() {
+    let rng = &mut ark_std::test_rng();
+    // only the king draws from `rng` to share, so draw every value first
+    let masks = vec![Fr::rand(rng), Fr::rand(rng)];
+    let (a, b): (Vec<Fr>, Vec<Fr>) = (0..3).map(|_| (Fr::rand(rng), Fr::rand(rng))).unzip();
+    let mut pre = Preprocessed::<Fr, S, _>::open(MemoryStore::new()).unwrap();
+    let (xs, ys, zs) = triples::<S>(4);
+    pre.add_triples(&xs, &ys, &zs).unwrap();
+    pre.add_shares(&S::king_share_batch(masks, rng)).unwrap();
+    pre.add_zeros(&S::king_share_batch(vec![Fr::zero()], rng))
+        .unwrap();
+    let stock = Stock {
+        triples: 4,
+        shares: 2,
+        zeros: 1,
+        ..Stock::default()
+    };
+    assert_eq!(pre.stock().unwrap(), stock);
+
+    // a clone draws from the same store, and counts toward the same metrics
+    let products = S::batch_mul(
+        S::king_share_batch(a.clone(), rng),
+        S::king_share_batch(b.clone(), rng),
+        &mut pre.clone(),
+    );
+    for ((p, a), b) in S::batch_open(products).into_iter().zip(a).zip(b) {
+        assert_eq!(p, a * b);
+    }
+    assert_eq!(pre.stock().unwrap().triples, 1);
+    assert_eq!(pre.taken().triples, 3);
+
+    // a draw that cannot be met takes nothing
+    assert!(matches!(
+        pre.take_triples(2),
+        Err(StoreError::Exhausted {
+            kind: "triples",
+            requested: 2,
+            available: 1,
+        })
+    ));
+    assert!(matches!(
+        pre.random_shares(3),
+        Err(RandomnessError::Exhausted {
+            kind: "shares",
+            ..
+        })
+    ));
+    assert_eq!(pre.stock().unwrap().triples, 1);
+    assert_eq!(pre.stock().unwrap().shares, 2);
+
+    let mask = pre.mask_share().unwrap();
+    assert_ne!(mask, pre.random_share().unwrap());
+    assert!(pre.zero_share().unwrap().open().is_zero());
+    assert_eq!(
+        pre.taken(),
+        Stock {
+            triples: 3,
+            shares: 2,
+            zeros: 1,
+            ..Stock::default()
+        }
+    );
+    assert_eq!(
+        pre.stock().unwrap(),
+        Stock {
+            triples: 1,
+            ..Stock::default()
+        }
+    );
+}
+
+#[test]
+fn additive_memory() {
+    run_parties(3, check_memory::<AdditiveFieldShare<Fr>>);
+}
+
+#[test]
+fn spdz_memory() {
+    run_parties(2, check_memory::<SpdzFieldShare<Fr>>);
+}
+
+#[test]
+fn other_share_type() {
+    run_parties(2, || {
+        let pre = Preprocessed::<Fr, SpdzFieldShare<Fr>, _>::open(MemoryStore::new()).unwrap();
+        let header = pre.store().header().unwrap().unwrap();
+        let copy = MemoryStore::new();
+        copy.set_header(&header).unwrap();
+        assert!(matches!(
+            Preprocessed::<Fr, AdditiveFieldShare<Fr>, _>::open(copy),
+            Err(StoreError::Envelope(EnvelopeError::WrongType { .. }))
+        ));
+    });
+}
+
+#[cfg(feature = "sled")]
+#[test]
+fn sled_survives_reopening() {
+    use mpc_algebra::share::store::SledStore;
+    use mpc_algebra::Reveal;
+    use mpc_net::{MpcMultiNet as Net, MpcNet};
+    type S = AdditiveFieldShare<Fr>;
+
+    let dir = std::env::temp_dir().join(format!("mpc-store-{}", std::process::id()));
+    run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        let path = dir.join(format!("party-{}", Net::party_id()));
+        let values: Vec<Fr> = (0..5).map(|_| Fr::rand(rng)).collect();
+        let shares = S::king_share_batch(values, rng);
+        let (xs, ys, zs) = triples::<S>(2);
+        let first = {
+            let mut pre = Preprocessed::<Fr, S, _>::open(SledStore::open(&path).unwrap()).unwrap();
+            pre.add_shares(&shares).unwrap();
+            pre.add_triples(&xs, &ys, &zs).unwrap();
+            pre.triple();
+            pre.random_shares(2).unwrap()
+        };
+        assert_eq!(first, shares[..2]);
+
+        // what was drawn before the store was closed is never drawn again
+        let mut pre = Preprocessed::<Fr, S, _>::open(SledStore::open(&path).unwrap()).unwrap();
+        assert_eq!(
+            pre.stock().unwrap(),
+            Stock {
+                triples: 1,
+                shares: 3,
+                ..Stock::default()
+            }
+        );
+        assert_eq!(pre.taken(), Stock::default());
+        assert_eq!(pre.triple(), (xs[1], ys[1], zs[1]));
+        assert_eq!(pre.random_shares(3).unwrap(), shares[2..]);
+        assert!(pre.random_share().is_err());
+        drop(pre);
+        assert!(matches!(
+            Preprocessed::<Fr, SpdzFieldShare<Fr>, _>::open(SledStore::open(&path).unwrap()),
+            Err(StoreError::Envelope(EnvelopeError::WrongType { .. }))
+        ));
+    });
+    std::fs::remove_dir_all(&dir).unwrap();
+}
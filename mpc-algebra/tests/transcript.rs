@@ -0,0 +1,104 @@
+//! Challenges that every party derives alike, and their tables speculated during the round that
+//! fixes them.
+use ark_bls12_377::Fr;
+use ark_ff::{One, UniformRand};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use mpc_algebra::channel::MpcSerNet;
+use mpc_algebra::honest_but_curious::MpcField;
+use mpc_algebra::transcript::{speculate, LagrangeTable};
+use mpc_algebra::{MpcTranscript, Reveal};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_trait::MpcWire;
+use mpc_test_utils::run_parties;
+use std::sync::mpsc;
+use std::time::Duration;
+
+#[test]
+fn lagrange_at_challenge() {
+    let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+    let outs = run_parties(3, move || {
+        let mut t = MpcTranscript::new(b"speculate");
+        let table = LagrangeTable::speculate(domain);
+        // the round the challenge depends on
+        let mine = Fr::from(Net::party_id() as u64 + 1);
+        for x in Net::broadcast(&mine) {
+            t.append(b"message", &x);
+        }
+        let (z, evals): (Fr, _) = t.challenge_with(b"z", table, |table, z| table.evaluate(z));
+        t.check_agreement();
+        (z, evals)
+    });
+    for (z, evals) in &outs {
+        assert_eq!(z, &outs[0].0);
+        assert_eq!(evals, &domain.evaluate_all_lagrange_coefficients(*z));
+    }
+}
+
+#[test]
+fn lagrange_in_domain() {
+    let domain = Radix2EvaluationDomain::<Fr>::new(8).unwrap();
+    let table = LagrangeTable::new(domain);
+    let w = domain.element(3);
+    let evals = table.evaluate(w);
+    for (i, e) in evals.iter().enumerate() {
+        assert_eq!(e.is_one(), i == 3);
+    }
+    let z = Fr::rand(&mut ark_std::test_rng());
+    assert_eq!(
+        table.evaluate(z),
+        domain.evaluate_all_lagrange_coefficients(z)
+    );
+}
+
+#[test]
+fn lagrange_at_shared_point() {
+    let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+    let outs = run_parties(3, move || {
+        let table = LagrangeTable::new(domain);
+        let rng = &mut ark_std::test_rng();
+        let z = Fr::rand(rng);
+        let shared = MpcField::<Fr>::king_share(z, rng);
+        let evals = table.evaluate_shared(shared).reveal();
+        let public = table.evaluate_shared(MpcField::from_public(domain.element(5)));
+        assert!(public.iter().all(|e| !e.is_shared()));
+        (z, evals, public.reveal())
+    });
+    for (z, evals, public) in &outs {
+        assert_eq!(evals, &domain.evaluate_all_lagrange_coefficients(*z));
+        for (i, e) in public.iter().enumerate() {
+            assert_eq!(e.is_one(), i == 5);
+        }
+    }
+}
+
+#[test]
+fn overlaps_the_round() {
+    let outs = run_parties(2, || {
+        let mut t = MpcTranscript::new(b"speculate");
+        let (ready, on_ready) = mpsc::channel();
+        let powers = speculate(move || {
+            let xs = (0..1000u64).map(Fr::from).collect::<Vec<_>>();
+            ready.send(()).unwrap();
+            xs
+        });
+        // the table is done while the round that fixes the challenge is still under way
+        on_ready
+            .recv_timeout(Duration::from_secs(10))
+            .expect("the speculation did not run during the round");
+        let all = Net::broadcast_vec(&[Fr::from(Net::party_id() as u64)]);
+        for x in all.iter().flatten() {
+            t.append(b"message", x);
+        }
+        let (z, dot): (Fr, Fr) =
+            t.challenge_with(b"z", powers, |xs, z| xs.iter().map(|x| *x * z).sum());
+        (z, dot)
+    });
+    assert_eq!(outs[0], outs[1]);
+    assert_eq!(outs[0].1, outs[0].0 * Fr::from(999u64 * 1000 / 2));
+}
+
+#[test]
+#[should_panic(expected = "no table")]
+fn resumes_panics() {
+    speculate(|| -> u8 { panic!("no table") }).join();
+}
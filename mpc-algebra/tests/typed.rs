@@ -0,0 +1,59 @@
+//! Typed shared and public values, with every party run in-process.
+use ark_bls12_377::Fr;
+use ark_ff::{Field, UniformRand};
+use mpc_algebra::{
+    share::{add::AdditiveFieldShare, field::FieldShare, spdz::SpdzFieldShare},
+    MpcField, Public, Reveal, Shared,
+};
+use mpc_test_utils::run_parties;
+use mpc_trait::MpcWire;
+
+use std::convert::TryFrom;
+
+fn test_typed<S: FieldShare<Fr>>(n: usize) {
+    run_parties(n, || {
+        S::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let (a_pub, b_pub, c_pub) = (Fr::rand(rng), Fr::rand(rng), Fr::rand(rng));
+        let a = Shared::<Fr, S>::input(0, a_pub, rng);
+        let b = Shared::<Fr, S>::input(n - 1, b_pub, rng);
+        let c = Public::new(c_pub);
+
+        let mut d: Shared<Fr, S> = a * b + c;
+        d -= c * a;
+        d /= b;
+        let e: Public<Fr> = c * c - c;
+        let expected = (a_pub * b_pub + c_pub - c_pub * a_pub) / b_pub;
+        assert_eq!(d.open().value(), expected);
+        assert_eq!(e.value(), c_pub.square() - c_pub);
+        assert_eq!(
+            Shared::open_batch(vec![-a, Shared::from_public(c)]),
+            vec![Public::new(-a_pub), c]
+        );
+
+        let m: MpcField<Fr, S> = d.into_mpc();
+        assert!(m.is_shared());
+        assert!(Shared::try_from(m).is_ok());
+        assert!(Public::try_from(m).is_err());
+        assert_eq!(Public::try_from(c.into_mpc::<S>()), Ok(c));
+        assert_eq!(Shared::<Fr, S>::try_from(c.into_mpc::<S>()), Err(c_pub));
+        assert_eq!(
+            Shared::<Fr, S>::from_add_shared(a_pub).open(),
+            Public::new(a_pub * Fr::from(n as u64))
+        );
+        // converting to an MpcField keeps the value
+        assert_eq!(a.into_mpc().reveal(), a_pub);
+        S::deinit_protocol();
+    });
+}
+
+#[test]
+fn additive() {
+    test_typed::<AdditiveFieldShare<Fr>>(2);
+    test_typed::<AdditiveFieldShare<Fr>>(3);
+}
+
+#[test]
+fn spdz() {
+    test_typed::<SpdzFieldShare<Fr>>(2);
+}
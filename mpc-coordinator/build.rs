@@ -0,0 +1,5 @@
+fn main() {
+    // prost no longer bundles protoc
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path().unwrap());
+    tonic_build::compile_protos("proto/coordinator.proto").unwrap();
+}
@@ -0,0 +1,89 @@
+//! The standard gRPC health service, from tonic-health, for orchestration to probe the coordinator
+//! and the parties with.
+//!
+//! A server as a whole (the service `""`) is live, and serving, for as long as the process
+//! serves. The [Coordinator service](SERVICE) is ready once it takes sessions, and stops being so
+//! while the server shuts down, so that probes stop routing operators and parties to it before it
+//! goes away. A [party](PARTY) is ready while it has all it needs to prove: the session's proving
+//! key, a connection to each of the other parties, and, if it keeps preprocessing in a store,
+//! enough of it. Kubernetes' gRPC probes, and `mpc-coordinator health`, check these.
+use log::info;
+use mpc_algebra::share::store::{Material, Stock};
+use tokio::runtime::Handle;
+
+use std::sync::{Arc, Mutex};
+
+pub use tonic_health::server::{health_reporter, HealthReporter};
+pub use tonic_health::ServingStatus;
+
+/// The name under which the coordinator's own service is reported.
+pub const SERVICE: &str = "coordinator.Coordinator";
+
+/// The name under which a party's readiness to prove is reported.
+pub const PARTY: &str = "coordinator.Party";
+
+/// What a party has, of what it needs to prove.
+#[derive(Clone, Debug, Default)]
+pub struct PartyState {
+    /// Whether it has loaded the session's proving key.
+    pub key: bool,
+    /// Whether it is connected to every other party, and none has become unreachable since.
+    pub peers: bool,
+    /// What its store holds, if it keeps one, and has read it.
+    pub stock: Option<Stock>,
+}
+
+/// Reports a party [PARTY] serving while its [PartyState] is ready, and not serving otherwise.
+///
+/// Clones report the same state.
+#[derive(Clone)]
+pub struct Readiness {
+    reporter: HealthReporter,
+    handle: Handle,
+    state: Arc<Mutex<PartyState>>,
+    /// The stock a store must hold, or `None` if the party keeps none.
+    min_stock: Option<Stock>,
+}
+
+impl Readiness {
+    /// A party that has nothing yet, reported with `reporter` from the runtime of `handle`, and
+    /// whose store, if it keeps one, must hold `min_stock`.
+    pub fn new(reporter: HealthReporter, handle: Handle, min_stock: Option<Stock>) -> Self {
+        let readiness = Self {
+            reporter,
+            handle,
+            state: Default::default(),
+            min_stock,
+        };
+        readiness.update(|_| ());
+        readiness
+    }
+
+    /// Change the state with `f`, and report whether the party is now ready.
+    ///
+    /// It blocks on the runtime, so must not be called from within it.
+    pub fn update(&self, f: impl FnOnce(&mut PartyState)) {
+        let mut state = self.state.lock().expect("poisoned party state");
+        f(&mut state);
+        let status = if self.is_ready(&state) {
+            ServingStatus::Serving
+        } else {
+            ServingStatus::NotServing
+        };
+        info!("Party {}: {:?}", status, state);
+        // under the lock, so that the last state is the one reported
+        let mut reporter = self.reporter.clone();
+        self.handle.block_on(reporter.set_service_status(PARTY, status));
+    }
+
+    fn is_ready(&self, state: &PartyState) -> bool {
+        let stocked = match (&self.min_stock, &state.stock) {
+            (None, _) => true,
+            (Some(min), Some(stock)) => Material::ALL
+                .iter()
+                .all(|kind| stock.get(*kind) >= min.get(*kind)),
+            (Some(_), None) => false,
+        };
+        state.key && state.peers && stocked
+    }
+}
@@ -0,0 +1,158 @@
+//! A coordinator for collaborative proving sessions, served over gRPC.
+//!
+//! Without one, an operator runs each party's binary by hand, with the same hosts file, keys and
+//! options, and collects the proof from one of them. With one, the operator starts a session on
+//! the [service] ([StartSession](proto::StartSessionRequest)), with its parameters, and gives
+//! each party only the coordinator's address, the session's id, and one of its join tickets:
+//!
+//! 1. each [party] joins ([Join](proto::JoinRequest)) with its ticket and the address it listens
+//!    at, and gets its id and the session's parameters, proving key included;
+//! 2. it polls [Status](proto::StatusRequest) until every party has joined, and connects to the
+//!    others at the addresses they joined with;
+//! 3. the parties prove together, as the `proof` binary's would, and each submits the proof it
+//!    opened ([SubmitShares](proto::SubmitSharesRequest)), or why it failed;
+//! 4. once they all have submitted the same proof, and the coordinator has verified it, anyone
+//!    may get it ([GetProof](proto::GetProofRequest)).
+//!
+//! Session ids are random, and each ticket lets one party join, so no one the operator did not
+//! hand a ticket to can take a party's place. Starting a session gives the operator a token, and
+//! joining it gives each party one, which it must show to submit: no client can submit as a party
+//! it did not join as.
+//!
+//! The coordinator sees only what is public: the parameters, the parties' addresses, and the
+//! opened proof. Each party's secret input stays with it. The coordinator is trusted to hand out
+//! the parties' addresses and the key faithfully, though, and it keeps its sessions in memory
+//! only, for a while after they were last used.
+//!
+//! The `mpc-coordinator` binary serves the coordinator, and also runs its clients: an operator's
+//! `start`, `status` and `proof`, and a party's `party`.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_groth16::ProvingKey;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mpc_snarks::demo::MultiplicationCircuit;
+use mpc_snarks::setup;
+use rand::Rng;
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+pub mod health;
+pub mod party;
+pub mod service;
+
+/// The messages and stubs generated from `proto/coordinator.proto`.
+pub mod proto {
+    tonic::include_proto!("coordinator");
+}
+
+pub type E = Bls12_377;
+
+/// The only circuit a session proves, so far: [MultiplicationCircuit], in which party 0 knows
+/// `a`, party 1 knows `b`, and `c = ab` is public.
+pub const MULTIPLICATION: &str = "multiplication";
+
+/// The kinds of shares a session may prove with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sharing {
+    /// Additive shares, for parties that do not cheat.
+    Hbc,
+    /// SPDZ, with MACs, against any number of cheating parties.
+    Spdz,
+    /// GSZ20, against a cheating minority.
+    Gsz,
+}
+
+impl Sharing {
+    /// The fewest parties a session may have with these shares (and the multiplication circuit's
+    /// two owners of secrets).
+    pub fn min_parties(self) -> usize {
+        match self {
+            Sharing::Gsz => 3,
+            _ => 2,
+        }
+    }
+}
+
+impl FromStr for Sharing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "hbc" => Ok(Sharing::Hbc),
+            "spdz" => Ok(Sharing::Spdz),
+            "gsz" => Ok(Sharing::Gsz),
+            _ => Err(format!("unknown shares `{}`: expected hbc, spdz or gsz", s)),
+        }
+    }
+}
+
+impl Display for Sharing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Sharing::Hbc => "hbc",
+            Sharing::Spdz => "spdz",
+            Sharing::Gsz => "gsz",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Groth16 keys for the multiplication circuit, from the randomness of `rng`, which the operator
+/// must then forget.
+pub fn multiplication_key<R: Rng>(rng: &mut R) -> ProvingKey<E> {
+    let blank = MultiplicationCircuit::<Fr> { a: None, b: None };
+    setup::<E, _, _>(blank, rng).expect("the multiplication circuit synthesizes")
+}
+
+/// The parameters of a session of `parties` proving the multiplication circuit with `sharing`,
+/// and the key `pk`.
+pub fn multiplication_parameters(
+    parties: usize,
+    sharing: Sharing,
+    pk: &ProvingKey<E>,
+) -> proto::Parameters {
+    let mut proving_key = Vec::new();
+    pk.serialize_uncompressed(&mut proving_key).unwrap();
+    proto::Parameters {
+        parties: parties as u32,
+        circuit: MULTIPLICATION.to_owned(),
+        sharing: sharing.to_string(),
+        proving_key,
+    }
+}
+
+/// A session's parameters, checked and decoded.
+pub struct Session {
+    pub parties: usize,
+    pub sharing: Sharing,
+    pub pk: ProvingKey<E>,
+}
+
+impl Session {
+    /// Check `parameters`, and decode the proving key.
+    pub fn new(parameters: &proto::Parameters) -> Result<Self, String> {
+        if parameters.circuit != MULTIPLICATION {
+            return Err(format!(
+                "unknown circuit `{}`: expected {}",
+                parameters.circuit, MULTIPLICATION
+            ));
+        }
+        let sharing: Sharing = parameters.sharing.parse()?;
+        let parties = parameters.parties as usize;
+        if parties < sharing.min_parties() {
+            return Err(format!(
+                "{} shares need at least {} parties, not {}",
+                sharing,
+                sharing.min_parties(),
+                parties
+            ));
+        }
+        let pk = ProvingKey::deserialize_uncompressed(&parameters.proving_key[..])
+            .map_err(|e| format!("bad proving key: {}", e))?;
+        Ok(Self {
+            parties,
+            sharing,
+            pk,
+        })
+    }
+}
@@ -0,0 +1,399 @@
+use ark_bls12_377::Fr;
+use ark_groth16::Proof;
+use ark_serialize::CanonicalDeserialize;
+use log::info;
+use mpc_algebra::share::store::Stock;
+use mpc_coordinator::health::{self, HealthReporter, ServingStatus};
+use mpc_coordinator::party::{self, PartyConfig};
+use mpc_coordinator::proto::coordinator_client::CoordinatorClient;
+use mpc_coordinator::proto::coordinator_server::CoordinatorServer;
+use mpc_coordinator::proto::status_reply::State;
+use mpc_coordinator::proto::*;
+use mpc_coordinator::service::CoordinatorService;
+use mpc_coordinator::{multiplication_key, multiplication_parameters, Sharing, E};
+use mpc_net::{Compression, NetConfig};
+use structopt::StructOpt;
+use tonic::transport::{Channel, Server};
+use tonic_health::pb::health_check_response::ServingStatus as CheckedStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
+
+use std::error::Error;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "mpc-coordinator",
+    about = "Coordinates collaborative proving sessions"
+)]
+enum Opt {
+    /// Serve the coordinator, and the gRPC health service
+    Serve {
+        /// Address to listen at
+        #[structopt(long, default_value = "127.0.0.1:50051")]
+        listen: SocketAddr,
+
+        /// Seconds to keep serving, though not ready, after an interrupt or SIGTERM, so that
+        /// orchestration stops sending requests first
+        #[structopt(long, default_value = "5")]
+        drain: u64,
+
+        /// Seconds to keep a session after the last request that names it
+        #[structopt(long, default_value = "3600")]
+        session_ttl: u64,
+
+        /// How many sessions to keep at once; more are refused
+        #[structopt(long, default_value = "1024")]
+        max_sessions: usize,
+    },
+    /// Set up keys for the multiplication circuit, start a session with them, and print its id,
+    /// and on the next lines, the operator's token, and a ticket for each party
+    Start {
+        /// The coordinator's URL
+        #[structopt(long, default_value = "http://127.0.0.1:50051")]
+        coordinator: String,
+
+        /// How many parties prove
+        #[structopt(long, default_value = "2")]
+        parties: usize,
+
+        /// The kind of shares: hbc, spdz or gsz
+        #[structopt(long, default_value = "hbc")]
+        sharing: Sharing,
+    },
+    /// Join a session as a party, prove, and submit the proof
+    Party {
+        /// The coordinator's URL
+        #[structopt(long, default_value = "http://127.0.0.1:50051")]
+        coordinator: String,
+
+        /// The session to join
+        #[structopt(long)]
+        session: String,
+
+        /// The ticket to join it with, which `start` printed
+        #[structopt(long)]
+        ticket: String,
+
+        /// HOST:PORT to listen at for the other parties
+        #[structopt(long)]
+        listen: String,
+
+        /// This party's secret: a for party 0, b for party 1; other parties have none
+        #[structopt(long)]
+        input: Option<u64>,
+
+        /// Seconds to wait for the other parties to join
+        #[structopt(long, default_value = "60")]
+        join_timeout: u64,
+
+        /// Seconds to wait for the other parties at start-up, once they all have joined
+        #[structopt(long, default_value = "30")]
+        connect_timeout: u64,
+
+        /// Seconds a single read or write may block before a party is presumed dead (0 to wait
+        /// forever)
+        #[structopt(long, default_value = "600")]
+        io_timeout: u64,
+
+        /// Message compression: none, lz4, or zstd[:level] if mpc-net has its zstd feature
+        #[structopt(long, default_value = "none")]
+        compression: Compression,
+
+        /// Where to write this party's profiles, as flamegraph folded stacks, when the session's
+        /// profiling is turned on (see `profile`)
+        #[structopt(long, parse(from_os_str), value_name = "DIR")]
+        profile: Option<PathBuf>,
+
+        /// Tell the other parties every SECONDS that this one is alive, and stop being ready as
+        /// soon as one misses a few of these. All parties must agree on whether to
+        #[structopt(long, value_name = "SECONDS")]
+        heartbeat: Option<f64>,
+
+        /// HOST:PORT at which to serve the gRPC health service: live while the party runs, and
+        /// ready while it has the session's key, the other parties and, with `--store`, its stock
+        #[structopt(long)]
+        health: Option<String>,
+
+        /// This party's preprocessing store, as `proof dealer` stocks it, whose stock the party
+        /// reports
+        #[structopt(long, parse(from_os_str), value_name = "DIR")]
+        store: Option<PathBuf>,
+
+        /// How many triples the store must hold for the party to be ready
+        #[structopt(long, default_value = "1")]
+        min_triples: usize,
+    },
+    /// Print where a session stands
+    Status {
+        /// The coordinator's URL
+        #[structopt(long, default_value = "http://127.0.0.1:50051")]
+        coordinator: String,
+
+        #[structopt(long)]
+        session: String,
+    },
+    /// Print a session's proof, in hex, and its public inputs
+    Proof {
+        /// The coordinator's URL
+        #[structopt(long, default_value = "http://127.0.0.1:50051")]
+        coordinator: String,
+
+        #[structopt(long)]
+        session: String,
+    },
+    /// Turn a session's profiling on, for the parties given somewhere to write their profiles,
+    /// or change what it covers, or with `--off`, turn it off; the parties follow as they prove
+    Profile {
+        /// The coordinator's URL
+        #[structopt(long, default_value = "http://127.0.0.1:50051")]
+        coordinator: String,
+
+        #[structopt(long)]
+        session: String,
+
+        /// The operator's token, which `start` printed
+        #[structopt(long)]
+        token: String,
+
+        /// Profile only these phases (comma-separated), such as "fft,msm"
+        #[structopt(long, use_delimiter = true)]
+        phases: Vec<String>,
+
+        /// Also sample where the CPU goes, which needs the parties' pprof feature
+        #[structopt(long)]
+        cpu: bool,
+
+        #[structopt(long, conflicts_with_all = &["phases", "cpu"])]
+        off: bool,
+    },
+    /// Check that the coordinator is live, or with `--ready`, that it takes sessions; fails if not.
+    /// With `--party`, check a party's health service instead, where `--ready` means ready to prove
+    Health {
+        /// The coordinator's URL, or with `--party`, that of the party's health service
+        #[structopt(long, default_value = "http://127.0.0.1:50051")]
+        coordinator: String,
+
+        #[structopt(long)]
+        ready: bool,
+
+        #[structopt(long)]
+        party: bool,
+    },
+}
+
+async fn client(coordinator: String) -> Result<CoordinatorClient<Channel>, Box<dyn Error>> {
+    Ok(CoordinatorClient::connect(coordinator).await?)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Wait for an interrupt, or on Unix, SIGTERM.
+async fn terminated() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut term = signal(SignalKind::terminate()).expect("SIGTERM handler");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = term.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await.ok();
+}
+
+/// Once terminated, report the coordinator not ready, and stop serving `drain` later.
+async fn shutdown(mut health: HealthReporter, drain: Duration) {
+    terminated().await;
+    info!("Shutting down in {:?}", drain);
+    health
+        .set_service_status(health::SERVICE, ServingStatus::NotServing)
+        .await;
+    tokio::time::sleep(drain).await;
+}
+
+#[tokio::main]
+async fn run(opt: Opt) -> Result<(), Box<dyn Error>> {
+    match opt {
+        Opt::Serve {
+            listen,
+            drain,
+            session_ttl,
+            max_sessions,
+        } => {
+            info!("Serving at {}", listen);
+            let (mut health, health_service) = health::health_reporter();
+            health
+                .set_service_status(health::SERVICE, ServingStatus::Serving)
+                .await;
+            let service = CoordinatorService::new(Duration::from_secs(session_ttl), max_sessions);
+            Server::builder()
+                .add_service(health_service)
+                .add_service(CoordinatorServer::new(service))
+                .serve_with_shutdown(listen, shutdown(health, Duration::from_secs(drain)))
+                .await?;
+        }
+        Opt::Start {
+            coordinator,
+            parties,
+            sharing,
+        } => {
+            let pk = multiplication_key(&mut rand::thread_rng());
+            let parameters = multiplication_parameters(parties, sharing, &pk);
+            let reply = client(coordinator)
+                .await?
+                .start_session(StartSessionRequest {
+                    parameters: Some(parameters),
+                })
+                .await?;
+            let reply = reply.into_inner();
+            println!("{}", reply.session);
+            println!("{}", reply.token);
+            for ticket in &reply.tickets {
+                println!("{}", ticket);
+            }
+        }
+        Opt::Status {
+            coordinator,
+            session,
+        } => {
+            let status = client(coordinator)
+                .await?
+                .status(StatusRequest { session })
+                .await?
+                .into_inner();
+            println!("state: {:?}", status.state());
+            for (party, host) in status.hosts.iter().enumerate() {
+                println!("party {}: {}", party, host);
+            }
+            println!("submitted: {}", status.submitted);
+            if status.state() == State::Failed {
+                println!("error: {}", status.error);
+            }
+        }
+        Opt::Proof {
+            coordinator,
+            session,
+        } => {
+            let reply = client(coordinator)
+                .await?
+                .get_proof(GetProofRequest { session })
+                .await?
+                .into_inner();
+            // the coordinator verified it, so it decodes
+            Proof::<E>::deserialize_uncompressed(&reply.proof[..])?;
+            let inputs = Vec::<Fr>::deserialize_uncompressed(&reply.public_inputs[..])?;
+            println!("proof: {}", hex(&reply.proof));
+            for input in inputs {
+                println!("input: {}", input);
+            }
+        }
+        Opt::Profile {
+            coordinator,
+            session,
+            token,
+            phases,
+            cpu,
+            off,
+        } => {
+            client(coordinator)
+                .await?
+                .set_profiling(SetProfilingRequest {
+                    session,
+                    profiling: Some(Profiling {
+                        enabled: !off,
+                        phases,
+                        cpu,
+                    }),
+                    token,
+                })
+                .await?;
+        }
+        Opt::Health {
+            coordinator,
+            ready,
+            party,
+        } => {
+            let service = match (ready, party) {
+                (true, true) => health::PARTY,
+                (true, false) => health::SERVICE,
+                (false, _) => "",
+            };
+            let channel = Channel::from_shared(coordinator)?.connect().await?;
+            let reply = HealthClient::new(channel)
+                .check(HealthCheckRequest {
+                    service: service.to_owned(),
+                })
+                .await?;
+            let status = reply.into_inner().status();
+            println!("{:?}", status);
+            if status != CheckedStatus::Serving {
+                return Err(format!("not serving: {:?}", status).into());
+            }
+        }
+        Opt::Party { .. } => unreachable!("parties run outside the runtime"),
+    }
+    Ok(())
+}
+
+fn main() {
+    env_logger::init();
+    let opt = Opt::from_args();
+    let result = match opt {
+        // a party blocks on the network, so it runs its own runtime, between its calls
+        Opt::Party {
+            coordinator,
+            session,
+            ticket,
+            listen,
+            input,
+            join_timeout,
+            connect_timeout,
+            io_timeout,
+            compression,
+            profile,
+            heartbeat,
+            health,
+            store,
+            min_triples,
+        } => party::run(&PartyConfig {
+            coordinator,
+            session,
+            ticket,
+            address: listen,
+            input,
+            join_timeout: Duration::from_secs(join_timeout),
+            net: NetConfig {
+                connect_timeout: Duration::from_secs(connect_timeout),
+                io_timeout: Some(Duration::from_secs(io_timeout)).filter(|t| !t.is_zero()),
+                compression,
+                heartbeat: heartbeat.map(Duration::from_secs_f64),
+                ..NetConfig::default()
+            },
+            profile,
+            health,
+            store: store.map(|dir| {
+                let min = Stock {
+                    triples: min_triples,
+                    ..Stock::default()
+                };
+                (dir, min)
+            }),
+        })
+        .map(|submitted| {
+            println!("party {}: submitted", submitted.party);
+        })
+        .map_err(|e| e.into()),
+        opt => run(opt),
+    };
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
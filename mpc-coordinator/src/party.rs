@@ -0,0 +1,381 @@
+//! A party of a coordinated session: it joins through the coordinator, proves with the others
+//! over the network as usual, and submits what it opened.
+use ark_bls12_377::Fr;
+use ark_groth16::{Proof, ProvingKey};
+use ark_serialize::CanonicalSerialize;
+use log::{info, warn};
+use mpc_algebra::share::store::{Preprocessed, SledStore, Stock, StoreError};
+use mpc_algebra::{
+    seed, AdditivePairingShare, GszPairingShare, PairingShare, Reveal, SpdzPairingShare,
+};
+use mpc_net::{catch_abort, profile, MpcMultiNet, NetConfig, NetError};
+use mpc_snarks::demo::MultiplicationCircuit;
+use mpc_snarks::{
+    prove_collaborative, reveal_proof, share_proving_key, Network, ProveError, ProveOptions,
+};
+use tokio::net::TcpListener;
+use tokio::runtime::Runtime;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::{Channel, Server};
+
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use std::{env, fs, io, process, thread};
+
+use crate::health::{self, Readiness};
+use crate::proto::coordinator_client::CoordinatorClient;
+use crate::proto::status_reply::State;
+use crate::proto::*;
+use crate::{Session, Sharing, E};
+
+#[derive(Debug)]
+pub enum PartyError {
+    /// The coordinator could not be reached.
+    Connect(tonic::transport::Error),
+    /// The coordinator refused a request.
+    Rpc(Box<tonic::Status>),
+    /// The session's parameters are not ones this party can prove with.
+    Parameters(String),
+    /// The other parties did not all join in time.
+    JoinTimeout,
+    /// The session ended before this party proved.
+    Ended(String),
+    Io(io::Error),
+    Net(NetError),
+    /// This party's preprocessing store could not be read.
+    Store(StoreError),
+    Prove(ProveError),
+}
+
+impl Display for PartyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PartyError::Connect(e) => write!(f, "cannot reach the coordinator: {}", e),
+            PartyError::Rpc(s) => write!(f, "the coordinator refused: {}", s.message()),
+            PartyError::Parameters(e) => write!(f, "{}", e),
+            PartyError::JoinTimeout => write!(f, "the other parties did not all join in time"),
+            PartyError::Ended(e) => write!(f, "the session ended: {}", e),
+            PartyError::Io(e) => write!(f, "{}", e),
+            PartyError::Net(e) => write!(f, "{}", e),
+            PartyError::Store(e) => write!(f, "{}", e),
+            PartyError::Prove(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for PartyError {}
+
+impl From<tonic::transport::Error> for PartyError {
+    fn from(e: tonic::transport::Error) -> Self {
+        PartyError::Connect(e)
+    }
+}
+
+impl From<tonic::Status> for PartyError {
+    fn from(s: tonic::Status) -> Self {
+        PartyError::Rpc(Box::new(s))
+    }
+}
+
+impl From<io::Error> for PartyError {
+    fn from(e: io::Error) -> Self {
+        PartyError::Io(e)
+    }
+}
+
+impl From<NetError> for PartyError {
+    fn from(e: NetError) -> Self {
+        PartyError::Net(e)
+    }
+}
+
+impl From<StoreError> for PartyError {
+    fn from(e: StoreError) -> Self {
+        PartyError::Store(e)
+    }
+}
+
+impl From<ProveError> for PartyError {
+    fn from(e: ProveError) -> Self {
+        PartyError::Prove(e)
+    }
+}
+
+/// How a party takes part in a coordinated session.
+#[derive(Clone, Debug)]
+pub struct PartyConfig {
+    /// The coordinator's URL, such as `http://127.0.0.1:50051`.
+    pub coordinator: String,
+    pub session: String,
+    /// The ticket the operator handed this party, to join with.
+    pub ticket: String,
+    /// The `HOST:PORT` at which to listen for the other parties.
+    pub address: String,
+    /// This party's secret: `a` if it turns out to be party 0, `b` if party 1. Other parties
+    /// have none.
+    pub input: Option<u64>,
+    /// How long to wait for the other parties to join.
+    pub join_timeout: Duration,
+    pub net: NetConfig,
+    /// Where to write this party's profiles, if the session's profiling is turned on.
+    pub profile: Option<PathBuf>,
+    /// The `HOST:PORT` at which to serve the gRPC health service, if any.
+    pub health: Option<String>,
+    /// This party's preprocessing store, if it keeps one, and the stock it must hold for the
+    /// party to be ready.
+    pub store: Option<(PathBuf, Stock)>,
+}
+
+/// What a party submitted.
+#[derive(Clone, Debug)]
+pub struct Submitted {
+    pub party: usize,
+    pub proof: Proof<E>,
+    pub public_inputs: Vec<Fr>,
+}
+
+/// How often to ask the coordinator whether every party has joined.
+const POLL: Duration = Duration::from_millis(100);
+
+/// Join the session of `config`, prove with its other parties, and submit the proof, or why
+/// there is none.
+///
+/// The process must not be in any other MPC network: this one joins the process-wide one.
+pub fn run(config: &PartyConfig) -> Result<Submitted, PartyError> {
+    let rt = Runtime::new()?;
+    let readiness = serve_health(&rt, config)?;
+    let mut client = rt.block_on(CoordinatorClient::connect(config.coordinator.clone()))?;
+    let joined = rt
+        .block_on(client.join(JoinRequest {
+            session: config.session.clone(),
+            address: config.address.clone(),
+            ticket: config.ticket.clone(),
+        }))?
+        .into_inner();
+    let party = joined.party as usize;
+    let parameters = joined.parameters.clone().unwrap_or_default();
+    let proved = prove_in_session(&rt, &mut client, config, party, &parameters, &readiness);
+    let submission = match &proved {
+        Ok(s) => {
+            let (mut proof, mut public_inputs) = (Vec::new(), Vec::new());
+            s.proof.serialize_uncompressed(&mut proof).unwrap();
+            s.public_inputs
+                .serialize_uncompressed(&mut public_inputs)
+                .unwrap();
+            SubmitSharesRequest {
+                session: config.session.clone(),
+                party: party as u32,
+                proof,
+                public_inputs,
+                error: String::new(),
+                token: joined.token.clone(),
+            }
+        }
+        Err(e) => SubmitSharesRequest {
+            session: config.session.clone(),
+            party: party as u32,
+            error: e.to_string(),
+            token: joined.token.clone(),
+            ..Default::default()
+        },
+    };
+    rt.block_on(client.submit_shares(submission))?;
+    proved
+}
+
+/// Serve the health service at `config.health`, if anywhere, and return what reports this
+/// party's readiness through it.
+fn serve_health(rt: &Runtime, config: &PartyConfig) -> Result<Readiness, PartyError> {
+    let (reporter, service) = health::health_reporter();
+    if let Some(address) = &config.health {
+        let listener = rt.block_on(TcpListener::bind(address))?;
+        info!("Serving health at {}", listener.local_addr()?);
+        rt.spawn(
+            Server::builder()
+                .add_service(service)
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+    }
+    let min_stock = config.store.as_ref().map(|(_, min)| *min);
+    Ok(Readiness::new(reporter, rt.handle().clone(), min_stock))
+}
+
+fn prove_in_session(
+    rt: &Runtime,
+    client: &mut CoordinatorClient<Channel>,
+    config: &PartyConfig,
+    party: usize,
+    parameters: &Parameters,
+    readiness: &Readiness,
+) -> Result<Submitted, PartyError> {
+    let session = Session::new(parameters).map_err(PartyError::Parameters)?;
+    readiness.update(|s| s.key = true);
+    if let Some((path, _)) = &config.store {
+        let stock = match session.sharing {
+            Sharing::Hbc => stock::<AdditivePairingShare<E>>(path),
+            Sharing::Spdz => stock::<SpdzPairingShare<E>>(path),
+            Sharing::Gsz => stock::<GszPairingShare<E>>(path),
+        }?;
+        readiness.update(|s| s.stock = Some(stock));
+    }
+    if party < 2 && config.input.is_none() {
+        return Err(PartyError::Parameters(format!(
+            "party {} needs an input",
+            party
+        )));
+    }
+    let hosts = wait_for_parties(
+        rt,
+        client,
+        &config.session,
+        session.parties,
+        config.join_timeout,
+    )?;
+    let path = env::temp_dir().join(format!(
+        "mpc-coordinator-{}-{}-{}.hosts",
+        process::id(),
+        config.session,
+        party
+    ));
+    fs::write(&path, hosts.join("\n") + "\n")?;
+    let net = Network::join(
+        path.to_str().expect("temporary path is not UTF-8"),
+        party,
+        config.net.clone(),
+    );
+    let _ = fs::remove_file(&path);
+    let _net = net?;
+    readiness.update(|s| s.peers = true);
+    let lost = readiness.clone();
+    MpcMultiNet::on_unreachable(move |e| {
+        warn!("{}", e);
+        lost.update(|s| s.peers = false);
+    });
+    let follower = match &config.profile {
+        Some(dir) => Some(follow_profiling(rt, client, &config.session, dir)?),
+        None => None,
+    };
+    let proved = catch_abort(|| match session.sharing {
+        Sharing::Hbc => prove::<AdditivePairingShare<E>>(party, config.input, &session.pk),
+        Sharing::Spdz => prove::<SpdzPairingShare<E>>(party, config.input, &session.pk),
+        Sharing::Gsz => prove::<GszPairingShare<E>>(party, config.input, &session.pk),
+    });
+    if let Some(follower) = follower {
+        follower.abort();
+    }
+    // the network goes with this call
+    readiness.update(|s| s.peers = false);
+    if profile::is_active() {
+        match profile::finish() {
+            Ok(paths) => paths.iter().for_each(|p| info!("Profile: {}", p.display())),
+            Err(e) => warn!("Could not write the profile: {}", e),
+        }
+    }
+    let (proof, public_inputs) = proved??;
+    Ok(Submitted {
+        party,
+        proof,
+        public_inputs,
+    })
+}
+
+/// What the store at `path` holds, as shares of `S`'s scalars.
+fn stock<S: PairingShare<E>>(path: &Path) -> Result<Stock, StoreError> {
+    Preprocessed::<Fr, S::FrShare, _>::open(SledStore::open(path)?)?.stock()
+}
+
+/// Poll the coordinator until all `parties` of `session` have joined, and return where they
+/// listen.
+fn wait_for_parties(
+    rt: &Runtime,
+    client: &mut CoordinatorClient<Channel>,
+    session: &str,
+    parties: usize,
+    timeout: Duration,
+) -> Result<Vec<String>, PartyError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let request = StatusRequest {
+            session: session.to_owned(),
+        };
+        let status = rt.block_on(client.status(request))?.into_inner();
+        match status.state() {
+            State::Joining => {}
+            State::Proving => {
+                debug_assert_eq!(status.hosts.len(), parties);
+                return Ok(status.hosts);
+            }
+            State::Done | State::Failed => return Err(PartyError::Ended(status.error)),
+        }
+        if Instant::now() > deadline {
+            return Err(PartyError::JoinTimeout);
+        }
+        thread::sleep(POLL);
+    }
+}
+
+/// Profile this party as the coordinator says the session's parties are to, writing to `dir`,
+/// from now on: as it says now, and then as it changes, until the returned task is aborted.
+fn follow_profiling(
+    rt: &Runtime,
+    client: &mut CoordinatorClient<Channel>,
+    session: &str,
+    dir: &Path,
+) -> Result<JoinHandle<()>, PartyError> {
+    let apply = {
+        let dir = dir.to_owned();
+        move |profiling: &Profiling| {
+            if profiling.enabled || profile::is_active() {
+                profile::start(profile::ProfileConfig {
+                    dir: dir.clone(),
+                    phases: profiling.phases.clone(),
+                    cpu: profiling.cpu,
+                });
+                profile::set_enabled(profiling.enabled);
+            }
+        }
+    };
+    let request = StatusRequest {
+        session: session.to_owned(),
+    };
+    let status = rt.block_on(client.status(request.clone()))?;
+    let mut current = status.into_inner().profiling.unwrap_or_default();
+    apply(&current);
+    let mut client = client.clone();
+    Ok(rt.spawn(async move {
+        loop {
+            tokio::time::sleep(POLL).await;
+            // a missed poll only delays the change
+            if let Ok(status) = client.status(request.clone()).await {
+                let profiling = status.into_inner().profiling.unwrap_or_default();
+                if profiling != current {
+                    apply(&profiling);
+                    current = profiling;
+                }
+            }
+        }
+    }))
+}
+
+/// Prove the multiplication circuit with `input` as this party's secret, and open the proof.
+fn prove<S: PairingShare<E>>(
+    party: usize,
+    input: Option<u64>,
+    pk: &ProvingKey<E>,
+) -> Result<(Proof<E>, Vec<Fr>), ProveError> {
+    S::FrShare::init_protocol();
+    let rng = &mut seed::rng(b"coordinated party");
+    let plain = MultiplicationCircuit {
+        a: input.map(Fr::from).filter(|_| party == 0),
+        b: input.map(Fr::from).filter(|_| party == 1),
+    };
+    let circuit = plain.share::<S::FrShare, _>(rng);
+    let c = (circuit.a.unwrap() * circuit.b.unwrap()).reveal();
+    let mpc_pk = share_proving_key::<E, S>(pk);
+    let proof = prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default())
+        .map(|(proof, _)| reveal_proof::<E, S>(proof));
+    S::FrShare::deinit_protocol();
+    Ok((proof?, vec![c]))
+}
@@ -0,0 +1,363 @@
+//! The coordinator's gRPC service, which keeps its sessions in memory.
+//!
+//! A session's id is random, so that only those it was given to can name it. Joining it takes one
+//! of the tickets the operator got on starting it, each good for one party, so that no one else
+//! can take a party's place. Each request that acts for a session's operator or one of its
+//! parties must carry the token the operator got on starting the session, or the party on joining
+//! it: only the operator may change how the parties profile, and only a party may submit as
+//! itself. Reading where a session stands or its proof, which are public, needs no token.
+//!
+//! A session is forgotten once no request has named it for the service's time to live, and the
+//! service refuses to start sessions beyond its limit, so that abandoned sessions do not pile up.
+use ark_bls12_377::Fr;
+use ark_groth16::Proof;
+use ark_serialize::CanonicalDeserialize;
+use log::info;
+use mpc_snarks::verify;
+use rand::Rng;
+use tonic::{Request, Response, Status};
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::proto::coordinator_server::{Coordinator, CoordinatorServer};
+use crate::proto::status_reply::State;
+use crate::proto::*;
+use crate::{Session, E};
+
+/// How long a session is kept, by default, after the last request that named it.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// How many sessions are kept at once, by default.
+pub const DEFAULT_MAX_SESSIONS: usize = 1024;
+
+/// The coordinator: its sessions, by id.
+pub struct CoordinatorService {
+    sessions: Mutex<Sessions>,
+    /// How long a session is kept after the last request that named it.
+    ttl: Duration,
+    /// How many sessions are kept at once.
+    max_sessions: usize,
+}
+
+impl Default for CoordinatorService {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL, DEFAULT_MAX_SESSIONS)
+    }
+}
+
+#[derive(Default)]
+struct Sessions {
+    by_id: HashMap<String, Entry>,
+}
+
+impl Sessions {
+    /// Forget the sessions no request has named for `ttl`.
+    fn evict(&mut self, ttl: Duration) {
+        self.by_id.retain(|id, entry| {
+            let kept = entry.touched.elapsed() < ttl;
+            if !kept {
+                info!("Session {}: evicted", id);
+            }
+            kept
+        });
+    }
+}
+
+/// A session, and how far it has got.
+struct Entry {
+    parameters: Parameters,
+    session: Session,
+    /// Where the parties that have joined listen, by id.
+    hosts: Vec<String>,
+    /// The parties' submissions, by id: a proof and its public inputs, serialized.
+    submitted: Vec<Option<(Vec<u8>, Vec<u8>)>>,
+    /// The session's proof, once it has one, or why it never will.
+    verdict: Option<Result<(Vec<u8>, Vec<u8>), String>>,
+    /// How the parties are to profile themselves.
+    profiling: Profiling,
+    /// The operator's token.
+    token: String,
+    /// The parties' tickets, until each is joined with.
+    tickets: Vec<Option<String>>,
+    /// The tokens of the parties that have joined, by id.
+    tokens: Vec<String>,
+    /// When a request last named the session.
+    touched: Instant,
+}
+
+impl Entry {
+    /// Once every party has submitted, the verdict on their submissions.
+    fn judge(&self) -> Option<Result<(Vec<u8>, Vec<u8>), String>> {
+        let submitted: Option<Vec<_>> = self.submitted.iter().cloned().collect();
+        let submitted = submitted?;
+        let first = &submitted[0];
+        if submitted.iter().any(|s| s != first) {
+            return Some(Err("the parties opened different proofs".into()));
+        }
+        let (proof, inputs) = first;
+        let verified = Proof::<E>::deserialize_uncompressed(&proof[..])
+            .and_then(|proof| Ok((proof, Vec::<Fr>::deserialize_uncompressed(&inputs[..])?)))
+            .map_err(|e| format!("the parties' proof does not decode: {}", e))
+            .map(|(proof, inputs)| verify(&self.session.pk.vk, &proof, &inputs).unwrap_or(false));
+        Some(match verified {
+            Ok(true) => Ok(first.clone()),
+            Ok(false) => Err("the parties' proof does not verify".into()),
+            Err(e) => Err(e),
+        })
+    }
+
+    fn state(&self) -> State {
+        match &self.verdict {
+            Some(Ok(_)) => State::Done,
+            Some(Err(_)) => State::Failed,
+            None if self.hosts.len() == self.session.parties => State::Proving,
+            None => State::Joining,
+        }
+    }
+}
+
+impl CoordinatorService {
+    /// A coordinator that keeps each session for `ttl` after the last request that names it, and
+    /// at most `max_sessions` at once.
+    pub fn new(ttl: Duration, max_sessions: usize) -> Self {
+        Self {
+            sessions: Mutex::default(),
+            ttl,
+            max_sessions,
+        }
+    }
+
+    /// The service, with the default limits, to serve with [tonic::transport::Server].
+    pub fn server() -> CoordinatorServer<Self> {
+        CoordinatorServer::new(Self::default())
+    }
+
+    /// Run `f` on session `id`.
+    fn with<T>(
+        &self,
+        id: &str,
+        f: impl FnOnce(&mut Entry) -> Result<T, Status>,
+    ) -> Result<T, Status> {
+        let mut sessions = self.sessions.lock().expect("poisoned sessions");
+        sessions.evict(self.ttl);
+        let entry = sessions
+            .by_id
+            .get_mut(id)
+            .ok_or_else(|| Status::not_found(format!("no session {}", id)))?;
+        entry.touched = Instant::now();
+        f(entry)
+    }
+}
+
+/// A new session id, ticket or token: 128 random bits, in hex.
+fn token() -> String {
+    let bytes: [u8; 16] = rand::thread_rng().gen();
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Whether `given` is `token`, in time that does not depend on where they differ.
+fn is_token(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[tonic::async_trait]
+impl Coordinator for CoordinatorService {
+    async fn start_session(
+        &self,
+        request: Request<StartSessionRequest>,
+    ) -> Result<Response<StartSessionReply>, Status> {
+        let parameters = request
+            .into_inner()
+            .parameters
+            .ok_or_else(|| Status::invalid_argument("no parameters"))?;
+        let session = Session::new(&parameters).map_err(Status::invalid_argument)?;
+        let mut sessions = self.sessions.lock().expect("poisoned sessions");
+        sessions.evict(self.ttl);
+        if sessions.by_id.len() >= self.max_sessions {
+            return Err(Status::resource_exhausted(format!(
+                "{} sessions are open already",
+                sessions.by_id.len()
+            )));
+        }
+        let id = token();
+        info!(
+            "Session {}: {} parties, {} shares",
+            id, session.parties, session.sharing
+        );
+        let entry = Entry {
+            submitted: vec![None; session.parties],
+            tickets: (0..session.parties).map(|_| Some(token())).collect(),
+            parameters,
+            session,
+            hosts: Vec::new(),
+            verdict: None,
+            profiling: Profiling::default(),
+            token: token(),
+            tokens: Vec::new(),
+            touched: Instant::now(),
+        };
+        let reply = StartSessionReply {
+            session: id.clone(),
+            token: entry.token.clone(),
+            tickets: entry.tickets.iter().flatten().cloned().collect(),
+        };
+        sessions.by_id.insert(id, entry);
+        Ok(Response::new(reply))
+    }
+
+    async fn join(&self, request: Request<JoinRequest>) -> Result<Response<JoinReply>, Status> {
+        let request = request.into_inner();
+        let id = request.session.clone();
+        self.with(&id, |entry| {
+            let ticket = entry
+                .tickets
+                .iter()
+                .position(|t| matches!(t, Some(t) if is_token(&request.ticket, t)))
+                .ok_or_else(|| {
+                    Status::unauthenticated(format!(
+                        "not an unused ticket of session {}",
+                        request.session
+                    ))
+                })?;
+            if entry.hosts.contains(&request.address) {
+                return Err(Status::already_exists(format!(
+                    "a party of session {} already listens at {}",
+                    request.session, request.address
+                )));
+            }
+            let party = entry.hosts.len() as u32;
+            info!(
+                "Session {}: party {} at {}",
+                request.session, party, request.address
+            );
+            entry.tickets[ticket] = None;
+            entry.hosts.push(request.address);
+            entry.tokens.push(token());
+            Ok(Response::new(JoinReply {
+                party,
+                parameters: Some(entry.parameters.clone()),
+                token: entry.tokens[party as usize].clone(),
+            }))
+        })
+    }
+
+    async fn status(
+        &self,
+        request: Request<StatusRequest>,
+    ) -> Result<Response<StatusReply>, Status> {
+        self.with(&request.into_inner().session, |entry| {
+            let mut reply = StatusReply {
+                state: 0,
+                hosts: entry.hosts.clone(),
+                submitted: entry.submitted.iter().filter(|s| s.is_some()).count() as u32,
+                error: match &entry.verdict {
+                    Some(Err(e)) => e.clone(),
+                    _ => String::new(),
+                },
+                profiling: Some(entry.profiling.clone()),
+            };
+            reply.set_state(entry.state());
+            Ok(Response::new(reply))
+        })
+    }
+
+    async fn submit_shares(
+        &self,
+        request: Request<SubmitSharesRequest>,
+    ) -> Result<Response<SubmitSharesReply>, Status> {
+        let request = request.into_inner();
+        let id = request.session.clone();
+        self.with(&id, |entry| {
+            let party = request.party as usize;
+            if party >= entry.hosts.len() {
+                return Err(Status::invalid_argument(format!(
+                    "no party {} in session {}",
+                    party, request.session
+                )));
+            }
+            if !is_token(&request.token, &entry.tokens[party]) {
+                return Err(Status::unauthenticated(format!(
+                    "not party {} of session {}",
+                    party, request.session
+                )));
+            }
+            if entry.submitted[party].is_some() {
+                return Err(Status::already_exists(format!(
+                    "party {} of session {} has submitted already",
+                    party, request.session
+                )));
+            }
+            if !request.error.is_empty() {
+                info!(
+                    "Session {}: party {} failed: {}",
+                    request.session, party, request.error
+                );
+                // the first failure is the session's
+                if entry.verdict.is_none() {
+                    entry.verdict = Some(Err(format!("party {}: {}", party, request.error)));
+                }
+                // nothing to submit, but no more to wait for either
+                entry.submitted[party] = Some(Default::default());
+                return Ok(Response::new(SubmitSharesReply {}));
+            }
+            entry.submitted[party] = Some((request.proof, request.public_inputs));
+            if entry.verdict.is_none() {
+                entry.verdict = entry.judge();
+                match &entry.verdict {
+                    Some(Ok(_)) => info!("Session {}: done", request.session),
+                    Some(Err(e)) => info!("Session {}: failed: {}", request.session, e),
+                    None => {}
+                }
+            }
+            Ok(Response::new(SubmitSharesReply {}))
+        })
+    }
+
+    async fn get_proof(
+        &self,
+        request: Request<GetProofRequest>,
+    ) -> Result<Response<GetProofReply>, Status> {
+        let id = request.into_inner().session;
+        self.with(&id, |entry| match &entry.verdict {
+            None => Err(Status::failed_precondition(format!(
+                "session {} has no proof yet",
+                id
+            ))),
+            Some(Err(e)) => Err(Status::aborted(format!("session {} failed: {}", id, e))),
+            Some(Ok((proof, public_inputs))) => Ok(Response::new(GetProofReply {
+                proof: proof.clone(),
+                public_inputs: public_inputs.clone(),
+            })),
+        })
+    }
+
+    async fn set_profiling(
+        &self,
+        request: Request<SetProfilingRequest>,
+    ) -> Result<Response<SetProfilingReply>, Status> {
+        let SetProfilingRequest {
+            session,
+            profiling,
+            token,
+        } = request.into_inner();
+        let profiling = profiling.unwrap_or_default();
+        self.with(&session, |entry| {
+            if !is_token(&token, &entry.token) {
+                return Err(Status::unauthenticated(format!(
+                    "not the operator of session {}",
+                    session
+                )));
+            }
+            info!("Session {}: profiling {:?}", session, profiling);
+            entry.profiling = profiling;
+            Ok(Response::new(SetProfilingReply {}))
+        })
+    }
+}
@@ -0,0 +1,461 @@
+//! Sessions on an in-process coordinator, with the parties run as processes, and their health.
+use ark_bls12_377::Fr;
+use ark_groth16::Proof;
+use ark_serialize::CanonicalDeserialize;
+use mpc_algebra::share::store::Stock;
+use mpc_coordinator::health::{self, Readiness};
+use mpc_coordinator::proto::coordinator_client::CoordinatorClient;
+use mpc_coordinator::proto::coordinator_server::CoordinatorServer;
+use mpc_coordinator::proto::status_reply::State;
+use mpc_coordinator::proto::*;
+use mpc_coordinator::service::CoordinatorService;
+use mpc_coordinator::{multiplication_key, multiplication_parameters, Sharing, E};
+use mpc_snarks::verify;
+use tokio::net::TcpListener;
+use tokio::runtime::Runtime;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::{Channel, Server};
+use tonic::Code;
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
+
+use std::path::Path;
+use std::process::{self, Child, Command, Stdio};
+use std::time::Duration;
+use std::{fs, io};
+
+/// Serve a coordinator on a free port, and return its URL.
+async fn serve() -> String {
+    serve_with(CoordinatorService::default()).await
+}
+
+async fn serve_with(service: CoordinatorService) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(
+        Server::builder()
+            .add_service(CoordinatorServer::new(service))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    url
+}
+
+/// `n` distinct free ports on localhost.
+fn free_addresses(n: usize) -> Vec<String> {
+    // hold every port until all are chosen, so that they are distinct
+    let listeners = (0..n)
+        .map(|_| std::net::TcpListener::bind("127.0.0.1:0"))
+        .collect::<io::Result<Vec<_>>>()
+        .unwrap();
+    listeners
+        .iter()
+        .map(|l| l.local_addr().unwrap().to_string())
+        .collect()
+}
+
+/// Start a session: its id, the operator's token and the parties' tickets.
+async fn start(
+    client: &mut CoordinatorClient<Channel>,
+    parties: usize,
+    sharing: Sharing,
+) -> StartSessionReply {
+    let pk = multiplication_key(&mut ark_std::test_rng());
+    client
+        .start_session(StartSessionRequest {
+            parameters: Some(multiplication_parameters(parties, sharing, &pk)),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+}
+
+fn status(session: &str) -> StatusRequest {
+    StatusRequest {
+        session: session.to_owned(),
+    }
+}
+
+async fn joined(client: &mut CoordinatorClient<Channel>, session: &str) -> usize {
+    let status = client.status(status(session)).await.unwrap();
+    status.into_inner().hosts.len()
+}
+
+fn spawn_party(
+    url: &str,
+    session: &str,
+    ticket: &str,
+    address: &str,
+    input: Option<u64>,
+    profile: Option<&Path>,
+    flags: &[&str],
+) -> Child {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_mpc-coordinator"));
+    command
+        .args(["party", "--coordinator", url, "--listen", address])
+        .args(["--session", session, "--ticket", ticket, "--join-timeout", "30"])
+        .stdout(Stdio::null());
+    if let Some(input) = input {
+        command.args(["--input", &input.to_string()]);
+    }
+    if let Some(dir) = profile {
+        command.arg("--profile").arg(dir);
+    }
+    command.args(flags).spawn().unwrap()
+}
+
+/// Run a session of `parties` with `sharing` to the end, with the parties joining in turn, and
+/// check its proof. With `profile`, the session's parties profile themselves to it.
+async fn prove(parties: usize, sharing: Sharing, profile: Option<&Path>) {
+    let url = serve().await;
+    let mut client = CoordinatorClient::connect(url.clone()).await.unwrap();
+    let StartSessionReply {
+        session,
+        token,
+        tickets,
+    } = start(&mut client, parties, sharing).await;
+    if profile.is_some() {
+        let profiling = Profiling {
+            enabled: true,
+            ..Default::default()
+        };
+        client
+            .set_profiling(SetProfilingRequest {
+                session: session.clone(),
+                profiling: Some(profiling),
+                token,
+            })
+            .await
+            .unwrap();
+    }
+    let inputs = [Some(3), Some(5)];
+    let mut children = Vec::new();
+    for (i, address) in free_addresses(parties).iter().enumerate() {
+        let input = inputs.get(i).cloned().flatten();
+        let party = spawn_party(&url, &session, &tickets[i], address, input, profile, &[]);
+        children.push(party);
+        // the parties' ids are the order they join in: let each join before the next
+        while joined(&mut client, &session).await <= i {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+    for mut child in children {
+        let status = tokio::task::spawn_blocking(move || child.wait().unwrap())
+            .await
+            .unwrap();
+        assert!(status.success());
+    }
+
+    let status = client.status(status(&session)).await.unwrap().into_inner();
+    assert_eq!(status.state(), State::Done, "{}", status.error);
+    assert_eq!(status.submitted as usize, parties);
+    let reply = client
+        .get_proof(GetProofRequest { session })
+        .await
+        .unwrap()
+        .into_inner();
+    let proof = Proof::<E>::deserialize_uncompressed(&reply.proof[..]).unwrap();
+    let public_inputs = Vec::<Fr>::deserialize_uncompressed(&reply.public_inputs[..]).unwrap();
+    assert_eq!(public_inputs, vec![Fr::from(15u64)]);
+    let pk = multiplication_key(&mut ark_std::test_rng());
+    assert!(verify(&pk.vk, &proof, &public_inputs).unwrap());
+}
+
+#[tokio::test]
+async fn hbc_session() {
+    prove(2, Sharing::Hbc, None).await;
+}
+
+#[tokio::test]
+async fn gsz_session() {
+    prove(3, Sharing::Gsz, None).await;
+}
+
+#[tokio::test]
+async fn profiled_session() {
+    let dir = std::env::temp_dir().join(format!("mpc-coordinator-profile-{}", process::id()));
+    prove(2, Sharing::Hbc, Some(&dir)).await;
+    for party in 0..2 {
+        let folded = fs::read_to_string(dir.join(format!("party{}.folded", party))).unwrap();
+        assert!(folded.lines().count() > 0);
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn refuses_bad_requests() {
+    let url = serve().await;
+    let mut client = CoordinatorClient::connect(url).await.unwrap();
+    let pk = multiplication_key(&mut ark_std::test_rng());
+
+    let mut parameters = multiplication_parameters(2, Sharing::Hbc, &pk);
+    parameters.sharing = "shamir".into();
+    let refused = client
+        .start_session(StartSessionRequest {
+            parameters: Some(parameters),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(refused.code(), Code::InvalidArgument);
+    let refused = client
+        .start_session(StartSessionRequest {
+            parameters: Some(multiplication_parameters(2, Sharing::Gsz, &pk)),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(refused.code(), Code::InvalidArgument);
+
+    // session ids are random, not counted
+    let StartSessionReply {
+        session,
+        token,
+        tickets,
+    } = start(&mut client, 2, Sharing::Hbc).await;
+    let other = start(&mut client, 2, Sharing::Hbc).await;
+    assert_eq!(session.len(), 32);
+    assert_ne!(session, other.session);
+    assert_eq!(tickets.len(), 2);
+
+    let missing = client.status(status("1")).await.unwrap_err();
+    assert_eq!(missing.code(), Code::NotFound);
+    let missing = client
+        .set_profiling(SetProfilingRequest {
+            session: "1".into(),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(missing.code(), Code::NotFound);
+
+    let not_operator = client
+        .set_profiling(SetProfilingRequest {
+            session: session.clone(),
+            token: "0".repeat(token.len()),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(not_operator.code(), Code::Unauthenticated);
+    client
+        .set_profiling(SetProfilingRequest {
+            session: session.clone(),
+            token: token.clone(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let join = |address: &str, ticket: &str| JoinRequest {
+        session: session.clone(),
+        address: address.into(),
+        ticket: ticket.into(),
+    };
+    // neither the operator's token nor another session's ticket lets anyone join
+    for ticket in &[&token, &other.tickets[0]] {
+        let uninvited = client.join(join("127.0.0.1:1", ticket)).await.unwrap_err();
+        assert_eq!(uninvited.code(), Code::Unauthenticated);
+    }
+    let joined = client.join(join("127.0.0.1:1", &tickets[1])).await;
+    let joined = joined.unwrap().into_inner();
+    assert_eq!(joined.party, 0);
+    assert_eq!(joined.parameters.unwrap().parties, 2);
+    let duplicate = client.join(join("127.0.0.1:1", &tickets[0])).await;
+    assert_eq!(duplicate.unwrap_err().code(), Code::AlreadyExists);
+    // a ticket lets only one party join
+    let reused = client.join(join("127.0.0.1:2", &tickets[1])).await;
+    assert_eq!(reused.unwrap_err().code(), Code::Unauthenticated);
+    let second = client.join(join("127.0.0.1:2", &tickets[0])).await;
+    let second = second.unwrap().into_inner();
+    assert_eq!(second.party, 1);
+
+    let early = client
+        .get_proof(GetProofRequest {
+            session: session.clone(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(early.code(), Code::FailedPrecondition);
+    let failure = |token: &str| SubmitSharesRequest {
+        session: session.clone(),
+        party: 1,
+        error: "out of memory".into(),
+        token: token.into(),
+        ..Default::default()
+    };
+    // party 0 cannot submit as party 1
+    let impostor = client.submit_shares(failure(&joined.token)).await;
+    assert_eq!(impostor.unwrap_err().code(), Code::Unauthenticated);
+    client.submit_shares(failure(&second.token)).await.unwrap();
+    let status = client.status(status(&session)).await.unwrap().into_inner();
+    assert_eq!(status.state(), State::Failed);
+    assert_eq!(status.error, "party 1: out of memory");
+    let failed = client
+        .get_proof(GetProofRequest { session })
+        .await
+        .unwrap_err();
+    assert_eq!(failed.code(), Code::Aborted);
+}
+
+#[tokio::test]
+async fn evicts_sessions() {
+    let ttl = Duration::from_secs(2);
+    let url = serve_with(CoordinatorService::new(ttl, 2)).await;
+    let mut client = CoordinatorClient::connect(url).await.unwrap();
+    let pk = multiplication_key(&mut ark_std::test_rng());
+    let request = StartSessionRequest {
+        parameters: Some(multiplication_parameters(2, Sharing::Hbc, &pk)),
+    };
+    let first = client.start_session(request.clone()).await.unwrap();
+    let second = client.start_session(request.clone()).await.unwrap();
+    let (first, second) = (first.into_inner().session, second.into_inner().session);
+    let full = client.start_session(request.clone()).await.unwrap_err();
+    assert_eq!(full.code(), Code::ResourceExhausted);
+
+    // a request keeps a session, and the others go
+    tokio::time::sleep(ttl * 3 / 4).await;
+    client.status(status(&second)).await.unwrap();
+    tokio::time::sleep(ttl * 3 / 4).await;
+    let evicted = client.status(status(&first)).await.unwrap_err();
+    assert_eq!(evicted.code(), Code::NotFound);
+    client.status(status(&second)).await.unwrap();
+    client.start_session(request).await.unwrap();
+}
+
+/// Whether `mpc-coordinator health` passes against `url`, with `flags`.
+async fn health_command(url: &str, flags: &[&str]) -> bool {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_mpc-coordinator"));
+    command
+        .args(["health", "--coordinator", url])
+        .args(flags)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    let status = tokio::task::spawn_blocking(move || command.status().unwrap());
+    status.await.unwrap().success()
+}
+
+#[tokio::test]
+async fn reports_health() {
+    let (mut reporter, health_service) = health::health_reporter();
+    reporter
+        .set_service_status(health::SERVICE, health::ServingStatus::Serving)
+        .await;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(
+        Server::builder()
+            .add_service(health_service)
+            .add_service(CoordinatorService::server())
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    let mut client = health_client(&url).await;
+    for service in &["", health::SERVICE] {
+        let reply = client.check(check(service)).await.unwrap().into_inner();
+        assert_eq!(reply.status(), ServingStatus::Serving);
+    }
+    let unknown = client.check(check("nonesuch")).await.unwrap_err();
+    assert_eq!(unknown.code(), Code::NotFound);
+    assert!(health_command(&url, &[]).await && health_command(&url, &["--ready"]).await);
+
+    let mut watch = client
+        .watch(check(health::SERVICE))
+        .await
+        .unwrap()
+        .into_inner();
+    let first = watch.message().await.unwrap().unwrap();
+    assert_eq!(first.status(), ServingStatus::Serving);
+    // as on shutdown, the coordinator stops being ready, but stays live
+    reporter
+        .set_service_status(health::SERVICE, health::ServingStatus::NotServing)
+        .await;
+    let next = watch.message().await.unwrap().unwrap();
+    assert_eq!(next.status(), ServingStatus::NotServing);
+    let reply = client.check(check(health::SERVICE)).await.unwrap();
+    assert_eq!(reply.into_inner().status(), ServingStatus::NotServing);
+    assert!(health_command(&url, &[]).await && !health_command(&url, &["--ready"]).await);
+
+    let unknown = client.watch(check("nonesuch")).await.unwrap_err();
+    assert_eq!(unknown.code(), Code::NotFound);
+}
+
+fn check(service: &str) -> HealthCheckRequest {
+    HealthCheckRequest {
+        service: service.to_owned(),
+    }
+}
+
+async fn health_client(url: &str) -> HealthClient<Channel> {
+    let channel = Channel::from_shared(url.to_owned()).unwrap();
+    HealthClient::new(channel.connect().await.unwrap())
+}
+
+#[test]
+fn reports_party_readiness() {
+    let rt = Runtime::new().unwrap();
+    let (reporter, health_service) = health::health_reporter();
+    let min = Stock {
+        triples: 2,
+        ..Stock::default()
+    };
+    let readiness = Readiness::new(reporter, rt.handle().clone(), Some(min));
+    let listener = rt.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    rt.spawn(
+        Server::builder()
+            .add_service(health_service)
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    let mut client = rt.block_on(health_client(&url));
+    let mut status = |service: &str| {
+        let reply = rt.block_on(client.check(check(service))).unwrap();
+        reply.into_inner().status()
+    };
+    assert_eq!(status(""), ServingStatus::Serving);
+    assert_eq!(status(health::PARTY), ServingStatus::NotServing);
+
+    readiness.update(|s| s.key = true);
+    readiness.update(|s| s.peers = true);
+    // not until it has read its store
+    assert_eq!(status(health::PARTY), ServingStatus::NotServing);
+    readiness.update(|s| s.stock = Some(Stock::default()));
+    assert_eq!(status(health::PARTY), ServingStatus::NotServing);
+    readiness.update(|s| s.stock = Some(min));
+    assert_eq!(status(health::PARTY), ServingStatus::Serving);
+    assert!(rt.block_on(health_command(&url, &["--party", "--ready"])));
+
+    // as when a heartbeat finds a peer unreachable
+    readiness.update(|s| s.peers = false);
+    assert_eq!(status(health::PARTY), ServingStatus::NotServing);
+    assert!(rt.block_on(health_command(&url, &["--party"])));
+    assert!(!rt.block_on(health_command(&url, &["--party", "--ready"])));
+}
+
+#[tokio::test]
+async fn party_serves_health() {
+    let url = serve().await;
+    let mut client = CoordinatorClient::connect(url.clone()).await.unwrap();
+    let StartSessionReply {
+        session, tickets, ..
+    } = start(&mut client, 2, Sharing::Hbc).await;
+    let addresses = free_addresses(3);
+    let health_url = format!("http://{}", addresses[2]);
+    // an empty store, which never lets the party be ready, but does not keep it from proving
+    let store = std::env::temp_dir().join(format!("mpc-coordinator-store-{}", process::id()));
+    let flags = ["--health", &addresses[2], "--store", store.to_str().unwrap()];
+    let first = spawn_party(&url, &session, &tickets[0], &addresses[0], Some(3), None, &flags);
+    // live once it serves, but not ready while the other party has yet to join
+    let mut tries = 0;
+    while !health_command(&health_url, &["--party"]).await {
+        tries += 1;
+        assert!(tries < 300, "the party does not serve its health");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(!health_command(&health_url, &["--party", "--ready"]).await);
+
+    let second = spawn_party(&url, &session, &tickets[1], &addresses[1], Some(5), None, &[]);
+    for mut child in [first, second] {
+        let status = tokio::task::spawn_blocking(move || child.wait().unwrap());
+        assert!(status.await.unwrap().success());
+    }
+    let status = client.status(status(&session)).await.unwrap().into_inner();
+    assert_eq!(status.state(), State::Done, "{}", status.error);
+    fs::remove_dir_all(&store).unwrap();
+}
@@ -0,0 +1,5 @@
+//! One party of the built-in integration scenarios.
+fn main() {
+    env_logger::init();
+    mpc_integration::party_main(&mpc_integration::scenarios::builtin())
+}
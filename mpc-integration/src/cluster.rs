@@ -0,0 +1,184 @@
+//! Running a scenario with one process per party.
+use std::fs;
+use std::io::{self, Read};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::{Outcome, Report, EXIT_ABORTED, EXIT_CRASHED};
+
+/// `n` processes of a party binary (one whose `main` is [party_main](crate::party_main)),
+/// connected over TCP on localhost.
+#[derive(Clone, Debug)]
+pub struct Cluster {
+    program: PathBuf,
+    n: usize,
+    timeout: Duration,
+}
+
+/// A running party process, and the threads collecting its output.
+struct Spawned {
+    child: Child,
+    stdout: JoinHandle<String>,
+    stderr: JoinHandle<String>,
+}
+
+impl Cluster {
+    pub fn new(program: impl AsRef<Path>, n: usize) -> Self {
+        assert!(n > 0, "no parties to run");
+        Self {
+            program: program.as_ref().to_owned(),
+            n,
+            timeout: Duration::from_secs(120),
+        }
+    }
+
+    /// How long a run may take before its processes are killed. Two minutes by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run the scenario named `scenario` in fresh processes, on free ports, and return the
+    /// parties' outcomes in order.
+    ///
+    /// Fails if the processes cannot be started, or have not all exited within the timeout.
+    pub fn run(&self, scenario: &str) -> io::Result<Vec<Outcome>> {
+        let hosts = HostsFile::new(self.n)?;
+        let mut parties = Vec::with_capacity(self.n);
+        for i in 0..self.n {
+            let spawned = Command::new(&self.program)
+                .arg("--hosts")
+                .arg(&hosts.path)
+                .arg("--party")
+                .arg(i.to_string())
+                .arg("--scenario")
+                .arg(scenario)
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn();
+            match spawned {
+                Ok(mut child) => {
+                    let stdout = collect(child.stdout.take().unwrap());
+                    let stderr = collect(child.stderr.take().unwrap());
+                    parties.push(Spawned {
+                        child,
+                        stdout,
+                        stderr,
+                    });
+                }
+                Err(e) => {
+                    kill_all(&mut parties);
+                    return Err(e);
+                }
+            }
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        let mut statuses: Vec<Option<ExitStatus>> = vec![None; self.n];
+        while statuses.iter().any(Option::is_none) {
+            for (p, status) in parties.iter_mut().zip(&mut statuses) {
+                if status.is_none() {
+                    *status = p.child.try_wait()?;
+                }
+            }
+            if Instant::now() > deadline {
+                kill_all(&mut parties);
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!(
+                        "scenario {} did not finish within {:?}",
+                        scenario, self.timeout
+                    ),
+                ));
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        Ok(parties
+            .into_iter()
+            .zip(statuses)
+            .map(|(p, status)| {
+                let stdout = p.stdout.join().unwrap_or_default();
+                let stderr = p.stderr.join().unwrap_or_default();
+                outcome(status.unwrap(), &stdout, &stderr)
+            })
+            .collect())
+    }
+}
+
+fn collect(mut r: impl Read + Send + 'static) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut out = String::new();
+        let _ = r.read_to_string(&mut out);
+        out
+    })
+}
+
+fn kill_all(parties: &mut [Spawned]) {
+    for p in parties {
+        let _ = p.child.kill();
+        let _ = p.child.wait();
+    }
+}
+
+/// Decode a party's outcome from its exit status and what it printed.
+fn outcome(status: ExitStatus, stdout: &str, stderr: &str) -> Outcome {
+    match status.code() {
+        Some(0) => {
+            let mut report = Report::new();
+            for line in stdout.lines() {
+                let mut words = line.splitn(3, ' ');
+                if let (Some("report"), Some(k), v) = (words.next(), words.next(), words.next()) {
+                    report.set(k, v.unwrap_or(""));
+                }
+            }
+            Outcome::Finished(report)
+        }
+        Some(EXIT_ABORTED) => Outcome::Aborted(
+            stdout
+                .lines()
+                .find_map(|l| l.strip_prefix("aborted "))
+                .unwrap_or("")
+                .to_owned(),
+        ),
+        Some(EXIT_CRASHED) => Outcome::Crashed,
+        _ => Outcome::Panicked(format!("party exited with {}:\n{}", status, stderr)),
+    }
+}
+
+/// A temporary hosts file, listing free ports on localhost.
+struct HostsFile {
+    path: PathBuf,
+}
+
+impl HostsFile {
+    fn new(n: usize) -> io::Result<Self> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        // hold every port until all are chosen, so that they are distinct
+        let listeners = (0..n)
+            .map(|_| TcpListener::bind("127.0.0.1:0"))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut text = String::new();
+        for l in &listeners {
+            text.push_str(&format!("{}\n", l.local_addr()?));
+        }
+        let path = std::env::temp_dir().join(format!(
+            "mpc-integration-{}-{}.hosts",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&path, text)?;
+        Ok(Self { path })
+    }
+}
+
+impl Drop for HostsFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,253 @@
+//! End-to-end tests of whole MPC sessions.
+//!
+//! A [Scenario] is a named piece of protocol code that every party runs, ending in a [Report]
+//! of what it saw. The same scenario can run two ways:
+//!
+//! * [run_in_threads] plays every party in this process, over in-memory connections, as
+//!   [mpc_test_utils::run_parties] does;
+//! * a [Cluster] spawns one process per party, connected over TCP on localhost. Each runs a party
+//!   binary whose `main` is just [party_main] over its scenarios.
+//!
+//! Either way, each party ends in an [Outcome], so tests can assert on failures as well as
+//! results. The processes are the real thing: they exercise the TCP transport, reconnection, and
+//! what the other parties see when one of them dies.
+//!
+//! The `mpc-party` binary of this crate runs the built-in [scenarios]. To test your own circuit,
+//! write a binary like it over your own scenarios, and spawn it from a test with
+//! `Cluster::new(env!("CARGO_BIN_EXE_<name>"), n)`.
+use mpc_net::{catch_abort, MpcMultiNet as Net, MpcNet, NetConfig, Stats};
+use structopt::StructOpt;
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub mod cluster;
+pub mod scenarios;
+
+pub use cluster::Cluster;
+
+/// Protocol code for every party to run, under a name by which a [Cluster] selects it.
+#[derive(Clone, Copy)]
+pub struct Scenario {
+    pub name: &'static str,
+    pub run: fn(&Party) -> Report,
+}
+
+/// The party running a scenario.
+#[derive(Clone, Copy, Debug)]
+pub struct Party {
+    pub id: usize,
+    pub n: usize,
+}
+
+/// The payload of a [Party::crash].
+struct Crash;
+
+impl Party {
+    /// Stop this party abruptly, as a crash would: it leaves the session without a word to the
+    /// others, whose next exchange with it fails.
+    pub fn crash(&self) -> ! {
+        panic::resume_unwind(Box::new(Crash))
+    }
+}
+
+/// What a party reports at the end of a scenario: named values, in text.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report(BTreeMap<String, String>);
+
+impl Report {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `value` under `key`, which must be a single word.
+    pub fn set(&mut self, key: &str, value: impl Display) {
+        assert!(
+            !key.is_empty() && !key.contains(char::is_whitespace),
+            "bad report key `{}`",
+            key
+        );
+        let value = value.to_string();
+        assert!(!value.contains('\n'), "multi-line report value for {}", key);
+        self.0.insert(key.to_owned(), value);
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(|v| v.as_str())
+    }
+
+    /// The value under `key`, parsed. Panics if it is missing or malformed.
+    pub fn value<T: FromStr>(&self, key: &str) -> T {
+        let v = self
+            .get(key)
+            .unwrap_or_else(|| panic!("no `{}` in report {:?}", key, self));
+        v.parse()
+            .unwrap_or_else(|_| panic!("bad `{}` in report: {}", key, v))
+    }
+
+    /// Record the party's network statistics, under their field names.
+    pub fn stats(&mut self, stats: &Stats) {
+        self.set("bytes_sent", stats.bytes_sent);
+        self.set("bytes_recv", stats.bytes_recv);
+        self.set("broadcasts", stats.broadcasts);
+        self.set("to_king", stats.to_king);
+        self.set("from_king", stats.from_king);
+        self.set("wire_bytes_sent", stats.wire_bytes_sent);
+        self.set("wire_bytes_recv", stats.wire_bytes_recv);
+        self.set("writes", stats.writes);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+/// How a party's run of a scenario ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// It ran to the end. The report includes the party's network statistics.
+    Finished(Report),
+    /// The network aborted, with this error.
+    Aborted(String),
+    /// The party called [Party::crash].
+    Crashed,
+    /// The party panicked, or its process failed, with this message.
+    Panicked(String),
+}
+
+impl Outcome {
+    /// The report, if the party finished.
+    pub fn report(&self) -> Option<&Report> {
+        match self {
+            Outcome::Finished(r) => Some(r),
+            _ => None,
+        }
+    }
+
+    /// The report, panicking if the party did not finish.
+    pub fn unwrap(self) -> Report {
+        match self {
+            Outcome::Finished(r) => r,
+            o => panic!("party did not finish: {:?}", o),
+        }
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        matches!(self, Outcome::Aborted(_))
+    }
+}
+
+/// The network configuration of a scenario's parties: the default, with timeouts short enough
+/// that a failing scenario ends in seconds rather than minutes.
+pub fn config() -> NetConfig {
+    NetConfig {
+        reconnect_timeout: Duration::from_secs(5),
+        ..mpc_test_utils::test_config()
+    }
+}
+
+/// Run `scenario` as the current party of the current network.
+fn run_party(scenario: &Scenario) -> Outcome {
+    let party = Party {
+        id: Net::party_id(),
+        n: Net::n_parties(),
+    };
+    let r = panic::catch_unwind(AssertUnwindSafe(|| catch_abort(|| (scenario.run)(&party))));
+    match r {
+        Ok(Ok(mut report)) => {
+            report.stats(&Net::stats());
+            Outcome::Finished(report)
+        }
+        Ok(Err(e)) => Outcome::Aborted(e.to_string()),
+        Err(payload) if payload.is::<Crash>() => Outcome::Crashed,
+        Err(payload) => Outcome::Panicked(panic_message(&payload)),
+    }
+}
+
+fn panic_message(payload: &Box<dyn Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_owned()
+    }
+}
+
+/// Run `scenario` as each of `n` parties, on threads of this process, and return their outcomes
+/// in order.
+///
+/// The parties connect in memory, which cannot drop and resume, so scenarios that cut
+/// connections test reconnection only in a [Cluster].
+pub fn run_in_threads(n: usize, scenario: &Scenario) -> Vec<Outcome> {
+    mpc_test_utils::run_parties_with(n, config(), || run_party(scenario))
+}
+
+/// The exit codes of a party process, besides 0 for [Outcome::Finished].
+pub(crate) const EXIT_ABORTED: i32 = 2;
+pub(crate) const EXIT_CRASHED: i32 = 3;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "One party of an MPC integration test")]
+struct Opt {
+    /// File with one `HOST:PORT` per party
+    #[structopt(long, parse(from_os_str))]
+    hosts: PathBuf,
+
+    /// Which party we are
+    #[structopt(long)]
+    party: usize,
+
+    /// The scenario to run
+    #[structopt(long)]
+    scenario: String,
+}
+
+/// The `main` of a party process: run the scenario named on the command line as one party of a
+/// [Cluster], print the report, and exit with a code for the [Outcome].
+///
+/// Usage: `PROGRAM --hosts FILE --party ID --scenario NAME`.
+pub fn party_main(scenarios: &[Scenario]) -> ! {
+    let opt = Opt::from_args();
+    let scenario = match scenarios.iter().find(|s| s.name == opt.scenario) {
+        Some(s) => s,
+        None => {
+            let names: Vec<_> = scenarios.iter().map(|s| s.name).collect();
+            eprintln!(
+                "Unknown scenario `{}`; expected one of {}",
+                opt.scenario,
+                names.join(", ")
+            );
+            process::exit(1)
+        }
+    };
+    Net::set_config(config());
+    let hosts = opt.hosts.to_str().expect("hosts path is not UTF-8");
+    let outcome = match catch_abort(|| Net::init_from_file(hosts, opt.party)) {
+        Ok(()) => run_party(scenario),
+        Err(e) => Outcome::Aborted(e.to_string()),
+    };
+    match outcome {
+        Outcome::Finished(report) => {
+            for (k, v) in report.iter() {
+                println!("report {} {}", k, v);
+            }
+            Net::deinit();
+            process::exit(0)
+        }
+        Outcome::Aborted(e) => {
+            println!("aborted {}", e);
+            process::exit(EXIT_ABORTED)
+        }
+        // exit without closing connections gracefully
+        Outcome::Crashed => process::exit(EXIT_CRASHED),
+        // the panic hook has printed the message
+        Outcome::Panicked(_) => process::exit(101),
+    }
+}
@@ -0,0 +1,106 @@
+//! The built-in scenarios, which the `mpc-party` binary runs.
+//!
+//! * `prove`, `prove-spdz`, `prove-gsz`: prove a small Groth16 statement collaboratively, with
+//!   the honest-but-curious, SPDZ and GSZ20 shares, and report whether the opened proof
+//!   `verified`;
+//! * `abort`: the last party crashes after the first exchange, and the others should abort;
+//! * `reconnect`: party 0 cuts its connections part way through, and the session should resume,
+//!   and still prove.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::UniformRand;
+use mpc_algebra::{
+    AdditivePairingShare, GszPairingShare, MpcField, PairingShare, Reveal, SpdzPairingShare,
+};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::{
+    prove_collaborative, reveal_proof, setup, share_proving_key, verify, ProveOptions,
+};
+
+use crate::{Party, Report, Scenario};
+
+type E = Bls12_377;
+
+pub fn builtin() -> Vec<Scenario> {
+    vec![
+        Scenario {
+            name: "prove",
+            run: prove::<AdditivePairingShare<E>>,
+        },
+        Scenario {
+            name: "prove-spdz",
+            run: prove::<SpdzPairingShare<E>>,
+        },
+        Scenario {
+            name: "prove-gsz",
+            run: prove::<GszPairingShare<E>>,
+        },
+        Scenario {
+            name: "abort",
+            run: abort,
+        },
+        Scenario {
+            name: "reconnect",
+            run: reconnect,
+        },
+    ]
+}
+
+/// Prove knowledge of a factorisation `c = a * b`, with `a` and `b` shared, and check the opened
+/// proof.
+pub fn prove<S: PairingShare<E>>(_: &Party) -> Report {
+    S::FrShare::init_protocol();
+    // every party draws the same keys and inputs
+    let rng = &mut ark_std::test_rng();
+    let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+    let mpc_pk = share_proving_key::<E, S>(&pk);
+
+    let a = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+    let b = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+    let c = (a * b).reveal();
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = reveal_proof::<E, S>(
+        prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default()).unwrap().0,
+    );
+    let ok = verify(&pk.vk, &proof, &[c]).unwrap();
+    let wrong = verify(&pk.vk, &proof, &[c + Fr::from(1u8)]).unwrap();
+    S::FrShare::deinit_protocol();
+
+    let mut report = Report::new();
+    report.set("verified", ok && !wrong);
+    report
+}
+
+/// Each party broadcasts its id, and checks what it got back.
+fn exchange_ids(party: &Party, round: u8) {
+    let all = Net::broadcast_bytes(&[party.id as u8, round]);
+    let expected: Vec<_> = (0..party.n).map(|i| vec![i as u8, round]).collect();
+    assert_eq!(all, expected, "round {}", round);
+}
+
+fn abort(party: &Party) -> Report {
+    exchange_ids(party, 0);
+    if party.id == party.n - 1 {
+        party.crash();
+    }
+    exchange_ids(party, 1);
+    Report::new()
+}
+
+fn reconnect(party: &Party) -> Report {
+    const ROUNDS: u8 = 4;
+    for round in 0..ROUNDS {
+        if party.id == 0 && round == ROUNDS / 2 {
+            for peer in 1..party.n {
+                Net::drop_connection(peer);
+            }
+        }
+        exchange_ids(party, round);
+    }
+    let mut report = prove::<AdditivePairingShare<E>>(party);
+    report.set("rounds", ROUNDS);
+    report
+}
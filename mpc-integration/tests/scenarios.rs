@@ -0,0 +1,80 @@
+//! The built-in scenarios, with the parties run as processes and as threads.
+use mpc_integration::{run_in_threads, scenarios, Cluster, Outcome, Scenario};
+
+fn cluster(n: usize) -> Cluster {
+    Cluster::new(env!("CARGO_BIN_EXE_mpc-party"), n)
+}
+
+fn scenario(name: &str) -> Scenario {
+    scenarios::builtin()
+        .into_iter()
+        .find(|s| s.name == name)
+        .unwrap()
+}
+
+fn check_proved(outcomes: Vec<Outcome>, n: usize) {
+    assert_eq!(outcomes.len(), n);
+    for o in outcomes {
+        let report = o.unwrap();
+        assert!(report.value::<bool>("verified"));
+        assert!(report.value::<usize>("bytes_sent") > 0);
+        assert!(report.value::<usize>("broadcasts") > 0);
+    }
+}
+
+/// The survivors of a crash abort, rather than hang.
+fn check_aborted(outcomes: Vec<Outcome>) {
+    let (last, rest) = outcomes.split_last().unwrap();
+    assert_eq!(*last, Outcome::Crashed);
+    for o in rest {
+        assert!(o.is_aborted(), "{:?}", o);
+    }
+}
+
+#[test]
+fn prove_in_processes() {
+    check_proved(cluster(2).run("prove").unwrap(), 2);
+    check_proved(cluster(3).run("prove-spdz").unwrap(), 3);
+    check_proved(cluster(3).run("prove-gsz").unwrap(), 3);
+}
+
+#[test]
+fn prove_in_threads() {
+    check_proved(run_in_threads(3, &scenario("prove")), 3);
+    check_proved(run_in_threads(2, &scenario("prove-spdz")), 2);
+}
+
+#[test]
+fn abort_in_processes() {
+    check_aborted(cluster(3).run("abort").unwrap());
+}
+
+#[test]
+fn abort_in_threads() {
+    check_aborted(run_in_threads(3, &scenario("abort")));
+}
+
+#[test]
+fn reconnect_in_processes() {
+    let outcomes = cluster(3).run("reconnect").unwrap();
+    for o in &outcomes {
+        assert_eq!(
+            o.report().and_then(|r| r.get("rounds")),
+            Some("4"),
+            "{:?}",
+            o
+        );
+    }
+    check_proved(outcomes, 3);
+}
+
+#[test]
+fn unknown_scenario() {
+    match &cluster(2).run("no-such-scenario").unwrap()[..] {
+        [Outcome::Panicked(a), Outcome::Panicked(b)] => {
+            assert!(a.contains("Unknown scenario `no-such-scenario`"), "{}", a);
+            assert_eq!(a, b);
+        }
+        o => panic!("{:?}", o),
+    }
+}
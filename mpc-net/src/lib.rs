@@ -1,8 +1,20 @@
+pub mod mem;
 pub mod multi;
+pub mod profile;
+#[cfg(feature = "quic")]
+pub mod quic;
+mod seal;
+pub mod transcript;
 pub mod two;
 
 pub use two::MpcTwoNet;
-pub use multi::MpcMultiNet;
+pub use multi::{MpcMultiNet, MpcSession};
+
+use std::fmt::{self, Display, Formatter};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(Clone, Debug)]
 pub struct Stats {
@@ -11,6 +23,20 @@ pub struct Stats {
     pub broadcasts: usize,
     pub to_king: usize,
     pub from_king: usize,
+    /// Bytes actually written to sockets, after framing and compression.
+    pub wire_bytes_sent: usize,
+    /// Bytes actually read from sockets.
+    pub wire_bytes_recv: usize,
+    /// Number of socket writes; batching lowers this.
+    pub writes: usize,
+}
+
+impl Stats {
+    /// How many fewer bytes went out than were sent, thanks to compression (negative if framing
+    /// cost more than compression saved).
+    pub fn bytes_saved(&self) -> i64 {
+        self.bytes_sent as i64 - self.wire_bytes_sent as i64
+    }
 }
 
 impl std::default::Default for Stats {
@@ -21,16 +47,210 @@ impl std::default::Default for Stats {
             broadcasts: 0,
             to_king: 0,
             from_king: 0,
+            wire_bytes_sent: 0,
+            wire_bytes_recv: 0,
+            writes: 0,
         }
     }
 }
 
+/// Timeouts and reconnection policy for [MpcMultiNet]. [MpcTwoNet] takes only the timeouts.
+#[derive(Clone, Debug)]
+pub struct NetConfig {
+    /// How long to keep trying to reach the other parties at start-up.
+    pub connect_timeout: Duration,
+    /// How long a single read or write may block before its peer is presumed dead.
+    ///
+    /// This must exceed the longest local computation of any party. `None` waits forever.
+    pub io_timeout: Option<Duration>,
+    /// How long to spend re-establishing a dropped connection.
+    pub reconnect_timeout: Duration,
+    /// How many times a single exchange may reconnect before giving up.
+    pub max_reconnects: usize,
+    /// How many sent messages to keep per peer, to replay after a reconnection.
+    ///
+    /// If a connection drops with more messages than this in flight, the session can't resume.
+    pub replay_messages: usize,
+    /// How to compress the messages we send. Parties may differ in this, since every frame says
+    /// how it was compressed.
+    pub compression: Compression,
+    /// Whether to coalesce the messages to a peer within an exchange, or within an
+    /// [MpcMultiNet::round], into one write.
+    pub batch: bool,
+    /// How often to tell every peer we are alive, even while computing, if at all.
+    ///
+    /// A peer that then stays silent for a few of these is presumed unreachable, long before the
+    /// I/O timeout; see [MpcMultiNet::on_unreachable]. All parties must agree on whether this is
+    /// set. Over QUIC, this is the keep-alive interval instead.
+    pub heartbeat: Option<Duration>,
+    /// TLS identities for QUIC connections.
+    #[cfg(feature = "quic")]
+    pub quic: quic::QuicConfig,
+    /// Slow the links to every peer down, as a WAN would. For benchmarks, not deployments.
+    pub shaping: Option<Shaping>,
+    /// Seal every message, with keys derived from this key and each connection's handshake, so
+    /// that a message altered on the way fails with [NetError::Tampered]. For links without TLS
+    /// of their own; all parties must agree on the key.
+    pub seal: Option<SealKey>,
+    /// Write a [transcript] of every exchange to this directory, as `party<id>.transcript`.
+    pub record: Option<PathBuf>,
+}
+
+impl std::default::Default for NetConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(30),
+            io_timeout: Some(Duration::from_secs(600)),
+            reconnect_timeout: Duration::from_secs(10),
+            max_reconnects: 3,
+            replay_messages: 4,
+            compression: Compression::None,
+            batch: false,
+            heartbeat: None,
+            #[cfg(feature = "quic")]
+            quic: quic::QuicConfig::default(),
+            shaping: None,
+            seal: None,
+            record: None,
+        }
+    }
+}
+
+/// A secret that all parties share, from which each connection derives the keys that seal its
+/// messages.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SealKey(pub [u8; 32]);
+
+impl fmt::Debug for SealKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("SealKey(..)")
+    }
+}
+
+/// A simulated link to a peer, to measure what a WAN costs without one.
+///
+/// It applies to the messages we send, over TCP or in memory, so every party should use the same.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Shaping {
+    /// How long a message takes to reach the peer, once the link has carried it.
+    pub latency: Duration,
+    /// How many bytes per second the link carries, if it is limited.
+    pub bandwidth: Option<u64>,
+}
+
+/// How messages are compressed on the wire.
+///
+/// Shares of random values are incompressible, so this pays off for traffic with structure, like
+/// public values, small integers, or padding. A message that does not shrink is sent as is, in a
+/// frame with the same five-byte header as any other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// Messages as they are.
+    None,
+    /// LZ4: fast, with modest ratios.
+    Lz4,
+    /// Zstandard at the given level: slower, with better ratios.
+    #[cfg(feature = "zstd")]
+    Zstd(i32),
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    /// Parse `none`, `lz4`, `zstd`, or `zstd:LEVEL`.
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "none" => Ok(Compression::None),
+            "lz4" => Ok(Compression::Lz4),
+            #[cfg(feature = "zstd")]
+            "zstd" => Ok(Compression::Zstd(3)),
+            #[cfg(feature = "zstd")]
+            s if s.starts_with("zstd:") => s[5..]
+                .parse()
+                .map(Compression::Zstd)
+                .map_err(|e| format!("bad zstd level: {}", e)),
+            _ => Err(format!("unknown compression: {}", s)),
+        }
+    }
+}
+
+/// Why the network aborted.
+///
+/// Once a network operation fails, the network layer tells the other parties why and closes all its
+/// connections (so that they abort too, rather than waiting on us), and unwinds with this error as
+/// the payload. Use [catch_abort] to get it back as a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NetError {
+    /// A peer did not respond within the configured timeout.
+    Timeout { peer: usize },
+    /// A peer that should send heartbeats sent nothing at all for `silent`.
+    Unreachable { peer: usize, silent: Duration },
+    /// A connection dropped, and could not be re-established and resumed.
+    Disconnected { peer: usize, reason: String },
+    /// Any other I/O failure.
+    Io { peer: usize, error: String },
+    /// Party `peer` failed, or gave up with [MpcMultiNet::broadcast_abort], and the session
+    /// aborted because of it.
+    Aborted { peer: usize, reason: String },
+    /// What came from `peer` failed to open: it was altered on the way, or not sealed with our
+    /// key.
+    Tampered { peer: usize, reason: String },
+    /// Replaying a [transcript], exchange number `exchange` (counting from 0) was not the one
+    /// recorded.
+    Diverged { exchange: usize, reason: String },
+}
+
+impl Display for NetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            NetError::Timeout { peer } => write!(f, "party {} timed out", peer),
+            NetError::Unreachable { peer, silent } => {
+                write!(f, "party {} unreachable: nothing heard for {:?}", peer, silent)
+            }
+            NetError::Disconnected { peer, reason } => {
+                write!(f, "lost connection to party {}: {}", peer, reason)
+            }
+            NetError::Io { peer, error } => write!(f, "I/O error with party {}: {}", peer, error),
+            NetError::Aborted { peer, reason } => write!(f, "party {} aborted: {}", peer, reason),
+            NetError::Tampered { peer, reason } => {
+                write!(f, "tampered message from party {}: {}", peer, reason)
+            }
+            NetError::Diverged { exchange, reason } => {
+                write!(f, "replay diverged at exchange {}: {}", exchange, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetError {}
+
+/// Unwind with `err`. Network operations call this when they fail.
+pub fn abort(err: NetError) -> ! {
+    eprintln!("mpc-net: aborting: {}", err);
+    panic::resume_unwind(Box::new(err))
+}
+
+/// Run `f`, returning the error if the network aborts during it.
+///
+/// Other panics pass through unchanged.
+pub fn catch_abort<T>(f: impl FnOnce() -> T) -> Result<T, NetError> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| match payload.downcast::<NetError>() {
+        Ok(err) => *err,
+        Err(payload) => panic::resume_unwind(payload),
+    })
+}
+
 pub trait MpcNet {
     /// Am I the first party?
     #[inline]
     fn am_king() -> bool {
         Self::party_id() == 0
     }
+    /// Am I the only party? Then shares are plain values and nothing needs to be exchanged.
+    #[inline]
+    fn is_single_party() -> bool {
+        Self::n_parties() == 1
+    }
     /// How many parties are there?
     fn n_parties() -> usize;
     /// What is my party number (0 to n-1)?
@@ -51,11 +271,32 @@ pub trait MpcNet {
     fn stats() -> Stats;
     /// All parties send bytes to each other.
     fn broadcast_bytes(bytes: &[u8]) -> Vec<Vec<u8>>;
+    /// All parties send bytes to party `king`.
+    fn send_bytes_to(king: usize, bytes: &[u8]) -> Option<Vec<Vec<u8>>>;
+    /// All parties recv bytes from party `king`.
+    /// Provide bytes iff you're `king`!
+    fn recv_bytes_from(king: usize, bytes: Option<Vec<Vec<u8>>>) -> Vec<u8>;
+    /// Which party acts as king for the next rotating-king exchange?
+    ///
+    /// Advances the rotation, so every party must call this the same number of times.
+    fn next_king() -> usize;
+    /// Run `f` as one protocol round, in which messages sent without waiting for a reply may be
+    /// coalesced, where the network batches them. Otherwise, this just runs `f`.
+    #[inline]
+    fn round<T>(f: impl FnOnce() -> T) -> T {
+        f()
+    }
     /// All parties send bytes to the king.
-    fn send_bytes_to_king(bytes: &[u8]) -> Option<Vec<Vec<u8>>>;
+    #[inline]
+    fn send_bytes_to_king(bytes: &[u8]) -> Option<Vec<Vec<u8>>> {
+        Self::send_bytes_to(0, bytes)
+    }
     /// All parties recv bytes from the king.
     /// Provide bytes iff you're the king!
-    fn recv_bytes_from_king(bytes: Option<Vec<Vec<u8>>>) -> Vec<u8>;
+    #[inline]
+    fn recv_bytes_from_king(bytes: Option<Vec<Vec<u8>>>) -> Vec<u8> {
+        Self::recv_bytes_from(0, bytes)
+    }
 
     /// Everyone sends bytes to the king, who recieves those bytes, runs a computation on them, and
     /// redistributes the resulting bytes.
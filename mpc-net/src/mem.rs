@@ -0,0 +1,138 @@
+//! Heap usage accounting.
+//!
+//! Install [CountingAlloc] as the `#[global_allocator]` of a binary to enable tracking. Without it,
+//! all figures read zero.
+//!
+//! Code marks the phases it wants to measure with [phase]; [report] then gives the high-water mark
+//! of live heap bytes for the whole run and for each phase. The same phases delimit what
+//! [profile](crate::profile) keeps, and, over QUIC, which streams messages go on; so every party
+//! must enter the same phases around the same exchanges.
+use lazy_static::lazy_static;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+/// Peak since the innermost active phase began.
+static PHASE_PEAK: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    /// Per-phase peaks, in order of first use.
+    static ref PHASES: Mutex<Vec<(&'static str, usize)>> = Mutex::new(Vec::new());
+}
+
+/// A wrapper around the system allocator which counts live heap bytes.
+pub struct CountingAlloc;
+
+#[inline]
+fn on_alloc(size: usize) {
+    let now = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(now, Ordering::Relaxed);
+    PHASE_PEAK.fetch_max(now, Ordering::Relaxed);
+}
+
+#[inline]
+fn on_dealloc(size: usize) {
+    CURRENT.fetch_sub(size, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let p = System.alloc(layout);
+        if !p.is_null() {
+            on_alloc(layout.size());
+        }
+        p
+    }
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let p = System.alloc_zeroed(layout);
+        if !p.is_null() {
+            on_alloc(layout.size());
+        }
+        p
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        on_dealloc(layout.size());
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let p = System.realloc(ptr, layout, new_size);
+        if !p.is_null() {
+            on_dealloc(layout.size());
+            on_alloc(new_size);
+        }
+        p
+    }
+}
+
+/// Live heap bytes right now.
+pub fn current() -> usize {
+    CURRENT.load(Ordering::Relaxed)
+}
+
+/// Most live heap bytes seen since start-up (or the last [reset]).
+pub fn peak() -> usize {
+    PEAK.load(Ordering::Relaxed)
+}
+
+/// Forget all peaks, including the per-phase ones.
+pub fn reset() {
+    let now = current();
+    PEAK.store(now, Ordering::Relaxed);
+    PHASE_PEAK.store(now, Ordering::Relaxed);
+    PHASES.lock().unwrap().clear();
+}
+
+/// A measured phase. Its peak is recorded when it is dropped.
+///
+/// Phases may nest; an outer phase's peak includes those of its inner phases. Since the counters
+/// are process-wide, phases should only be entered from one thread at a time.
+#[must_use = "the phase ends when this is dropped"]
+pub struct Phase {
+    name: &'static str,
+    outer_peak: usize,
+    /// The session's phase before this one.
+    outer_label: String,
+    _profile: crate::profile::Span,
+}
+
+/// Begin measuring the phase `name`. Re-entering a phase keeps the largest peak.
+pub fn phase(name: &'static str) -> Phase {
+    let outer_peak = PHASE_PEAK.swap(current(), Ordering::Relaxed);
+    Phase {
+        name,
+        outer_peak,
+        outer_label: crate::multi::enter_phase(name),
+        _profile: crate::profile::phase(name),
+    }
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        crate::multi::enter_phase(&self.outer_label);
+        let p = PHASE_PEAK.fetch_max(self.outer_peak, Ordering::Relaxed);
+        let mut phases = PHASES.lock().unwrap();
+        match phases.iter_mut().find(|(n, _)| *n == self.name) {
+            Some((_, old)) => *old = std::cmp::max(*old, p),
+            None => phases.push((self.name, p)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MemReport {
+    /// Peak live heap bytes overall.
+    pub peak_bytes: usize,
+    /// Peak live heap bytes during each phase.
+    pub phases: Vec<(&'static str, usize)>,
+}
+
+/// The peaks so far.
+pub fn report() -> MemReport {
+    MemReport {
+        peak_bytes: peak(),
+        phases: PHASES.lock().unwrap().clone(),
+    }
+}
@@ -1,32 +1,369 @@
 use lazy_static::lazy_static;
-use log::debug;
+use log::{debug, warn};
 use rayon::prelude::*;
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryFrom;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read, Write};
-use std::net::{SocketAddr, TcpListener, TcpStream};
-use std::sync::Mutex;
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::cell::RefCell;
+use std::fmt::{self, Debug, Formatter};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::any::{Any, TypeId};
+use std::panic::{self, Location};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, Once, TryLockError, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use ark_std::{end_timer, start_timer};
 
-use super::{MpcNet, Stats};
+#[cfg(feature = "quic")]
+use super::quic;
+use super::seal::{self, Seal};
+use super::transcript::{self, Exchange, Recorder, Replayer, Transcript};
+use super::{abort, Compression, MpcNet, NetConfig, NetError, Shaping, Stats};
 
 #[macro_use]
 lazy_static! {
-    static ref CONNECTIONS: Mutex<Connections> = Mutex::new(Connections::default());
+    /// The session used outside of [MpcSession::run].
+    static ref DEFAULT_SESSION: Arc<Mutex<Connections>> =
+        Arc::new(Mutex::new(Connections::default()));
+    /// Listening sockets by address, shared by all sessions bound there.
+    static ref LISTENERS: Mutex<HashMap<SocketAddr, Weak<SharedListener>>> =
+        Mutex::new(HashMap::new());
 }
 
-/// Macro for locking the FieldChannel singleton in the current scope.
-macro_rules! get_ch {
-    () => {
-        CONNECTIONS.lock().expect("Poisoned FieldChannel")
-    };
+thread_local! {
+    /// The session this thread works for, if it belongs to an [MpcSession].
+    static CURRENT_SESSION: RefCell<Option<Arc<Mutex<Connections>>>> = RefCell::new(None);
+}
+
+/// The connections of the current session.
+fn current_session() -> Arc<Mutex<Connections>> {
+    CURRENT_SESSION
+        .with(|c| c.borrow().clone())
+        .unwrap_or_else(|| DEFAULT_SESSION.clone())
+}
+
+/// Run `f` on the connections of the current session.
+fn with_ch<T>(f: impl FnOnce(&mut Connections) -> T) -> T {
+    let ch = current_session();
+    let mut ch = ch.lock().expect("Poisoned FieldChannel");
+    f(&mut ch)
+}
+
+/// Identifies our hello messages: "mpc-net" and a version byte.
+const HELLO_MAGIC: u64 = 0x6d70_632d_6e65_7405;
+
+/// How long to wait for a newly accepted connection to say who it is.
+const HELLO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Each message is a frame: this header (the payload length, then a codec byte), then the
+/// payload.
+pub(crate) const FRAME_HEADER: usize = 5;
+const CODEC_STORED: u8 = 0;
+const CODEC_LZ4: u8 = 1;
+#[cfg(feature = "zstd")]
+const CODEC_ZSTD: u8 = 2;
+/// Not a message, but notice that the session aborted: the party whose failure started it, then
+/// why.
+const CODEC_ABORT: u8 = 0xff;
+/// Not a message, but a sign of life, with no payload.
+pub(crate) const CODEC_HEARTBEAT: u8 = 0xfe;
+/// Not a message, but a record of frames, sealed; see [seal].
+pub(crate) const CODEC_SEALED: u8 = 0xfd;
+
+/// How many heartbeats a peer may miss before we give up on it.
+const MISSED_HEARTBEATS: u32 = 4;
+
+/// Messages shorter than this are sent stored, since compressing them would not pay off.
+const MIN_COMPRESS: usize = 64;
+
+/// A batch is written out once it is this large, even within a round.
+const MAX_BATCH: usize = 1 << 20;
+
+/// One end of an in-memory connection, between sessions in the same process.
+#[derive(Debug)]
+struct MemStream {
+    tx: mpsc::Sender<Vec<u8>>,
+    rx: mpsc::Receiver<Vec<u8>>,
+    /// The last chunk received, and how much of it has been read.
+    buf: Vec<u8>,
+    pos: usize,
+    timeout: Option<Duration>,
+}
+
+impl MemStream {
+    fn pair(timeout: Option<Duration>) -> (Self, Self) {
+        let (tx_a, rx_b) = mpsc::channel();
+        let (tx_b, rx_a) = mpsc::channel();
+        let end = |tx, rx| MemStream {
+            tx,
+            rx,
+            buf: Vec::new(),
+            pos: 0,
+            timeout,
+        };
+        (end(tx_a, rx_a), end(tx_b, rx_b))
+    }
+}
+
+impl Read for MemStream {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.buf.len() {
+            let closed = || io::Error::new(ErrorKind::UnexpectedEof, "peer closed the connection");
+            self.buf = match self.timeout {
+                Some(t) => self.rx.recv_timeout(t).map_err(|e| match e {
+                    mpsc::RecvTimeoutError::Timeout => {
+                        io::Error::new(ErrorKind::TimedOut, "read timed out")
+                    }
+                    mpsc::RecvTimeoutError::Disconnected => closed(),
+                })?,
+                None => self.rx.recv().map_err(|_| closed())?,
+            };
+            self.pos = 0;
+        }
+        let n = out.len().min(self.buf.len() - self.pos);
+        out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl Write for MemStream {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.tx
+            .send(bytes.to_vec())
+            .map_err(|_| io::Error::new(ErrorKind::BrokenPipe, "peer closed the connection"))?;
+        Ok(bytes.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A connection to a peer: over TCP or QUIC, or in memory.
+#[derive(Debug)]
+enum Stream {
+    Tcp(TcpStream),
+    #[cfg(feature = "quic")]
+    Quic(quic::QuicStream),
+    Mem(MemStream),
+}
+
+impl Stream {
+    fn shutdown(&self) {
+        match self {
+            Stream::Tcp(s) => {
+                let _ = s.shutdown(Shutdown::Both);
+            }
+            #[cfg(feature = "quic")]
+            Stream::Quic(s) => s.shutdown(),
+            // an in-memory connection closes when dropped
+            Stream::Mem(_) => {}
+        }
+    }
+
+    /// Read and write on the stream of phase `label`, if the connection has one per phase.
+    fn select(&mut self, label: &str) {
+        #[cfg(feature = "quic")]
+        if let Stream::Quic(s) = self {
+            s.select(label)
+        }
+        #[cfg(not(feature = "quic"))]
+        let _ = label;
+    }
+}
+
+/// A second handle on a connection, on which other threads write: heartbeats, and shaped
+/// messages.
+#[derive(Debug)]
+enum Beat {
+    Tcp(TcpStream),
+    Mem(mpsc::Sender<Vec<u8>>),
+}
+
+impl Beat {
+    fn of(stream: &Stream) -> io::Result<Self> {
+        match stream {
+            Stream::Tcp(s) => s.try_clone().map(Beat::Tcp),
+            #[cfg(feature = "quic")]
+            Stream::Quic(_) => Err(io::Error::new(
+                ErrorKind::Unsupported,
+                "QUIC keeps its connections alive itself",
+            )),
+            Stream::Mem(s) => Ok(Beat::Mem(s.tx.clone())),
+        }
+    }
+
+    fn send(&mut self) -> io::Result<()> {
+        self.write_all(frame(CODEC_HEARTBEAT, &[])?)
+    }
+
+    fn write_all(&mut self, bytes: Vec<u8>) -> io::Result<()> {
+        match self {
+            Beat::Tcp(s) => s.write_all(&bytes),
+            Beat::Mem(tx) => tx
+                .send(bytes)
+                .map_err(|_| io::Error::new(ErrorKind::BrokenPipe, "peer closed the connection")),
+        }
+    }
+}
+
+/// Writes to a peer as [Shaping] says: each reaches the peer `latency` after the link has carried
+/// it, at `bandwidth`, after those before it.
+///
+/// A thread delivers the writes once they are due, so that, as over a real link, the sender
+/// need not wait for them to arrive.
+#[derive(Debug)]
+struct Shaper {
+    shaping: Shaping,
+    queue: mpsc::Sender<(Instant, Vec<u8>)>,
+    /// When the link will have carried all that was written so far.
+    busy_until: Instant,
+    /// Why a delivery failed, if one did; the next write reports it.
+    failed: Arc<Mutex<Option<io::Error>>>,
+    deliverer: thread::JoinHandle<()>,
+}
+
+impl Shaper {
+    /// Shape the writes on `stream`, taking turns with heartbeats on `turn`.
+    fn start(shaping: Shaping, peer: usize, stream: &Stream, turn: BeatSlot) -> io::Result<Self> {
+        let mut out = Beat::of(stream)?;
+        let (queue, due) = mpsc::channel::<(Instant, Vec<u8>)>();
+        let failed = Arc::new(Mutex::new(None));
+        let failures = failed.clone();
+        let deliverer = thread::Builder::new()
+            .name(format!("mpc-shaper-{}", peer))
+            .spawn(move || {
+                for (at, bytes) in due {
+                    thread::sleep(at.saturating_duration_since(Instant::now()));
+                    let _turn = turn.lock().unwrap();
+                    if let Err(e) = out.write_all(bytes) {
+                        *failures.lock().unwrap() = Some(e);
+                        return;
+                    }
+                }
+            })?;
+        Ok(Self {
+            shaping,
+            queue,
+            busy_until: Instant::now(),
+            failed,
+            deliverer,
+        })
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if let Some(e) = self.failed.lock().unwrap().take() {
+            return Err(e);
+        }
+        let start = self.busy_until.max(Instant::now());
+        self.busy_until = match self.shaping.bandwidth {
+            Some(b) => start + Duration::from_secs_f64(bytes.len() as f64 / b as f64),
+            None => start,
+        };
+        self.queue
+            .send((self.busy_until + self.shaping.latency, bytes.to_vec()))
+            .map_err(|_| io::Error::new(ErrorKind::BrokenPipe, "peer closed the connection"))
+    }
+
+    /// Wait until all that was written has been delivered.
+    fn finish(self) {
+        drop(self.queue);
+        let _ = self.deliverer.join();
+    }
+}
+
+/// Heartbeats to a peer, while there is a connection to send them on.
+///
+/// Our own writes to the peer also take this lock, so that they and the heartbeats don't
+/// interleave.
+type BeatSlot = Arc<Mutex<Option<Beat>>>;
+
+/// What to do about a peer that a heartbeat could not reach; see [MpcMultiNet::on_unreachable].
+type Callback = Arc<dyn Fn(&NetError) + Send + Sync>;
+
+/// A session's [Callback], if any, shared with its heartbeat thread.
+#[derive(Clone, Default)]
+struct Watcher(Arc<Mutex<Option<Callback>>>);
+
+impl Watcher {
+    fn unreachable(&self, e: NetError) {
+        let f = self.0.lock().unwrap().clone();
+        match f {
+            Some(f) => f(&e),
+            None => warn!("{}", e),
+        }
+    }
+}
+
+impl Debug for Watcher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Watcher")
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(s) => s.read(out),
+            #[cfg(feature = "quic")]
+            Stream::Quic(s) => s.read(out),
+            Stream::Mem(s) => s.read(out),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(s) => s.write(bytes),
+            #[cfg(feature = "quic")]
+            Stream::Quic(s) => s.write(bytes),
+            Stream::Mem(s) => s.write(bytes),
+        }
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(s) => s.flush(),
+            #[cfg(feature = "quic")]
+            Stream::Quic(s) => s.flush(),
+            Stream::Mem(s) => s.flush(),
+        }
+    }
 }
 
 #[derive(Debug)]
 struct Peer {
     id: usize,
     addr: SocketAddr,
-    stream: Option<TcpStream>,
+    stream: Option<Stream>,
+    /// Number of messages written to this peer, over all connections.
+    sent: u64,
+    /// Number of messages read from this peer, over all connections.
+    recvd: u64,
+    /// The last messages written, oldest first, for replay after a reconnection; shared with the
+    /// write, rather than copied.
+    log: VecDeque<Arc<Vec<u8>>>,
+    /// Messages written but not yet flushed to the socket, when batching.
+    out: Vec<u8>,
+    /// Number of messages in `out`.
+    unflushed: usize,
+    /// Socket bytes written and read, and socket writes, for [Stats].
+    wire_sent: usize,
+    wire_recvd: usize,
+    writes: usize,
+    /// Heartbeats to this peer.
+    beat: BeatSlot,
+    /// The phase of our last exchange with this peer, and so of the batch.
+    label: String,
+    /// Shapes our writes on the current connection, if the configuration says to.
+    shaper: Option<Shaper>,
+    /// Seals what goes over the current connection, and opens what comes, if the configuration
+    /// has a key.
+    seal: Option<Seal>,
 }
 
 #[derive(Default, Debug)]
@@ -34,6 +371,41 @@ struct Connections {
     id: usize,
     peers: Vec<Peer>,
     stats: Stats,
+    /// Number of rotating-king exchanges so far; determines the next king.
+    king_rotation: usize,
+    config: NetConfig,
+    /// Tells this session's connections apart from other sessions' at a shared listener.
+    tag: u64,
+    /// Kept open for reconnections.
+    listener: Option<Arc<SharedListener>>,
+    /// Set once an operation fails; all later operations fail the same way.
+    aborted: Option<NetError>,
+    /// Depth of nested [MpcMultiNet::round]s; while positive, batched messages wait for a read.
+    rounds: usize,
+    watcher: Watcher,
+    /// The current phase, as [mem::phase](crate::mem::phase) marks it. Over QUIC, each phase's
+    /// messages go on their own streams.
+    label: String,
+    /// Whether the hosts file asked for QUIC.
+    quic: bool,
+    /// Our QUIC endpoint, which must outlive the connections made from it.
+    #[cfg(feature = "quic")]
+    endpoint: Option<quic::Endpoint>,
+    /// Writes the transcript, if the configuration asks for one.
+    recorder: Option<Recorder>,
+    /// Answers our exchanges, in place of the other parties, when replaying a transcript.
+    replayer: Option<Replayer>,
+    /// What protocols keep for this session, by type; see [MpcMultiNet::with_local].
+    locals: Arc<Mutex<Locals>>,
+}
+
+#[derive(Default)]
+struct Locals(HashMap<TypeId, Box<dyn Any + Send>>);
+
+impl Debug for Locals {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Locals({} types)", self.0.len())
+    }
 }
 
 impl std::default::Default for Peer {
@@ -42,267 +414,1626 @@ impl std::default::Default for Peer {
             id: 0,
             addr: "127.0.0.1:8000".parse().unwrap(),
             stream: None,
+            sent: 0,
+            recvd: 0,
+            log: VecDeque::new(),
+            out: Vec::new(),
+            unflushed: 0,
+            wire_sent: 0,
+            wire_recvd: 0,
+            writes: 0,
+            beat: Arc::new(Mutex::new(None)),
+            label: String::new(),
+            shaper: None,
+            seal: None,
         }
     }
 }
 
+/// A listening socket, shared by all sessions bound to its address.
+#[derive(Debug)]
+struct SharedListener {
+    socket: TcpListener,
+    /// Connections accepted while waiting for a different peer or session, by session tag and
+    /// sender, with their hellos.
+    strays: Mutex<HashMap<(u64, usize), (TcpStream, Hello)>>,
+}
+
+/// The listener at `addr`, opening it unless some session already has.
+fn listen(addr: SocketAddr) -> io::Result<Arc<SharedListener>> {
+    let mut listeners = LISTENERS.lock().unwrap();
+    if let Some(l) = listeners.get(&addr).and_then(Weak::upgrade) {
+        return Ok(l);
+    }
+    let socket = TcpListener::bind(addr)?;
+    socket.set_nonblocking(true)?;
+    let l = Arc::new(SharedListener {
+        socket,
+        strays: Mutex::new(HashMap::new()),
+    });
+    listeners.insert(addr, Arc::downgrade(&l));
+    Ok(l)
+}
+
+/// What a [Peer] needs to re-establish its connection.
+struct Link<'a> {
+    own_id: usize,
+    tag: u64,
+    /// `None` when running alone.
+    listener: Option<&'a SharedListener>,
+    config: &'a NetConfig,
+    /// The current phase.
+    label: &'a str,
+}
+
+impl Link<'_> {
+    fn hello(&self, recvd: u64) -> Hello {
+        Hello {
+            tag: self.tag,
+            id: self.own_id,
+            recvd,
+            salt: seal::salt(),
+        }
+    }
+}
+
+/// The first message on each connection.
+#[derive(Clone, Copy, Debug)]
+struct Hello {
+    /// The sender's session.
+    tag: u64,
+    /// The sender.
+    id: usize,
+    /// How many of our messages the sender has read.
+    recvd: u64,
+    /// The sender's part of the connection's [Seal], if it has one.
+    salt: [u8; 16],
+}
+
+impl Hello {
+    /// Check that the other end of a connection is the one we expect.
+    fn check(&self, expected: &Hello) -> io::Result<()> {
+        if (self.tag, self.id) != (expected.tag, expected.id) {
+            return Err(io::Error::new(ErrorKind::InvalidData, "wrong party"));
+        }
+        Ok(())
+    }
+}
+
+fn write_hello(stream: &mut impl Write, hello: &Hello) -> io::Result<()> {
+    let mut bytes = [0u8; 48];
+    bytes[..8].copy_from_slice(&HELLO_MAGIC.to_le_bytes());
+    bytes[8..16].copy_from_slice(&hello.tag.to_le_bytes());
+    bytes[16..24].copy_from_slice(&(hello.id as u64).to_le_bytes());
+    bytes[24..32].copy_from_slice(&hello.recvd.to_le_bytes());
+    bytes[32..].copy_from_slice(&hello.salt);
+    stream.write_all(&bytes)
+}
+
+fn read_hello(stream: &mut impl Read) -> io::Result<Hello> {
+    let mut hello = [0u8; 48];
+    stream.read_exact(&mut hello)?;
+    let word = |i: usize| {
+        let mut w = [0u8; 8];
+        w.copy_from_slice(&hello[8 * i..8 * (i + 1)]);
+        u64::from_le_bytes(w)
+    };
+    if word(0) != HELLO_MAGIC {
+        return Err(io::Error::new(ErrorKind::InvalidData, "bad hello"));
+    }
+    let mut salt = [0u8; 16];
+    salt.copy_from_slice(&hello[32..]);
+    Ok(Hello {
+        tag: word(1),
+        id: word(2) as usize,
+        recvd: word(3),
+        salt,
+    })
+}
+
+/// Prepare `bytes` for the wire, as a frame, compressed if that makes it smaller.
+fn encode(compression: Compression, bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let packed = match compression {
+        Compression::None => None,
+        _ if bytes.len() < MIN_COMPRESS => None,
+        Compression::Lz4 => Some((CODEC_LZ4, lz4_flex::compress(bytes))),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd(level) => zstd::bulk::compress(bytes, level)
+            .ok()
+            .map(|c| (CODEC_ZSTD, c)),
+    };
+    let (codec, payload) = match &packed {
+        Some((codec, c)) if c.len() < bytes.len() => (*codec, &c[..]),
+        _ => (CODEC_STORED, bytes),
+    };
+    frame(codec, payload)
+}
+
+fn frame(codec: u8, payload: &[u8]) -> io::Result<Vec<u8>> {
+    let mut frame = Vec::with_capacity(FRAME_HEADER + payload.len());
+    frame.extend_from_slice(&frame_len(payload.len())?.to_le_bytes());
+    frame.push(codec);
+    frame.extend_from_slice(payload);
+    Ok(frame)
+}
+
+/// The length field of a frame with `len` bytes of payload, which must fit in it.
+pub(crate) fn frame_len(len: usize) -> io::Result<u32> {
+    u32::try_from(len).map_err(|_| {
+        io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("a frame holds less than 4 GiB, not {} bytes", len),
+        )
+    })
+}
+
+/// Notice that the session aborted, because of party `origin`'s failure `reason`.
+///
+/// It travels as the payload of an abort frame, or, over QUIC, as the reason the connection
+/// closed; the latter reads as an I/O error that wraps it.
+#[derive(Clone, Debug)]
+pub(crate) struct AbortNotice {
+    pub(crate) origin: usize,
+    pub(crate) reason: String,
+}
+
+impl AbortNotice {
+    pub(crate) fn payload(&self) -> Vec<u8> {
+        let mut payload = (self.origin as u64).to_le_bytes().to_vec();
+        payload.extend_from_slice(self.reason.as_bytes());
+        payload
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Option<Self> {
+        if payload.len() < 8 {
+            return None;
+        }
+        let mut origin = [0u8; 8];
+        origin.copy_from_slice(&payload[..8]);
+        Some(Self {
+            origin: u64::from_le_bytes(origin) as usize,
+            reason: String::from_utf8_lossy(&payload[8..]).into_owned(),
+        })
+    }
+
+    /// The notice that `e` wraps, if any.
+    fn in_error(e: &io::Error) -> Option<NetError> {
+        let notice = e.get_ref()?.downcast_ref::<AbortNotice>()?;
+        Some(notice.clone().into())
+    }
+}
+
+impl From<AbortNotice> for NetError {
+    fn from(notice: AbortNotice) -> Self {
+        NetError::Aborted {
+            peer: notice.origin,
+            reason: notice.reason,
+        }
+    }
+}
+
+impl fmt::Display for AbortNotice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "party {} aborted: {}", self.origin, self.reason)
+    }
+}
+
+impl std::error::Error for AbortNotice {}
+
+/// What [read_frame] found.
+enum Frame {
+    /// A message, of this many bytes on the wire.
+    Message(usize),
+    /// Notice that the session aborted.
+    Abort(AbortNotice),
+}
+
+/// Read a frame from `stream`, decompressing its payload into `out`, which must have exactly the
+/// payload's original length.
+fn read_frame(stream: &mut impl Read, out: &mut [u8]) -> io::Result<Frame> {
+    let bad = |what: &str| io::Error::new(ErrorKind::InvalidData, what.to_string());
+    let mut header = [0u8; FRAME_HEADER];
+    let (len, codec) = loop {
+        stream.read_exact(&mut header)?;
+        let mut len = [0u8; 4];
+        len.copy_from_slice(&header[..4]);
+        let len = u32::from_le_bytes(len) as usize;
+        match header[4] {
+            CODEC_HEARTBEAT if len == 0 => continue,
+            CODEC_HEARTBEAT => return Err(bad("heartbeat has a payload")),
+            codec => break (len, codec),
+        }
+    };
+    if codec == CODEC_ABORT {
+        let mut payload = vec![0u8; len];
+        stream.read_exact(&mut payload)?;
+        return AbortNotice::parse(&payload)
+            .map(Frame::Abort)
+            .ok_or_else(|| bad("abort notice is too short"));
+    }
+    if codec == CODEC_SEALED {
+        return Err(bad("frame is sealed, but we have no key to open it"));
+    }
+    if codec == CODEC_STORED {
+        if len != out.len() {
+            return Err(bad("frame has the wrong length"));
+        }
+        stream.read_exact(out)?;
+        return Ok(Frame::Message(FRAME_HEADER + len));
+    }
+    // only frames that shrank are compressed
+    if len >= out.len() {
+        return Err(bad("frame has the wrong length"));
+    }
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload)?;
+    let unpacked = match codec {
+        CODEC_LZ4 => lz4_flex::decompress_into(&payload, out)
+            .map_err(|e| bad(&format!("bad lz4 frame: {}", e)))?,
+        #[cfg(feature = "zstd")]
+        CODEC_ZSTD => zstd::bulk::decompress_to_buffer(&payload, out)?,
+        _ => return Err(bad("unknown codec")),
+    };
+    if unpacked != out.len() {
+        return Err(bad("frame has the wrong length"));
+    }
+    Ok(Frame::Message(FRAME_HEADER + len))
+}
+
+/// How long a peer may send nothing at all before we give up on it, if heartbeats make that
+/// shorter than the I/O timeout.
+fn silence(config: &NetConfig) -> Option<Duration> {
+    let silence = config.heartbeat? * MISSED_HEARTBEATS;
+    match config.io_timeout {
+        Some(t) if t <= silence => None,
+        _ => Some(silence),
+    }
+}
+
+fn read_timeout(config: &NetConfig) -> Option<Duration> {
+    silence(config).or(config.io_timeout)
+}
+
+fn configure(stream: &TcpStream, config: &NetConfig) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(read_timeout(config))?;
+    stream.set_write_timeout(config.io_timeout)
+}
+
+/// Connect to `addr`, retrying until `deadline` while nobody is listening there.
+fn connect(addr: SocketAddr, deadline: Instant) -> io::Result<TcpStream> {
+    loop {
+        match TcpStream::connect(addr) {
+            Ok(s) => return Ok(s),
+            Err(e) => match e.kind() {
+                ErrorKind::ConnectionRefused | ErrorKind::ConnectionReset => {
+                    if Instant::now() > deadline {
+                        return Err(io::Error::new(ErrorKind::TimedOut, e));
+                    }
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+                _ => return Err(e),
+            },
+        }
+    }
+}
+
+/// Accept the connection from party `want`, until `deadline`.
+///
+/// Connections from other parties or sessions that arrive in the meantime are set aside for them.
+fn accept_from(link: &Link, want: usize, deadline: Instant) -> io::Result<(TcpStream, Hello)> {
+    loop {
+        let listener = link
+            .listener
+            .ok_or_else(|| io::Error::new(ErrorKind::NotConnected, "not listening"))?;
+        let expected = Hello {
+            id: want,
+            ..link.hello(0)
+        };
+        if let Some((s, hello)) = listener.strays.lock().unwrap().remove(&(link.tag, want)) {
+            hello.check(&expected)?;
+            return Ok((s, hello));
+        }
+        match listener.socket.accept() {
+            Ok((mut s, _addr)) => {
+                s.set_nonblocking(false)?;
+                s.set_read_timeout(Some(HELLO_TIMEOUT))?;
+                match read_hello(&mut s) {
+                    Ok(hello) if (hello.tag, hello.id) == (link.tag, want) => {
+                        hello.check(&expected)?;
+                        return Ok((s, hello));
+                    }
+                    Ok(hello) => {
+                        listener
+                            .strays
+                            .lock()
+                            .unwrap()
+                            .insert((hello.tag, hello.id), (s, hello));
+                    }
+                    Err(e) => debug!("Dropping bad connection: {}", e),
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                if Instant::now() > deadline {
+                    return Err(io::Error::new(ErrorKind::TimedOut, "no connection"));
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+fn is_disconnect(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        ErrorKind::UnexpectedEof
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::BrokenPipe
+            | ErrorKind::NotConnected
+    )
+}
+
+impl Peer {
+    fn io_error(&self, e: io::Error) -> NetError {
+        if is_timeout(&e) {
+            NetError::Timeout { peer: self.id }
+        } else {
+            NetError::Io {
+                peer: self.id,
+                error: e.to_string(),
+            }
+        }
+    }
+
+    /// Write one message, or add it to the batch if batching.
+    fn send(&mut self, link: &Link, bytes: &[u8]) -> Result<(), NetError> {
+        self.enter(link)?;
+        let msg = encode(link.config.compression, bytes).map_err(|e| self.io_error(e))?;
+        // logged before writing, so that a reconnection replays it. Batched messages are kept
+        // until flushed, since the peer cannot have read them.
+        let keep = if link.config.batch {
+            link.config.replay_messages.max(self.unflushed + 1)
+        } else {
+            link.config.replay_messages
+        };
+        let msg = Arc::new(msg);
+        if keep > 0 {
+            self.log.push_back(Arc::clone(&msg));
+            while self.log.len() > keep {
+                self.log.pop_front();
+            }
+        }
+        self.sent += 1;
+        if link.config.batch {
+            self.out.extend_from_slice(&msg);
+            self.unflushed += 1;
+            if self.out.len() >= MAX_BATCH {
+                return self.flush(link);
+            }
+            Ok(())
+        } else {
+            self.write(link, &msg)
+        }
+    }
+
+    fn write(&mut self, link: &Link, bytes: &[u8]) -> Result<(), NetError> {
+        self.writes += 1;
+        let sealed;
+        let bytes = match &mut self.seal {
+            Some(seal) => {
+                sealed = seal.seal(bytes).map_err(|e| NetError::Io {
+                    peer: self.id,
+                    error: e.to_string(),
+                })?;
+                &sealed[..]
+            }
+            None => bytes,
+        };
+        if let (Some(shaping), None) = (link.config.shaping, &self.shaper) {
+            let stream = self.stream.as_ref().unwrap();
+            match Shaper::start(shaping, self.id, stream, self.beat.clone()) {
+                Ok(shaper) => self.shaper = Some(shaper),
+                Err(e) => warn!("Not shaping the link to {}: {}", self.id, e),
+            }
+        }
+        let r = match &mut self.shaper {
+            Some(shaper) => shaper.write(bytes),
+            None => {
+                let _turn = self.beat.lock().unwrap();
+                let stream = self.stream.as_mut().unwrap();
+                stream.select(&self.label);
+                stream.write_all(bytes)
+            }
+        };
+        match r {
+            Ok(()) => {
+                self.wire_sent += bytes.len();
+                Ok(())
+            }
+            Err(e) if AbortNotice::in_error(&e).is_some() => Err(AbortNotice::in_error(&e).unwrap()),
+            Err(e) if is_disconnect(&e) => match self.pending_abort() {
+                Some(abort) => Err(abort),
+                None => self.reconnect(link, e),
+            },
+            Err(e) => Err(self.io_error(e)),
+        }
+    }
+
+    /// Move on to the current phase, first writing out the batch of the previous one, which the
+    /// peer will read in that phase.
+    fn enter(&mut self, link: &Link) -> Result<(), NetError> {
+        if self.label != link.label {
+            self.flush(link)?;
+            self.label = link.label.to_string();
+        }
+        Ok(())
+    }
+
+    /// Write out the batch, if any.
+    fn flush(&mut self, link: &Link) -> Result<(), NetError> {
+        if self.out.is_empty() {
+            return Ok(());
+        }
+        let mut out = std::mem::take(&mut self.out);
+        let r = self.write(link, &out);
+        out.clear();
+        self.out = out;
+        self.unflushed = 0;
+        r
+    }
+
+    /// Read one message, of exactly `bytes.len()` bytes once decompressed.
+    ///
+    /// Flushes our batch to this peer first, in case it is waiting on it.
+    fn recv(&mut self, link: &Link, bytes: &mut [u8]) -> Result<(), NetError> {
+        self.enter(link)?;
+        self.flush(link)?;
+        let mut reconnects = 0;
+        loop {
+            match self.read(bytes) {
+                Ok(Frame::Message(_)) => {
+                    self.recvd += 1;
+                    return Ok(());
+                }
+                Ok(Frame::Abort(notice)) => return Err(notice.into()),
+                Err(e) if AbortNotice::in_error(&e).is_some() => {
+                    return Err(AbortNotice::in_error(&e).unwrap())
+                }
+                Err(e) if seal::is_tampered(&e) => {
+                    return Err(NetError::Tampered {
+                        peer: self.id,
+                        reason: e.to_string(),
+                    })
+                }
+                Err(e) if is_disconnect(&e) && reconnects < link.config.max_reconnects => {
+                    reconnects += 1;
+                    // the peer replays the message, so start reading it over
+                    self.reconnect(link, e)?;
+                }
+                Err(e) if is_timeout(&e) => {
+                    return Err(match silence(link.config) {
+                        Some(silent) => NetError::Unreachable {
+                            peer: self.id,
+                            silent,
+                        },
+                        None => NetError::Timeout { peer: self.id },
+                    })
+                }
+                Err(e) => return Err(self.io_error(e)),
+            }
+        }
+    }
+
+    /// Read a frame, opening its record first if the connection is sealed.
+    fn read(&mut self, out: &mut [u8]) -> io::Result<Frame> {
+        let stream = self.stream.as_mut().unwrap();
+        stream.select(&self.label);
+        match &mut self.seal {
+            Some(seal) => {
+                let frame = read_frame(&mut seal.reader(stream), out);
+                self.wire_recvd += std::mem::take(&mut seal.wire);
+                frame
+            }
+            None => {
+                let frame = read_frame(stream, out);
+                if let Ok(Frame::Message(n)) = frame {
+                    self.wire_recvd += n;
+                }
+                frame
+            }
+        }
+    }
+
+    /// Seal the connection, if the configuration has a key, with the salts of its hellos.
+    fn reseal(&mut self, link: &Link, ours: &Hello, theirs: &Hello) {
+        self.seal = link
+            .config
+            .seal
+            .as_ref()
+            .map(|key| Seal::new(key, link.own_id, &ours.salt, self.id, &theirs.salt));
+    }
+
+    /// Once the peer has hung up on us, whether it first told us why, in a notice that we have
+    /// yet to read.
+    fn pending_abort(&mut self) -> Option<NetError> {
+        let stream = self.stream.as_mut()?;
+        match stream {
+            Stream::Tcp(s) => s.set_nonblocking(true).ok()?,
+            #[cfg(feature = "quic")]
+            Stream::Quic(s) => return s.abort_notice().map(NetError::from),
+            Stream::Mem(_) => {}
+        }
+        match self.read(&mut []) {
+            Ok(Frame::Abort(notice)) => Some(notice.into()),
+            _ => None,
+        }
+    }
+
+    /// Tell the peer that the session aborted, then hang up. Best effort, since the peer may be
+    /// the reason it did.
+    fn send_abort(&mut self, notice: &[u8]) {
+        // the notice overtakes whatever is still on its way
+        self.shaper = None;
+        let mut beat = self.beat.lock().unwrap();
+        *beat = None;
+        if let Some(mut s) = self.stream.take() {
+            // what is batched would only confuse the notice
+            self.out.clear();
+            self.unflushed = 0;
+            match &s {
+                // a notice written on a stream would be lost when the connection closes
+                #[cfg(feature = "quic")]
+                Stream::Quic(q) => q.abort(&notice[FRAME_HEADER..]),
+                _ => {
+                    let notice = match &mut self.seal {
+                        Some(seal) => seal.seal(notice).expect("an abort notice fits in a frame"),
+                        None => notice.to_vec(),
+                    };
+                    let _ = s.write_all(&notice);
+                    s.shutdown();
+                }
+            }
+        }
+        self.seal = None;
+    }
+
+    /// Replace a dropped connection, and resume the session: each side tells the other how many
+    /// messages it has read, and the other replays the rest from its log.
+    ///
+    /// The lower-numbered party of the pair reconnects, and the higher one accepts, as at
+    /// start-up. This only succeeds if the peer also notices the drop within the reconnection
+    /// timeout, which it does if its next exchange involves us.
+    fn reconnect(&mut self, link: &Link, cause: io::Error) -> Result<(), NetError> {
+        debug!("Connection to {} dropped ({}); reconnecting", self.id, cause);
+        let peer = self.id;
+        let disconnected = |reason: String| NetError::Disconnected { peer, reason };
+        *self.beat.lock().unwrap() = None;
+        self.shaper = None;
+        self.seal = None;
+        match self.stream.take() {
+            // in-memory connections only drop with their session, and QUIC ones only once QUIC
+            // itself has given up on them
+            Some(Stream::Mem(_)) => return Err(disconnected(cause.to_string())),
+            #[cfg(feature = "quic")]
+            Some(Stream::Quic(_)) => return Err(disconnected(cause.to_string())),
+            Some(s) => s.shutdown(),
+            None => {}
+        }
+        let deadline = Instant::now() + link.config.reconnect_timeout;
+        let ours = link.hello(self.recvd);
+        let handshake = || -> io::Result<(TcpStream, Hello)> {
+            if link.own_id < self.id {
+                let mut s = connect(self.addr, deadline)?;
+                write_hello(&mut s, &ours)?;
+                s.set_read_timeout(Some(HELLO_TIMEOUT))?;
+                let theirs = read_hello(&mut s)?;
+                theirs.check(&Hello { id: self.id, ..ours })?;
+                Ok((s, theirs))
+            } else {
+                let (mut s, theirs) = accept_from(link, self.id, deadline)?;
+                write_hello(&mut s, &ours)?;
+                Ok((s, theirs))
+            }
+        };
+        let (mut s, theirs) = handshake().map_err(|e| disconnected(e.to_string()))?;
+        let their_recvd = theirs.recvd;
+        self.reseal(link, &ours, &theirs);
+        configure(&s, link.config).map_err(|e| disconnected(e.to_string()))?;
+        let first_logged = self.sent - self.log.len() as u64;
+        if their_recvd < first_logged || their_recvd > self.sent {
+            return Err(disconnected(format!(
+                "cannot resume: peer has read {} of our messages, but we can only replay from {}",
+                their_recvd, first_logged
+            )));
+        }
+        for msg in self.log.iter().skip((their_recvd - first_logged) as usize) {
+            let sealed;
+            let msg = match &mut self.seal {
+                Some(seal) => {
+                    sealed = seal.seal(msg).map_err(|e| disconnected(e.to_string()))?;
+                    &sealed[..]
+                }
+                None => &msg[..],
+            };
+            s.write_all(msg).map_err(|e| disconnected(e.to_string()))?;
+        }
+        // the replay covered the batch
+        self.out.clear();
+        self.unflushed = 0;
+        debug!("Resumed connection to {}", self.id);
+        let s = Stream::Tcp(s);
+        if link.config.heartbeat.is_some() {
+            *self.beat.lock().unwrap() = Beat::of(&s).ok();
+        }
+        self.stream = Some(s);
+        Ok(())
+    }
+}
+
 impl Connections {
     /// Given a path and the `id` of oneself, initialize the structure
+    ///
+    /// Each line of the file is `HOST:PORT`, for TCP, or `quic://HOST:PORT`, for QUIC; all
+    /// parties must use the same.
     fn init_from_path(&mut self, path: &str, id: usize) {
         let f = BufReader::new(File::open(path).expect("host configuration path"));
         let mut peer_id = 0;
+        self.peers.clear();
         for line in f.lines() {
             let line = line.unwrap();
             let trimmed = line.trim();
             if trimmed.len() > 0 {
+                let quic = trimmed.starts_with("quic://");
+                if peer_id == 0 {
+                    self.quic = quic;
+                } else if quic != self.quic {
+                    panic!("hosts mix QUIC and TCP: {}", trimmed);
+                }
+                let trimmed = trimmed.trim_start_matches("quic://");
                 let addr: SocketAddr = trimmed
                     .parse()
                     .unwrap_or_else(|e| panic!("bad socket address: {}:\n{}", trimmed, e));
                 let peer = Peer {
                     id: peer_id,
                     addr,
-                    stream: None,
+                    ..Peer::default()
                 };
                 self.peers.push(peer);
                 peer_id += 1;
             }
         }
         assert!(id < self.peers.len());
+        if self.quic && !cfg!(feature = "quic") {
+            panic!("QUIC hosts need mpc-net's quic feature");
+        }
         self.id = id;
+        self.king_rotation = 0;
+        self.aborted = None;
+        self.rounds = 0;
+    }
+    /// Become the only party: there is no one to connect to.
+    fn init_alone(&mut self) {
+        self.uninit();
+        self.peers = vec![Peer::default()];
+        self.id = 0;
+        self.king_rotation = 0;
+        self.aborted = None;
+        self.rounds = 0;
+    }
+    /// Stand in for the other parties with `transcript`, as the party that recorded it.
+    fn init_replay(&mut self, transcript: Transcript) {
+        self.uninit();
+        self.peers = (0..transcript.n_parties)
+            .map(|id| Peer {
+                id,
+                ..Peer::default()
+            })
+            .collect();
+        self.id = transcript.party;
+        self.king_rotation = 0;
+        self.aborted = None;
+        self.rounds = 0;
+        self.replayer = Some(Replayer::new(transcript));
+    }
+    /// Begin the transcript, if the configuration asks for one.
+    fn start_recording(&mut self) {
+        if let Some(dir) = &self.config.record {
+            let r = Recorder::create(dir, self.id, self.peers.len());
+            self.recorder = Some(r.unwrap_or_else(|e| {
+                panic!("could not create a transcript in {}: {}", dir.display(), e)
+            }));
+        }
+    }
+    /// When an exchange that begins now began, if we are recording.
+    fn record_start(&self) -> Option<Duration> {
+        self.recorder.as_ref().map(|r| r.now())
+    }
+    /// Record the exchange that began `at`, if we are recording.
+    fn record(
+        &mut self,
+        at: Option<Duration>,
+        exchange: Exchange,
+        sent: &[&[u8]],
+        recvd: &[Vec<u8>],
+    ) {
+        if let (Some(r), Some(at)) = (&mut self.recorder, at) {
+            let recvd: Vec<&[u8]> = recvd.iter().map(|m| &m[..]).collect();
+            if let Err(e) = r.record(at, &self.label, exchange, sent, &recvd) {
+                warn!("Not recording the transcript any further: {}", e);
+                self.recorder = None;
+            }
+        }
+    }
+    /// Answer an exchange from the transcript we replay.
+    fn replay(&mut self, exchange: Exchange, sent: &[&[u8]]) -> Result<Vec<Vec<u8>>, NetError> {
+        if let Some(e) = &self.aborted {
+            return Err(e.clone());
+        }
+        let r = self.replayer.as_mut().unwrap().next(exchange, sent);
+        r.map_err(|e| self.fail(e))
+    }
+    /// Split into the peers and what they need to reconnect.
+    fn link(&mut self) -> Result<(&mut [Peer], Link<'_>), NetError> {
+        if let Some(e) = &self.aborted {
+            return Err(e.clone());
+        }
+        let link = Link {
+            own_id: self.id,
+            tag: self.tag,
+            listener: self.listener.as_deref(),
+            config: &self.config,
+            label: &self.label,
+        };
+        Ok((&mut self.peers, link))
+    }
+    /// Write out every peer's batch. Done before any read, since the peer we read from may be
+    /// waiting on a third party that is waiting on our batch.
+    fn flush_all(&mut self) -> Result<(), NetError> {
+        if !self.config.batch {
+            return Ok(());
+        }
+        let (peers, link) = self.link()?;
+        let r = peers
+            .par_iter_mut()
+            .map(|peer| peer.flush(&link))
+            .collect::<Result<(), NetError>>();
+        r.map_err(|e| self.fail(e))
+    }
+    /// Finish an exchange that only wrote: outside a round, nothing may stay batched, since we
+    /// may compute for a long time before the next exchange.
+    fn end_exchange(&mut self) -> Result<(), NetError> {
+        if self.rounds == 0 {
+            self.flush_all()
+        } else {
+            Ok(())
+        }
     }
-    fn connect_to_all(&mut self) {
+    fn stats(&self) -> Stats {
+        let mut stats = self.stats.clone();
+        for p in &self.peers {
+            stats.wire_bytes_sent += p.wire_sent;
+            stats.wire_bytes_recv += p.wire_recvd;
+            stats.writes += p.writes;
+        }
+        stats
+    }
+    fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+        for p in &mut self.peers {
+            p.wire_sent = 0;
+            p.wire_recvd = 0;
+            p.writes = 0;
+        }
+    }
+    /// Give up: tell the other parties why, and close every connection, so that they abort
+    /// promptly too.
+    ///
+    /// An abort that started elsewhere is passed on as it is, so every party reports the failure
+    /// that caused it.
+    fn fail(&mut self, e: NetError) -> NetError {
+        let notice = match &e {
+            NetError::Aborted { peer, reason } => AbortNotice {
+                origin: *peer,
+                reason: reason.clone(),
+            },
+            e => AbortNotice {
+                origin: self.id,
+                reason: e.to_string(),
+            },
+        };
+        let notice =
+            frame(CODEC_ABORT, &notice.payload()).expect("an abort notice fits in a frame");
+        for p in &mut self.peers {
+            p.send_abort(&notice);
+        }
+        self.listener = None;
+        self.aborted = Some(e.clone());
+        e
+    }
+    fn connect_to_all(&mut self) -> Result<(), NetError> {
         let timer = start_timer!(|| "Connecting");
         let n = self.peers.len();
-        for from_id in 0..n {
-            for to_id in (from_id + 1)..n {
-                debug!("{} to {}", from_id, to_id);
-                if self.id == from_id {
-                    let to_addr = self.peers[to_id].addr;
-                    debug!("Contacting {}", to_id);
-                    let stream = loop {
-                        let mut ms_waited = 0;
-                        match TcpStream::connect(to_addr) {
-                            Ok(s) => break s,
-                            Err(e) => match e.kind() {
-                                std::io::ErrorKind::ConnectionRefused
-                                | std::io::ErrorKind::ConnectionReset => {
-                                    ms_waited += 10;
-                                    std::thread::sleep(std::time::Duration::from_millis(10));
-                                    if ms_waited % 3_000 == 0 {
-                                        debug!("Still waiting");
-                                    } else if ms_waited > 30_000 {
-                                        panic!("Could not find peer in 30s");
-                                    }
-                                }
-                                _ => {
-                                    panic!("Error during FieldChannel::new: {}", e);
-                                }
-                            },
-                        }
-                    };
-                    stream.set_nodelay(true).unwrap();
-                    self.peers[to_id].stream = Some(stream);
-                } else if self.id == to_id {
-                    debug!("Awaiting {}", from_id);
-                    let listener = TcpListener::bind(self.peers[self.id].addr).unwrap();
-                    let (stream, _addr) = listener.accept().unwrap();
-                    stream.set_nodelay(true).unwrap();
-                    self.peers[from_id].stream = Some(stream);
-                }
-            }
-            // Sender for next round waits for note from this sender to prevent race on receipt.
-            if from_id + 1 < n {
-                if self.id == from_id {
-                    self.peers[self.id + 1]
-                        .stream
-                        .as_mut()
-                        .unwrap()
-                        .write_all(&[0u8])
-                        .unwrap();
-                } else if self.id == from_id + 1 {
-                    self.peers[self.id - 1]
-                        .stream
-                        .as_mut()
-                        .unwrap()
-                        .read_exact(&mut [0u8])
-                        .unwrap();
+        let io_err = |peer: usize| {
+            move |e: io::Error| {
+                if is_timeout(&e) {
+                    NetError::Timeout { peer }
+                } else {
+                    NetError::Io {
+                        peer,
+                        error: e.to_string(),
+                    }
                 }
             }
+        };
+        if self.quic {
+            #[cfg(feature = "quic")]
+            self.connect_quic(io_err)?;
+        } else {
+            self.connect_tcp(io_err)?;
         }
         // Do a round with the king, to be sure everyone is ready
-        let from_all = self.send_to_king(&[self.id as u8]);
-        self.recv_from_king(from_all);
+        let from_all = self.send_to(0, &[self.id as u8])?;
+        self.recv_from(0, from_all)?;
         for id in 0..n {
             if id != self.id {
                 assert!(self.peers[id].stream.is_some());
             }
         }
+        self.start_heartbeat();
+        self.start_recording();
         end_timer!(timer);
+        Ok(())
     }
-    fn am_king(&self) -> bool {
-        self.id == 0
+    fn connect_tcp<E: Fn(io::Error) -> NetError>(
+        &mut self,
+        io_err: impl Fn(usize) -> E,
+    ) -> Result<(), NetError> {
+        let n = self.peers.len();
+        let own_id = self.id;
+        // Listen first, then connect to every later party and accept every earlier one. Each
+        // connection opens with a hello naming its sender, so they may arrive in any order. The
+        // accepting party answers with a hello of its own, which is read once all are connected,
+        // since it only accepts once it has connected to the parties after it.
+        self.listener = Some(listen(self.peers[own_id].addr).map_err(io_err(own_id))?);
+        let deadline = Instant::now() + self.config.connect_timeout;
+        let mut sent = Vec::new();
+        for to_id in (own_id + 1)..n {
+            debug!("Contacting {}", to_id);
+            let mut stream = connect(self.peers[to_id].addr, deadline).map_err(io_err(to_id))?;
+            let (_, link) = self.link()?;
+            let hello = link.hello(0);
+            write_hello(&mut stream, &hello).map_err(io_err(to_id))?;
+            configure(&stream, &self.config).map_err(io_err(to_id))?;
+            self.peers[to_id].stream = Some(Stream::Tcp(stream));
+            sent.push((to_id, hello));
+        }
+        for from_id in 0..own_id {
+            debug!("Awaiting {}", from_id);
+            let (peers, link) = self.link()?;
+            let (mut stream, theirs) =
+                accept_from(&link, from_id, deadline).map_err(io_err(from_id))?;
+            let ours = link.hello(0);
+            write_hello(&mut stream, &ours).map_err(io_err(from_id))?;
+            configure(&stream, link.config).map_err(io_err(from_id))?;
+            peers[from_id].stream = Some(Stream::Tcp(stream));
+            peers[from_id].reseal(&link, &ours, &theirs);
+        }
+        self.read_answers(&sent, &io_err)
     }
-    fn broadcast(&mut self, bytes_out: &[u8]) -> Vec<Vec<u8>> {
+    /// Read the hellos with which the parties we contacted answered ours (`sent`, by party), and
+    /// seal those connections.
+    fn read_answers<E: Fn(io::Error) -> NetError>(
+        &mut self,
+        sent: &[(usize, Hello)],
+        io_err: &impl Fn(usize) -> E,
+    ) -> Result<(), NetError> {
+        let (peers, link) = self.link()?;
+        for (id, ours) in sent {
+            let peer = &mut peers[*id];
+            let stream = peer.stream.as_mut().unwrap();
+            stream.select("hello");
+            let theirs = read_hello(stream)
+                .and_then(|theirs| {
+                    theirs.check(&Hello { id: *id, ..*ours })?;
+                    Ok(theirs)
+                })
+                .map_err(io_err(*id))?;
+            peer.reseal(&link, ours, &theirs);
+        }
+        Ok(())
+    }
+    /// As [Connections::connect_tcp], over QUIC, from an endpoint of our own: sessions can't share
+    /// an address, as they can over TCP.
+    #[cfg(feature = "quic")]
+    fn connect_quic<E: Fn(io::Error) -> NetError>(
+        &mut self,
+        io_err: impl Fn(usize) -> E,
+    ) -> Result<(), NetError> {
+        let n = self.peers.len();
+        let own_id = self.id;
+        let endpoint = quic::Endpoint::bind(
+            self.peers[own_id].addr,
+            &self.config,
+            read_timeout(&self.config),
+        )
+        .map_err(io_err(own_id))?;
+        let deadline = Instant::now() + self.config.connect_timeout;
+        let mut sent = Vec::new();
+        for to_id in (own_id + 1)..n {
+            debug!("Contacting {}", to_id);
+            let mut stream = endpoint
+                .connect(self.peers[to_id].addr, deadline)
+                .map_err(io_err(to_id))?;
+            let hello = self.link()?.1.hello(0);
+            stream.select("hello");
+            write_hello(&mut stream, &hello).map_err(io_err(to_id))?;
+            self.peers[to_id].stream = Some(Stream::Quic(stream));
+            sent.push((to_id, hello));
+        }
+        // the earlier parties may connect in any order
+        while let Some(from_id) = (0..own_id).find(|&id| self.peers[id].stream.is_none()) {
+            let mut stream = endpoint.accept(deadline).map_err(io_err(from_id))?;
+            stream.select("hello");
+            match read_hello(&mut stream) {
+                Ok(theirs)
+                    if theirs.tag == self.tag
+                        && theirs.id < own_id
+                        && self.peers[theirs.id].stream.is_none() =>
+                {
+                    let (peers, link) = self.link()?;
+                    let ours = link.hello(0);
+                    write_hello(&mut stream, &ours).map_err(io_err(theirs.id))?;
+                    peers[theirs.id].stream = Some(Stream::Quic(stream));
+                    peers[theirs.id].reseal(&link, &ours, &theirs);
+                }
+                Ok(theirs) => debug!(
+                    "Dropping connection from party {} of session {}",
+                    theirs.id, theirs.tag
+                ),
+                Err(e) => debug!("Dropping bad connection: {}", e),
+            }
+        }
+        self.endpoint = Some(endpoint);
+        self.read_answers(&sent, &io_err)
+    }
+    /// If heartbeats are on, start sending them to every peer we are connected to, until the
+    /// connections close.
+    fn start_heartbeat(&mut self) {
+        let interval = match self.config.heartbeat {
+            Some(interval) => interval,
+            None => return,
+        };
+        if self.quic {
+            // QUIC sends keep-alives at this interval instead
+            return;
+        }
+        let mut slots = Vec::new();
+        for p in &self.peers {
+            if let Some(s) = &p.stream {
+                match Beat::of(s) {
+                    Ok(beat) => *p.beat.lock().unwrap() = Some(beat),
+                    Err(e) => warn!("No heartbeats to {}: {}", p.id, e),
+                }
+                slots.push((p.id, Arc::downgrade(&p.beat)));
+            }
+        }
+        let watcher = self.watcher.clone();
+        thread::Builder::new()
+            .name(format!("mpc-heartbeat-{}", self.tag))
+            .spawn(move || heartbeat(interval, slots, watcher))
+            .expect("heartbeat thread");
+    }
+    fn next_king(&mut self) -> usize {
+        let king = self.king_rotation % self.peers.len();
+        self.king_rotation += 1;
+        king
+    }
+    fn broadcast(&mut self, bytes_out: &[u8]) -> Result<Vec<Vec<u8>>, NetError> {
+        let exchange = Exchange::Broadcast;
+        if self.replayer.is_some() {
+            return self.replay(exchange, &[bytes_out]);
+        }
+        let at = self.record_start();
+        let bytes_in = self.broadcast_over_links(bytes_out)?;
+        self.record(at, exchange, &[bytes_out], &bytes_in);
+        Ok(bytes_in)
+    }
+    fn broadcast_over_links(&mut self, bytes_out: &[u8]) -> Result<Vec<Vec<u8>>, NetError> {
         let timer = start_timer!(|| format!("Broadcast {}", bytes_out.len()));
         let m = bytes_out.len();
         let own_id = self.id;
         self.stats.bytes_sent += (self.peers.len() - 1) * m;
         self.stats.bytes_recv += (self.peers.len() - 1) * m;
         self.stats.broadcasts += 1;
-        let r = self
-            .peers
+        self.flush_all()?;
+        let (peers, link) = self.link()?;
+        let r = peers
             .par_iter_mut()
             .enumerate()
             .map(|(id, peer)| {
                 let mut bytes_in = vec![0u8; m];
                 if id < own_id {
-                    let stream = peer.stream.as_mut().unwrap();
-                    stream.read_exact(&mut bytes_in[..]).unwrap();
-                    stream.write_all(bytes_out).unwrap();
+                    peer.recv(&link, &mut bytes_in[..])?;
+                    peer.send(&link, bytes_out)?;
+                    // other peers' reads may depend on this
+                    peer.flush(&link)?;
                 } else if id == own_id {
                     bytes_in.copy_from_slice(bytes_out);
                 } else {
-                    let stream = peer.stream.as_mut().unwrap();
-                    stream.write_all(bytes_out).unwrap();
-                    stream.read_exact(&mut bytes_in[..]).unwrap();
+                    peer.send(&link, bytes_out)?;
+                    peer.recv(&link, &mut bytes_in[..])?;
                 };
-                bytes_in
+                Ok(bytes_in)
             })
-            .collect();
+            .collect::<Result<Vec<_>, NetError>>()
+            .map_err(|e| self.fail(e));
         end_timer!(timer);
         r
     }
-    fn send_to_king(&mut self, bytes_out: &[u8]) -> Option<Vec<Vec<u8>>> {
+    fn send_to(&mut self, king: usize, bytes_out: &[u8]) -> Result<Option<Vec<Vec<u8>>>, NetError> {
+        let exchange = Exchange::ToKing { king };
+        if self.replayer.is_some() {
+            let bytes_in = self.replay(exchange, &[bytes_out])?;
+            return Ok((king == self.id).then_some(bytes_in));
+        }
+        let at = self.record_start();
+        let bytes_in = self.send_over_links(king, bytes_out)?;
+        self.record(at, exchange, &[bytes_out], bytes_in.as_deref().unwrap_or_default());
+        Ok(bytes_in)
+    }
+    fn send_over_links(
+        &mut self,
+        king: usize,
+        bytes_out: &[u8],
+    ) -> Result<Option<Vec<Vec<u8>>>, NetError> {
         let timer = start_timer!(|| format!("To king {}", bytes_out.len()));
         let m = bytes_out.len();
         let own_id = self.id;
         self.stats.to_king += 1;
-        let r = if self.am_king() {
+        if own_id == king {
             self.stats.bytes_recv += (self.peers.len() - 1) * m;
-            Some(
-                self.peers
-                    .par_iter_mut()
-                    .enumerate()
-                    .map(|(id, peer)| {
-                        let mut bytes_in = vec![0u8; m];
-                        if id == own_id {
-                            bytes_in.copy_from_slice(bytes_out);
-                        } else {
-                            let stream = peer.stream.as_mut().unwrap();
-                            stream.read_exact(&mut bytes_in[..]).unwrap();
-                        };
-                        bytes_in
-                    })
-                    .collect(),
-            )
         } else {
             self.stats.bytes_sent += m;
-            self.peers[0]
-                .stream
-                .as_mut()
-                .unwrap()
-                .write_all(bytes_out)
-                .unwrap();
-            None
+        }
+        if own_id == king {
+            self.flush_all()?;
+        }
+        let (peers, link) = self.link()?;
+        let r = if own_id == king {
+            peers
+                .par_iter_mut()
+                .enumerate()
+                .map(|(id, peer)| {
+                    let mut bytes_in = vec![0u8; m];
+                    if id == own_id {
+                        bytes_in.copy_from_slice(bytes_out);
+                    } else {
+                        peer.recv(&link, &mut bytes_in[..])?;
+                    };
+                    Ok(bytes_in)
+                })
+                .collect::<Result<Vec<_>, NetError>>()
+                .map(Some)
+        } else {
+            peers[king].send(&link, bytes_out).map(|()| None)
         };
         end_timer!(timer);
-        r
+        let r = r.map_err(|e| self.fail(e))?;
+        self.end_exchange()?;
+        Ok(r)
+    }
+    fn recv_from(&mut self, king: usize, bytes_out: Option<Vec<Vec<u8>>>) -> Result<Vec<u8>, NetError> {
+        let exchange = Exchange::FromKing { king };
+        let sent: Vec<&[u8]> = bytes_out.iter().flatten().map(|m| &m[..]).collect();
+        if self.replayer.is_some() {
+            let mut bytes_in = self.replay(exchange, &sent)?;
+            return Ok(bytes_in.pop().unwrap_or_default());
+        }
+        let at = self.record_start();
+        let bytes_in = self.recv_over_links(king, bytes_out.as_deref())?;
+        self.record(at, exchange, &sent, std::slice::from_ref(&bytes_in));
+        Ok(bytes_in)
     }
-    fn recv_from_king(&mut self, bytes_out: Option<Vec<Vec<u8>>>) -> Vec<u8> {
+    fn recv_over_links(
+        &mut self,
+        king: usize,
+        bytes_out: Option<&[Vec<u8>]>,
+    ) -> Result<Vec<u8>, NetError> {
         let own_id = self.id;
         self.stats.from_king += 1;
-        if self.am_king() {
+        let n = self.peers.len();
+        if own_id == king {
             let bytes_out = bytes_out.unwrap();
             let m = bytes_out[0].len();
             let timer = start_timer!(|| format!("From king {}", m));
             let bytes_size = (m as u64).to_le_bytes();
-            self.stats.bytes_sent += (self.peers.len() - 1) * (m + 8);
-            self.peers
+            self.stats.bytes_sent += (n - 1) * (m + 8);
+            let (peers, link) = self.link()?;
+            let r = peers
                 .par_iter_mut()
                 .enumerate()
                 .filter(|p| p.0 != own_id)
-                .for_each(|(id, peer)| {
-                    let stream = peer.stream.as_mut().unwrap();
+                .map(|(id, peer)| {
                     assert_eq!(bytes_out[id].len(), m);
-                    stream.write_all(&bytes_size).unwrap();
-                    stream.write_all(&bytes_out[id]).unwrap();
-                });
+                    peer.send(&link, &bytes_size)?;
+                    peer.send(&link, &bytes_out[id])
+                })
+                .collect::<Result<(), NetError>>();
             end_timer!(timer);
-            bytes_out[own_id].clone()
+            r.map_err(|e| self.fail(e))?;
+            self.end_exchange()?;
+            Ok(bytes_out[own_id].clone())
         } else {
-            let stream = self.peers[0].stream.as_mut().unwrap();
+            self.flush_all()?;
+            let (peers, link) = self.link()?;
+            let peer = &mut peers[king];
             let mut bytes_size = [0u8; 8];
-            stream.read_exact(&mut bytes_size).unwrap();
-            let m = u64::from_le_bytes(bytes_size) as usize;
-            self.stats.bytes_recv += m;
-            let mut bytes_in = vec![0u8; m];
-            stream.read_exact(&mut bytes_in).unwrap();
-            bytes_in
+            let r = peer.recv(&link, &mut bytes_size).and_then(|()| {
+                let m = u64::from_le_bytes(bytes_size) as usize;
+                let mut bytes_in = vec![0u8; m];
+                peer.recv(&link, &mut bytes_in)?;
+                Ok(bytes_in)
+            });
+            let bytes_in = r.map_err(|e| self.fail(e))?;
+            self.stats.bytes_recv += bytes_in.len();
+            Ok(bytes_in)
         }
     }
     fn uninit(&mut self) {
+        // best effort: the others may still be waiting on our last messages
+        let _ = self.flush_all();
         for p in &mut self.peers {
+            if let Some(shaper) = p.shaper.take() {
+                shaper.finish();
+            }
+            *p.beat.lock().unwrap() = None;
             p.stream = None;
+            p.seal = None;
+            p.sent = 0;
+            p.recvd = 0;
+            p.log.clear();
+            p.out.clear();
+            p.unflushed = 0;
+        }
+        if let Some(l) = self.listener.take() {
+            let tag = self.tag;
+            l.strays.lock().unwrap().retain(|(t, _), _| *t != tag);
         }
+        #[cfg(feature = "quic")]
+        {
+            self.endpoint = None;
+        }
+        self.recorder = None;
+        self.replayer = None;
+        self.locals = Default::default();
     }
 }
 
+/// Send a heartbeat on each of `slots` every `interval`, for as long as any of them is connected,
+/// telling `watcher` of the peers that can't be reached.
+fn heartbeat(interval: Duration, slots: Vec<(usize, Weak<Mutex<Option<Beat>>>)>, watcher: Watcher) {
+    loop {
+        thread::sleep(interval);
+        let mut connected = false;
+        for (peer, slot) in &slots {
+            let slot = match slot.upgrade() {
+                Some(slot) => slot,
+                None => continue,
+            };
+            let mut beat = slot.lock().unwrap();
+            let r = match beat.as_mut() {
+                Some(b) => b.send(),
+                None => continue,
+            };
+            match r {
+                Ok(()) => connected = true,
+                Err(e) => {
+                    *beat = None;
+                    drop(beat);
+                    watcher.unreachable(NetError::Disconnected {
+                        peer: *peer,
+                        reason: e.to_string(),
+                    });
+                }
+            }
+        }
+        if !connected {
+            return;
+        }
+    }
+}
+
+/// Unwrap the result of a network operation, aborting on failure.
+///
+/// The result must be computed before calling this, so that the lock on the session is released before
+/// unwinding.
+fn or_abort<T>(r: Result<T, NetError>) -> T {
+    r.unwrap_or_else(|e| abort(e))
+}
+
+/// Abort the current session because of a panic with `payload`, if it is running and not in use.
+fn abort_current_session(payload: &(dyn Any + Send), location: Option<&Location>) {
+    let ch = current_session();
+    let mut ch = match ch.try_lock() {
+        Ok(ch) => ch,
+        // an earlier panic in the network layer
+        Err(TryLockError::Poisoned(p)) => p.into_inner(),
+        Err(TryLockError::WouldBlock) => return,
+    };
+    if ch.aborted.is_some() || ch.peers.iter().all(|p| p.stream.is_none()) {
+        return;
+    }
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panic".into());
+    let reason = match location {
+        Some(l) => format!("{} at {}:{}", message, l.file(), l.line()),
+        None => message,
+    };
+    let e = NetError::Aborted {
+        peer: ch.id,
+        reason,
+    };
+    ch.fail(e);
+}
+
+/// Make `label` the current session's phase, returning the previous one.
+pub(crate) fn enter_phase(label: &str) -> String {
+    let ch = current_session();
+    // a phase may end as a panic unwinds
+    let mut ch = ch.lock().unwrap_or_else(|p| p.into_inner());
+    std::mem::replace(&mut ch.label, label.to_string())
+}
+
+/// The network of the current session: the [MpcSession] whose [MpcSession::run] we are in, or
+/// else the process-wide default session.
 pub struct MpcMultiNet;
 
+impl MpcMultiNet {
+    /// The tag of the current session; `0` for the default session.
+    ///
+    /// Protocols that keep per-session state can key it by this.
+    pub fn session_tag() -> u64 {
+        with_ch(|ch| ch.tag)
+    }
+
+    /// Run `f` on the current session's value of type `T`, which starts as `T::default()`.
+    ///
+    /// Protocols keep their per-session state here, rather than in globals keyed by
+    /// [MpcMultiNet::session_tag], so that it goes with the session: when an [MpcSession] drops,
+    /// or the default session is [deinit](MpcNet::deinit)ed. `f` must not call this again.
+    pub fn with_local<T: Any + Send + Default, R>(f: impl FnOnce(&mut T) -> R) -> R {
+        let locals = with_ch(|ch| ch.locals.clone());
+        let mut locals = locals.lock().expect("Poisoned session locals");
+        let local = locals
+            .0
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(T::default()));
+        f(local.downcast_mut().expect("keyed by type"))
+    }
+
+    /// Set timeouts and the reconnection policy. Takes effect at the next
+    /// [MpcNet::init_from_file].
+    pub fn set_config(config: NetConfig) {
+        with_ch(|ch| ch.config = config)
+    }
+
+    /// Run as the only party, with no networking at all.
+    ///
+    /// Every exchange then involves only ourselves, so it completes locally, and shared values
+    /// are just their plain values. This lets the same code serve as a local prover or benchmark
+    /// baseline.
+    pub fn init_single_party() {
+        with_ch(|ch| ch.init_alone())
+    }
+
+    /// Replay the transcript at `path`: become the party that recorded it, with each exchange
+    /// answered from the transcript rather than by the other parties. See
+    /// [transcript](crate::transcript).
+    pub fn init_replay(path: &Path) {
+        let transcript = transcript::read(path)
+            .unwrap_or_else(|e| panic!("could not read transcript {}: {}", path.display(), e));
+        with_ch(|ch| ch.init_replay(transcript))
+    }
+
+    /// Abort the current session, telling the other parties `reason`, and unwind as a failed
+    /// network operation would.
+    ///
+    /// The other parties' pending and later exchanges fail with [NetError::Aborted], whatever
+    /// peer they are waiting on.
+    pub fn broadcast_abort(reason: &str) -> ! {
+        let e = with_ch(|ch| {
+            let e = NetError::Aborted {
+                peer: ch.id,
+                reason: reason.to_string(),
+            };
+            ch.fail(e)
+        });
+        abort(e)
+    }
+
+    /// From now on, when a thread panics, abort the session it works for, telling the other
+    /// parties the panic's message, so that they fail with [NetError::Aborted] rather than wait
+    /// for a message that never comes.
+    ///
+    /// The hook runs before any panic hook installed earlier, which still reports the panic. It
+    /// does nothing if the session is busy in another thread; that thread's exchange then fails
+    /// as the panic unwinds.
+    pub fn abort_on_panic() {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            let previous = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                abort_current_session(info.payload(), info.location());
+                previous(info)
+            }))
+        })
+    }
+
+    /// Call `f`, from the heartbeat thread, when a heartbeat finds a peer of the current session
+    /// unreachable. Without this, that is only logged.
+    ///
+    /// A party waiting on an unreachable peer fails with [NetError::Unreachable] regardless;
+    /// this lets a party that is busy computing find out before its next exchange, and, say, give
+    /// up at once.
+    pub fn on_unreachable(f: impl Fn(&NetError) + Send + Sync + 'static) {
+        with_ch(|ch| *ch.watcher.0.lock().unwrap() = Some(Arc::new(f)))
+    }
+
+    /// Cut the connection to `peer`, as a network fault would, so that the next exchange with it
+    /// reconnects and resumes. For testing recovery.
+    ///
+    /// In-memory connections cannot resume, so they are left alone.
+    pub fn drop_connection(peer: usize) {
+        with_ch(|ch| {
+            if let Some(s) = ch.peers.get(peer).and_then(|p| p.stream.as_ref()) {
+                s.shutdown()
+            }
+        })
+    }
+}
+
 impl MpcNet for MpcMultiNet {
     #[inline]
     fn party_id() -> usize {
-        get_ch!().id
+        with_ch(|ch| ch.id)
     }
 
     #[inline]
     fn n_parties() -> usize {
-        get_ch!().peers.len()
+        with_ch(|ch| ch.peers.len())
     }
 
     #[inline]
     fn init_from_file(path: &str, party_id: usize) {
-        let mut ch = get_ch!();
-        ch.init_from_path(path, party_id);
-        ch.connect_to_all();
+        let r = with_ch(|ch| {
+            ch.init_from_path(path, party_id);
+            ch.connect_to_all().map_err(|e| ch.fail(e))
+        });
+        or_abort(r)
     }
 
     #[inline]
     fn is_init() -> bool {
-        get_ch!()
-            .peers
-            .first()
-            .map(|p| p.stream.is_some())
-            .unwrap_or(false)
+        with_ch(|ch| {
+            ch.peers
+                .first()
+                .map(|p| p.stream.is_some())
+                .unwrap_or(false)
+        })
     }
 
     #[inline]
     fn deinit() {
-        get_ch!().uninit()
+        with_ch(|ch| ch.uninit())
     }
 
     #[inline]
     fn reset_stats() {
-        get_ch!().stats = Stats::default();
+        with_ch(|ch| ch.reset_stats())
     }
 
     #[inline]
     fn stats() -> crate::Stats {
-        get_ch!().stats.clone()
+        with_ch(|ch| ch.stats())
     }
 
     #[inline]
     fn broadcast_bytes(bytes: &[u8]) -> Vec<Vec<u8>> {
-        get_ch!().broadcast(bytes)
+        let _net = crate::profile::span("net");
+        let r = with_ch(|ch| ch.broadcast(bytes));
+        or_abort(r)
     }
 
     #[inline]
-    fn send_bytes_to_king(bytes: &[u8]) -> Option<Vec<Vec<u8>>> {
-        get_ch!().send_to_king(bytes)
+    fn send_bytes_to(king: usize, bytes: &[u8]) -> Option<Vec<Vec<u8>>> {
+        let _net = crate::profile::span("net");
+        let r = with_ch(|ch| ch.send_to(king, bytes));
+        or_abort(r)
     }
 
     #[inline]
-    fn recv_bytes_from_king(bytes: Option<Vec<Vec<u8>>>) -> Vec<u8> {
-        get_ch!().recv_from_king(bytes)
+    fn recv_bytes_from(king: usize, bytes: Option<Vec<Vec<u8>>>) -> Vec<u8> {
+        let _net = crate::profile::span("net");
+        let r = with_ch(|ch| ch.recv_from(king, bytes));
+        or_abort(r)
+    }
+
+    #[inline]
+    fn next_king() -> usize {
+        with_ch(|ch| ch.next_king())
+    }
+
+    /// When batching, messages that `f` sends without waiting for a reply are held back and
+    /// coalesced with later ones, until `f` next reads or returns.
+    ///
+    /// Rounds may nest.
+    fn round<T>(f: impl FnOnce() -> T) -> T {
+        with_ch(|ch| ch.rounds += 1);
+        let out = f();
+        let r = with_ch(|ch| {
+            ch.rounds -= 1;
+            ch.end_exchange()
+        });
+        or_abort(r);
+        out
+    }
+}
+
+/// One of several independent protocol runs in the same process.
+///
+/// Each session has its own connections, counters, king rotation and configuration. Code run by
+/// [MpcSession::run] sees the session through the usual [MpcMultiNet] calls, so a server can prove
+/// several statements at once, each in its own session.
+///
+/// Sessions bound to the same address share its listening socket: every connection opens by
+/// naming its session's tag, so the parties must agree on the tag of each session, and sessions
+/// that are live at the same time must have distinct tags. Tag `0` belongs to the default session.
+#[derive(Clone)]
+pub struct MpcSession {
+    inner: Arc<SessionInner>,
+}
+
+struct SessionInner {
+    ch: Arc<Mutex<Connections>>,
+    /// Runs [MpcSession::run], so that parallel iterators inside it stay in the session.
+    pool: rayon::ThreadPool,
+}
+
+impl Drop for SessionInner {
+    fn drop(&mut self) {
+        self.ch.lock().expect("Poisoned FieldChannel").uninit();
+    }
+}
+
+impl MpcSession {
+    fn from_connections(ch: Connections) -> Self {
+        let tag = ch.tag;
+        let ch = Arc::new(Mutex::new(ch));
+        let worker_ch = ch.clone();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .thread_name(move |i| format!("mpc-session-{}-{}", tag, i))
+            .start_handler(move |_| {
+                CURRENT_SESSION.with(|c| *c.borrow_mut() = Some(worker_ch.clone()))
+            })
+            .build()
+            .expect("session thread pool");
+        Self {
+            inner: Arc::new(SessionInner { ch, pool }),
+        }
+    }
+
+    /// Connect to the parties listed in `path` (one `HOST:PORT`, or `quic://HOST:PORT`, per line),
+    /// as party `party_id`, in the session `tag`.
+    pub fn connect(
+        path: &str,
+        party_id: usize,
+        tag: u64,
+        config: NetConfig,
+    ) -> Result<Self, NetError> {
+        assert_ne!(tag, 0, "tag 0 belongs to the default session");
+        let mut ch = Connections {
+            tag,
+            config,
+            ..Connections::default()
+        };
+        ch.init_from_path(path, party_id);
+        ch.connect_to_all().map_err(|e| ch.fail(e))?;
+        Ok(Self::from_connections(ch))
+    }
+
+    /// `n` sessions, one for each party, connected in memory rather than over the network, so
+    /// that one process can play every party (in tests, say).
+    ///
+    /// Each session gets a distinct tag, from a range that [MpcSession::connect] callers should
+    /// avoid (the top half of `u64`). Lost connections are not resumed, since they only drop when
+    /// a party's session does.
+    pub fn in_memory(n: usize, config: NetConfig) -> Vec<Self> {
+        static NEXT_TAG: AtomicU64 = AtomicU64::new(1 << 63);
+        let mut all: Vec<Connections> = (0..n)
+            .map(|id| Connections {
+                id,
+                tag: NEXT_TAG.fetch_add(1, Ordering::Relaxed),
+                // nothing to replay
+                config: NetConfig {
+                    replay_messages: 0,
+                    ..config.clone()
+                },
+                peers: (0..n)
+                    .map(|peer| Peer {
+                        id: peer,
+                        ..Peer::default()
+                    })
+                    .collect(),
+                ..Connections::default()
+            })
+            .collect();
+        for a in 0..n {
+            for b in (a + 1)..n {
+                let (to_b, to_a) = MemStream::pair(read_timeout(&config));
+                all[a].peers[b].stream = Some(Stream::Mem(to_b));
+                all[b].peers[a].stream = Some(Stream::Mem(to_a));
+                if let Some(key) = &config.seal {
+                    let (salt_a, salt_b) = (seal::salt(), seal::salt());
+                    all[a].peers[b].seal = Some(Seal::new(key, a, &salt_a, b, &salt_b));
+                    all[b].peers[a].seal = Some(Seal::new(key, b, &salt_b, a, &salt_a));
+                }
+            }
+        }
+        for ch in &mut all {
+            ch.start_heartbeat();
+            ch.start_recording();
+        }
+        all.into_iter().map(Self::from_connections).collect()
+    }
+
+    /// A session with no other parties, as [MpcMultiNet::init_single_party].
+    pub fn single_party(tag: u64) -> Self {
+        let mut ch = Connections {
+            tag,
+            ..Connections::default()
+        };
+        ch.init_alone();
+        Self::from_connections(ch)
+    }
+
+    /// A session, in the session `tag`, that replays the transcript at `path`, as
+    /// [MpcMultiNet::init_replay].
+    pub fn replay(path: &Path, tag: u64) -> io::Result<Self> {
+        let mut ch = Connections {
+            tag,
+            ..Connections::default()
+        };
+        ch.init_replay(transcript::read(path)?);
+        Ok(Self::from_connections(ch))
+    }
+
+    /// The session's tag.
+    pub fn tag(&self) -> u64 {
+        self.inner.ch.lock().expect("Poisoned FieldChannel").tag
+    }
+
+    /// Run `f` in this session.
+    ///
+    /// `f` runs on the session's own threads, and so does any parallel iterator inside it. An
+    /// abort in `f` unwinds out of this call, as it would outside a session.
+    pub fn run<T: Send>(&self, f: impl FnOnce() -> T + Send) -> T {
+        self.inner.pool.install(f)
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.inner.ch.lock().expect("Poisoned FieldChannel").stats()
+    }
+
+    pub fn reset_stats(&self) {
+        self.inner.ch.lock().expect("Poisoned FieldChannel").reset_stats()
     }
 }
@@ -0,0 +1,349 @@
+//! Profiling of where each party's time goes, as flamegraphs.
+//!
+//! The spans are the `ark_std` timers (`start_timer!`/`end_timer!`, which need its `print-trace`
+//! feature), the phases of [mem::phase](crate::mem::phase), and the network exchanges of
+//! [MpcMultiNet](crate::MpcMultiNet). Once [start]ed, each thread keeps a stack of the spans it is
+//! in, and charges their wall-clock time, less that of their children, to the stack. [finish] then
+//! writes the current party's stacks to `party<id>.folded`, in the folded format of `flamegraph.pl`
+//! and `inferno-flamegraph`, which render it.
+//!
+//! With [ProfileConfig::phases], only time within those phases is kept, so one run can profile,
+//! say, just the MSMs. Spans are per thread: time a span hands off to a thread pool counts as the
+//! opening thread's wait.
+//!
+//! With the `pprof` feature and [ProfileConfig::cpu], pprof also samples every thread's stack
+//! while a profiled phase runs, so time spent in a thread pool is charged to the functions it ran,
+//! and [finish] writes the samples to `party<id>.cpu.folded`. With that feature, each folded file
+//! is also rendered, as `party<id>.svg` and `party<id>.cpu.svg`.
+//!
+//! Profiling can be reconfigured while the party runs, from a control channel such as a
+//! coordinator's: [set_enabled] pauses and resumes it, and [set_phases] changes the phases it
+//! keeps, from the next one that begins.
+use lazy_static::lazy_static;
+#[cfg(not(feature = "pprof"))]
+use log::warn;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::{MpcMultiNet, MpcNet};
+
+#[derive(Clone, Debug, Default)]
+pub struct ProfileConfig {
+    /// Where to write the folded stacks.
+    pub dir: PathBuf,
+    /// The phases to profile; all of the run, if empty.
+    pub phases: Vec<String>,
+    /// Whether to also sample the CPU's stacks, with pprof: this needs the `pprof` feature.
+    pub cpu: bool,
+}
+
+/// How often pprof samples the stacks, per second. The kernel's profiling timer may tick no
+/// faster, or slower.
+#[cfg(feature = "pprof")]
+const SAMPLE_HZ: i32 = 100;
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// How many profiled phases are open, in all threads.
+static OPEN_PHASES: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    static ref CONFIG: Mutex<ProfileConfig> = Mutex::new(ProfileConfig::default());
+}
+
+struct Frame {
+    name: String,
+    start: Instant,
+    /// Time spent in finished children.
+    children: Duration,
+    /// Whether this is, or is within, a profiled phase.
+    kept: bool,
+    /// Whether this is a profiled phase, counted in [OPEN_PHASES].
+    counted: bool,
+}
+
+#[derive(Default)]
+struct Profile {
+    stack: Vec<Frame>,
+    /// Self time by stack, in microseconds.
+    folded: HashMap<String, u64>,
+}
+
+thread_local! {
+    static PROFILE: RefCell<Profile> = RefCell::new(Profile::default());
+}
+
+/// Begin profiling, with `config`, and follow the `ark_std` timers. Spans are followed for the
+/// rest of the process, but starting again replaces the configuration.
+pub fn start(config: ProfileConfig) {
+    ark_std::perf_trace::set_span_hook(ark_std::perf_trace::SpanHook {
+        enter: |msg| {
+            enter(msg, false);
+        },
+        exit,
+    });
+    #[cfg(not(feature = "pprof"))]
+    if config.cpu {
+        warn!("CPU profiles need the `pprof` feature; profiling spans only");
+    }
+    *CONFIG.lock().unwrap() = config;
+    ENABLED.store(true, Ordering::Relaxed);
+    STARTED.store(true, Ordering::Relaxed);
+    cpu::sync();
+}
+
+/// Whether profiling has started.
+pub fn is_active() -> bool {
+    STARTED.load(Ordering::Relaxed)
+}
+
+/// Pause (`false`) or resume (`true`) keeping spans and samples. Spans already begun are kept
+/// as they were when they began.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    cpu::sync();
+}
+
+/// Profile only `phases` from now on (all of the run, if empty). Phases already begun are
+/// profiled, or not, as they were when they began.
+pub fn set_phases(phases: Vec<String>) {
+    CONFIG.lock().unwrap().phases = phases;
+    cpu::sync();
+}
+
+/// A span, which ends when dropped.
+#[must_use = "the span ends when this is dropped"]
+pub struct Span {
+    entered: bool,
+}
+
+/// Begin the span `name`.
+pub fn span(name: &str) -> Span {
+    Span {
+        entered: enter(name, false),
+    }
+}
+
+/// Begin the phase `name`, which is kept if it is one of [ProfileConfig::phases].
+pub(crate) fn phase(name: &str) -> Span {
+    Span {
+        entered: enter(name, true),
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if self.entered {
+            exit()
+        }
+    }
+}
+
+/// `name`, as a frame of a folded stack, in which `;` separates frames.
+fn frame_name(name: &str) -> String {
+    name.trim().replace(|c| c == ';' || c == '\n', ",")
+}
+
+fn enter(name: &str, is_phase: bool) -> bool {
+    if !is_active() {
+        return false;
+    }
+    let (all, counted) = {
+        let config = CONFIG.lock().unwrap();
+        let counted = is_phase && config.phases.iter().any(|p| p == name);
+        (config.phases.is_empty(), counted)
+    };
+    let enabled = ENABLED.load(Ordering::Relaxed);
+    PROFILE.with(|p| {
+        let mut p = p.borrow_mut();
+        let within = p.stack.last().map_or(false, |f| f.kept);
+        p.stack.push(Frame {
+            name: frame_name(name),
+            start: Instant::now(),
+            children: Duration::default(),
+            kept: enabled && (all || counted || within),
+            counted,
+        });
+    });
+    if counted {
+        OPEN_PHASES.fetch_add(1, Ordering::Relaxed);
+        cpu::sync();
+    }
+    true
+}
+
+fn exit() {
+    let counted = PROFILE.with(|p| {
+        let mut p = p.borrow_mut();
+        // a timer that ends on another thread than it started on has nothing to pop here
+        let frame = match p.stack.pop() {
+            Some(f) => f,
+            None => return false,
+        };
+        let elapsed = frame.start.elapsed();
+        if let Some(parent) = p.stack.last_mut() {
+            parent.children += elapsed;
+        }
+        if frame.kept {
+            let mut key: Vec<&str> = p.stack.iter().map(|f| f.name.as_str()).collect();
+            key.push(&frame.name);
+            let key = key.join(";");
+            let micros = elapsed.saturating_sub(frame.children).as_micros() as u64;
+            *p.folded.entry(key).or_insert(0) += micros;
+        }
+        frame.counted
+    });
+    if counted {
+        OPEN_PHASES.fetch_sub(1, Ordering::Relaxed);
+        cpu::sync();
+    }
+}
+
+/// Write this thread's stacks for the current party to `party<id>.folded` in the configured
+/// directory, and clear them, and with [ProfileConfig::cpu], the CPU samples so far to
+/// `party<id>.cpu.folded`. Spans still open are not included. Returns the files written.
+pub fn finish() -> io::Result<Vec<PathBuf>> {
+    let dir = CONFIG.lock().unwrap().dir.clone();
+    fs::create_dir_all(&dir)?;
+    let party = MpcMultiNet::party_id();
+    let folded = PROFILE.with(|p| std::mem::take(&mut p.borrow_mut().folded));
+    let mut written = write_folded(&dir.join(format!("party{}.folded", party)), folded, "us")?;
+    if let Some(samples) = cpu::take() {
+        let path = dir.join(format!("party{}.cpu.folded", party));
+        written.extend(write_folded(&path, samples, "samples")?);
+    }
+    Ok(written)
+}
+
+/// Write `folded` stacks to `path`, and with the `pprof` feature, render them, counted in
+/// `unit`, beside it. Returns the files written.
+#[cfg_attr(not(feature = "pprof"), allow(unused_variables))]
+fn write_folded(path: &Path, folded: HashMap<String, u64>, unit: &str) -> io::Result<Vec<PathBuf>> {
+    let mut lines: Vec<String> = folded
+        .into_iter()
+        .map(|(stack, count)| format!("{} {}", stack, count))
+        .collect();
+    lines.sort();
+    let mut f = io::BufWriter::new(fs::File::create(path)?);
+    for line in &lines {
+        writeln!(f, "{}", line)?;
+    }
+    f.flush()?;
+    #[cfg(feature = "pprof")]
+    if !lines.is_empty() {
+        let svg = path.with_extension("svg");
+        let mut options = pprof::flamegraph::Options::default();
+        options.count_name = unit.to_owned();
+        let f = io::BufWriter::new(fs::File::create(&svg)?);
+        pprof::flamegraph::from_lines(&mut options, lines.iter().map(|l| l.as_str()), f)
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        return Ok(vec![path.to_owned(), svg]);
+    }
+    Ok(vec![path.to_owned()])
+}
+
+/// Sampling the CPU, with pprof, while profiled phases are open.
+#[cfg(feature = "pprof")]
+mod cpu {
+    use lazy_static::lazy_static;
+    use log::warn;
+    use pprof::{ProfilerGuard, ProfilerGuardBuilder};
+
+    use std::collections::HashMap;
+    use std::sync::atomic::Ordering;
+    use std::sync::Mutex;
+
+    use super::{frame_name, CONFIG, ENABLED, OPEN_PHASES, SAMPLE_HZ, STARTED};
+
+    #[derive(Default)]
+    struct Sampler {
+        /// The profiler, while it samples.
+        guard: Option<ProfilerGuard<'static>>,
+        /// Samples by stack, from the times it sampled before.
+        folded: HashMap<String, u64>,
+    }
+
+    lazy_static! {
+        static ref SAMPLER: Mutex<Sampler> = Mutex::new(Sampler::default());
+    }
+
+    impl Sampler {
+        /// Stop sampling, and keep the samples.
+        fn stop(&mut self) {
+            let guard = match self.guard.take() {
+                Some(g) => g,
+                None => return,
+            };
+            let report = match guard.report().build() {
+                Ok(r) => r,
+                Err(e) => {
+                    warn!("Could not read the CPU samples: {}", e);
+                    return;
+                }
+            };
+            for (frames, count) in report.data {
+                let mut stack = vec![frame_name(&frames.thread_name_or_id())];
+                for frame in frames.frames.iter().rev() {
+                    stack.extend(frame.iter().rev().map(|s| frame_name(&s.to_string())));
+                }
+                *self.folded.entry(stack.join(";")).or_insert(0) += count as u64;
+            }
+        }
+    }
+
+    /// Sample if, and only if, the configuration asks for it now.
+    pub(super) fn sync() {
+        let mut sampler = SAMPLER.lock().unwrap();
+        let wanted = {
+            let config = CONFIG.lock().unwrap();
+            config.cpu
+                && STARTED.load(Ordering::Relaxed)
+                && ENABLED.load(Ordering::Relaxed)
+                && (config.phases.is_empty() || OPEN_PHASES.load(Ordering::Relaxed) > 0)
+        };
+        if wanted && sampler.guard.is_none() {
+            let guard = ProfilerGuardBuilder::default()
+                .frequency(SAMPLE_HZ)
+                .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+                .build();
+            match guard {
+                Ok(g) => sampler.guard = Some(g),
+                Err(e) => warn!("Could not sample the CPU: {}", e),
+            }
+        } else if !wanted {
+            sampler.stop();
+        }
+    }
+
+    /// The samples so far, by folded stack, if sampling is configured. Sampling goes on, if it
+    /// was.
+    pub(super) fn take() -> Option<HashMap<String, u64>> {
+        if !CONFIG.lock().unwrap().cpu {
+            return None;
+        }
+        let mut sampler = SAMPLER.lock().unwrap();
+        sampler.stop();
+        let folded = std::mem::take(&mut sampler.folded);
+        drop(sampler);
+        sync();
+        Some(folded)
+    }
+}
+
+#[cfg(not(feature = "pprof"))]
+mod cpu {
+    use std::collections::HashMap;
+
+    pub(super) fn sync() {}
+
+    pub(super) fn take() -> Option<HashMap<String, u64>> {
+        None
+    }
+}
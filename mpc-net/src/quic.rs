@@ -0,0 +1,437 @@
+//! Connections over QUIC, for hosts given as `quic://HOST:PORT`.
+//!
+//! Each connection carries, in each direction, one stream per phase (as [mem::phase](crate::mem::phase)
+//! marks them), so that a packet lost in one phase's traffic holds up only that phase, rather than
+//! everything behind it on a single TCP connection. QUIC brings its own TLS and keep-alives; the
+//! frames on the streams are the same as over TCP.
+use log::debug;
+use quinn::crypto::rustls::{QuicClientConfig, QuicServerConfig};
+use quinn::{
+    ClientConfig, Connection, ConnectionError, IdleTimeout, ReadError, RecvStream, SendStream,
+    ServerConfig, TransportConfig, VarInt, WriteError,
+};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, RootCertStore, SignatureScheme};
+use tokio::runtime::Runtime;
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::future::Future;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::multi::AbortNotice;
+use super::NetConfig;
+
+/// The name that the parties' certificates must be for.
+pub const SERVER_NAME: &str = "mpc-net";
+
+/// The close code of a connection that a party gave up on, whose reason is an [AbortNotice].
+const ABORT_CODE: u32 = 1;
+/// The close code of a connection that simply ended.
+const DONE_CODE: u32 = 0;
+
+/// How often to keep a connection alive, when the configuration has no heartbeat: an idle QUIC
+/// connection would otherwise close itself.
+const KEEP_ALIVE: Duration = Duration::from_secs(5);
+
+/// How long to wait, when closing a connection, for the peer to acknowledge what we sent.
+const LINGER: Duration = Duration::from_secs(5);
+
+/// How long a single attempt to reach a peer may take.
+const CONNECT_ATTEMPT: Duration = Duration::from_secs(1);
+
+/// This party's TLS identity, and whom it trusts.
+#[derive(Clone, Debug, Default)]
+pub struct QuicConfig {
+    /// This party's certificate chain and (PKCS #8) private key, DER-encoded. The certificate
+    /// must be for [SERVER_NAME]. Without one, the party makes itself a self-signed one.
+    pub identity: Option<(Vec<Vec<u8>>, Vec<u8>)>,
+    /// The certificates, DER-encoded, that the other parties' must chain to. If there are none,
+    /// any certificate will do: the connections are then encrypted, but not authenticated.
+    pub roots: Vec<Vec<u8>>,
+}
+
+/// A party's QUIC endpoint, from which it connects to the parties after it and accepts those
+/// before it.
+pub(crate) struct Endpoint {
+    rt: Arc<Runtime>,
+    endpoint: quinn::Endpoint,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+}
+
+impl Endpoint {
+    /// Listen at `addr`.
+    pub(crate) fn bind(
+        addr: SocketAddr,
+        config: &NetConfig,
+        read_timeout: Option<Duration>,
+    ) -> io::Result<Self> {
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("mpc-quic")
+            .enable_all()
+            .build()?;
+        let mut transport = TransportConfig::default();
+        transport.keep_alive_interval(Some(config.heartbeat.unwrap_or(KEEP_ALIVE)));
+        transport.max_idle_timeout(
+            read_timeout
+                .map(IdleTimeout::try_from)
+                .transpose()
+                .map_err(|e| io::Error::new(ErrorKind::InvalidInput, e))?,
+        );
+        transport.max_concurrent_uni_streams(VarInt::from_u32(1 << 10));
+        let transport = Arc::new(transport);
+
+        let provider = Arc::new(ring::default_provider());
+        let (chain, key) = match &config.quic.identity {
+            Some((chain, key)) => (
+                chain
+                    .iter()
+                    .map(|c| CertificateDer::from(c.clone()))
+                    .collect(),
+                PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.clone())),
+            ),
+            None => {
+                let cert = rcgen::generate_simple_self_signed(vec![SERVER_NAME.to_string()])
+                    .map_err(io::Error::other)?;
+                (
+                    vec![cert.cert.der().clone()],
+                    PrivateKeyDer::Pkcs8(cert.key_pair.serialize_der().into()),
+                )
+            }
+        };
+        let tls = |e: rustls::Error| io::Error::new(ErrorKind::InvalidInput, e);
+        let server = rustls::ServerConfig::builder_with_provider(provider.clone())
+            .with_protocol_versions(&[&rustls::version::TLS13])
+            .map_err(tls)?
+            .with_no_client_auth()
+            .with_single_cert(chain, key)
+            .map_err(tls)?;
+        let server = QuicServerConfig::try_from(server)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidInput, e))?;
+        let mut server = ServerConfig::with_crypto(Arc::new(server));
+        server.transport_config(transport.clone());
+
+        let client = rustls::ClientConfig::builder_with_provider(provider.clone())
+            .with_protocol_versions(&[&rustls::version::TLS13])
+            .map_err(tls)?;
+        let client = if config.quic.roots.is_empty() {
+            client
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(AnyCertificate(provider)))
+                .with_no_client_auth()
+        } else {
+            let mut roots = RootCertStore::empty();
+            for root in &config.quic.roots {
+                roots.add(CertificateDer::from(root.clone())).map_err(tls)?;
+            }
+            client.with_root_certificates(roots).with_no_client_auth()
+        };
+        let client = QuicClientConfig::try_from(client)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidInput, e))?;
+        let mut client = ClientConfig::new(Arc::new(client));
+        client.transport_config(transport);
+
+        let mut endpoint = {
+            let _rt = rt.enter();
+            quinn::Endpoint::server(server, addr)?
+        };
+        endpoint.set_default_client_config(client);
+        Ok(Self {
+            rt: Arc::new(rt),
+            endpoint,
+            read_timeout,
+            write_timeout: config.io_timeout,
+        })
+    }
+
+    fn stream(&self, conn: Connection) -> QuicStream {
+        QuicStream {
+            rt: self.rt.clone(),
+            conn,
+            label: String::new(),
+            send: HashMap::new(),
+            recv: HashMap::new(),
+            read_timeout: self.read_timeout,
+            write_timeout: self.write_timeout,
+        }
+    }
+
+    /// Connect to `addr`, retrying until `deadline` while nobody answers there.
+    pub(crate) fn connect(&self, addr: SocketAddr, deadline: Instant) -> io::Result<QuicStream> {
+        // connecting spawns the connection's driver
+        let _rt = self.rt.enter();
+        loop {
+            let attempt = self
+                .endpoint
+                .connect(addr, SERVER_NAME)
+                .map_err(|e| io::Error::new(ErrorKind::InvalidInput, e))?;
+            match self.rt.block_on(within(Some(CONNECT_ATTEMPT), async {
+                attempt.await.map_err(lost)
+            })) {
+                Ok(conn) => return Ok(self.stream(conn)),
+                Err(e) if Instant::now() < deadline && e.kind() == ErrorKind::TimedOut => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Accept the next connection, until `deadline`.
+    ///
+    /// Attempts that fail during the handshake, such as those a peer gave up on before retrying,
+    /// are skipped.
+    pub(crate) fn accept(&self, deadline: Instant) -> io::Result<QuicStream> {
+        let wait = deadline.saturating_duration_since(Instant::now());
+        let conn = self.rt.block_on(within(Some(wait), async {
+            loop {
+                let incoming = self
+                    .endpoint
+                    .accept()
+                    .await
+                    .ok_or_else(|| io::Error::new(ErrorKind::NotConnected, "not listening"))?;
+                match incoming.await {
+                    Ok(conn) => return Ok(conn),
+                    Err(e) => debug!("Dropping failed connection: {}", e),
+                }
+            }
+        }))?;
+        Ok(self.stream(conn))
+    }
+}
+
+impl std::fmt::Debug for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Endpoint")
+            .field("addr", &self.endpoint.local_addr().ok())
+            .finish()
+    }
+}
+
+impl Drop for Endpoint {
+    fn drop(&mut self) {
+        // so that the last closes, such as abort notices, go out
+        let endpoint = &self.endpoint;
+        let _ = self.rt.block_on(within(Some(LINGER), async {
+            endpoint.wait_idle().await;
+            Ok(())
+        }));
+    }
+}
+
+/// A QUIC connection to a peer, which reads and writes on the stream of the current phase.
+pub(crate) struct QuicStream {
+    rt: Arc<Runtime>,
+    conn: Connection,
+    /// The phase whose stream reads and writes go to.
+    label: String,
+    send: HashMap<String, SendStream>,
+    /// The streams opened by the peer so far, by phase.
+    recv: HashMap<String, RecvStream>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+}
+
+impl QuicStream {
+    /// Read and write on the stream of phase `label` from now on.
+    pub(crate) fn select(&mut self, label: &str) {
+        if self.label != label {
+            self.label = label.to_string();
+        }
+    }
+
+    /// Hang up, telling the peer that the session aborted, with the payload of an abort frame.
+    pub(crate) fn abort(&self, notice: &[u8]) {
+        self.conn.close(VarInt::from_u32(ABORT_CODE), notice);
+    }
+
+    /// Hang up at once.
+    pub(crate) fn shutdown(&self) {
+        self.conn.close(VarInt::from_u32(DONE_CODE), b"");
+    }
+
+    /// Why the peer hung up, if to tell us that the session aborted.
+    pub(crate) fn abort_notice(&self) -> Option<AbortNotice> {
+        match self.conn.close_reason()? {
+            ConnectionError::ApplicationClosed(c)
+                if c.error_code == VarInt::from_u32(ABORT_CODE) =>
+            {
+                AbortNotice::parse(&c.reason)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Read for QuicStream {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let rt = self.rt.clone();
+        let timeout = self.read_timeout;
+        rt.block_on(within(timeout, async {
+            while !self.recv.contains_key(&self.label) {
+                let mut s = self.conn.accept_uni().await.map_err(lost)?;
+                let label = read_label(&mut s).await?;
+                self.recv.insert(label, s);
+            }
+            let s = self.recv.get_mut(&self.label).unwrap();
+            match s.read(out).await {
+                Ok(Some(n)) => Ok(n),
+                Ok(None) => Err(io::Error::new(ErrorKind::UnexpectedEof, "stream finished")),
+                Err(ReadError::ConnectionLost(e)) => Err(lost(e)),
+                Err(e) => Err(e.into()),
+            }
+        }))
+    }
+}
+
+impl Write for QuicStream {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        let rt = self.rt.clone();
+        let timeout = self.write_timeout;
+        rt.block_on(within(timeout, async {
+            if !self.send.contains_key(&self.label) {
+                let mut s = self.conn.open_uni().await.map_err(lost)?;
+                let label = self.label.as_bytes();
+                let len = label.len().min(u8::MAX as usize);
+                let mut header = vec![len as u8];
+                header.extend_from_slice(&label[..len]);
+                s.write_all(&header).await.map_err(write_error)?;
+                self.send.insert(self.label.clone(), s);
+            }
+            let s = self.send.get_mut(&self.label).unwrap();
+            s.write(bytes).await.map_err(write_error)
+        }))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for QuicStream {
+    fn drop(&mut self) {
+        // let the peer read all we wrote before we close
+        for s in self.send.values_mut() {
+            let _ = s.finish();
+        }
+        let sent: Vec<_> = self.send.values().map(|s| s.stopped()).collect();
+        let _ = self.rt.block_on(within(Some(LINGER), async {
+            for s in sent {
+                let _ = s.await;
+            }
+            Ok(())
+        }));
+        self.shutdown();
+    }
+}
+
+impl std::fmt::Debug for QuicStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuicStream")
+            .field("peer", &self.conn.remote_address())
+            .field("label", &self.label)
+            .finish()
+    }
+}
+
+/// Read the phase that a stream the peer opened is for.
+async fn read_label(s: &mut RecvStream) -> io::Result<String> {
+    let exact = |e: quinn::ReadExactError| match e {
+        quinn::ReadExactError::ReadError(ReadError::ConnectionLost(e)) => lost(e),
+        e => io::Error::new(ErrorKind::InvalidData, e),
+    };
+    let mut len = [0u8; 1];
+    s.read_exact(&mut len).await.map_err(exact)?;
+    let mut label = vec![0u8; len[0] as usize];
+    s.read_exact(&mut label).await.map_err(exact)?;
+    String::from_utf8(label).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
+
+fn write_error(e: WriteError) -> io::Error {
+    match e {
+        WriteError::ConnectionLost(e) => lost(e),
+        e => e.into(),
+    }
+}
+
+/// The I/O error for a lost connection: an [AbortNotice] if the peer gave up, and otherwise of
+/// the kinds that TCP would give.
+fn lost(e: ConnectionError) -> io::Error {
+    match e {
+        ConnectionError::ApplicationClosed(c) if c.error_code == VarInt::from_u32(ABORT_CODE) => {
+            match AbortNotice::parse(&c.reason) {
+                Some(notice) => io::Error::new(ErrorKind::ConnectionAborted, notice),
+                None => io::Error::new(ErrorKind::InvalidData, "bad abort notice"),
+            }
+        }
+        ConnectionError::TimedOut => io::Error::new(ErrorKind::TimedOut, e),
+        e => io::Error::new(ErrorKind::ConnectionReset, e),
+    }
+}
+
+/// Run `f`, failing with a timeout after `timeout`, if any.
+async fn within<T>(
+    timeout: Option<Duration>,
+    f: impl Future<Output = io::Result<T>>,
+) -> io::Result<T> {
+    match timeout {
+        Some(t) => tokio::time::timeout(t, f)
+            .await
+            .unwrap_or_else(|_| Err(io::Error::new(ErrorKind::TimedOut, "timed out"))),
+        None => f.await,
+    }
+}
+
+/// Accepts any certificate, for when [QuicConfig::roots] is empty.
+#[derive(Debug)]
+struct AnyCertificate(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
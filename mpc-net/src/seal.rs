@@ -0,0 +1,196 @@
+//! Sealing: authenticated encryption of all that a party sends, for links without TLS of their
+//! own (such as TCP to a proxy that terminates it).
+//!
+//! Each direction of each connection has its own ChaCha20-Poly1305 key, derived from the parties'
+//! [SealKey] and random salts that both ends send in their hellos, so every connection has fresh
+//! keys. Each write goes out as one sealed record: a frame whose payload is the ciphertext of the
+//! frames written, under a nonce that counts the records, so that records can't be altered,
+//! dropped, reordered or replayed unnoticed. Heartbeats, which carry nothing, go in the clear.
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::io::{self, ErrorKind, Read};
+
+use super::multi::{frame_len, CODEC_HEARTBEAT, CODEC_SEALED, FRAME_HEADER};
+use super::SealKey;
+
+/// Bytes that sealing adds to a record's payload.
+const TAG: usize = 16;
+
+/// A fresh salt for a hello.
+pub(crate) fn salt() -> [u8; 16] {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+/// The keys of one connection, and what has been read of the record being opened.
+pub(crate) struct Seal {
+    out: ChaCha20Poly1305,
+    sealed: u64,
+    inp: ChaCha20Poly1305,
+    opened: u64,
+    /// The frames of the record being read, and how much of them has been read.
+    buf: Vec<u8>,
+    pos: usize,
+    /// Bytes read off the wire, for [Stats](crate::Stats).
+    pub(crate) wire: usize,
+}
+
+impl Seal {
+    /// The seal of the connection between party `own` and party `peer`, with the salts of their
+    /// hellos.
+    pub(crate) fn new(
+        key: &SealKey,
+        own: usize,
+        own_salt: &[u8; 16],
+        peer: usize,
+        peer_salt: &[u8; 16],
+    ) -> Self {
+        // both ends must agree on the order of the salts
+        let salts = if own < peer {
+            [&own_salt[..], &peer_salt[..]].concat()
+        } else {
+            [&peer_salt[..], &own_salt[..]].concat()
+        };
+        let hk = Hkdf::<Sha256>::new(Some(&salts), &key.0);
+        let direction = |from: usize, to: usize| {
+            let mut info = b"mpc-net seal".to_vec();
+            info.extend_from_slice(&(from as u64).to_le_bytes());
+            info.extend_from_slice(&(to as u64).to_le_bytes());
+            let mut key = Key::default();
+            hk.expand(&info, &mut key)
+                .expect("a key is a valid length of output");
+            ChaCha20Poly1305::new(&key)
+        };
+        Self {
+            out: direction(own, peer),
+            sealed: 0,
+            inp: direction(peer, own),
+            opened: 0,
+            buf: Vec::new(),
+            pos: 0,
+            wire: 0,
+        }
+    }
+
+    /// The record that carries `frames`, which must fit in one frame once sealed.
+    pub(crate) fn seal(&mut self, frames: &[u8]) -> io::Result<Vec<u8>> {
+        let header = header(frames.len() + TAG)?;
+        let payload = Payload {
+            msg: frames,
+            aad: &header,
+        };
+        let sealed = self
+            .out
+            .encrypt(&nonce(self.sealed), payload)
+            .expect("a record fits in a nonce's worth of blocks");
+        self.sealed += 1;
+        Ok([&header[..], &sealed[..]].concat())
+    }
+
+    /// Read the frames in the records on `stream`.
+    pub(crate) fn reader<'a, R: Read>(&'a mut self, stream: &'a mut R) -> Unseal<'a, R> {
+        Unseal { seal: self, stream }
+    }
+
+    /// Read the next record from `stream`.
+    fn open(&mut self, stream: &mut impl Read) -> io::Result<()> {
+        let mut header = [0u8; FRAME_HEADER];
+        let len = loop {
+            stream.read_exact(&mut header)?;
+            self.wire += FRAME_HEADER;
+            let mut len = [0u8; 4];
+            len.copy_from_slice(&header[..4]);
+            let len = u32::from_le_bytes(len) as usize;
+            match header[4] {
+                CODEC_HEARTBEAT if len == 0 => continue,
+                CODEC_SEALED if len >= TAG => break len,
+                CODEC_SEALED => return Err(tampered("sealed record is too short")),
+                _ => return Err(tampered("frame is not sealed; do all parties seal?")),
+            }
+        };
+        let mut sealed = vec![0u8; len];
+        stream.read_exact(&mut sealed)?;
+        self.wire += len;
+        let payload = Payload {
+            msg: &sealed,
+            aad: &header,
+        };
+        self.buf = self
+            .inp
+            .decrypt(&nonce(self.opened), payload)
+            .map_err(|_| {
+                tampered("record failed authentication: it was altered, or sealed with another key")
+            })?;
+        self.pos = 0;
+        self.opened += 1;
+        Ok(())
+    }
+}
+
+impl Debug for Seal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Seal")
+            .field("sealed", &self.sealed)
+            .field("opened", &self.opened)
+            .finish()
+    }
+}
+
+/// The frames in the records on a stream.
+pub(crate) struct Unseal<'a, R> {
+    seal: &'a mut Seal,
+    stream: &'a mut R,
+}
+
+impl<R: Read> Read for Unseal<'_, R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let seal = &mut *self.seal;
+        while seal.pos == seal.buf.len() {
+            seal.open(self.stream)?;
+        }
+        let n = out.len().min(seal.buf.len() - seal.pos);
+        out[..n].copy_from_slice(&seal.buf[seal.pos..seal.pos + n]);
+        seal.pos += n;
+        Ok(n)
+    }
+}
+
+fn header(len: usize) -> io::Result<[u8; FRAME_HEADER]> {
+    let mut header = [0u8; FRAME_HEADER];
+    header[..4].copy_from_slice(&frame_len(len)?.to_le_bytes());
+    header[4] = CODEC_SEALED;
+    Ok(header)
+}
+
+fn nonce(count: u64) -> Nonce {
+    let mut nonce = Nonce::default();
+    nonce[..8].copy_from_slice(&count.to_le_bytes());
+    nonce
+}
+
+/// Why what a peer sent can't be trusted.
+#[derive(Debug)]
+struct Tampered(&'static str);
+
+impl Display for Tampered {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl std::error::Error for Tampered {}
+
+fn tampered(what: &'static str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, Tampered(what))
+}
+
+/// Whether `e` is because what a peer sent failed to open.
+pub(crate) fn is_tampered(e: &io::Error) -> bool {
+    e.get_ref().is_some_and(|e| e.is::<Tampered>())
+}
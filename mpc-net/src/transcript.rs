@@ -0,0 +1,287 @@
+//! Transcripts: a record of every exchange a party made, for debugging.
+//!
+//! With [NetConfig::record](crate::NetConfig::record), each party writes `party<id>.transcript` to
+//! the given directory: for every [MpcNet](crate::MpcNet) exchange, when it began, how long it
+//! took, the phase it was in (as [mem::phase](crate::mem::phase) marks it), and the messages the
+//! party sent and received. [read] reads one back, for study.
+//!
+//! A transcript can also stand in for the other parties.
+//! [MpcSession::replay](crate::MpcSession::replay) (or
+//! [MpcMultiNet::init_replay](crate::MpcMultiNet::init_replay)) makes a session that answers each
+//! exchange with what was received in the recording, without any network, so that one party's
+//! computation can be re-run offline, under a debugger, say. The party must send what it
+//! sent in the recording, so it must draw the same randomness (as with a fixed seed); where it
+//! does not, the exchange fails with [NetError::Diverged], naming the first that differs.
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::NetError;
+
+/// Identifies a transcript file, and its version.
+const MAGIC: &[u8; 20] = b"mpc-net transcript 1";
+
+/// Party `id`'s transcript in `dir`.
+pub fn path(dir: &Path, id: usize) -> PathBuf {
+    dir.join(format!("party{}.transcript", id))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exchange {
+    /// [MpcNet::broadcast_bytes](crate::MpcNet::broadcast_bytes).
+    Broadcast,
+    /// [MpcNet::send_bytes_to](crate::MpcNet::send_bytes_to).
+    ToKing { king: usize },
+    /// [MpcNet::recv_bytes_from](crate::MpcNet::recv_bytes_from).
+    FromKing { king: usize },
+}
+
+impl Exchange {
+    fn code(self) -> (u8, usize) {
+        match self {
+            Exchange::Broadcast => (0, 0),
+            Exchange::ToKing { king } => (1, king),
+            Exchange::FromKing { king } => (2, king),
+        }
+    }
+}
+
+/// One exchange.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// When it began, since the session connected.
+    pub at: Duration,
+    /// How long it took, waiting on the other parties included.
+    pub took: Duration,
+    /// The phase it was in; empty outside any.
+    pub label: String,
+    pub exchange: Exchange,
+    /// What we sent: our message, or, as the king of a [Exchange::FromKing], every party's (ours
+    /// included). Nothing, if we only received.
+    pub sent: Vec<Vec<u8>>,
+    /// What we received: every party's message (ours included), or the king's. Nothing, if we
+    /// only sent.
+    pub recvd: Vec<Vec<u8>>,
+}
+
+/// A party's transcript.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transcript {
+    pub party: usize,
+    pub n_parties: usize,
+    pub entries: Vec<Entry>,
+}
+
+/// Read the transcript at `path`.
+pub fn read(path: &Path) -> io::Result<Transcript> {
+    let mut f = BufReader::new(File::open(path)?);
+    let mut magic = [0u8; MAGIC.len()];
+    f.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            format!("{} is not an mpc-net transcript", path.display()),
+        ));
+    }
+    let party = read_u64(&mut f)? as usize;
+    let n_parties = read_u64(&mut f)? as usize;
+    let mut entries = Vec::new();
+    // a transcript cut short, by a crash say, keeps the entries before the cut
+    while let Some(e) = read_entry(&mut f)? {
+        entries.push(e);
+    }
+    Ok(Transcript {
+        party,
+        n_parties,
+        entries,
+    })
+}
+
+fn read_u64(f: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    f.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_bytes(f: &mut impl Read) -> io::Result<Vec<u8>> {
+    let len = read_u64(f)?;
+    let mut bytes = Vec::new();
+    f.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+fn read_messages(f: &mut impl Read) -> io::Result<Vec<Vec<u8>>> {
+    (0..read_u64(f)?).map(|_| read_bytes(f)).collect()
+}
+
+fn read_entry(f: &mut impl Read) -> io::Result<Option<Entry>> {
+    let at = match read_u64(f) {
+        Ok(at) => Duration::from_micros(at),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let entry = (|| {
+        let took = Duration::from_micros(read_u64(f)?);
+        let label = String::from_utf8_lossy(&read_bytes(f)?).into_owned();
+        let mut code = [0u8];
+        f.read_exact(&mut code)?;
+        let king = read_u64(f)? as usize;
+        let exchange = match code[0] {
+            0 => Exchange::Broadcast,
+            1 => Exchange::ToKing { king },
+            2 => Exchange::FromKing { king },
+            c => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("unknown exchange {}", c),
+                ))
+            }
+        };
+        Ok(Entry {
+            at,
+            took,
+            label,
+            exchange,
+            sent: read_messages(f)?,
+            recvd: read_messages(f)?,
+        })
+    })();
+    match entry {
+        Ok(e) => Ok(Some(e)),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Writes a session's transcript as it goes.
+#[derive(Debug)]
+pub(crate) struct Recorder {
+    file: BufWriter<File>,
+    start: Instant,
+}
+
+impl Recorder {
+    pub(crate) fn create(dir: &Path, id: usize, n: usize) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let mut file = BufWriter::new(File::create(path(dir, id))?);
+        file.write_all(MAGIC)?;
+        file.write_all(&(id as u64).to_le_bytes())?;
+        file.write_all(&(n as u64).to_le_bytes())?;
+        file.flush()?;
+        Ok(Self {
+            file,
+            start: Instant::now(),
+        })
+    }
+
+    /// When an exchange that begins now began.
+    pub(crate) fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Write out the exchange that began `at`. Each is flushed, so that a crash keeps what led up
+    /// to it.
+    pub(crate) fn record(
+        &mut self,
+        at: Duration,
+        label: &str,
+        exchange: Exchange,
+        sent: &[&[u8]],
+        recvd: &[&[u8]],
+    ) -> io::Result<()> {
+        let took = self.now().saturating_sub(at);
+        let (code, king) = exchange.code();
+        let f = &mut self.file;
+        f.write_all(&(at.as_micros() as u64).to_le_bytes())?;
+        f.write_all(&(took.as_micros() as u64).to_le_bytes())?;
+        write_bytes(f, label.as_bytes())?;
+        f.write_all(&[code])?;
+        f.write_all(&(king as u64).to_le_bytes())?;
+        for messages in [sent, recvd] {
+            f.write_all(&(messages.len() as u64).to_le_bytes())?;
+            for m in messages {
+                write_bytes(f, m)?;
+            }
+        }
+        f.flush()
+    }
+}
+
+fn write_bytes(f: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    f.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    f.write_all(bytes)
+}
+
+/// Answers a session's exchanges from a transcript.
+#[derive(Debug)]
+pub(crate) struct Replayer {
+    entries: std::vec::IntoIter<Entry>,
+    /// Number of exchanges replayed so far.
+    done: usize,
+}
+
+impl Replayer {
+    pub(crate) fn new(transcript: Transcript) -> Self {
+        Self {
+            entries: transcript.entries.into_iter(),
+            done: 0,
+        }
+    }
+
+    /// What we received in the next exchange, which must be `exchange`, sending `sent`.
+    pub(crate) fn next(
+        &mut self,
+        exchange: Exchange,
+        sent: &[&[u8]],
+    ) -> Result<Vec<Vec<u8>>, NetError> {
+        let i = self.done;
+        self.done += 1;
+        let diverged = |reason: String| NetError::Diverged {
+            exchange: i,
+            reason,
+        };
+        let entry = self
+            .entries
+            .next()
+            .ok_or_else(|| diverged(format!("{:?} after the end of the transcript", exchange)))?;
+        if entry.exchange != exchange {
+            return Err(diverged(format!(
+                "{:?}, but the transcript has {:?} (in phase {:?})",
+                exchange, entry.exchange, entry.label
+            )));
+        }
+        if entry.sent.len() != sent.len() {
+            return Err(diverged(format!(
+                "{:?} (in phase {:?}) sent {} messages, not {}",
+                exchange,
+                entry.label,
+                sent.len(),
+                entry.sent.len()
+            )));
+        }
+        for (j, (ours, theirs)) in sent.iter().zip(&entry.sent).enumerate() {
+            if ours != theirs {
+                let at = ours
+                    .iter()
+                    .zip(theirs)
+                    .position(|(a, b)| a != b)
+                    .unwrap_or_else(|| ours.len().min(theirs.len()));
+                return Err(diverged(format!(
+                    "{:?} (in phase {:?}) sent a message {} that differs from the recorded one at \
+                     byte {} (of {}, not {})",
+                    exchange,
+                    entry.label,
+                    j,
+                    at,
+                    ours.len(),
+                    theirs.len()
+                )));
+            }
+        }
+        Ok(entry.recvd)
+    }
+}
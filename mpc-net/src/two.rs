@@ -1,13 +1,14 @@
 use lazy_static::lazy_static;
 use log::debug;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read, Write};
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
 use std::net::{SocketAddr, TcpListener, TcpStream};
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use ark_std::{end_timer, start_timer};
 
-use super::{MpcNet, Stats};
+use super::{abort, MpcNet, NetConfig, NetError, Stats};
 
 #[macro_use]
 lazy_static! {
@@ -28,6 +29,10 @@ pub struct FieldChannel {
     pub other_addr: SocketAddr,
     pub stats: Stats,
     pub talk_first: bool,
+    /// Number of rotating-king exchanges so far; determines the next king.
+    pub king_rotation: usize,
+    /// Of this, only the timeouts apply.
+    pub config: NetConfig,
 }
 
 impl std::default::Default for FieldChannel {
@@ -39,6 +44,8 @@ impl std::default::Default for FieldChannel {
             other_addr: "127.0.0.1:8000".parse().unwrap(),
             stats: Stats::default(),
             talk_first: false,
+            king_rotation: 0,
+            config: NetConfig::default(),
         }
     }
 }
@@ -62,42 +69,47 @@ impl FieldChannel {
         self.self_addr = addrs[id];
         self.other_addr = addrs[1 - id];
         self.talk_first = id == 0;
+        self.king_rotation = 0;
     }
 
+    /// The other party's id.
+    fn peer(&self) -> usize {
+        if self.talk_first {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn net_error(&self, e: io::Error) -> NetError {
+        match e.kind() {
+            ErrorKind::TimedOut | ErrorKind::WouldBlock => NetError::Timeout { peer: self.peer() },
+            _ => NetError::Io {
+                peer: self.peer(),
+                error: e.to_string(),
+            },
+        }
+    }
+
+    /// Connect to the other party, within the connect timeout.
     #[inline]
-    pub fn connect(&mut self) {
+    pub fn connect(&mut self) -> Result<(), NetError> {
         debug!("I am {}, connecting to {}", self.self_addr, self.other_addr);
-        self.stream = Some(if self.talk_first {
+        let deadline = Instant::now() + self.config.connect_timeout;
+        let stream = if self.talk_first {
             debug!("Attempting to contact peer");
-            loop {
-                let mut ms_waited = 0;
-                match TcpStream::connect(self.other_addr) {
-                    Ok(s) => break s,
-                    Err(e) => {
-                        if e.kind() == std::io::ErrorKind::ConnectionRefused {
-                            ms_waited += 100;
-                            std::thread::sleep(std::time::Duration::from_millis(100));
-                            if ms_waited % 3_000 == 0 {
-                                debug!("Still waiting");
-                            } else if ms_waited > 30_000 {
-                                panic!("Could not find peer in 30s");
-                            }
-                        } else {
-                            panic!("Error during FieldChannel::new: {}", e);
-                        }
-                    }
-                }
-            }
+            connect(self.other_addr, deadline)
         } else {
-            let listener = TcpListener::bind(self.self_addr).unwrap();
             debug!("Waiting for peer to contact us");
-            let (stream, _addr) = listener.accept().unwrap();
-            stream
-        });
+            accept(self.self_addr, deadline)
+        };
+        let stream = stream.map_err(|e| self.net_error(e))?;
         // disable nagle's alg
-        self.stream.as_mut().unwrap().set_nodelay(true).unwrap();
-        self.stream.as_mut().unwrap().set_nonblocking(true).unwrap();
+        stream.set_nodelay(true).map_err(|e| self.net_error(e))?;
+        self.stream = Some(stream);
+        Ok(())
     }
+
     #[inline]
     pub fn stream(&mut self) -> &mut TcpStream {
         self.stream
@@ -105,43 +117,64 @@ impl FieldChannel {
             .expect("Unitialized FieldChannel. Did you forget init(..)?")
     }
 
-    #[inline]
-    pub fn send_slice(&mut self, v: &[u8]) {
+    /// The stream, to block on for at most the I/O timeout.
+    fn blocking_stream(&mut self) -> io::Result<&mut TcpStream> {
+        let timeout = self.config.io_timeout;
         let s = self.stream();
-        s.set_nonblocking(false).unwrap();
+        s.set_nonblocking(false)?;
+        s.set_read_timeout(timeout)?;
+        s.set_write_timeout(timeout)?;
+        Ok(s)
+    }
+
+    #[inline]
+    pub fn send_slice(&mut self, v: &[u8]) -> Result<(), NetError> {
         let bytes = (v.len() as u64).to_ne_bytes();
-        s.write_all(&bytes[..]).unwrap();
-        s.write_all(v).unwrap();
-        s.set_nonblocking(true).unwrap();
+        self.blocking_stream()
+            .and_then(|s| {
+                s.write_all(&bytes[..])?;
+                s.write_all(v)
+            })
+            .map_err(|e| self.net_error(e))?;
         self.stats.bytes_sent += bytes.len() + v.len();
+        Ok(())
     }
 
     #[inline]
-    pub fn recv_vec(&mut self) -> Vec<u8> {
-        let s = self.stream();
+    pub fn recv_vec(&mut self) -> Result<Vec<u8>, NetError> {
         let mut len = [0u8; 8];
-        s.set_nonblocking(false).unwrap();
-        s.read_exact(&mut len[..]).unwrap();
-        let mut bytes = vec![0u8; u64::from_ne_bytes(len) as usize];
-        s.read_exact(&mut bytes[..]).unwrap();
-        s.set_nonblocking(true).unwrap();
+        let bytes = self
+            .blocking_stream()
+            .and_then(|s| {
+                s.read_exact(&mut len[..])?;
+                let mut bytes = vec![0u8; u64::from_ne_bytes(len) as usize];
+                s.read_exact(&mut bytes[..])?;
+                Ok(bytes)
+            })
+            .map_err(|e| self.net_error(e))?;
         self.stats.bytes_recv += bytes.len() + len.len();
-        bytes
+        Ok(bytes)
     }
 
+    /// Send `bytes_out` while receiving as many bytes, failing with
+    /// [ErrorKind::TimedOut] if neither moves for the I/O timeout.
     #[inline]
     pub fn exchange_bytes(&mut self, bytes_out: &[u8]) -> std::io::Result<Vec<u8>> {
         let timer = start_timer!(|| format!("Exchanging {}", bytes_out.len()));
+        let timeout = self.config.io_timeout;
         let s = self.stream();
+        s.set_nonblocking(true)?;
         let n = bytes_out.len();
         let mut bytes_in = vec![0u8; n];
         let mut bytes_in_offset = 0;
         let mut bytes_out_offset = 0;
+        let mut progress = Instant::now();
         while bytes_out_offset < n || bytes_in_offset < n {
             if bytes_out_offset < n {
                 match s.write(&bytes_out[bytes_out_offset..]) {
                     Ok(written) => {
                         bytes_out_offset += written;
+                        progress = Instant::now();
                         let _e = s.flush();
                     }
                     Err(e) => {
@@ -155,8 +188,15 @@ impl FieldChannel {
             }
             if bytes_in_offset < n {
                 match s.read(&mut bytes_in[bytes_in_offset..]) {
+                    Ok(0) => {
+                        return Err(io::Error::new(
+                            ErrorKind::UnexpectedEof,
+                            "peer closed the connection",
+                        ))
+                    }
                     Ok(read) => {
                         bytes_in_offset += read;
+                        progress = Instant::now();
                     }
                     Err(e) => {
                         if e.kind() == std::io::ErrorKind::WouldBlock {
@@ -167,6 +207,9 @@ impl FieldChannel {
                     }
                 }
             }
+            if timeout.map_or(false, |t| progress.elapsed() > t) {
+                return Err(io::Error::new(ErrorKind::TimedOut, "exchange stalled"));
+            }
         }
         self.stats.broadcasts += 1;
         self.stats.bytes_sent += n;
@@ -186,6 +229,48 @@ impl FieldChannel {
     }
 }
 
+/// Connect to `addr`, retrying until `deadline` while nobody is listening there.
+fn connect(addr: SocketAddr, deadline: Instant) -> io::Result<TcpStream> {
+    let mut waited = 0;
+    loop {
+        match TcpStream::connect(addr) {
+            Ok(s) => return Ok(s),
+            Err(e) if e.kind() == ErrorKind::ConnectionRefused => {
+                if Instant::now() > deadline {
+                    return Err(io::Error::new(ErrorKind::TimedOut, e));
+                }
+                std::thread::sleep(Duration::from_millis(100));
+                waited += 1;
+                if waited % 30 == 0 {
+                    debug!("Still waiting");
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Accept one connection on `addr`, giving up at `deadline`.
+fn accept(addr: SocketAddr, deadline: Instant) -> io::Result<TcpStream> {
+    let listener = TcpListener::bind(addr)?;
+    listener.set_nonblocking(true)?;
+    loop {
+        match listener.accept() {
+            Ok((stream, _addr)) => {
+                stream.set_nonblocking(false)?;
+                return Ok(stream);
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                if Instant::now() > deadline {
+                    return Err(io::Error::new(ErrorKind::TimedOut, "no peer connected"));
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 #[inline]
 /// Initialize the MPC
 pub fn init_from_path(path: &str, id: usize) {
@@ -195,7 +280,11 @@ pub fn init_from_path(path: &str, id: usize) {
         "FieldChannel should no be re-intialized. Did you call init(..) twice?"
     );
     ch.init_from_path(path, id);
-    ch.connect();
+    let r = ch.connect();
+    drop(ch);
+    if let Err(e) = r {
+        abort(e);
+    }
     debug!("Connected");
 }
 
@@ -238,6 +327,14 @@ pub fn am_first() -> bool {
 
 pub struct MpcTwoNet;
 
+impl MpcTwoNet {
+    /// Set the timeouts for connecting and for each exchange; the rest of `config` is for
+    /// [MpcMultiNet](crate::MpcMultiNet) only. Applies from the next connection.
+    pub fn set_config(config: NetConfig) {
+        get_ch!().config = config;
+    }
+}
+
 impl MpcNet for MpcTwoNet {
     #[inline]
     fn party_id() -> usize {
@@ -256,7 +353,7 @@ impl MpcNet for MpcTwoNet {
 
     #[inline]
     fn init_from_file(path: &str, party_id: usize) {
-        get_ch!().init_from_path(path, party_id);
+        init_from_path(path, party_id);
     }
 
     #[inline]
@@ -281,7 +378,13 @@ impl MpcNet for MpcTwoNet {
 
     #[inline]
     fn broadcast_bytes(bytes: &[u8]) -> Vec<Vec<u8>> {
-        let other = get_ch!().exchange_bytes(bytes).unwrap();
+        // the channel is unlocked before any abort, so that it isn't poisoned
+        let other = {
+            let mut ch = get_ch!();
+            let r = ch.exchange_bytes(bytes);
+            r.map_err(|e| ch.net_error(e))
+        };
+        let other = other.unwrap_or_else(|e| abort(e));
         if Self::am_king() {
             vec![bytes.to_vec(), other]
         } else {
@@ -290,30 +393,50 @@ impl MpcNet for MpcTwoNet {
     }
 
     #[inline]
-    fn send_bytes_to_king(bytes: &[u8]) -> Option<Vec<Vec<u8>>> {
-        let mut ch = get_ch!();
-        ch.stats.to_king += 1;
-        if ch.talk_first {
-            let other = ch.recv_vec();
-            debug_assert_eq!(bytes.len(), other.len());
-            Some(vec![bytes.to_vec(), other])
-        } else {
-            ch.send_slice(bytes);
-            None
-        }
+    fn send_bytes_to(king: usize, bytes: &[u8]) -> Option<Vec<Vec<u8>>> {
+        let r = {
+            let mut ch = get_ch!();
+            ch.stats.to_king += 1;
+            let own_id = if ch.talk_first { 0 } else { 1 };
+            if own_id == king {
+                ch.recv_vec().map(|other| {
+                    debug_assert_eq!(bytes.len(), other.len());
+                    let mut all = vec![bytes.to_vec(), other];
+                    if own_id == 1 {
+                        all.reverse();
+                    }
+                    Some(all)
+                })
+            } else {
+                ch.send_slice(bytes).map(|()| None)
+            }
+        };
+        r.unwrap_or_else(|e| abort(e))
     }
 
     #[inline]
-    fn recv_bytes_from_king(bytes: Option<Vec<Vec<u8>>>) -> Vec<u8> {
+    fn recv_bytes_from(king: usize, bytes: Option<Vec<Vec<u8>>>) -> Vec<u8> {
+        let r = {
+            let mut ch = get_ch!();
+            ch.stats.from_king += 1;
+            let own_id = if ch.talk_first { 0 } else { 1 };
+            if own_id == king {
+                let mut bytes = bytes.expect("king needs bytes");
+                assert_eq!(bytes.len(), 2);
+                let other = bytes.remove(1 - own_id);
+                ch.send_slice(&other).map(|()| bytes.pop().unwrap())
+            } else {
+                ch.recv_vec()
+            }
+        };
+        r.unwrap_or_else(|e| abort(e))
+    }
+
+    #[inline]
+    fn next_king() -> usize {
         let mut ch = get_ch!();
-        ch.stats.from_king += 1;
-        if ch.talk_first {
-            let mut bytes = bytes.expect("king needs bytes");
-            assert_eq!(bytes.len(), 2);
-            ch.send_slice(&bytes.pop().unwrap());
-            bytes.pop().unwrap()
-        } else {
-            ch.recv_vec()
-        }
+        let king = ch.king_rotation % 2;
+        ch.king_rotation += 1;
+        king
     }
 }
@@ -0,0 +1,65 @@
+//! A party that gives up, or panics, aborts the session for every party, with its reason.
+use mpc_net::{catch_abort, MpcMultiNet as Net, MpcNet, MpcSession, NetConfig, NetError};
+
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+use std::time::Duration;
+
+/// Run `f` as each of `n` parties, connected in memory, and return what became of each.
+fn run_parties<T: Send>(n: usize, f: impl Fn() -> T + Sync) -> Vec<thread::Result<T>> {
+    let config = NetConfig {
+        io_timeout: Some(Duration::from_secs(20)),
+        ..NetConfig::default()
+    };
+    let f = &f;
+    thread::scope(|s| {
+        let handles: Vec<_> = MpcSession::in_memory(n, config)
+            .into_iter()
+            .map(|session| {
+                s.spawn(move || panic::catch_unwind(AssertUnwindSafe(|| session.run(f))))
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    })
+}
+
+#[test]
+fn broadcast_abort() {
+    let results = run_parties(3, || {
+        catch_abort(|| {
+            Net::broadcast_bytes(&[1, 2, 3]);
+            if Net::party_id() == 0 {
+                Net::broadcast_abort("bad input");
+            }
+            Net::broadcast_bytes(&[4, 5, 6])
+        })
+    });
+    for r in results {
+        assert_eq!(
+            r.unwrap(),
+            Err(NetError::Aborted {
+                peer: 0,
+                reason: "bad input".into()
+            })
+        );
+    }
+}
+
+#[test]
+fn panic_aborts() {
+    Net::abort_on_panic();
+    let results = run_parties(3, || {
+        catch_abort(|| {
+            Net::broadcast_bytes(&[1, 2, 3]);
+            assert_ne!(Net::party_id(), 1, "boom");
+            Net::broadcast_bytes(&[4, 5, 6])
+        })
+    });
+    assert!(results[1].is_err());
+    for r in [&results[0], &results[2]] {
+        match r.as_ref().unwrap() {
+            Err(NetError::Aborted { peer: 1, reason }) => assert!(reason.contains("boom")),
+            other => panic!("expected party 1 to abort, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! With pprof, profiling samples the CPU within the selected phases, and renders flamegraphs.
+#![cfg(feature = "pprof")]
+use mpc_net::{mem, profile};
+
+use std::fs;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+fn spin(time: Duration) -> u64 {
+    let start = Instant::now();
+    let mut x = 0u64;
+    while start.elapsed() < time {
+        x = black_box(x.wrapping_mul(6364136223846793005).wrapping_add(1));
+    }
+    x
+}
+
+#[inline(never)]
+fn spin_outside() -> u64 {
+    spin(Duration::from_millis(300))
+}
+
+#[inline(never)]
+fn spin_inside() -> u64 {
+    spin(Duration::from_millis(300))
+}
+
+#[test]
+fn samples_phases() {
+    let dir = std::env::temp_dir().join(format!("mpc-net-cpu-profile-{}", std::process::id()));
+    profile::start(profile::ProfileConfig {
+        dir: dir.clone(),
+        phases: vec!["kept".into()],
+        cpu: true,
+    });
+    {
+        let _p = mem::phase("skipped");
+        spin_outside();
+    }
+    {
+        let _p = mem::phase("kept");
+        spin_inside();
+    }
+
+    let written = profile::finish().unwrap();
+    let names: Vec<_> = written
+        .iter()
+        .map(|p| p.file_name().unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "party0.folded",
+            "party0.svg",
+            "party0.cpu.folded",
+            "party0.cpu.svg"
+        ]
+    );
+    let cpu = fs::read_to_string(dir.join("party0.cpu.folded")).unwrap();
+    let samples = |function: &str| -> u64 {
+        cpu.lines()
+            .filter(|l| l.contains(function))
+            .map(|l| l.rsplit_once(' ').unwrap().1.parse::<u64>().unwrap())
+            .sum()
+    };
+    assert!(samples("cpu_profile::spin_inside") > 0);
+    assert_eq!(samples("cpu_profile::spin_outside"), 0);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,98 @@
+//! Heartbeats keep a silent but busy party alive, and find out about an unreachable one early.
+use mpc_net::{catch_abort, MpcMultiNet as Net, MpcNet, MpcSession, NetConfig, NetError};
+
+use std::fs;
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+fn config(heartbeat: Option<Duration>) -> NetConfig {
+    NetConfig {
+        io_timeout: Some(Duration::from_secs(20)),
+        heartbeat,
+        ..NetConfig::default()
+    }
+}
+
+#[test]
+fn busy_party_stays_alive() {
+    let sessions = MpcSession::in_memory(2, config(Some(Duration::from_millis(10))));
+    let results: Vec<_> = thread::scope(|s| {
+        let handles: Vec<_> = sessions
+            .into_iter()
+            .map(|session| {
+                s.spawn(move || {
+                    session.run(|| {
+                        if Net::party_id() == 1 {
+                            // ten times as long as a peer may stay silent
+                            thread::sleep(Duration::from_millis(400));
+                        }
+                        catch_abort(|| Net::broadcast_bytes(&[Net::party_id() as u8]))
+                    })
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    for r in results {
+        assert_eq!(r, Ok(vec![vec![0], vec![1]]));
+    }
+}
+
+#[test]
+fn silent_party_is_unreachable() {
+    // party 1 sends no heartbeats, so party 0 gives up on it while it sleeps
+    let ports: Vec<_> = (0..2)
+        .map(|_| TcpListener::bind("127.0.0.1:0").unwrap())
+        .collect();
+    let hosts = std::env::temp_dir().join(format!("mpc-net-heartbeat-{}", std::process::id()));
+    let addrs: Vec<_> = ports
+        .iter()
+        .map(|l| l.local_addr().unwrap().to_string())
+        .collect();
+    fs::write(&hosts, addrs.join("\n")).unwrap();
+    drop(ports);
+    let heartbeats = [Some(Duration::from_millis(10)), None];
+    let results: Vec<_> = thread::scope(|s| {
+        let handles: Vec<_> = (0..2)
+            .map(|id| {
+                let hosts = hosts.to_str().unwrap();
+                s.spawn(move || {
+                    let session =
+                        MpcSession::connect(hosts, id, 7, config(heartbeats[id])).unwrap();
+                    session.run(|| {
+                        if id == 1 {
+                            thread::sleep(Duration::from_millis(400));
+                        }
+                        catch_abort(|| Net::broadcast_bytes(&[id as u8]))
+                    })
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    fs::remove_file(&hosts).unwrap();
+    assert_eq!(
+        results[0],
+        Err(NetError::Unreachable {
+            peer: 1,
+            silent: Duration::from_millis(40)
+        })
+    );
+}
+
+#[test]
+fn callback_on_unreachable() {
+    let mut sessions = MpcSession::in_memory(2, config(Some(Duration::from_millis(10))));
+    let (tx, rx) = mpsc::channel();
+    let tx = std::sync::Mutex::new(tx);
+    sessions[0]
+        .run(move || Net::on_unreachable(move |e| tx.lock().unwrap().send(e.clone()).unwrap()));
+    // party 1 leaves while party 0 is busy
+    drop(sessions.pop());
+    match rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+        NetError::Disconnected { peer: 1, .. } => {}
+        e => panic!("expected party 1 to be unreachable, got {}", e),
+    }
+}
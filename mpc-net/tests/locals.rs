@@ -0,0 +1,30 @@
+//! Session locals belong to their session, not to its tag or to the process.
+use mpc_net::{MpcMultiNet as Net, MpcSession};
+
+fn push(x: u32) -> Vec<u32> {
+    Net::with_local(|xs: &mut Vec<u32>| {
+        xs.push(x);
+        xs.clone()
+    })
+}
+
+#[test]
+fn locals_last_as_long_as_their_session() {
+    let session = MpcSession::single_party(1);
+    assert_eq!(session.run(|| push(1)), vec![1]);
+    assert_eq!(session.run(|| push(2)), vec![1, 2]);
+    // each type has its own local
+    assert_eq!(session.run(|| Net::with_local(|x: &mut u64| *x)), 0);
+}
+
+#[test]
+fn sessions_do_not_share_locals() {
+    let a = MpcSession::single_party(2);
+    let b = MpcSession::single_party(2);
+    assert_eq!(a.run(|| push(1)), vec![1]);
+    assert_eq!(b.run(|| push(2)), vec![2]);
+    drop(a);
+    // a later session under the same tag starts afresh
+    let c = MpcSession::single_party(2);
+    assert_eq!(c.run(|| push(3)), vec![3]);
+}
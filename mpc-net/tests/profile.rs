@@ -0,0 +1,71 @@
+//! Profiling keeps the selected phases, with the spans and timers inside them.
+use ark_std::{end_timer, start_timer};
+use mpc_net::{mem, profile};
+
+use std::fs;
+use std::path::Path;
+use std::thread::sleep;
+use std::time::Duration;
+
+#[test]
+fn phases() {
+    let dir = std::env::temp_dir().join(format!("mpc-net-profile-{}", std::process::id()));
+    profile::start(profile::ProfileConfig {
+        dir: dir.clone(),
+        phases: vec!["kept".into()],
+        cpu: false,
+    });
+    {
+        let _p = mem::phase("skipped");
+        let _s = profile::span("outside");
+        sleep(Duration::from_millis(2));
+    }
+    {
+        let _p = mem::phase("kept");
+        let _s = profile::span("inner");
+        let t = start_timer!(|| "timer; with a semicolon");
+        sleep(Duration::from_millis(2));
+        end_timer!(t);
+    }
+
+    let stacks = finish(&dir);
+    let names: Vec<&str> = stacks.iter().map(|(s, _)| s.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["kept", "kept;inner", "kept;inner;timer, with a semicolon"]
+    );
+    assert!(stacks[2].1 >= 2000);
+
+    // reconfigured as the party runs
+    profile::set_phases(vec!["skipped".into()]);
+    {
+        let _p = mem::phase("skipped");
+        let _s = profile::span("now kept");
+    }
+    profile::set_enabled(false);
+    {
+        let _p = mem::phase("skipped");
+        let _s = profile::span("paused");
+    }
+    profile::set_enabled(true);
+    {
+        let _p = mem::phase("kept");
+    }
+    let names: Vec<String> = finish(&dir).into_iter().map(|(s, _)| s).collect();
+    assert_eq!(names, vec!["skipped", "skipped;now kept"]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// The stacks [profile::finish] writes to `dir`, with their time.
+fn finish(dir: &Path) -> Vec<(String, u64)> {
+    let written = profile::finish().unwrap();
+    assert_eq!(written[0], dir.join("party0.folded"));
+    fs::read_to_string(&written[0])
+        .unwrap()
+        .lines()
+        .map(|l| {
+            let (stack, micros) = l.rsplit_once(' ').unwrap();
+            (stack.to_owned(), micros.parse().unwrap())
+        })
+        .collect()
+}
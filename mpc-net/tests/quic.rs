@@ -0,0 +1,105 @@
+//! Sessions over QUIC exchange as they do over TCP, on a stream per phase, and pass aborts on.
+#![cfg(feature = "quic")]
+use mpc_net::{catch_abort, mem, MpcMultiNet as Net, MpcNet, MpcSession, NetConfig, NetError};
+
+use std::fs;
+use std::net::UdpSocket;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+fn config(batch: bool) -> NetConfig {
+    NetConfig {
+        io_timeout: Some(Duration::from_secs(20)),
+        batch,
+        ..NetConfig::default()
+    }
+}
+
+/// A hosts file of `n` free local QUIC addresses.
+fn hosts(n: usize, name: &str) -> PathBuf {
+    let sockets: Vec<_> = (0..n)
+        .map(|_| UdpSocket::bind("127.0.0.1:0").unwrap())
+        .collect();
+    let lines: Vec<_> = sockets
+        .iter()
+        .map(|s| format!("quic://{}", s.local_addr().unwrap()))
+        .collect();
+    let path = std::env::temp_dir().join(format!("mpc-net-{}-{}", name, std::process::id()));
+    fs::write(&path, lines.join("\n")).unwrap();
+    path
+}
+
+/// Run `f` as each of `n` parties, connected over QUIC.
+fn run_parties<T: Send>(
+    n: usize,
+    name: &str,
+    config: NetConfig,
+    f: impl Fn(usize) -> T + Send + Sync,
+) -> Vec<T> {
+    // the parties connect outside their sessions' pools, on the global one, where a party
+    // waiting to read must not hold up another's writes
+    let _ = rayon::ThreadPoolBuilder::new()
+        .num_threads(2 * n)
+        .build_global();
+    let hosts = hosts(n, name);
+    let results = thread::scope(|s| {
+        let handles: Vec<_> = (0..n)
+            .map(|id| {
+                let (hosts, config, f) = (hosts.to_str().unwrap(), config.clone(), &f);
+                s.spawn(move || {
+                    let session = MpcSession::connect(hosts, id, 11, config).unwrap();
+                    session.run(|| f(id))
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    fs::remove_file(&hosts).unwrap();
+    results
+}
+
+fn exchange(id: usize) -> (Vec<Vec<u8>>, Vec<u8>) {
+    let all = {
+        let _p = mem::phase("first");
+        Net::broadcast_bytes(&[id as u8; 100])
+    };
+    let _p = mem::phase("second");
+    let king = Net::send_bytes_to(0, &[id as u8 + 10]);
+    let mine = Net::recv_bytes_from(0, king.map(|v| v.into_iter().rev().collect()));
+    (all, mine)
+}
+
+#[test]
+fn exchanges_in_phases() {
+    for batch in [false, true] {
+        let results = run_parties(3, "quic-phases", config(batch), |id| {
+            Net::round(|| exchange(id))
+        });
+        for (id, (all, mine)) in results.into_iter().enumerate() {
+            assert_eq!(all, (0..3).map(|i| vec![i as u8; 100]).collect::<Vec<_>>());
+            assert_eq!(mine, vec![12 - id as u8]);
+        }
+    }
+}
+
+#[test]
+fn abort_reaches_every_party() {
+    let results = run_parties(3, "quic-abort", config(false), |id| {
+        catch_abort(|| {
+            if id == 1 {
+                Net::broadcast_abort("gave up");
+            }
+            Net::broadcast_bytes(&[id as u8])
+        })
+    });
+    for r in results {
+        assert_eq!(
+            r,
+            Err(NetError::Aborted {
+                peer: 1,
+                reason: "gave up".into()
+            })
+        );
+    }
+}
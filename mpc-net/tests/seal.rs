@@ -0,0 +1,112 @@
+//! Sealed connections carry messages as usual, and a record altered on the way is caught.
+use mpc_net::{catch_abort, MpcMultiNet as Net, MpcNet, MpcSession, NetConfig, NetError, SealKey};
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+fn config() -> NetConfig {
+    NetConfig {
+        io_timeout: Some(Duration::from_secs(20)),
+        seal: Some(SealKey([7; 32])),
+        ..NetConfig::default()
+    }
+}
+
+fn free_addr() -> SocketAddr {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+fn hosts_file(name: &str, addrs: &[SocketAddr]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("mpc-net-{}-{}", name, std::process::id()));
+    let lines: Vec<_> = addrs.iter().map(|a| a.to_string()).collect();
+    fs::write(&path, lines.join("\n")).unwrap();
+    path
+}
+
+/// Connect as each party, with its own hosts file, and broadcast its id.
+fn broadcast(hosts: &[PathBuf], batch: bool) -> Vec<Result<Vec<Vec<u8>>, NetError>> {
+    thread::scope(|s| {
+        let handles: Vec<_> = hosts
+            .iter()
+            .enumerate()
+            .map(|(id, hosts)| {
+                s.spawn(move || {
+                    let config = NetConfig { batch, ..config() };
+                    let session = MpcSession::connect(hosts.to_str().unwrap(), id, 5, config)?;
+                    session.run(|| catch_abort(|| Net::broadcast_bytes(&[id as u8; 100])))
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    })
+}
+
+#[test]
+fn sealed_exchanges() {
+    for batch in [false, true] {
+        let hosts = hosts_file("seal", &[free_addr(), free_addr(), free_addr()]);
+        let results = broadcast(&[hosts.clone(), hosts.clone(), hosts.clone()], batch);
+        fs::remove_file(&hosts).unwrap();
+        for r in results {
+            assert_eq!(r, Ok((0..3).map(|i| vec![i; 100]).collect()));
+        }
+    }
+}
+
+/// Forward a connection to `to`, flipping a bit of the `nth` byte sent that way.
+fn tampering_proxy(to: SocketAddr, nth: usize) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut from, _) = listener.accept().unwrap();
+        let mut to = TcpStream::connect(to).unwrap();
+        let (mut back_from, mut back_to) = (to.try_clone().unwrap(), from.try_clone().unwrap());
+        thread::spawn(move || {
+            let _ = io::copy(&mut back_from, &mut back_to);
+            let _ = back_to.shutdown(Shutdown::Both);
+        });
+        let mut buf = [0u8; 4096];
+        let mut seen = 0;
+        while let Ok(n @ 1..) = from.read(&mut buf) {
+            if (seen..seen + n).contains(&nth) {
+                buf[nth - seen] ^= 1;
+            }
+            seen += n;
+            if to.write_all(&buf[..n]).is_err() {
+                break;
+            }
+        }
+        let _ = to.shutdown(Shutdown::Both);
+    });
+    addr
+}
+
+#[test]
+fn tampered_record_is_caught() {
+    let addrs = [free_addr(), free_addr()];
+    // past party 0's hello (48 bytes) and the header of its first record (5)
+    let proxy = tampering_proxy(addrs[1], 48 + 5 + 1);
+    let hosts = [
+        hosts_file("seal-0", &[addrs[0], proxy]),
+        hosts_file("seal-1", &addrs),
+    ];
+    let results = broadcast(&hosts, false);
+    for h in &hosts {
+        fs::remove_file(h).unwrap();
+    }
+    match &results[1] {
+        Err(NetError::Tampered { peer: 0, .. }) => {}
+        r => panic!("expected party 1 to catch the tampering, got {:?}", r),
+    }
+    match &results[0] {
+        Err(NetError::Aborted { peer: 1, reason }) => assert!(reason.contains("tampered")),
+        r => panic!("expected party 1 to abort, got {:?}", r),
+    }
+}
@@ -0,0 +1,63 @@
+//! Shaped links deliver messages late, and no faster than their bandwidth.
+use mpc_net::{MpcMultiNet as Net, MpcNet, MpcSession, NetConfig, Shaping};
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Run `f` as each of two in-memory parties on links shaped by `shaping`, returning its results
+/// and how long the slower party took.
+fn run_shaped<T: Send>(shaping: Shaping, f: impl Fn() -> T + Send + Sync) -> (Vec<T>, Duration) {
+    let config = NetConfig {
+        shaping: Some(shaping),
+        ..NetConfig::default()
+    };
+    let sessions = MpcSession::in_memory(2, config);
+    let f = &f;
+    let start = Instant::now();
+    let results = thread::scope(|s| {
+        let handles: Vec<_> = sessions
+            .into_iter()
+            .map(|session| s.spawn(move || session.run(f)))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    (results, start.elapsed())
+}
+
+#[test]
+fn latency_delays_each_exchange() {
+    let latency = Duration::from_millis(50);
+    let shaping = Shaping {
+        latency,
+        bandwidth: None,
+    };
+    let (results, elapsed) = run_shaped(shaping, || {
+        (0..3)
+            .map(|i| Net::broadcast_bytes(&[Net::party_id() as u8 + i]))
+            .collect::<Vec<_>>()
+    });
+    for r in results {
+        assert_eq!(
+            r,
+            (0..3)
+                .map(|i| vec![vec![i], vec![i + 1]])
+                .collect::<Vec<_>>()
+        );
+    }
+    // party 1 waits for party 0's message, and party 0 then for its reply
+    assert!(elapsed >= 6 * latency, "took {:?}", elapsed);
+}
+
+#[test]
+fn bandwidth_paces_long_messages() {
+    let shaping = Shaping {
+        latency: Duration::ZERO,
+        bandwidth: Some(1 << 20),
+    };
+    let (results, elapsed) = run_shaped(shaping, || Net::broadcast_bytes(&[7; 1 << 17]));
+    for r in results {
+        assert_eq!(r, vec![vec![7; 1 << 17]; 2]);
+    }
+    // an eighth of a second for each party's message, one after the other
+    assert!(elapsed >= Duration::from_millis(250), "took {:?}", elapsed);
+}
@@ -0,0 +1,120 @@
+//! A recorded session's transcripts hold every exchange, and replay one party's part offline.
+use mpc_net::transcript::{self, Exchange};
+use mpc_net::{catch_abort, mem, MpcMultiNet as Net, MpcNet, MpcSession, NetConfig, NetError};
+
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+
+/// What a party gets from [exchange]: every party's broadcast, and the king's reply.
+type Outcome = (Vec<Vec<u8>>, Vec<u8>);
+
+fn exchange(id: usize, nudge: u8) -> Outcome {
+    let all = {
+        let _p = mem::phase("first");
+        Net::broadcast_bytes(&[id as u8; 10])
+    };
+    let _p = mem::phase("second");
+    let king = Net::send_bytes_to(0, &[id as u8 + nudge]);
+    let mine = Net::recv_bytes_from(0, king.map(|v| v.into_iter().rev().collect()));
+    (all, mine)
+}
+
+/// Run [exchange] as each of 3 parties in memory, recording to a fresh directory.
+fn record(name: &str) -> (PathBuf, Vec<Outcome>) {
+    let dir = std::env::temp_dir().join(format!("mpc-net-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let config = NetConfig {
+        record: Some(dir.clone()),
+        ..NetConfig::default()
+    };
+    let sessions = MpcSession::in_memory(3, config);
+    let results = thread::scope(|s| {
+        let handles: Vec<_> = sessions
+            .iter()
+            .enumerate()
+            .map(|(id, session)| s.spawn(move || session.run(|| exchange(id, 10))))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    // the transcripts are complete once the sessions end
+    drop(sessions);
+    (dir, results)
+}
+
+#[test]
+fn records_every_exchange() {
+    let (dir, _) = record("transcript-record");
+    let t = transcript::read(&transcript::path(&dir, 1)).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!((t.party, t.n_parties), (1, 3));
+    let summary: Vec<_> = t
+        .entries
+        .iter()
+        .map(|e| {
+            (
+                e.label.as_str(),
+                e.exchange,
+                e.sent.clone(),
+                e.recvd.clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                "first",
+                Exchange::Broadcast,
+                vec![vec![1; 10]],
+                (0..3).map(|i| vec![i; 10]).collect()
+            ),
+            (
+                "second",
+                Exchange::ToKing { king: 0 },
+                vec![vec![11]],
+                vec![]
+            ),
+            (
+                "second",
+                Exchange::FromKing { king: 0 },
+                vec![],
+                vec![vec![11]]
+            ),
+        ]
+    );
+    assert!(t.entries.windows(2).all(|w| w[0].at <= w[1].at));
+}
+
+#[test]
+fn replays_one_party() {
+    let (dir, results) = record("transcript-replay");
+    for (id, recorded) in results.iter().enumerate() {
+        let session = MpcSession::replay(&transcript::path(&dir, id), 21).unwrap();
+        let replayed = session.run(|| {
+            assert_eq!((Net::party_id(), Net::n_parties()), (id, 3));
+            exchange(id, 10)
+        });
+        assert_eq!(&replayed, recorded);
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn replay_catches_divergence() {
+    let (dir, _) = record("transcript-diverge");
+    let session = MpcSession::replay(&transcript::path(&dir, 2), 22).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    match session.run(|| catch_abort(|| exchange(2, 20))) {
+        Err(NetError::Diverged {
+            exchange: 1,
+            reason,
+        }) => {
+            assert!(reason.contains("differs"), "{}", reason)
+        }
+        r => panic!("expected the second exchange to diverge, got {:?}", r),
+    }
+    // and it stays aborted
+    let again = session.run(|| catch_abort(|| Net::broadcast_bytes(&[2; 10])));
+    assert!(matches!(again, Err(NetError::Diverged { exchange: 1, .. })));
+}
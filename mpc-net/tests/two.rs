@@ -0,0 +1,53 @@
+//! The two-party net gives up on a peer that doesn't connect or doesn't answer in time.
+use mpc_net::{catch_abort, MpcNet, MpcTwoNet as Net, NetConfig, NetError};
+
+use std::fs;
+use std::net::TcpListener;
+use std::time::{Duration, Instant};
+
+fn config() -> NetConfig {
+    NetConfig {
+        connect_timeout: Duration::from_millis(300),
+        io_timeout: Some(Duration::from_millis(300)),
+        ..NetConfig::default()
+    }
+}
+
+/// A hosts file for two parties, on ports that were free.
+fn hosts() -> (String, Vec<String>) {
+    let ports: Vec<_> = (0..2)
+        .map(|_| TcpListener::bind("127.0.0.1:0").unwrap())
+        .collect();
+    let addrs: Vec<_> = ports
+        .iter()
+        .map(|l| l.local_addr().unwrap().to_string())
+        .collect();
+    let path = std::env::temp_dir().join(format!("mpc-net-two-{}", std::process::id()));
+    fs::write(&path, addrs.join("\n")).unwrap();
+    (path.to_str().unwrap().to_owned(), addrs)
+}
+
+// one test, since the two-party net is a single channel per process
+#[test]
+fn times_out() {
+    Net::set_config(config());
+    let (path, addrs) = hosts();
+
+    // party 1 waits for party 0, which never connects
+    let start = Instant::now();
+    let r = catch_abort(|| Net::init_from_file(&path, 1));
+    assert_eq!(r, Err(NetError::Timeout { peer: 0 }));
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert!(!Net::is_init());
+
+    // party 0 reaches a party 1 that never says anything
+    let silent = TcpListener::bind(&addrs[1]).unwrap();
+    Net::init_from_file(&path, 0);
+    let (_peer, _) = silent.accept().unwrap();
+    let r = catch_abort(|| Net::broadcast_bytes(&[1, 2, 3]));
+    assert_eq!(r, Err(NetError::Timeout { peer: 1 }));
+    let r = catch_abort(|| Net::recv_bytes_from(1, None));
+    assert_eq!(r, Err(NetError::Timeout { peer: 1 }));
+    Net::deinit();
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,43 @@
+//! Triples from OTs, in triples per second, with both parties run in-process.
+use ark_bls12_377::Fr;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use mpc_offline::OtTripleGenerator;
+use mpc_test_utils::run_parties;
+
+use std::time::{Duration, Instant};
+
+/// The slowest party's time to set up, and then to generate `batch` triples `iters` times.
+fn time_generation(iters: u64, batch: usize) -> (Duration, Duration) {
+    run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        let start = Instant::now();
+        let mut generator = OtTripleGenerator::<Fr>::setup(rng);
+        let setup = start.elapsed();
+        let start = Instant::now();
+        for _ in 0..iters {
+            criterion::black_box(generator.triples(batch, rng));
+        }
+        (setup, start.elapsed())
+    })
+    .into_iter()
+    .max_by_key(|(_, triples)| *triples)
+    .unwrap()
+}
+
+fn bench_triples(c: &mut Criterion) {
+    let mut group = c.benchmark_group("offline/ot-triples");
+    group.sample_size(10);
+    group.bench_function("setup", |b| {
+        b.iter_custom(|iters| (0..iters).map(|_| time_generation(0, 0).0).sum())
+    });
+    for &batch in &[128usize, 1024, 4096] {
+        group.throughput(Throughput::Elements(batch as u64));
+        group.bench_with_input(BenchmarkId::new("batch", batch), &batch, |b, &batch| {
+            b.iter_custom(|iters| time_generation(iters, batch).1)
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_triples);
+criterion_main!(benches);
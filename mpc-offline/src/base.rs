@@ -0,0 +1,83 @@
+//! Base oblivious transfers, from public-key operations.
+//!
+//! This is the "simplest OT" of Chou and Orlandi, over the Edwards curve on BLS12-377's scalar
+//! field. The sender publishes `A = aG`; for each transfer, the receiver publishes `B = bG`, or
+//! `B = bG + A` to choose the second key, and keeps `H(bA)`. The sender's keys are `H(aB)` and
+//! `H(a(B - A))`, of which the receiver knows exactly the one it chose, and `B` does not say which.
+use ark_ec::ProjectiveCurve;
+use ark_ed_on_bls12_377::{EdwardsProjective as G, Fr as Scalar};
+use ark_ff::{PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use blake2::{Blake2s, Digest};
+use rand::Rng;
+use rayon::prelude::*;
+
+/// A key that one OT transfers: a seed for [iknp](crate::iknp)'s generators.
+pub type Key = [u8; 32];
+
+/// Run an OT for each of `choices` in each direction with the other party, who must make as many
+/// choices. As the sender, this party gets both keys of each; as the receiver, the key it chose of
+/// the other's.
+///
+/// `rng` must be this party's alone.
+pub fn transfer<R: Rng>(choices: &[bool], rng: &mut R) -> (Vec<(Key, Key)>, Vec<Key>) {
+    let g = G::prime_subgroup_generator();
+    let a = Scalar::rand(rng);
+    let big_a = g.mul(a.into_repr());
+    let their_a = read_points(&crate::exchange(&write_points(&[big_a])))[0];
+
+    let bs: Vec<Scalar> = choices.iter().map(|_| Scalar::rand(rng)).collect();
+    let big_bs: Vec<G> = bs
+        .par_iter()
+        .zip(choices)
+        .map(|(b, choice)| {
+            let big_b = g.mul(b.into_repr());
+            if *choice {
+                big_b + their_a
+            } else {
+                big_b
+            }
+        })
+        .collect();
+    let their_bs = read_points(&crate::exchange(&write_points(&big_bs)));
+
+    let pairs = their_bs
+        .par_iter()
+        .enumerate()
+        .map(|(i, big_b)| {
+            (
+                key(i, big_b.mul(a.into_repr())),
+                key(i, (*big_b - big_a).mul(a.into_repr())),
+            )
+        })
+        .collect();
+    let chosen = bs
+        .par_iter()
+        .enumerate()
+        .map(|(i, b)| key(i, their_a.mul(b.into_repr())))
+        .collect();
+    (pairs, chosen)
+}
+
+/// The key of the `i`th transfer, from its shared point.
+fn key(i: usize, point: G) -> Key {
+    let mut bytes = (i as u64).to_le_bytes().to_vec();
+    point.into_affine().serialize(&mut bytes).unwrap();
+    let mut key = Key::default();
+    key.copy_from_slice(&Blake2s::digest(&bytes));
+    key
+}
+
+fn write_points(points: &[G]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    G::batch_normalization_into_affine(points)
+        .serialize(&mut bytes)
+        .unwrap();
+    bytes
+}
+
+fn read_points(bytes: &[u8]) -> Vec<G> {
+    let points: Vec<<G as ProjectiveCurve>::Affine> =
+        CanonicalDeserialize::deserialize(bytes).expect("curve points from the other party");
+    points.into_iter().map(Into::into).collect()
+}
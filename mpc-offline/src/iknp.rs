@@ -0,0 +1,168 @@
+//! Oblivious transfer extension, after Ishai, Kilian, Nissim and Petrank.
+//!
+//! [KAPPA] base OTs, with the roles reversed, set up an [ExtSender] and an [ExtReceiver], which
+//! then make any number of correlated OTs from a PRG and one message. The sender holds a random
+//! `delta`. For the `i`th OT, the receiver, choosing bit `r_i`, gets a 128-bit `t_i`, and the
+//! sender gets `q_i = t_i ^ (r_i * delta)`; hashing `q_i` and `q_i ^ delta` makes two pads, of
+//! which the receiver knows just the one it chose.
+//!
+//! The receiver's message is [KAPPA] bits per OT. The PRGs run on from one extension to the next,
+//! so the parties must extend in step.
+use rand::Rng;
+use rand_chacha::rand_core::{RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use rayon::prelude::*;
+
+use std::convert::TryInto;
+
+use crate::base::{self, Key};
+
+/// The security parameter, and the width of the correlations.
+pub const KAPPA: usize = 128;
+
+/// Set up both ends of OT extension with the other party: this party's sender, for OTs to it, and
+/// its receiver, for OTs from it.
+///
+/// `rng` must be this party's alone.
+pub fn setup<R: Rng>(rng: &mut R) -> (ExtSender, ExtReceiver) {
+    let delta: u128 = rng.gen();
+    let choices: Vec<bool> = (0..KAPPA).map(|j| delta >> j & 1 == 1).collect();
+    let (pairs, chosen) = base::transfer(&choices, rng);
+    (ExtSender::new(delta, &chosen), ExtReceiver::new(&pairs))
+}
+
+/// The sending end, which learns both pads of each OT.
+pub struct ExtSender {
+    delta: u128,
+    /// The generator of each column, from the key that bit of `delta` chose.
+    columns: Vec<ChaCha12Rng>,
+}
+
+impl ExtSender {
+    /// From the base OTs in which this party, as the receiver, chose `keys[j]` by bit `j` of
+    /// `delta`.
+    pub fn new(delta: u128, keys: &[Key]) -> Self {
+        assert_eq!(keys.len(), KAPPA);
+        Self {
+            delta,
+            columns: keys.iter().map(|k| ChaCha12Rng::from_seed(*k)).collect(),
+        }
+    }
+
+    pub fn delta(&self) -> u128 {
+        self.delta
+    }
+
+    /// Finish `m` OTs, from the receiver's `message`: the `q_i`.
+    pub fn extend(&mut self, m: usize, message: &[u8]) -> Vec<u128> {
+        assert_eq!(m % KAPPA, 0, "OTs are extended in blocks of {}", KAPPA);
+        let bytes = m / 8;
+        assert_eq!(message.len(), KAPPA * bytes, "an OT extension message of the wrong length");
+        let delta = self.delta;
+        let qs: Vec<Vec<u8>> = self
+            .columns
+            .par_iter_mut()
+            .zip(message.par_chunks(bytes))
+            .enumerate()
+            .map(|(j, (prg, u))| {
+                let mut q = vec![0u8; bytes];
+                prg.fill_bytes(&mut q);
+                if delta >> j & 1 == 1 {
+                    q.iter_mut().zip(u).for_each(|(q, u)| *q ^= u);
+                }
+                q
+            })
+            .collect();
+        transpose(&qs)
+    }
+}
+
+/// The receiving end, which learns the pad it chose of each OT.
+pub struct ExtReceiver {
+    /// The generators of each column, from both keys of a base OT.
+    columns: Vec<(ChaCha12Rng, ChaCha12Rng)>,
+}
+
+impl ExtReceiver {
+    /// From the base OTs in which this party, as the sender, had `keys`.
+    pub fn new(keys: &[(Key, Key)]) -> Self {
+        assert_eq!(keys.len(), KAPPA);
+        Self {
+            columns: keys
+                .iter()
+                .map(|(k0, k1)| (ChaCha12Rng::from_seed(*k0), ChaCha12Rng::from_seed(*k1)))
+                .collect(),
+        }
+    }
+
+    /// Start an OT for each of `choices`, a multiple of [KAPPA] of them: the message for the
+    /// sender, and the `t_i`.
+    pub fn extend(&mut self, choices: &[bool]) -> (Vec<u8>, Vec<u128>) {
+        assert_eq!(choices.len() % KAPPA, 0, "OTs are extended in blocks of {}", KAPPA);
+        let bytes = choices.len() / 8;
+        let rs = pack(choices);
+        let (ts, us): (Vec<Vec<u8>>, Vec<Vec<u8>>) = self
+            .columns
+            .par_iter_mut()
+            .map(|(prg0, prg1)| {
+                let mut t = vec![0u8; bytes];
+                let mut u = vec![0u8; bytes];
+                prg0.fill_bytes(&mut t);
+                prg1.fill_bytes(&mut u);
+                for ((u, t), r) in u.iter_mut().zip(&t).zip(&rs) {
+                    *u ^= t ^ r;
+                }
+                (t, u)
+            })
+            .unzip();
+        (us.concat(), transpose(&ts))
+    }
+}
+
+/// Bit `i` of `bits` at bit `i % 8` of byte `i / 8`.
+fn pack(bits: &[bool]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|byte| {
+            byte.iter()
+                .enumerate()
+                .fold(0u8, |acc, (k, bit)| acc | (*bit as u8) << k)
+        })
+        .collect()
+}
+
+/// The rows of the bit matrix whose [KAPPA] columns are packed in `columns`: bit `j` of row `i`
+/// is bit `i` of column `j`.
+fn transpose(columns: &[Vec<u8>]) -> Vec<u128> {
+    assert_eq!(columns.len(), KAPPA);
+    let mut rows = vec![0u128; columns[0].len() * 8];
+    rows.par_chunks_mut(KAPPA)
+        .enumerate()
+        .for_each(|(block, rows)| {
+            let mut square = [0u128; KAPPA];
+            for (word, column) in square.iter_mut().zip(columns) {
+                let bytes = &column[16 * block..16 * (block + 1)];
+                *word = u128::from_le_bytes(bytes.try_into().unwrap());
+            }
+            transpose_square(&mut square);
+            rows.copy_from_slice(&square);
+        });
+    rows
+}
+
+/// Transpose a 128 x 128 bit matrix in place, by swapping ever smaller off-diagonal blocks.
+fn transpose_square(a: &mut [u128; KAPPA]) {
+    let mut width = KAPPA / 2;
+    let mut mask: u128 = u64::MAX as u128;
+    while width != 0 {
+        let mut k = 0;
+        while k < KAPPA {
+            let t = ((a[k] >> width) ^ a[k + width]) & mask;
+            a[k] ^= t << width;
+            a[k + width] ^= t;
+            k = (k + width + 1) & !width;
+        }
+        width /= 2;
+        mask ^= mask << width;
+    }
+}
+
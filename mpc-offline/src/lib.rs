@@ -0,0 +1,26 @@
+//! An offline phase for two parties, without a trusted dealer.
+//!
+//! The online protocols multiply shared values with Beaver triples, which the
+//! [DummyFieldTripleSource](mpc_algebra::DummyFieldTripleSource) makes up in place of a dealer.
+//! Here the two parties make them together, from oblivious transfers:
+//!
+//! - [base] runs 128 public-key OTs in each direction, once;
+//! - [iknp] extends those to as many correlated OTs as needed, by hashing alone;
+//! - [triples] multiplies each party's share of `x` by the other's share of `y` with Gilboa's
+//!   protocol, one OT per bit of the field, and serves the results as a
+//!   [BeaverSource](mpc_algebra::BeaverSource) of additive shares.
+//!
+//! All of it is secure against semi-honest parties only: a party that deviates can make the
+//! triples wrong, undetected.
+pub mod base;
+pub mod iknp;
+pub mod triples;
+pub use triples::{OtTripleGenerator, OtTripleSource};
+
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+
+/// Send `bytes` to the other party, and receive what it sent, which must be as long.
+pub(crate) fn exchange(bytes: &[u8]) -> Vec<u8> {
+    assert_eq!(Net::n_parties(), 2, "the OT offline phase is for two parties");
+    Net::broadcast_bytes(bytes).swap_remove(1 - Net::party_id())
+}
@@ -0,0 +1,286 @@
+//! Beaver triples for two parties, from Gilboa's OT-based multiplication.
+//!
+//! Each party picks its shares of `x` and `y`, and
+//! `xy = x0 y0 + x1 y1 + x0 y1 + x1 y0`. Each party computes its own product. Each cross term,
+//! say `x0 y1`, is shared by one correlated OT per bit of the field. For bit `i` of `y1`, party 1
+//! chooses, and party 0 sends the difference of the two pads, less `x0 2^i`. Party 1 gets the
+//! first pad, plus `x0 2^i` if the bit is set. Summed over the bits, party 1 holds the sum of the
+//! first pads plus `x0 y1`, and party 0 holds minus the sum of the first pads.
+//!
+//! A triple costs `MODULUS_BITS` OTs each way, and each OT sends 16 bytes to extend it and a field
+//! element to correct it: about 12 KB each way for a 253-bit field. A batch takes two rounds,
+//! whatever its size.
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use blake2::{Blake2b, Digest};
+use derivative::Derivative;
+use log::debug;
+use mpc_algebra::{cost, seed, AdditiveFieldShare, BeaverSource, FieldShare, Reveal};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use rand::rngs::StdRng;
+use rand::Rng;
+use rayon::prelude::*;
+
+use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use crate::iknp::{self, ExtReceiver, ExtSender, KAPPA};
+
+/// Generates triples with the other party.
+pub struct OtTripleGenerator<F: PrimeField> {
+    sender: ExtSender,
+    receiver: ExtReceiver,
+    /// OTs extended so far in each direction, which tweak the hash of each.
+    ots: u64,
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> OtTripleGenerator<F> {
+    /// Run the base OTs with the other party.
+    ///
+    /// `rng` must be this party's alone.
+    pub fn setup<R: Rng>(rng: &mut R) -> Self {
+        let (sender, receiver) = iknp::setup(rng);
+        Self {
+            sender,
+            receiver,
+            ots: 0,
+            _field: PhantomData,
+        }
+    }
+
+    /// This party's shares of `n` triples: of random `x` and `y`, and of `x * y`.
+    ///
+    /// `rng` must be this party's alone.
+    pub fn triples<R: Rng>(
+        &mut self,
+        n: usize,
+        rng: &mut R,
+    ) -> (
+        Vec<AdditiveFieldShare<F>>,
+        Vec<AdditiveFieldShare<F>>,
+        Vec<AdditiveFieldShare<F>>,
+    ) {
+        let bits = F::Params::MODULUS_BITS as usize;
+        let m = (n * bits + KAPPA - 1) / KAPPA * KAPPA;
+        let xs: Vec<F> = (0..n).map(|_| F::rand(rng)).collect();
+        let ys: Vec<F> = (0..n).map(|_| F::rand(rng)).collect();
+
+        // receive x' y, choosing by the bits of y, and send x y'
+        let mut choices: Vec<bool> = ys
+            .iter()
+            .flat_map(|y| y.into_repr().to_bits_le().into_iter().take(bits))
+            .collect();
+        choices.resize(m, false);
+        let (message, ts) = self.receiver.extend(&choices);
+        let qs = self.sender.extend(m, &crate::exchange(&message));
+        let first = self.ots;
+        self.ots += m as u64;
+
+        let me = Net::party_id() as u8;
+        let delta = self.sender.delta();
+        let shift = F::from(2u64).pow(&[F::Params::CAPACITY as u64]);
+        let powers: Vec<F> = (0..bits)
+            .scan(F::one(), |power, _| {
+                let this = *power;
+                power.double_in_place();
+                Some(this)
+            })
+            .collect();
+        let (sent, differences): (Vec<F>, Vec<Vec<F>>) = qs[..n * bits]
+            .par_chunks(bits)
+            .zip(&xs)
+            .enumerate()
+            .map(|(k, (qs, x))| {
+                let mut share = F::zero();
+                let mut differences = Vec::with_capacity(bits);
+                for (i, (q, power)) in qs.iter().zip(&powers).enumerate() {
+                    let ot = first + (k * bits + i) as u64;
+                    let pad0 = pad(me, ot, *q, shift);
+                    let pad1 = pad(me, ot, q ^ delta, shift);
+                    share -= pad0;
+                    differences.push(pad1 - pad0 - *x * power);
+                }
+                (share, differences)
+            })
+            .unzip();
+        let mut bytes = Vec::new();
+        differences.concat().serialize(&mut bytes).unwrap();
+        let theirs = Vec::<F>::deserialize(&crate::exchange(&bytes)[..])
+            .expect("OT corrections from the other party");
+
+        let received: Vec<F> = ts[..n * bits]
+            .par_chunks(bits)
+            .zip(theirs.par_chunks(bits))
+            .zip(choices.par_chunks(bits))
+            .enumerate()
+            .map(|(k, ((ts, differences), choices))| {
+                let mut share = F::zero();
+                for (i, ((t, d), c)) in ts.iter().zip(differences).zip(choices).enumerate() {
+                    let ot = first + (k * bits + i) as u64;
+                    share += pad::<F>(1 - me, ot, *t, shift);
+                    if *c {
+                        share -= d;
+                    }
+                }
+                share
+            })
+            .collect();
+
+        let zs = (0..n)
+            .map(|k| AdditiveFieldShare::from_add_shared(xs[k] * ys[k] + sent[k] + received[k]))
+            .collect();
+        debug!("Generated {} triples from {} OTs each way", n, m);
+        (
+            xs.into_iter().map(AdditiveFieldShare::from_add_shared).collect(),
+            ys.into_iter().map(AdditiveFieldShare::from_add_shared).collect(),
+            zs,
+        )
+    }
+}
+
+/// The pad of OT number `ot` from `sender` to the other party, for the correlation `block`.
+///
+/// The hash is read as two integers below `2^CAPACITY`, so that each converts to the field
+/// without reduction, and combined as `lo + hi * shift`, with `shift = 2^CAPACITY`: nearly
+/// uniform, and far cheaper than reducing a wide integer.
+fn pad<F: PrimeField>(sender: u8, ot: u64, block: u128, shift: F) -> F {
+    let limbs = F::BigInt::NUM_LIMBS;
+    let mut bytes = Vec::with_capacity(16 * limbs + 64);
+    let mut counter = 0u8;
+    while bytes.len() < 16 * limbs {
+        let mut h = Blake2b::new();
+        h.update(&[sender, counter]);
+        h.update(&ot.to_le_bytes());
+        h.update(&block.to_le_bytes());
+        bytes.extend_from_slice(&h.finalize());
+        counter += 1;
+    }
+    let (lo, hi) = bytes.split_at(8 * limbs);
+    below_capacity::<F>(lo) + below_capacity::<F>(hi) * shift
+}
+
+/// The field element whose integer is the low `CAPACITY` bits of `bytes`, little-endian.
+fn below_capacity<F: PrimeField>(bytes: &[u8]) -> F {
+    let capacity = F::Params::CAPACITY as usize;
+    let mut int = F::BigInt::default();
+    for (i, (limb, bytes)) in int.as_mut().iter_mut().zip(bytes.chunks(8)).enumerate() {
+        *limb = u64::from_le_bytes(bytes.try_into().unwrap());
+        let keep = capacity.saturating_sub(64 * i);
+        if keep < 64 {
+            *limb &= (1u64 << keep) - 1;
+        }
+    }
+    F::from_repr(int).unwrap()
+}
+
+/// A [BeaverSource] of additive shares, which generates triples with the other party as they are
+/// drawn, at least a batch at a time.
+///
+/// Clones draw from the same generator. Both parties must draw the same numbers of triples in the
+/// same order, as from any source.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct OtTripleSource<F: PrimeField> {
+    inner: Arc<Mutex<Buffered<F>>>,
+}
+
+struct Buffered<F: PrimeField> {
+    generator: OtTripleGenerator<F>,
+    rng: StdRng,
+    batch: usize,
+    triples: VecDeque<(AdditiveFieldShare<F>, AdditiveFieldShare<F>, AdditiveFieldShare<F>)>,
+}
+
+impl<F: PrimeField> OtTripleSource<F> {
+    /// Run the base OTs with the other party, and generate `batch` triples at a time. This party's
+    /// randomness comes from [seed::rng].
+    pub fn new(batch: usize) -> Self {
+        let mut rng = seed::rng(b"offline triples");
+        let generator = OtTripleGenerator::setup(&mut rng);
+        Self {
+            inner: Arc::new(Mutex::new(Buffered {
+                generator,
+                rng,
+                batch: batch.max(1),
+                triples: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Triples generated, but not yet drawn.
+    pub fn buffered(&self) -> usize {
+        self.inner.lock().unwrap().triples.len()
+    }
+}
+
+impl<F: PrimeField> BeaverSource<AdditiveFieldShare<F>, AdditiveFieldShare<F>, AdditiveFieldShare<F>>
+    for OtTripleSource<F>
+{
+    fn triple(&mut self) -> (AdditiveFieldShare<F>, AdditiveFieldShare<F>, AdditiveFieldShare<F>) {
+        let (mut xs, mut ys, mut zs) = self.triples(1);
+        (xs.pop().unwrap(), ys.pop().unwrap(), zs.pop().unwrap())
+    }
+
+    fn triples(
+        &mut self,
+        n: usize,
+    ) -> (
+        Vec<AdditiveFieldShare<F>>,
+        Vec<AdditiveFieldShare<F>>,
+        Vec<AdditiveFieldShare<F>>,
+    ) {
+        let mut inner = self.inner.lock().unwrap();
+        let Buffered {
+            generator,
+            rng,
+            batch,
+            triples,
+        } = &mut *inner;
+        if triples.len() < n {
+            let (xs, ys, zs) = generator.triples((n - triples.len()).max(*batch), rng);
+            triples.extend(xs.into_iter().zip(ys).zip(zs).map(|((x, y), z)| (x, y, z)));
+        }
+        cost::drew_triples(n as u64);
+        let mut xs = Vec::with_capacity(n);
+        let mut ys = Vec::with_capacity(n);
+        let mut zs = Vec::with_capacity(n);
+        for (x, y, z) in triples.drain(..n) {
+            xs.push(x);
+            ys.push(y);
+            zs.push(z);
+        }
+        (xs, ys, zs)
+    }
+
+    fn triples_into(
+        &mut self,
+        n: usize,
+        xs: &mut Vec<AdditiveFieldShare<F>>,
+        ys: &mut Vec<AdditiveFieldShare<F>>,
+        zs: &mut Vec<AdditiveFieldShare<F>>,
+    ) {
+        let (x, y, z) = self.triples(n);
+        *xs = x;
+        *ys = y;
+        *zs = z;
+    }
+
+    fn inv_pair(&mut self) -> (AdditiveFieldShare<F>, AdditiveFieldShare<F>) {
+        let (mut rs, mut r_invs) = self.inv_pairs(1);
+        (rs.pop().unwrap(), r_invs.pop().unwrap())
+    }
+
+    /// From triples: with `xy` opened, `y / xy` is a sharing of `1 / x`.
+    fn inv_pairs(&mut self, n: usize) -> (Vec<AdditiveFieldShare<F>>, Vec<AdditiveFieldShare<F>>) {
+        let (xs, ys, zs) = self.triples(n);
+        let r_invs = ys
+            .into_iter()
+            .zip(AdditiveFieldShare::batch_open(zs))
+            .map(|(mut y, z)| *y.scale(&z.inverse().expect("a zero product of random elements")))
+            .collect();
+        (xs, r_invs)
+    }
+}
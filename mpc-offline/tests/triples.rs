@@ -0,0 +1,109 @@
+//! OTs and triples, with both parties run in-process.
+use ark_bls12_377::Fr;
+use ark_ff::{Field, UniformRand};
+use mpc_algebra::{cost, AdditiveFieldShare, FieldShare, Reveal};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_offline::{base, iknp, OtTripleGenerator, OtTripleSource};
+use mpc_test_utils::run_parties;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use std::convert::TryInto;
+
+/// An rng of this party's alone.
+fn party_rng() -> StdRng {
+    StdRng::seed_from_u64(Net::party_id() as u64)
+}
+
+/// The other party's `bytes`.
+fn theirs(bytes: &[u8]) -> Vec<u8> {
+    Net::broadcast_bytes(bytes).swap_remove(1 - Net::party_id())
+}
+
+#[test]
+fn base_ots_transfer_the_chosen_key() {
+    run_parties(2, || {
+        let rng = &mut party_rng();
+        let choices: Vec<bool> = (0..8).map(|_| rng.gen()).collect();
+        let (pairs, chosen) = base::transfer(&choices, rng);
+        let mine: Vec<u8> = pairs.iter().flat_map(|(k0, k1)| k0.iter().chain(k1)).copied().collect();
+        let their_pairs = theirs(&mine);
+        for (i, (choice, key)) in choices.iter().zip(&chosen).enumerate() {
+            let pair = &their_pairs[64 * i..64 * (i + 1)];
+            let (k0, k1) = pair.split_at(32);
+            let (want, other) = if *choice { (k1, k0) } else { (k0, k1) };
+            assert_eq!(&key[..], want);
+            assert_ne!(&key[..], other);
+        }
+    });
+}
+
+#[test]
+fn extended_ots_are_correlated_by_delta() {
+    run_parties(2, || {
+        let rng = &mut party_rng();
+        let (mut sender, mut receiver) = iknp::setup(rng);
+        // twice, as the generators must run on in step
+        for &m in &[256, 128] {
+            let choices: Vec<bool> = (0..m).map(|_| rng.gen()).collect();
+            let (message, ts) = receiver.extend(&choices);
+            let qs = sender.extend(m, &theirs(&message));
+            let mut mine = sender.delta().to_le_bytes().to_vec();
+            mine.extend(qs.iter().flat_map(|q| q.to_le_bytes()));
+            let their = theirs(&mine);
+            let words: Vec<u128> = their
+                .chunks(16)
+                .map(|w| u128::from_le_bytes(w.try_into().unwrap()))
+                .collect();
+            let (delta, their_qs) = (words[0], &words[1..]);
+            for ((t, q), choice) in ts.iter().zip(their_qs).zip(&choices) {
+                assert_eq!(t ^ q, if *choice { delta } else { 0 });
+            }
+        }
+    });
+}
+
+#[test]
+fn generated_triples_multiply() {
+    run_parties(2, || {
+        let rng = &mut party_rng();
+        let mut generator = OtTripleGenerator::<Fr>::setup(rng);
+        for &n in &[3, 1] {
+            let (xs, ys, zs) = generator.triples(n, rng);
+            let xs = AdditiveFieldShare::batch_open(xs);
+            let ys = AdditiveFieldShare::batch_open(ys);
+            let zs = AdditiveFieldShare::batch_open(zs);
+            for ((x, y), z) in xs.iter().zip(&ys).zip(&zs) {
+                assert_eq!(*x * y, *z);
+            }
+            assert_ne!(xs[0], ys[0]);
+        }
+    });
+}
+
+#[test]
+fn source_multiplies_and_inverts() {
+    run_parties(2, || {
+        // the values are drawn alike, before the king draws more to share them
+        let rng = &mut ark_std::test_rng();
+        let mut source = OtTripleSource::<Fr>::new(8);
+        let before = cost::costs();
+        let a: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+        let b: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+        let shared_a = AdditiveFieldShare::king_share_batch(a.clone(), rng);
+        let shared_b = AdditiveFieldShare::king_share_batch(b.clone(), rng);
+        let products = AdditiveFieldShare::batch_mul(shared_a.clone(), shared_b, &mut source);
+        for ((p, a), b) in AdditiveFieldShare::batch_open(products).iter().zip(&a).zip(&b) {
+            assert_eq!(*p, *a * b);
+        }
+        assert_eq!(source.buffered(), 5);
+
+        let inverses = AdditiveFieldShare::batch_inv(shared_a, &mut source.clone());
+        for (inv, a) in AdditiveFieldShare::batch_open(inverses).iter().zip(&a) {
+            assert_eq!(*inv, a.inverse().unwrap());
+        }
+        // inverting 3 takes a triple for each pair, and one for each product
+        assert_eq!(source.buffered(), 8 + 5 - 6);
+        assert_eq!(cost::costs().since(before).triples, 9);
+    });
+}
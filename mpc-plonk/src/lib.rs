@@ -25,7 +25,7 @@ use ark_poly_commit::{LabeledCommitment, LabeledPolynomial, PCRandomness, Polyno
 use ark_poly::{
     domain::EvaluationDomain,
     univariate::{DenseOrSparsePolynomial, DensePolynomial},
-    Polynomial, UVPolynomial,
+    Evaluations, Polynomial, UVPolynomial,
 };
 
 use ark_std::{end_timer, rand::RngCore, start_timer};
@@ -36,6 +36,7 @@ use std::iter::once;
 use std::marker::PhantomData;
 use thiserror::Error;
 
+use mpc_algebra::transcript::{speculate, Speculation};
 use mpc_trait::MpcWire;
 pub use util::FiatShamirRng;
 
@@ -196,18 +197,22 @@ where
     }
 
     /// Prove that p(X) = p(w(X)) on the domain.
+    ///
+    /// `w_evals` are w's evaluations over the domain, speculated before the rounds that fix the
+    /// challenges.
     fn prove_wiring<D: EvaluationDomain<F>>(
         &self,
         p: &LabeledPolynomial<F, DensePolynomial<F>>,
         p_cmt: &LabeledCommitment<PC::Commitment>,
         p_rand: &PC::Randomness,
         dom: D,
+        w_evals: Speculation<Evaluations<F, D>>,
     ) -> WiringProof<PC::Commitment, (F, PC::Proof)> {
         let timer = start_timer!(|| "prove_wiring");
         let y = self.fs_rng.borrow_mut().gen::<F>();
         let z = self.fs_rng.borrow_mut().gen::<F>();
         let p_evals = p.evaluate_over_domain_by_ref(dom);
-        let w_evals = self.pk.w.evaluate_over_domain_by_ref(dom);
+        let w_evals = w_evals.join();
         let yx_z_evals =
             DensePolynomial::from_coefficients_vec(vec![z, y]).evaluate_over_domain_by_ref(dom);
         let num_evals = &(&p_evals + &(&w_evals * &y)) + &z;
@@ -408,6 +413,12 @@ where
         assert!(circ.p.is_some());
         let n_gates = circ.domains.gates.size();
         let n_wires = n_gates * 3;
+        // w is public and independent of the wiring challenges, so its FFT overlaps the rounds
+        // up to them.
+        let w_evals = {
+            let (w, dom) = (self.pk.w.polynomial().clone(), circ.domains.wires);
+            speculate(move || w.evaluate_over_domain(dom))
+        };
         let (p_cmt, p, p_rand) = self
             .commit(
                 "p".to_owned(),
@@ -418,7 +429,7 @@ where
             .unwrap();
         let public = self.prove_public(&p, &p_cmt, &p_rand, circ);
         let gates = self.prove_gates(&p, &p_cmt, &p_rand, circ);
-        let wiring = self.prove_wiring(&p, &p_cmt, &p_rand, circ.domains.wires);
+        let wiring = self.prove_wiring(&p, &p_cmt, &p_rand, circ.domains.wires, w_evals);
         Proof {
             p_cmt: p_cmt.commitment,
             wiring,
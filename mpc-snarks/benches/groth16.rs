@@ -0,0 +1,137 @@
+//! End-to-end Groth16 proofs, plain and collaborative, with every party run in-process.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::{Field, UniformRand};
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError},
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use mpc_algebra::{AdditivePairingShare, GszPairingShare, MpcField, PairingShare, Reveal};
+use mpc_snarks::groth::prover::create_random_proof;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+use std::time::{Duration, Instant};
+
+type E = Bls12_377;
+
+const STEPS: [usize; 2] = [1 << 6, 1 << 8];
+
+/// Knowledge of `x` with `x^(2^steps) = y`: one multiplication per constraint.
+#[derive(Clone)]
+struct Squarings<F: Field> {
+    x: Option<F>,
+    steps: usize,
+}
+
+impl<F: Field> ConstraintSynthesizer<F> for Squarings<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let mut x = self.x;
+        let mut v = cs.new_witness_variable(|| x.ok_or(SynthesisError::AssignmentMissing))?;
+        for i in 0..self.steps {
+            x = x.map(|x| x.square());
+            let next = if i + 1 == self.steps {
+                cs.new_input_variable(|| x.ok_or(SynthesisError::AssignmentMissing))?
+            } else {
+                cs.new_witness_variable(|| x.ok_or(SynthesisError::AssignmentMissing))?
+            };
+            cs.enforce_constraint(lc!() + v, lc!() + v, lc!() + next)?;
+            v = next;
+        }
+        Ok(())
+    }
+}
+
+fn bench_plain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("groth16/plain");
+    group.sample_size(10);
+    for &steps in &STEPS {
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(Squarings { x: None, steps }, rng).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(steps), &steps, |b, &steps| {
+            b.iter(|| {
+                let circuit = Squarings {
+                    x: Some(Fr::rand(rng)),
+                    steps,
+                };
+                create_random_proof::<E, _, _>(circuit, &pk, rng, ProveOptions::default()).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// What a collaborative prover precomputes for its key, before its proofs.
+#[derive(Clone, Copy)]
+enum Precomputed {
+    Nothing,
+    /// Fixed-base tables.
+    Tables,
+    /// A [ProverContext]: tables, and the witness map's domain.
+    Context,
+}
+
+/// The slowest party's time to make `iters` collaborative proofs, with `precomputed` built
+/// beforehand. Setup and precomputation are not timed.
+fn time_proofs<S: PairingShare<E>>(
+    n: usize,
+    steps: usize,
+    iters: u64,
+    precomputed: Precomputed,
+) -> Duration {
+    run_parties(n, || {
+        S::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(Squarings { x: None, steps }, rng).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let (tables, context) = match precomputed {
+            Precomputed::Nothing => (None, None),
+            Precomputed::Tables => (Some(proving_key_tables(&mpc_pk, None)), None),
+            Precomputed::Context => (None, Some(prover_context(mpc_pk.clone(), None).unwrap())),
+        };
+        let x = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let start = Instant::now();
+        for _ in 0..iters {
+            let circuit = Squarings { x: Some(x), steps };
+            let options = match (&tables, &context) {
+                (Some(t), _) => ProveOptions::default().tables(t),
+                (_, Some(c)) => ProveOptions::default().context(c),
+                _ => ProveOptions::default(),
+            };
+            let (proof, _) = prove_collaborative(&mpc_pk, circuit, rng, options).unwrap();
+            criterion::black_box(reveal_proof::<E, S>(proof));
+        }
+        let elapsed = start.elapsed();
+        S::FrShare::deinit_protocol();
+        elapsed
+    })
+    .into_iter()
+    .max()
+    .unwrap()
+}
+
+fn bench_scheme<S: PairingShare<E>>(c: &mut Criterion, name: &str, n: usize) {
+    let mut group = c.benchmark_group(format!("groth16/{}-{}", name, n));
+    group.sample_size(10);
+    for &steps in &STEPS {
+        for &(id, precomputed) in &[
+            ("prove", Precomputed::Nothing),
+            ("prove_with_tables", Precomputed::Tables),
+            ("prove_with_context", Precomputed::Context),
+        ] {
+            group.bench_with_input(BenchmarkId::new(id, steps), &steps, |b, &steps| {
+                b.iter_custom(|iters| time_proofs::<S>(n, steps, iters, precomputed))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_groth16(c: &mut Criterion) {
+    bench_plain(c);
+    bench_scheme::<AdditivePairingShare<E>>(c, "additive", 2);
+    bench_scheme::<GszPairingShare<E>>(c, "gsz20", 3);
+}
+
+criterion_group!(benches, bench_groth16);
+criterion_main!(benches);
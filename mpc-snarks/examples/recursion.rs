@@ -0,0 +1,131 @@
+//! One layer of proof composition, with every party played by this process: the parties prove
+//! together, over an inner curve, that the public `c` is the product of `a` and `b`, which party 0
+//! shares with them, and then wrap that proof in one, over an outer curve, that it verifies. The
+//! wrapping proof is collaborative too, with party 0's assignment shared, or, with `--public`,
+//! made by party 0 alone.
+//!
+//! * `mnt`: MNT4-753 inside MNT6-753, a cycle, so the wrapping could go on.
+//! * `bw6`: BLS12-377 inside BW6-761, as in the Celo/Zexe stack.
+//!
+//! `cargo run --release --example recursion -- [mnt|bw6] [PARTIES] [--public]`. Over MNT6-753, the
+//! outer setup and proof each take minutes, and over BW6-761 most of one.
+use ark_bls12_377::Bls12_377;
+use ark_bw6_761::BW6_761;
+use ark_ec::PairingEngine;
+use ark_ff::UniformRand;
+use ark_groth16::create_random_proof;
+use ark_mnt4_753::MNT4_753;
+use ark_mnt6_753::MNT6_753;
+use ark_r1cs_std::pairing::{bls12, mnt4, PairingVar};
+use ark_std::test_rng;
+use mpc_algebra::{AdditivePairingShare, MpcField, PairingShare, Reveal};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_snarks::demo::run_local;
+use mpc_snarks::recursion::{pack_inputs, SharedAssignment, VerifierCircuit};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+
+use std::time::Instant;
+
+fn compose<Inner, PV, Outer>(parties: usize, public: bool)
+where
+    Inner: PairingEngine,
+    PV: PairingVar<Inner>,
+    Outer: PairingEngine<Fr = Inner::Fq>,
+{
+    type S<E> = AdditivePairingShare<E>;
+    let rng = &mut test_rng();
+    let (a, b) = (Inner::Fr::rand(rng), Inner::Fr::rand(rng));
+    let c = a * b;
+
+    let start = Instant::now();
+    let inner_pk =
+        setup::<Inner, _, _>(MySillyCircuit::<Inner::Fr> { a: None, b: None }, rng).unwrap();
+    let inner_proofs = run_local(parties, || {
+        let rng = &mut test_rng();
+        let shared = MpcField::<_, <S<Inner> as PairingShare<Inner>>::FrShare>::king_share_batch(
+            vec![a, b],
+            rng,
+        );
+        let circuit = MySillyCircuit {
+            a: Some(shared[0]),
+            b: Some(shared[1]),
+        };
+        let mpc_pk = share_proving_key::<Inner, S<Inner>>(&inner_pk);
+        let proof = prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default()).unwrap().0;
+        reveal_proof::<Inner, S<Inner>>(proof)
+    })
+    .expect("a party failed");
+    let inner_proof = inner_proofs[0].clone();
+    assert!(verify(&inner_pk.vk, &inner_proof, &[c]).unwrap());
+    println!(
+        "{} parties proved that c is a product in {:.1?}",
+        parties,
+        start.elapsed()
+    );
+
+    let start = Instant::now();
+    let verifier = VerifierCircuit::<Inner, PV>::new(inner_pk.vk);
+    let pk = setup::<Outer, _, _>(verifier.clone(), rng).unwrap();
+    println!("set up the verifier circuit in {:.1?}", start.elapsed());
+
+    let start = Instant::now();
+    let inputs = pack_inputs(&[c]);
+    let proof = if public {
+        let circuit = verifier.with_proof(inner_proof, vec![c]);
+        let proof = create_random_proof(circuit, &pk, rng).unwrap();
+        println!("party 0 wrapped the proof in {:.1?}", start.elapsed());
+        proof
+    } else {
+        let proofs = run_local(parties, || {
+            let rng = &mut test_rng();
+            let circuit = if Net::party_id() == 0 {
+                verifier.clone().with_proof(inner_proof.clone(), vec![c])
+            } else {
+                verifier.clone()
+            };
+            let shared = SharedAssignment::share(circuit, 0, inputs.clone(), rng).unwrap();
+            if Net::party_id() == 0 {
+                println!("{} constraints, shared", shared.num_constraints());
+            }
+            let mpc_pk = share_proving_key::<Outer, S<Outer>>(&pk);
+            let proof =
+                prove_collaborative(&mpc_pk, shared, rng, ProveOptions::default()).unwrap().0;
+            reveal_proof::<Outer, S<Outer>>(proof)
+        })
+        .expect("a party failed");
+        println!(
+            "{} parties wrapped the proof in {:.1?}",
+            parties,
+            start.elapsed()
+        );
+        proofs[0].clone()
+    };
+    assert!(verify(&pk.vk, &proof, &inputs).unwrap());
+    println!("and the wrapping proof verifies");
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let public = args.iter().any(|a| a == "--public");
+    let mut args = args.iter().filter(|a| *a != "--public");
+    let curves = args.next().map_or("mnt", |c| c.as_str());
+    let parties = args
+        .next()
+        .map_or(2, |n| n.parse().expect("PARTIES must be a number"));
+    match curves {
+        "mnt" => {
+            println!("MNT4-753 inside MNT6-753");
+            compose::<MNT4_753, mnt4::PairingVar<ark_mnt4_753::Parameters>, MNT6_753>(
+                parties, public,
+            )
+        }
+        "bw6" => {
+            println!("BLS12-377 inside BW6-761");
+            compose::<Bls12_377, bls12::PairingVar<ark_bls12_377::Parameters>, BW6_761>(
+                parties, public,
+            )
+        }
+        _ => panic!("the curves are mnt or bw6, not {}", curves),
+    }
+}
@@ -0,0 +1,294 @@
+//! Which sessions a party's node agrees to join.
+//!
+//! A node that proves for partially trusted collaborators should not help with any circuit they
+//! ask for: proving leaks what the circuit reveals about the node's inputs, and costs it time in
+//! proportion to the circuit's size. An [AdmissionPolicy], set by the node's operator, lists the
+//! circuits it proves (by [CircuitSummary::hash], the hash of their constraint matrices), bounds
+//! their size, and names the counterparties it connects to.
+//! [Network::join_with_policy](crate::Network::join_with_policy) checks a session against it
+//! before connecting.
+//!
+//! Policies can be written as text, one rule per line, with `#` starting a comment:
+//!
+//! ```text
+//! circuit 3f2a...     # allow this circuit (64 hex digits); any circuit, if none are listed
+//! max_constraints 1048576
+//! max_variables 2097152
+//! counterparty 10.0.0.2:8000   # allow this party; any party, if none are listed
+//! ```
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, Result as R1CSResult,
+    SynthesisMode,
+};
+use sha2::{Digest, Sha256};
+
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::net::SocketAddr;
+use std::path::Path;
+
+/// The shape of a circuit: what a policy judges it by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CircuitSummary {
+    /// SHA-256 of the constraint matrices and the numbers of variables.
+    pub hash: [u8; 32],
+    pub num_constraints: usize,
+    pub num_instance_variables: usize,
+    pub num_witness_variables: usize,
+}
+
+impl CircuitSummary {
+    /// Summarize `circuit`, which need not have a witness.
+    pub fn of<F: PrimeField, C: ConstraintSynthesizer<F>>(circuit: C) -> R1CSResult<Self> {
+        let cs = ConstraintSystem::<F>::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+        circuit.generate_constraints(cs.clone())?;
+        cs.finalize();
+        let m = cs.to_matrices().expect("matrices are built in setup mode");
+        Ok(Self::of_matrices(&m))
+    }
+
+    pub fn of_matrices<F: PrimeField>(m: &ConstraintMatrices<F>) -> Self {
+        let mut h = Sha256::new();
+        for n in &[
+            m.num_instance_variables,
+            m.num_witness_variables,
+            m.num_constraints,
+        ] {
+            h.update(&(*n as u64).to_le_bytes());
+        }
+        let mut bytes = Vec::new();
+        for matrix in &[&m.a, &m.b, &m.c] {
+            for row in matrix.iter() {
+                h.update(&(row.len() as u64).to_le_bytes());
+                for (coeff, var) in row {
+                    bytes.clear();
+                    coeff.serialize(&mut bytes).unwrap();
+                    h.update(&bytes);
+                    h.update(&(*var as u64).to_le_bytes());
+                }
+            }
+        }
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&h.finalize());
+        Self {
+            hash,
+            num_constraints: m.num_constraints,
+            num_instance_variables: m.num_instance_variables,
+            num_witness_variables: m.num_witness_variables,
+        }
+    }
+
+    /// All variables, including the constant one.
+    pub fn num_variables(&self) -> usize {
+        self.num_instance_variables + self.num_witness_variables
+    }
+
+    /// The hash, in hex, as policies list it.
+    pub fn hash_hex(&self) -> String {
+        self.hash.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// Why a session was refused, or a policy could not be read.
+#[derive(Debug)]
+pub enum AdmissionError {
+    Io(io::Error),
+    /// A malformed policy or hosts file, at the given (1-based) line.
+    Parse {
+        line: usize,
+        msg: String,
+    },
+    /// A circuit not on the allowlist, by hash.
+    CircuitNotAllowed(String),
+    TooManyConstraints {
+        num: usize,
+        max: usize,
+    },
+    TooManyVariables {
+        num: usize,
+        max: usize,
+    },
+    /// A party, by address, that is not a permitted counterparty.
+    CounterpartyNotAllowed(SocketAddr),
+}
+
+impl Display for AdmissionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AdmissionError::Io(e) => write!(f, "{}", e),
+            AdmissionError::Parse { line, msg } => write!(f, "line {}: {}", line, msg),
+            AdmissionError::CircuitNotAllowed(h) => write!(f, "circuit {} is not allowed", h),
+            AdmissionError::TooManyConstraints { num, max } => {
+                write!(f, "{} constraints, but at most {} are allowed", num, max)
+            }
+            AdmissionError::TooManyVariables { num, max } => {
+                write!(f, "{} variables, but at most {} are allowed", num, max)
+            }
+            AdmissionError::CounterpartyNotAllowed(a) => {
+                write!(f, "party {} is not a permitted counterparty", a)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AdmissionError {}
+
+impl From<io::Error> for AdmissionError {
+    fn from(e: io::Error) -> Self {
+        AdmissionError::Io(e)
+    }
+}
+
+/// The sessions a node joins. The default admits every session.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdmissionPolicy {
+    circuits: HashSet<[u8; 32]>,
+    max_constraints: Option<usize>,
+    max_variables: Option<usize>,
+    counterparties: HashSet<SocketAddr>,
+}
+
+impl AdmissionPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow the circuit with hash `hash`. Once any circuit is allowed, no other is.
+    pub fn circuit(mut self, hash: [u8; 32]) -> Self {
+        self.circuits.insert(hash);
+        self
+    }
+
+    pub fn max_constraints(mut self, max: usize) -> Self {
+        self.max_constraints = Some(max);
+        self
+    }
+
+    /// Bound the variables, including the constant one.
+    pub fn max_variables(mut self, max: usize) -> Self {
+        self.max_variables = Some(max);
+        self
+    }
+
+    /// Permit the party at `addr`. Once any party is permitted, no other is.
+    pub fn counterparty(mut self, addr: SocketAddr) -> Self {
+        self.counterparties.insert(addr);
+        self
+    }
+
+    /// Parse a policy from its text form.
+    pub fn parse(text: &str) -> Result<Self, AdmissionError> {
+        let mut policy = Self::new();
+        for (i, line) in text.lines().enumerate() {
+            let err = |msg: String| AdmissionError::Parse { line: i + 1, msg };
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let (key, value) = match (words.next(), words.next(), words.next()) {
+                (Some(k), Some(v), None) => (k, v),
+                _ => return Err(err(format!("expected a rule and a value: {}", line))),
+            };
+            let number = || {
+                value
+                    .parse::<usize>()
+                    .map_err(|e| err(format!("{}: {}", value, e)))
+            };
+            policy = match key {
+                "circuit" => policy.circuit(
+                    parse_hash(value)
+                        .ok_or_else(|| err(format!("{} is not a hash of 64 hex digits", value)))?,
+                ),
+                "max_constraints" => policy.max_constraints(number()?),
+                "max_variables" => policy.max_variables(number()?),
+                "counterparty" => policy.counterparty(
+                    value
+                        .parse()
+                        .map_err(|e| err(format!("{}: {}", value, e)))?,
+                ),
+                _ => return Err(err(format!("unknown rule {}", key))),
+            };
+        }
+        Ok(policy)
+    }
+
+    /// Read a policy from a file in its text form.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, AdmissionError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Whether this node proves `circuit`.
+    pub fn check_circuit(&self, circuit: &CircuitSummary) -> Result<(), AdmissionError> {
+        if !self.circuits.is_empty() && !self.circuits.contains(&circuit.hash) {
+            return Err(AdmissionError::CircuitNotAllowed(circuit.hash_hex()));
+        }
+        if let Some(max) = self.max_constraints {
+            if circuit.num_constraints > max {
+                return Err(AdmissionError::TooManyConstraints {
+                    num: circuit.num_constraints,
+                    max,
+                });
+            }
+        }
+        if let Some(max) = self.max_variables {
+            if circuit.num_variables() > max {
+                return Err(AdmissionError::TooManyVariables {
+                    num: circuit.num_variables(),
+                    max,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether this node, as party `party_id`, connects to all of `hosts`.
+    pub fn check_counterparties(
+        &self,
+        hosts: &[SocketAddr],
+        party_id: usize,
+    ) -> Result<(), AdmissionError> {
+        if self.counterparties.is_empty() {
+            return Ok(());
+        }
+        match hosts
+            .iter()
+            .enumerate()
+            .find(|(i, a)| *i != party_id && !self.counterparties.contains(a))
+        {
+            Some((_, a)) => Err(AdmissionError::CounterpartyNotAllowed(*a)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_hash(hex: &str) -> Option<[u8; 32]> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    let mut hash = [0u8; 32];
+    for (i, b) in hash.iter_mut().enumerate() {
+        *b = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(hash)
+}
+
+/// Read a hosts file, of one `HOST:PORT` per line, as [Network::join](crate::Network::join)
+/// takes.
+pub fn read_hosts(path: impl AsRef<Path>) -> Result<Vec<SocketAddr>, AdmissionError> {
+    fs::read_to_string(path)?
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            l.trim().parse().map_err(|e| AdmissionError::Parse {
+                line: i + 1,
+                msg: format!("bad socket address {}: {}", l.trim(), e),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,339 @@
+//! Collaborative Groth16 proving, as a library.
+//!
+//! The parties agree on a circuit and its keys ([setup]), join the network ([Network::join]),
+//! lift the proving key to the MPC engine ([share_proving_key]), and each run the prover on their
+//! shares of the witness ([prove_collaborative]). Revealing the result ([reveal_proof]) gives an
+//! ordinary Groth16 proof that anyone can [verify].
+//!
+//! The circuit is typically written once, generically over the field, and used with the plain
+//! field for [setup] and with [MpcField] for proving:
+//!
+//! ```
+//! use ark_bls12_377::{Bls12_377, Fr};
+//! use ark_std::{test_rng, UniformRand};
+//! use mpc_algebra::honest_but_curious::MpcField;
+//! use mpc_algebra::{AdditivePairingShare, Reveal};
+//! use mpc_snarks::silly::MySillyCircuit;
+//! use mpc_snarks::*;
+//!
+//! type E = Bls12_377;
+//! type S = AdditivePairingShare<E>;
+//! let rng = &mut test_rng();
+//!
+//! // With no other parties, the same code runs as a plain prover.
+//! let _net = Network::single_party();
+//! let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+//! let mpc_pk = share_proving_key::<E, S>(&pk);
+//!
+//! let a = MpcField::<Fr>::king_share(Fr::rand(rng), rng);
+//! let b = MpcField::<Fr>::king_share(Fr::rand(rng), rng);
+//! let c = (a * b).reveal();
+//! let circuit = MySillyCircuit { a: Some(a), b: Some(b) };
+//! let (proof, _) = prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default()).unwrap();
+//! let proof = reveal_proof::<E, S>(proof);
+//! assert!(verify(&pk.vk, &proof, &[c]).unwrap());
+//! ```
+//!
+//! [ProveOptions] say what to reuse from earlier proofs with the same key, such as a
+//! [ProverContext] from [prover_context], and what to save, export or check along the way.
+use ark_ec::PairingEngine;
+use ark_ff::{Field, Zero};
+use ark_groth16::{
+    generate_random_parameters, prepare_verifying_key, verify_proof, Proof, ProvingKey,
+    VerifyingKey,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, Result as R1CSResult};
+use ark_serialize::{CanonicalSerialize, SerializationError};
+use ark_std::rand::Rng;
+use mpc_algebra::channel::PointEncoding;
+use mpc_algebra::{
+    channel, fixed_base, shared_rng, MpcField, MpcPairingEngine, PairingShare, Reveal,
+};
+use mpc_net::{catch_abort, MpcMultiNet, MpcNet, NetConfig, NetError};
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::admission::{self, AdmissionError, AdmissionPolicy, CircuitSummary};
+use crate::groth::ceremony;
+use crate::groth::import::PowersOfTau;
+use crate::groth::prover::{
+    create_random_proof, ProveError, ProveOptions, ProverContext, ProvingKeyTables,
+};
+use crate::groth::report::ProvingReport;
+
+/// Membership in the process-wide MPC network, which is left when this is dropped.
+///
+/// Only one may exist at a time. To run several independent networks in one process, use
+/// [mpc_net::MpcSession] instead.
+pub struct Network {
+    _private: (),
+}
+
+impl Network {
+    /// Connect to the parties listed in `hosts` (one `HOST:PORT` per line), as party `party_id`,
+    /// and agree on the session's [shared_rng] seed.
+    pub fn join(hosts: &str, party_id: usize, config: NetConfig) -> Result<Self, NetError> {
+        MpcMultiNet::set_config(config);
+        catch_abort(|| {
+            MpcMultiNet::init_from_file(hosts, party_id);
+            shared_rng::setup();
+        })?;
+        Ok(Self { _private: () })
+    }
+
+    /// [Network::join], once `policy` admits the parties in `hosts` and `circuit`, the circuit of
+    /// the session. A session it refuses is refused before connecting to anyone.
+    pub fn join_with_policy(
+        hosts: &str,
+        party_id: usize,
+        config: NetConfig,
+        policy: &AdmissionPolicy,
+        circuit: &CircuitSummary,
+    ) -> Result<Self, JoinError> {
+        policy.check_counterparties(&admission::read_hosts(hosts)?, party_id)?;
+        policy.check_circuit(circuit)?;
+        Ok(Self::join(hosts, party_id, config)?)
+    }
+
+    /// Run as the only party, with no networking: shared values are then just plain values.
+    pub fn single_party() -> Self {
+        MpcMultiNet::init_single_party();
+        Self { _private: () }
+    }
+
+    pub fn party_id(&self) -> usize {
+        MpcMultiNet::party_id()
+    }
+
+    pub fn n_parties(&self) -> usize {
+        MpcMultiNet::n_parties()
+    }
+}
+
+impl Drop for Network {
+    fn drop(&mut self) {
+        MpcMultiNet::deinit();
+    }
+}
+
+/// Why [Network::join_with_policy] did not join.
+#[derive(Debug)]
+pub enum JoinError {
+    /// The policy refused the session.
+    Refused(AdmissionError),
+    Net(NetError),
+}
+
+impl Display for JoinError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinError::Refused(e) => write!(f, "session refused: {}", e),
+            JoinError::Net(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+impl From<AdmissionError> for JoinError {
+    fn from(e: AdmissionError) -> Self {
+        JoinError::Refused(e)
+    }
+}
+
+impl From<NetError> for JoinError {
+    fn from(e: NetError) -> Self {
+        JoinError::Net(e)
+    }
+}
+
+/// Generate Groth16 keys for `circuit`, which need not have a witness.
+///
+/// This is a local, trusted setup: all parties should use the same keys, generated by someone
+/// they trust to forget the randomness.
+pub fn setup<E, C, R>(circuit: C, rng: &mut R) -> R1CSResult<ProvingKey<E>>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+{
+    generate_random_parameters::<E, C, R>(circuit, rng)
+}
+
+/// Generate Groth16 keys for `circuit` together with the other parties, so that no single party
+/// knows the randomness behind them (see [ceremony]).
+///
+/// Every party must call this, with the same circuit, and gets the same key.
+pub fn setup_collaborative<E, S, C>(circuit: C) -> R1CSResult<ProvingKey<E>>
+where
+    E: PairingEngine,
+    S: PairingShare<E>,
+    C: ConstraintSynthesizer<E::Fr>,
+{
+    channel::without_cheating(|| ceremony::generate_parameters::<E, S, C>(circuit))
+}
+
+/// Generate Groth16 keys for `circuit` from `powers`, the outcome of a powers-of-tau ceremony
+/// (read with [PowersOfTau::read]), together with the other parties, who draw the key's last
+/// secret (see [ceremony::generate_parameters_from_powers]).
+///
+/// Every party must call this, with the same circuit and powers, and gets the same key.
+pub fn setup_from_powers_of_tau<E, S, C>(
+    circuit: C,
+    powers: &PowersOfTau<E>,
+) -> R1CSResult<ProvingKey<E>>
+where
+    E: PairingEngine,
+    S: PairingShare<E>,
+    C: ConstraintSynthesizer<E::Fr>,
+{
+    channel::without_cheating(|| {
+        ceremony::generate_parameters_from_powers::<E, S, C>(circuit, powers)
+    })
+}
+
+/// Lift a proving key to the MPC engine, as a public value.
+pub fn share_proving_key<E: PairingEngine, S: PairingShare<E>>(
+    pk: &ProvingKey<E>,
+) -> ProvingKey<MpcPairingEngine<E, S>> {
+    Reveal::from_public(pk.clone())
+}
+
+/// Prove `circuit`, whose witness is (partly) shared, together with the other parties, with a
+/// report of what the proof cost this party: the time of each of the prover's phases, the bytes it
+/// sent and received, and the triples and openings it used.
+///
+/// Every party must call this, with the same key, circuit structure, and options. The proof stays
+/// shared until [reveal_proof].
+///
+/// `rng` draws this party's shares of the proof's blinding, so it must be the party's own, such as
+/// [seed::rng](mpc_algebra::seed::rng) gives, not one that the other parties also draw.
+///
+/// A proof that was interrupted (say, because a party restarted) with
+/// [ProveOptions::checkpoint] is finished by every party reconnecting,
+/// [resuming](crate::Checkpoint::resume) its checkpoint, and calling this again, with the same key
+/// and circuit.
+pub fn prove_collaborative<E, S, C, R>(
+    pk: &ProvingKey<MpcPairingEngine<E, S>>,
+    circuit: C,
+    rng: &mut R,
+    options: ProveOptions<MpcPairingEngine<E, S>>,
+) -> Result<(Proof<MpcPairingEngine<E, S>>, ProvingReport), ProveError>
+where
+    E: PairingEngine,
+    S: PairingShare<E>,
+    C: ConstraintSynthesizer<MpcField<E::Fr, S::FrShare>>,
+    R: Rng,
+{
+    channel::without_cheating(|| create_random_proof(circuit, pk, rng, options))
+}
+
+/// Precompute fixed-base tables for a lifted proving key, with windows of `width` bits (or a
+/// width suited to the key's size), to speed up every proof made with it (see
+/// [ProveOptions::tables]).
+///
+/// The tables take about `256 / width` points per point of the key.
+pub fn proving_key_tables<E: PairingEngine, S: PairingShare<E>>(
+    pk: &ProvingKey<MpcPairingEngine<E, S>>,
+    width: Option<usize>,
+) -> ProvingKeyTables<MpcPairingEngine<E, S>> {
+    let width = width.unwrap_or_else(|| fixed_base::default_width(pk.a_query.len()));
+    ProvingKeyTables::new(pk, width)
+}
+
+/// Precompute, once, what every proof with a lifted proving key needs: fixed-base tables as
+/// [proving_key_tables] builds them (with windows of `width` bits, or a width suited to the key's
+/// size), and the witness map's domain. Prove with the result through [ProveOptions::context],
+/// as many times as needed.
+pub fn prover_context<E: PairingEngine, S: PairingShare<E>>(
+    pk: ProvingKey<MpcPairingEngine<E, S>>,
+    width: Option<usize>,
+) -> R1CSResult<ProverContext<MpcPairingEngine<E, S>>> {
+    let width = width.unwrap_or_else(|| fixed_base::default_width(pk.a_query.len()));
+    ProverContext::new(pk, width)
+}
+
+/// Open a shared proof. Every party must call this.
+pub fn reveal_proof<E: PairingEngine, S: PairingShare<E>>(
+    proof: Proof<MpcPairingEngine<E, S>>,
+) -> Proof<E> {
+    proof.reveal()
+}
+
+/// Verify a (revealed) proof against the instance `public_inputs`.
+pub fn verify<E: PairingEngine>(
+    vk: &VerifyingKey<E>,
+    proof: &Proof<E>,
+    public_inputs: &[E::Fr],
+) -> R1CSResult<bool> {
+    verify_proof(&prepare_verifying_key(vk), proof, public_inputs)
+}
+
+/// The byte order of the coordinates in an encoded proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// arkworks' own order.
+    LittleEndian,
+    /// Each coordinate reversed, and the coefficients of `b`'s extension-field coordinates
+    /// highest first, as verifiers on other platforms, like the EVM (EIP-197), expect.
+    BigEndian,
+}
+
+/// How a proof is written out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofFormat {
+    pub points: PointEncoding,
+    pub order: ByteOrder,
+}
+
+impl ProofFormat {
+    /// The current session's [point encoding](channel::point_encoding), in little-endian order.
+    pub fn session() -> Self {
+        Self {
+            points: channel::point_encoding(),
+            order: ByteOrder::LittleEndian,
+        }
+    }
+
+    /// Encode `proof`.
+    pub fn encode<E: PairingEngine>(&self, proof: &Proof<E>) -> Vec<u8> {
+        let mut bytes = self.points.serialize(proof);
+        self.reorder::<E>(&mut bytes);
+        bytes
+    }
+
+    /// Decode a proof encoded in this format.
+    pub fn decode<E: PairingEngine>(&self, bytes: &[u8]) -> Result<Proof<E>, SerializationError> {
+        let mut bytes = bytes.to_vec();
+        self.reorder::<E>(&mut bytes);
+        self.points.deserialize(&bytes)
+    }
+
+    /// Between little- and big-endian. A proof's points are made of base-field coordinates, each
+    /// (with any flags in its spare top bits) as long as a base-field element; `b`'s come in
+    /// groups of [Fqe](PairingEngine::Fqe)'s extension degree, lowest coefficient first.
+    fn reorder<E: PairingEngine>(&self, bytes: &mut [u8]) {
+        if self.order == ByteOrder::LittleEndian {
+            return;
+        }
+        let width = E::Fq::zero().serialized_size();
+        for coordinate in bytes.chunks_mut(width) {
+            coordinate.reverse();
+        }
+        let degree = E::Fqe::extension_degree() as usize;
+        let g1 = match self.points {
+            PointEncoding::Compressed => width,
+            PointEncoding::Uncompressed => 2 * width,
+        };
+        // too short to be a proof, which decoding will find
+        if let Some(b) = bytes.get_mut(g1..g1 * (1 + degree)) {
+            for element in b.chunks_mut(width * degree) {
+                // reverses the coefficients' order, and then each back
+                element.reverse();
+                for coefficient in element.chunks_mut(width) {
+                    coefficient.reverse();
+                }
+            }
+        }
+    }
+}
@@ -7,7 +7,7 @@ use ark_ec::{group::Group, AffineCurve, PairingEngine, ProjectiveCurve};
 use ark_ff::Field;
 use ark_poly::domain::radix2::Radix2EvaluationDomain;
 use ark_poly::univariate::DensePolynomial;
-use ark_poly::{EvaluationDomain, Polynomial, UVPolynomial};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, Polynomial, UVPolynomial};
 use ark_poly_commit::marlin_pc;
 use ark_poly_commit::PolynomialCommitment;
 use ark_serialize::CanonicalSerialize;
@@ -15,12 +15,16 @@ use ark_std::rand::SeedableRng;
 use std::borrow::Cow;
 use std::path::PathBuf;
 
+use mpc_algebra::budget::{self, RevealBudget};
+use mpc_algebra::leak::{self, LeakCheck};
+use mpc_algebra::seed;
 use mpc_algebra::com::ComField;
 use mpc_algebra::honest_but_curious as hbc;
 use mpc_algebra::malicious_majority as mm;
 use mpc_algebra::*;
 use mpc_trait::MpcWire;
 use mpc_net::{MpcNet, MpcMultiNet};
+use mpc_snarks::witness::{SharedWitness, WitnessError, WitnessFile};
 
 use clap::arg_enum;
 use merlin::Transcript;
@@ -29,6 +33,7 @@ use structopt::StructOpt;
 mod groth;
 mod marlin;
 mod plonk;
+mod qap;
 mod silly;
 
 arg_enum! {
@@ -104,6 +109,26 @@ struct Opt {
     /// Input a
     #[structopt()]
     args: Vec<u64>,
+
+    /// Witness file (JSON or TOML), instead of additive shares as arguments
+    #[structopt(long, parse(from_os_str), conflicts_with = "args")]
+    witness: Option<PathBuf>,
+
+    /// Most shared values to open (inputs and outputs included)
+    #[structopt(long)]
+    reveal_budget: Option<u64>,
+
+    /// Only warn when the reveal budget is exceeded, rather than aborting
+    #[structopt(long, requires = "reveal-budget")]
+    reveal_budget_warn: bool,
+
+    /// Flag openings of values that may leak private inputs (slow; for development)
+    #[structopt(long)]
+    leak_check: bool,
+
+    /// Derive local randomness from this seed, so that runs are reproducible (for testing)
+    #[structopt(long)]
+    seed: Option<u64>,
 }
 
 impl Opt {
@@ -135,6 +160,42 @@ impl Opt {
             _ => ComputationDomain::Field,
         }
     }
+
+    /// How many inputs the computation takes, if that is fixed.
+    fn expected_inputs(&self) -> Option<usize> {
+        match &self.computation {
+            Computation::Product | Computation::PProduct => Some(2),
+            Computation::PairingDh => Some(3),
+            Computation::PairingProd | Computation::PairingDiv => Some(4),
+            Computation::MarlinPcBatch | Computation::KzgZkBatch => Some(6),
+            Computation::Dh if self.spdz => Some(2),
+            Computation::Dh => Some(3),
+            _ => None,
+        }
+    }
+
+    /// The inputs: shared from the witness file, if there is one, or else this party's additive
+    /// shares from the arguments.
+    fn inputs<S: FieldShare<Fr>>(&self) -> Result<Vec<MpcField<Fr, S>>, WitnessError> {
+        let inputs = match &self.witness {
+            Some(path) => {
+                let file = WitnessFile::read(path)?;
+                SharedWitness::<Fr, S>::share(&file, &mut seed::rng(b"witness"))?.values()
+            }
+            None => self
+                .args
+                .iter()
+                .map(|i| MpcField::from_add_shared(Fr::from(*i)))
+                .collect(),
+        };
+        match self.expected_inputs() {
+            Some(expected) if expected != inputs.len() => Err(WitnessError::Count {
+                expected,
+                got: inputs.len(),
+            }),
+            _ => Ok(inputs),
+        }
+    }
 }
 
 fn pairing_engine_test<E: PairingEngine>(
@@ -193,16 +254,30 @@ impl Computation {
                 groth::mpc_test_prove_and_verify::<
                     ark_bls12_377::Bls12_377,
                     mpc_algebra::AdditivePairingShare<ark_bls12_377::Bls12_377>,
-                >(1);
+                    _,
+                    _,
+                >(1, &mut SharedRng::new(b"groth16"), &mut seed::rng(b"groth16"));
                 vec![]
             }
             Computation::Marlin => {
-                marlin::mpc_test_prove_and_verify(1);
+                marlin::mpc_test_prove_and_verify(
+                    1,
+                    &mut SharedRng::new(b"marlin"),
+                    &mut seed::rng(b"marlin"),
+                );
                 vec![]
             }
             Computation::Plonk => {
-                plonk::local_test_prove_and_verify(1);
-                plonk::mpc_test_prove_and_verify(1);
+                plonk::local_test_prove_and_verify(
+                    1,
+                    &mut SharedRng::new(b"plonk"),
+                    &mut seed::rng(b"plonk"),
+                );
+                plonk::mpc_test_prove_and_verify(
+                    1,
+                    &mut SharedRng::new(b"plonk"),
+                    &mut seed::rng(b"plonk"),
+                );
                 vec![]
             }
             Computation::MarlinPc => {
@@ -214,12 +289,13 @@ impl Computation {
                     Some(2),
                     Some(1),
                 )];
-                let rng = &mut ark_std::test_rng();
+                let rng = &mut SharedRng::new(b"marlin pc setup");
+                let zk_rng = &mut seed::rng(b"marlin pc hiding");
                 let srs = MarlinPc::setup(10, Some(1), rng).unwrap();
                 let (ck, vk) = MarlinPc::trim(&srs, 2, 1, Some(&[2])).unwrap();
                 let mpc_ck = <MarlinMPc as PolynomialCommitment<MFr, DensePolynomial<MFr>>>::CommitterKey::from_public(ck);
                 let (mpc_commits, mpc_rands) =
-                    MarlinMPc::commit(&mpc_ck, &polys, Some(rng)).unwrap();
+                    MarlinMPc::commit(&mpc_ck, &polys, Some(zk_rng)).unwrap();
                 let commits = mpc_commits.clone().reveal();
                 //let srs = mpc_algebra::poly::pc::MpcPolyCommit::setup(10, Some(1), rng).unwrap();
                 //let (ck, vk) =
@@ -236,7 +312,7 @@ impl Computation {
                     &x,
                     chal,
                     &mpc_rands,
-                    Some(rng),
+                    Some(zk_rng),
                 )
                 .unwrap();
                 println!("{:?}", mpc_pf);
@@ -267,12 +343,13 @@ impl Computation {
                         Some(1),
                     ),
                 ];
-                let rng = &mut ark_std::test_rng();
+                let rng = &mut SharedRng::new(b"marlin pc setup");
+                let zk_rng = &mut seed::rng(b"marlin pc hiding");
                 let srs = MarlinPc::setup(10, Some(1), rng).unwrap();
                 let (ck, vk) = MarlinPc::trim(&srs, 2, 1, Some(&[2])).unwrap();
                 let mpc_ck = <MarlinMPc as PolynomialCommitment<MFr, DensePolynomial<MFr>>>::CommitterKey::from_public(ck);
                 let (mpc_commits, mpc_rands) =
-                    MarlinMPc::commit(&mpc_ck, &polys, Some(rng)).unwrap();
+                    MarlinMPc::commit(&mpc_ck, &polys, Some(zk_rng)).unwrap();
                 let commits = mpc_commits.clone().reveal();
                 let x = MFr::from(2u32);
                 let chal = MFr::from(4u32);
@@ -287,7 +364,7 @@ impl Computation {
                     &x,
                     chal,
                     &mpc_rands,
-                    Some(rng),
+                    Some(zk_rng),
                 )
                 .unwrap();
                 println!("{:?}", mpc_pf);
@@ -316,7 +393,7 @@ impl Computation {
                     )
                 }
                 let poly = MP::from_coefficients_slice(&inputs);
-                let rng = &mut ark_std::test_rng();
+                let rng = &mut SharedRng::new(b"kzg setup");
                 let pp = ark_poly_commit::kzg10::KZG10::<
                     ark_bls12_377::Bls12_377,
                     ark_poly::univariate::DensePolynomial<ark_bls12_377::Fr>,
@@ -330,14 +407,12 @@ impl Computation {
                     powers_of_gamma_g: Cow::Owned(powers_of_gamma_g),
                 };
                 let mpc_powers = powers_to_mpc(powers);
-                let (commit, rand) =
-                    ark_poly_commit::kzg10::KZG10::commit(&mpc_powers, &poly, None, None).unwrap();
+                let (commit, rand) = MKzg::commit(&mpc_powers, &poly, None, None).unwrap();
                 println!("{:?}", commit);
                 let commit = commit_from_mpc(commit);
                 println!("{:?}", commit);
                 let mpc_x = MFr::from(2u32);
-                let mpc_pf =
-                    ark_poly_commit::kzg10::KZG10::open(&mpc_powers, &poly, mpc_x, &rand).unwrap();
+                let mpc_pf = MKzg::open(&mpc_powers, &poly, mpc_x, &rand).unwrap();
                 let y = poly.evaluate(&mpc_x).reveal();
                 let x = mpc_x.reveal();
                 println!("{:?}", mpc_pf);
@@ -363,7 +438,8 @@ impl Computation {
             }
             Computation::KzgZk => {
                 let poly = MP::from_coefficients_slice(&inputs);
-                let rng = &mut ark_std::test_rng();
+                let rng = &mut SharedRng::new(b"kzg setup");
+                let zk_rng = &mut seed::rng(b"kzg hiding");
                 let pp = ark_poly_commit::kzg10::KZG10::<
                     ark_bls12_377::Bls12_377,
                     ark_poly::univariate::DensePolynomial<ark_bls12_377::Fr>,
@@ -378,12 +454,10 @@ impl Computation {
                 };
                 let mpc_powers = powers_to_mpc(powers);
                 let (commit, rand) =
-                    ark_poly_commit::kzg10::KZG10::commit(&mpc_powers, &poly, Some(2), Some(rng))
-                        .unwrap();
+                    MKzg::commit(&mpc_powers, &poly, Some(2), Some(zk_rng)).unwrap();
                 let commit = commit_from_mpc(commit);
                 let mpc_x = MFr::from(2u32);
-                let mpc_pf =
-                    ark_poly_commit::kzg10::KZG10::open(&mpc_powers, &poly, mpc_x, &rand).unwrap();
+                let mpc_pf = MKzg::open(&mpc_powers, &poly, mpc_x, &rand).unwrap();
                 let y = poly.evaluate(&mpc_x).reveal();
                 let x = mpc_x.reveal();
                 let pf = pf_from_mpc(mpc_pf);
@@ -408,7 +482,8 @@ impl Computation {
                 assert_eq!(inputs.len(), 6);
                 let poly = MP::from_coefficients_slice(&inputs[0..3]);
                 let poly2 = MP::from_coefficients_slice(&inputs[3..6]);
-                let rng = &mut ark_std::test_rng();
+                let rng = &mut SharedRng::new(b"kzg setup");
+                let zk_rng = &mut seed::rng(b"kzg hiding");
                 let pp = ark_poly_commit::kzg10::KZG10::<
                     ark_bls12_377::Bls12_377,
                     ark_poly::univariate::DensePolynomial<ark_bls12_377::Fr>,
@@ -423,20 +498,15 @@ impl Computation {
                 };
                 let mpc_powers = powers_to_mpc(powers);
                 let (commit, rand) =
-                    ark_poly_commit::kzg10::KZG10::commit(&mpc_powers, &poly, Some(2), Some(rng))
-                        .unwrap();
+                    MKzg::commit(&mpc_powers, &poly, Some(2), Some(zk_rng)).unwrap();
                 let (commit2, rand2) =
-                    ark_poly_commit::kzg10::KZG10::commit(&mpc_powers, &poly2, Some(2), Some(rng))
-                        .unwrap();
+                    MKzg::commit(&mpc_powers, &poly2, Some(2), Some(zk_rng)).unwrap();
                 let commit = commit_from_mpc(commit);
                 let commit2 = commit_from_mpc(commit2);
                 let mpc_x = MFr::from(2u32);
                 let mpc_x2 = MFr::from(1u32);
-                let mpc_pf =
-                    ark_poly_commit::kzg10::KZG10::open(&mpc_powers, &poly, mpc_x, &rand).unwrap();
-                let mpc_pf2 =
-                    ark_poly_commit::kzg10::KZG10::open(&mpc_powers, &poly2, mpc_x2, &rand2)
-                        .unwrap();
+                let mpc_pf = MKzg::open(&mpc_powers, &poly, mpc_x, &rand).unwrap();
+                let mpc_pf2 = MKzg::open(&mpc_powers, &poly2, mpc_x2, &rand2).unwrap();
                 let y = poly.evaluate(&mpc_x).reveal();
                 let y2 = poly2.evaluate(&mpc_x2).reveal();
                 let x = mpc_x.reveal();
@@ -464,7 +534,7 @@ impl Computation {
                 vec![]
             }
             Computation::Msm => {
-                let rng = &mut rand::rngs::StdRng::from_seed([0u8; 32]);
+                let rng = &mut SharedRng::new(b"msm coefficients");
                 let ps: Vec<MFr> = (0..inputs.len()).map(|_| MFr::public_rand(rng)).collect();
                 let sum: MFr = inputs.iter().zip(ps.iter()).map(|(a, b)| *a * b).sum();
                 let mut public_gens =
@@ -667,7 +737,7 @@ impl Computation {
     fn run_field<F: ComField>(&self, mut inputs: Vec<F>) -> Vec<F> {
         let outputs = match self {
             Computation::Fft => {
-                let d = Radix2EvaluationDomain::<F>::new(inputs.len()).unwrap();
+                let d = GeneralEvaluationDomain::<F>::new(inputs.len()).unwrap();
                 d.ifft_in_place(&mut inputs);
                 inputs
             }
@@ -861,6 +931,13 @@ trait Pc = ark_poly_commit::PolynomialCommitment<Fr, DensePolynomial<Fr>>;
 trait MPc = ark_poly_commit::PolynomialCommitment<MFr, DensePolynomial<MFr>>;
 type MarlinPc = marlin_pc::MarlinKZG10<E, P>;
 type MarlinMPc = marlin_pc::MarlinKZG10<ME, MP>;
+type MKzg = ark_poly_commit::kzg10::KZG10<ME, MP>;
+
+fn fail(e: WitnessError) -> ! {
+    eprintln!("Bad inputs: {}", e);
+    MpcMultiNet::deinit();
+    std::process::exit(1)
+}
 
 fn main() -> () {
     let opt = Opt::from_args();
@@ -873,13 +950,26 @@ fn main() -> () {
     }
     let domain = opt.domain();
     MpcMultiNet::init_from_file(opt.hosts.to_str().unwrap(), opt.party as usize);
+    // before the shared rng, whose joint seed is drawn from each party's
+    if let Some(s) = opt.seed {
+        let mut bytes = [0u8; 32];
+        bytes[..8].copy_from_slice(&s.to_le_bytes());
+        seed::set_seed(Some(bytes));
+    }
+    shared_rng::setup();
+    budget::set_reveal_budget(opt.reveal_budget.map(|limit| {
+        if opt.reveal_budget_warn {
+            RevealBudget::warn_after(limit)
+        } else {
+            RevealBudget::abort_after(limit)
+        }
+    }));
+    if opt.leak_check {
+        leak::enable(LeakCheck::default());
+    }
     debug!("Start");
     if opt.spdz {
-        let inputs = opt
-            .args
-            .iter()
-            .map(|i| mm::MpcField::<Fr>::from_add_shared(Fr::from(*i)))
-            .collect::<Vec<_>>();
+        let inputs: Vec<mm::MpcField<Fr>> = opt.inputs().unwrap_or_else(|e| fail(e));
         println!("Inputs:");
         for (i, v) in inputs.iter().enumerate() {
             println!("  {}: {}", i, v);
@@ -900,11 +990,7 @@ fn main() -> () {
             d => panic!("Bad domain: {:?}", d),
         }
     } else {
-        let inputs = opt
-            .args
-            .iter()
-            .map(|i| MFr::from_add_shared(Fr::from(*i)))
-            .collect::<Vec<MFr>>();
+        let inputs: Vec<MFr> = opt.inputs().unwrap_or_else(|e| fail(e));
         println!("Inputs:");
         for (i, v) in inputs.iter().enumerate() {
             println!("  {}: {}", i, v);
@@ -963,6 +1049,13 @@ fn main() -> () {
             d => panic!("Bad domain: {:?}", d),
         }
     }
+    if opt.leak_check {
+        let findings = leak::disable();
+        println!("Possible leaks: {}", findings.len());
+        for f in findings {
+            println!("  {}", f);
+        }
+    }
     debug!("Stats: {:#?}", MpcMultiNet::stats());
     MpcMultiNet::deinit();
     debug!("Done");
@@ -3,9 +3,9 @@ use ark_bls12_377::{Fr, FrParameters};
 use ark_ec::PairingEngine;
 use ark_ff::Fp256;
 use ark_ff::UniformRand;
-use ark_std::test_rng;
 use mpc_algebra::malicious_majority::MpcField;
 use mpc_algebra::reveal::Reveal;
+use mpc_algebra::seed;
 use mpc_net::{MpcMultiNet, MpcNet};
 use std::ops::Mul;
 use structopt::StructOpt;
@@ -17,7 +17,7 @@ pub fn test_collaborative_commitment<E: PairingEngine>() {
 
     MpcMultiNet::init_from_file("./data/2", party_id as usize);
 
-    let rng = &mut test_rng();
+    let rng = &mut seed::rng(b"link hider");
 
     // Create commitment randomness
 
@@ -3,11 +3,13 @@ use crate::Opt;
 use ark_bls12_377::{Fr, Parameters};
 use ark_ec::bls12::Bls12;
 use ark_groth16::{generate_random_parameters, prepare_verifying_key, verify_proof, ProvingKey};
-use ark_std::test_rng;
 use mpc_algebra::reveal::Reveal;
+use mpc_algebra::seed;
+use mpc_algebra::shared_rng::SharedRng;
 use mpc_algebra::{malicious_majority::MpcField, MpcPairingEngine, SpdzPairingShare};
 use mpc_net::{MpcMultiNet, MpcNet};
 use mpc_snarks::groth::prover::create_random_proof;
+use mpc_snarks::{ProveOptions, PublicInputs};
 use structopt::StructOpt;
 
 pub fn test_groth() {
@@ -19,8 +21,6 @@ pub fn test_groth() {
     type E = Bls12<Parameters>;
     type S = SpdzPairingShare<E>;
 
-    let rng = &mut test_rng();
-
     let inputs = opt
         .args
         .iter()
@@ -29,26 +29,27 @@ pub fn test_groth() {
 
     let circ_no_data = VerifyMultiplicationCircuit { a: None, b: None };
 
-    let params: ProvingKey<E> = generate_random_parameters::<E, _, _>(circ_no_data, rng).unwrap();
+    let params: ProvingKey<E> = generate_random_parameters::<E, _, _>(circ_no_data, &mut SharedRng::new(b"setup")).unwrap();
 
     let pvk = prepare_verifying_key::<E>(&params.vk);
 
     // ########################################
     // Here the MPC starts
     // ########################################
-    let mpc_params = ProvingKey::from_public(params);
+    let mpc_params = ProvingKey::from_public(params.clone());
 
     let a = inputs[0];
     let b = inputs[1];
     let c = inputs[2];
 
-    let mpc_proof = create_random_proof::<MpcPairingEngine<E, S>, _, _>(
+    let (mpc_proof, _) = create_random_proof::<MpcPairingEngine<E, S>, _, _>(
         VerifyMultiplicationCircuit {
             a: Some(a),
             b: Some(b),
         },
         &mpc_params,
-        rng,
+        &mut seed::rng(b"prover"),
+        ProveOptions::default(),
     )
     .unwrap();
 
@@ -60,5 +61,9 @@ pub fn test_groth() {
     // Assert that verify_proof works with correct value
     assert!(verify_proof(&pvk, &proof, &[pub_c]).unwrap());
 
+    // PublicInputs reveals c, and checks its MAC, before verifying the proof against it
+    let inputs = PublicInputs::<E, S>::new().shared(c);
+    assert!(inputs.verify(&params.vk, &proof).unwrap());
+
     MpcMultiNet::deinit();
 }
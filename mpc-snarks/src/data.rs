@@ -0,0 +1,705 @@
+//! Turning tabular data into witness vectors.
+//!
+//! Every party reads its own copy of a table: CSV with a header row, or, with the `parquet`
+//! feature, Parquet.
+//! A [Policy], which all parties must agree on, then says what becomes of each column:
+//!
+//! * [ColumnPolicy::Public] columns must be identical at every party, which is checked.
+//! * [ColumnPolicy::SharedBy] columns need only be present at their owner, who secret-shares them.
+//! * Columns that the policy drops, or does not mention, are ignored.
+//!
+//! Cells are parsed as exact decimals and encoded with a [FixedPoint] encoding.
+//!
+//! The owners of shared columns can also publish Pedersen commitments to them
+//! ([Dataset::commit_columns]). Before proving anything about the data, the parties can then check
+//! ([Dataset::check_commitment]) that their shares are of the committed columns, so that a proof is
+//! over the committed dataset rather than over inputs chosen after the fact.
+//!
+//! Audits over a random sample of rows need a sample that no party chose. The parties can either
+//! agree on a public sample ([joint_seed], [sample_indices], [Dataset::select_rows]), or draw a
+//! hidden one as a shared 0/1 mask over the rows ([sample_mask]), which a circuit can use to
+//! constrain only the sampled rows.
+use ark_ec::hashing::{self, HashToCurveError, WBParams};
+use ark_ec::short_weierstrass_jacobian::GroupProjective;
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{One, PrimeField, SquareRootField, Zero};
+use ark_serialize::CanonicalSerialize;
+use mpc_algebra::dp;
+use mpc_algebra::channel::MpcSerNet;
+use mpc_algebra::fixed::{FixedPoint, FixedPointError};
+use mpc_algebra::{FieldShare, GroupShare, MpcField, MpcMulScratch, Reveal};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sha2::{Digest, Sha256};
+
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+/// What becomes of one column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnPolicy {
+    /// Known to every party.
+    Public,
+    /// Known only to the given party, and secret-shared by them.
+    SharedBy(usize),
+    /// Not used.
+    Drop,
+}
+
+/// What becomes of each column of a table, in the order in which they are ingested.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Policy {
+    columns: Vec<(String, ColumnPolicy)>,
+}
+
+impl Policy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the policy for column `name`.
+    pub fn column(mut self, name: &str, policy: ColumnPolicy) -> Self {
+        self.columns.retain(|(n, _)| n != name);
+        self.columns.push((name.to_owned(), policy));
+        self
+    }
+
+    /// The policy for column `name`; unmentioned columns are dropped.
+    pub fn get(&self, name: &str) -> ColumnPolicy {
+        self.columns
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, p)| *p)
+            .unwrap_or(ColumnPolicy::Drop)
+    }
+}
+
+/// Why a table could not be ingested.
+#[derive(Debug)]
+pub enum DataError {
+    Io(io::Error),
+    /// A malformed CSV file, at the given line.
+    Csv {
+        line: usize,
+        msg: String,
+    },
+    /// A column that the policy needs from this party is missing.
+    MissingColumn(String),
+    /// A cell that could not be encoded, at the given (0-based) row.
+    Encode {
+        column: String,
+        row: usize,
+        err: FixedPointError,
+    },
+    /// A public column differs between parties.
+    PublicMismatch(String),
+    /// The owner of a shared column could not share it (and returned why).
+    OwnerFailed {
+        column: String,
+        owner: usize,
+    },
+    /// A Parquet file that could not be read.
+    Parquet(String),
+    /// A column with more cells than the commitment key has generators.
+    ColumnTooLong(usize),
+    /// A party whose seed does not match its commitment, when sampling.
+    BadSeed(usize),
+}
+
+impl Display for DataError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DataError::Io(e) => write!(f, "{}", e),
+            DataError::Csv { line, msg } => write!(f, "CSV line {}: {}", line, msg),
+            DataError::MissingColumn(c) => write!(f, "missing column {}", c),
+            DataError::Encode { column, row, err } => {
+                write!(f, "column {}, row {}: {}", column, row, err)
+            }
+            DataError::ColumnTooLong(n) => write!(f, "cannot commit to {} cells", n),
+            DataError::BadSeed(p) => write!(f, "party {} opened a different seed", p),
+            DataError::PublicMismatch(c) => {
+                write!(f, "public column {} differs between parties", c)
+            }
+            DataError::OwnerFailed { column, owner } => {
+                write!(f, "party {} could not share column {}", owner, column)
+            }
+            DataError::Parquet(msg) => write!(f, "Parquet: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for DataError {}
+
+impl From<io::Error> for DataError {
+    fn from(e: io::Error) -> Self {
+        DataError::Io(e)
+    }
+}
+
+/// A table of strings, with named columns.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(header: Vec<String>, rows: Vec<Vec<String>>) -> Self {
+        Self { header, rows }
+    }
+
+    /// Read a CSV file whose first row is the column names.
+    pub fn read_csv(path: impl AsRef<Path>) -> Result<Self, DataError> {
+        Self::from_csv_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Parse CSV per RFC 4180: fields may be quoted, with `""` for a quote inside a quoted field,
+    /// and lines may end in `\n` or `\r\n`. Blank lines are skipped.
+    pub fn from_csv_reader(mut reader: impl Read) -> Result<Self, DataError> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let mut records: Vec<(usize, Vec<String>)> = Vec::new();
+        let mut record = Vec::new();
+        let mut field = String::new();
+        let (mut line, mut record_line) = (1, 1);
+        let mut quoted = false;
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (quoted, c) {
+                (true, '"') if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                (true, '"') => quoted = false,
+                (false, '"') if field.is_empty() => quoted = true,
+                (false, ',') => record.push(std::mem::take(&mut field)),
+                (false, '\r') if chars.peek() == Some(&'\n') => {}
+                (false, '\n') => {
+                    if !(record.is_empty() && field.is_empty()) {
+                        record.push(std::mem::take(&mut field));
+                        records.push((record_line, std::mem::take(&mut record)));
+                    }
+                    line += 1;
+                    record_line = line;
+                }
+                (_, c) => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+        }
+        if quoted {
+            return Err(DataError::Csv {
+                line: record_line,
+                msg: "unterminated quote".into(),
+            });
+        }
+        if !(record.is_empty() && field.is_empty()) {
+            record.push(field);
+            records.push((record_line, record));
+        }
+        let mut records = records.into_iter();
+        let header = records.next().map(|(_, r)| r).unwrap_or_default();
+        let rows = records
+            .map(|(line, r)| {
+                if r.len() == header.len() {
+                    Ok(r)
+                } else {
+                    Err(DataError::Csv {
+                        line,
+                        msg: format!("{} fields, but {} columns", r.len(), header.len()),
+                    })
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { header, rows })
+    }
+
+    /// Read a Parquet file. Strings are taken as they are, numbers in decimal, and nulls as empty
+    /// cells; other types as Parquet displays them.
+    #[cfg(feature = "parquet")]
+    pub fn read_parquet(path: impl AsRef<Path>) -> Result<Self, DataError> {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        use parquet::record::Field;
+        let parquet_err = |e: parquet::errors::ParquetError| DataError::Parquet(e.to_string());
+        let reader = SerializedFileReader::new(File::open(path)?).map_err(parquet_err)?;
+        let header = reader
+            .metadata()
+            .file_metadata()
+            .schema()
+            .get_fields()
+            .iter()
+            .map(|f| f.name().to_owned())
+            .collect();
+        let rows = reader
+            .get_row_iter(None)
+            .map_err(parquet_err)?
+            .map(|row| {
+                let row = row.map_err(parquet_err)?;
+                Ok(row
+                    .get_column_iter()
+                    .map(|(_, cell)| match cell {
+                        Field::Null => String::new(),
+                        Field::Str(s) => s.clone(),
+                        // unlike Parquet's, Rust's display never uses an exponent
+                        Field::Float(x) => x.to_string(),
+                        Field::Double(x) => x.to_string(),
+                        cell => cell.to_string(),
+                    })
+                    .collect())
+            })
+            .collect::<Result<_, DataError>>()?;
+        Ok(Self { header, rows })
+    }
+
+    pub fn header(&self) -> &[String] {
+        &self.header
+    }
+
+    pub fn n_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The cells of column `name`.
+    pub fn column(&self, name: &str) -> Option<Vec<&str>> {
+        let i = self.header.iter().position(|h| h == name)?;
+        Some(self.rows.iter().map(|r| r[i].as_str()).collect())
+    }
+
+    fn encode_column<F: PrimeField>(
+        &self,
+        name: &str,
+        encoding: &FixedPoint,
+    ) -> Result<Vec<F>, DataError> {
+        let cells = self
+            .column(name)
+            .ok_or_else(|| DataError::MissingColumn(name.to_owned()))?;
+        cells
+            .into_iter()
+            .enumerate()
+            .map(|(row, cell)| {
+                encoding
+                    .encode_decimal(cell)
+                    .map_err(|err| DataError::Encode {
+                        column: name.to_owned(),
+                        row,
+                        err,
+                    })
+            })
+            .collect()
+    }
+}
+
+/// The columns of a table that the policy keeps, as public or shared field elements.
+#[derive(Clone, Debug)]
+pub struct Dataset<F: PrimeField, S: FieldShare<F>> {
+    columns: Vec<(String, Vec<MpcField<F, S>>)>,
+    /// The shared blinding factors of committed columns.
+    blindings: Vec<(String, MpcField<F, S>)>,
+}
+
+impl<F: PrimeField, S: FieldShare<F>> Dataset<F, S> {
+    /// Apply `policy` to this party's `table`.
+    ///
+    /// Every party must call this, with the same policy and encoding. Every party fails if a
+    /// column fails at any party: a public column at any, or a shared one at its owner.
+    pub fn ingest<R: Rng>(
+        table: &Table,
+        policy: &Policy,
+        encoding: &FixedPoint,
+        rng: &mut R,
+    ) -> Result<Self, DataError> {
+        let me = Net::party_id();
+        let mut columns = Vec::new();
+        for (name, p) in &policy.columns {
+            match *p {
+                ColumnPolicy::Public => {
+                    let values = table.encode_column::<F>(name, encoding);
+                    // a party that can't encode the column still takes part, with a zero digest,
+                    // since broadcasts must be of the same length at every party
+                    let digest = match &values {
+                        Ok(v) => {
+                            let mut bytes = Vec::new();
+                            v.serialize(&mut bytes).unwrap();
+                            Sha256::digest(&bytes).to_vec()
+                        }
+                        Err(_) => vec![0; 32],
+                    };
+                    let digests = Net::broadcast_bytes(&digest);
+                    let values = values?;
+                    if digests.iter().any(|d| d != &digest) {
+                        return Err(DataError::PublicMismatch(name.clone()));
+                    }
+                    columns.push((
+                        name.clone(),
+                        values.into_iter().map(MpcField::Public).collect(),
+                    ));
+                }
+                ColumnPolicy::SharedBy(owner) => {
+                    assert!(owner < Net::n_parties(), "no party {}", owner);
+                    let values = if me == owner {
+                        Some(table.encode_column::<F>(name, encoding))
+                    } else {
+                        None
+                    };
+                    let values = owner_result(name, owner, values)?.unwrap_or_default();
+                    columns.push((
+                        name.clone(),
+                        MpcField::party_share_batch(owner, values, rng),
+                    ));
+                }
+                ColumnPolicy::Drop => {}
+            }
+        }
+        Ok(Self {
+            columns,
+            blindings: Vec::new(),
+        })
+    }
+
+    pub fn column_names(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(|(n, _)| n.as_str())
+    }
+
+    pub fn column(&self, name: &str) -> Option<&[MpcField<F, S>]> {
+        self.columns
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, c)| &c[..])
+    }
+
+    /// Commit to each column that `policy` keeps, with `key`.
+    ///
+    /// The owner of each shared column commits to it with a random blinding factor, publishes the
+    /// commitment to the other parties, and shares the blinding factor, which this dataset keeps
+    /// for [Dataset::check_commitment]. Public columns are committed to without blinding, by every
+    /// party. Every party must call this, with the table, policy, and encoding they ingested.
+    pub fn commit_columns<G: ProjectiveCurve<ScalarField = F>, R: Rng>(
+        &mut self,
+        table: &Table,
+        policy: &Policy,
+        encoding: &FixedPoint,
+        key: &PedersenKey<G>,
+        rng: &mut R,
+    ) -> Result<Vec<ColumnCommitment<G>>, DataError> {
+        let me = Net::party_id();
+        let mut out = Vec::new();
+        for (name, p) in &policy.columns {
+            let len = match self.column(name) {
+                Some(c) => c.len(),
+                None => continue,
+            };
+            let (owner, commitment) = match *p {
+                ColumnPolicy::Public => {
+                    let values = table.encode_column::<F>(name, encoding)?;
+                    (None, key.commit(&values, F::zero())?)
+                }
+                ColumnPolicy::SharedBy(owner) => {
+                    let committed = if me == owner {
+                        let blinding = F::rand(rng);
+                        Some(
+                            table
+                                .encode_column::<F>(name, encoding)
+                                .and_then(|values| key.commit(&values, blinding))
+                                .map(|c| (blinding, c)),
+                        )
+                    } else {
+                        None
+                    };
+                    let (blinding, published) = match owner_result(name, owner, committed)? {
+                        Some((blinding, c)) => (vec![blinding], Some(vec![c; Net::n_parties()])),
+                        None => (Vec::new(), None),
+                    };
+                    let c: G::Affine = Net::recv_from(owner, published);
+                    let blinding = MpcField::party_share_batch(owner, blinding, rng);
+                    self.blindings.retain(|(n, _)| n != name);
+                    self.blindings.push((name.clone(), blinding[0]));
+                    (Some(owner), c)
+                }
+                ColumnPolicy::Drop => continue,
+            };
+            out.push(ColumnCommitment {
+                column: name.clone(),
+                owner,
+                len,
+                commitment,
+            });
+        }
+        Ok(out)
+    }
+
+    /// Check that this dataset's column is the one committed to in `com`, by computing the
+    /// commitment from the shares and opening it.
+    ///
+    /// Opening reveals nothing but the commitment itself. Every party must call this.
+    pub fn check_commitment<G, GS>(&self, com: &ColumnCommitment<G>, key: &PedersenKey<G>) -> bool
+    where
+        G: ProjectiveCurve<ScalarField = F>,
+        GS: GroupShare<G, FieldShare = S>,
+    {
+        let values = match self.column(&com.column) {
+            Some(v) if v.len() == com.len && v.len() <= key.bases.len() => v.to_vec(),
+            _ => return false,
+        };
+        let blinding = self
+            .blindings
+            .iter()
+            .find(|(n, _)| n == &com.column)
+            .map(|(_, b)| *b)
+            .unwrap_or_else(MpcField::zero);
+        let bases: Vec<G> = key.bases[..values.len()]
+            .iter()
+            .chain(std::iter::once(&key.blinding_base))
+            .map(|b| b.into_projective())
+            .collect();
+        let mut scalars = values;
+        scalars.push(blinding);
+        let commitment = match MpcField::all_public_or_shared(scalars.iter().cloned()) {
+            Ok(public) => key.commit(&public[..public.len() - 1], public[public.len() - 1]),
+            // public cells (and a missing blinding) are shared trivially
+            Err(_) => {
+                let shares: Vec<S> = scalars
+                    .into_iter()
+                    .map(|x| match x {
+                        MpcField::Shared(s) => s,
+                        MpcField::Public(x) => S::from_public(x),
+                    })
+                    .collect();
+                Ok(GS::multi_scale_pub_group(&bases, &shares)
+                    .reveal()
+                    .into_affine())
+            }
+        };
+        commitment.map_or(false, |c| c == com.commitment)
+    }
+
+    /// Add (or replace) a column, such as a mask from [sample_mask].
+    pub fn add_column(&mut self, name: &str, values: Vec<MpcField<F, S>>) {
+        self.columns.retain(|(n, _)| n != name);
+        self.columns.push((name.to_owned(), values));
+    }
+
+    /// The given rows of every column, in the given order.
+    ///
+    /// Commitments to the full columns do not carry over to the selection.
+    pub fn select_rows(&self, rows: &[usize]) -> Self {
+        let columns = self
+            .columns
+            .iter()
+            .map(|(n, c)| (n.clone(), rows.iter().map(|r| c[*r]).collect()))
+            .collect();
+        Self {
+            columns,
+            blindings: Vec::new(),
+        }
+    }
+
+    /// The witness for a circuit over the given columns: row by row, the cells of each column in
+    /// turn. Columns of different lengths are truncated to the shortest.
+    pub fn witness(&self, names: &[&str]) -> Result<Vec<MpcField<F, S>>, DataError> {
+        let columns = names
+            .iter()
+            .map(|n| {
+                self.column(n)
+                    .ok_or_else(|| DataError::MissingColumn((*n).to_owned()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let rows = columns.iter().map(|c| c.len()).min().unwrap_or(0);
+        Ok((0..rows)
+            .flat_map(|r| columns.iter().map(move |c| c[r]))
+            .collect())
+    }
+}
+
+/// Tell every party whether the owner of column `name` succeeded, given its `result` (`None` at
+/// the other parties). Every party then gets the owner's value (`None` elsewhere), or the owner's
+/// error ([DataError::OwnerFailed] elsewhere).
+fn owner_result<T>(
+    name: &str,
+    owner: usize,
+    result: Option<Result<T, DataError>>,
+) -> Result<Option<T>, DataError> {
+    let ok = result.as_ref().map(|r| vec![r.is_ok(); Net::n_parties()]);
+    match (Net::recv_from(owner, ok), result) {
+        (_, Some(r)) => r.map(Some),
+        (true, None) => Ok(None),
+        (false, None) => Err(DataError::OwnerFailed {
+            column: name.to_owned(),
+            owner,
+        }),
+    }
+}
+
+/// A random seed that no party controls, by coin tossing.
+///
+/// Each party commits to a random seed of its own, then opens it, and the result is their XOR. A
+/// party can still bias the result by aborting once it has seen the others' seeds, but it cannot
+/// choose it. Every party must call this.
+pub fn joint_seed<R: Rng>(rng: &mut R) -> Result<[u8; 32], DataError> {
+    let mine: [u8; 32] = rng.gen();
+    let commitments = Net::broadcast_bytes(&Sha256::digest(&mine));
+    let seeds = Net::broadcast_bytes(&mine);
+    let mut seed = [0u8; 32];
+    for (party, (c, s)) in commitments.iter().zip(&seeds).enumerate() {
+        if s.len() != 32 || Sha256::digest(s)[..] != c[..] {
+            return Err(DataError::BadSeed(party));
+        }
+        for (x, y) in seed.iter_mut().zip(s) {
+            *x ^= y;
+        }
+    }
+    Ok(seed)
+}
+
+/// `k` distinct rows out of `n`, in increasing order, drawn uniformly with the seed.
+pub fn sample_indices(seed: [u8; 32], n: usize, k: usize) -> Vec<usize> {
+    assert!(k <= n, "cannot sample {} rows out of {}", k, n);
+    let mut rng = StdRng::from_seed(seed);
+    // a partial Fisher-Yates shuffle
+    let mut rows: Vec<usize> = (0..n).collect();
+    for i in 0..k {
+        let j = rng.gen_range(i, n);
+        rows.swap(i, j);
+    }
+    rows.truncate(k);
+    rows.sort_unstable();
+    rows
+}
+
+/// A hidden random sample of `n` rows, as a shared 0/1 mask: each row is sampled independently,
+/// with probability `rate`, rounded to a multiple of `2^-32`.
+///
+/// Each row draws 32 shared random bits `b`, read as an integer, and is sampled if that is less
+/// than `rate * 2^32`. The bits are drawn together, and the comparison runs from the least
+/// significant bit up, at one batched multiplication per bit. Every party must call this.
+pub fn sample_mask<F, S, R>(n: usize, rate: f64, rng: &mut R) -> Vec<MpcField<F, S>>
+where
+    F: PrimeField + SquareRootField,
+    S: FieldShare<F>,
+    R: Rng,
+{
+    assert!(
+        (0.0..=1.0).contains(&rate),
+        "sampling rate {} is not a probability",
+        rate
+    );
+    let threshold = (rate * 2f64.powi(32)).round() as u64;
+    if threshold == 0 {
+        return vec![MpcField::zero(); n];
+    }
+    if threshold >> 32 != 0 {
+        return vec![MpcField::one(); n];
+    }
+    // below the lowest set bit of the threshold, "less than" is always false, so at that bit it is
+    // just whether the row's bit is clear
+    let low = threshold.trailing_zeros();
+    let all_bits = dp::random_bits::<F, S, R>(n * (32 - low) as usize, rng);
+    let one = MpcField::<F, S>::one();
+    let mut chunks = all_bits.chunks(n.max(1));
+    let mut less: Vec<MpcField<F, S>> = chunks
+        .next()
+        .unwrap_or(&[])
+        .iter()
+        .map(|b| one - b)
+        .collect();
+    let mut scratch = MpcMulScratch::default();
+    for (j, bits) in (low + 1..32).zip(chunks) {
+        // if the bits differ, the threshold's decides; if not, the lower bits do
+        less = if threshold >> j & 1 == 1 {
+            // (1 - b) + b * less
+            let mut prod = bits.to_vec();
+            MpcField::batch_product_in_place_with(&mut prod, &less, &mut scratch);
+            bits.iter().zip(prod).map(|(b, p)| one - b + p).collect()
+        } else {
+            // (1 - b) * less
+            let mut prod: Vec<_> = bits.iter().map(|b| one - b).collect();
+            MpcField::batch_product_in_place_with(&mut prod, &less, &mut scratch);
+            prod
+        };
+    }
+    less
+}
+
+/// Public generators for Pedersen commitments to columns of up to some length.
+///
+/// The generators are hashed to the curve from a label, so that nobody knows their discrete logs
+/// with respect to each other: by try-and-increment ([PedersenKey::new]), or, on the curves with a
+/// standard hash to the curve, by that ([PedersenKey::derive_bases]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct PedersenKey<G: ProjectiveCurve> {
+    pub bases: Vec<G::Affine>,
+    pub blinding_base: G::Affine,
+}
+
+impl<G: ProjectiveCurve> PedersenKey<G> {
+    /// Generators for columns of up to `len` cells, derived from `label`.
+    pub fn new(label: &[u8], len: usize) -> Self {
+        let mut counter = 0u64;
+        let mut next = || loop {
+            let hash = Sha256::new()
+                .chain(b"pedersen")
+                .chain(label)
+                .chain(&counter.to_le_bytes())
+                .finalize();
+            counter += 1;
+            if let Some(p) = G::Affine::from_random_bytes(&hash) {
+                let p = p.mul_by_cofactor();
+                if !p.is_zero() {
+                    return p;
+                }
+            }
+        };
+        let blinding_base = next();
+        let bases = (0..len).map(|_| next()).collect();
+        Self {
+            bases,
+            blinding_base,
+        }
+    }
+
+    /// `Σ values_i G_i + blinding H`
+    pub fn commit(
+        &self,
+        values: &[G::ScalarField],
+        blinding: G::ScalarField,
+    ) -> Result<G::Affine, DataError> {
+        if values.len() > self.bases.len() {
+            return Err(DataError::ColumnTooLong(values.len()));
+        }
+        let mut c =
+            <G::Affine as AffineCurve>::multi_scalar_mul(&self.bases[..values.len()], values);
+        c += &self.blinding_base.mul(blinding.into_repr());
+        Ok(c.into_affine())
+    }
+}
+
+impl<P: WBParams> PedersenKey<GroupProjective<P>> {
+    /// Generators for columns of up to `n` cells, hashed to the curve under `domain_tag` as RFC
+    /// 9380 hashes (see [hashing::derive_bases]); the blinding base is the first.
+    ///
+    /// Unlike [PedersenKey::new], anyone with an implementation of the RFC can derive them.
+    pub fn derive_bases(domain_tag: &[u8], n: usize) -> Result<Self, HashToCurveError> {
+        let mut bases = hashing::derive_bases::<P>(domain_tag, n + 1)?;
+        let blinding_base = bases.remove(0);
+        Ok(Self {
+            bases,
+            blinding_base,
+        })
+    }
+}
+
+/// A commitment to one column of a dataset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnCommitment<G: ProjectiveCurve> {
+    pub column: String,
+    /// The party who committed, or `None` for a public column.
+    pub owner: Option<usize>,
+    /// The number of cells.
+    pub len: usize,
+    pub commitment: G::Affine,
+}
@@ -0,0 +1,373 @@
+//! Three small circuits that show collaborative proving end to end, for `proof demo`.
+//!
+//! * [MultiplicationCircuit]: party 0 knows `a` and party 1 knows `b`, and together they prove
+//!   that `ab` is the public `c`, while neither learns the other's factor.
+//! * [CommitmentCircuit]: party 0 has published a Pedersen commitment on a twisted Edwards curve
+//!   whose base field is the circuit's (such as Edwards-BLS12 for BLS12-377), and the parties
+//!   prove that it opens to a value of at most [VALUE_BITS] bits, without learning the value.
+//! * [MembershipCircuit]: party 1 knows a leaf of a Merkle tree of [mimc_hash]es, and the parties
+//!   prove that it is in the tree with the public root, without learning which leaf it is.
+//!
+//! Each circuit is generic over the field, and its `share` method lifts a plain circuit, with the
+//! witness at its owners, to [MpcField]. [run_local] plays every party in this process, connected
+//! in memory, and [prove] is what each of them runs.
+//!
+//! The keys come from a setup with a fixed seed, which every party repeats, so they are only fit
+//! for a demonstration.
+use ark_ec::models::twisted_edwards_extended::{GroupAffine, GroupProjective};
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve, TEModelParameters};
+use ark_ff::{BigInteger, Field, One, PrimeField};
+use ark_groth16::Proof;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use derivative::Derivative;
+use mpc_algebra::shared_rng::SharedRng;
+use mpc_algebra::{FieldShare, MpcEdwardsParameters, MpcField, PairingShare, Reveal};
+use mpc_net::{MpcSession, NetConfig};
+use rand::Rng;
+
+use std::thread;
+
+use crate::data::PedersenKey;
+use crate::schnorr::{coords, hash_gadget, mimc_hash, Num, PointNum};
+use crate::{
+    prove_collaborative, reveal_proof, setup, share_proving_key, verify, ProveError, ProveOptions,
+};
+
+/// Proves that the public `c` is the product of the secret `a` and `b`.
+#[derive(Clone)]
+pub struct MultiplicationCircuit<F: Field> {
+    pub a: Option<F>,
+    pub b: Option<F>,
+}
+
+impl<F: PrimeField> MultiplicationCircuit<F> {
+    /// Lift this circuit to [MpcField]: `a` is party 0's and `b` is party 1's. Every party must
+    /// call this.
+    pub fn share<S: FieldShare<F>, R: Rng>(
+        &self,
+        rng: &mut R,
+    ) -> MultiplicationCircuit<MpcField<F, S>> {
+        let a = MpcField::party_share_batch(0, vec![self.a.unwrap_or_default()], rng);
+        let b = MpcField::party_share_batch(1, vec![self.b.unwrap_or_default()], rng);
+        MultiplicationCircuit {
+            a: Some(a[0]),
+            b: Some(b[0]),
+        }
+    }
+}
+
+impl<F: Field> ConstraintSynthesizer<F> for MultiplicationCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let cs = &cs;
+        let a = Num::witness(cs, self.a)?;
+        let b = Num::witness(cs, self.b)?;
+        let c = Num::input(cs, self.a.and_then(|a| self.b.map(|b| a * b)))?;
+        cs.enforce_constraint(a.lc, b.lc, c.lc)
+    }
+}
+
+/// The number of bits of a value committed to with [commit].
+pub const VALUE_BITS: usize = 64;
+
+/// The generators `G` and `H` of the demo's Pedersen commitments, hashed to the curve.
+pub fn commitment_key<P: TEModelParameters>() -> (GroupAffine<P>, GroupAffine<P>) {
+    let key = PedersenKey::<GroupProjective<P>>::new(b"mpc-snarks demo", 1);
+    (key.bases[0], key.blinding_base)
+}
+
+/// `mG + rH`, for the value `m` and the blinding `r`.
+pub fn commit<P: TEModelParameters>(value: u64, blinding: &P::ScalarField) -> GroupAffine<P> {
+    let (g, h) = commitment_key::<P>();
+    (g.mul(P::ScalarField::from(value)) + &h.mul(*blinding)).into_affine()
+}
+
+/// `base`, `2 base`, `4 base`, and so on, `n` of them.
+fn doublings<P: TEModelParameters>(base: GroupAffine<P>, n: usize) -> Vec<GroupAffine<P>> {
+    let mut p = base.into_projective();
+    let powers: Vec<GroupProjective<P>> = (0..n)
+        .map(|_| {
+            let q = p;
+            p.double_in_place();
+            q
+        })
+        .collect();
+    GroupProjective::batch_normalization_into_affine(&powers)
+}
+
+/// Proves that the public `commitment` opens to a value of at most [VALUE_BITS] bits.
+///
+/// Its public inputs are the coordinates of the commitment.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct CommitmentCircuit<P: TEModelParameters> {
+    /// `2^i G` for each bit of the value, and then `2^i H` for each bit of the blinding.
+    pub powers: Vec<GroupAffine<P>>,
+    pub commitment: Option<GroupAffine<P>>,
+    /// The bits of the value, and then those of the blinding, from the least significant.
+    pub bits: Vec<Option<P::BaseField>>,
+}
+
+impl<P: TEModelParameters> CommitmentCircuit<P>
+where
+    P::BaseField: PrimeField,
+{
+    /// The circuit for `commitment`, with its opening (the value and the blinding), if given.
+    pub fn new(commitment: GroupAffine<P>, opening: Option<(u64, P::ScalarField)>) -> Self {
+        let (g, h) = commitment_key::<P>();
+        let blinding_bits = P::ScalarField::size_in_bits();
+        let mut powers = doublings(g, VALUE_BITS);
+        powers.extend(doublings(h, blinding_bits));
+        let bits = match opening {
+            Some((value, blinding)) => (0..VALUE_BITS)
+                .map(|i| value >> i & 1 == 1)
+                .chain(
+                    blinding
+                        .into_repr()
+                        .to_bits_le()
+                        .into_iter()
+                        .take(blinding_bits),
+                )
+                .map(|b| Some(P::BaseField::from(b)))
+                .collect(),
+            None => vec![None; VALUE_BITS + blinding_bits],
+        };
+        Self {
+            powers,
+            commitment: Some(commitment),
+            bits,
+        }
+    }
+
+    /// The public inputs of this circuit, to verify its proofs with.
+    pub fn public_inputs(&self) -> Vec<P::BaseField> {
+        let c = self.commitment.expect("the commitment");
+        vec![c.x, c.y]
+    }
+
+    /// Lift this circuit to [MpcField]: the opening is party `owner`'s. Every party must call
+    /// this.
+    pub fn share<S: FieldShare<P::BaseField>, R: Rng>(
+        &self,
+        owner: usize,
+        rng: &mut R,
+    ) -> CommitmentCircuit<MpcEdwardsParameters<P, S>> {
+        let bits = self.bits.iter().map(|b| b.unwrap_or_default()).collect();
+        CommitmentCircuit {
+            powers: self
+                .powers
+                .iter()
+                .map(|p| Reveal::from_public(*p))
+                .collect(),
+            commitment: self.commitment.map(Reveal::from_public),
+            bits: MpcField::party_share_batch(owner, bits, rng)
+                .into_iter()
+                .map(Some)
+                .collect(),
+        }
+    }
+}
+
+impl<P: TEModelParameters> ConstraintSynthesizer<P::BaseField> for CommitmentCircuit<P> {
+    fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<P::BaseField>,
+    ) -> Result<(), SynthesisError> {
+        let cs = &cs;
+        let commitment = PointNum::alloc(cs, coords(&self.commitment), Num::input)?;
+        let one = Num::constant(P::BaseField::one());
+        let mut acc = PointNum::identity();
+        for (power, bit) in self.powers.iter().zip(&self.bits) {
+            let b = Num::witness(cs, *bit)?;
+            cs.enforce_constraint(b.lc.clone(), b.lc.clone(), b.lc.clone())?;
+            // `power` if the bit is set, and the identity otherwise, which is linear in the bit
+            let term = PointNum {
+                x: b.scale(power.x),
+                y: b.scale(power.y - P::BaseField::one()).add(&one),
+            };
+            acc = acc.add::<P>(&term, cs)?;
+        }
+        acc.enforce_equal(&commitment, cs)
+    }
+}
+
+/// A Merkle tree of [mimc_hash]es over a power-of-two number of values.
+///
+/// A leaf is the hash of its value alone, and an inner node that of its two children.
+#[derive(Clone, Debug)]
+pub struct MerkleTree<F: Field> {
+    /// The nodes, level by level from the leaves.
+    levels: Vec<Vec<F>>,
+}
+
+impl<F: Field> MerkleTree<F> {
+    pub fn new(values: &[F]) -> Self {
+        assert!(
+            values.len().is_power_of_two(),
+            "a Merkle tree of {} values",
+            values.len()
+        );
+        let mut levels = vec![values.iter().map(|v| mimc_hash(&[*v])).collect::<Vec<_>>()];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels.last().unwrap().chunks(2).map(mimc_hash).collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    pub fn root(&self) -> F {
+        self.levels.last().unwrap()[0]
+    }
+
+    /// The number of levels below the root.
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// The siblings on the path from leaf `index` to the root, from the leaf up.
+    pub fn path(&self, index: usize) -> Vec<F> {
+        self.levels[..self.depth()]
+            .iter()
+            .enumerate()
+            .map(|(l, level)| level[(index >> l) ^ 1])
+            .collect()
+    }
+}
+
+/// Proves that a secret value is a leaf of the Merkle tree with the public `root`.
+///
+/// Its only public input is the root.
+#[derive(Clone)]
+pub struct MembershipCircuit<F: Field> {
+    pub root: Option<F>,
+    pub value: Option<F>,
+    /// The siblings on the leaf's path to the root, from the leaf up.
+    pub siblings: Vec<Option<F>>,
+    /// For each of the siblings, `1` if it is on the left, and `0` if on the right.
+    pub directions: Vec<Option<F>>,
+}
+
+impl<F: PrimeField> MembershipCircuit<F> {
+    /// The circuit for `tree`, with the witness that leaf `index` holds `value`, if given.
+    pub fn new(tree: &MerkleTree<F>, leaf: Option<(usize, F)>) -> Self {
+        let depth = tree.depth();
+        let (value, siblings, directions) = match leaf {
+            Some((index, value)) => (
+                Some(value),
+                tree.path(index).into_iter().map(Some).collect(),
+                (0..depth)
+                    .map(|l| Some(F::from((index >> l & 1) as u64)))
+                    .collect(),
+            ),
+            None => (None, vec![None; depth], vec![None; depth]),
+        };
+        Self {
+            root: Some(tree.root()),
+            value,
+            siblings,
+            directions,
+        }
+    }
+
+    /// The public inputs of this circuit, to verify its proofs with.
+    pub fn public_inputs(&self) -> Vec<F> {
+        vec![self.root.expect("the root")]
+    }
+
+    /// Lift this circuit to [MpcField]: the leaf and its path are party `owner`'s. Every party
+    /// must call this.
+    pub fn share<S: FieldShare<F>, R: Rng>(
+        &self,
+        owner: usize,
+        rng: &mut R,
+    ) -> MembershipCircuit<MpcField<F, S>> {
+        let depth = self.siblings.len();
+        let private = std::iter::once(&self.value)
+            .chain(&self.siblings)
+            .chain(&self.directions)
+            .map(|x| x.unwrap_or_default())
+            .collect();
+        let mut private = MpcField::party_share_batch(owner, private, rng).into_iter();
+        MembershipCircuit {
+            root: self.root.map(MpcField::from_public),
+            value: private.next(),
+            siblings: private.by_ref().take(depth).map(Some).collect(),
+            directions: private.map(Some).collect(),
+        }
+    }
+}
+
+impl<F: Field> ConstraintSynthesizer<F> for MembershipCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let cs = &cs;
+        let root = Num::input(cs, self.root)?;
+        let mut node = hash_gadget(&[Num::witness(cs, self.value)?], cs)?;
+        for (sibling, direction) in self.siblings.iter().zip(&self.directions) {
+            let sibling = Num::witness(cs, *sibling)?;
+            let d = Num::witness(cs, *direction)?;
+            cs.enforce_constraint(d.lc.clone(), d.lc.clone(), d.lc.clone())?;
+            // swap the node and its sibling if the sibling is on the left
+            let swap = d.mul(&sibling.sub(&node), cs)?;
+            let (left, right) = (node.add(&swap), sibling.sub(&swap));
+            node = hash_gadget(&[left, right], cs)?;
+        }
+        node.enforce_equal(&root, cs)
+    }
+}
+
+/// A proof from [prove], revealed, and whether it verified against its public inputs.
+#[derive(Clone, Debug)]
+pub struct Proven<E: PairingEngine> {
+    pub proof: Proof<E>,
+    pub inputs: Vec<E::Fr>,
+    pub verified: bool,
+}
+
+/// Set up keys for `blank` (the circuit without a witness), prove `circuit` together with the
+/// other parties, and check the revealed proof against `inputs`.
+///
+/// Every party must call this, with `circuit` lifted by the same `share`. The keys are drawn from a
+/// [SharedRng], so every party has the same, and the prover's blinding from `rng`, which must be
+/// this party's own.
+pub fn prove<E, S, B, C, R>(
+    blank: B,
+    circuit: C,
+    inputs: Vec<E::Fr>,
+    rng: &mut R,
+) -> Result<Proven<E>, ProveError>
+where
+    E: PairingEngine,
+    S: PairingShare<E>,
+    B: ConstraintSynthesizer<E::Fr>,
+    C: ConstraintSynthesizer<MpcField<E::Fr, S::FrShare>>,
+    R: Rng,
+{
+    let pk = setup::<E, _, _>(blank, &mut SharedRng::new(b"demo setup"))?;
+    let mpc_pk = share_proving_key::<E, S>(&pk);
+    let (proof, _) = prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default())?;
+    let proof = reveal_proof::<E, S>(proof);
+    let verified = verify(&pk.vk, &proof, &inputs)?;
+    Ok(Proven {
+        proof,
+        inputs,
+        verified,
+    })
+}
+
+/// Run `f` as each of `n` parties, on threads of this process connected in memory, and return
+/// the parties' results in order.
+///
+/// If a party panics, the others abort, and this returns the first party's panic.
+pub fn run_local<T: Send>(n: usize, f: impl Fn() -> T + Sync) -> thread::Result<Vec<T>> {
+    let sessions = MpcSession::in_memory(n, NetConfig::default());
+    let f = &f;
+    thread::scope(|s| {
+        let handles: Vec<_> = sessions
+            .into_iter()
+            // the session moves in, so a party that panics hangs up on the others
+            .map(|session| s.spawn(move || session.run(f)))
+            .collect();
+        // join every party before giving up on any
+        let results: Vec<_> = handles.into_iter().map(|h| h.join()).collect();
+        results.into_iter().collect()
+    })
+}
@@ -1,22 +1,44 @@
 use super::silly::MySillyCircuit;
 use ark_ec::PairingEngine;
 use ark_groth16::{generate_random_parameters, prepare_verifying_key, verify_proof, ProvingKey};
-use ark_std::{test_rng, UniformRand};
+use ark_std::rand::Rng;
+use ark_std::UniformRand;
 use mpc_algebra::Reveal;
 use mpc_algebra::*;
 
+pub mod aggregate;
+pub mod ceremony;
+pub mod check;
+pub mod checkpoint;
+pub mod export;
+pub mod import;
+pub mod inputs;
 pub mod prover;
+pub mod public_inputs;
 pub mod r1cs_to_qap;
+pub mod report;
+pub mod sliced_key;
+pub mod solidity;
+pub mod verifier;
 
-pub fn mpc_test_prove_and_verify<E: PairingEngine, S: PairingShare<E>>(n_iters: usize) {
-    let rng = &mut test_rng();
-
+/// Prove and verify `n_iters` proofs, drawing the keys and the verifier's coins from
+/// `public_rng`, which every party must draw alike, and the witnesses and blinding from `rng`,
+/// this party's own.
+pub fn mpc_test_prove_and_verify<E, S, P, R>(n_iters: usize, public_rng: &mut P, rng: &mut R)
+where
+    E: PairingEngine,
+    S: PairingShare<E>,
+    P: Rng,
+    R: Rng,
+{
     let params =
-        generate_random_parameters::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+        generate_random_parameters::<E, _, _>(MySillyCircuit { a: None, b: None }, public_rng)
+            .unwrap();
 
     let pvk = prepare_verifying_key::<E>(&params.vk);
     let mpc_params = ProvingKey::from_public(params);
 
+    let mut revealed = Vec::new();
     for _ in 0..n_iters {
         let a = MpcField::<E::Fr, S::FrShare>::rand(rng);
         let b = MpcField::<E::Fr, S::FrShare>::rand(rng);
@@ -30,13 +52,31 @@ pub fn mpc_test_prove_and_verify<E: PairingEngine, S: PairingShare<E>>(n_iters:
             },
             &mpc_params,
             rng,
+            prover::ProveOptions::default(),
         )
-        .unwrap();
+        .unwrap()
+        .0;
         let proof = mpc_proof.reveal();
         let pub_a = a.reveal();
         let pub_c = c.reveal();
 
         assert!(verify_proof(&pvk, &proof, &[pub_c]).unwrap());
         assert!(!verify_proof(&pvk, &proof, &[pub_a]).unwrap());
+        revealed.push((proof, vec![pub_c], pub_a));
+    }
+
+    let mut batch: Vec<_> = revealed.iter().map(|(p, c, _)| (p.clone(), c.clone())).collect();
+    assert!(verifier::verify_batch(&pvk, &batch, public_rng).unwrap());
+    if !batch.is_empty() {
+        // and so does one aggregate proof for all of them
+        let key = aggregate::AggregationKey::<E>::setup(batch.len(), public_rng);
+        let inputs: Vec<_> = batch.iter().map(|(_, c)| c.clone()).collect();
+        let agg = aggregate::aggregate_proofs(&key, &batch).unwrap();
+        let avk = key.verifying_key();
+        assert!(aggregate::verify_aggregate_proof(&avk, &pvk, &inputs, &agg).unwrap());
+    }
+    if let Some((_, _, pub_a)) = revealed.last() {
+        batch.last_mut().unwrap().1 = vec![*pub_a];
+        assert!(!verifier::verify_batch(&pvk, &batch, public_rng).unwrap());
     }
 }
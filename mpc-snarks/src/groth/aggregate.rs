@@ -0,0 +1,563 @@
+//! Aggregation of many (revealed) Groth16 proofs into one proof of logarithmic size, after
+//! [SnarkPack](https://eprint.iacr.org/2021/529).
+//!
+//! The `n` proofs `(A_i, B_i, C_i)` are committed to with pairing-based commitments. A random `r`
+//! drawn from those commitments collapses the `n` Groth16 checks into one:
+//! `Π e(A_i, B_i)^(r^i) = e(α, β)^(Σ r^i) e(Σ r^i IC_i, γ) e(Σ r^i C_i, δ)`. The aggregate proof
+//! then shows, with a generalized inner-product argument that halves the vectors in each round,
+//! that the claimed `Π e(A_i, B_i)^(r^i)` and `Σ r^i C_i` match the commitments. The commitment
+//! keys that the argument folds are checked at the end with KZG openings.
+//!
+//! Batches whose size is not a power of two are padded by repeating the last proof.
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use ark_groth16::{PreparedVerifyingKey, Proof};
+use ark_relations::r1cs::{Result as R1CSResult, SynthesisError};
+use ark_serialize::*;
+use ark_std::rand::Rng;
+use ark_std::{end_timer, start_timer, vec::Vec};
+use merlin::Transcript;
+
+/// The structured reference string for aggregating up to some number of proofs: powers of two
+/// secrets `a` and `b`, in both groups.
+///
+/// [AggregationKey::setup] samples the secrets locally, which is only sound if whoever runs it
+/// forgets them. In production, the powers should come from two independent powers-of-tau
+/// ceremonies.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregationKey<E: PairingEngine> {
+    /// `a^i G` for `i < 2n`.
+    pub g_alpha: Vec<E::G1Affine>,
+    /// `b^i G` for `i < 2n`.
+    pub g_beta: Vec<E::G1Affine>,
+    /// `a^i H` for `i < n`.
+    pub h_alpha: Vec<E::G2Affine>,
+    /// `b^i H` for `i < n`.
+    pub h_beta: Vec<E::G2Affine>,
+}
+
+/// What a verifier needs from the [AggregationKey].
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregationVerifyingKey<E: PairingEngine> {
+    pub g: E::G1Affine,
+    pub h: E::G2Affine,
+    pub g_alpha: E::G1Affine,
+    pub g_beta: E::G1Affine,
+    pub h_alpha: E::G2Affine,
+    pub h_beta: E::G2Affine,
+}
+
+fn powers<G: AffineCurve>(base: G, x: G::ScalarField, n: usize) -> Vec<G> {
+    let mut p = base.into_projective();
+    let mut out = Vec::with_capacity(n);
+    for _ in 0..n {
+        out.push(p);
+        p = p.mul(x.into_repr());
+    }
+    G::Projective::batch_normalization_into_affine(&out)
+}
+
+impl<E: PairingEngine> AggregationKey<E> {
+    /// Sample a key for aggregating up to `max_proofs` proofs.
+    pub fn setup<R: Rng>(max_proofs: usize, rng: &mut R) -> Self {
+        let n = padded_len(max_proofs);
+        let (a, b) = (E::Fr::rand(rng), E::Fr::rand(rng));
+        let g = E::G1Affine::prime_subgroup_generator();
+        let h = E::G2Affine::prime_subgroup_generator();
+        Self {
+            g_alpha: powers(g, a, 2 * n),
+            g_beta: powers(g, b, 2 * n),
+            h_alpha: powers(h, a, n),
+            h_beta: powers(h, b, n),
+        }
+    }
+
+    /// The most proofs this key can aggregate.
+    pub fn max_proofs(&self) -> usize {
+        self.h_alpha.len()
+    }
+
+    pub fn verifying_key(&self) -> AggregationVerifyingKey<E> {
+        AggregationVerifyingKey {
+            g: self.g_alpha[0],
+            h: self.h_alpha[0],
+            g_alpha: self.g_alpha[1],
+            g_beta: self.g_beta[1],
+            h_alpha: self.h_alpha[1],
+            h_beta: self.h_beta[1],
+        }
+    }
+}
+
+/// A pair of commitments to the same vectors, under the keys from `a` and from `b`.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PairCommitment<E: PairingEngine> {
+    pub t: E::Fqk,
+    pub u: E::Fqk,
+}
+
+impl<E: PairingEngine> PairCommitment<E> {
+    /// The commitment to the folded vectors: `self * up^x * down^(1/x)`.
+    fn fold(&self, up: &Self, down: &Self, x: E::Fr, x_inv: E::Fr) -> Self {
+        Self {
+            t: self.t * up.t.pow(x.into_repr()) * down.t.pow(x_inv.into_repr()),
+            u: self.u * up.u.pow(x.into_repr()) * down.u.pow(x_inv.into_repr()),
+        }
+    }
+}
+
+/// The cross terms of one halving round of the inner-product argument: `up` terms get weight
+/// `x` in the folded values, and `down` terms `1/x`.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct GipaRound<E: PairingEngine> {
+    pub ab_up: PairCommitment<E>,
+    pub ab_down: PairCommitment<E>,
+    pub z_ab_up: E::Fqk,
+    pub z_ab_down: E::Fqk,
+    pub c_up: PairCommitment<E>,
+    pub c_down: PairCommitment<E>,
+    pub z_c_up: E::G1Affine,
+    pub z_c_down: E::G1Affine,
+}
+
+/// A proof that a batch of Groth16 proofs verify.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregateProof<E: PairingEngine> {
+    pub com_ab: PairCommitment<E>,
+    pub com_c: PairCommitment<E>,
+    /// `Π e(A_i, B_i)^(r^i)`
+    pub z_ab: E::Fqk,
+    /// `Σ r^i C_i`
+    pub z_c: E::G1Affine,
+    pub rounds: Vec<GipaRound<E>>,
+    pub a: E::G1Affine,
+    pub b: E::G2Affine,
+    pub c: E::G1Affine,
+    pub v1: E::G2Affine,
+    pub v2: E::G2Affine,
+    pub w1: E::G1Affine,
+    pub w2: E::G1Affine,
+    pub v1_opening: E::G2Affine,
+    pub v2_opening: E::G2Affine,
+    pub w1_opening: E::G1Affine,
+    pub w2_opening: E::G1Affine,
+}
+
+fn padded_len(n: usize) -> usize {
+    n.max(2).next_power_of_two()
+}
+
+fn absorb<T: CanonicalSerialize>(t: &mut Transcript, label: &'static [u8], x: &T) {
+    let mut bytes = Vec::new();
+    x.serialize(&mut bytes).unwrap();
+    t.append_message(label, &bytes);
+}
+
+fn challenge<F: PrimeField>(t: &mut Transcript, label: &'static [u8]) -> F {
+    loop {
+        let mut bytes = [0u8; 64];
+        t.challenge_bytes(label, &mut bytes);
+        let x = F::from_le_bytes_mod_order(&bytes);
+        if !x.is_zero() {
+            return x;
+        }
+    }
+}
+
+fn start_transcript<E: PairingEngine>(
+    inputs: &[&Vec<E::Fr>],
+    com_ab: &PairCommitment<E>,
+    com_c: &PairCommitment<E>,
+) -> Transcript {
+    let mut t = Transcript::new(b"snarkpack");
+    absorb(&mut t, b"n", &(inputs.len() as u64));
+    for x in inputs {
+        absorb(&mut t, b"input", *x);
+    }
+    absorb(&mut t, b"com_ab", com_ab);
+    absorb(&mut t, b"com_c", com_c);
+    t
+}
+
+/// `Π e(P_i, Q_i)`
+fn pairing_product<'a, E: PairingEngine>(
+    pairs: impl Iterator<Item = (&'a E::G1Affine, &'a E::G2Affine)>,
+) -> E::Fqk {
+    let prepared: Vec<(E::G1Prepared, E::G2Prepared)> =
+        pairs.map(|(p, q)| ((*p).into(), (*q).into())).collect();
+    E::product_of_pairings(prepared.iter())
+}
+
+/// The commitment keys, as the argument folds them.
+struct Keys<E: PairingEngine> {
+    v1: Vec<E::G2Affine>,
+    v2: Vec<E::G2Affine>,
+    w1: Vec<E::G1Affine>,
+    w2: Vec<E::G1Affine>,
+}
+
+fn commit_ab<E: PairingEngine>(
+    a: &[E::G1Affine],
+    b: &[E::G2Affine],
+    v1: &[E::G2Affine],
+    v2: &[E::G2Affine],
+    w1: &[E::G1Affine],
+    w2: &[E::G1Affine],
+) -> PairCommitment<E> {
+    PairCommitment {
+        t: pairing_product::<E>(a.iter().zip(v1).chain(w1.iter().zip(b))),
+        u: pairing_product::<E>(a.iter().zip(v2).chain(w2.iter().zip(b))),
+    }
+}
+
+fn commit_c<E: PairingEngine>(
+    c: &[E::G1Affine],
+    v1: &[E::G2Affine],
+    v2: &[E::G2Affine],
+) -> PairCommitment<E> {
+    PairCommitment {
+        t: pairing_product::<E>(c.iter().zip(v1)),
+        u: pairing_product::<E>(c.iter().zip(v2)),
+    }
+}
+
+/// `(v_i * s_i)_i`
+fn scale<G: AffineCurve>(v: &[G], s: &[G::ScalarField]) -> Vec<G> {
+    let scaled: Vec<G::Projective> = v.iter().zip(s).map(|(g, s)| g.mul(s.into_repr())).collect();
+    G::Projective::batch_normalization_into_affine(&scaled)
+}
+
+/// `left + x * right`, for the two halves of `v`.
+fn fold<G: AffineCurve>(v: &[G], x: G::ScalarField) -> Vec<G> {
+    let (l, r) = v.split_at(v.len() / 2);
+    let folded: Vec<G::Projective> = l
+        .iter()
+        .zip(r)
+        .map(|(l, r)| r.mul(x.into_repr()) + l.into_projective())
+        .collect();
+    G::Projective::batch_normalization_into_affine(&folded)
+}
+
+/// `x^i` for `i < n`
+fn field_powers<F: Field>(x: F, n: usize) -> Vec<F> {
+    let mut p = F::one();
+    let mut out = Vec::with_capacity(n);
+    for _ in 0..n {
+        out.push(p);
+        p *= x;
+    }
+    out
+}
+
+fn sum<G: AffineCurve>(v: &[G]) -> G::Projective {
+    v.iter()
+        .fold(G::Projective::zero(), |acc, g| acc + g.into_projective())
+}
+
+/// The coefficients of `Π_j (1 + c_j X^(2^j))`.
+fn product_poly<F: Field>(cs: &[F]) -> Vec<F> {
+    let mut p = vec![F::one()];
+    for c in cs {
+        let high: Vec<F> = p.iter().map(|x| *x * c).collect();
+        p.extend(high);
+    }
+    p
+}
+
+/// `Π_j (1 + c_j z^(2^j))`
+fn eval_product_poly<F: Field>(cs: &[F], z: F) -> F {
+    let mut z_pow = z;
+    let mut out = F::one();
+    for c in cs {
+        out *= F::one() + *c * z_pow;
+        z_pow.square_in_place();
+    }
+    out
+}
+
+/// A KZG opening of the commitment to `poly` at `z`: a commitment to `(poly(X) - poly(z)) / (X - z)`.
+fn kzg_open<G: AffineCurve>(powers: &[G], poly: &[G::ScalarField], z: G::ScalarField) -> G {
+    let mut quotient = vec![G::ScalarField::zero(); poly.len() - 1];
+    let mut acc = G::ScalarField::zero();
+    for i in (1..poly.len()).rev() {
+        acc = poly[i] + z * acc;
+        quotient[i - 1] = acc;
+    }
+    G::multi_scalar_mul(&powers[..quotient.len()], &quotient).into_affine()
+}
+
+/// The coefficients `c_j` of the polynomials whose evaluations at the secrets are the final keys:
+/// the `v` keys are `Π_j (1 + c_j X^(2^j))` at `a` and `b`, and the `w` keys `X^n` times the same
+/// with `w_cs`. The rounds fold the longest halves first, so these run backwards.
+fn key_poly_coeffs<F: Field>(xs: &[F], r_inv: F) -> (Vec<F>, Vec<F>) {
+    let mut v_cs = Vec::with_capacity(xs.len());
+    let mut r_inv_pow = r_inv;
+    for x in xs.iter().rev() {
+        v_cs.push(x.inverse().unwrap() * r_inv_pow);
+        r_inv_pow.square_in_place();
+    }
+    let w_cs = xs.iter().rev().cloned().collect();
+    (v_cs, w_cs)
+}
+
+fn absorb_final<E: PairingEngine>(t: &mut Transcript, p: &AggregateProof<E>) {
+    absorb(t, b"a", &p.a);
+    absorb(t, b"b", &p.b);
+    absorb(t, b"c", &p.c);
+    absorb(t, b"v1", &p.v1);
+    absorb(t, b"v2", &p.v2);
+    absorb(t, b"w1", &p.w1);
+    absorb(t, b"w2", &p.w2);
+}
+
+/// Aggregate `proofs`, each paired with its instance, into one proof.
+///
+/// Fails if `key` is too small for this many proofs.
+pub fn aggregate_proofs<E: PairingEngine>(
+    key: &AggregationKey<E>,
+    proofs: &[(Proof<E>, Vec<E::Fr>)],
+) -> R1CSResult<AggregateProof<E>> {
+    assert!(!proofs.is_empty(), "no proofs to aggregate");
+    let n = padded_len(proofs.len());
+    if n > key.max_proofs() {
+        return Err(SynthesisError::PolynomialDegreeTooLarge);
+    }
+    let timer = start_timer!(|| format!("Aggregating {} proofs", proofs.len()));
+    let padded: Vec<&(Proof<E>, Vec<E::Fr>)> = proofs
+        .iter()
+        .chain(std::iter::repeat(proofs.last().unwrap()))
+        .take(n)
+        .collect();
+    let inputs: Vec<&Vec<E::Fr>> = padded.iter().map(|(_, x)| x).collect();
+    let mut a: Vec<E::G1Affine> = padded.iter().map(|(p, _)| p.a).collect();
+    let mut b: Vec<E::G2Affine> = padded.iter().map(|(p, _)| p.b).collect();
+    let mut c: Vec<E::G1Affine> = padded.iter().map(|(p, _)| p.c).collect();
+    let mut keys = Keys::<E> {
+        v1: key.h_alpha[..n].to_vec(),
+        v2: key.h_beta[..n].to_vec(),
+        w1: key.g_alpha[n..2 * n].to_vec(),
+        w2: key.g_beta[n..2 * n].to_vec(),
+    };
+
+    let com_ab = commit_ab::<E>(&a, &b, &keys.v1, &keys.v2, &keys.w1, &keys.w2);
+    let com_c = commit_c::<E>(&c, &keys.v1, &keys.v2);
+    let mut t = start_transcript(&inputs, &com_ab, &com_c);
+    let r: E::Fr = challenge(&mut t, b"r");
+    let r_inv = r.inverse().unwrap();
+
+    // Scale the proofs by r^i, and the v keys by r^-i, which leaves the commitments unchanged.
+    let r_pows = field_powers(r, n);
+    let r_inv_pows = field_powers(r_inv, n);
+    a = scale(&a, &r_pows);
+    c = scale(&c, &r_pows);
+    keys.v1 = scale(&keys.v1, &r_inv_pows);
+    keys.v2 = scale(&keys.v2, &r_inv_pows);
+    let z_ab = pairing_product::<E>(a.iter().zip(&b));
+    let z_c = sum(&c).into_affine();
+    absorb(&mut t, b"z_ab", &z_ab);
+    absorb(&mut t, b"z_c", &z_c);
+
+    // The multiplier of each C in the inner product, which is the same for all of them.
+    let mut s = E::Fr::one();
+    let mut rounds = Vec::new();
+    let mut xs = Vec::new();
+    while a.len() > 1 {
+        let m = a.len() / 2;
+        let (a_l, a_r) = a.split_at(m);
+        let (b_l, b_r) = b.split_at(m);
+        let (c_l, c_r) = c.split_at(m);
+        let (v1_l, v1_r) = keys.v1.split_at(m);
+        let (v2_l, v2_r) = keys.v2.split_at(m);
+        let (w1_l, w1_r) = keys.w1.split_at(m);
+        let (w2_l, w2_r) = keys.w2.split_at(m);
+        let round = GipaRound {
+            ab_up: commit_ab::<E>(a_r, b_l, v1_l, v2_l, w1_r, w2_r),
+            ab_down: commit_ab::<E>(a_l, b_r, v1_r, v2_r, w1_l, w2_l),
+            z_ab_up: pairing_product::<E>(a_r.iter().zip(b_l)),
+            z_ab_down: pairing_product::<E>(a_l.iter().zip(b_r)),
+            c_up: commit_c::<E>(c_r, v1_l, v2_l),
+            c_down: commit_c::<E>(c_l, v1_r, v2_r),
+            z_c_up: sum(c_r).mul(s.into_repr()).into_affine(),
+            z_c_down: sum(c_l).mul(s.into_repr()).into_affine(),
+        };
+        absorb(&mut t, b"round", &round);
+        let x: E::Fr = challenge(&mut t, b"x");
+        let x_inv = x.inverse().unwrap();
+        a = fold(&a, x);
+        b = fold(&b, x_inv);
+        c = fold(&c, x);
+        keys.v1 = fold(&keys.v1, x_inv);
+        keys.v2 = fold(&keys.v2, x_inv);
+        keys.w1 = fold(&keys.w1, x);
+        keys.w2 = fold(&keys.w2, x);
+        s *= E::Fr::one() + x_inv;
+        rounds.push(round);
+        xs.push(x);
+    }
+
+    let mut proof = AggregateProof {
+        com_ab,
+        com_c,
+        z_ab,
+        z_c,
+        rounds,
+        a: a[0],
+        b: b[0],
+        c: c[0],
+        v1: keys.v1[0],
+        v2: keys.v2[0],
+        w1: keys.w1[0],
+        w2: keys.w2[0],
+        v1_opening: E::G2Affine::zero(),
+        v2_opening: E::G2Affine::zero(),
+        w1_opening: E::G1Affine::zero(),
+        w2_opening: E::G1Affine::zero(),
+    };
+    absorb_final(&mut t, &proof);
+    let z: E::Fr = challenge(&mut t, b"z");
+    let (v_cs, w_cs) = key_poly_coeffs(&xs, r_inv);
+    let v_poly = product_poly(&v_cs);
+    let mut w_poly = vec![E::Fr::zero(); n];
+    w_poly.extend(product_poly(&w_cs));
+    proof.v1_opening = kzg_open(&key.h_alpha, &v_poly, z);
+    proof.v2_opening = kzg_open(&key.h_beta, &v_poly, z);
+    proof.w1_opening = kzg_open(&key.g_alpha, &w_poly, z);
+    proof.w2_opening = kzg_open(&key.g_beta, &w_poly, z);
+    end_timer!(timer);
+    Ok(proof)
+}
+
+/// Check that `proof` shows that a Groth16 proof for each of `public_inputs` verifies against
+/// `pvk`.
+pub fn verify_aggregate_proof<E: PairingEngine>(
+    avk: &AggregationVerifyingKey<E>,
+    pvk: &PreparedVerifyingKey<E>,
+    public_inputs: &[Vec<E::Fr>],
+    proof: &AggregateProof<E>,
+) -> R1CSResult<bool> {
+    assert!(!public_inputs.is_empty(), "no proofs to verify");
+    let n_inputs = pvk.vk.gamma_abc_g1.len();
+    if public_inputs.iter().any(|x| x.len() + 1 != n_inputs) {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+    let n = padded_len(public_inputs.len());
+    if 1 << proof.rounds.len() != n {
+        return Ok(false);
+    }
+    let timer = start_timer!(|| format!("Verifying aggregate of {}", public_inputs.len()));
+    let inputs: Vec<&Vec<E::Fr>> = public_inputs
+        .iter()
+        .chain(std::iter::repeat(public_inputs.last().unwrap()))
+        .take(n)
+        .collect();
+    let mut t = start_transcript(&inputs, &proof.com_ab, &proof.com_c);
+    let r: E::Fr = challenge(&mut t, b"r");
+    absorb(&mut t, b"z_ab", &proof.z_ab);
+    absorb(&mut t, b"z_c", &proof.z_c);
+
+    // The Groth16 equation, for all the proofs at once
+    let mut ic_scalars = vec![E::Fr::zero(); n_inputs];
+    let mut r_pow = E::Fr::one();
+    for x in &inputs {
+        ic_scalars[0] += r_pow;
+        for (s, x) in ic_scalars[1..].iter_mut().zip(x.iter()) {
+            *s += r_pow * x;
+        }
+        r_pow *= r;
+    }
+    let ic = <E::G1Affine as AffineCurve>::multi_scalar_mul(&pvk.vk.gamma_abc_g1, &ic_scalars);
+    let groth16 = proof.z_ab
+        * E::product_of_pairings(
+            [
+                (ic.into_affine().into(), pvk.gamma_g2_neg_pc.clone()),
+                (proof.z_c.into(), pvk.delta_g2_neg_pc.clone()),
+            ]
+            .iter(),
+        )
+        == pvk.alpha_g1_beta_g2.pow(ic_scalars[0].into_repr());
+
+    // Fold the claims as the prover folded the vectors
+    let mut com_ab = proof.com_ab.clone();
+    let mut com_c = proof.com_c.clone();
+    let mut z_ab = proof.z_ab;
+    let mut z_c = proof.z_c.into_projective();
+    let mut s = E::Fr::one();
+    let mut xs = Vec::new();
+    for round in &proof.rounds {
+        absorb(&mut t, b"round", round);
+        let x: E::Fr = challenge(&mut t, b"x");
+        let x_inv = x.inverse().unwrap();
+        com_ab = com_ab.fold(&round.ab_up, &round.ab_down, x, x_inv);
+        com_c = com_c.fold(&round.c_up, &round.c_down, x, x_inv);
+        z_ab *= round.z_ab_up.pow(x.into_repr()) * round.z_ab_down.pow(x_inv.into_repr());
+        z_c += round.z_c_up.mul(x.into_repr()) + round.z_c_down.mul(x_inv.into_repr());
+        s *= E::Fr::one() + x_inv;
+        xs.push(x);
+    }
+    let tipp = z_ab == E::pairing(proof.a, proof.b)
+        && com_ab
+            == commit_ab::<E>(
+                &[proof.a],
+                &[proof.b],
+                &[proof.v1],
+                &[proof.v2],
+                &[proof.w1],
+                &[proof.w2],
+            );
+    let mipp = z_c == proof.c.mul(s.into_repr())
+        && com_c == commit_c::<E>(&[proof.c], &[proof.v1], &[proof.v2]);
+
+    // The final keys are the folds of the real keys
+    absorb_final(&mut t, proof);
+    let z: E::Fr = challenge(&mut t, b"z");
+    let (v_cs, w_cs) = key_poly_coeffs(&xs, r.inverse().unwrap());
+    let v_at_z = eval_product_poly(&v_cs, z);
+    let w_at_z = eval_product_poly(&w_cs, z) * z.pow(&[n as u64]);
+    let (g, h) = (avk.g.into_projective(), avk.h.into_projective());
+    let g_z = g.mul(z.into_repr());
+    let h_z = h.mul(z.into_repr());
+    // e(secret * G - z * G, opening) = e(G, key - f(z) * H)
+    let v_ok = |opening: E::G2Affine, g_secret: E::G1Affine, key: E::G2Affine| {
+        E::product_of_pairings(
+            [
+                (
+                    (g_secret.into_projective() - g_z).into_affine().into(),
+                    opening.into(),
+                ),
+                (
+                    (-avk.g).into(),
+                    (key.into_projective() - h.mul(v_at_z.into_repr()))
+                        .into_affine()
+                        .into(),
+                ),
+            ]
+            .iter(),
+        )
+        .is_one()
+    };
+    // e(key - f(z) * G, H) = e(opening, secret * H - z * H)
+    let w_ok = |opening: E::G1Affine, h_secret: E::G2Affine, key: E::G1Affine| {
+        E::product_of_pairings(
+            [
+                (
+                    (key.into_projective() - g.mul(w_at_z.into_repr()))
+                        .into_affine()
+                        .into(),
+                    avk.h.into(),
+                ),
+                (
+                    (-opening).into(),
+                    (h_secret.into_projective() - h_z).into_affine().into(),
+                ),
+            ]
+            .iter(),
+        )
+        .is_one()
+    };
+    let keys = v_ok(proof.v1_opening, avk.g_alpha, proof.v1)
+        && v_ok(proof.v2_opening, avk.g_beta, proof.v2)
+        && w_ok(proof.w1_opening, avk.h_alpha, proof.w1)
+        && w_ok(proof.w2_opening, avk.h_beta, proof.w2);
+    end_timer!(timer);
+    Ok(groth16 && tipp && mipp && keys)
+}
@@ -0,0 +1,345 @@
+//! A trusted setup run by the parties together, so that none of them knows its toxic waste.
+//!
+//! Groth16 keys are made from five secret scalars: `α`, `β`, `γ`, `δ`, and the point `τ` at which
+//! the QAP's polynomials are evaluated. Anyone who learns them can forge proofs, so
+//! [setup](crate::setup) trusts whoever runs it to forget them. Here they are shared instead: each
+//! party draws its own shares of them, from [seed::rng], and the parties compute the key's scalars
+//! on shares, opening only the key's points. No party ever holds the toxic waste, so the key is
+//! sound as long as the share type's threshold holds: one honest party for additive and SPDZ
+//! shares, an honest majority for GSZ20.
+//!
+//! The key is the one [ark_groth16::generate_parameters] makes from the same scalars and the
+//! standard generators. The Lagrange coefficients at `τ` are the inverse FFT of `1, τ, τ^2, ...`,
+//! which is linear, so the parties compute those powers (one round of multiplication per doubling)
+//! and the rest of the QAP locally. Past that, the setup costs two inversions, two rounds of
+//! multiplication, and one opening of every point of the key.
+//!
+//! The parties can also build on a public powers-of-tau ceremony, with its many contributors,
+//! rather than trust only themselves for `τ`, `α` and `β`: [generate_parameters_from_powers]
+//! takes those from a [PowersOfTau], and has the parties draw only `δ`, which ties the key to the
+//! circuit.
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_relations::r1cs::{
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, OptimizationGoal,
+    Result as R1CSResult, SynthesisError, SynthesisMode,
+};
+use mpc_algebra::{seed, GroupShare, MpcField, PairingShare};
+use mpc_trait::MpcWire;
+
+use super::import::PowersOfTau;
+
+type Shared<E, S> = MpcField<<E as PairingEngine>::Fr, <S as PairingShare<E>>::FrShare>;
+
+/// `xs[i] * ys[i]` for each `i`, in one round.
+///
+/// Products with a public factor (such as the zero coefficients of variables a QAP polynomial
+/// skips) are local; the rest are multiplied in one batch, which needs its operands shared alike.
+fn products<E: PairingEngine, S: PairingShare<E>>(
+    xs: &[Shared<E, S>],
+    ys: &[Shared<E, S>],
+) -> Vec<Shared<E, S>> {
+    let (mut batch_xs, mut batch_ys, mut at) = (Vec::new(), Vec::new(), Vec::new());
+    let mut out: Vec<_> = xs
+        .iter()
+        .zip(ys)
+        .enumerate()
+        .map(|(i, (x, y))| {
+            if x.is_shared() && y.is_shared() {
+                batch_xs.push(*x);
+                batch_ys.push(*y);
+                at.push(i);
+                MpcField::zero()
+            } else {
+                *x * *y
+            }
+        })
+        .collect();
+    MpcField::batch_product_in_place(&mut batch_xs, &batch_ys);
+    for (i, p) in at.into_iter().zip(batch_xs) {
+        out[i] = p;
+    }
+    out
+}
+
+/// `1, τ, ..., τ^n`, in one round per doubling.
+fn powers<E: PairingEngine, S: PairingShare<E>>(tau: Shared<E, S>, n: usize) -> Vec<Shared<E, S>> {
+    let mut powers = vec![tau];
+    while powers.len() < n {
+        let top = *powers.last().unwrap();
+        let next = products::<E, S>(&powers, &vec![top; powers.len()]);
+        powers.extend(next);
+    }
+    powers.truncate(n);
+    powers.insert(0, MpcField::one());
+    powers
+}
+
+/// `x P` for each point `P` and scalar `x`, opening the shared ones in one batch.
+fn open_points<G, GS>(terms: &[(G, MpcField<G::ScalarField, GS::FieldShare>)]) -> Vec<G::Affine>
+where
+    G: ProjectiveCurve,
+    GS: GroupShare<G>,
+{
+    let shared: Vec<_> = terms
+        .iter()
+        .filter_map(|(p, x)| match x {
+            MpcField::Shared(s) => Some(GS::scale_pub_group(*p, s)),
+            MpcField::Public(_) => None,
+        })
+        .collect();
+    let mut opened = GS::batch_open(shared).into_iter();
+    let points: Vec<_> = terms
+        .iter()
+        .map(|(p, x)| match x {
+            MpcField::Shared(_) => opened.next().unwrap(),
+            MpcField::Public(x) => p.mul(x.into_repr()),
+        })
+        .collect();
+    G::batch_normalization_into_affine(&points)
+}
+
+/// The matrices of `circuit`, synthesized for setup.
+fn synthesize<F: Field, C: ConstraintSynthesizer<F>>(
+    circuit: C,
+) -> R1CSResult<ConstraintMatrices<F>> {
+    let cs = ConstraintSystem::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::Constraints);
+    cs.set_mode(SynthesisMode::Setup);
+    circuit.generate_constraints(cs.clone())?;
+    cs.finalize();
+    Ok(cs.to_matrices().unwrap())
+}
+
+/// Generate Groth16 keys for `circuit`, which need not have a witness, together with the other
+/// parties. Every party must call this, with the same circuit, and gets the same key.
+pub fn generate_parameters<E, S, C>(circuit: C) -> R1CSResult<ProvingKey<E>>
+where
+    E: PairingEngine,
+    S: PairingShare<E>,
+    C: ConstraintSynthesizer<E::Fr>,
+{
+    let matrices = synthesize(circuit)?;
+    let num_constraints = matrices.num_constraints;
+    let num_instance = matrices.num_instance_variables;
+    let num_variables = num_instance + matrices.num_witness_variables;
+
+    let domain = GeneralEvaluationDomain::<Shared<E, S>>::new(num_constraints + num_instance)
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+    let n = domain.size();
+
+    let rng = &mut seed::rng(b"toxic waste");
+    let mut waste: Vec<Shared<E, S>> = (0..5).map(|_| MpcField::rand(rng)).collect();
+    // a τ in the domain, which would make the vanishing polynomial zero there, is negligibly
+    // unlikely
+    let tau = waste.pop().unwrap();
+    let (alpha, beta, gamma, delta) = (waste[0], waste[1], waste[2], waste[3]);
+
+    let tau_powers = powers::<E, S>(tau, n);
+    let zt = tau_powers[n] - MpcField::one();
+    let u = domain.ifft(&tau_powers[..n]);
+
+    let mut a = vec![Shared::<E, S>::zero(); num_variables];
+    let mut b = vec![Shared::<E, S>::zero(); num_variables];
+    let mut c = vec![Shared::<E, S>::zero(); num_variables];
+    a[..num_instance].copy_from_slice(&u[num_constraints..num_constraints + num_instance]);
+    for (i, u_i) in u.iter().enumerate().take(num_constraints) {
+        for (coeff, index) in &matrices.a[i] {
+            a[*index] += *u_i * MpcField::from_public(*coeff);
+        }
+        for (coeff, index) in &matrices.b[i] {
+            b[*index] += *u_i * MpcField::from_public(*coeff);
+        }
+        for (coeff, index) in &matrices.c[i] {
+            c[*index] += *u_i * MpcField::from_public(*coeff);
+        }
+    }
+
+    let gamma_inverse = gamma.inv().ok_or(SynthesisError::UnexpectedIdentity)?;
+    let delta_inverse = delta.inv().ok_or(SynthesisError::UnexpectedIdentity)?;
+
+    // β a_i + α b_i + c_i, and zt / δ
+    let mut xs = a.clone();
+    xs.extend_from_slice(&b);
+    xs.push(zt);
+    let mut ys = vec![beta; num_variables];
+    ys.extend(vec![alpha; num_variables]);
+    ys.push(delta_inverse);
+    let mut terms = products::<E, S>(&xs, &ys);
+    let zt_over_delta = terms.pop().unwrap();
+    let abc: Vec<_> = (0..num_variables)
+        .map(|i| terms[i] + terms[num_variables + i] + c[i])
+        .collect();
+
+    // then over γ for the instance, over δ for the witness, and the powers of τ times zt / δ
+    let mut xs = abc;
+    xs.extend_from_slice(&tau_powers[..n - 1]);
+    let mut ys = vec![gamma_inverse; num_instance];
+    ys.extend(vec![delta_inverse; num_variables - num_instance]);
+    ys.extend(vec![zt_over_delta; n - 1]);
+    let mut scaled = products::<E, S>(&xs, &ys);
+    let h = scaled.split_off(num_variables);
+    let l = scaled.split_off(num_instance);
+    let gamma_abc = scaled;
+
+    let g1 = E::G1Projective::prime_subgroup_generator();
+    let g2 = E::G2Projective::prime_subgroup_generator();
+    let g1_terms: Vec<_> = [alpha, beta, delta]
+        .iter()
+        .chain(&a)
+        .chain(&b)
+        .chain(&h)
+        .chain(&l)
+        .chain(&gamma_abc)
+        .map(|x| (g1, *x))
+        .collect();
+    let g2_terms: Vec<_> = [beta, gamma, delta]
+        .iter()
+        .chain(&b)
+        .map(|x| (g2, *x))
+        .collect();
+    let mut g1_points = open_points::<_, S::G1ProjectiveShare>(&g1_terms).into_iter();
+    let mut g2_points = open_points::<_, S::G2ProjectiveShare>(&g2_terms).into_iter();
+    let mut g1_take = |k: usize| -> Vec<E::G1Affine> { g1_points.by_ref().take(k).collect() };
+    let (alpha_g1, beta_g1, delta_g1) = {
+        let v = g1_take(3);
+        (v[0], v[1], v[2])
+    };
+    let a_query = g1_take(num_variables);
+    let b_g1_query = g1_take(num_variables);
+    let h_query = g1_take(n - 1);
+    let l_query = g1_take(num_variables - num_instance);
+    let gamma_abc_g1 = g1_take(num_instance);
+    let (beta_g2, gamma_g2, delta_g2) = {
+        let v: Vec<_> = g2_points.by_ref().take(3).collect();
+        (v[0], v[1], v[2])
+    };
+    let b_g2_query = g2_points.collect();
+
+    Ok(ProvingKey {
+        vk: VerifyingKey {
+            alpha_g1,
+            beta_g2,
+            gamma_g2,
+            delta_g2,
+            gamma_abc_g1,
+        },
+        beta_g1,
+        delta_g1,
+        a_query,
+        b_g1_query,
+        b_g2_query,
+        h_query,
+        l_query,
+    })
+}
+
+/// Generate Groth16 keys for `circuit` from the outcome of a powers-of-tau ceremony, together
+/// with the other parties, who draw `δ` on shares. Every party must call this, with the same
+/// circuit and powers, and gets the same key.
+///
+/// `τ`, `α` and `β` are the ceremony's, and `γ` is one, as snarkjs makes it. The key's queries
+/// are sums of the powers in the Lagrange basis of the QAP's domain, which an inverse FFT over
+/// the groups computes from the powers themselves; only the H- and L-queries, and `δ` itself, are
+/// scaled by the shared `δ^-1`, and opened.
+pub fn generate_parameters_from_powers<E, S, C>(
+    circuit: C,
+    powers: &PowersOfTau<E>,
+) -> R1CSResult<ProvingKey<E>>
+where
+    E: PairingEngine,
+    S: PairingShare<E>,
+    C: ConstraintSynthesizer<E::Fr>,
+{
+    let matrices = synthesize(circuit)?;
+    let num_constraints = matrices.num_constraints;
+    let num_instance = matrices.num_instance_variables;
+    let num_variables = num_instance + matrices.num_witness_variables;
+
+    let domain = GeneralEvaluationDomain::<E::Fr>::new(num_constraints + num_instance)
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+    let n = domain.size();
+    if n > powers.max_domain_size() {
+        return Err(SynthesisError::PolynomialDegreeTooLarge);
+    }
+    fn lagrange<G: AffineCurve, D: EvaluationDomain<G::ScalarField>>(
+        domain: &D,
+        powers: &[G],
+    ) -> Vec<G::Projective> {
+        let powers: Vec<_> = powers[..domain.size()]
+            .iter()
+            .map(|p| p.into_projective())
+            .collect();
+        domain.ifft(&powers)
+    }
+    let u = lagrange(&domain, &powers.tau_g1);
+    let alpha_u = lagrange(&domain, &powers.alpha_tau_g1);
+    let beta_u = lagrange(&domain, &powers.beta_tau_g1);
+    let u_g2 = lagrange(&domain, &powers.tau_g2);
+
+    // a_i, b_i, and β a_i + α b_i + c_i, at τ
+    let mut a = vec![E::G1Projective::zero(); num_variables];
+    let mut b_g1 = vec![E::G1Projective::zero(); num_variables];
+    let mut b_g2 = vec![E::G2Projective::zero(); num_variables];
+    let mut abc = vec![E::G1Projective::zero(); num_variables];
+    a[..num_instance].copy_from_slice(&u[num_constraints..num_constraints + num_instance]);
+    abc[..num_instance].copy_from_slice(&beta_u[num_constraints..num_constraints + num_instance]);
+    fn add<G: ProjectiveCurve>(sum: &mut G, p: G, coeff: G::ScalarField) {
+        *sum += &if coeff.is_one() {
+            p
+        } else {
+            p.mul(coeff.into_repr())
+        };
+    }
+    for i in 0..num_constraints {
+        for (coeff, index) in &matrices.a[i] {
+            add(&mut a[*index], u[i], *coeff);
+            add(&mut abc[*index], beta_u[i], *coeff);
+        }
+        for (coeff, index) in &matrices.b[i] {
+            add(&mut b_g1[*index], u[i], *coeff);
+            add(&mut b_g2[*index], u_g2[i], *coeff);
+            add(&mut abc[*index], alpha_u[i], *coeff);
+        }
+        for (coeff, index) in &matrices.c[i] {
+            add(&mut abc[*index], u[i], *coeff);
+        }
+    }
+
+    let rng = &mut seed::rng(b"toxic waste");
+    let delta = Shared::<E, S>::rand(rng);
+    let delta_inverse = delta.inv().ok_or(SynthesisError::UnexpectedIdentity)?;
+    let g1 = powers.tau_g1[0].into_projective();
+    let g2 = powers.tau_g2[0].into_projective();
+    // τ^i Z(τ) = τ^{n+i} - τ^i
+    let h = (0..n - 1)
+        .map(|i| powers.tau_g1[n + i].into_projective() - powers.tau_g1[i].into_projective());
+    let g1_terms: Vec<_> = std::iter::once((g1, delta))
+        .chain(h.map(|p| (p, delta_inverse)))
+        .chain(abc[num_instance..].iter().map(|p| (*p, delta_inverse)))
+        .collect();
+    let mut g1_points = open_points::<_, S::G1ProjectiveShare>(&g1_terms);
+    let l_query = g1_points.split_off(n);
+    let h_query = g1_points.split_off(1);
+    let delta_g1 = g1_points[0];
+    let delta_g2 = open_points::<_, S::G2ProjectiveShare>(&[(g2, delta)])[0];
+
+    Ok(ProvingKey {
+        vk: VerifyingKey {
+            alpha_g1: powers.alpha_tau_g1[0],
+            beta_g2: powers.beta_g2,
+            gamma_g2: powers.tau_g2[0],
+            delta_g2,
+            gamma_abc_g1: E::G1Projective::batch_normalization_into_affine(&abc[..num_instance]),
+        },
+        beta_g1: powers.beta_tau_g1[0],
+        delta_g1,
+        a_query: E::G1Projective::batch_normalization_into_affine(&a),
+        b_g1_query: E::G1Projective::batch_normalization_into_affine(&b_g1),
+        b_g2_query: E::G2Projective::batch_normalization_into_affine(&b_g2),
+        h_query,
+        l_query,
+    })
+}
@@ -0,0 +1,173 @@
+//! Checking a shared witness against its constraints, before proving, without revealing it.
+//!
+//! A collaborative witness that does not satisfy its circuit gives a proof that does not verify,
+//! and nothing more to go on, since no party can see the witness. [debug_check_constraints]
+//! evaluates every constraint's error, `<A_i, z> <B_i, z> - <C_i, z>`, over the shares, in one
+//! batch of multiplications, and combines the errors with the powers of a random public `rho`.
+//! It opens only that combination, times a random shared mask, which is zero exactly when the
+//! combination is, and so, except with negligible probability, when every constraint holds.
+//! Otherwise it opens each error times a mask of its own, and reports the constraints whose
+//! openings are not zero, as [UnsatisfiedConstraints].
+//!
+//! The openings reveal which constraints fail, and nothing else about the witness. Each check
+//! costs a few rounds, and a few more when it fails: it is for debugging, not for every proof.
+//! [ProveOptions::check](crate::ProveOptions::check) runs it on the prover's own constraint
+//! system, once synthesized, and proves only if it passes.
+use ark_ec::PairingEngine;
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use ark_relations::r1cs::{ConstraintMatrix, ConstraintSystemRef, SynthesisError};
+use mpc_algebra::{seed, FieldShare, MpcField, MpcPairingEngine, PairingShare, Reveal};
+use mpc_trait::MpcWire;
+
+use std::fmt::{self, Display, Formatter};
+
+use super::prover::{Phases, ProveError};
+
+/// The constraints that a shared witness does not satisfy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsatisfiedConstraints {
+    /// In the order the circuit enforced them, from `0`.
+    pub indices: Vec<usize>,
+    pub num_constraints: usize,
+}
+
+impl Display for UnsatisfiedConstraints {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} constraints unsatisfied: {:?}",
+            self.indices.len(),
+            self.num_constraints,
+            self.indices
+        )
+    }
+}
+
+impl std::error::Error for UnsatisfiedConstraints {}
+
+#[derive(Debug)]
+pub enum CheckError {
+    Unsatisfied(UnsatisfiedConstraints),
+    Synthesis(SynthesisError),
+}
+
+impl Display for CheckError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckError::Unsatisfied(e) => write!(f, "constraint check: {}", e),
+            CheckError::Synthesis(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for CheckError {}
+
+impl From<UnsatisfiedConstraints> for CheckError {
+    fn from(e: UnsatisfiedConstraints) -> Self {
+        CheckError::Unsatisfied(e)
+    }
+}
+
+impl From<SynthesisError> for CheckError {
+    fn from(e: SynthesisError) -> Self {
+        CheckError::Synthesis(e)
+    }
+}
+
+/// Check that the assignment of `cs`, which must be finalized, satisfies its constraints, opening
+/// only masked errors. Every party must call this, with the same constraints.
+pub fn debug_check_constraints<F: PrimeField, S: FieldShare<F>>(
+    cs: &ConstraintSystemRef<MpcField<F, S>>,
+) -> Result<(), CheckError> {
+    if cs.is_in_setup_mode() {
+        return Err(SynthesisError::AssignmentMissing.into());
+    }
+    let n = cs.num_constraints();
+    let z = {
+        let cs = cs.borrow().unwrap();
+        [
+            cs.instance_assignment.as_slice(),
+            cs.witness_assignment.as_slice(),
+        ]
+        .concat()
+    };
+    let row_values = |which| -> Result<Vec<MpcField<F, S>>, SynthesisError> {
+        let m = cs
+            .to_csr_matrix(which)
+            .ok_or(SynthesisError::AssignmentMissing)?;
+        let mut out = vec![MpcField::zero(); n];
+        m.mul_vector_into(&z, &mut out);
+        Ok(out)
+    };
+    let (mut errors, b, c) = (
+        row_values(ConstraintMatrix::A)?,
+        row_values(ConstraintMatrix::B)?,
+        row_values(ConstraintMatrix::C)?,
+    );
+    products_in_place(&mut errors, &b);
+    for (e, c) in errors.iter_mut().zip(c) {
+        *e -= c;
+    }
+
+    let rng = &mut seed::rng(b"constraint check");
+    let rho = MpcField::<F, S>::rand(rng).reveal();
+    let mut combined = MpcField::zero();
+    let mut power = F::one();
+    for e in &errors {
+        combined += *e * MpcField::Public(power);
+        power *= rho;
+    }
+    if (combined * MpcField::rand(rng)).reveal().is_zero() {
+        return Ok(());
+    }
+
+    let mut masked = errors;
+    let masks: Vec<_> = (0..n).map(|_| MpcField::rand(rng)).collect();
+    products_in_place(&mut masked, &masks);
+    let indices = masked
+        .reveal()
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| !e.is_zero())
+        .map(|(i, _)| i)
+        .collect();
+    Err(UnsatisfiedConstraints {
+        indices,
+        num_constraints: n,
+    }
+    .into())
+}
+
+/// Set `xs[i] *= ys[i]`, in one batch for the pairs that are both shared, and locally for the
+/// rest: a constraint's rows may be public, as when they involve only the constant `1`.
+fn products_in_place<F: PrimeField, S: FieldShare<F>>(
+    xs: &mut [MpcField<F, S>],
+    ys: &[MpcField<F, S>],
+) {
+    let shared: Vec<usize> = (0..xs.len())
+        .filter(|&i| xs[i].is_shared() && ys[i].is_shared())
+        .collect();
+    let mut batch: Vec<_> = shared.iter().map(|&i| xs[i]).collect();
+    let others: Vec<_> = shared.iter().map(|&i| ys[i]).collect();
+    MpcField::batch_product_in_place(&mut batch, &others);
+    for (x, y) in xs.iter_mut().zip(ys) {
+        if !(x.is_shared() && y.is_shared()) {
+            *x *= y;
+        }
+    }
+    for (i, x) in shared.into_iter().zip(batch) {
+        xs[i] = x;
+    }
+}
+
+/// Every phase computed, once the constraints pass [debug_check_constraints].
+pub(crate) struct CheckConstraints;
+
+impl<E: PairingEngine, S: PairingShare<E>> Phases<MpcPairingEngine<E, S>> for CheckConstraints {
+    fn constraints(
+        &mut self,
+        cs: &ConstraintSystemRef<MpcField<E::Fr, S::FrShare>>,
+    ) -> Result<(), ProveError> {
+        Ok(debug_check_constraints(cs)?)
+    }
+}
@@ -0,0 +1,236 @@
+//! Checkpoints of the collaborative Groth16 prover, so that a proof survives a party restarting.
+//!
+//! The prover runs in phases: drawing its randomness `r` and `s`, the witness map (the FFTs), and
+//! the five MSMs. With a [Checkpoint], each party writes its shares of a phase's result to disk
+//! as the phase ends, one [envelope](mpc_algebra::share::envelope) per phase.
+//!
+//! If a party dies, every party restarts, reconnects, and [resumes](Checkpoint::resume): the
+//! parties agree on the phases that all of them finished, read those back, and run the rest.
+//! Since the randomness is the first phase, the resumed proof uses the same `r` and `s` as the
+//! interrupted one. Synthesis is always rerun, as the later phases need the witness.
+use ark_ec::PairingEngine;
+use ark_relations::r1cs::SynthesisError;
+use log::debug;
+use mpc_algebra::share::envelope::{self, EnvelopeError, PayloadKind};
+use mpc_algebra::{Persist, Persisted};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use sha2::{Digest, Sha256};
+
+use std::convert::TryInto;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::prover::{Phases, ProveError};
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Synthesis(SynthesisError),
+    /// A phase or label file is damaged, or not this party's.
+    Envelope(EnvelopeError),
+    Io(io::Error),
+    /// The checkpoint is of another proof.
+    WrongLabel { expected: String, found: String },
+    /// The parties are resuming checkpoints of different proofs.
+    Mismatch,
+}
+
+impl Display for CheckpointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Synthesis(e) => write!(f, "{}", e),
+            CheckpointError::Envelope(e) => write!(f, "checkpoint: {}", e),
+            CheckpointError::Io(e) => write!(f, "checkpoint I/O: {}", e),
+            CheckpointError::WrongLabel { expected, found } => write!(
+                f,
+                "checkpoint is of proof {:?}, but {:?} was expected",
+                found, expected
+            ),
+            CheckpointError::Mismatch => {
+                write!(f, "the parties are resuming checkpoints of different proofs")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+impl From<SynthesisError> for CheckpointError {
+    fn from(e: SynthesisError) -> Self {
+        CheckpointError::Synthesis(e)
+    }
+}
+
+impl From<EnvelopeError> for CheckpointError {
+    fn from(e: EnvelopeError) -> Self {
+        CheckpointError::Envelope(e)
+    }
+}
+
+impl From<io::Error> for CheckpointError {
+    fn from(e: io::Error) -> Self {
+        CheckpointError::Io(e)
+    }
+}
+
+const LABEL_FILE: &str = "label";
+
+/// A party's checkpoint of one proof, in a directory of its own.
+#[derive(Debug)]
+pub struct Checkpoint {
+    dir: PathBuf,
+    /// The phases that every party finished, which are read back rather than run.
+    agreed: usize,
+    /// The phase the prover is at.
+    next: usize,
+}
+
+impl Checkpoint {
+    /// The conventional directory for `party_id`'s checkpoint within `dir`.
+    pub fn party_dir(dir: impl AsRef<Path>, party_id: usize) -> PathBuf {
+        dir.as_ref().join(format!("party{}", party_id))
+    }
+
+    /// Start checkpointing the proof called `label` in `dir`, discarding any earlier checkpoint
+    /// there.
+    pub fn create(dir: impl AsRef<Path>, label: &str) -> Result<Self, CheckpointError> {
+        let dir = dir.as_ref().to_owned();
+        fs::create_dir_all(&dir)?;
+        let checkpoint = Self {
+            dir,
+            agreed: 0,
+            next: 0,
+        };
+        checkpoint.discard_from(0)?;
+        envelope::save(
+            PayloadKind::ProofFragment,
+            &label.to_owned(),
+            checkpoint.dir.join(LABEL_FILE),
+        )?;
+        Ok(checkpoint)
+    }
+
+    /// Resume the proof called `label` from its checkpoint in `dir`, agreeing with the other
+    /// parties (who must all resume too) on the phases that every party finished.
+    pub fn resume(dir: impl AsRef<Path>, label: &str) -> Result<Self, CheckpointError> {
+        let dir = dir.as_ref().to_owned();
+        let found: String = envelope::load(PayloadKind::ProofFragment, dir.join(LABEL_FILE))?;
+        if found != label {
+            return Err(CheckpointError::WrongLabel {
+                expected: label.to_owned(),
+                found,
+            });
+        }
+        let mut checkpoint = Self {
+            dir,
+            agreed: 0,
+            next: 0,
+        };
+        let finished = (0..)
+            .take_while(|i| checkpoint.phase_path(*i).is_file())
+            .count();
+
+        // every party sends the same number of bytes
+        let mut mine = (finished as u64).to_le_bytes().to_vec();
+        mine.extend_from_slice(&Sha256::digest(label.as_bytes()));
+        let all = Net::broadcast_bytes(&mine);
+        if all.iter().any(|theirs| theirs[8..] != mine[8..]) {
+            return Err(CheckpointError::Mismatch);
+        }
+        checkpoint.agreed = all
+            .iter()
+            .map(|theirs| u64::from_le_bytes(theirs[..8].try_into().unwrap()) as usize)
+            .min()
+            .unwrap();
+        debug!(
+            "Resuming after {} phases ({} finished here)",
+            checkpoint.agreed, finished
+        );
+        // phases past the agreed ones will be rerun, and may come out differently
+        checkpoint.discard_from(checkpoint.agreed)?;
+        Ok(checkpoint)
+    }
+
+    /// The number of phases that are read back rather than run.
+    pub fn resumed_phases(&self) -> usize {
+        self.agreed
+    }
+
+    fn phase_path(&self, i: usize) -> PathBuf {
+        self.dir.join(format!("phase.{}", i))
+    }
+
+    /// Remove the files of phase `first` and later.
+    fn discard_from(&self, first: usize) -> io::Result<()> {
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let phase = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix("phase."))
+                .and_then(|i| i.trim_end_matches(".tmp").parse::<usize>().ok());
+            if phase.map_or(false, |i| i >= first) {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The next phase's result, if it was agreed on, and so is read back rather than run.
+    fn recall<T: Persist>(&mut self, name: &str) -> Result<Option<T>, CheckpointError> {
+        let i = self.next;
+        self.next += 1;
+        if i >= self.agreed {
+            return Ok(None);
+        }
+        debug!("Reading back phase {}", name);
+        let Persisted(value) = envelope::load(PayloadKind::ProofFragment, self.phase_path(i))?;
+        Ok(Some(value))
+    }
+
+    /// Save the result of the phase just run, unless it was read back.
+    fn save<T: Persist>(&self, value: T) -> Result<T, CheckpointError> {
+        let i = self.next - 1;
+        if i < self.agreed {
+            return Ok(value);
+        }
+        let value = Persisted(value);
+        // a party that dies mid-write leaves no phase file, rather than a damaged one
+        let tmp = self.dir.join(format!("phase.{}.tmp", i));
+        envelope::save(PayloadKind::ProofFragment, &value, &tmp)?;
+        fs::rename(tmp, self.phase_path(i))?;
+        Ok(value.0)
+    }
+}
+
+impl<E: PairingEngine> Phases<E> for Checkpoint
+where
+    E::Fr: Persist,
+    E::G1Projective: Persist,
+    E::G2Projective: Persist,
+{
+    fn recall_scalars(&mut self, name: &str) -> Result<Option<Vec<E::Fr>>, ProveError> {
+        Ok(self.recall(name)?)
+    }
+
+    fn recall_g1(&mut self, name: &str) -> Result<Option<E::G1Projective>, ProveError> {
+        Ok(self.recall(name)?)
+    }
+
+    fn recall_g2(&mut self, name: &str) -> Result<Option<E::G2Projective>, ProveError> {
+        Ok(self.recall(name)?)
+    }
+
+    fn scalars(&mut self, _name: &str, value: Vec<E::Fr>) -> Result<Vec<E::Fr>, ProveError> {
+        Ok(self.save(value)?)
+    }
+
+    fn g1(&mut self, _name: &str, value: E::G1Projective) -> Result<E::G1Projective, ProveError> {
+        Ok(self.save(value)?)
+    }
+
+    fn g2(&mut self, _name: &str, value: E::G2Projective) -> Result<E::G2Projective, ProveError> {
+        Ok(self.save(value)?)
+    }
+}
@@ -0,0 +1,153 @@
+//! Exports of the collaborative Groth16 prover's intermediate values, for studying its workloads
+//! offline.
+//!
+//! With a [DebugExport], each party writes its shares of every phase's result (the values a
+//! [Checkpoint](super::checkpoint::Checkpoint) keeps), and also of the witness map's polynomials
+//! and of the scalars of each MSM, one [envelope](mpc_algebra::share::envelope) per value. An
+//! `index` file lists them in the order they were written (the witness map's polynomials just
+//! after its result), one per line: the file name, the kind of its elements (`scalars`, `g1` or
+//! `g2`), and how many there are.
+//!
+//! The scalars of the H-MSM are the witness map's result, and those of the A- and B-MSMs are the
+//! same `assignment`. The MSMs' bases are the proving key's queries, which are not exported.
+//!
+//! An export holds several times the witness's size, and is as secret as the witness: it is for
+//! research on test workloads, not for production proofs.
+use ark_ec::PairingEngine;
+use ark_relations::r1cs::SynthesisError;
+use mpc_algebra::share::envelope::{self, EnvelopeError, PayloadKind};
+use mpc_algebra::{Persist, Persisted};
+
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::prover::{Phases, ProveError};
+
+#[derive(Debug)]
+pub enum ExportError {
+    Synthesis(SynthesisError),
+    Envelope(EnvelopeError),
+    Io(io::Error),
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Synthesis(e) => write!(f, "{}", e),
+            ExportError::Envelope(e) => write!(f, "debug export: {}", e),
+            ExportError::Io(e) => write!(f, "debug export I/O: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<SynthesisError> for ExportError {
+    fn from(e: SynthesisError) -> Self {
+        ExportError::Synthesis(e)
+    }
+}
+
+impl From<EnvelopeError> for ExportError {
+    fn from(e: EnvelopeError) -> Self {
+        ExportError::Envelope(e)
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(e: io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+const INDEX_FILE: &str = "index";
+
+/// A party's export of one proof's intermediate values, in a directory of its own.
+#[derive(Debug)]
+pub struct DebugExport {
+    dir: PathBuf,
+    /// The number of values exported so far.
+    next: usize,
+}
+
+impl DebugExport {
+    /// Start exporting to `dir`, replacing any earlier export there.
+    pub fn create(dir: impl AsRef<Path>) -> Result<Self, ExportError> {
+        let dir = dir.as_ref().to_owned();
+        fs::create_dir_all(&dir)?;
+        // only the files that the earlier export indexed
+        if let Ok(index) = fs::read_to_string(dir.join(INDEX_FILE)) {
+            for file in index.lines().filter_map(|line| line.split('\t').next()) {
+                match fs::remove_file(dir.join(file)) {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                    _ => {}
+                }
+            }
+        }
+        fs::write(dir.join(INDEX_FILE), "")?;
+        Ok(Self { dir, next: 0 })
+    }
+
+    /// The directory of the export.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The number of values exported so far.
+    pub fn exported(&self) -> usize {
+        self.next
+    }
+
+    /// Write `value`, which holds `len` elements of `kind`, and index it.
+    fn export<T: Persist>(
+        &mut self,
+        name: &str,
+        kind: &str,
+        len: usize,
+        value: &Persisted<T>,
+    ) -> Result<(), ExportError> {
+        let file = format!("{:02}.{}", self.next, name.replace(' ', "_"));
+        self.next += 1;
+        envelope::save(PayloadKind::Shares, value, self.dir.join(&file))?;
+        let mut index = OpenOptions::new()
+            .append(true)
+            .open(self.dir.join(INDEX_FILE))?;
+        writeln!(index, "{}\t{}\t{}", file, kind, len)?;
+        Ok(())
+    }
+}
+
+impl<E: PairingEngine> Phases<E> for DebugExport
+where
+    E::Fr: Persist,
+    E::G1Projective: Persist,
+    E::G2Projective: Persist,
+{
+    fn scalars(&mut self, name: &str, value: Vec<E::Fr>) -> Result<Vec<E::Fr>, ProveError> {
+        let value = Persisted(value);
+        self.export(name, "scalars", value.0.len(), &value)?;
+        Ok(value.0)
+    }
+
+    fn g1(&mut self, name: &str, value: E::G1Projective) -> Result<E::G1Projective, ProveError> {
+        let value = Persisted(value);
+        self.export(name, "g1", 1, &value)?;
+        Ok(value.0)
+    }
+
+    fn g2(&mut self, name: &str, value: E::G2Projective) -> Result<E::G2Projective, ProveError> {
+        let value = Persisted(value);
+        self.export(name, "g2", 1, &value)?;
+        Ok(value.0)
+    }
+
+    fn inspecting(&self) -> bool {
+        true
+    }
+
+    fn inspect(&mut self, name: &str, scalars: &[E::Fr]) -> Result<(), ProveError> {
+        Ok(self.export(name, "scalars", scalars.len(), &Persisted(scalars.to_vec()))?)
+    }
+}
@@ -0,0 +1,486 @@
+//! Parameters from existing trusted-setup ceremonies, in the formats of snarkjs.
+//!
+//! A `.ptau` file holds the outcome of a powers-of-tau ceremony, which any circuit up to its size
+//! can use: `τ^i G1` and `τ^i G2`, `α τ^i G1` and `β τ^i G1`, and `β G2`. [PowersOfTau::read]
+//! reads one, and [setup_from_powers_of_tau](crate::setup_from_powers_of_tau) turns it into keys
+//! for a circuit, with the parties drawing the last secret, `δ`, together.
+//!
+//! A `.zkey` file holds Groth16 keys for one circuit, after a circuit-specific (Phase 2)
+//! ceremony; [read_zkey] reads one. The circuit proven with it must be the one it was made for,
+//! with the same constraints in the same order, and the same variables: the one, then the public
+//! inputs, then the witness, as circom numbers its signals. snarkjs writes the H-query as the
+//! Lagrange basis of a domain twice the size, at its odd points, where this prover expects powers
+//! of `τ`; [read_zkey] converts it with an FFT over G1. snarkjs places the constraints at the
+//! powers of a root of unity it derives from the scalar field's least quadratic non-residue, which
+//! is the prover's on BN254 but not on BLS12-381, so only BN254 keys can be used as they are.
+//!
+//! Both are binary files of numbered sections, with points in affine coordinates, each in
+//! Montgomery form, little-endian. Every point read is checked to be on the curve and in its
+//! prime-order subgroup, and the parameters are checked to be consistent with each other, as far
+//! as pairings can tell without the ceremony's secrets. A check that passes does not make a
+//! ceremony trustworthy: it only rules out files that no honest ceremony could have produced.
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{FftField, Field, FpParameters, PrimeField, SquareRootField, UniformRand, Zero};
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Read};
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io(io::Error),
+    /// The file does not start with the magic bytes of its format.
+    NotA(&'static str),
+    /// The file's format version is not one this reads.
+    Version(u32),
+    MissingSection(u32),
+    /// The section's size does not match what the file's header implies.
+    SectionSize {
+        section: u32,
+        expected: u64,
+        found: u64,
+    },
+    /// The file is for a curve with other fields.
+    Curve,
+    /// The `.zkey` is for a proof system other than Groth16.
+    Protocol(u32),
+    /// A point is not on the curve, or not in its prime-order subgroup.
+    Point {
+        section: u32,
+        index: usize,
+    },
+    /// snarkjs's roots of unity in the scalar field are not the prover's, so the key's QAP
+    /// polynomials are not the ones the prover computes.
+    Roots,
+    /// The parameters are not consistent with each other.
+    Inconsistent(&'static str),
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(e) => write!(f, "parameter file I/O: {}", e),
+            ImportError::NotA(kind) => write!(f, "not a {} file", kind),
+            ImportError::Version(v) => write!(f, "unsupported format version {}", v),
+            ImportError::MissingSection(s) => write!(f, "section {} is missing", s),
+            ImportError::SectionSize {
+                section,
+                expected,
+                found,
+            } => write!(
+                f,
+                "section {} holds {} bytes, but should hold {}",
+                section, found, expected
+            ),
+            ImportError::Curve => write!(f, "the parameters are for another curve"),
+            ImportError::Protocol(p) => write!(f, "the key is for protocol {}, not Groth16", p),
+            ImportError::Point { section, index } => write!(
+                f,
+                "point {} of section {} is not in the curve's prime-order subgroup",
+                index, section
+            ),
+            ImportError::Roots => write!(
+                f,
+                "snarkjs's roots of unity differ from the prover's on this curve"
+            ),
+            ImportError::Inconsistent(what) => write!(f, "inconsistent parameters: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<io::Error> for ImportError {
+    fn from(e: io::Error) -> Self {
+        ImportError::Io(e)
+    }
+}
+
+/// The sections of a snarkjs binary file, by type, after checking its magic bytes and version.
+fn read_sections<R: Read>(
+    mut reader: R,
+    magic: &[u8; 4],
+    kind: &'static str,
+) -> Result<HashMap<u32, Vec<u8>>, ImportError> {
+    let mut head = [0u8; 4];
+    reader.read_exact(&mut head)?;
+    if &head != magic {
+        return Err(ImportError::NotA(kind));
+    }
+    let version = read_u32(&mut reader)?;
+    if version != 1 {
+        return Err(ImportError::Version(version));
+    }
+    let n_sections = read_u32(&mut reader)?;
+    let mut sections = HashMap::new();
+    for _ in 0..n_sections {
+        let section = read_u32(&mut reader)?;
+        let mut size = [0u8; 8];
+        reader.read_exact(&mut size)?;
+        let mut data = Vec::new();
+        (&mut reader)
+            .take(u64::from_le_bytes(size))
+            .read_to_end(&mut data)?;
+        if data.len() as u64 != u64::from_le_bytes(size) {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        sections.insert(section, data);
+    }
+    Ok(sections)
+}
+
+fn read_u32<R: Read>(mut reader: R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Section `section`, which must hold `expected` bytes.
+fn section(
+    sections: &HashMap<u32, Vec<u8>>,
+    section: u32,
+    expected: usize,
+) -> Result<&[u8], ImportError> {
+    let data = sections
+        .get(&section)
+        .ok_or(ImportError::MissingSection(section))?;
+    if data.len() != expected {
+        return Err(ImportError::SectionSize {
+            section,
+            expected: expected as u64,
+            found: data.len() as u64,
+        });
+    }
+    Ok(data)
+}
+
+/// Bytes per element of `F`, as snarkjs writes it: whole 64-bit limbs.
+fn n8<F: PrimeField>() -> usize {
+    F::Params::MODULUS.as_ref().len() * 8
+}
+
+/// Whether `bytes` are `F`'s modulus.
+fn is_modulus<F: PrimeField>(bytes: &[u8]) -> bool {
+    let mut modulus = Vec::new();
+    F::Params::MODULUS.serialize(&mut modulus).unwrap();
+    bytes == &modulus[..]
+}
+
+/// Whether snarkjs's roots of unity in `F`, the powers of `F`'s least quadratic non-residue, are
+/// the prover's, the powers of `F`'s two-adic root of unity.
+fn same_roots<F: PrimeField + SquareRootField>() -> bool {
+    let mut nqr = F::from(2u64);
+    while !nqr.legendre().is_qnr() {
+        nqr += F::one();
+    }
+    nqr.pow(F::Params::T) == F::two_adic_root_of_unity()
+}
+
+/// Reads points whose coordinates are made of elements of `F`.
+struct Points<F: PrimeField> {
+    /// `R^-1`, for `R` the Montgomery constant.
+    r_inv: F,
+}
+
+impl<F: PrimeField> Points<F> {
+    fn new() -> Self {
+        let r = F::from_repr(F::Params::R).unwrap();
+        Self {
+            r_inv: r.inverse().unwrap(),
+        }
+    }
+
+    /// The points of `bytes`, which must be whole points of `G`, in section `section`.
+    fn read<G: AffineCurve>(&self, bytes: &[u8], section: u32) -> Result<Vec<G>, ImportError> {
+        bytes
+            .chunks(Self::size::<G>())
+            .enumerate()
+            .map(|(index, p)| self.point(p).ok_or(ImportError::Point { section, index }))
+            .collect()
+    }
+
+    /// Bytes per point of `G`: two coordinates of one or more elements of `F` each.
+    fn size<G: AffineCurve>() -> usize {
+        2 * G::BaseField::extension_degree() as usize * n8::<F>()
+    }
+
+    /// A point, with the identity as zeros.
+    fn point<G: AffineCurve>(&self, bytes: &[u8]) -> Option<G> {
+        if bytes.iter().all(|b| *b == 0) {
+            return Some(G::zero());
+        }
+        let mut canonical = Vec::with_capacity(bytes.len());
+        for x in bytes.chunks(n8::<F>()) {
+            let x = F::from_repr(F::BigInt::deserialize(x).ok()?)? * self.r_inv;
+            x.serialize(&mut canonical).unwrap();
+        }
+        let p = G::deserialize_unchecked(&canonical[..]).ok()?;
+        // decompressing p recomputes its y-coordinate on the curve, and checks its subgroup
+        let mut compressed = Vec::new();
+        p.serialize(&mut compressed).unwrap();
+        G::deserialize(&compressed[..]).ok().filter(|q| *q == p)
+    }
+}
+
+/// Random combinations `Σ r_i p_i` and `Σ r_i p_{i+1}` of `points`, the second `x` times the
+/// first if each point is `x` times the one before.
+fn consecutive<G: AffineCurve, R: Rng>(points: &[G], rng: &mut R) -> (G, G) {
+    let rs: Vec<_> = (1..points.len())
+        .map(|_| G::ScalarField::rand(rng))
+        .collect();
+    let first = G::multi_scalar_mul(&points[..points.len() - 1], &rs);
+    let second = G::multi_scalar_mul(&points[1..], &rs);
+    (first.into_affine(), second.into_affine())
+}
+
+/// Whether `a1 / b1 = a2 / b2`, in the exponent.
+fn same_ratio<E: PairingEngine>(
+    (a1, b1): (E::G1Affine, E::G1Affine),
+    (a2, b2): (E::G2Affine, E::G2Affine),
+) -> bool {
+    E::pairing(a1, b2) == E::pairing(b1, a2)
+}
+
+/// The outcome of a powers-of-tau ceremony, for circuits whose QAP domain has up to `2^power`
+/// points.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PowersOfTau<E: PairingEngine> {
+    /// `τ^i G1`, for `i < 2^{power+1} - 1`.
+    pub tau_g1: Vec<E::G1Affine>,
+    /// `τ^i G2`, for `i < 2^power`.
+    pub tau_g2: Vec<E::G2Affine>,
+    /// `α τ^i G1`, for `i < 2^power`.
+    pub alpha_tau_g1: Vec<E::G1Affine>,
+    /// `β τ^i G1`, for `i < 2^power`.
+    pub beta_tau_g1: Vec<E::G1Affine>,
+    pub beta_g2: E::G2Affine,
+}
+
+impl<E: PairingEngine> PowersOfTau<E> {
+    /// Read a `.ptau` file, and check that its powers are consistent.
+    ///
+    /// The checks combine the powers at random, with coefficients from `rng`.
+    pub fn read<R: Read, G: Rng>(reader: R, rng: &mut G) -> Result<Self, ImportError> {
+        let sections = read_sections(reader, b"ptau", ".ptau")?;
+        let header = sections.get(&1).ok_or(ImportError::MissingSection(1))?;
+        let n8 = n8::<E::Fq>();
+        if header.len() != 4 + n8 + 8 {
+            return Err(ImportError::Curve);
+        }
+        if u32::from_le_bytes(header[..4].try_into().unwrap()) as usize != n8
+            || !is_modulus::<E::Fq>(&header[4..4 + n8])
+        {
+            return Err(ImportError::Curve);
+        }
+        let power = u32::from_le_bytes(header[4 + n8..8 + n8].try_into().unwrap());
+        if power >= 32 {
+            return Err(ImportError::Inconsistent("the power is too large"));
+        }
+        let n = 1usize << power;
+        let points = Points::<E::Fq>::new();
+        let g1 = Points::<E::Fq>::size::<E::G1Affine>();
+        let g2 = Points::<E::Fq>::size::<E::G2Affine>();
+        let powers = Self {
+            tau_g1: points.read(section(&sections, 2, (2 * n - 1) * g1)?, 2)?,
+            tau_g2: points.read(section(&sections, 3, n * g2)?, 3)?,
+            alpha_tau_g1: points.read(section(&sections, 4, n * g1)?, 4)?,
+            beta_tau_g1: points.read(section(&sections, 5, n * g1)?, 5)?,
+            beta_g2: points.read(section(&sections, 6, g2)?, 6)?[0],
+        };
+        powers.check(rng)?;
+        Ok(powers)
+    }
+
+    /// The number of points of the largest QAP domain these powers support.
+    pub fn max_domain_size(&self) -> usize {
+        self.tau_g2.len()
+    }
+
+    fn check<R: Rng>(&self, rng: &mut R) -> Result<(), ImportError> {
+        let (g1, g2) = (self.tau_g1[0], self.tau_g2[0]);
+        if g1 != E::G1Affine::prime_subgroup_generator()
+            || g2 != E::G2Affine::prime_subgroup_generator()
+        {
+            return Err(ImportError::Inconsistent(
+                "the powers start at another generator",
+            ));
+        }
+        if self.tau_g2.len() < 2
+            || self.tau_g1[1].is_zero()
+            || self.alpha_tau_g1[0].is_zero()
+            || self.beta_g2.is_zero()
+        {
+            return Err(ImportError::Inconsistent("τ, α or β is zero"));
+        }
+        let tau_g2 = (g2, self.tau_g2[1]);
+        for (powers, what) in &[
+            (&self.tau_g1, "τ^i G1 are not successive powers"),
+            (&self.alpha_tau_g1, "α τ^i G1 are not successive powers"),
+            (&self.beta_tau_g1, "β τ^i G1 are not successive powers"),
+        ] {
+            if !same_ratio::<E>(consecutive(powers, rng), tau_g2) {
+                return Err(ImportError::Inconsistent(what));
+            }
+        }
+        if !same_ratio::<E>((g1, self.tau_g1[1]), consecutive(&self.tau_g2, rng)) {
+            return Err(ImportError::Inconsistent(
+                "τ^i G2 are not successive powers",
+            ));
+        }
+        if !same_ratio::<E>((g1, self.beta_tau_g1[0]), (g2, self.beta_g2)) {
+            return Err(ImportError::Inconsistent("β in G1 and G2 differ"));
+        }
+        Ok(())
+    }
+}
+
+/// Read a `.zkey` file of Groth16 keys, and [check_proving_key] them.
+///
+/// The check combines the key's points at random, with coefficients from `rng`. A well-formed
+/// file for a curve on which snarkjs's roots of unity are not the prover's is
+/// [ImportError::Roots].
+pub fn read_zkey<E, R, G>(reader: R, rng: &mut G) -> Result<ProvingKey<E>, ImportError>
+where
+    E: PairingEngine,
+    R: Read,
+    G: Rng,
+{
+    let sections = read_sections(reader, b"zkey", ".zkey")?;
+    let protocol = section(&sections, 1, 4)?;
+    let protocol = u32::from_le_bytes(protocol.try_into().unwrap());
+    if protocol != 1 {
+        return Err(ImportError::Protocol(protocol));
+    }
+
+    let (n8q, n8r) = (n8::<E::Fq>(), n8::<E::Fr>());
+    let points = Points::<E::Fq>::new();
+    let g1 = Points::<E::Fq>::size::<E::G1Affine>();
+    let g2 = Points::<E::Fq>::size::<E::G2Affine>();
+    let header = sections.get(&2).ok_or(ImportError::MissingSection(2))?;
+    let fields = 4 + n8q + 4 + n8r;
+    if header.len() < fields || header[..4] != (n8q as u32).to_le_bytes()[..] {
+        return Err(ImportError::Curve);
+    }
+    if !is_modulus::<E::Fq>(&header[4..4 + n8q])
+        || header[4 + n8q..8 + n8q] != (n8r as u32).to_le_bytes()[..]
+        || !is_modulus::<E::Fr>(&header[8 + n8q..fields])
+    {
+        return Err(ImportError::Curve);
+    }
+    let header = section(&sections, 2, fields + 12 + 3 * g1 + 3 * g2)?;
+    let size = |i: usize| {
+        let at = fields + 4 * i;
+        u32::from_le_bytes(header[at..at + 4].try_into().unwrap()) as usize
+    };
+    let (n_vars, n_public, domain_size) = (size(0), size(1), size(2));
+    if n_public + 1 > n_vars || !domain_size.is_power_of_two() {
+        return Err(ImportError::Inconsistent(
+            "the header's sizes are impossible",
+        ));
+    }
+    let domain = GeneralEvaluationDomain::<E::Fr>::new(domain_size)
+        .filter(|d| d.size() == domain_size)
+        .ok_or(ImportError::Inconsistent(
+            "the domain is too large for the curve",
+        ))?;
+    let odd = E::Fr::get_root_of_unity(2 * domain_size).ok_or(ImportError::Inconsistent(
+        "the domain is too large for the curve",
+    ))?;
+
+    let mut at = fields + 12;
+    let mut next = |size: usize| {
+        at += size;
+        &header[at - size..at]
+    };
+    let alpha_g1: Vec<E::G1Affine> = points.read(next(g1), 2)?;
+    let beta_g1: Vec<E::G1Affine> = points.read(next(g1), 2)?;
+    let beta_g2: Vec<E::G2Affine> = points.read(next(g2), 2)?;
+    let gamma_g2: Vec<E::G2Affine> = points.read(next(g2), 2)?;
+    let delta_g1: Vec<E::G1Affine> = points.read(next(g1), 2)?;
+    let delta_g2: Vec<E::G2Affine> = points.read(next(g2), 2)?;
+
+    let n_witness = n_vars - n_public - 1;
+    let h: Vec<E::G1Affine> = points.read(section(&sections, 9, domain_size * g1)?, 9)?;
+    // snarkjs's H_i is L_{2i+1}(τ) / δ, for L_k the Lagrange basis of the domain of size 2n, so
+    // (τ^j Z(τ) / δ) G1, which is zero at the domain's even points and -2 x^j at its odd points x,
+    // is -2 Σ_i (g ω^i)^j H_i, for g the odd points' generator and ω the domain's
+    let h: Vec<_> = h.into_iter().map(|p| p.into_projective()).collect();
+    let h_query: Vec<_> = domain
+        .fft(&h)
+        .into_iter()
+        .take(domain_size - 1)
+        .scan(-E::Fr::from(2u64), |scale, mut p| {
+            p *= *scale;
+            *scale *= odd;
+            Some(p)
+        })
+        .collect();
+
+    let pk = ProvingKey {
+        vk: VerifyingKey {
+            alpha_g1: alpha_g1[0],
+            beta_g2: beta_g2[0],
+            gamma_g2: gamma_g2[0],
+            delta_g2: delta_g2[0],
+            gamma_abc_g1: points.read(section(&sections, 3, (n_public + 1) * g1)?, 3)?,
+        },
+        beta_g1: beta_g1[0],
+        delta_g1: delta_g1[0],
+        a_query: points.read(section(&sections, 5, n_vars * g1)?, 5)?,
+        b_g1_query: points.read(section(&sections, 6, n_vars * g1)?, 6)?,
+        b_g2_query: points.read(section(&sections, 7, n_vars * g2)?, 7)?,
+        h_query: E::G1Projective::batch_normalization_into_affine(&h_query),
+        l_query: points.read(section(&sections, 8, n_witness * g1)?, 8)?,
+    };
+    check_proving_key(&pk, rng)?;
+    if !same_roots::<E::Fr>() {
+        return Err(ImportError::Roots);
+    }
+    Ok(pk)
+}
+
+/// Check that the parts of `pk` that pairings can relate are consistent: `β` and `δ` the same in
+/// G1 as in G2, and so the B-query, and the queries of sizes that fit together.
+///
+/// The B-queries are combined at random, with coefficients from `rng`.
+pub fn check_proving_key<E: PairingEngine, R: Rng>(
+    pk: &ProvingKey<E>,
+    rng: &mut R,
+) -> Result<(), ImportError> {
+    let vk = &pk.vk;
+    let n_vars = pk.a_query.len();
+    if pk.b_g1_query.len() != n_vars
+        || pk.b_g2_query.len() != n_vars
+        || vk.gamma_abc_g1.len() + pk.l_query.len() != n_vars
+        || vk.gamma_abc_g1.is_empty()
+    {
+        return Err(ImportError::Inconsistent(
+            "the queries' sizes do not fit together",
+        ));
+    }
+    if vk.alpha_g1.is_zero()
+        || vk.beta_g2.is_zero()
+        || vk.gamma_g2.is_zero()
+        || vk.delta_g2.is_zero()
+    {
+        return Err(ImportError::Inconsistent("α, β, γ or δ is zero"));
+    }
+    let delta = (pk.delta_g1, vk.delta_g2);
+    if !same_ratio::<E>((delta.0, pk.beta_g1), (delta.1, vk.beta_g2)) {
+        return Err(ImportError::Inconsistent(
+            "β in G1 and G2 differ, or δ does",
+        ));
+    }
+    let rs: Vec<_> = (0..n_vars).map(|_| E::Fr::rand(rng)).collect();
+    let b_g1 = E::G1Affine::multi_scalar_mul(&pk.b_g1_query, &rs).into_affine();
+    let b_g2 = E::G2Affine::multi_scalar_mul(&pk.b_g2_query, &rs).into_affine();
+    if !same_ratio::<E>((delta.0, b_g1), (delta.1, b_g2)) {
+        return Err(ImportError::Inconsistent("the B-query in G1 and G2 differ"));
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+//! Commitments to the parties' input shares, made before proving.
+//!
+//! The witness is fixed once the circuit is synthesized, but nothing stops a party from swapping
+//! its shares of it for others after seeing the prover's first messages, adapting its cheating to
+//! them. With [ProveOptions::commit_inputs](crate::ProveOptions::commit_inputs), each party
+//! commits to its shares of the witness right after synthesis (a SHA-256 hash of them, its id,
+//! and 32 random bytes), and the parties exchange their commitments before the prover sends
+//! anything else.
+//!
+//! Each party keeps everyone's commitments, and its own opening, in [InputCommitments]. An audit
+//! after the fact (say, after a failed MAC check) can then hold every party to the shares it
+//! committed to: a party opens by revealing its shares and randomness, and the others
+//! [check](InputCommitments::check) them.
+use ark_ec::PairingEngine;
+use ark_serialize::CanonicalSerialize;
+use digest::Digest;
+use mpc_algebra::{ct, seed, MpcField, MpcPairingEngine, PairingShare, Reveal};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use rand::RngCore;
+use sha2::Sha256;
+use zeroize::Zeroize;
+
+use super::prover::{Phases, ProveError};
+
+/// Randomness bytes per commitment.
+const RAND_BYTES: usize = 32;
+
+/// The commitment of party `party` to `shares`, with randomness `rand`.
+pub fn commitment<T: CanonicalSerialize>(party: usize, shares: &[T], rand: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    shares.serialize(&mut bytes).unwrap();
+    let c = Sha256::new()
+        .chain(b"mpc-snarks input commitment")
+        .chain(&(party as u64).to_le_bytes())
+        .chain(&bytes)
+        .chain(rand)
+        .finalize()
+        .to_vec();
+    bytes.zeroize();
+    c
+}
+
+/// The commitments of all parties to their input shares, and this party's opening of its own.
+///
+/// Empty until a proof commits to the shares (see
+/// [ProveOptions::commit_inputs](crate::ProveOptions::commit_inputs)). The opening is as secret as
+/// the shares, and is wiped when this is dropped.
+pub struct InputCommitments<T: Zeroize> {
+    /// By party.
+    pub commitments: Vec<Vec<u8>>,
+    shares: Vec<T>,
+    rand: Vec<u8>,
+}
+
+impl<T: CanonicalSerialize + Zeroize> InputCommitments<T> {
+    /// This party's opening: its shares of the witness, and the randomness of its commitment.
+    pub fn opening(&self) -> (&[T], &[u8]) {
+        (&self.shares, &self.rand)
+    }
+
+    /// Whether `shares` and `rand` open the commitment of `party`.
+    pub fn check(&self, party: usize, shares: &[T], rand: &[u8]) -> bool {
+        match self.commitments.get(party) {
+            Some(c) => ct::bytes_eq(c, &commitment(party, shares, rand)).into(),
+            None => false,
+        }
+    }
+}
+
+impl<T: Zeroize> Default for InputCommitments<T> {
+    fn default() -> Self {
+        Self {
+            commitments: Vec::new(),
+            shares: Vec::new(),
+            rand: Vec::new(),
+        }
+    }
+}
+
+impl<T: Zeroize> Drop for InputCommitments<T> {
+    fn drop(&mut self) {
+        self.shares.zeroize();
+        self.rand.zeroize();
+    }
+}
+
+impl<E: PairingEngine, S: PairingShare<E>> Phases<MpcPairingEngine<E, S>>
+    for InputCommitments<S::FrShare>
+{
+    fn synthesized(&mut self, witness: &[MpcField<E::Fr, S::FrShare>]) -> Result<(), ProveError> {
+        let shares: Vec<S::FrShare> = witness
+            .iter()
+            .map(|w| match w {
+                MpcField::Public(x) => S::FrShare::from_public(*x),
+                MpcField::Shared(s) => *s,
+            })
+            .collect();
+        let mut rand = vec![0u8; RAND_BYTES];
+        seed::rng(b"input commitment").fill_bytes(&mut rand);
+        let mine = commitment(Net::party_id(), &shares, &rand);
+        self.commitments = Net::broadcast_bytes(&mine);
+        self.shares.zeroize();
+        self.rand.zeroize();
+        self.shares = shares;
+        self.rand = rand;
+        Ok(())
+    }
+}
@@ -2,14 +2,32 @@
 use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
 use ark_ff::{Field, UniformRand, Zero};
 use super::r1cs_to_qap::R1CStoQAP;
+use crate::qap::{self, QapDomain};
 use ark_groth16::{Proof, ProvingKey, VerifyingKey};
 use ark_poly::GeneralEvaluationDomain;
 use ark_relations::r1cs::{
-    ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, Result as R1CSResult,
+    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, OptimizationGoal,
+    Result as R1CSResult, SynthesisError,
 };
 use ark_std::rand::Rng;
 use ark_std::{end_timer, start_timer, vec::Vec};
 use log::debug;
+use mpc_algebra::fixed_base::FixedBaseMsm;
+use mpc_algebra::{
+    MpcField, MpcG1Affine, MpcG1Projective, MpcG2Affine, MpcG2Projective, MpcPairingEngine,
+    PairingShare, Persist, Reveal,
+};
+use mpc_net::mem;
+
+use std::fmt::{self, Display, Formatter};
+use std::time::Instant;
+
+use super::check::{CheckConstraints, CheckError, UnsatisfiedConstraints};
+use super::checkpoint::{Checkpoint, CheckpointError};
+use super::export::{DebugExport, ExportError};
+use super::inputs::InputCommitments;
+use super::report::{ProvingReport, Timed};
+use super::sliced_key::{Query, SlicedKey};
 
 // Changelog:
 // 1. Specialized to Bls12_377 (our MPC lifting machinery cannot be written fully generically b/c
@@ -17,32 +35,45 @@ use log::debug;
 // 2. Lift to MsmCurve.
 // 3. Remove zero-check for prover randomness r.
 
-#[cfg(feature = "parallel")]
-use rayon::prelude::*;
-
-/// Create a Groth16 proof that is zero-knowledge.
+/// Create a Groth16 proof that is zero-knowledge, with a report of what it cost this party.
 /// This method samples randomness for zero knowledges via `rng`.
-#[inline]
+///
+/// `options` say what to reuse from earlier proofs with the same key, and what to save, export or
+/// check along the way; [ProveOptions::default] for none of it.
 pub fn create_random_proof<E, C, R>(
     circuit: C,
     pk: &ProvingKey<E>,
     rng: &mut R,
-) -> R1CSResult<Proof<E>>
+    options: ProveOptions<E>,
+) -> Result<(Proof<E>, ProvingReport), ProveError>
 where
     E: PairingEngine,
     //E::Fr: BatchProd,
     C: ConstraintSynthesizer<<E as PairingEngine>::Fr>,
     R: Rng,
 {
-    //use ark_ff::One;
-    //let r = <E as PairingEngine>::Fr::one();
-    //let s = <E as PairingEngine>::Fr::one();
-    let t = start_timer!(|| "zk sampling");
-    let r = <E as PairingEngine>::Fr::rand(rng);
-    let s = <E as PairingEngine>::Fr::rand(rng);
-    end_timer!(t);
-
-    create_proof::<E, C>(circuit, pk, r, s)
+    let ProveOptions { msms, phases } = options;
+    let plain = PlainMsms(pk);
+    let msms = match &msms {
+        Some(msms) => {
+            if let Some(key) = msms.pk() {
+                assert!(key.vk == pk.vk, "the prover context is for another key");
+            }
+            msms.as_ref()
+        }
+        None => &plain,
+    };
+    let mut run = Run::new(phases);
+    // the first phase, so that a resumed proof draws nothing from `rng` if the interrupted one
+    // got past it
+    let rs = run.scalars("randomness", || {
+        Ok(vec![
+            <E as PairingEngine>::Fr::rand(rng),
+            <E as PairingEngine>::Fr::rand(rng),
+        ])
+    })?;
+    let proof = create_proof_by::<E, C>(circuit, pk, msms, &mut run, rs[0], rs[1])?;
+    Ok((proof, run.timed.report()))
 }
 
 /// Create a Groth16 proof that is *not* zero-knowledge.
@@ -73,12 +104,583 @@ where
     E: PairingEngine,
     //E::Fr: BatchProd,
     C: ConstraintSynthesizer<<E as PairingEngine>::Fr>,
+{
+    let mut run = Run::new(Vec::new());
+    create_proof_by::<E, C>(circuit, pk, &PlainMsms(pk), &mut run, r, s).map_err(|e| match e {
+        ProveError::Synthesis(e) => e,
+        // only the phases of [ProveOptions] fail otherwise
+        e => unreachable!("{}", e),
+    })
+}
+
+/// Why [create_random_proof] failed.
+#[derive(Debug)]
+pub enum ProveError {
+    Synthesis(SynthesisError),
+    /// The shared witness does not satisfy the circuit (see [ProveOptions::check]).
+    Unsatisfied(UnsatisfiedConstraints),
+    Checkpoint(CheckpointError),
+    Export(ExportError),
+}
+
+impl Display for ProveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ProveError::Synthesis(e) => write!(f, "{}", e),
+            ProveError::Unsatisfied(e) => write!(f, "constraint check: {}", e),
+            ProveError::Checkpoint(e) => write!(f, "{}", e),
+            ProveError::Export(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ProveError {}
+
+impl From<SynthesisError> for ProveError {
+    fn from(e: SynthesisError) -> Self {
+        ProveError::Synthesis(e)
+    }
+}
+
+impl From<CheckError> for ProveError {
+    fn from(e: CheckError) -> Self {
+        match e {
+            CheckError::Unsatisfied(e) => ProveError::Unsatisfied(e),
+            CheckError::Synthesis(e) => ProveError::Synthesis(e),
+        }
+    }
+}
+
+impl From<CheckpointError> for ProveError {
+    fn from(e: CheckpointError) -> Self {
+        match e {
+            CheckpointError::Synthesis(e) => ProveError::Synthesis(e),
+            e => ProveError::Checkpoint(e),
+        }
+    }
+}
+
+impl From<ExportError> for ProveError {
+    fn from(e: ExportError) -> Self {
+        match e {
+            ExportError::Synthesis(e) => ProveError::Synthesis(e),
+            e => ProveError::Export(e),
+        }
+    }
+}
+
+/// What [create_random_proof] does beyond proving with the key's own bases: the precomputation it
+/// reuses from earlier proofs with the same key, and what it saves, exports or checks as it goes.
+/// The options combine freely, except that the bases come from one place.
+pub struct ProveOptions<'a, E: PairingEngine> {
+    /// Where the bases come from, if not from the key.
+    msms: Option<Box<dyn KeyMsms<E> + 'a>>,
+    /// In the order they were added.
+    phases: Vec<Box<dyn Phases<E> + 'a>>,
+}
+
+impl<'a, E: PairingEngine> Default for ProveOptions<'a, E> {
+    fn default() -> Self {
+        Self {
+            msms: None,
+            phases: Vec::new(),
+        }
+    }
+}
+
+impl<'a, E: PairingEngine> ProveOptions<'a, E> {
+    /// Do the MSMs over the key with `tables`, which must be for the key proved with.
+    pub fn tables(mut self, tables: &'a ProvingKeyTables<E>) -> Self
+    where
+        E::G1Affine: FixedBaseMsm,
+        E::G2Affine: FixedBaseMsm,
+    {
+        self.msms = Some(Box::new(tables));
+        self
+    }
+
+    /// Take everything precomputed for the key from `context`, which must be for the key proved
+    /// with.
+    pub fn context(mut self, context: &'a ProverContext<E>) -> Self
+    where
+        E::G1Affine: FixedBaseMsm,
+        E::G2Affine: FixedBaseMsm,
+    {
+        self.msms = Some(Box::new(context));
+        self
+    }
+
+    /// Save each phase to `checkpoint`, or read it back from there if the checkpoint was resumed
+    /// past it.
+    pub fn checkpoint(mut self, checkpoint: &'a mut Checkpoint) -> Self
+    where
+        E::Fr: Persist,
+        E::G1Projective: Persist,
+        E::G2Projective: Persist,
+    {
+        self.phases.push(Box::new(checkpoint));
+        self
+    }
+
+    /// Export each phase's result, and the values in between, to `export`.
+    pub fn export(mut self, export: &'a mut DebugExport) -> Self
+    where
+        E::Fr: Persist,
+        E::G1Projective: Persist,
+        E::G2Projective: Persist,
+    {
+        self.phases.push(Box::new(export));
+        self
+    }
+}
+
+impl<'a, E: PairingEngine, S: PairingShare<E>> ProveOptions<'a, MpcPairingEngine<E, S>> {
+    /// Read each query of `key` just before the MSM that needs it, and drop it after, rather than
+    /// holding the key in memory throughout. Prove with the lifted [SlicedKey::head].
+    ///
+    /// The proof panics if a query can no longer be read; [SlicedKey::open] checks that they can.
+    pub fn sliced(mut self, key: &'a SlicedKey<E>) -> Self {
+        self.msms = Some(Box::new(SlicedMsms(key)));
+        self
+    }
+
+    /// Prove only once [debug_check_constraints](super::check::debug_check_constraints) finds
+    /// that the shared witness satisfies every constraint.
+    pub fn check(mut self) -> Self {
+        self.phases.push(Box::new(CheckConstraints));
+        self
+    }
+
+    /// Have every party commit to its shares of the witness, in `commitments`, before the prover
+    /// first communicates.
+    pub fn commit_inputs(mut self, commitments: &'a mut InputCommitments<S::FrShare>) -> Self {
+        self.phases.push(Box::new(commitments));
+        self
+    }
+}
+
+/// Fixed-base tables for the bases of a proving key, which speed up the MSMs of every proof made
+/// with it. For the MPC engines, the key must be public.
+pub struct ProvingKeyTables<E: PairingEngine>
+where
+    E::G1Affine: FixedBaseMsm,
+    E::G2Affine: FixedBaseMsm,
+{
+    h: <E::G1Affine as FixedBaseMsm>::Table,
+    l: <E::G1Affine as FixedBaseMsm>::Table,
+    a: <E::G1Affine as FixedBaseMsm>::Table,
+    b_g1: <E::G1Affine as FixedBaseMsm>::Table,
+    b_g2: <E::G2Affine as FixedBaseMsm>::Table,
+}
+
+impl<E: PairingEngine> ProvingKeyTables<E>
+where
+    E::G1Affine: FixedBaseMsm,
+    E::G2Affine: FixedBaseMsm,
+{
+    /// Tables for `pk`, with windows of `width` bits (see
+    /// [default_width](mpc_algebra::fixed_base::default_width)).
+    pub fn new(pk: &ProvingKey<E>, width: usize) -> Self {
+        let t = start_timer!(|| "Fixed-base tables");
+        let tables = Self {
+            h: E::G1Affine::fixed_base_table(&pk.h_query, width),
+            l: E::G1Affine::fixed_base_table(&pk.l_query, width),
+            a: E::G1Affine::fixed_base_table(&pk.a_query[1..], width),
+            b_g1: E::G1Affine::fixed_base_table(&pk.b_g1_query[1..], width),
+            b_g2: E::G2Affine::fixed_base_table(&pk.b_g2_query[1..], width),
+        };
+        end_timer!(t);
+        tables
+    }
+}
+
+/// What a prover can compute once for a proving key, and reuse in every proof made with it: the
+/// key's fixed-base tables, and the witness map's domain, with the factors of its coset transforms
+/// and its roots of unity.
+/// For the MPC engines, the key must be public.
+///
+/// Each proof then spends nothing on precomputation; see the `groth16` bench for the savings.
+pub struct ProverContext<E: PairingEngine>
+where
+    E::G1Affine: FixedBaseMsm,
+    E::G2Affine: FixedBaseMsm,
+{
+    pk: ProvingKey<E>,
+    tables: ProvingKeyTables<E>,
+    domain: QapDomain<E::Fr, D<E::Fr>>,
+}
+
+impl<E: PairingEngine> ProverContext<E>
+where
+    E::G1Affine: FixedBaseMsm,
+    E::G2Affine: FixedBaseMsm,
+{
+    /// The context for `pk`, with tables of windows of `width` bits (see [ProvingKeyTables::new]).
+    pub fn new(pk: ProvingKey<E>, width: usize) -> R1CSResult<Self> {
+        let tables = ProvingKeyTables::new(&pk, width);
+        let t = start_timer!(|| "Witness map domain");
+        // the key has one `h` base per point of the domain but the last
+        let domain = QapDomain::new(pk.h_query.len() + 1)
+            .filter(|d| d.size() == pk.h_query.len() + 1)
+            .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        end_timer!(t);
+        Ok(Self { pk, tables, domain })
+    }
+
+    pub fn pk(&self) -> &ProvingKey<E> {
+        &self.pk
+    }
+}
+
+// radix-2 where the two-adicity of the field allows, and mixed-radix beyond (as on MNT6-753)
+type D<F> = GeneralEvaluationDomain<F>;
+
+/// The MSMs of a proof, over the bases of a proving key (skipping the first of `a`, `b_g1` and
+/// `b_g2`).
+trait KeyMsms<E: PairingEngine> {
+    /// The witness map's domain, if precomputed.
+    fn domain(&self) -> Option<&QapDomain<E::Fr, D<E::Fr>>> {
+        None
+    }
+    /// The key the bases are for, if known.
+    fn pk(&self) -> Option<&ProvingKey<E>> {
+        None
+    }
+    fn h(&self, scalars: &[E::Fr]) -> E::G1Projective;
+    fn l(&self, scalars: &[E::Fr]) -> E::G1Projective;
+    fn a(&self, scalars: &[E::Fr]) -> E::G1Projective;
+    fn b_g1(&self, scalars: &[E::Fr]) -> E::G1Projective;
+    fn b_g2(&self, scalars: &[E::Fr]) -> E::G2Projective;
+}
+
+impl<E: PairingEngine, M: KeyMsms<E> + ?Sized> KeyMsms<E> for &M {
+    fn domain(&self) -> Option<&QapDomain<E::Fr, D<E::Fr>>> {
+        (**self).domain()
+    }
+    fn pk(&self) -> Option<&ProvingKey<E>> {
+        (**self).pk()
+    }
+    fn h(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        (**self).h(scalars)
+    }
+    fn l(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        (**self).l(scalars)
+    }
+    fn a(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        (**self).a(scalars)
+    }
+    fn b_g1(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        (**self).b_g1(scalars)
+    }
+    fn b_g2(&self, scalars: &[E::Fr]) -> E::G2Projective {
+        (**self).b_g2(scalars)
+    }
+}
+
+struct PlainMsms<'a, E: PairingEngine>(&'a ProvingKey<E>);
+
+impl<'a, E: PairingEngine> KeyMsms<E> for PlainMsms<'a, E> {
+    fn h(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        E::G1Affine::multi_scalar_mul(&self.0.h_query, scalars)
+    }
+    fn l(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        E::G1Affine::multi_scalar_mul(&self.0.l_query, scalars)
+    }
+    fn a(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        E::G1Affine::multi_scalar_mul(&self.0.a_query[1..], scalars)
+    }
+    fn b_g1(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        E::G1Affine::multi_scalar_mul(&self.0.b_g1_query[1..], scalars)
+    }
+    fn b_g2(&self, scalars: &[E::Fr]) -> E::G2Projective {
+        E::G2Affine::multi_scalar_mul(&self.0.b_g2_query[1..], scalars)
+    }
+}
+
+/// The MSMs over the queries of a [SlicedKey], lifted to the MPC engine as they are read.
+struct SlicedMsms<'a, E: PairingEngine>(&'a SlicedKey<E>);
+
+impl<'a, E: PairingEngine> SlicedMsms<'a, E> {
+    fn g1<S: PairingShare<E>>(&self, query: Query) -> Vec<MpcG1Affine<E, S>> {
+        let _mem = mem::phase("key load");
+        Reveal::from_public(self.0.load_g1(query).unwrap_or_else(|e| panic!("{}", e)))
+    }
+}
+
+impl<'a, E: PairingEngine, S: PairingShare<E>> KeyMsms<MpcPairingEngine<E, S>>
+    for SlicedMsms<'a, E>
+{
+    fn h(&self, scalars: &[MpcField<E::Fr, S::FrShare>]) -> MpcG1Projective<E, S> {
+        AffineCurve::multi_scalar_mul(&self.g1::<S>(Query::H), scalars)
+    }
+    fn l(&self, scalars: &[MpcField<E::Fr, S::FrShare>]) -> MpcG1Projective<E, S> {
+        AffineCurve::multi_scalar_mul(&self.g1::<S>(Query::L), scalars)
+    }
+    fn a(&self, scalars: &[MpcField<E::Fr, S::FrShare>]) -> MpcG1Projective<E, S> {
+        AffineCurve::multi_scalar_mul(&self.g1::<S>(Query::A), scalars)
+    }
+    fn b_g1(&self, scalars: &[MpcField<E::Fr, S::FrShare>]) -> MpcG1Projective<E, S> {
+        AffineCurve::multi_scalar_mul(&self.g1::<S>(Query::BG1), scalars)
+    }
+    fn b_g2(&self, scalars: &[MpcField<E::Fr, S::FrShare>]) -> MpcG2Projective<E, S> {
+        let bases: Vec<MpcG2Affine<E, S>> = {
+            let _mem = mem::phase("key load");
+            Reveal::from_public(self.0.load_b_g2().unwrap_or_else(|e| panic!("{}", e)))
+        };
+        AffineCurve::multi_scalar_mul(&bases, scalars)
+    }
+}
+
+impl<E: PairingEngine> KeyMsms<E> for ProvingKeyTables<E>
+where
+    E::G1Affine: FixedBaseMsm,
+    E::G2Affine: FixedBaseMsm,
+{
+    fn h(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        E::G1Affine::fixed_base_msm(&self.h, scalars)
+    }
+    fn l(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        E::G1Affine::fixed_base_msm(&self.l, scalars)
+    }
+    fn a(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        E::G1Affine::fixed_base_msm(&self.a, scalars)
+    }
+    fn b_g1(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        E::G1Affine::fixed_base_msm(&self.b_g1, scalars)
+    }
+    fn b_g2(&self, scalars: &[E::Fr]) -> E::G2Projective {
+        E::G2Affine::fixed_base_msm(&self.b_g2, scalars)
+    }
+}
+
+impl<E: PairingEngine> KeyMsms<E> for ProverContext<E>
+where
+    E::G1Affine: FixedBaseMsm,
+    E::G2Affine: FixedBaseMsm,
+{
+    fn domain(&self) -> Option<&QapDomain<E::Fr, D<E::Fr>>> {
+        Some(&self.domain)
+    }
+    fn pk(&self) -> Option<&ProvingKey<E>> {
+        Some(&self.pk)
+    }
+    fn h(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        self.tables.h(scalars)
+    }
+    fn l(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        self.tables.l(scalars)
+    }
+    fn a(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        self.tables.a(scalars)
+    }
+    fn b_g1(&self, scalars: &[E::Fr]) -> E::G1Projective {
+        self.tables.b_g1(scalars)
+    }
+    fn b_g2(&self, scalars: &[E::Fr]) -> E::G2Projective {
+        self.tables.b_g2(scalars)
+    }
+}
+
+/// What a proof does at each phase of the prover besides running it: read the result back from
+/// an earlier attempt, save or export it, or check the witness before the prover communicates.
+///
+/// Each phase's result passes through every [ProveOptions] phase in turn.
+pub(crate) trait Phases<E: PairingEngine> {
+    /// The next phase's result, if it is read back rather than run.
+    fn recall_scalars(&mut self, _name: &str) -> Result<Option<Vec<E::Fr>>, ProveError> {
+        Ok(None)
+    }
+    fn recall_g1(&mut self, _name: &str) -> Result<Option<E::G1Projective>, ProveError> {
+        Ok(None)
+    }
+    fn recall_g2(&mut self, _name: &str) -> Result<Option<E::G2Projective>, ProveError> {
+        Ok(None)
+    }
+    /// See the result of the phase, run or read back.
+    fn scalars(&mut self, _name: &str, value: Vec<E::Fr>) -> Result<Vec<E::Fr>, ProveError> {
+        Ok(value)
+    }
+    fn g1(&mut self, _name: &str, value: E::G1Projective) -> Result<E::G1Projective, ProveError> {
+        Ok(value)
+    }
+    fn g2(&mut self, _name: &str, value: E::G2Projective) -> Result<E::G2Projective, ProveError> {
+        Ok(value)
+    }
+    /// See the witness once the circuit is synthesized, before the prover first communicates.
+    fn synthesized(&mut self, _witness: &[E::Fr]) -> Result<(), ProveError> {
+        Ok(())
+    }
+    /// See the finalized constraint system, with its assignment, right after
+    /// [Phases::synthesized].
+    fn constraints(&mut self, _cs: &ConstraintSystemRef<E::Fr>) -> Result<(), ProveError> {
+        Ok(())
+    }
+    /// Whether [Phases::inspect] does anything, so that the prover can skip gathering values for
+    /// it.
+    fn inspecting(&self) -> bool {
+        false
+    }
+    /// See an intermediate value that is not a phase's result: a polynomial of the witness map,
+    /// or the scalars of an MSM. Only a [DebugExport] looks.
+    fn inspect(&mut self, _name: &str, _scalars: &[E::Fr]) -> Result<(), ProveError> {
+        Ok(())
+    }
+}
+
+impl<E: PairingEngine, P: Phases<E> + ?Sized> Phases<E> for &mut P {
+    fn recall_scalars(&mut self, name: &str) -> Result<Option<Vec<E::Fr>>, ProveError> {
+        (**self).recall_scalars(name)
+    }
+    fn recall_g1(&mut self, name: &str) -> Result<Option<E::G1Projective>, ProveError> {
+        (**self).recall_g1(name)
+    }
+    fn recall_g2(&mut self, name: &str) -> Result<Option<E::G2Projective>, ProveError> {
+        (**self).recall_g2(name)
+    }
+    fn scalars(&mut self, name: &str, value: Vec<E::Fr>) -> Result<Vec<E::Fr>, ProveError> {
+        (**self).scalars(name, value)
+    }
+    fn g1(&mut self, name: &str, value: E::G1Projective) -> Result<E::G1Projective, ProveError> {
+        (**self).g1(name, value)
+    }
+    fn g2(&mut self, name: &str, value: E::G2Projective) -> Result<E::G2Projective, ProveError> {
+        (**self).g2(name, value)
+    }
+    fn synthesized(&mut self, witness: &[E::Fr]) -> Result<(), ProveError> {
+        (**self).synthesized(witness)
+    }
+    fn constraints(&mut self, cs: &ConstraintSystemRef<E::Fr>) -> Result<(), ProveError> {
+        (**self).constraints(cs)
+    }
+    fn inspecting(&self) -> bool {
+        (**self).inspecting()
+    }
+    fn inspect(&mut self, name: &str, scalars: &[E::Fr]) -> Result<(), ProveError> {
+        (**self).inspect(name, scalars)
+    }
+}
+
+/// One run of the prover: its phases, each timed, and passed through the [Phases] of its options.
+struct Run<'a, E: PairingEngine> {
+    phases: Vec<Box<dyn Phases<E> + 'a>>,
+    timed: Timed,
+}
+
+impl<'a, E: PairingEngine> Run<'a, E> {
+    fn new(phases: Vec<Box<dyn Phases<E> + 'a>>) -> Self {
+        Self {
+            phases,
+            timed: Timed::start(),
+        }
+    }
+
+    /// Every phase is asked, so that each keeps count of the phases.
+    fn recall<T>(
+        &mut self,
+        mut recall: impl FnMut(&mut dyn Phases<E>) -> Result<Option<T>, ProveError>,
+    ) -> Result<Option<T>, ProveError> {
+        let mut recalled = None;
+        for p in &mut self.phases {
+            if let Some(value) = recall(p.as_mut())? {
+                recalled.get_or_insert(value);
+            }
+        }
+        Ok(recalled)
+    }
+
+    fn scalars(
+        &mut self,
+        name: &str,
+        f: impl FnOnce() -> Result<Vec<E::Fr>, ProveError>,
+    ) -> Result<Vec<E::Fr>, ProveError> {
+        let start = Instant::now();
+        let mut value = match self.recall(|p| p.recall_scalars(name))? {
+            Some(value) => value,
+            None => f()?,
+        };
+        self.timed.record(name, start);
+        for p in &mut self.phases {
+            value = p.scalars(name, value)?;
+        }
+        Ok(value)
+    }
+
+    fn g1(
+        &mut self,
+        name: &str,
+        f: impl FnOnce() -> E::G1Projective,
+    ) -> Result<E::G1Projective, ProveError> {
+        let start = Instant::now();
+        let mut value = match self.recall(|p| p.recall_g1(name))? {
+            Some(value) => value,
+            None => f(),
+        };
+        self.timed.record(name, start);
+        for p in &mut self.phases {
+            value = p.g1(name, value)?;
+        }
+        Ok(value)
+    }
+
+    fn g2(
+        &mut self,
+        name: &str,
+        f: impl FnOnce() -> E::G2Projective,
+    ) -> Result<E::G2Projective, ProveError> {
+        let start = Instant::now();
+        let mut value = match self.recall(|p| p.recall_g2(name))? {
+            Some(value) => value,
+            None => f(),
+        };
+        self.timed.record(name, start);
+        for p in &mut self.phases {
+            value = p.g2(name, value)?;
+        }
+        Ok(value)
+    }
+
+    fn synthesized(&mut self, cs: &ConstraintSystemRef<E::Fr>) -> Result<(), ProveError> {
+        {
+            let witness = &cs.borrow().unwrap().witness_assignment;
+            for p in &mut self.phases {
+                p.synthesized(witness)?;
+            }
+        }
+        for p in &mut self.phases {
+            p.constraints(cs)?;
+        }
+        Ok(())
+    }
+
+    fn inspecting(&self) -> bool {
+        self.phases.iter().any(|p| p.inspecting())
+    }
+
+    fn inspect(&mut self, name: &str, scalars: &[E::Fr]) -> Result<(), ProveError> {
+        for p in &mut self.phases {
+            p.inspect(name, scalars)?;
+        }
+        Ok(())
+    }
+}
+
+fn create_proof_by<E, C>(
+    circuit: C,
+    pk: &ProvingKey<E>,
+    msms: &dyn KeyMsms<E>,
+    run: &mut Run<E>,
+    r: <E as PairingEngine>::Fr,
+    s: <E as PairingEngine>::Fr,
+) -> Result<Proof<E>, ProveError>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<<E as PairingEngine>::Fr>,
 {
     debug!("r: {}", r);
     debug!("s: {}", s);
-    type D<F> = GeneralEvaluationDomain<F>;
 
     let prover_time = start_timer!(|| "Groth16::Prover");
+    let synthesis = Instant::now();
     let cs = ConstraintSystem::new_ref();
 
     // Set the optimization goal
@@ -93,19 +695,40 @@ where
     let lc_time = start_timer!(|| "Inlining LCs");
     cs.finalize();
     end_timer!(lc_time);
+    run.timed.record("synthesis", synthesis);
+    run.synthesized(&cs)?;
 
     let witness_map_time = start_timer!(|| "R1CS to QAP witness map");
-    let h = R1CStoQAP::witness_map::<<E as PairingEngine>::Fr, D<<E as PairingEngine>::Fr>>(
-        cs.clone(),
-    )?;
+    let inspecting = run.inspecting();
+    let mut polys = Vec::new();
+    let h = run.scalars("witness map", || {
+        let _fft_mem = mem::phase("fft");
+        let inspect = |name, poly: &[E::Fr]| {
+            if inspecting {
+                polys.push((name, poly.to_vec()));
+            }
+        };
+        Ok(match msms.domain() {
+            Some(domain) => qap::quotient_in_with(domain, cs.clone(), inspect)?,
+            None => R1CStoQAP::witness_map_with::<
+                <E as PairingEngine>::Fr,
+                D<<E as PairingEngine>::Fr>,
+            >(cs.clone(), inspect)?,
+        })
+    })?;
+    for (name, poly) in polys {
+        run.inspect(name, &poly)?;
+    }
     end_timer!(witness_map_time);
     let prover_crypto_time = start_timer!(|| "crypto");
     let c_acc_time = start_timer!(|| "Compute C");
-    let h_acc = <<E as PairingEngine>::G1Affine as AffineCurve>::multi_scalar_mul(&pk.h_query, &h);
+    let h_acc = run.g1("h", || msms.h(&h))?;
+    drop(h);
     debug!("h_acc: {}", h_acc);
     // Compute C
     let prover = cs.borrow().unwrap();
-    let l_aux_acc = <<E as PairingEngine>::G1Affine as AffineCurve>::multi_scalar_mul(&pk.l_query, &prover.witness_assignment);
+    run.inspect("witness", &prover.witness_assignment)?;
+    let l_aux_acc = run.g1("l", || msms.l(&prover.witness_assignment))?;
 
     let r_s_delta_g1 = pk
         .delta_g1
@@ -119,6 +742,7 @@ where
     let assignment: Vec<<E as PairingEngine>::Fr> = prover.instance_assignment[1..].iter().chain(prover.witness_assignment.iter()).cloned().collect();
     drop(prover);
     drop(cs);
+    run.inspect("assignment", &assignment)?;
 
     // Compute A
     let a_acc_time = start_timer!(|| "Compute A");
@@ -129,7 +753,8 @@ where
     //     debug!("  a[{}]: {}", i, a);
     // }
 
-    let g_a = calculate_coeff(r_g1, &pk.a_query, pk.vk.alpha_g1, &assignment);
+    let a_acc = run.g1("a", || msms.a(&assignment))?;
+    let g_a = calculate_coeff(r_g1, pk.a_query[0], a_acc, pk.vk.alpha_g1);
     debug!("g_a: {}", g_a);
 
     let s_g_a = g_a.scalar_mul(&s);
@@ -140,7 +765,8 @@ where
 //    let g1_b = if !r.is_zero() {
         let b_g1_acc_time = start_timer!(|| "Compute B in G1");
         let s_g1 = pk.delta_g1.scalar_mul(s);
-        let g1_b = calculate_coeff(s_g1, &pk.b_g1_query, pk.beta_g1, &assignment);
+        let b_g1_acc = run.g1("b_g1", || msms.b_g1(&assignment))?;
+        let g1_b = calculate_coeff(s_g1, pk.b_g1_query[0], b_g1_acc, pk.beta_g1);
 
         end_timer!(b_g1_acc_time);
 //
@@ -152,7 +778,8 @@ where
     // Compute B in G2
     let b_g2_acc_time = start_timer!(|| "Compute B in G2");
     let s_g2 = pk.vk.delta_g2.scalar_mul(s);
-    let g2_b = calculate_coeff(s_g2, &pk.b_g2_query, pk.vk.beta_g2, &assignment);
+    let b_g2_acc = run.g2("b_g2", || msms.b_g2(&assignment))?;
+    let g2_b = calculate_coeff(s_g2, pk.b_g2_query[0], b_g2_acc, pk.vk.beta_g2);
     let r_g1_b = g1_b.scalar_mul(&r);
     debug!("r_g1_b: {}", r_g1_b);
     drop(assignment);
@@ -213,16 +840,13 @@ where
     }
 }
 
+/// `initial + el + acc + vk_param`, where `acc` is the MSM over the rest of the query.
 fn calculate_coeff<G: AffineCurve>(
     initial: G::Projective,
-    query: &[G],
+    el: G,
+    acc: G::Projective,
     vk_param: G,
-    assignment: &[G::ScalarField],
 ) -> G::Projective where {
-    let el = query[0];
-    let t = start_timer!(|| format!("MSM size {} {}", query.len() - 1, assignment.len()));
-    let acc = G::multi_scalar_mul(&query[1..], assignment);
-    end_timer!(t);
     let mut res = initial;
     res.add_assign_mixed(&el);
     res += &acc;
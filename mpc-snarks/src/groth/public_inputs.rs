@@ -0,0 +1,145 @@
+//! A proof's public inputs, some known to every party from the start, others shared until the
+//! proof is done.
+//!
+//! In a collaborative proof, an instance variable may be public all along (a constant, or a value
+//! that every party was given), or computed from the parties' secrets, and so shared until it is
+//! revealed along with the proof. [PublicInputs] lists them in the circuit's order, with each
+//! marked as one or the other, and [reveals](PublicInputs::reveal) the shared ones together, in one
+//! batch of openings, before anything is verified against them:
+//!
+//! ```
+//! use ark_bls12_377::{Bls12_377, Fr};
+//! use ark_std::{test_rng, UniformRand};
+//! use mpc_algebra::honest_but_curious::MpcField;
+//! use mpc_algebra::{AdditivePairingShare, Reveal};
+//! use mpc_snarks::silly::MySillyCircuit;
+//! use mpc_snarks::*;
+//!
+//! type E = Bls12_377;
+//! type S = AdditivePairingShare<E>;
+//! let rng = &mut test_rng();
+//! let _net = Network::single_party();
+//! let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+//!
+//! let a = MpcField::<Fr>::king_share(Fr::rand(rng), rng);
+//! let b = MpcField::<Fr>::king_share(Fr::rand(rng), rng);
+//! let circuit = MySillyCircuit { a: Some(a), b: Some(b) };
+//! let mpc_pk = share_proving_key::<E, S>(&pk);
+//! let proof = prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default()).unwrap().0;
+//! let proof = reveal_proof::<E, S>(proof);
+//! // c = ab stays shared until here
+//! let inputs = PublicInputs::<E, S>::new().shared(a * b);
+//! assert!(inputs.verify(&pk.vk, &proof).unwrap());
+//! ```
+//!
+//! Opening the shared inputs checks them as any opening does: their MACs, with SPDZ shares, and
+//! the products computed so far, with GSZ20 shares. A cheating party is caught there, before
+//! verification, rather than blamed on a proof that does not verify. In identifiable-abort mode
+//! (see [blame](mpc_algebra::share::blame)), a failed MAC check is returned as
+//! [PublicInputsError::MacCheck], with its culprits.
+use ark_ec::PairingEngine;
+use ark_groth16::{prepare_verifying_key, verify_proof, Proof, VerifyingKey};
+use ark_relations::r1cs::SynthesisError;
+use derivative::Derivative;
+use mpc_algebra::share::blame::{self, MacCheckFailure};
+use mpc_algebra::{MpcField, PairingShare};
+
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug)]
+pub enum PublicInputsError {
+    /// Opening the shared inputs failed its MAC check, in identifiable-abort mode.
+    MacCheck(MacCheckFailure),
+    Synthesis(SynthesisError),
+}
+
+impl Display for PublicInputsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PublicInputsError::MacCheck(e) => write!(f, "revealing the public inputs: {}", e),
+            PublicInputsError::Synthesis(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for PublicInputsError {}
+
+impl From<MacCheckFailure> for PublicInputsError {
+    fn from(e: MacCheckFailure) -> Self {
+        PublicInputsError::MacCheck(e)
+    }
+}
+
+impl From<SynthesisError> for PublicInputsError {
+    fn from(e: SynthesisError) -> Self {
+        PublicInputsError::Synthesis(e)
+    }
+}
+
+/// A proof's public inputs, in the circuit's order, each public from the start or shared until
+/// revealed.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), Default(bound = ""))]
+pub struct PublicInputs<E: PairingEngine, S: PairingShare<E>> {
+    inputs: Vec<MpcField<E::Fr, S::FrShare>>,
+}
+
+impl<E: PairingEngine, S: PairingShare<E>> PublicInputs<E, S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an input that every party knows already.
+    pub fn public(mut self, x: E::Fr) -> Self {
+        self.inputs.push(MpcField::Public(x));
+        self
+    }
+
+    /// Append an input that is shared until [PublicInputs::reveal]. One that turns out to be
+    /// public already is kept as is.
+    pub fn shared(mut self, x: MpcField<E::Fr, S::FrShare>) -> Self {
+        self.inputs.push(x);
+        self
+    }
+
+    /// Append each of `xs`, as [PublicInputs::shared] does.
+    pub fn shared_all(mut self, xs: impl IntoIterator<Item = MpcField<E::Fr, S::FrShare>>) -> Self {
+        self.inputs.extend(xs);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Whether input `i` is still shared.
+    pub fn is_shared(&self, i: usize) -> bool {
+        matches!(self.inputs[i], MpcField::Shared(_))
+    }
+
+    /// Every input's value, opening the shared ones in one batch. Every party must call this.
+    pub fn reveal(self) -> Result<Vec<E::Fr>, MacCheckFailure> {
+        let shared = self.inputs.iter().filter(|x| matches!(x, MpcField::Shared(_)));
+        let shared: Vec<_> = shared.cloned().collect();
+        let mut opened = blame::catch_mac_failure(|| MpcField::open_batch(shared))?.into_iter();
+        Ok(self
+            .inputs
+            .into_iter()
+            .map(|x| match x {
+                MpcField::Public(x) => x,
+                MpcField::Shared(_) => opened.next().unwrap(),
+            })
+            .collect())
+    }
+
+    /// [Reveal](PublicInputs::reveal) the inputs, and verify `proof` against them. Every party
+    /// must call this.
+    pub fn verify(self, vk: &VerifyingKey<E>, proof: &Proof<E>) -> Result<bool, PublicInputsError> {
+        let inputs = self.reveal()?;
+        Ok(verify_proof(&prepare_verifying_key(vk), proof, &inputs)?)
+    }
+}
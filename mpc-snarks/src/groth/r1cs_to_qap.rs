@@ -1,45 +1,10 @@
-use ark_ff::{One, PrimeField, Zero};
+use ark_ff::PrimeField;
 use ark_poly::EvaluationDomain;
-use ark_std::{cfg_iter, cfg_iter_mut, vec, start_timer, end_timer};
+use ark_relations::r1cs::{ConstraintSystemRef, Result as R1CSResult};
 
-use ark_relations::r1cs::{ConstraintSystemRef, Result as R1CSResult, SynthesisError};
-use core::ops::{AddAssign, Deref};
-
-#[cfg(feature = "parallel")]
-use rayon::prelude::*;
-
-#[inline]
-fn evaluate_constraint<'a, LHS, RHS, R>(terms: &'a [(LHS, usize)], assignment: &'a [RHS]) -> R
-where
-    LHS: One + Send + Sync + PartialEq,
-    RHS: Send + Sync + core::ops::Mul<&'a LHS, Output = RHS> + Copy,
-    R: Zero + Send + Sync + AddAssign<RHS> + core::iter::Sum,
-{
-    // Need to wrap in a closure when using Rayon
-    #[cfg(feature = "parallel")]
-    let zero = || R::zero();
-    #[cfg(not(feature = "parallel"))]
-    let zero = R::zero();
-
-    let res = cfg_iter!(terms).fold(zero, |mut sum, (coeff, index)| {
-        let val = &assignment[*index];
-
-        if coeff.is_one() {
-            sum += *val;
-        } else {
-            sum += val.mul(coeff);
-        }
-
-        sum
-    });
-
-    // Need to explicitly call `.sum()` when using Rayon
-    #[cfg(feature = "parallel")]
-    return res.sum();
-    #[cfg(not(feature = "parallel"))]
-    return res;
-}
+use crate::qap;
 
+/// The witness map as Groth16's prover uses it: `h` alone. See [qap] for the full QAP.
 pub struct R1CStoQAP;
 
 impl R1CStoQAP {
@@ -47,68 +12,15 @@ impl R1CStoQAP {
     pub fn witness_map<F: PrimeField, D: EvaluationDomain<F>>(
         prover: ConstraintSystemRef<F>,
     ) -> R1CSResult<Vec<F>> {
-        let matrices = prover.to_matrices().unwrap();
-        let zero = F::zero();
-        let num_inputs = prover.num_instance_variables();
-        let num_constraints = prover.num_constraints();
-        let cs = prover.borrow().unwrap();
-        let prover = cs.deref();
-
-        let full_assignment = [
-            prover.instance_assignment.as_slice(),
-            prover.witness_assignment.as_slice(),
-        ]
-        .concat();
-
-        let domain =
-            D::new(num_constraints + num_inputs).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
-        let domain_size = domain.size();
-
-        let mut a = vec![zero; domain_size];
-        let mut b = vec![zero; domain_size];
-
-        cfg_iter_mut!(a[..num_constraints])
-            .zip(cfg_iter_mut!(b[..num_constraints]))
-            .zip(cfg_iter!(&matrices.a))
-            .zip(cfg_iter!(&matrices.b))
-            .for_each(|(((a, b), at_i), bt_i)| {
-                *a = evaluate_constraint(&at_i, &full_assignment);
-                *b = evaluate_constraint(&bt_i, &full_assignment);
-            });
-
-        {
-            let start = num_constraints;
-            let end = start + num_inputs;
-            a[start..end].clone_from_slice(&full_assignment[..num_inputs]);
-        }
-
-        domain.ifft_in_place(&mut a);
-        domain.ifft_in_place(&mut b);
-
-        domain.coset_fft_in_place(&mut a);
-        domain.coset_fft_in_place(&mut b);
-        let mut ab = a.clone();
-        let batch_product_timer = start_timer!(|| "batch product");
-        F::batch_product_in_place(&mut ab, &b);
-        end_timer!(batch_product_timer);
-
-        let mut c = vec![zero; domain_size];
-        cfg_iter_mut!(c[..prover.num_constraints])
-            .enumerate()
-            .for_each(|(i, c)| {
-                *c = evaluate_constraint(&matrices.c[i], &full_assignment);
-            });
-
-        domain.ifft_in_place(&mut c);
-        domain.coset_fft_in_place(&mut c);
-
-        cfg_iter_mut!(ab)
-            .zip(c)
-            .for_each(|(ab_i, c_i)| *ab_i -= &c_i);
-
-        domain.divide_by_vanishing_poly_on_coset_in_place(&mut ab);
-        domain.coset_ifft_in_place(&mut ab);
+        qap::quotient::<F, D>(prover)
+    }
 
-        Ok(ab)
+    /// [R1CStoQAP::witness_map], showing `inspect` its polynomials along the way: the evaluations
+    /// of `a`, `b` and `c` over the domain, then over its coset, and `h` over the coset.
+    pub fn witness_map_with<F: PrimeField, D: EvaluationDomain<F>>(
+        prover: ConstraintSystemRef<F>,
+        inspect: impl FnMut(&'static str, &[F]),
+    ) -> R1CSResult<Vec<F>> {
+        qap::quotient_with::<F, D>(prover, inspect)
     }
 }
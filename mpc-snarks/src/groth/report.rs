@@ -0,0 +1,97 @@
+//! What one run of the prover cost this party: time by phase, bytes, triples and openings.
+//!
+//! [create_random_proof](super::prover::create_random_proof) times each phase of the prover
+//! (synthesis, the randomness, the witness map and each MSM, as a
+//! [Checkpoint](super::checkpoint::Checkpoint) names them), and counts what the proof sent and
+//! received, from the network's [Stats](mpc_net::Stats), and the triples it drew and the values it
+//! opened, from [cost](mpc_algebra::cost). Comparing reports across runs shows regressions in any
+//! of them, and across schemes, what each costs.
+use mpc_algebra::cost::{self, Costs};
+use mpc_net::{MpcMultiNet as Net, MpcNet, Stats};
+
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// What a proof cost this party.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProvingReport {
+    /// In the order the prover ran them.
+    pub phase_timings: Vec<(String, Duration)>,
+    pub bytes_sent: usize,
+    pub bytes_received: usize,
+    /// Multiplication triples drawn; none for schemes that multiply without them.
+    pub triples_consumed: u64,
+    /// Shared values opened, including those masked within protocols.
+    pub openings: u64,
+}
+
+impl ProvingReport {
+    /// The time of all the phases.
+    pub fn total_time(&self) -> Duration {
+        self.phase_timings.iter().map(|(_, t)| *t).sum()
+    }
+
+    /// The report as a JSON object, with times in seconds.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"phase_timings\":[");
+        for (i, (name, time)) in self.phase_timings.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write!(
+                json,
+                "{{\"phase\":\"{}\",\"seconds\":{}}}",
+                name.escape_default(),
+                time.as_secs_f64()
+            )
+            .unwrap();
+        }
+        write!(
+            json,
+            "],\"total_seconds\":{},\"bytes_sent\":{},\"bytes_received\":{},\
+             \"triples_consumed\":{},\"openings\":{}}}",
+            self.total_time().as_secs_f64(),
+            self.bytes_sent,
+            self.bytes_received,
+            self.triples_consumed,
+            self.openings
+        )
+        .unwrap();
+        json
+    }
+}
+
+/// The phases timed so far, with the network's and the protocols' counts from when this was
+/// created.
+pub(crate) struct Timed {
+    stats: Stats,
+    costs: Costs,
+    timings: Vec<(String, Duration)>,
+}
+
+impl Timed {
+    pub(crate) fn start() -> Self {
+        Timed {
+            stats: Net::stats(),
+            costs: cost::costs(),
+            timings: Vec::new(),
+        }
+    }
+
+    /// Add the phase `name`, which began at `start` and has just ended.
+    pub(crate) fn record(&mut self, name: &str, start: Instant) {
+        self.timings.push((name.to_owned(), start.elapsed()));
+    }
+
+    pub(crate) fn report(self) -> ProvingReport {
+        let stats = Net::stats();
+        let costs = cost::costs().since(self.costs);
+        ProvingReport {
+            phase_timings: self.timings,
+            bytes_sent: stats.bytes_sent.saturating_sub(self.stats.bytes_sent),
+            bytes_received: stats.bytes_recv.saturating_sub(self.stats.bytes_recv),
+            triples_consumed: costs.triples,
+            openings: costs.openings,
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! Proving keys split into a file per query, for parties short of memory.
+//!
+//! A proving key is mostly its five queries, each of which only one MSM of the prover reads. A
+//! [SlicedKey] keeps the rest of the key (its head) in memory, and reads each query from its own
+//! file just before the MSM that needs it, so that the prover holds at most one query at a time.
+//!
+//! Each query file starts with a hash of the head, so that queries of different keys do not mix.
+use ark_ec::PairingEngine;
+use ark_groth16::ProvingKey;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use sha2::{Digest, Sha256};
+
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// A query of a proving key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Query {
+    /// All but the first element of the A-query.
+    A,
+    /// All but the first element of the B-query in G1.
+    BG1,
+    /// All but the first element of the B-query in G2.
+    BG2,
+    H,
+    L,
+}
+
+impl Query {
+    pub const ALL: [Query; 5] = [Query::A, Query::BG1, Query::BG2, Query::H, Query::L];
+
+    fn file_name(self) -> &'static str {
+        match self {
+            Query::A => "a_query",
+            Query::BG1 => "b_g1_query",
+            Query::BG2 => "b_g2_query",
+            Query::H => "h_query",
+            Query::L => "l_query",
+        }
+    }
+
+    fn index(self) -> usize {
+        Query::ALL.iter().position(|q| *q == self).unwrap()
+    }
+}
+
+#[derive(Debug)]
+pub enum SliceError {
+    Io(io::Error),
+    Serialization(SerializationError),
+    /// The query's file belongs to another key.
+    WrongKey(Query),
+    /// The query's file holds a different number of elements than the head says.
+    Length {
+        query: Query,
+        expected: u64,
+        found: u64,
+    },
+}
+
+impl Display for SliceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SliceError::Io(e) => write!(f, "sliced key I/O: {}", e),
+            SliceError::Serialization(e) => write!(f, "sliced key encoding: {}", e),
+            SliceError::WrongKey(q) => write!(f, "the {} file is of another key", q.file_name()),
+            SliceError::Length {
+                query,
+                expected,
+                found,
+            } => write!(
+                f,
+                "the {} file holds {} elements, but the key has {}",
+                query.file_name(),
+                found,
+                expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SliceError {}
+
+impl From<io::Error> for SliceError {
+    fn from(e: io::Error) -> Self {
+        SliceError::Io(e)
+    }
+}
+
+impl From<SerializationError> for SliceError {
+    fn from(e: SerializationError) -> Self {
+        match e {
+            SerializationError::IoError(e) => SliceError::Io(e),
+            e => SliceError::Serialization(e),
+        }
+    }
+}
+
+const HEAD_FILE: &str = "head";
+
+/// A proving key in a directory, with its queries in separate files.
+pub struct SlicedKey<E: PairingEngine> {
+    dir: PathBuf,
+    /// The key without its queries, but for the first elements of the A- and B-queries, which
+    /// the prover uses on their own.
+    head: ProvingKey<E>,
+    /// The number of elements of each query, in the order of [Query::ALL].
+    lens: Vec<u64>,
+    hash: [u8; 32],
+}
+
+impl<E: PairingEngine> SlicedKey<E> {
+    /// Write `pk` to `dir`, as a head and a file per query.
+    pub fn write(pk: &ProvingKey<E>, dir: impl AsRef<Path>) -> Result<Self, SliceError> {
+        let dir = dir.as_ref().to_owned();
+        fs::create_dir_all(&dir)?;
+        let head = ProvingKey {
+            vk: pk.vk.clone(),
+            beta_g1: pk.beta_g1,
+            delta_g1: pk.delta_g1,
+            a_query: pk.a_query[..1].to_vec(),
+            b_g1_query: pk.b_g1_query[..1].to_vec(),
+            b_g2_query: pk.b_g2_query[..1].to_vec(),
+            h_query: Vec::new(),
+            l_query: Vec::new(),
+        };
+        let lens = vec![
+            pk.a_query.len() as u64 - 1,
+            pk.b_g1_query.len() as u64 - 1,
+            pk.b_g2_query.len() as u64 - 1,
+            pk.h_query.len() as u64,
+            pk.l_query.len() as u64,
+        ];
+        let mut bytes = Vec::new();
+        head.serialize(&mut bytes)?;
+        lens.serialize(&mut bytes)?;
+        fs::write(dir.join(HEAD_FILE), &bytes)?;
+        let key = Self::from_head(dir, &bytes)?;
+        key.write_query(Query::A, &pk.a_query[1..])?;
+        key.write_query(Query::BG1, &pk.b_g1_query[1..])?;
+        key.write_query(Query::BG2, &pk.b_g2_query[1..])?;
+        key.write_query(Query::H, &pk.h_query)?;
+        key.write_query(Query::L, &pk.l_query)?;
+        Ok(key)
+    }
+
+    /// Read the head of the key in `dir`, and check that its query files are there and of this
+    /// key, without reading the queries themselves.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, SliceError> {
+        let dir = dir.as_ref().to_owned();
+        let bytes = fs::read(dir.join(HEAD_FILE))?;
+        let key = Self::from_head(dir, &bytes)?;
+        for q in &Query::ALL {
+            key.query_reader(*q)?;
+        }
+        Ok(key)
+    }
+
+    fn from_head(dir: PathBuf, bytes: &[u8]) -> Result<Self, SliceError> {
+        let mut r = bytes;
+        let head = ProvingKey::deserialize(&mut r)?;
+        let lens = Vec::<u64>::deserialize(&mut r)?;
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&Sha256::digest(bytes));
+        Ok(Self {
+            dir,
+            head,
+            lens,
+            hash,
+        })
+    }
+
+    /// The key without its queries, but for the first elements of the A- and B-queries.
+    pub fn head(&self) -> &ProvingKey<E> {
+        &self.head
+    }
+
+    /// The number of elements of `query`.
+    pub fn len(&self, query: Query) -> usize {
+        self.lens[query.index()] as usize
+    }
+
+    fn query_path(&self, query: Query) -> PathBuf {
+        self.dir.join(query.file_name())
+    }
+
+    fn write_query<G: CanonicalSerialize>(
+        &self,
+        query: Query,
+        elems: &[G],
+    ) -> Result<(), SliceError> {
+        let mut w = BufWriter::new(File::create(self.query_path(query))?);
+        w.write_all(&self.hash)?;
+        (elems.len() as u64).serialize(&mut w)?;
+        for g in elems {
+            g.serialize(&mut w)?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+
+    /// A reader of `query`'s elements, once its hash and length are checked.
+    fn query_reader(&self, query: Query) -> Result<BufReader<File>, SliceError> {
+        let mut r = BufReader::new(File::open(self.query_path(query))?);
+        let mut hash = [0u8; 32];
+        r.read_exact(&mut hash)?;
+        if hash != self.hash {
+            return Err(SliceError::WrongKey(query));
+        }
+        let found = u64::deserialize(&mut r)?;
+        let expected = self.lens[query.index()];
+        if found != expected {
+            return Err(SliceError::Length {
+                query,
+                expected,
+                found,
+            });
+        }
+        Ok(r)
+    }
+
+    fn read_query<G: CanonicalDeserialize>(&self, query: Query) -> Result<Vec<G>, SliceError> {
+        let mut r = self.query_reader(query)?;
+        (0..self.len(query))
+            .map(|_| Ok(G::deserialize(&mut r)?))
+            .collect()
+    }
+
+    /// Read `query`, which must be one in G1.
+    pub fn load_g1(&self, query: Query) -> Result<Vec<E::G1Affine>, SliceError> {
+        assert_ne!(query, Query::BG2, "the B-query in G2 is not in G1");
+        self.read_query(query)
+    }
+
+    /// Read the B-query in G2.
+    pub fn load_b_g2(&self) -> Result<Vec<E::G2Affine>, SliceError> {
+        self.read_query(Query::BG2)
+    }
+}
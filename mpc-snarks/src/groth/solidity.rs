@@ -0,0 +1,203 @@
+//! Verification of revealed proofs on Ethereum, by a Solidity contract with the verifying key
+//! built in.
+//!
+//! The contract checks proofs with the EVM's BN254 precompiles (EIP-196 and EIP-197), so it can
+//! only be made for keys over BN254. Its `verifyProof` takes the proof's points and the public
+//! inputs as `uint256` words, as [solidity_calldata] encodes them: each coordinate big-endian, a
+//! G2 coordinate as its imaginary part then its real part, and the identity as zeros. The
+//! function's signature, and so its selector, depends on the number of public inputs.
+use ark_ec::bn::{Bn, BnParameters, G1Affine, G2Affine};
+use ark_ec::PairingEngine;
+use ark_ff::{BigInteger, FpParameters, PrimeField, Zero};
+use ark_groth16::{Proof, VerifyingKey};
+use sha3::{Digest, Keccak256};
+
+use std::fmt::{Display, Write};
+
+/// BN254's base-field modulus, the one of the EVM's precompiles, as its `BigInteger` displays.
+const BN254_MODULUS: &str = "30644E72E131A029B85045B68181585D97816A916871CA8D3C208C16D87CFD47";
+
+/// Panics unless `P` is BN254.
+fn check_curve<P: BnParameters>() {
+    assert_eq!(
+        <P::Fp as PrimeField>::Params::MODULUS.to_string(),
+        BN254_MODULUS,
+        "the EVM's pairing precompiles are for BN254 only"
+    );
+}
+
+/// `x`, as a Solidity literal.
+fn literal(x: impl Display) -> String {
+    format!("0x{}", x).to_lowercase()
+}
+
+/// `x`, as a `uint256` word.
+fn word<F: PrimeField>(x: F) -> Vec<u8> {
+    x.into_repr().to_bytes_be()
+}
+
+/// The coordinates of `p`, as the precompiles take them.
+fn g1<P: BnParameters>(p: &G1Affine<P>) -> [P::Fp; 2] {
+    if p.infinity {
+        return [P::Fp::zero(); 2];
+    }
+    [p.x, p.y]
+}
+
+/// The coordinates of `p`, as the precompiles take them: imaginary parts first.
+fn g2<P: BnParameters>(p: &G2Affine<P>) -> [P::Fp; 4] {
+    if p.infinity {
+        return [P::Fp::zero(); 4];
+    }
+    [p.x.c1, p.x.c0, p.y.c1, p.y.c0]
+}
+
+/// The signature of the contract's `verifyProof`, for `n_public` public inputs.
+fn signature(n_public: usize) -> String {
+    let input = match n_public {
+        0 => String::new(),
+        n => format!(",uint256[{}]", n),
+    };
+    format!("verifyProof(uint256[2],uint256[2][2],uint256[2]{})", input)
+}
+
+/// A Solidity contract, `Groth16Verifier`, whose `verifyProof` checks proofs against `vk`.
+///
+/// # Panics
+///
+/// If `vk` is not over BN254.
+pub fn solidity_verifier<P: BnParameters>(vk: &VerifyingKey<Bn<P>>) -> String {
+    check_curve::<P>();
+    let n_public = vk.gamma_abc_g1.len() - 1;
+    let scalar_modulus = <<Bn<P> as PairingEngine>::Fr as PrimeField>::Params::MODULUS;
+    let mut s = String::new();
+    let mut constant = |name: &str, value: String| {
+        writeln!(s, "    uint256 constant {} = {};", name, value).unwrap()
+    };
+    constant("Q", literal(BN254_MODULUS));
+    constant("R", literal(scalar_modulus));
+    let [x, y] = g1::<P>(&vk.alpha_g1);
+    constant("ALPHA_X", literal(x.into_repr()));
+    constant("ALPHA_Y", literal(y.into_repr()));
+    let g2s = [
+        ("BETA", &vk.beta_g2),
+        ("GAMMA", &vk.gamma_g2),
+        ("DELTA", &vk.delta_g2),
+    ];
+    for (name, p) in g2s.iter() {
+        let [x1, x0, y1, y0] = g2::<P>(p);
+        constant(&format!("{}_X1", name), literal(x1.into_repr()));
+        constant(&format!("{}_X0", name), literal(x0.into_repr()));
+        constant(&format!("{}_Y1", name), literal(y1.into_repr()));
+        constant(&format!("{}_Y0", name), literal(y0.into_repr()));
+    }
+    for (i, p) in vk.gamma_abc_g1.iter().enumerate() {
+        let [x, y] = g1::<P>(p);
+        constant(&format!("IC{}_X", i), literal(x.into_repr()));
+        constant(&format!("IC{}_Y", i), literal(y.into_repr()));
+    }
+    let constants = s;
+
+    let input = match n_public {
+        0 => String::new(),
+        n => format!(",\n        uint256[{}] calldata input", n),
+    };
+    let mut vk_x = String::new();
+    for i in 0..n_public {
+        let (s, ic) = (format!("input[{}]", i), format!("IC{}", i + 1));
+        writeln!(
+            vk_x,
+            "        if ({0} >= R || !mulAdd(x, {1}_X, {1}_Y, {0})) return false;",
+            s, ic
+        )
+        .unwrap();
+    }
+    // e(-A, B) e(α, β) e(x, γ) e(C, δ) = 1, for x the public inputs' combination of the IC
+    let mut pairing = String::new();
+    let words = [
+        "a[0]", "ay", "b[0][0]", "b[0][1]", "b[1][0]", "b[1][1]", "ALPHA_X", "ALPHA_Y", "BETA_X1",
+        "BETA_X0", "BETA_Y1", "BETA_Y0", "x[0]", "x[1]", "GAMMA_X1", "GAMMA_X0", "GAMMA_Y1",
+        "GAMMA_Y0", "c[0]", "c[1]", "DELTA_X1", "DELTA_X0", "DELTA_Y1", "DELTA_Y0",
+    ];
+    for (i, word) in words.iter().enumerate() {
+        writeln!(pairing, "        p[{}] = {};", i, word).unwrap();
+    }
+
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// Verifies Groth16 proofs for one circuit, with the EVM's BN254 precompiles.
+contract Groth16Verifier {{
+    // G2 coordinates are written imaginary part first, as the pairing precompile takes them.
+{constants}
+    /// Whether (a, b, c) proves the statement with these public inputs.
+    function verifyProof(
+        uint256[2] calldata a,
+        uint256[2][2] calldata b,
+        uint256[2] calldata c{input}
+    ) external view returns (bool) {{
+        uint256[2] memory x = [IC0_X, IC0_Y];
+{vk_x}        if (a[1] >= Q) return false;
+        uint256 ay = (Q - a[1]) % Q;
+        uint256[24] memory p;
+{pairing}        uint256[1] memory out;
+        bool ok;
+        assembly {{
+            ok := staticcall(gas(), 0x08, p, 0x300, out, 0x20)
+        }}
+        return ok && out[0] == 1;
+    }}
+
+    /// Set p to p + s q, in G1, for q = (qx, qy). False if a precompile fails.
+    function mulAdd(uint256[2] memory p, uint256 qx, uint256 qy, uint256 s)
+        internal
+        view
+        returns (bool ok)
+    {{
+        uint256[4] memory t;
+        t[0] = qx;
+        t[1] = qy;
+        t[2] = s;
+        assembly {{
+            ok := staticcall(gas(), 0x07, t, 0x60, t, 0x40)
+        }}
+        if (!ok) return false;
+        t[2] = p[0];
+        t[3] = p[1];
+        assembly {{
+            ok := staticcall(gas(), 0x06, t, 0x80, p, 0x40)
+        }}
+    }}
+}}
+"#,
+        constants = constants,
+        input = input,
+        vk_x = vk_x,
+        pairing = pairing,
+    )
+}
+
+/// The calldata of a call to [solidity_verifier]'s `verifyProof`, with `proof` and
+/// `public_inputs`.
+///
+/// # Panics
+///
+/// If `proof` is not over BN254.
+pub fn solidity_calldata<P: BnParameters>(
+    proof: &Proof<Bn<P>>,
+    public_inputs: &[<Bn<P> as PairingEngine>::Fr],
+) -> Vec<u8> {
+    check_curve::<P>();
+    let selector = Keccak256::digest(signature(public_inputs.len()).as_bytes());
+    let mut bytes = selector[..4].to_vec();
+    let coordinates = g1::<P>(&proof.a)
+        .iter()
+        .chain(&g2::<P>(&proof.b))
+        .chain(&g1::<P>(&proof.c))
+        .flat_map(|x| word(*x))
+        .collect::<Vec<_>>();
+    bytes.extend(coordinates);
+    bytes.extend(public_inputs.iter().flat_map(|x| word(*x)));
+    bytes
+}
@@ -0,0 +1,71 @@
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_groth16::{PreparedVerifyingKey, Proof};
+use ark_relations::r1cs::{Result as R1CSResult, SynthesisError};
+use ark_std::rand::Rng;
+use ark_std::{end_timer, start_timer, vec::Vec};
+
+/// Verify many Groth16 proofs against the prepared verification key `pvk` at once: `proofs`
+/// pairs each (revealed) proof with its instance.
+///
+/// Each proof's check `e(A, B) = e(α, β) e(IC, γ) e(C, δ)` is raised to a random 128-bit power
+/// drawn from `rng`, and the results are multiplied. The `IC` and `C` terms then collapse into
+/// one pairing each, so the batch costs a single multi-pairing of `proofs.len() + 2` Miller loops
+/// and one final exponentiation, rather than three pairings per proof. If any proof is invalid,
+/// the batch passes with probability at most `2^-128`.
+pub fn verify_batch<E, R>(
+    pvk: &PreparedVerifyingKey<E>,
+    proofs: &[(Proof<E>, Vec<E::Fr>)],
+    rng: &mut R,
+) -> R1CSResult<bool>
+where
+    E: PairingEngine,
+    R: Rng,
+{
+    let n_inputs = pvk.vk.gamma_abc_g1.len();
+    if proofs.iter().any(|(_, x)| x.len() + 1 != n_inputs) {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+    if proofs.is_empty() {
+        return Ok(true);
+    }
+    let timer = start_timer!(|| format!("Groth16 batch verification of {}", proofs.len()));
+
+    // The first power may as well be one.
+    let powers: Vec<E::Fr> = (0..proofs.len())
+        .map(|i| {
+            if i == 0 {
+                E::Fr::one()
+            } else {
+                E::Fr::from(rng.gen::<u128>())
+            }
+        })
+        .collect();
+
+    // Σ r_i IC_i = Σ_j (Σ_i r_i x_ij) γ_j, where x_i0 = 1
+    let mut ic_scalars = vec![E::Fr::zero(); n_inputs];
+    for ((_, x), r) in proofs.iter().zip(&powers) {
+        ic_scalars[0] += r;
+        for (s, x) in ic_scalars[1..].iter_mut().zip(x) {
+            *s += *r * x;
+        }
+    }
+    let ic = <E::G1Affine as AffineCurve>::multi_scalar_mul(&pvk.vk.gamma_abc_g1, &ic_scalars);
+    let cs: Vec<E::G1Affine> = proofs.iter().map(|(p, _)| p.c).collect();
+    let c = <E::G1Affine as AffineCurve>::multi_scalar_mul(&cs, &powers);
+
+    let mut pairs: Vec<(E::G1Prepared, E::G2Prepared)> = proofs
+        .iter()
+        .zip(&powers)
+        .map(|((p, _), r)| (p.a.scalar_mul(*r).into_affine().into(), p.b.into()))
+        .collect();
+    pairs.push((ic.into_affine().into(), pvk.gamma_g2_neg_pc.clone()));
+    pairs.push((c.into_affine().into(), pvk.delta_g2_neg_pc.clone()));
+    let lhs = E::final_exponentiation(&E::miller_loop(pairs.iter()))
+        .ok_or(SynthesisError::UnexpectedIdentity)?;
+
+    let power_sum: E::Fr = powers.iter().sum();
+    let rhs = pvk.alpha_g1_beta_g2.pow(power_sum.into_repr());
+    end_timer!(timer);
+    Ok(lhs == rhs)
+}
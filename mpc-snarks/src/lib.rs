@@ -6,8 +6,32 @@ mod tests {
     }
 }
 
+pub mod admission;
+pub mod api;
+pub mod data;
+pub mod demo;
 pub mod groth;
+pub mod qap;
+pub mod recursion;
+pub mod schnorr;
 pub mod silly;
+pub mod witness;
 
 mod cp;
 mod subspace_snark_tests;
+
+pub use api::{
+    prove_collaborative, prover_context, proving_key_tables, reveal_proof, setup,
+    setup_collaborative, setup_from_powers_of_tau, share_proving_key, verify, ByteOrder, JoinError,
+    Network, ProofFormat,
+};
+pub use groth::inputs::InputCommitments;
+pub use groth::prover::{ProveError, ProveOptions, ProverContext};
+pub use groth::check::{debug_check_constraints, CheckError, UnsatisfiedConstraints};
+pub use groth::checkpoint::{Checkpoint, CheckpointError};
+pub use groth::export::{DebugExport, ExportError};
+pub use groth::import::{check_proving_key, read_zkey, ImportError, PowersOfTau};
+pub use groth::public_inputs::{PublicInputs, PublicInputsError};
+pub use groth::report::ProvingReport;
+pub use groth::sliced_key::{SliceError, SlicedKey};
+pub use groth::solidity::{solidity_calldata, solidity_verifier};
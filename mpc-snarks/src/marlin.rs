@@ -2,7 +2,8 @@ use super::silly::MySillyCircuit;
 use ark_marlin::{ahp::prover::*, *};
 use ark_poly::univariate::DensePolynomial;
 use ark_poly_commit::marlin_pc::MarlinKZG10;
-use ark_std::{end_timer, start_timer, test_rng};
+use ark_std::rand::Rng;
+use ark_std::{end_timer, start_timer};
 use blake2::Blake2s;
 use mpc_algebra::honest_but_curious::*;
 use mpc_algebra::Reveal;
@@ -83,10 +84,11 @@ type LocalMarlinKZG10 = MarlinKZG10<E, DensePolynomial<Fr>>;
 type LocalMarlin = Marlin<Fr, LocalMarlinKZG10, Blake2s>;
 type MpcMarlin = Marlin<MFr, MpcMarlinKZG10, Blake2s>;
 
-pub fn mpc_test_prove_and_verify(n_iters: usize) {
-    let rng = &mut test_rng();
-
-    let srs = LocalMarlin::universal_setup(100, 50, 100, rng).unwrap();
+/// Prove and verify `n_iters` proofs, drawing the universal setup and the verifier's coins from
+/// `public_rng`, which every party must draw alike, and the prover's blinding from `rng`, this
+/// party's own.
+pub fn mpc_test_prove_and_verify<P: Rng, R: Rng>(n_iters: usize, public_rng: &mut P, rng: &mut R) {
+    let srs = LocalMarlin::universal_setup(100, 50, 100, public_rng).unwrap();
     let empty_circuit: MySillyCircuit<Fr> = MySillyCircuit { a: None, b: None };
     let (index_pk, index_vk) = LocalMarlin::index(&srs, empty_circuit.clone()).unwrap();
     let mpc_index_pk = IndexProverKey::from_public(index_pk);
@@ -105,9 +107,9 @@ pub fn mpc_test_prove_and_verify(n_iters: usize) {
         let mpc_proof = MpcMarlin::prove(&mpc_index_pk, circ, rng).unwrap();
         let proof = pf_publicize(mpc_proof);
         let public_a = a.reveal();
-        let is_valid = LocalMarlin::verify(&index_vk, &inputs, &proof, rng).unwrap();
+        let is_valid = LocalMarlin::verify(&index_vk, &inputs, &proof, public_rng).unwrap();
         assert!(is_valid);
-        let is_valid = LocalMarlin::verify(&index_vk, &[public_a], &proof, rng).unwrap();
+        let is_valid = LocalMarlin::verify(&index_vk, &[public_a], &proof, public_rng).unwrap();
         assert!(!is_valid);
     }
 }
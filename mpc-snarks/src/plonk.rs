@@ -1,7 +1,8 @@
 use ark_ff::UniformRand;
 use ark_poly::univariate::DensePolynomial;
 use ark_poly_commit::marlin_pc::MarlinKZG10;
-use ark_std::{end_timer, start_timer, test_rng};
+use ark_std::rand::Rng;
+use ark_std::{end_timer, start_timer};
 use mpc_algebra::honest_but_curious::*;
 use mpc_plonk::*;
 use mpc_algebra::Reveal;
@@ -16,7 +17,13 @@ type LocalMarlinKZG10 = MarlinKZG10<E, DensePolynomial<F>>;
 type LocalPlonk = mpc_plonk::Plonk<F, LocalMarlinKZG10>;
 type MpcPlonk = mpc_plonk::Plonk<MF, MpcMarlinKZG10>;
 
-pub fn local_test_prove_and_verify(n_iters: usize) {
+/// Prove and verify a squaring circuit of `n_iters` steps, drawing the setup from `setup_rng` and
+/// the prover's blinding from `zk_rng`.
+pub fn local_test_prove_and_verify<P: Rng, R: Rng>(
+    n_iters: usize,
+    setup_rng: &mut P,
+    zk_rng: &mut R,
+) {
     use relations::{flat::*, structured::*};
     let steps = n_iters;
     let start = F::from(2u64);
@@ -25,9 +32,6 @@ pub fn local_test_prove_and_verify(n_iters: usize) {
     let public: HashMap<String, F> = vec![("out".to_owned(), res)].into_iter().collect();
     let circ = CircuitLayout::from_circuit(&c);
 
-    let setup_rng = &mut test_rng();
-    let zk_rng = &mut test_rng();
-
     let v_circ = {
         let mut t = circ.clone();
         t.p = None;
@@ -40,7 +44,13 @@ pub fn local_test_prove_and_verify(n_iters: usize) {
     LocalPlonk::verify(&vk, &v_circ, pf, &public);
 }
 
-pub fn mpc_test_prove_and_verify(n_iters: usize) {
+/// [local_test_prove_and_verify], with a shared witness. `setup_rng` must be drawn alike by every
+/// party, and `zk_rng`, which also draws the witness, by this party alone.
+pub fn mpc_test_prove_and_verify<P: Rng, R: Rng>(
+    n_iters: usize,
+    setup_rng: &mut P,
+    zk_rng: &mut R,
+) {
     use relations::{flat::*, structured::*};
     let steps = n_iters;
 
@@ -48,13 +58,11 @@ pub fn mpc_test_prove_and_verify(n_iters: usize) {
     let v_c = PlonkCircuit::<F>::new_squaring_circuit(steps, None);
     let v_circ = CircuitLayout::from_circuit(&v_c);
     // setup
-    let setup_rng = &mut test_rng();
     let srs = LocalPlonk::universal_setup(steps.next_power_of_two(), setup_rng);
     let (pk, vk) = LocalPlonk::circuit_setup(&srs, &v_circ);
 
     // data circuit
-    let data_rng = &mut test_rng();
-    let start = MF::rand(data_rng);
+    let start = MF::rand(zk_rng);
     let res = (0..steps).fold(start, |a, _| a * a);
     let public: HashMap<String, F> = vec![("out".to_owned(), res.reveal())]
         .into_iter()
@@ -64,7 +72,7 @@ pub fn mpc_test_prove_and_verify(n_iters: usize) {
 
     let t = start_timer!(|| "timed section");
     let mpc_pk = ProverKey::from_public(pk);
-    let mpc_pf = MpcPlonk::prove(&mpc_pk, &circ, &mut test_rng());
+    let mpc_pf = MpcPlonk::prove(&mpc_pk, &circ, zk_rng);
     let pf = mpc_pf.reveal();
     end_timer!(t);
     LocalPlonk::verify(&vk, &v_circ, pf, &public);
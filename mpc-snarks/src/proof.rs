@@ -7,29 +7,119 @@ use ark_relations::{
     lc,
     r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
 };
-use ark_std::test_rng;
 use ark_std::{end_timer, start_timer};
 use blake2::Blake2s;
-use clap::arg_enum;
+use clap::{arg_enum, ErrorKind};
 use log::debug;
-use mpc_algebra::{channel, MpcPairingEngine, PairingShare, Reveal};
-use mpc_net::{MpcMultiNet, MpcNet, MpcTwoNet};
+use mpc_algebra::channel::PointEncoding;
+use mpc_algebra::share::dynamic::{self, DynPairingShare, Scheme};
+#[cfg(feature = "sled")]
+use mpc_algebra::share::store::Stock;
+use mpc_algebra::shared_rng::SharedRng;
+use mpc_algebra::{channel, seed, share::blame, MpcPairingEngine, PairingShare, Reveal};
+use mpc_net::{
+    mem, profile, transcript, Compression, MpcMultiNet, MpcNet, MpcTwoNet, NetConfig, SealKey,
+    Shaping,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use structopt::StructOpt;
 
-use std::path::PathBuf;
+use std::convert::TryInto;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::groth::report::ProvingReport;
 
 mod groth;
 mod marlin;
+mod qap;
 mod silly;
 
 const TIMED_SECTION_LABEL: &str = "timed section";
 
+#[global_allocator]
+static ALLOC: mem::CountingAlloc = mem::CountingAlloc;
+
+/// A proof's randomness, from two sources that are kept apart.
+struct Rngs<R> {
+    /// For what is public: the keys, and the verifier's coins. Every party draws the same.
+    public: R,
+    /// For what is secret: the witness, and this party's shares of the prover's blinding. No
+    /// other party can draw the same.
+    secret: R,
+}
+
+impl Rngs<StdRng> {
+    /// For a prover on its own: both derived from `seed`, or else seeded from the OS.
+    fn local(seed: Option<u64>) -> Self {
+        let mut root = match seed {
+            Some(s) => StdRng::from_seed(seed_bytes(s)),
+            None => StdRng::from_rng(rand::thread_rng()).unwrap(),
+        };
+        Self {
+            public: StdRng::from_rng(&mut root).unwrap(),
+            secret: StdRng::from_rng(&mut root).unwrap(),
+        }
+    }
+
+    /// For a party of the current session: the public rng derived from the session's joint seed
+    /// (see [mpc_algebra::shared_rng]), and the secret one from [seed::rng], which is derived
+    /// from `--seed` and this party's id, if the session has a seed.
+    fn session() -> Self {
+        Self {
+            public: StdRng::from_rng(SharedRng::new(b"proof public")).unwrap(),
+            secret: seed::rng(b"proof secret"),
+        }
+    }
+}
+
+/// A `--seed`, as the seed of a session (see [seed::set_seed]).
+fn seed_bytes(seed: u64) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes[..8].copy_from_slice(&seed.to_le_bytes());
+    bytes
+}
+
+/// A `--seal-key` file's key.
+fn read_seal_key(path: &Path) -> SealKey {
+    let bytes = std::fs::read(path)
+        .unwrap_or_else(|e| panic!("could not read seal key {}: {}", path.display(), e));
+    let key = bytes
+        .try_into()
+        .unwrap_or_else(|b: Vec<u8>| panic!("seal key must be 32 bytes, not {}", b.len()));
+    SealKey(key)
+}
+
 trait SnarkBench {
-    fn local<E: PairingEngine>(n: usize, timer_label: &str);
-    fn ark_local<E: PairingEngine>(_n: usize, _timer_label: &str) {
+    fn local<E: PairingEngine, R: Rng>(n: usize, timer_label: &str, rngs: &mut Rngs<R>);
+    fn ark_local<E: PairingEngine, R: Rng>(_n: usize, _timer_label: &str, _rngs: &mut Rngs<R>) {
         unimplemented!("ark benchmark for {}", std::any::type_name::<Self>())
     }
-    fn mpc<E: PairingEngine, S: PairingShare<E>>(n: usize, timer_label: &str);
+    fn mpc<E: PairingEngine, S: PairingShare<E>, R: Rng>(
+        n: usize,
+        timer_label: &str,
+        rngs: &mut Rngs<R>,
+    );
+    /// [SnarkBench::mpc], with the Groth16 prover's options, returning what the proof cost this
+    /// party.
+    fn mpc_with<E: PairingEngine, S: PairingShare<E>, R: Rng>(
+        _n: usize,
+        _timer_label: &str,
+        _rngs: &mut Rngs<R>,
+        _flags: &ProverFlags,
+    ) -> ProvingReport {
+        unimplemented!("prover options for {}", std::any::type_name::<Self>())
+    }
+}
+
+/// The Groth16 prover's options, from the flags of `proof mpc`.
+#[derive(Clone, Copy, Default)]
+struct ProverFlags<'a> {
+    /// `--checkpoint`, and whether to `--resume`.
+    checkpoint: Option<(&'a Path, bool)>,
+    /// `--debug-export`.
+    export: Option<&'a Path>,
 }
 
 mod squarings {
@@ -67,37 +157,47 @@ mod squarings {
     pub mod groth {
         use super::*;
         use crate::ark_groth16::{generate_random_parameters, prepare_verifying_key, verify_proof};
-        use crate::groth::prover::create_random_proof;
+        use crate::groth::checkpoint::Checkpoint;
+        use crate::groth::export::DebugExport;
+        use crate::groth::prover::{create_random_proof, ProveOptions};
 
         pub struct Groth16Bench;
 
         impl SnarkBench for Groth16Bench {
-            fn local<E: PairingEngine>(n: usize, timer_label: &str) {
-                let rng = &mut test_rng();
+            fn local<E: PairingEngine, R: Rng>(n: usize, timer_label: &str, rngs: &mut Rngs<R>) {
                 let circ_no_data = RepeatedSquaringCircuit::without_data(n);
 
-                let params = generate_random_parameters::<E, _, _>(circ_no_data, rng).unwrap();
+                let params =
+                    generate_random_parameters::<E, _, _>(circ_no_data, &mut rngs.public).unwrap();
 
                 let pvk = prepare_verifying_key::<E>(&params.vk);
 
+                let rng = &mut rngs.secret;
                 let a = E::Fr::rand(rng);
                 let circ_data = RepeatedSquaringCircuit::from_start(a, n);
                 let public_inputs = vec![circ_data.chain.last().unwrap().unwrap()];
                 let timer = start_timer!(|| timer_label);
-                let proof = create_random_proof::<E, _, _>(circ_data, &params, rng).unwrap();
+                let (proof, _) =
+                    create_random_proof::<E, _, _>(circ_data, &params, rng, ProveOptions::default())
+                        .unwrap();
                 end_timer!(timer);
 
                 assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
             }
 
-            fn ark_local<E: PairingEngine>(n: usize, timer_label: &str) {
-                let rng = &mut test_rng();
+            fn ark_local<E: PairingEngine, R: Rng>(
+                n: usize,
+                timer_label: &str,
+                rngs: &mut Rngs<R>,
+            ) {
                 let circ_no_data = RepeatedSquaringCircuit::without_data(n);
 
-                let params = generate_random_parameters::<E, _, _>(circ_no_data, rng).unwrap();
+                let params =
+                    generate_random_parameters::<E, _, _>(circ_no_data, &mut rngs.public).unwrap();
 
                 let pvk = prepare_verifying_key::<E>(&params.vk);
 
+                let rng = &mut rngs.secret;
                 let a = E::Fr::rand(rng);
                 let circ_data = RepeatedSquaringCircuit::from_start(a, n);
                 let public_inputs = vec![circ_data.chain.last().unwrap().unwrap()];
@@ -109,37 +209,114 @@ mod squarings {
                 assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
             }
 
-            fn mpc<E: PairingEngine, S: PairingShare<E>>(n: usize, timer_label: &str) {
-                let rng = &mut test_rng();
-                let circ_no_data = RepeatedSquaringCircuit::without_data(n);
+            fn mpc<E: PairingEngine, S: PairingShare<E>, R: Rng>(
+                n: usize,
+                timer_label: &str,
+                rngs: &mut Rngs<R>,
+            ) {
+                mpc_proof::<E, S, R>(n, timer_label, rngs, &ProverFlags::default());
+            }
 
-                let params = generate_random_parameters::<E, _, _>(circ_no_data, rng).unwrap();
+            fn mpc_with<E: PairingEngine, S: PairingShare<E>, R: Rng>(
+                n: usize,
+                timer_label: &str,
+                rngs: &mut Rngs<R>,
+                flags: &ProverFlags,
+            ) -> ProvingReport {
+                mpc_proof::<E, S, R>(n, timer_label, rngs, flags)
+            }
+        }
 
-                let pvk = prepare_verifying_key::<E>(&params.vk);
-                let mpc_params = Reveal::from_public(params);
+        /// `n`'s checkpoint, in `dir`, created, or resumed if `resume`.
+        fn checkpoint(n: usize, dir: &Path, resume: bool) -> Checkpoint {
+            // a resumed proof must draw the same keys and witness, so it needs the same seed
+            let label = match seed::seed() {
+                Some(s) => {
+                    let seed = u64::from_le_bytes(s[..8].try_into().unwrap());
+                    format!("groth16 squaring {} seed {}", n, seed)
+                }
+                None => format!("groth16 squaring {}", n),
+            };
+            let dir = Checkpoint::party_dir(dir, MpcMultiNet::party_id());
+            let checkpoint = if resume {
+                Checkpoint::resume(&dir, &label).map(|c| {
+                    println!("Resuming after {} phases", c.resumed_phases());
+                    c
+                })
+            } else {
+                Checkpoint::create(&dir, &label)
+            };
+            checkpoint.unwrap_or_else(|e| panic!("{}", e))
+        }
 
-                let a = E::Fr::rand(rng);
-                let computation_timer = start_timer!(|| "do the mpc (cheat)");
-                let circ_data = mpc_squaring_circuit::<
-                    E::Fr,
-                    <MpcPairingEngine<E, S> as PairingEngine>::Fr,
-                >(a, n);
-                let public_inputs = vec![circ_data.chain.last().unwrap().unwrap().reveal()];
-                end_timer!(computation_timer);
-                MpcMultiNet::reset_stats();
-                let timer = start_timer!(|| timer_label);
-                let proof = channel::without_cheating(|| {
-                    let pf = create_random_proof::<MpcPairingEngine<E, S>, _, _>(circ_data, &mpc_params, rng)
-                        .unwrap();
-                    let reveal_timer = start_timer!(|| "reveal");
-                    let pf = pf.reveal();
-                    end_timer!(reveal_timer);
-                    pf
-                });
-                end_timer!(timer);
+        fn mpc_proof<E: PairingEngine, S: PairingShare<E>, R: Rng>(
+            n: usize,
+            timer_label: &str,
+            rngs: &mut Rngs<R>,
+            flags: &ProverFlags,
+        ) -> ProvingReport {
+            let mut checkpoint = flags
+                .checkpoint
+                .map(|(dir, resume)| checkpoint(n, dir, resume));
+            let mut export = flags.export.map(|dir| {
+                let dir = Checkpoint::party_dir(dir, MpcMultiNet::party_id());
+                DebugExport::create(&dir).unwrap_or_else(|e| panic!("{}", e))
+            });
 
-                assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+            let circ_no_data = RepeatedSquaringCircuit::without_data(n);
+
+            let params =
+                generate_random_parameters::<E, _, _>(circ_no_data, &mut rngs.public).unwrap();
+
+            let pvk = prepare_verifying_key::<E>(&params.vk);
+            let mpc_params = {
+                let _mem = mem::phase("key load");
+                Reveal::from_public(params)
+            };
+
+            let rng = &mut rngs.secret;
+            let a = E::Fr::rand(rng);
+            let computation_timer = start_timer!(|| "do the mpc (cheat)");
+            let circ_data = mpc_squaring_circuit::<
+                E::Fr,
+                <MpcPairingEngine<E, S> as PairingEngine>::Fr,
+                _,
+            >(a, n, rng);
+            let public_inputs = vec![circ_data.chain.last().unwrap().unwrap().reveal()];
+            end_timer!(computation_timer);
+            MpcMultiNet::reset_stats();
+            let timer = start_timer!(|| timer_label);
+            let (proof, report) = channel::without_cheating(|| {
+                let mut options = ProveOptions::default();
+                if let Some(checkpoint) = &mut checkpoint {
+                    options = options.checkpoint(checkpoint);
+                }
+                if let Some(export) = &mut export {
+                    options = options.export(export);
+                }
+                let (pf, report) = create_random_proof::<MpcPairingEngine<E, S>, _, _>(
+                    circ_data,
+                    &mpc_params,
+                    rng,
+                    options,
+                )
+                .unwrap_or_else(|e| panic!("{}", e));
+                let reveal_timer = start_timer!(|| "reveal");
+                let pf = pf.reveal();
+                end_timer!(reveal_timer);
+                (pf, report)
+            });
+            end_timer!(timer);
+
+            assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+            if let Some(export) = &export {
+                println!(
+                    "Exported {} values to {}",
+                    export.exported(),
+                    export.dir().display()
+                );
             }
+            report
         }
     }
 
@@ -155,55 +332,67 @@ mod squarings {
         pub struct MarlinBench;
 
         impl SnarkBench for MarlinBench {
-            fn local<E: PairingEngine>(n: usize, timer_label: &str) {
-                let rng = &mut test_rng();
+            fn local<E: PairingEngine, R: Rng>(n: usize, timer_label: &str, rngs: &mut Rngs<R>) {
+                let Rngs { public, secret } = rngs;
                 let circ_no_data = RepeatedSquaringCircuit::without_data(n);
 
-                let srs = KzgMarlin::<E::Fr, E>::universal_setup(n, n + 2, 3 * n, rng).unwrap();
+                let srs =
+                    KzgMarlin::<E::Fr, E>::universal_setup(n, n + 2, 3 * n, public).unwrap();
 
                 let (pk, vk) = KzgMarlin::<E::Fr, E>::index(&srs, circ_no_data).unwrap();
 
-                let a = E::Fr::rand(rng);
+                let a = E::Fr::rand(secret);
                 let circ_data = RepeatedSquaringCircuit::from_start(a, n);
                 let public_inputs = vec![circ_data.chain.last().unwrap().unwrap()];
                 let timer = start_timer!(|| timer_label);
-                let zk_rng = &mut test_rng();
-                let proof = KzgMarlin::<E::Fr, E>::prove(&pk, circ_data, zk_rng).unwrap();
+                let proof = KzgMarlin::<E::Fr, E>::prove(&pk, circ_data, secret).unwrap();
                 end_timer!(timer);
-                assert!(KzgMarlin::<E::Fr, E>::verify(&vk, &public_inputs, &proof, rng).unwrap());
+                assert!(
+                    KzgMarlin::<E::Fr, E>::verify(&vk, &public_inputs, &proof, public).unwrap()
+                );
             }
 
-            fn mpc<E: PairingEngine, S: PairingShare<E>>(n: usize, timer_label: &str) {
-                let rng = &mut test_rng();
+            fn mpc<E: PairingEngine, S: PairingShare<E>, R: Rng>(
+                n: usize,
+                timer_label: &str,
+                rngs: &mut Rngs<R>,
+            ) {
+                let Rngs { public, secret } = rngs;
                 let circ_no_data = RepeatedSquaringCircuit::without_data(n);
 
-                let srs = KzgMarlin::<E::Fr, E>::universal_setup(n, n + 2, 3 * n, rng).unwrap();
+                let srs =
+                    KzgMarlin::<E::Fr, E>::universal_setup(n, n + 2, 3 * n, public).unwrap();
 
                 let (pk, vk) = KzgMarlin::<E::Fr, E>::index(&srs, circ_no_data).unwrap();
-                let mpc_pk = IndexProverKey::from_public(pk);
+                let mpc_pk = {
+                    let _mem = mem::phase("key load");
+                    IndexProverKey::from_public(pk)
+                };
 
-                let a = E::Fr::rand(rng);
+                let a = E::Fr::rand(secret);
                 let computation_timer = start_timer!(|| "do the mpc (cheat)");
                 let circ_data = mpc_squaring_circuit::<
                     E::Fr,
                     <MpcPairingEngine<E, S> as PairingEngine>::Fr,
-                >(a, n);
+                    _,
+                >(a, n, secret);
                 let public_inputs = vec![circ_data.chain.last().unwrap().unwrap().reveal()];
                 end_timer!(computation_timer);
 
                 MpcMultiNet::reset_stats();
                 let timer = start_timer!(|| timer_label);
-                let zk_rng = &mut test_rng();
                 let proof = channel::without_cheating(|| {
                     KzgMarlin::<
                         <MpcPairingEngine<E, S> as PairingEngine>::Fr,
                         MpcPairingEngine<E, S>,
-                    >::prove(&mpc_pk, circ_data, zk_rng)
+                    >::prove(&mpc_pk, circ_data, secret)
                     .unwrap()
                     .reveal()
                 });
                 end_timer!(timer);
-                assert!(KzgMarlin::<E::Fr, E>::verify(&vk, &public_inputs, &proof, rng).unwrap());
+                assert!(
+                    KzgMarlin::<E::Fr, E>::verify(&vk, &public_inputs, &proof, public).unwrap()
+                );
             }
         }
     }
@@ -236,20 +425,21 @@ mod squarings {
         pub struct PlonkBench;
 
         impl SnarkBench for PlonkBench {
-            fn local<E: PairingEngine>(n: usize, timer_label: &str) {
-                let rng = &mut test_rng();
+            fn local<E: PairingEngine, R: Rng>(n: usize, timer_label: &str, rngs: &mut Rngs<R>) {
+                let Rngs {
+                    public: setup_rng,
+                    secret: zk_rng,
+                } = rngs;
                 let circ_no_data = plonk_squaring_circuit(RepeatedSquaringCircuit::without_data(n));
                 let circ_no_data = CircuitLayout::from_circuit(&circ_no_data);
 
-                let a = E::Fr::rand(rng);
+                let a = E::Fr::rand(zk_rng);
                 let circ_data = RepeatedSquaringCircuit::from_start(a, n);
                 let plonk_circ_data = plonk_squaring_circuit(circ_data.clone());
                 let plonk_circ_data = CircuitLayout::from_circuit(&plonk_circ_data);
                 let public_inputs =
                     std::iter::once(("out".to_owned(), circ_data.chain.last().unwrap().unwrap()))
                         .collect();
-                let setup_rng = &mut test_rng();
-                let zk_rng = &mut test_rng();
                 let srs =
                     MarlinPcPlonk::<E::Fr, E>::universal_setup(n.next_power_of_two(), setup_rng);
                 let (pk, vk) = MarlinPcPlonk::<E::Fr, E>::circuit_setup(&srs, &circ_no_data);
@@ -259,16 +449,24 @@ mod squarings {
                 MarlinPcPlonk::<E::Fr, E>::verify(&vk, &circ_no_data, pf, &public_inputs);
             }
 
-            fn mpc<E: PairingEngine, S: PairingShare<E>>(n: usize, timer_label: &str) {
-                let rng = &mut test_rng();
+            fn mpc<E: PairingEngine, S: PairingShare<E>, R: Rng>(
+                n: usize,
+                timer_label: &str,
+                rngs: &mut Rngs<R>,
+            ) {
+                let Rngs {
+                    public: setup_rng,
+                    secret: zk_rng,
+                } = rngs;
                 let circ_no_data = plonk_squaring_circuit(RepeatedSquaringCircuit::without_data(n));
                 let circ_no_data = CircuitLayout::from_circuit(&circ_no_data);
 
-                let a = E::Fr::rand(rng);
+                let a = E::Fr::rand(zk_rng);
                 let circ_data = mpc_squaring_circuit::<
                     E::Fr,
                     <MpcPairingEngine<E, S> as PairingEngine>::Fr,
-                >(a, n);
+                    _,
+                >(a, n, zk_rng);
                 let plonk_circ_data = plonk_squaring_circuit(circ_data.clone());
                 let plonk_circ_data = CircuitLayout::from_circuit(&plonk_circ_data);
                 let public_inputs = std::iter::once((
@@ -276,12 +474,13 @@ mod squarings {
                     circ_data.chain.last().unwrap().unwrap().reveal(),
                 ))
                 .collect();
-                let setup_rng = &mut test_rng();
-                let zk_rng = &mut test_rng();
                 let srs =
                     MarlinPcPlonk::<E::Fr, E>::universal_setup(n.next_power_of_two(), setup_rng);
                 let (pk, vk) = MarlinPcPlonk::<E::Fr, E>::circuit_setup(&srs, &circ_no_data);
-                let mpc_pk = Reveal::from_public(pk);
+                let mpc_pk = {
+                    let _mem = mem::phase("key load");
+                    Reveal::from_public(pk)
+                };
                 MpcMultiNet::reset_stats();
                 let t = start_timer!(|| timer_label);
                 let pf = channel::without_cheating(|| {
@@ -301,14 +500,16 @@ mod squarings {
         }
     }
 
-    fn mpc_squaring_circuit<Fr: Field, MFr: Field + Reveal<Base = Fr>>(
+    /// The chain from `start`, shared by the king, who draws its shares from `rng`.
+    fn mpc_squaring_circuit<Fr: Field, MFr: Field + Reveal<Base = Fr>, R: Rng>(
         start: Fr,
         squarings: usize,
+        rng: &mut R,
     ) -> RepeatedSquaringCircuit<MFr> {
         let raw_chain: Vec<Fr> = std::iter::successors(Some(start), |a| Some(a.square()))
             .take(squarings + 1)
             .collect();
-        let rng = &mut test_rng();
+        let _mem = mem::phase("share buffers");
         let chain_shares = MFr::king_share_batch(raw_chain, rng);
         RepeatedSquaringCircuit {
             chain: chain_shares.into_iter().map(Some).collect(),
@@ -346,7 +547,8 @@ mod squarings {
 
 #[derive(Debug, StructOpt)]
 struct ShareInfo {
-    /// File with list of hosts
+    /// File with list of hosts: one HOST:PORT per line, or quic://HOST:PORT for QUIC (which needs
+    /// the quic feature)
     #[structopt(long, parse(from_os_str))]
     hosts: PathBuf,
 
@@ -357,42 +559,818 @@ struct ShareInfo {
     /// Use spdz?
     #[structopt(long)]
     alg: MpcAlg,
+
+    /// Seconds to wait for the other parties at start-up
+    #[structopt(long, default_value = "30")]
+    connect_timeout: u64,
+
+    /// Seconds a single read or write may block before a party is presumed dead (0 to wait
+    /// forever)
+    #[structopt(long, default_value = "600")]
+    io_timeout: u64,
+
+    /// Tell the other parties every SECONDS that this one is alive, and give up at once on a
+    /// party that misses a few of these, rather than at the I/O timeout. All parties must agree
+    /// on whether to
+    #[structopt(long, value_name = "SECONDS")]
+    heartbeat: Option<f64>,
+
+    /// Message compression: none, lz4, or zstd[:level] if mpc-net has its zstd feature
+    #[structopt(long, default_value = "none")]
+    compression: Compression,
+
+    /// Coalesce the messages to each party into fewer writes
+    #[structopt(long)]
+    batch: bool,
+
+    /// Delay each message by MS milliseconds on its way to the other parties, as a WAN would, to
+    /// benchmark over one without one
+    #[structopt(long, value_name = "MS")]
+    latency: Option<f64>,
+
+    /// Send to each other party at most MBIT megabits per second, as a WAN would
+    #[structopt(long, value_name = "MBIT")]
+    bandwidth: Option<f64>,
+
+    /// Seal every message with a key derived from the 32 bytes in FILE, so that one altered on
+    /// the way is caught, for links without TLS. All parties must have the same key
+    #[structopt(long, parse(from_os_str), value_name = "FILE")]
+    seal_key: Option<PathBuf>,
+
+    /// Write a transcript of every message this party sends and receives, with when it did and
+    /// in what phase, to DIR/party<id>.transcript
+    #[structopt(long, parse(from_os_str), value_name = "DIR")]
+    record: Option<PathBuf>,
+
+    /// Re-run this party alone, offline, against its transcript in DIR (from --record), which
+    /// stands in for the other parties; --hosts is then ignored. Pass the recorded run's --seed,
+    /// so the party sends what it did then; the run stops at the first message that differs
+    #[structopt(long, parse(from_os_str), value_name = "DIR", conflicts_with = "record")]
+    replay: Option<PathBuf>,
+
+    /// How to send curve points: compressed (half the bytes, for WANs) or uncompressed (less
+    /// computation, for LANs). All parties must agree on it
+    #[structopt(long, default_value = "compressed")]
+    point_encoding: PointEncoding,
+
+    /// Most field or group elements to send in one message; longer vectors are split. All
+    /// parties must agree on it
+    #[structopt(long)]
+    max_chunk: Option<usize>,
+
+    /// Save each phase of the Groth16 prover to DIR/party<id>, so that an interrupted proof can
+    /// be resumed
+    #[structopt(long, parse(from_os_str), value_name = "DIR")]
+    checkpoint: Option<PathBuf>,
+
+    /// Resume the proof from its checkpoint. All parties must resume, with the --seed of the
+    /// interrupted proof, and they carry on from the last phase that all of them finished
+    #[structopt(long, requires = "checkpoint")]
+    resume: bool,
+
+    /// Export this party's shares of the prover's intermediate values (the witness map's
+    /// polynomials, and each MSM's scalars and result) to DIR/party<id>, for offline study
+    #[structopt(long, parse(from_os_str), value_name = "DIR")]
+    debug_export: Option<PathBuf>,
+
+    /// When a SPDZ MAC check fails, find out which party cheated, at the cost of extra
+    /// communication for every opening. All parties must agree on it
+    #[structopt(long)]
+    identifiable_abort: bool,
+
+    /// Prove with shares whose scheme is chosen at run time, by --alg, rather than with shares of
+    /// that scheme's own type: the prover is then compiled once for every scheme
+    #[structopt(long)]
+    dyn_shares: bool,
+
+    /// Print what the Groth16 proof cost this party (the time of each of the prover's phases, the
+    /// bytes it sent and received, and the triples and openings it used) in FORMAT: json
+    #[structopt(long, value_name = "FORMAT")]
+    report: Option<ReportFormat>,
 }
 
 impl ShareInfo {
-    fn setup(&self) {
-        MpcMultiNet::init_from_file(self.hosts.to_str().unwrap(), self.party as usize)
+    fn setup(&self, seed: Option<u64>) {
+        MpcMultiNet::set_config(NetConfig {
+            connect_timeout: Duration::from_secs(self.connect_timeout),
+            io_timeout: Some(Duration::from_secs(self.io_timeout)).filter(|t| !t.is_zero()),
+            compression: self.compression,
+            batch: self.batch,
+            heartbeat: self.heartbeat.map(Duration::from_secs_f64),
+            shaping: self.shaping(),
+            seal: self.seal_key.as_deref().map(read_seal_key),
+            record: self.record.clone(),
+            ..NetConfig::default()
+        });
+        // give up at once, even mid-computation; the others then see us hang up, and abort too
+        MpcMultiNet::on_unreachable(|e| {
+            eprintln!("mpc-net: aborting: {}", e);
+            std::process::exit(1);
+        });
+        match &self.replay {
+            Some(dir) => MpcMultiNet::init_replay(&transcript::path(dir, self.party as usize)),
+            None => MpcMultiNet::init_from_file(self.hosts.to_str().unwrap(), self.party as usize),
+        }
+        // before the shared rng, whose joint seed is drawn from each party's
+        seed::set_seed(seed.map(seed_bytes));
+        mpc_algebra::channel::set_max_chunk(self.max_chunk);
+        mpc_algebra::channel::set_point_encoding(Some(self.point_encoding));
+        mpc_algebra::shared_rng::setup();
+        blame::set_identifiable_abort(self.identifiable_abort);
+        if self.dyn_shares {
+            dynamic::set_scheme(self.alg.into());
+        }
+    }
+    fn shaping(&self) -> Option<Shaping> {
+        if self.latency.is_none() && self.bandwidth.is_none() {
+            return None;
+        }
+        Some(Shaping {
+            latency: Duration::from_secs_f64(self.latency.unwrap_or(0.0) / 1e3),
+            bandwidth: self.bandwidth.map(|mbit| (mbit * 1e6 / 8.0) as u64),
+        })
     }
     fn teardown(&self) {
         debug!("Stats: {:#?}", MpcMultiNet::stats());
         MpcMultiNet::deinit();
     }
-    fn run<E: PairingEngine, B: SnarkBench>(
+    fn run<E: PairingEngine, B: SnarkBench, R: Rng>(
         &self,
         computation: Computation,
         computation_size: usize,
         _b: B,
         timed_label: &str,
+        rngs: &mut Rngs<R>,
     ) {
-        match computation {
-            Computation::Squaring => match self.alg {
-                MpcAlg::Spdz => B::mpc::<E, mpc_algebra::share::spdz::SpdzPairingShare<E>>(
-                    computation_size,
-                    timed_label,
-                ),
-                MpcAlg::Hbc => B::mpc::<E, mpc_algebra::share::add::AdditivePairingShare<E>>(
-                    computation_size,
-                    timed_label,
-                ),
-                MpcAlg::Gsz => B::mpc::<E, mpc_algebra::share::gsz20::GszPairingShare<E>>(
-                    computation_size,
-                    timed_label,
-                ),
-            },
+        let (n, label) = (computation_size, timed_label);
+        let flags = ProverFlags {
+            checkpoint: self.checkpoint.as_deref().map(|dir| (dir, self.resume)),
+            export: self.debug_export.as_deref(),
+        };
+        if self.report.is_none() && flags.checkpoint.is_none() && flags.export.is_none() {
+            return match (computation, self.dyn_shares) {
+                (Computation::Squaring, true) => {
+                    B::mpc::<E, DynPairingShare<E>, R>(n, label, rngs)
+                }
+                (_, false) => run_mpc::<E, B, R>(self.alg, computation, n, label, rngs),
+            };
+        }
+        let report = match (computation, self.dyn_shares) {
+            (Computation::Squaring, true) => {
+                B::mpc_with::<E, DynPairingShare<E>, R>(n, label, rngs, &flags)
+            }
+            (_, false) => run_mpc_with::<E, B, R>(self.alg, computation, n, label, rngs, &flags),
+        };
+        match self.report {
+            Some(ReportFormat::Json) => println!("{}", report.to_json()),
+            None => {}
         }
     }
 }
 
+/// Run the MPC prover for `computation`, with shares of kind `alg`.
+fn run_mpc<E: PairingEngine, B: SnarkBench, R: Rng>(
+    alg: MpcAlg,
+    computation: Computation,
+    computation_size: usize,
+    timed_label: &str,
+    rngs: &mut Rngs<R>,
+) {
+    use mpc_algebra::share::{add::AdditivePairingShare, gsz20::GszPairingShare};
+    use mpc_algebra::share::spdz::SpdzPairingShare;
+    let (n, label) = (computation_size, timed_label);
+    match computation {
+        Computation::Squaring => match alg {
+            MpcAlg::Spdz => B::mpc::<E, SpdzPairingShare<E>, R>(n, label, rngs),
+            MpcAlg::Hbc => B::mpc::<E, AdditivePairingShare<E>, R>(n, label, rngs),
+            MpcAlg::Gsz => B::mpc::<E, GszPairingShare<E>, R>(n, label, rngs),
+        },
+    }
+}
+
+/// [run_mpc], with the Groth16 prover's options, returning what the proof cost this party.
+fn run_mpc_with<E: PairingEngine, B: SnarkBench, R: Rng>(
+    alg: MpcAlg,
+    computation: Computation,
+    computation_size: usize,
+    timed_label: &str,
+    rngs: &mut Rngs<R>,
+    flags: &ProverFlags,
+) -> ProvingReport {
+    use mpc_algebra::share::{add::AdditivePairingShare, gsz20::GszPairingShare};
+    use mpc_algebra::share::spdz::SpdzPairingShare;
+    let (n, label) = (computation_size, timed_label);
+    match computation {
+        Computation::Squaring => match alg {
+            MpcAlg::Spdz => B::mpc_with::<E, SpdzPairingShare<E>, R>(n, label, rngs, flags),
+            MpcAlg::Hbc => B::mpc_with::<E, AdditivePairingShare<E>, R>(n, label, rngs, flags),
+            MpcAlg::Gsz => B::mpc_with::<E, GszPairingShare<E>, R>(n, label, rngs, flags),
+        },
+    }
+}
+
+/// `proof demo`: the example circuits of [mpc_snarks::demo], proved by parties that this process
+/// plays, connected in memory.
+mod demo {
+    use super::*;
+    use ark_bls12_377::{Bls12_377, Fr};
+    use ark_ed_on_bls12_377::{EdwardsParameters, Fr as EdFr};
+    use ark_ff::PrimeField;
+    use ark_serialize::CanonicalSerialize;
+    use mpc_algebra::share::spdz::SpdzPairingShare;
+    use mpc_algebra::share::{add::AdditivePairingShare, gsz20::GszPairingShare};
+    use mpc_algebra::{seed, FieldShare};
+    use mpc_snarks::demo::*;
+    use mpc_snarks::ProveError;
+
+    use std::any::Any;
+    use std::fmt::Display;
+    use std::io::{self, BufRead, IsTerminal, Write};
+    use std::process;
+    use std::str::FromStr;
+    use std::time::Instant;
+
+    type E = Bls12_377;
+    type P = EdwardsParameters;
+
+    /// The leaves of the membership example's tree.
+    const MEMBERS: [&str; 8] = [
+        "alice", "bob", "carol", "dave", "erin", "frank", "grace", "heidi",
+    ];
+
+    /// The secrets of an example, and what is public about them.
+    enum Secrets {
+        Multiplication {
+            a: u64,
+            b: u64,
+        },
+        Commitment {
+            value: u64,
+            blinding: EdFr,
+            commitment: ark_ed_on_bls12_377::EdwardsAffine,
+        },
+        Membership {
+            member: usize,
+        },
+    }
+
+    /// Asks questions on the terminal, or takes the defaults when it may not.
+    struct Prompt {
+        interactive: bool,
+    }
+
+    impl Prompt {
+        fn ask<T: FromStr + Display>(
+            &self,
+            question: &str,
+            default: T,
+            valid: impl Fn(&T) -> bool,
+        ) -> T {
+            if !self.interactive {
+                println!("{} {}", question, default);
+                return default;
+            }
+            let stdin = io::stdin();
+            loop {
+                print!("{} [{}] ", question, default);
+                io::stdout().flush().unwrap();
+                let mut line = String::new();
+                if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+                    // end of input
+                    println!();
+                    return default;
+                }
+                let line = line.trim();
+                if line.is_empty() {
+                    return default;
+                }
+                match line.parse() {
+                    Ok(t) if valid(&t) => return t,
+                    _ => println!("  \"{}\" won't do here.", line),
+                }
+            }
+        }
+    }
+
+    fn hex(f: &Fr) -> String {
+        format!("0x{}", f.into_repr())
+    }
+
+    fn leaf(member: usize) -> Fr {
+        Fr::from_le_bytes_mod_order(MEMBERS[member].as_bytes())
+    }
+
+    fn tree() -> MerkleTree<Fr> {
+        MerkleTree::new(&(0..MEMBERS.len()).map(leaf).collect::<Vec<_>>())
+    }
+
+    pub fn run(
+        example: Option<Example>,
+        parties: Option<usize>,
+        alg: Option<MpcAlg>,
+        yes: bool,
+        seed: Option<u64>,
+    ) {
+        let prompt = Prompt {
+            interactive: !yes && io::stdin().is_terminal(),
+        };
+        println!(
+            "In a collaborative proof, several parties prove a statement together, each about \
+             secrets that only it knows, and get an ordinary Groth16 proof.\nHere, this process \
+             plays every party; `proof -c squaring -p groth16 mpc --hosts FILE` runs each on a \
+             machine of its own.\n"
+        );
+        let example = example.unwrap_or_else(|| {
+            prompt.ask(
+                "Which example: multiplication, commitment or membership?",
+                Example::Multiplication,
+                |_| true,
+            )
+        });
+        let alg = alg.unwrap_or_else(|| {
+            prompt.ask(
+                "Which shares: spdz (any number may cheat), hbc (no one cheats) or gsz (a \
+                 minority may cheat)?",
+                MpcAlg::Spdz,
+                |_| true,
+            )
+        });
+        let least = if alg == MpcAlg::Gsz { 3 } else { 2 };
+        let parties = match parties {
+            Some(n) if n < least => {
+                eprintln!(
+                    "{} shares need at least {} parties",
+                    alg.to_string().to_lowercase(),
+                    least
+                );
+                process::exit(2);
+            }
+            Some(n) => n,
+            None => prompt.ask(&format!("How many parties (at least {})?", least), 3, |n| {
+                *n >= least
+            }),
+        };
+        println!();
+
+        let secrets = match example {
+            Example::Multiplication => {
+                println!(
+                    "Party 0 knows a, and party 1 knows b. They publish c = ab, and prove it, \
+                     while neither learns the other's factor."
+                );
+                let a = prompt.ask("Party 0's a?", 6, |_| true);
+                let b = prompt.ask("Party 1's b?", 7, |_| true);
+                Secrets::Multiplication { a, b }
+            }
+            Example::Commitment => {
+                println!(
+                    "Party 0 has published a Pedersen commitment to a secret value, on \
+                     Edwards-BLS12. The parties prove that the value fits in {} bits, without \
+                     learning it.",
+                    VALUE_BITS
+                );
+                let value = prompt.ask("Party 0's value?", 1_000_000, |_| true);
+                let blinding = EdFr::rand(&mut Rngs::local(seed).secret);
+                let commitment = commit::<P>(value, &blinding);
+                Secrets::Commitment {
+                    value,
+                    blinding,
+                    commitment,
+                }
+            }
+            Example::Membership => {
+                println!(
+                    "The members {} are the leaves of a Merkle tree, whose root is public. Party \
+                     1 is one of them, and the parties prove that it is a member, without \
+                     learning which.",
+                    MEMBERS.join(", ")
+                );
+                let name = prompt.ask("Which member is party 1?", "carol".to_string(), |m| {
+                    MEMBERS.contains(&m.as_str())
+                });
+                Secrets::Membership {
+                    member: MEMBERS.iter().position(|m| *m == name).unwrap(),
+                }
+            }
+        };
+
+        println!(
+            "\nProving with {} parties and {} shares...",
+            parties,
+            alg.to_string().to_lowercase()
+        );
+        let start = Instant::now();
+        let proven = match alg {
+            MpcAlg::Spdz => prove_locally::<SpdzPairingShare<E>>(parties, &secrets, seed),
+            MpcAlg::Hbc => prove_locally::<AdditivePairingShare<E>>(parties, &secrets, seed),
+            MpcAlg::Gsz => prove_locally::<GszPairingShare<E>>(parties, &secrets, seed),
+        };
+        let proven = match proven {
+            Ok(proven) => proven,
+            Err(e) => {
+                eprintln!("Proof aborted: {}", e);
+                process::exit(1);
+            }
+        };
+        println!("Done in {:.1?}.\n", start.elapsed());
+
+        match &secrets {
+            Secrets::Multiplication { a, b } => {
+                assert_eq!(proven.inputs[0], Fr::from(*a as u128 * *b as u128));
+                println!("Public: c = {}", *a as u128 * *b as u128)
+            }
+            Secrets::Commitment { .. } => println!(
+                "Public: the commitment ({}, {})",
+                hex(&proven.inputs[0]),
+                hex(&proven.inputs[1])
+            ),
+            Secrets::Membership { .. } => println!("Public: the root {}", hex(&proven.inputs[0])),
+        }
+        println!(
+            "The proof is {} bytes, and {}.",
+            proven.proof.serialized_size(),
+            if proven.verified {
+                "it verifies"
+            } else {
+                "it does NOT verify"
+            }
+        );
+        if !proven.verified {
+            process::exit(1);
+        }
+    }
+
+    /// Prove the example of `secrets` with `n` parties and shares `S`, giving out each secret to
+    /// its owner only, and return party 0's proof, once every party has the same. With `seed`,
+    /// every party's randomness is derived from it.
+    fn prove_locally<S: PairingShare<E>>(
+        n: usize,
+        secrets: &Secrets,
+        seed: Option<u64>,
+    ) -> Result<Proven<E>, String> {
+        let results = run_local(n, || {
+            seed::set_seed(seed.map(seed_bytes));
+            S::FrShare::init_protocol();
+            let rng = &mut seed::rng(b"demo");
+            let me = MpcMultiNet::party_id();
+            let proven = match secrets {
+                Secrets::Multiplication { a, b } => {
+                    let plain = MultiplicationCircuit {
+                        a: Some(Fr::from(*a)).filter(|_| me == 0),
+                        b: Some(Fr::from(*b)).filter(|_| me == 1),
+                    };
+                    let circuit = plain.share::<S::FrShare, _>(rng);
+                    let c = (circuit.a.unwrap() * circuit.b.unwrap()).reveal();
+                    let blank = MultiplicationCircuit { a: None, b: None };
+                    prove::<E, S, _, _, _>(blank, circuit, vec![c], rng)
+                }
+                Secrets::Commitment {
+                    value,
+                    blinding,
+                    commitment,
+                } => {
+                    let opening = Some((*value, *blinding)).filter(|_| me == 0);
+                    let plain = CommitmentCircuit::<P>::new(*commitment, opening);
+                    let circuit = plain.share::<S::FrShare, _>(0, rng);
+                    let blank = CommitmentCircuit::<P>::new(*commitment, None);
+                    prove::<E, S, _, _, _>(blank, circuit, plain.public_inputs(), rng)
+                }
+                Secrets::Membership { member } => {
+                    let tree = tree();
+                    let witness = Some((*member, leaf(*member))).filter(|_| me == 1);
+                    let plain = MembershipCircuit::new(&tree, witness);
+                    let circuit = plain.share::<S::FrShare, _>(1, rng);
+                    let blank = MembershipCircuit::new(&tree, None);
+                    prove::<E, S, _, _, _>(blank, circuit, plain.public_inputs(), rng)
+                }
+            };
+            S::FrShare::deinit_protocol();
+            proven
+        });
+        let all = results
+            .map_err(panic_message)?
+            .into_iter()
+            .collect::<Result<Vec<_>, ProveError>>()
+            .map_err(|e| e.to_string())?;
+        if all.iter().any(|p| p.proof != all[0].proof) {
+            return Err("the parties revealed different proofs".into());
+        }
+        Ok(all.into_iter().next().unwrap())
+    }
+
+    fn panic_message(payload: Box<dyn Any + Send>) -> String {
+        if let Some(e) = payload.downcast_ref::<mpc_net::NetError>() {
+            e.to_string()
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.clone()
+        } else if let Some(s) = payload.downcast_ref::<&str>() {
+            s.to_string()
+        } else {
+            "a party failed".into()
+        }
+    }
+}
+
+/// `proof run-local`: an MPC proof by parties in processes of their own, on localhost, which this
+/// process starts, supervises and reports on.
+mod supervisor {
+    use super::*;
+    use std::fs;
+    use std::io::{self, BufRead, BufReader, Read};
+    use std::net::TcpListener;
+    use std::process::{self, Child, Command, ExitStatus, Stdio};
+    use std::thread;
+    use std::time::Instant;
+
+    /// How often to check on the parties.
+    const POLL: Duration = Duration::from_millis(50);
+
+    /// How a party's process ended.
+    struct Outcome {
+        status: Option<ExitStatus>,
+        /// From its start.
+        elapsed: Duration,
+        /// How long its timed section took, as it logged.
+        timed: Option<Duration>,
+    }
+
+    /// A temporary hosts file, listing free ports on localhost.
+    struct HostsFile(PathBuf);
+
+    impl HostsFile {
+        fn new(n: usize) -> io::Result<Self> {
+            // hold every port until all are chosen, so that they are distinct
+            let listeners = (0..n)
+                .map(|_| TcpListener::bind("127.0.0.1:0"))
+                .collect::<io::Result<Vec<_>>>()?;
+            let mut text = String::new();
+            for l in &listeners {
+                text.push_str(&format!("{}\n", l.local_addr()?));
+            }
+            let path =
+                std::env::temp_dir().join(format!("proof-run-local-{}.hosts", process::id()));
+            fs::write(&path, text)?;
+            Ok(Self(path))
+        }
+    }
+
+    impl Drop for HostsFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    /// Run `opt`'s computation with `parties` processes of this binary, each proving as `proof
+    /// ... mpc --alg alg`, with `mpc_args` too. Exits unless every party succeeds.
+    pub fn run(opt: &Opt, parties: usize, alg: MpcAlg, mpc_args: &[String]) {
+        let hosts = HostsFile::new(parties).unwrap_or_else(|e| {
+            eprintln!("Could not choose the parties' ports: {}", e);
+            process::exit(1);
+        });
+        let exe = std::env::current_exe().expect("no path to this binary");
+        let start = Instant::now();
+        let mut children = Vec::new();
+        let (mut loggers, mut error_loggers) = (Vec::new(), Vec::new());
+        for party in 0..parties {
+            let mut command = Command::new(&exe);
+            command.args(&["-c", &opt.computation.to_string()]);
+            command.args(&["-p", &opt.proof_system.to_string()]);
+            command.args(&["--computation-size", &opt.computation_size.to_string()]);
+            if let Some(seed) = opt.seed {
+                command.args(&["--seed", &seed.to_string()]);
+            }
+            if let Some(dir) = &opt.profile {
+                command.arg("--profile").arg(dir);
+                if !opt.profile_phases.is_empty() {
+                    command.args(&["--profile-phases", &opt.profile_phases.join(",")]);
+                }
+                if opt.profile_cpu {
+                    command.arg("--profile-cpu");
+                }
+            }
+            command.args(&["mpc", "--hosts"]).arg(&hosts.0);
+            command.args(&["--party", &party.to_string(), "--alg", &alg.to_string()]);
+            command.args(mpc_args);
+            let mut child = command
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .unwrap_or_else(|e| {
+                    eprintln!("Could not start party {}: {}", party, e);
+                    process::exit(1);
+                });
+            let stdout = child.stdout.take().unwrap();
+            let stderr = child.stderr.take().unwrap();
+            loggers.push(thread::spawn(move || log_lines(party, stdout, false)));
+            error_loggers.push(thread::spawn(move || log_lines(party, stderr, true)));
+            children.push(child);
+        }
+
+        let ended = supervise(&mut children, start);
+        for logger in error_loggers {
+            let _ = logger.join();
+        }
+        let outcomes: Vec<Outcome> = ended
+            .into_iter()
+            .zip(loggers)
+            .map(|((status, elapsed), logger)| Outcome {
+                status,
+                elapsed,
+                timed: logger.join().unwrap_or(None),
+            })
+            .collect();
+        if !report(&outcomes, alg, start.elapsed()) {
+            process::exit(1);
+        }
+    }
+
+    /// Wait for every party to exit, and return how and when each did. Once one fails, the others
+    /// are killed, rather than left to time out; their status is then `None`.
+    fn supervise(children: &mut [Child], start: Instant) -> Vec<(Option<ExitStatus>, Duration)> {
+        let mut ended: Vec<Option<(Option<ExitStatus>, Duration)>> = vec![None; children.len()];
+        while ended.iter().any(Option::is_none) {
+            for (child, end) in children.iter_mut().zip(&mut ended) {
+                if end.is_none() {
+                    if let Some(status) = child.try_wait().expect("cannot wait for a party") {
+                        *end = Some((Some(status), start.elapsed()));
+                    }
+                }
+            }
+            let failed = ended
+                .iter()
+                .flatten()
+                .any(|(s, _)| !s.map_or(false, |s| s.success()));
+            if failed {
+                for (child, end) in children.iter_mut().zip(&mut ended) {
+                    if end.is_none() {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        *end = Some((None, start.elapsed()));
+                    }
+                }
+            }
+            thread::sleep(POLL);
+        }
+        ended.into_iter().flatten().collect()
+    }
+
+    /// Copy `party`'s output to ours, a line at a time, prefixed with its id, and return how
+    /// long its timed section took, if it says.
+    fn log_lines(party: usize, from: impl Read, stderr: bool) -> Option<Duration> {
+        let mut timed = None;
+        for line in BufReader::new(from).lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+            if stderr {
+                eprintln!("[party {}] {}", party, line);
+            } else {
+                println!("[party {}] {}", party, line);
+            }
+            timed = timed.or_else(|| timed_section(&line));
+        }
+        timed
+    }
+
+    /// The duration in a line of the timer of [TIMED_SECTION_LABEL], such as
+    /// `End:     timed section .....1.234s`, perhaps with colors.
+    fn timed_section(line: &str) -> Option<Duration> {
+        let mut plain = String::new();
+        let mut escape = false;
+        for c in line.chars() {
+            match c {
+                '\u{1b}' => escape = true,
+                'm' if escape => escape = false,
+                _ if escape => {}
+                _ => plain.push(c),
+            }
+        }
+        let rest = plain.trim().strip_prefix("End:")?.trim_start();
+        let rest = rest.strip_prefix(TIMED_SECTION_LABEL)?;
+        let time = rest.rsplit("..").next()?.trim_start_matches('.');
+        let (number, unit) = time.split_at(time.find(|c: char| c.is_alphabetic())?);
+        let number: f64 = number.parse().ok()?;
+        let seconds = match unit {
+            "s" => number,
+            "ms" => number / 1e3,
+            "µs" => number / 1e6,
+            "ns" => number / 1e9,
+            _ => return None,
+        };
+        Some(Duration::from_secs_f64(seconds))
+    }
+
+    /// Print how each party fared, and how long the session took, and return whether every
+    /// party succeeded.
+    fn report(outcomes: &[Outcome], alg: MpcAlg, wall: Duration) -> bool {
+        println!(
+            "\n{} parties, {} shares:",
+            outcomes.len(),
+            alg.to_string().to_lowercase()
+        );
+        for (party, o) in outcomes.iter().enumerate() {
+            let how = match o.status {
+                Some(s) if s.success() => "finished".to_string(),
+                Some(s) => format!("failed ({})", s),
+                None => "killed".to_string(),
+            };
+            let timed = o
+                .timed
+                .map(|t| format!(", timed section {:.3?}", t))
+                .unwrap_or_default();
+            println!("  party {}: {} in {:.3?}{}", party, how, o.elapsed, timed);
+        }
+        let slowest = outcomes.iter().map(|o| o.elapsed).max().unwrap_or_default();
+        println!("Slowest party: {:.3?}", slowest);
+        if let Some(timed) = outcomes.iter().map(|o| o.timed).collect::<Option<Vec<_>>>() {
+            println!(
+                "Slowest timed section: {:.3?}",
+                timed.into_iter().max().unwrap_or_default()
+            );
+        }
+        println!("Wall clock: {:.3?}", wall);
+        outcomes
+            .iter()
+            .all(|o| o.status.map_or(false, |s| s.success()))
+    }
+}
+
+/// `proof dealer`: a trusted dealer, which knows every secret its material masks, for experiments
+/// only.
+#[cfg(feature = "sled")]
+mod dealer {
+    use super::*;
+    use ark_bls12_377::Fr;
+    use mpc_algebra::share::dealer;
+    use mpc_algebra::share::store::{Preprocessed, SledStore, StoreError};
+    use mpc_algebra::{AdditiveFieldShare, FieldShare, GszFieldShare, SpdzFieldShare};
+
+    /// Deal `counts` of each kind of material, as this party of those in `hosts`, into
+    /// `out/party<id>`, and print what was dealt, as JSON. Exits unless dealing succeeds.
+    pub fn run(
+        hosts: &Path,
+        party: usize,
+        alg: MpcAlg,
+        out: &Path,
+        counts: Stock,
+        seed: Option<u64>,
+    ) {
+        eprintln!(
+            "WARNING: a trusted dealer knows every party's secrets. \
+             Its preprocessing is insecure for production."
+        );
+        let path = out.join(format!("party{}", party));
+        let r = mpc_net::catch_abort(|| {
+            MpcMultiNet::init_from_file(hosts.to_str().unwrap(), party);
+            seed::set_seed(seed.map(seed_bytes));
+            let dealt = match alg {
+                MpcAlg::Hbc => deal::<AdditiveFieldShare<Fr>>(&path, counts).map(|_| ()),
+                // the dealer's MAC key shares are checked and noted beside the material
+                MpcAlg::Spdz => deal::<SpdzFieldShare<Fr>>(&path, counts)
+                    .and_then(|pre| dealer::deal_mac_key::<Fr, _>(pre.store()).map(|_| ())),
+                MpcAlg::Gsz => deal::<GszFieldShare<Fr>>(&path, counts).map(|_| ()),
+            };
+            MpcMultiNet::deinit();
+            dealt
+        });
+        match r {
+            Ok(Ok(())) => println!(
+                "{{\"party\":{},\"alg\":\"{}\",\"store\":\"{}\",\
+                 \"source\":\"trusted dealer\",\"insecure_for_production\":true,\
+                 \"triples\":{},\"inv_pairs\":{},\"shares\":{},\"bits\":{},\"zeros\":{}}}",
+                party,
+                alg.to_string().to_lowercase(),
+                path.display().to_string().escape_default(),
+                counts.triples,
+                counts.inv_pairs,
+                counts.shares,
+                counts.bits,
+                counts.zeros,
+            ),
+            Ok(Err(e)) => {
+                eprintln!("Could not store the dealt material: {}", e);
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("Dealing aborted: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// Deal into the store at `path`, which is returned.
+    fn deal<S: FieldShare<Fr>>(
+        path: &Path,
+        counts: Stock,
+    ) -> Result<Preprocessed<Fr, S, SledStore>, StoreError> {
+        let pre = Preprocessed::<Fr, S, _>::open(SledStore::open(path)?)?;
+        dealer::deal(&pre, counts, &mut seed::rng(b"dealer"))?;
+        debug!("Stock at {}: {}", path.display(), pre.stock()?);
+        Ok(pre)
+    }
+}
+
 arg_enum! {
     #[derive(PartialEq, Debug, Clone, Copy)]
     pub enum MpcAlg {
@@ -402,6 +1380,16 @@ arg_enum! {
     }
 }
 
+impl From<MpcAlg> for Scheme {
+    fn from(alg: MpcAlg) -> Scheme {
+        match alg {
+            MpcAlg::Spdz => Scheme::Spdz,
+            MpcAlg::Hbc => Scheme::Hbc,
+            MpcAlg::Gsz => Scheme::Gsz,
+        }
+    }
+}
+
 arg_enum! {
     #[derive(PartialEq, Debug, Clone, Copy)]
     pub enum Computation {
@@ -409,6 +1397,22 @@ arg_enum! {
     }
 }
 
+arg_enum! {
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum Example {
+        Multiplication,
+        Commitment,
+        Membership,
+    }
+}
+
+arg_enum! {
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum ReportFormat {
+        Json,
+    }
+}
+
 arg_enum! {
     #[derive(PartialEq, Debug, Clone, Copy)]
     pub enum ProofSystem {
@@ -424,23 +1428,110 @@ enum FieldOpt {
         #[structopt(flatten)]
         party_info: ShareInfo,
     },
+    /// The MPC prover, run by a single party with no networking. Shares degrade to plain values,
+    /// so this is a baseline for the MPC code path.
+    Single {
+        /// Which kind of shares to use
+        #[structopt(long)]
+        alg: MpcAlg,
+    },
     Local,
     ArkLocal,
+    /// A guided tour: prove one of three example circuits, with every party played by this
+    /// process, asking for whatever the options leave out. `-c` and `-p` do not apply
+    Demo {
+        /// The example: multiplication, commitment or membership
+        #[structopt(long)]
+        example: Option<Example>,
+        /// How many parties to play
+        #[structopt(long)]
+        parties: Option<usize>,
+        /// Which kind of shares to use
+        #[structopt(long)]
+        alg: Option<MpcAlg>,
+        /// Take the defaults for whatever the options leave out, rather than asking
+        #[structopt(long)]
+        yes: bool,
+    },
+    /// The MPC prover, with each party in a process of its own on localhost, started and
+    /// supervised by this one, which prefixes their output with their ids and reports their
+    /// timings. Any arguments after `--` go to each party's `mpc`, such as `-- --batch`
+    RunLocal {
+        /// How many parties to run
+        #[structopt(long, default_value = "2")]
+        parties: usize,
+        /// Which kind of shares to use
+        #[structopt(long)]
+        alg: MpcAlg,
+        /// More options for each party's `mpc`
+        #[structopt(last = true)]
+        mpc_args: Vec<String>,
+    },
+    /// INSECURE, for experiments only: party 0 plays a trusted dealer, drawing every party's
+    /// preprocessing (triples, inverse pairs, random shares and bits, sharings of zero, and the
+    /// SPDZ MAC key's shares) in the clear and sending each party its shares. Each party stores
+    /// its own in DIR/party<id>, noted as insecure for production. `-c` and `-p` do not apply
+    #[cfg(feature = "sled")]
+    Dealer {
+        /// File with list of hosts
+        #[structopt(long, parse(from_os_str))]
+        hosts: PathBuf,
+        /// Which party are you? 0 is the dealer
+        #[structopt(long, default_value = "0")]
+        party: u8,
+        /// Which kind of shares to deal
+        #[structopt(long)]
+        alg: MpcAlg,
+        /// Where to store the parties' material
+        #[structopt(long, parse(from_os_str), value_name = "DIR")]
+        out: PathBuf,
+        /// How many multiplication triples to deal
+        #[structopt(long, default_value = "0")]
+        triples: usize,
+        /// How many pairs of a random element and its inverse to deal
+        #[structopt(long, default_value = "0")]
+        inv_pairs: usize,
+        /// How many random shares to deal
+        #[structopt(long, default_value = "0")]
+        shares: usize,
+        /// How many random bits to deal
+        #[structopt(long, default_value = "0")]
+        bits: usize,
+        /// How many sharings of zero to deal
+        #[structopt(long, default_value = "0")]
+        zeros: usize,
+    },
 }
 
 impl FieldOpt {
-    fn setup(&self) {
+    fn setup(&self, seed: Option<u64>) {
         match self {
-            FieldOpt::Mpc { party_info, .. } => party_info.setup(),
+            FieldOpt::Mpc { party_info, .. } => party_info.setup(seed),
+            FieldOpt::Single { .. } => {
+                MpcMultiNet::init_single_party();
+                seed::set_seed(seed.map(seed_bytes));
+            }
             _ => {}
         }
     }
     fn teardown(&self) {
+        if profile::is_active() {
+            match profile::finish() {
+                Ok(paths) => {
+                    for path in paths {
+                        println!("Profile: {}", path.display());
+                    }
+                }
+                Err(e) => eprintln!("Could not write the profile: {}", e),
+            }
+        }
         match self {
             FieldOpt::Mpc { party_info, .. } => party_info.teardown(),
+            FieldOpt::Single { .. } => MpcMultiNet::deinit(),
             _ => {}
         }
         println!("Stats: {:#?}", MpcMultiNet::stats());
+        println!("Memory: {:#?}", mem::report());
     }
     fn run<E: PairingEngine, B: SnarkBench>(
         &self,
@@ -448,14 +1539,45 @@ impl FieldOpt {
         computation_size: usize,
         b: B,
         timed_label: &str,
+        seed: Option<u64>,
     ) {
-        self.setup();
-        match self {
-            FieldOpt::Mpc { party_info, .. } => {
-                party_info.run::<E, B>(computation, computation_size, b, timed_label)
+        if let FieldOpt::Mpc { party_info } = self {
+            if party_info.resume && seed.is_none() {
+                eprintln!("--resume needs the --seed of the interrupted proof");
+                std::process::exit(2);
             }
-            FieldOpt::Local => B::local::<E>(computation_size, timed_label),
-            FieldOpt::ArkLocal => B::ark_local::<E>(computation_size, timed_label),
+        }
+        let r = blame::catch_mac_failure(|| {
+            mpc_net::catch_abort(|| {
+                self.setup(seed);
+                let (n, label) = (computation_size, timed_label);
+                match self {
+                    FieldOpt::Mpc { party_info, .. } => {
+                        let rngs = &mut Rngs::session();
+                        party_info.run::<E, B, _>(computation, n, b, label, rngs)
+                    }
+                    FieldOpt::Single { alg } => {
+                        let rngs = &mut Rngs::session();
+                        run_mpc::<E, B, _>(*alg, computation, n, label, rngs)
+                    }
+                    FieldOpt::Local => B::local::<E, _>(n, label, &mut Rngs::local(seed)),
+                    FieldOpt::ArkLocal => B::ark_local::<E, _>(n, label, &mut Rngs::local(seed)),
+                    FieldOpt::Demo { .. } => unreachable!("the demo runs on its own"),
+                    FieldOpt::RunLocal { .. } => unreachable!("run-local only supervises"),
+                    #[cfg(feature = "sled")]
+                    FieldOpt::Dealer { .. } => unreachable!("the dealer proves nothing"),
+                }
+            })
+        });
+        // a failed MAC check, with its culprits, or any other abort
+        let r = match r {
+            Ok(r) => r.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = r {
+            eprintln!("Proof aborted: {}", e);
+            MpcMultiNet::deinit();
+            std::process::exit(1);
         }
         self.teardown();
     }
@@ -465,44 +1587,140 @@ impl FieldOpt {
 #[structopt(name = "proof", about = "Standard and MPC proofs")]
 struct Opt {
     /// Computation to perform
-    #[structopt(short = "c")]
+    #[structopt(short = "c", default_value = "squaring")]
     computation: Computation,
 
     /// Proof system to use
-    #[structopt(short = "p")]
+    #[structopt(short = "p", default_value = "groth16")]
     proof_system: ProofSystem,
 
     /// Computation to perform
     #[structopt(long, default_value = "10")]
     computation_size: usize,
 
+    /// Profile this party, and write its time by span, as flamegraph folded stacks, to
+    /// DIR/party<id>.folded
+    #[structopt(long, parse(from_os_str), value_name = "DIR")]
+    profile: Option<PathBuf>,
+
+    /// Profile only these phases (comma-separated), such as "fft,msm"
+    #[structopt(long, use_delimiter = true, requires = "profile")]
+    profile_phases: Vec<String>,
+
+    /// Also sample where the CPU goes, in every thread, to DIR/party<id>.cpu.folded (needs the
+    /// pprof feature, with which each profile is also rendered, to DIR/party<id>.svg and so on)
+    #[structopt(long, requires = "profile")]
+    profile_cpu: bool,
+
+    /// Derive all randomness from this seed, so that runs are reproducible (for benchmarking and
+    /// testing: the proof's secrets are then predictable). Each party derives its own secrets from
+    /// it and its id, and the public randomness, such as the keys, is agreed from all of theirs
+    #[structopt(long)]
+    seed: Option<u64>,
+
     #[structopt(subcommand)]
     field: FieldOpt,
 }
 
-impl Opt {}
+impl Opt {
+    /// Exit with a usage error if an MPC party is given a flag that only the Groth16 prover
+    /// supports, with another proof system.
+    fn check_prover_flags(&self) {
+        let info = match &self.field {
+            FieldOpt::Mpc { party_info } if self.proof_system != ProofSystem::Groth16 => party_info,
+            _ => return,
+        };
+        let groth16_only = [
+            ("--report", info.report.is_some()),
+            ("--checkpoint", info.checkpoint.is_some()),
+            ("--debug-export", info.debug_export.is_some()),
+        ];
+        if let Some((flag, _)) = groth16_only.iter().find(|(_, set)| *set) {
+            let message = format!(
+                "{} is only supported with -p groth16, not -p {}",
+                flag,
+                self.proof_system.to_string().to_lowercase()
+            );
+            clap::Error::with_description(&message, ErrorKind::ArgumentConflict).exit();
+        }
+    }
+}
 
 fn main() {
     let opt = Opt::from_args();
+    opt.check_prover_flags();
     env_logger::init();
+    MpcMultiNet::abort_on_panic();
+    if let FieldOpt::Demo {
+        example,
+        parties,
+        alg,
+        yes,
+    } = opt.field
+    {
+        demo::run(example, parties, alg, yes, opt.seed);
+        return;
+    }
+    if let FieldOpt::RunLocal {
+        parties,
+        alg,
+        mpc_args,
+    } = &opt.field
+    {
+        supervisor::run(&opt, *parties, *alg, mpc_args);
+        return;
+    }
+    #[cfg(feature = "sled")]
+    if let FieldOpt::Dealer {
+        hosts,
+        party,
+        alg,
+        out,
+        triples,
+        inv_pairs,
+        shares,
+        bits,
+        zeros,
+    } = &opt.field
+    {
+        let counts = Stock {
+            triples: *triples,
+            inv_pairs: *inv_pairs,
+            shares: *shares,
+            bits: *bits,
+            zeros: *zeros,
+        };
+        dealer::run(hosts, *party as usize, *alg, out, counts, opt.seed);
+        return;
+    }
+    if let Some(dir) = &opt.profile {
+        profile::start(profile::ProfileConfig {
+            dir: dir.clone(),
+            phases: opt.profile_phases.clone(),
+            cpu: opt.profile_cpu,
+        });
+    }
     match opt.proof_system {
         ProofSystem::Groth16 => opt.field.run::<ark_bls12_377::Bls12_377, _>(
             opt.computation,
             opt.computation_size,
             squarings::groth::Groth16Bench,
             TIMED_SECTION_LABEL,
+            opt.seed,
         ),
         ProofSystem::Plonk => opt.field.run::<ark_bls12_377::Bls12_377, _>(
             opt.computation,
             opt.computation_size,
             squarings::plonk::PlonkBench,
             TIMED_SECTION_LABEL,
+            opt.seed,
         ),
         ProofSystem::Marlin => opt.field.run::<ark_bls12_377::Bls12_377, _>(
             opt.computation,
             opt.computation_size,
             squarings::marlin::MarlinBench,
             TIMED_SECTION_LABEL,
+            opt.seed,
         ),
     }
 }
@@ -0,0 +1,268 @@
+//! The R1CS-to-QAP witness map, for any prover that needs a constraint system's assignment as
+//! polynomials.
+//!
+//! For an assignment `z` to the matrices `A`, `B` and `C`, over an evaluation domain `H` with one
+//! point per constraint and per instance variable, [witness_map] gives the polynomials `a`, `b`
+//! and `c` that take the values of `Az`, `Bz` and `Cz` on `H`, and the quotient `h` of
+//! `a b - c` by `H`'s vanishing polynomial. As in Groth16, `a` also takes the value of each
+//! instance variable at one of the points after the constraints, so that `h` is bound to the
+//! public inputs. Groth16's prover needs only `h`, which [quotient] computes without keeping the
+//! others; commit-and-prove variants commit to `a`, `b` and `c` too.
+//!
+//! Everything is generic over the field, so over [MpcField](mpc_algebra::MpcField) the
+//! polynomials are shared. The FFTs are linear, and so are local; the only communication is one
+//! batch of multiplications, of `a` and `b` on a coset of `H`. The seven FFTs of a reduction share
+//! one computation of the domain's roots of unity (its [Twiddles]), and transform in place: besides
+//! the assignment, a reduction holds two vectors of the domain's size and one of the constraint
+//! matrices at a time.
+//!
+//! A prover that reduces many assignments over the same domain can build a [QapDomain] once, with
+//! the factors of the coset transforms precomputed, and reduce over it with [quotient_in].
+//!
+//! With this crate's `parallel` feature, the FFTs and the pointwise products by public factors run
+//! on rayon's pool; the batch of multiplications is still sent from the calling thread.
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Twiddles};
+use ark_relations::r1cs::{
+    ConstraintMatrix, ConstraintSystemRef, Result as R1CSResult, SynthesisError,
+};
+use ark_std::{cfg_iter_mut, end_timer, start_timer, vec};
+use core::ops::Deref;
+use std::sync::OnceLock;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// A constraint system's QAP polynomials, by their coefficients, from the lowest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Qap<F> {
+    pub a: Vec<F>,
+    pub b: Vec<F>,
+    pub c: Vec<F>,
+    /// `(a b - c) / Z_H`.
+    pub h: Vec<F>,
+}
+
+/// The QAP of `cs`, which must be finalized and have its assignment, over the smallest domain of
+/// type `D` that fits it.
+pub fn witness_map<F: PrimeField, D: EvaluationDomain<F>>(
+    cs: ConstraintSystemRef<F>,
+) -> R1CSResult<Qap<F>> {
+    witness_map_with::<F, D>(cs, |_, _| {})
+}
+
+/// [witness_map], showing `inspect` the polynomials along the way: the evaluations of `a`, `b`
+/// and `c` over the domain, then over its coset, and `h` over the coset.
+pub fn witness_map_with<F: PrimeField, D: EvaluationDomain<F>>(
+    cs: ConstraintSystemRef<F>,
+    inspect: impl FnMut(&'static str, &[F]),
+) -> R1CSResult<Qap<F>> {
+    let (polys, h) = reduce::<F, D>(cs, None, true, inspect)?;
+    let (a, b, c) = polys.unwrap();
+    Ok(Qap { a, b, c, h })
+}
+
+/// `h` alone, as [witness_map] computes it, with no copies of the other polynomials.
+pub fn quotient<F: PrimeField, D: EvaluationDomain<F>>(
+    cs: ConstraintSystemRef<F>,
+) -> R1CSResult<Vec<F>> {
+    quotient_with::<F, D>(cs, |_, _| {})
+}
+
+/// [quotient], showing `inspect` the polynomials as [witness_map_with] does.
+pub fn quotient_with<F: PrimeField, D: EvaluationDomain<F>>(
+    cs: ConstraintSystemRef<F>,
+    inspect: impl FnMut(&'static str, &[F]),
+) -> R1CSResult<Vec<F>> {
+    Ok(reduce::<F, D>(cs, None, false, inspect)?.1)
+}
+
+/// [quotient], over `domain`, which must be the domain that [quotient] would pick for `cs`.
+pub fn quotient_in<F: PrimeField, D: EvaluationDomain<F>>(
+    domain: &QapDomain<F, D>,
+    cs: ConstraintSystemRef<F>,
+) -> R1CSResult<Vec<F>> {
+    quotient_in_with(domain, cs, |_, _| {})
+}
+
+/// [quotient_in], showing `inspect` the polynomials as [witness_map_with] does.
+pub fn quotient_in_with<F: PrimeField, D: EvaluationDomain<F>>(
+    domain: &QapDomain<F, D>,
+    cs: ConstraintSystemRef<F>,
+    inspect: impl FnMut(&'static str, &[F]),
+) -> R1CSResult<Vec<F>> {
+    Ok(reduce::<F, D>(cs, Some(domain), false, inspect)?.1)
+}
+
+/// An evaluation domain for the witness map, with the factors that its coset transforms multiply
+/// by, and its FFTs' roots of unity, computed once, for every reduction over it.
+///
+/// The factors take two field elements per point of the domain, and the roots (of a radix-2
+/// domain) one more.
+#[derive(Clone, Debug)]
+pub struct QapDomain<F: PrimeField, D: EvaluationDomain<F>> {
+    domain: D,
+    /// Built by the first reduction.
+    twiddles: OnceLock<Twiddles<F>>,
+    /// `g^i`, for the offset `g` of the coset.
+    coset_powers: Vec<F>,
+    /// `g^-i`.
+    coset_inv_powers: Vec<F>,
+}
+
+impl<F: PrimeField, D: EvaluationDomain<F>> QapDomain<F, D> {
+    /// The smallest domain of type `D` with `num_points` points, one per constraint and per
+    /// instance variable.
+    pub fn new(num_points: usize) -> Option<Self> {
+        let domain = D::new(num_points)?;
+        let g = F::multiplicative_generator();
+        let powers = |g: F| {
+            let mut pow = F::one();
+            (0..domain.size())
+                .map(|_| {
+                    let p = pow;
+                    pow *= &g;
+                    p
+                })
+                .collect()
+        };
+        Some(Self {
+            twiddles: OnceLock::new(),
+            coset_powers: powers(g),
+            coset_inv_powers: powers(g.inverse()?),
+            domain,
+        })
+    }
+
+    pub fn domain(&self) -> &D {
+        &self.domain
+    }
+
+    pub fn size(&self) -> usize {
+        self.domain.size()
+    }
+}
+
+type Polys<F> = (Vec<F>, Vec<F>, Vec<F>);
+
+/// `h`, with the coefficients of `a`, `b` and `c` if `keep`, over `cached` if given, and otherwise
+/// over a domain of its own.
+///
+/// The evaluations live in two buffers of the domain's size: `a`'s becomes `a b` and then `h`,
+/// and `b`'s is reused for `c`. Each matrix is packed just before its product with the assignment
+/// and dropped after it, and the transforms are all in place.
+fn reduce<F: PrimeField, D: EvaluationDomain<F>>(
+    prover: ConstraintSystemRef<F>,
+    cached: Option<&QapDomain<F, D>>,
+    keep: bool,
+    mut inspect: impl FnMut(&'static str, &[F]),
+) -> R1CSResult<(Option<Polys<F>>, Vec<F>)> {
+    let zero = F::zero();
+    let num_inputs = prover.num_instance_variables();
+    let num_constraints = prover.num_constraints();
+    let cs = prover.borrow().unwrap();
+    let prover = cs.deref();
+
+    let domain = match cached {
+        Some(cached) => {
+            if Some(cached.size()) != D::compute_size_of_domain(num_constraints + num_inputs) {
+                return Err(SynthesisError::PolynomialDegreeTooLarge);
+            }
+            cached.domain
+        }
+        None => {
+            D::new(num_constraints + num_inputs).ok_or(SynthesisError::PolynomialDegreeTooLarge)?
+        }
+    };
+    let domain_size = domain.size();
+    let own_twiddles;
+    let twiddles = match cached {
+        Some(cached) => cached.twiddles.get_or_init(|| domain.twiddles()),
+        None => {
+            own_twiddles = domain.twiddles();
+            &own_twiddles
+        }
+    };
+    let coset_fft = |v: &mut Vec<F>| match cached {
+        Some(cached) => {
+            cfg_iter_mut!(v)
+                .zip(&cached.coset_powers)
+                .for_each(|(v_i, p)| *v_i *= p);
+            domain.fft_in_place_with(twiddles, v);
+        }
+        None => domain.coset_fft_in_place_with(twiddles, v),
+    };
+
+    let full_assignment = [
+        prover.instance_assignment.as_slice(),
+        prover.witness_assignment.as_slice(),
+    ]
+    .concat();
+    let product = |which: ConstraintMatrix, out: &mut [F]| {
+        prover
+            .to_csr_matrix(which)
+            .unwrap()
+            .mul_vector_into(&full_assignment, out)
+    };
+
+    let mut a = vec![zero; domain_size];
+    let mut b = vec![zero; domain_size];
+    product(ConstraintMatrix::A, &mut a);
+    product(ConstraintMatrix::B, &mut b);
+    {
+        let start = num_constraints;
+        let end = start + num_inputs;
+        a[start..end].clone_from_slice(&full_assignment[..num_inputs]);
+    }
+    inspect("qap a", &a);
+    inspect("qap b", &b);
+
+    domain.ifft_in_place_with(twiddles, &mut a);
+    domain.ifft_in_place_with(twiddles, &mut b);
+    let kept_ab = if keep {
+        Some((a.clone(), b.clone()))
+    } else {
+        None
+    };
+
+    coset_fft(&mut a);
+    coset_fft(&mut b);
+    inspect("qap a on coset", &a);
+    inspect("qap b on coset", &b);
+    let mut ab = a;
+    let batch_product_timer = start_timer!(|| "batch product");
+    F::batch_product_in_place(&mut ab, &b);
+    end_timer!(batch_product_timer);
+
+    // `c` goes in `b`'s buffer, cleared past the constraints, which the product leaves untouched
+    let mut c = b;
+    c[num_constraints..].fill(zero);
+    product(ConstraintMatrix::C, &mut c);
+    drop(full_assignment);
+    inspect("qap c", &c);
+
+    domain.ifft_in_place_with(twiddles, &mut c);
+    let kept = kept_ab.map(|(a, b)| (a, b, c.clone()));
+    coset_fft(&mut c);
+    inspect("qap c on coset", &c);
+
+    cfg_iter_mut!(ab)
+        .zip(&c)
+        .for_each(|(ab_i, c_i)| *ab_i -= c_i);
+    drop(c);
+
+    let mut h = ab;
+    domain.divide_by_vanishing_poly_on_coset_in_place(&mut h);
+    inspect("qap h on coset", &h);
+    match cached {
+        Some(cached) => {
+            domain.ifft_in_place_with(twiddles, &mut h);
+            cfg_iter_mut!(h)
+                .zip(&cached.coset_inv_powers)
+                .for_each(|(h_i, p)| *h_i *= p);
+        }
+        None => domain.coset_ifft_in_place_with(twiddles, &mut h),
+    }
+
+    Ok((kept, h))
+}
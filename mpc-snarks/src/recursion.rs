@@ -0,0 +1,273 @@
+//! Collaborative recursion: a collaborative proof that an inner Groth16 proof verifies.
+//!
+//! When the scalar field of one pairing-friendly curve is the base field of another, a circuit for
+//! the outer curve can do the inner curve's arithmetic natively. MNT4-753 and MNT6-753 are such a
+//! pair both ways round, a cycle, and BW6-761 is the outer curve for BLS12-377, as in the Celo and
+//! Zexe stack. [VerifierCircuit] is the Groth16 verifier for proofs over `E`, as a circuit over
+//! `E`'s base field, with the pairings from a [PairingVar] gadget: [Mnt4Verifier] wires in the one
+//! for MNT4 and [Bls12Verifier] the one for BLS12. The inner verifying key is built into it. Its
+//! public inputs are the inner proof's, each as its bits, packed into as few outer field elements
+//! as hold them ([pack_inputs]), and its witness is the inner proof.
+//!
+//! Every value in that witness follows from the inner proof, so one party, the proof's holder,
+//! computes all of them. [SharedAssignment] synthesizes a circuit at that party, shares the
+//! assignment from it, and replays the constraints over [MpcField], so that the parties prove
+//! together that the holder has a proof of the public inputs, without any of them learning it:
+//!
+//! ```no_run
+//! # use ark_groth16::{Proof, VerifyingKey};
+//! # use ark_mnt4_753::{Fr, MNT4_753};
+//! # use ark_mnt6_753::MNT6_753;
+//! # use ark_std::test_rng;
+//! # use mpc_algebra::AdditivePairingShare;
+//! # use mpc_snarks::recursion::{pack_inputs, Mnt4Verifier, SharedAssignment};
+//! # use mpc_snarks::*;
+//! # fn run(vk: VerifyingKey<MNT4_753>, inner: Option<Proof<MNT4_753>>, inputs: Vec<Fr>) {
+//! type E = MNT6_753;
+//! type S = AdditivePairingShare<E>;
+//! let rng = &mut test_rng();
+//! // every party knows the inner `vk` and `inputs`, and the holder, party 0, has the proof
+//! let mut circuit = Mnt4Verifier::new(vk);
+//! let pk = setup::<E, _, _>(circuit.clone(), rng).unwrap();
+//! if let Some(inner) = inner {
+//!     circuit = circuit.with_proof(inner, inputs.clone());
+//! }
+//! let outer_inputs = pack_inputs(&inputs);
+//! let shared = SharedAssignment::share(circuit, 0, outer_inputs.clone(), rng).unwrap();
+//! let mpc_pk = share_proving_key::<E, S>(&pk);
+//! let proof = prove_collaborative(&mpc_pk, shared, rng, ProveOptions::default()).unwrap().0;
+//! let proof = reveal_proof::<E, S>(proof);
+//! assert!(verify(&pk.vk, &proof, &outer_inputs).unwrap());
+//! # }
+//! ```
+//!
+//! A proof that every party has, such as a collaborative one once revealed, can just as well be
+//! wrapped by any one of them, with the plain prover and [VerifierCircuit::with_proof].
+//!
+//! For one input, the MNT4-753 verifier is about 84,000 constraints and the BLS12-377 verifier
+//! about 33,000, so their setups and proofs take from tens of seconds to minutes, even in a
+//! release build. `examples/recursion.rs` runs them.
+use ark_ec::bls12::Bls12;
+use ark_ec::mnt4::MNT4;
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use ark_groth16::{Proof, VerifyingKey};
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::pairing::{bls12, mnt4};
+use ark_r1cs_std::prelude::*;
+use ark_relations::ns;
+use ark_relations::r1cs::{
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef,
+    LinearCombination, OptimizationGoal, SynthesisError, SynthesisMode, Variable,
+};
+use derivative::Derivative;
+use mpc_algebra::{FieldShare, MpcField, Reveal};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use rand::Rng;
+
+use std::marker::PhantomData;
+
+/// Verifies a Groth16 proof over `E` for the verifying key `vk`, as a circuit over `E`'s base
+/// field, with the pairings from `PV`.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct VerifierCircuit<E: PairingEngine, PV: PairingVar<E>> {
+    pub vk: VerifyingKey<E>,
+    pub proof: Option<Proof<E>>,
+    pub inputs: Option<Vec<E::Fr>>,
+    _pairing: PhantomData<fn() -> PV>,
+}
+
+/// The verifier for proofs over an MNT4 curve, with constraints over its base field, which is
+/// the scalar field of the matching MNT6 curve.
+pub type Mnt4Verifier<P> = VerifierCircuit<MNT4<P>, mnt4::PairingVar<P>>;
+
+/// The verifier for proofs over a BLS12 curve, with constraints over its base field, such as the
+/// scalar field of BW6-761 for BLS12-377.
+pub type Bls12Verifier<P> = VerifierCircuit<Bls12<P>, bls12::PairingVar<P>>;
+
+impl<E: PairingEngine, PV: PairingVar<E>> VerifierCircuit<E, PV> {
+    /// The circuit for `vk`, with no proof, for setup.
+    pub fn new(vk: VerifyingKey<E>) -> Self {
+        VerifierCircuit {
+            vk,
+            proof: None,
+            inputs: None,
+            _pairing: PhantomData,
+        }
+    }
+
+    /// The circuit with `proof`, of `inputs`, as its witness.
+    pub fn with_proof(self, proof: Proof<E>, inputs: Vec<E::Fr>) -> Self {
+        assert_eq!(
+            inputs.len(),
+            self.num_inputs(),
+            "the wrong number of inputs"
+        );
+        VerifierCircuit {
+            proof: Some(proof),
+            inputs: Some(inputs),
+            ..self
+        }
+    }
+
+    /// How many public inputs the inner proof has.
+    pub fn num_inputs(&self) -> usize {
+        self.vk.gamma_abc_g1.len() - 1
+    }
+}
+
+/// How many bits an element of `F` holds, whatever their values.
+fn capacity<F: PrimeField>() -> usize {
+    F::Params::CAPACITY as usize
+}
+
+/// The outer circuit's public inputs, for the inner proof's `inputs`: the bits of each, from the
+/// least significant, in chunks of as many as an element of `F` holds.
+pub fn pack_inputs<Fr: PrimeField, F: PrimeField>(inputs: &[Fr]) -> Vec<F> {
+    inputs
+        .iter()
+        .flat_map(|x| {
+            let bits = x.into_repr().to_bits_le();
+            bits[..Fr::size_in_bits()]
+                .chunks(capacity::<F>())
+                .map(|chunk| F::from_repr(F::BigInt::from_bits_le(chunk)).unwrap())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+impl<E: PairingEngine, PV: PairingVar<E>> ConstraintSynthesizer<E::Fq> for VerifierCircuit<E, PV> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<E::Fq>) -> Result<(), SynthesisError> {
+        let missing = || SynthesisError::AssignmentMissing;
+        let num_inputs = self.num_inputs();
+        let vk = self.vk;
+
+        // vk_x = gamma_abc[0] + sum x_i gamma_abc[i + 1], from the bits of the inputs
+        let mut vk_x = PV::G1Var::constant(vk.gamma_abc_g1[0].into_projective());
+        for i in 0..num_inputs {
+            let x = self
+                .inputs
+                .as_ref()
+                .map(|xs| xs[i].into_repr().to_bits_le());
+            let bits = (0..E::Fr::size_in_bits())
+                .map(|j| {
+                    Boolean::new_witness(ns!(cs, "input bit"), || {
+                        x.as_ref().map(|x| x[j]).ok_or_else(missing)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            for chunk in bits.chunks(capacity::<E::Fq>()) {
+                let packed = Boolean::le_bits_to_fp_var(chunk)?;
+                let input = FpVar::new_input(ns!(cs, "input"), || packed.value())?;
+                input.enforce_equal(&packed)?;
+            }
+            let base = PV::G1Var::constant(vk.gamma_abc_g1[i + 1].into_projective());
+            vk_x += base.scalar_mul_le(bits.iter())?;
+        }
+
+        let proof = self.proof;
+        let a = PV::G1Var::new_witness(ns!(cs, "a"), || {
+            proof.as_ref().map(|p| p.a).ok_or_else(missing)
+        })?;
+        let b = PV::G2Var::new_witness(ns!(cs, "b"), || {
+            proof.as_ref().map(|p| p.b).ok_or_else(missing)
+        })?;
+        let c = PV::G1Var::new_witness(ns!(cs, "c"), || {
+            proof.as_ref().map(|p| p.c).ok_or_else(missing)
+        })?;
+
+        // e(a, b) e(vk_x, -gamma) e(c, -delta) = e(alpha, beta), with -gamma and -delta prepared
+        // by the gadget, as constants: its prepared points need not match E::G2Prepared's
+        let neg_gamma = PV::prepare_g2(&PV::G2Var::constant((-vk.gamma_g2).into_projective()))?;
+        let neg_delta = PV::prepare_g2(&PV::G2Var::constant((-vk.delta_g2).into_projective()))?;
+        let miller = PV::miller_loop(
+            &[
+                PV::prepare_g1(&a)?,
+                PV::prepare_g1(&vk_x)?,
+                PV::prepare_g1(&c)?,
+            ],
+            &[PV::prepare_g2(&b)?, neg_gamma, neg_delta],
+        )?;
+        let alpha_beta = PV::GTVar::constant(E::pairing(vk.alpha_g1, vk.beta_g2));
+        PV::final_exponentiation(&miller)?.enforce_equal(&alpha_beta)
+    }
+}
+
+/// A circuit's constraints, with an assignment that one party computed and shared, to prove
+/// collaboratively.
+pub struct SharedAssignment<F: PrimeField, S: FieldShare<F>> {
+    matrices: ConstraintMatrices<F>,
+    inputs: Vec<F>,
+    witness: Vec<MpcField<F, S>>,
+}
+
+impl<F: PrimeField, S: FieldShare<F>> SharedAssignment<F, S> {
+    /// Synthesize `circuit`, whose public inputs are `inputs`, and share its witness from `owner`.
+    /// Only the owner's `circuit` needs values. Every party must call this.
+    pub fn share<C: ConstraintSynthesizer<F>, R: Rng>(
+        circuit: C,
+        owner: usize,
+        inputs: Vec<F>,
+        rng: &mut R,
+    ) -> Result<Self, SynthesisError> {
+        let owns = Net::party_id() == owner;
+        let cs = ConstraintSystem::new_ref();
+        // as the prover does, so that the constraints are those of the proving key
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        if !owns {
+            cs.set_mode(SynthesisMode::Setup);
+        }
+        circuit.generate_constraints(cs.clone())?;
+        cs.finalize();
+        let matrices = cs.to_matrices().unwrap();
+        assert_eq!(
+            matrices.num_instance_variables,
+            inputs.len() + 1,
+            "the wrong number of inputs"
+        );
+        let witness = if owns {
+            cs.borrow().unwrap().witness_assignment.clone()
+        } else {
+            vec![F::zero(); matrices.num_witness_variables]
+        };
+        Ok(SharedAssignment {
+            matrices,
+            inputs,
+            witness: MpcField::party_share_batch(owner, witness, rng),
+        })
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.matrices.num_constraints
+    }
+}
+
+impl<F: PrimeField, S: FieldShare<F>> ConstraintSynthesizer<MpcField<F, S>>
+    for SharedAssignment<F, S>
+{
+    fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<MpcField<F, S>>,
+    ) -> Result<(), SynthesisError> {
+        let mut vars = vec![Variable::One];
+        for x in self.inputs {
+            vars.push(cs.new_input_variable(|| Ok(MpcField::Public(x)))?);
+        }
+        for w in self.witness {
+            vars.push(cs.new_witness_variable(|| Ok(w))?);
+        }
+        let lc = |row: &[(F, usize)]| {
+            LinearCombination(
+                row.iter()
+                    .map(|(coeff, i)| (MpcField::Public(*coeff), vars[*i]))
+                    .collect(),
+            )
+        };
+        let m = &self.matrices;
+        for ((a, b), c) in m.a.iter().zip(&m.b).zip(&m.c) {
+            cs.enforce_constraint(lc(a), lc(b), lc(c))?;
+        }
+        Ok(())
+    }
+}
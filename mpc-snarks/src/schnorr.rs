@@ -0,0 +1,541 @@
+//! A circuit that verifies a Schnorr signature, for proofs about signed data that no one party
+//! may see.
+//!
+//! Say a bank has signed a customer's record, and the customer and an auditor want to prove
+//! something about it. The record's owner shares the message (and, if it must stay secret, the
+//! signer's key) as a witness, and the parties prove together that it carries a valid signature,
+//! while the verifier learns only hashes of the secret parts.
+//!
+//! Signatures are over a twisted Edwards curve whose base field is the circuit's field, such as
+//! Edwards-BLS12 (`ark-ed-on-bls12-377`) for BLS12-377: `(R, s)` signs `m` under `A = aB` if
+//! `sB = R + eA`, where `e` is the MiMC hash of `(R, A, m)`. `sB - R` is public, so the circuit
+//! only multiplies `A` by the bits of `e`. Those bits come with the witness, from whoever shares
+//! it, and the circuit checks them; they need not be the canonical ones, which gives a forger at
+//! most two challenges to aim at per hash.
+//!
+//! The circuit is generic over the curve, and so works over [MpcField](mpc_algebra::MpcField)
+//! through [MpcEdwardsParameters]. [SchnorrCircuit::share] lifts a plain circuit to one.
+use ark_ec::{
+    models::twisted_edwards_extended::GroupAffine, AffineCurve, ProjectiveCurve, TEModelParameters,
+};
+use ark_ff::{BigInteger, Field, One, PrimeField, UniformRand, Zero};
+use ark_relations::{
+    lc,
+    r1cs::{
+        ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable,
+    },
+};
+use derivative::Derivative;
+use mpc_algebra::{FieldShare, MpcEdwardsParameters, MpcField, Reveal};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+/// Rounds of the MiMC permutation, which are enough for `x^17` over fields of up to 253 bits.
+pub const MIMC_ROUNDS: usize = 62;
+
+/// The round constants of MiMC, derived from SHA-256.
+fn mimc_constants<F: Field>() -> Vec<F> {
+    (0..MIMC_ROUNDS as u64)
+        .map(|i| {
+            let digest = Sha256::new()
+                .chain(b"mpc-snarks mimc")
+                .chain(&i.to_le_bytes())
+                .finalize();
+            // the digest, as a little-endian integer
+            digest.chunks(8).rev().fold(F::zero(), |acc, limb| {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(limb);
+                acc * F::from(1u128 << 64) + F::from(u64::from_le_bytes(bytes))
+            })
+        })
+        .collect()
+}
+
+fn pow17<F: Field>(x: F) -> F {
+    x.square().square().square().square() * x
+}
+
+/// The MiMC hash of `inputs`: `x^17` MiMC in Miyaguchi–Preneel mode, which `17` suits for the
+/// scalar field of BLS12-377, as it is coprime to `p - 1`.
+pub fn mimc_hash<F: Field>(inputs: &[F]) -> F {
+    let constants = mimc_constants::<F>();
+    inputs.iter().fold(F::zero(), |h, x| {
+        let y = constants.iter().fold(*x, |y, c| pow17(y + h + c));
+        // E_h(x) + h + x, where E_h(x) = y + h
+        y + h + h + x
+    })
+}
+
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = "")
+)]
+pub struct Signature<P: TEModelParameters> {
+    /// `R`
+    pub nonce: GroupAffine<P>,
+    pub s: P::ScalarField,
+}
+
+/// The key of `secret`.
+pub fn public_key<P: TEModelParameters>(secret: &P::ScalarField) -> GroupAffine<P> {
+    GroupAffine::prime_subgroup_generator()
+        .mul(*secret)
+        .into_affine()
+}
+
+/// `e`, as a base field element.
+pub fn challenge<P: TEModelParameters>(
+    nonce: &GroupAffine<P>,
+    key: &GroupAffine<P>,
+    message: &[P::BaseField],
+) -> P::BaseField {
+    let mut inputs = vec![nonce.x, nonce.y, key.x, key.y];
+    inputs.extend_from_slice(message);
+    mimc_hash(&inputs)
+}
+
+fn challenge_scalar<P: TEModelParameters>(e: P::BaseField) -> P::ScalarField
+where
+    P::BaseField: PrimeField,
+{
+    P::ScalarField::from_le_bytes_mod_order(&e.into_repr().to_bytes_le())
+}
+
+/// Sign `message` with `secret`.
+pub fn sign<P: TEModelParameters, R: Rng>(
+    secret: &P::ScalarField,
+    message: &[P::BaseField],
+    rng: &mut R,
+) -> Signature<P>
+where
+    P::BaseField: PrimeField,
+{
+    let k = P::ScalarField::rand(rng);
+    let nonce = public_key::<P>(&k);
+    let e = challenge_scalar::<P>(challenge(&nonce, &public_key(secret), message));
+    Signature {
+        nonce,
+        s: k + e * secret,
+    }
+}
+
+/// Whether `signature` signs `message` under `key`.
+pub fn verify<P: TEModelParameters>(
+    key: &GroupAffine<P>,
+    message: &[P::BaseField],
+    signature: &Signature<P>,
+) -> bool
+where
+    P::BaseField: PrimeField,
+{
+    let e = challenge_scalar::<P>(challenge(&signature.nonce, key, message));
+    let lhs = GroupAffine::<P>::prime_subgroup_generator().mul(signature.s);
+    lhs == key.mul(e).add_mixed(&signature.nonce)
+}
+
+/// The signer's key, as the verifier sees it.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), PartialEq(bound = ""))]
+pub enum Key<P: TEModelParameters> {
+    Public(GroupAffine<P>),
+    /// A secret key, of which the verifier knows the hash of the coordinates.
+    Secret {
+        hash: P::BaseField,
+    },
+}
+
+/// What the verifier knows: the signature, the key or its hash, and the hash of the message.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), PartialEq(bound = ""))]
+pub struct SchnorrInstance<P: TEModelParameters> {
+    pub signature: Signature<P>,
+    pub key: Key<P>,
+    pub message_hash: P::BaseField,
+    pub message_len: usize,
+}
+
+impl<P: TEModelParameters> SchnorrInstance<P>
+where
+    P::BaseField: PrimeField,
+{
+    /// The instance for `signature` on `message` under `key`, which is hidden unless
+    /// `key_is_public`.
+    pub fn new(
+        key: &GroupAffine<P>,
+        key_is_public: bool,
+        message: &[P::BaseField],
+        signature: Signature<P>,
+    ) -> Self {
+        Self {
+            signature,
+            key: if key_is_public {
+                Key::Public(*key)
+            } else {
+                Key::Secret {
+                    hash: mimc_hash(&[key.x, key.y]),
+                }
+            },
+            message_hash: mimc_hash(message),
+            message_len: message.len(),
+        }
+    }
+
+    /// `sB - R`, which `eA` must equal.
+    fn target(&self) -> GroupAffine<P> {
+        let s = &self.signature;
+        (GroupAffine::<P>::prime_subgroup_generator().mul(s.s) - &s.nonce.into_projective())
+            .into_affine()
+    }
+
+    /// The public inputs of the [SchnorrCircuit] for this instance, to verify its proofs with.
+    pub fn public_inputs(&self) -> Vec<P::BaseField> {
+        let (nonce, target) = (self.signature.nonce, self.target());
+        let mut inputs = vec![nonce.x, nonce.y, target.x, target.y];
+        match &self.key {
+            Key::Public(key) => inputs.extend_from_slice(&[key.x, key.y]),
+            Key::Secret { hash } => inputs.push(*hash),
+        }
+        inputs.push(self.message_hash);
+        inputs
+    }
+}
+
+/// Proves that the secret message, with hash `message_hash`, carries a valid signature.
+///
+/// Its public inputs are `R`, `sB - R`, the key (or its hash), and the message's hash.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct SchnorrCircuit<P: TEModelParameters> {
+    pub nonce: Option<GroupAffine<P>>,
+    pub target: Option<GroupAffine<P>>,
+    pub key: Option<GroupAffine<P>>,
+    pub key_is_public: bool,
+    /// Of the key, if secret.
+    pub key_hash: Option<P::BaseField>,
+    pub message: Vec<Option<P::BaseField>>,
+    pub message_hash: Option<P::BaseField>,
+    /// The bits of `e`, from the least significant.
+    pub challenge_bits: Vec<Option<P::BaseField>>,
+}
+
+impl<P: TEModelParameters> SchnorrCircuit<P>
+where
+    P::BaseField: PrimeField,
+{
+    /// The circuit for `instance`, with the witness if given: the message, and the key, which
+    /// must match the instance.
+    pub fn new(
+        instance: &SchnorrInstance<P>,
+        witness: Option<(&GroupAffine<P>, &[P::BaseField])>,
+    ) -> Self {
+        let (key_is_public, key_hash, public_key) = match &instance.key {
+            Key::Public(key) => (true, None, Some(*key)),
+            Key::Secret { hash } => (false, Some(*hash), None),
+        };
+        let bits = P::BaseField::size_in_bits();
+        let (key, message, challenge_bits) = match witness {
+            Some((key, message)) => {
+                assert_eq!(message.len(), instance.message_len);
+                let e = challenge(&instance.signature.nonce, key, message);
+                let e_bits = e.into_repr().to_bits_le();
+                (
+                    Some(*key),
+                    message.iter().copied().map(Some).collect(),
+                    e_bits[..bits]
+                        .iter()
+                        .map(|b| Some(P::BaseField::from(*b)))
+                        .collect(),
+                )
+            }
+            None => (
+                public_key,
+                vec![None; instance.message_len],
+                vec![None; bits],
+            ),
+        };
+        Self {
+            nonce: Some(instance.signature.nonce),
+            target: Some(instance.target()),
+            key,
+            key_is_public,
+            key_hash,
+            message,
+            message_hash: Some(instance.message_hash),
+            challenge_bits,
+        }
+    }
+
+    /// Lift this circuit to [MpcField]: party `owner`'s witness is shared, and the public parts,
+    /// which every party must have, are lifted as they are. Every party must call this.
+    pub fn share<S: FieldShare<P::BaseField>, R: Rng>(
+        &self,
+        owner: usize,
+        rng: &mut R,
+    ) -> SchnorrCircuit<MpcEdwardsParameters<P, S>> {
+        let public = |p: &Option<GroupAffine<P>>| {
+            Some(Reveal::from_public(
+                p.expect("the public parts of the instance"),
+            ))
+        };
+        let mut private = Vec::new();
+        if !self.key_is_public {
+            let key = self.key.unwrap_or_default();
+            private.extend_from_slice(&[key.x, key.y]);
+        }
+        private.extend(self.message.iter().map(|m| m.unwrap_or_default()));
+        private.extend(self.challenge_bits.iter().map(|b| b.unwrap_or_default()));
+        let mut private = MpcField::party_share_batch(owner, private, rng).into_iter();
+        let key = if self.key_is_public {
+            public(&self.key)
+        } else {
+            let (x, y) = (private.next().unwrap(), private.next().unwrap());
+            Some(GroupAffine::new(x, y))
+        };
+        let message = self.message.iter().map(|_| private.next()).collect();
+        let challenge_bits = private.map(Some).collect();
+        SchnorrCircuit {
+            nonce: public(&self.nonce),
+            target: public(&self.target),
+            key,
+            key_is_public: self.key_is_public,
+            key_hash: self.key_hash.map(MpcField::from_public),
+            message,
+            message_hash: self.message_hash.map(MpcField::from_public),
+            challenge_bits,
+        }
+    }
+}
+
+/// A value in the circuit: a linear combination of variables, and its value, if known.
+#[derive(Clone)]
+pub(crate) struct Num<F: Field> {
+    pub(crate) lc: LinearCombination<F>,
+    pub(crate) val: Option<F>,
+}
+
+impl<F: Field> Num<F> {
+    pub(crate) fn constant(c: F) -> Self {
+        Self {
+            lc: lc!() + (c, Variable::One),
+            val: Some(c),
+        }
+    }
+
+    pub(crate) fn witness(
+        cs: &ConstraintSystemRef<F>,
+        val: Option<F>,
+    ) -> Result<Self, SynthesisError> {
+        let v = cs.new_witness_variable(|| val.ok_or(SynthesisError::AssignmentMissing))?;
+        Ok(Self { lc: lc!() + v, val })
+    }
+
+    pub(crate) fn input(
+        cs: &ConstraintSystemRef<F>,
+        val: Option<F>,
+    ) -> Result<Self, SynthesisError> {
+        let v = cs.new_input_variable(|| val.ok_or(SynthesisError::AssignmentMissing))?;
+        Ok(Self { lc: lc!() + v, val })
+    }
+
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        Self {
+            lc: &self.lc + &other.lc,
+            val: self.val.and_then(|a| other.val.map(|b| a + b)),
+        }
+    }
+
+    pub(crate) fn sub(&self, other: &Self) -> Self {
+        Self {
+            lc: &self.lc - &other.lc,
+            val: self.val.and_then(|a| other.val.map(|b| a - b)),
+        }
+    }
+
+    pub(crate) fn scale(&self, c: F) -> Self {
+        Self {
+            lc: &self.lc * c,
+            val: self.val.map(|a| a * c),
+        }
+    }
+
+    pub(crate) fn mul(
+        &self,
+        other: &Self,
+        cs: &ConstraintSystemRef<F>,
+    ) -> Result<Self, SynthesisError> {
+        let product = Self::witness(cs, self.val.and_then(|a| other.val.map(|b| a * b)))?;
+        cs.enforce_constraint(self.lc.clone(), other.lc.clone(), product.lc.clone())?;
+        Ok(product)
+    }
+
+    /// `self / den`, for a nonzero `den`.
+    pub(crate) fn div(
+        &self,
+        den: &Self,
+        cs: &ConstraintSystemRef<F>,
+    ) -> Result<Self, SynthesisError> {
+        let quotient = Self::witness(cs, self.val.and_then(|a| den.val.map(|b| a / b)))?;
+        cs.enforce_constraint(quotient.lc.clone(), den.lc.clone(), self.lc.clone())?;
+        Ok(quotient)
+    }
+
+    pub(crate) fn enforce_equal(
+        &self,
+        other: &Self,
+        cs: &ConstraintSystemRef<F>,
+    ) -> Result<(), SynthesisError> {
+        cs.enforce_constraint(self.sub(other).lc, lc!() + Variable::One, lc!())
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct PointNum<F: Field> {
+    pub(crate) x: Num<F>,
+    pub(crate) y: Num<F>,
+}
+
+impl<F: Field> PointNum<F> {
+    pub(crate) fn identity() -> Self {
+        Self {
+            x: Num::constant(F::zero()),
+            y: Num::constant(F::one()),
+        }
+    }
+
+    pub(crate) fn alloc(
+        cs: &ConstraintSystemRef<F>,
+        p: Option<(F, F)>,
+        alloc: fn(&ConstraintSystemRef<F>, Option<F>) -> Result<Num<F>, SynthesisError>,
+    ) -> Result<Self, SynthesisError> {
+        Ok(Self {
+            x: alloc(cs, p.map(|p| p.0))?,
+            y: alloc(cs, p.map(|p| p.1))?,
+        })
+    }
+
+    /// The sum, by the complete addition law of `P`.
+    pub(crate) fn add<P: TEModelParameters<BaseField = F>>(
+        &self,
+        other: &Self,
+        cs: &ConstraintSystemRef<F>,
+    ) -> Result<Self, SynthesisError> {
+        let xy = self.x.mul(&other.y, cs)?;
+        let yx = self.y.mul(&other.x, cs)?;
+        let yy = self.y.mul(&other.y, cs)?;
+        let xx = self.x.mul(&other.x, cs)?;
+        let dxxyy = xx.mul(&yy, cs)?.scale(P::COEFF_D);
+        let one = Num::constant(F::one());
+        Ok(Self {
+            x: xy.add(&yx).div(&one.add(&dxxyy), cs)?,
+            y: yy.sub(&xx.scale(P::COEFF_A)).div(&one.sub(&dxxyy), cs)?,
+        })
+    }
+
+    /// `self` if `bit`, and the identity otherwise.
+    pub(crate) fn select(
+        &self,
+        bit: &Num<F>,
+        cs: &ConstraintSystemRef<F>,
+    ) -> Result<Self, SynthesisError> {
+        let one = Num::constant(F::one());
+        Ok(Self {
+            x: self.x.mul(bit, cs)?,
+            y: self.y.sub(&one).mul(bit, cs)?.add(&one),
+        })
+    }
+
+    pub(crate) fn enforce_equal(
+        &self,
+        other: &Self,
+        cs: &ConstraintSystemRef<F>,
+    ) -> Result<(), SynthesisError> {
+        self.x.enforce_equal(&other.x, cs)?;
+        self.y.enforce_equal(&other.y, cs)
+    }
+}
+
+/// The MiMC hash of `inputs`, as [mimc_hash].
+pub(crate) fn hash_gadget<F: Field>(
+    inputs: &[Num<F>],
+    cs: &ConstraintSystemRef<F>,
+) -> Result<Num<F>, SynthesisError> {
+    let constants = mimc_constants::<F>();
+    let mut h = Num::constant(F::zero());
+    for x in inputs {
+        let mut y = x.clone();
+        for c in &constants {
+            let t = y.add(&h).add(&Num::constant(*c));
+            let t2 = t.mul(&t, cs)?;
+            let t4 = t2.mul(&t2, cs)?;
+            let t8 = t4.mul(&t4, cs)?;
+            let t16 = t8.mul(&t8, cs)?;
+            y = t16.mul(&t, cs)?;
+        }
+        h = y.add(&h).add(&h).add(x);
+    }
+    Ok(h)
+}
+
+pub(crate) fn coords<P: TEModelParameters>(
+    p: &Option<GroupAffine<P>>,
+) -> Option<(P::BaseField, P::BaseField)> {
+    p.map(|p| (p.x, p.y))
+}
+
+impl<P: TEModelParameters> ConstraintSynthesizer<P::BaseField> for SchnorrCircuit<P> {
+    fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<P::BaseField>,
+    ) -> Result<(), SynthesisError> {
+        let cs = &cs;
+        let nonce = PointNum::alloc(cs, coords(&self.nonce), Num::input)?;
+        let target = PointNum::alloc(cs, coords(&self.target), Num::input)?;
+        let key = if self.key_is_public {
+            PointNum::alloc(cs, coords(&self.key), Num::input)?
+        } else {
+            let key_hash = Num::input(cs, self.key_hash)?;
+            let key = PointNum::alloc(cs, coords(&self.key), Num::witness)?;
+            hash_gadget(&[key.x.clone(), key.y.clone()], cs)?.enforce_equal(&key_hash, cs)?;
+            key
+        };
+        let message_hash = Num::input(cs, self.message_hash)?;
+        let message = self
+            .message
+            .iter()
+            .map(|m| Num::witness(cs, *m))
+            .collect::<Result<Vec<_>, _>>()?;
+        hash_gadget(&message, cs)?.enforce_equal(&message_hash, cs)?;
+
+        // the bits of e
+        let mut inputs = vec![nonce.x, nonce.y, key.x.clone(), key.y.clone()];
+        inputs.extend(message);
+        let e = hash_gadget(&inputs, cs)?;
+        let bits = self
+            .challenge_bits
+            .iter()
+            .map(|b| Num::witness(cs, *b))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut sum = Num::constant(P::BaseField::zero());
+        let mut power = P::BaseField::one();
+        for b in &bits {
+            cs.enforce_constraint(b.lc.clone(), b.lc.clone(), b.lc.clone())?;
+            sum = sum.add(&b.scale(power));
+            power.double_in_place();
+        }
+        sum.enforce_equal(&e, cs)?;
+
+        // eA, by double-and-add
+        let mut acc = PointNum::identity();
+        let mut base = key;
+        for (i, b) in bits.iter().enumerate() {
+            acc = acc.add::<P>(&base.select(b, cs)?, cs)?;
+            if i + 1 < bits.len() {
+                base = base.add::<P>(&base, cs)?;
+            }
+        }
+        acc.enforce_equal(&target, cs)
+    }
+}
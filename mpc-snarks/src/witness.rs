@@ -0,0 +1,765 @@
+//! Witness files: named field elements, either public or private to one party.
+//!
+//! A witness file, in JSON or TOML, has a `public` table and, for each party `i` with private
+//! inputs, a table `party.i`:
+//!
+//! ```toml
+//! [public]
+//! n = 3
+//!
+//! [party.0]
+//! a = "0x1f"
+//! xs = [1, -2, "340282366920938463463374607431768211457"]
+//! ```
+//!
+//! or `{"public": {"n": 3}, "party": {"0": {"a": "0x1f", "xs": [1, -2, "3402..."]}}}`.
+//!
+//! Each value is an integer or an array of integers, written as a number or a string, in decimal
+//! or (with `0x`) hexadecimal. Integers must be below the field's modulus, and negative ones are
+//! negated in the field. Fractions should be encoded with [mpc_algebra::fixed] first.
+//!
+//! Every party reads its own file. The public tables must agree, which is checked, and each party
+//! reads only its own private table, so one file can serve every party in a local test.
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::CanonicalSerialize;
+use mpc_algebra::{FieldShare, MpcField, Public, Reveal, Shared};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Why a witness could not be read or shared.
+#[derive(Debug)]
+pub enum WitnessError {
+    Io(io::Error),
+    /// A malformed file, at the given line.
+    Syntax {
+        line: usize,
+        msg: String,
+    },
+    /// A well-formed file that is not a witness.
+    Schema(String),
+    /// A value that is not an integer below the modulus.
+    Value {
+        name: String,
+        value: String,
+    },
+    /// The public tables differ between parties.
+    PublicMismatch,
+    /// A party that could not read its witness.
+    PartyFailed(usize),
+    /// The wrong number of inputs.
+    Count {
+        expected: usize,
+        got: usize,
+    },
+}
+
+impl Display for WitnessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            WitnessError::Io(e) => write!(f, "{}", e),
+            WitnessError::Syntax { line, msg } => write!(f, "line {}: {}", line, msg),
+            WitnessError::Schema(msg) => write!(f, "{}", msg),
+            WitnessError::Value { name, value } => {
+                write!(f, "{}: {} is not a field element", name, value)
+            }
+            WitnessError::PublicMismatch => write!(f, "public inputs differ between parties"),
+            WitnessError::PartyFailed(p) => write!(f, "party {} could not read its witness", p),
+            WitnessError::Count { expected, got } => {
+                write!(f, "expected {} inputs, got {}", expected, got)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WitnessError {}
+
+impl From<io::Error> for WitnessError {
+    fn from(e: io::Error) -> Self {
+        WitnessError::Io(e)
+    }
+}
+
+/// Parse an integer below the modulus, in decimal or (with `0x`) hexadecimal, optionally negative
+/// and with `_` separators.
+pub fn parse_field<F: PrimeField>(s: &str) -> Option<F> {
+    let s: String = s.trim().chars().filter(|c| *c != '_').collect();
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(t) => (true, t),
+        None => (false, s.strip_prefix('+').unwrap_or(&s)),
+    };
+    let (radix, digits) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(h) => (16, h),
+        None => (10, s),
+    };
+    if digits.is_empty() {
+        return None;
+    }
+    let mut x = F::BigInt::from(0);
+    let bits = 64 * x.as_ref().len() as u32;
+    for c in digits.chars() {
+        let d = c.to_digit(radix)?;
+        if x.num_bits() + 4 > bits {
+            return None;
+        }
+        if radix == 16 {
+            x.muln(4);
+        } else {
+            // 10x = 8x + 2x
+            let mut twice = x;
+            twice.mul2();
+            x.muln(3);
+            x.add_nocarry(&twice);
+        }
+        x.add_nocarry(&F::BigInt::from(d as u64));
+    }
+    let x = F::from_repr(x)?;
+    Some(if negative { -x } else { x })
+}
+
+/// A parsed JSON or TOML value, with integers kept as text.
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Int(String),
+    Str(String),
+    Array(Vec<Value>),
+    Table(Vec<(String, Value)>),
+}
+
+type Table = Vec<(String, Value)>;
+
+fn insert(table: &mut Table, key: String, value: Value) -> Result<(), String> {
+    if table.iter().any(|(k, _)| k == &key) {
+        return Err(format!("{} is defined twice", key));
+    }
+    table.push((key, value));
+    Ok(())
+}
+
+/// The table at `path` below `table`, which is created if need be.
+fn table_at<'t>(mut table: &'t mut Table, path: &[String]) -> Result<&'t mut Table, String> {
+    for key in path {
+        let i = match table.iter().position(|(k, _)| k == key) {
+            Some(i) => i,
+            None => {
+                table.push((key.clone(), Value::Table(Vec::new())));
+                table.len() - 1
+            }
+        };
+        table = match &mut table[i].1 {
+            Value::Table(t) => t,
+            _ => return Err(format!("{} is not a table", key)),
+        };
+    }
+    Ok(table)
+}
+
+/// A parser for JSON, and for the subset of TOML that witnesses need: tables, dotted keys,
+/// strings, integers, and arrays.
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(s: &'a str) -> Self {
+        Self {
+            s: s.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn err<T>(&self, msg: impl Into<String>) -> Result<T, WitnessError> {
+        let line = self.s[..self.pos].iter().filter(|c| **c == b'\n').count() + 1;
+        Err(WitnessError::Syntax {
+            line,
+            msg: msg.into(),
+        })
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.s.get(self.pos).cloned()
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), WitnessError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            self.err(format!("expected '{}'", c as char))
+        }
+    }
+
+    /// A double-quoted string with JSON escapes, or (in TOML) a single-quoted literal one.
+    fn string(&mut self) -> Result<String, WitnessError> {
+        let mut out = Vec::new();
+        if self.eat(b'\'') {
+            while !self.eat(b'\'') {
+                match self.peek() {
+                    Some(b'\n') | None => return self.err("unterminated string"),
+                    Some(c) => out.push(c),
+                }
+                self.pos += 1;
+            }
+        } else {
+            self.expect(b'"')?;
+            while !self.eat(b'"') {
+                let c = match self.peek() {
+                    Some(b'\n') | None => return self.err("unterminated string"),
+                    Some(c) => c,
+                };
+                self.pos += 1;
+                if c != b'\\' {
+                    out.push(c);
+                    continue;
+                }
+                let escaped = match self.peek() {
+                    Some(b'"') => '"',
+                    Some(b'\\') => '\\',
+                    Some(b'/') => '/',
+                    Some(b'n') => '\n',
+                    Some(b't') => '\t',
+                    Some(b'r') => '\r',
+                    Some(b'u') => {
+                        let hex = self.s.get(self.pos + 1..self.pos + 5);
+                        let c = hex
+                            .and_then(|h| std::str::from_utf8(h).ok())
+                            .and_then(|h| u32::from_str_radix(h, 16).ok())
+                            .and_then(std::char::from_u32);
+                        match c {
+                            Some(c) => {
+                                self.pos += 4;
+                                c
+                            }
+                            None => return self.err("bad \\u escape"),
+                        }
+                    }
+                    _ => return self.err("bad escape"),
+                };
+                self.pos += 1;
+                out.extend_from_slice(escaped.encode_utf8(&mut [0; 4]).as_bytes());
+            }
+        }
+        // the input is a str, and we only split it at ASCII characters
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    /// An integer, which is checked later, when it is read as a field element.
+    fn integer(&mut self) -> Result<Value, WitnessError> {
+        let start = self.pos;
+        if matches!(self.peek(), Some(b'-') | Some(b'+')) {
+            self.pos += 1;
+        }
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == b'_') {
+            self.pos += 1;
+        }
+        if self.pos == start || matches!(self.peek(), Some(b'.')) {
+            return self.err("expected an integer");
+        }
+        let text = std::str::from_utf8(&self.s[start..self.pos]).unwrap();
+        Ok(Value::Int(text.to_owned()))
+    }
+
+    fn json_ws(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn json(&mut self) -> Result<Value, WitnessError> {
+        let v = self.json_value()?;
+        self.json_ws();
+        match self.peek() {
+            None => Ok(v),
+            Some(_) => self.err("trailing characters"),
+        }
+    }
+
+    fn json_value(&mut self) -> Result<Value, WitnessError> {
+        self.json_ws();
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+                let mut table = Vec::new();
+                self.json_ws();
+                if !self.eat(b'}') {
+                    loop {
+                        self.json_ws();
+                        let key = self.string()?;
+                        self.json_ws();
+                        self.expect(b':')?;
+                        let v = self.json_value()?;
+                        if let Err(e) = insert(&mut table, key, v) {
+                            return self.err(e);
+                        }
+                        self.json_ws();
+                        if self.eat(b'}') {
+                            break;
+                        }
+                        self.expect(b',')?;
+                    }
+                }
+                Ok(Value::Table(table))
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                self.json_ws();
+                if !self.eat(b']') {
+                    loop {
+                        items.push(self.json_value()?);
+                        self.json_ws();
+                        if self.eat(b']') {
+                            break;
+                        }
+                        self.expect(b',')?;
+                    }
+                }
+                Ok(Value::Array(items))
+            }
+            Some(b'"') => Ok(Value::Str(self.string()?)),
+            Some(b'-') | Some(b'0'..=b'9') => self.integer(),
+            _ => self.err("expected an object, array, string, or integer"),
+        }
+    }
+
+    /// Skip spaces and comments, and also newlines if `newlines`.
+    fn toml_ws(&mut self, newlines: bool) {
+        loop {
+            match self.peek() {
+                Some(b' ') | Some(b'\t') => self.pos += 1,
+                Some(b'\n') | Some(b'\r') if newlines => self.pos += 1,
+                Some(b'#') => {
+                    while !matches!(self.peek(), None | Some(b'\n')) {
+                        self.pos += 1;
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn toml(&mut self) -> Result<Value, WitnessError> {
+        let mut root = Vec::new();
+        let mut section: Vec<String> = Vec::new();
+        loop {
+            self.toml_ws(true);
+            match self.peek() {
+                None => break,
+                Some(b'[') => {
+                    self.pos += 1;
+                    section = self.toml_key()?;
+                    self.expect(b']')?;
+                    if let Err(e) = table_at(&mut root, &section) {
+                        return self.err(e);
+                    }
+                }
+                Some(_) => {
+                    let mut path = section.clone();
+                    path.extend(self.toml_key()?);
+                    self.expect(b'=')?;
+                    self.toml_ws(false);
+                    let v = self.toml_value()?;
+                    let key = path.pop().unwrap();
+                    if let Err(e) = table_at(&mut root, &path).and_then(|t| insert(t, key, v)) {
+                        return self.err(e);
+                    }
+                }
+            }
+            self.toml_ws(false);
+            if !matches!(self.peek(), None | Some(b'\n') | Some(b'\r')) {
+                return self.err("expected a new line");
+            }
+        }
+        Ok(Value::Table(root))
+    }
+
+    /// A dotted key, and the spaces after it.
+    fn toml_key(&mut self) -> Result<Vec<String>, WitnessError> {
+        let mut parts = Vec::new();
+        loop {
+            self.toml_ws(false);
+            match self.peek() {
+                Some(b'"') | Some(b'\'') => parts.push(self.string()?),
+                _ => {
+                    let start = self.pos;
+                    while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == b'_' || c == b'-')
+                    {
+                        self.pos += 1;
+                    }
+                    if self.pos == start {
+                        return self.err("expected a key");
+                    }
+                    let part = std::str::from_utf8(&self.s[start..self.pos]).unwrap();
+                    parts.push(part.to_owned());
+                }
+            }
+            self.toml_ws(false);
+            if !self.eat(b'.') {
+                return Ok(parts);
+            }
+        }
+    }
+
+    fn toml_value(&mut self) -> Result<Value, WitnessError> {
+        match self.peek() {
+            Some(b'"') | Some(b'\'') => Ok(Value::Str(self.string()?)),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.toml_ws(true);
+                    if self.eat(b']') {
+                        break;
+                    }
+                    items.push(self.toml_value()?);
+                    self.toml_ws(true);
+                    if self.eat(b']') {
+                        break;
+                    }
+                    self.expect(b',')?;
+                }
+                Ok(Value::Array(items))
+            }
+            _ => self.integer(),
+        }
+    }
+}
+
+/// Named values, as written.
+type Inputs = Vec<(String, Vec<String>)>;
+
+/// A witness file, as read by one party.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WitnessFile {
+    public: Inputs,
+    private: Vec<(usize, Inputs)>,
+}
+
+impl WitnessFile {
+    /// Read a `.json` or `.toml` file.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, WitnessError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json(&text),
+            Some("toml") => Self::from_toml(&text),
+            _ => Err(WitnessError::Schema(format!(
+                "{} is neither .json nor .toml",
+                path.display()
+            ))),
+        }
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, WitnessError> {
+        Self::from_value(Parser::new(text).json()?)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, WitnessError> {
+        Self::from_value(Parser::new(text).toml()?)
+    }
+
+    fn from_value(root: Value) -> Result<Self, WitnessError> {
+        let root = match root {
+            Value::Table(t) => t,
+            _ => return Self::schema("a witness must be a table".to_owned()),
+        };
+        let mut out = Self::default();
+        for (key, v) in root {
+            match (key.as_str(), v) {
+                ("public", Value::Table(t)) => out.public = Self::inputs("public", t)?,
+                ("party", Value::Table(t)) => {
+                    for (party, v) in t {
+                        let section = format!("party.{}", party);
+                        let i = match party.parse::<usize>() {
+                            Ok(i) => i,
+                            Err(_) => return Self::schema(format!("{} is not a party", section)),
+                        };
+                        match v {
+                            Value::Table(t) => out.private.push((i, Self::inputs(&section, t)?)),
+                            _ => return Self::schema(format!("{} must be a table", section)),
+                        }
+                    }
+                }
+                (_, _) => return Self::schema(format!("unexpected {}", key)),
+            }
+        }
+        Ok(out)
+    }
+
+    fn schema<T>(msg: String) -> Result<T, WitnessError> {
+        Err(WitnessError::Schema(msg))
+    }
+
+    /// The inputs in a table, with each flattened to a list of integers.
+    fn inputs(section: &str, table: Table) -> Result<Inputs, WitnessError> {
+        let scalar = |name: &str, v: Value| match v {
+            Value::Int(s) | Value::Str(s) => Ok(s),
+            _ => Err(WitnessError::Schema(format!(
+                "{}.{} must be an integer or an array of integers",
+                section, name
+            ))),
+        };
+        table
+            .into_iter()
+            .map(|(name, v)| {
+                let values = match v {
+                    Value::Array(items) => items
+                        .into_iter()
+                        .map(|v| scalar(&name, v))
+                        .collect::<Result<_, _>>()?,
+                    v => vec![scalar(&name, v)?],
+                };
+                Ok((name, values))
+            })
+            .collect()
+    }
+
+    fn encode<F: PrimeField>(inputs: &Inputs) -> Result<Vec<(String, Vec<F>)>, WitnessError> {
+        inputs
+            .iter()
+            .map(|(name, values)| {
+                let values = values
+                    .iter()
+                    .map(|v| {
+                        parse_field(v).ok_or_else(|| WitnessError::Value {
+                            name: name.clone(),
+                            value: v.clone(),
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok((name.clone(), values))
+            })
+            .collect()
+    }
+
+    pub fn public_names(&self) -> impl Iterator<Item = &str> {
+        self.public.iter().map(|(n, _)| n.as_str())
+    }
+
+    /// The names of `party`'s private inputs, if this file has them.
+    pub fn private_names(&self, party: usize) -> impl Iterator<Item = &str> {
+        self.private
+            .iter()
+            .filter(move |(p, _)| *p == party)
+            .flat_map(|(_, inputs)| inputs.iter().map(|(n, _)| n.as_str()))
+    }
+
+    pub fn public_values<F: PrimeField>(&self) -> Result<Vec<(String, Vec<F>)>, WitnessError> {
+        Self::encode(&self.public)
+    }
+
+    /// `party`'s private inputs, which are empty if this file does not have them.
+    pub fn private_values<F: PrimeField>(
+        &self,
+        party: usize,
+    ) -> Result<Vec<(String, Vec<F>)>, WitnessError> {
+        let mut out = Vec::new();
+        for (_, inputs) in self.private.iter().filter(|(p, _)| *p == party) {
+            out.extend(Self::encode(inputs)?);
+        }
+        Ok(out)
+    }
+}
+
+/// The names and lengths of a party's private inputs, as it announces them.
+fn encode_layout(inputs: &[(String, Vec<impl Sized>)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (name, values) in inputs {
+        out.extend_from_slice(&(name.len() as u32).to_le_bytes());
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(&(values.len() as u64).to_le_bytes());
+    }
+    out
+}
+
+fn decode_layout(mut bytes: &[u8]) -> Option<Vec<(String, usize)>> {
+    let mut out = Vec::new();
+    while !bytes.is_empty() {
+        let n = u32::from_le_bytes(bytes.get(..4)?.try_into().ok()?) as usize;
+        let name = std::str::from_utf8(bytes.get(4..4 + n)?).ok()?.to_owned();
+        let len = u64::from_le_bytes(bytes.get(4 + n..12 + n)?.try_into().ok()?) as usize;
+        out.push((name, len));
+        bytes = &bytes[12 + n..];
+    }
+    Some(out)
+}
+
+/// Broadcast messages that may differ in length between parties, by broadcasting the lengths
+/// first, and then the messages, padded to the longest.
+fn broadcast_var(bytes: &[u8]) -> Vec<Vec<u8>> {
+    let lens: Vec<usize> = Net::broadcast_bytes(&(bytes.len() as u64).to_le_bytes())
+        .into_iter()
+        .map(|l| u64::from_le_bytes(l[..].try_into().unwrap()) as usize)
+        .collect();
+    let mut padded = bytes.to_vec();
+    padded.resize(lens.iter().cloned().max().unwrap_or(0), 0);
+    Net::broadcast_bytes(&padded)
+        .into_iter()
+        .zip(lens)
+        .map(|(mut b, l)| {
+            b.truncate(l);
+            b
+        })
+        .collect()
+}
+
+/// A witness, with the public inputs public and each party's private inputs shared by that party.
+#[derive(Clone, Debug)]
+pub struct SharedWitness<F: PrimeField, S: FieldShare<F>> {
+    /// Each input, with the party that owns it (or `None` if it is public).
+    inputs: Vec<(String, Option<usize>, Vec<MpcField<F, S>>)>,
+}
+
+impl<F: PrimeField, S: FieldShare<F>> SharedWitness<F, S> {
+    /// Share this party's witness `file`.
+    ///
+    /// Each party announces a digest of its public inputs and the names and lengths of its
+    /// private ones, and then shares its private values. Every party must call this, and every
+    /// party fails if any party fails to read its witness.
+    pub fn share<R: Rng>(file: &WitnessFile, rng: &mut R) -> Result<Self, WitnessError> {
+        let me = Net::party_id();
+        let public = file.public_values::<F>();
+        let private = file.private_values::<F>(me);
+        // a party that can't read its witness still takes part, but announces nothing
+        let announcement = match (&public, &private) {
+            (Ok(public), Ok(private)) => {
+                let mut digest = Sha256::new();
+                for (name, values) in public {
+                    let mut bytes = Vec::new();
+                    values.serialize(&mut bytes).unwrap();
+                    digest.update(&(name.len() as u32).to_le_bytes());
+                    digest.update(name.as_bytes());
+                    digest.update(&bytes);
+                }
+                let mut a = digest.finalize().to_vec();
+                a.extend(encode_layout(private));
+                a
+            }
+            _ => Vec::new(),
+        };
+        let all = broadcast_var(&announcement);
+        let (public, private) = (public?, private?);
+        let layouts = all
+            .iter()
+            .enumerate()
+            .map(|(p, a)| {
+                a.get(32..)
+                    .and_then(decode_layout)
+                    .ok_or(WitnessError::PartyFailed(p))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if all.iter().any(|a| a[..32] != announcement[..32]) {
+            return Err(WitnessError::PublicMismatch);
+        }
+
+        let mut inputs: Vec<_> = public
+            .into_iter()
+            .map(|(name, values)| {
+                (
+                    name,
+                    None,
+                    values.into_iter().map(MpcField::Public).collect(),
+                )
+            })
+            .collect();
+        for (p, layout) in layouts.into_iter().enumerate() {
+            if layout.iter().all(|(_, len)| *len == 0) {
+                inputs.extend(layout.into_iter().map(|(n, _)| (n, Some(p), Vec::new())));
+                continue;
+            }
+            let values: Vec<F> = if p == me {
+                private
+                    .iter()
+                    .flat_map(|(_, v)| v.iter().cloned())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let mut shared = MpcField::party_share_batch(p, values, rng).into_iter();
+            for (name, len) in layout {
+                inputs.push((name, Some(p), shared.by_ref().take(len).collect()));
+            }
+        }
+        for (i, (name, _, _)) in inputs.iter().enumerate() {
+            if inputs[..i].iter().any(|(n, _, _)| n == name) {
+                return Err(WitnessError::Schema(format!("{} is defined twice", name)));
+            }
+        }
+        Ok(Self { inputs })
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.inputs.iter().map(|(n, _, _)| n.as_str())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&[MpcField<F, S>]> {
+        self.inputs
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .map(|(_, _, v)| &v[..])
+    }
+
+    /// A public input, or `None` if there is no such input or it is private.
+    pub fn public(&self, name: &str) -> Option<Vec<Public<F>>> {
+        match self.inputs.iter().find(|(n, _, _)| n == name)? {
+            (_, None, v) => v.iter().map(|x| Public::try_from(*x).ok()).collect(),
+            _ => None,
+        }
+    }
+
+    /// A private input, or `None` if there is no such input or it is public.
+    pub fn private(&self, name: &str) -> Option<Vec<Shared<F, S>>> {
+        match self.inputs.iter().find(|(n, _, _)| n == name)? {
+            (_, Some(_), v) => v.iter().map(|x| Shared::try_from(*x).ok()).collect(),
+            _ => None,
+        }
+    }
+
+    /// The party that owns an input, or `None` if it is public.
+    pub fn owner(&self, name: &str) -> Option<Option<usize>> {
+        self.inputs
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .map(|(_, o, _)| *o)
+    }
+
+    /// The number of field elements, over all inputs.
+    pub fn len(&self) -> usize {
+        self.inputs.iter().map(|(_, _, v)| v.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every field element: the public inputs, then party 0's, party 1's, and so on, each in the
+    /// order of their file.
+    pub fn values(&self) -> Vec<MpcField<F, S>> {
+        self.inputs
+            .iter()
+            .flat_map(|(_, _, v)| v.iter().cloned())
+            .collect()
+    }
+
+    /// [Self::values], if there are `expected` of them.
+    pub fn values_exactly(&self, expected: usize) -> Result<Vec<MpcField<F, S>>, WitnessError> {
+        match self.len() {
+            got if got == expected => Ok(self.values()),
+            got => Err(WitnessError::Count { expected, got }),
+        }
+    }
+}
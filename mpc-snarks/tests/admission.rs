@@ -0,0 +1,155 @@
+//! Admission policies, checked before a party joins a session.
+use ark_bls12_377::Fr;
+use ark_relations::lc;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use mpc_net::NetConfig;
+use mpc_snarks::admission::{AdmissionError, AdmissionPolicy, CircuitSummary};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::{JoinError, Network};
+
+use std::net::SocketAddr;
+
+/// `x^(2^n)`, with no witness.
+struct Squarings {
+    n: usize,
+}
+
+impl ConstraintSynthesizer<Fr> for Squarings {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let mut x = cs.new_witness_variable(|| Err(SynthesisError::AssignmentMissing))?;
+        for _ in 0..self.n {
+            let y = cs.new_witness_variable(|| Err(SynthesisError::AssignmentMissing))?;
+            cs.enforce_constraint(lc!() + x, lc!() + x, lc!() + y)?;
+            x = y;
+        }
+        Ok(())
+    }
+}
+
+fn silly() -> CircuitSummary {
+    CircuitSummary::of::<Fr, _>(MySillyCircuit { a: None, b: None }).unwrap()
+}
+
+fn squarings(n: usize) -> CircuitSummary {
+    CircuitSummary::of::<Fr, _>(Squarings { n }).unwrap()
+}
+
+fn addr(s: &str) -> SocketAddr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn summarizes_structure() {
+    let s = silly();
+    assert_eq!(s, silly());
+    assert_eq!(s.num_constraints, 6);
+    assert_eq!((s.num_instance_variables, s.num_witness_variables), (2, 2));
+    assert_eq!(squarings(3).num_constraints, 3);
+    assert_ne!(squarings(3).hash, squarings(4).hash);
+    assert_ne!(squarings(3).hash, s.hash);
+}
+
+#[test]
+fn checks_circuits() {
+    assert!(AdmissionPolicy::new()
+        .check_circuit(&squarings(100))
+        .is_ok());
+
+    let policy = AdmissionPolicy::new()
+        .circuit(silly().hash)
+        .circuit(squarings(10).hash)
+        .max_constraints(8);
+    assert!(policy.check_circuit(&silly()).is_ok());
+    match policy.check_circuit(&squarings(3)) {
+        Err(AdmissionError::CircuitNotAllowed(h)) => assert_eq!(h, squarings(3).hash_hex()),
+        r => panic!("{:?}", r),
+    }
+    match policy.check_circuit(&squarings(10)) {
+        Err(AdmissionError::TooManyConstraints { num: 10, max: 8 }) => {}
+        r => panic!("{:?}", r),
+    }
+    match AdmissionPolicy::new()
+        .max_variables(5)
+        .check_circuit(&squarings(10))
+    {
+        Err(AdmissionError::TooManyVariables { num: 12, max: 5 }) => {}
+        r => panic!("{:?}", r),
+    }
+}
+
+#[test]
+fn checks_counterparties() {
+    let hosts = [
+        addr("10.0.0.1:8000"),
+        addr("10.0.0.2:8000"),
+        addr("10.0.0.3:8000"),
+    ];
+    assert!(AdmissionPolicy::new()
+        .check_counterparties(&hosts, 0)
+        .is_ok());
+
+    let policy = AdmissionPolicy::new()
+        .counterparty(hosts[1])
+        .counterparty(hosts[2]);
+    // a party need not permit itself
+    assert!(policy.check_counterparties(&hosts, 0).is_ok());
+    match policy.check_counterparties(&hosts, 1) {
+        Err(AdmissionError::CounterpartyNotAllowed(a)) => assert_eq!(a, hosts[0]),
+        r => panic!("{:?}", r),
+    }
+}
+
+#[test]
+fn parses_policies() {
+    let text = format!(
+        "# for the audit\n\
+         circuit {}\n\
+         max_constraints 1000   # plenty\n\
+         \n\
+         max_variables 2000\n\
+         counterparty 10.0.0.2:8000\n",
+        silly().hash_hex()
+    );
+    assert_eq!(
+        AdmissionPolicy::parse(&text).unwrap(),
+        AdmissionPolicy::new()
+            .circuit(silly().hash)
+            .max_constraints(1000)
+            .max_variables(2000)
+            .counterparty(addr("10.0.0.2:8000"))
+    );
+    for (bad, line) in &[
+        ("circuit 12ab", 1),
+        ("\nmax_constraints lots", 2),
+        ("counterparty 10.0.0.2", 1),
+        ("max_rounds 3", 1),
+        ("max_constraints", 1),
+    ] {
+        match AdmissionPolicy::parse(bad) {
+            Err(AdmissionError::Parse { line: l, .. }) => assert_eq!(l, *line, "{}", bad),
+            r => panic!("{}: {:?}", bad, r),
+        }
+    }
+}
+
+#[test]
+fn refuses_before_connecting() {
+    let hosts = std::env::temp_dir().join(format!("admission-hosts-{}", std::process::id()));
+    std::fs::write(&hosts, "127.0.0.1:1\n127.0.0.1:2\n").unwrap();
+    let hosts = hosts.to_str().unwrap();
+
+    // no party listens at these addresses: joining would fail to connect
+    let policy = AdmissionPolicy::new().circuit(squarings(1).hash);
+    match Network::join_with_policy(hosts, 0, NetConfig::default(), &policy, &silly()) {
+        Err(JoinError::Refused(AdmissionError::CircuitNotAllowed(_))) => {}
+        r => panic!("{:?}", r.err()),
+    }
+    let policy = AdmissionPolicy::new().counterparty(addr("127.0.0.1:3"));
+    match Network::join_with_policy(hosts, 0, NetConfig::default(), &policy, &silly()) {
+        Err(JoinError::Refused(AdmissionError::CounterpartyNotAllowed(a))) => {
+            assert_eq!(a, addr("127.0.0.1:2"))
+        }
+        r => panic!("{:?}", r.err()),
+    }
+    std::fs::remove_file(hosts).unwrap();
+}
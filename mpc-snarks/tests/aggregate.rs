@@ -0,0 +1,113 @@
+//! Aggregation of real Groth16 proofs, and rejection of tampered aggregates.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::UniformRand;
+use ark_groth16::{
+    create_random_proof, generate_random_parameters, prepare_verifying_key, PreparedVerifyingKey,
+    Proof,
+};
+use mpc_algebra::{seed, AdditivePairingShare, SharedRng};
+use mpc_snarks::groth::aggregate::*;
+use mpc_snarks::groth::mpc_test_prove_and_verify;
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_test_utils::run_parties;
+
+type E = Bls12_377;
+
+/// `n` proofs for random factorisations, each with its instance.
+fn proofs(n: usize) -> (PreparedVerifyingKey<E>, Vec<(Proof<E>, Vec<Fr>)>) {
+    let rng = &mut ark_std::test_rng();
+    let blank = MySillyCircuit { a: None, b: None };
+    let pk = generate_random_parameters::<E, _, _>(blank, rng).unwrap();
+    let proofs = (0..n)
+        .map(|_| {
+            let (a, b) = (Fr::rand(rng), Fr::rand(rng));
+            let circuit = MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            };
+            (create_random_proof(circuit, &pk, rng).unwrap(), vec![a * b])
+        })
+        .collect();
+    (prepare_verifying_key(&pk.vk), proofs)
+}
+
+fn instances(proofs: &[(Proof<E>, Vec<Fr>)]) -> Vec<Vec<Fr>> {
+    proofs.iter().map(|(_, x)| x.clone()).collect()
+}
+
+#[test]
+fn round_trip() {
+    let rng = &mut ark_std::test_rng();
+    let key = AggregationKey::<E>::setup(8, rng);
+    let avk = key.verifying_key();
+    // a power of two, and a batch that is padded to one
+    for n in [4, 3, 1] {
+        let (pvk, proofs) = proofs(n);
+        let agg = aggregate_proofs(&key, &proofs).unwrap();
+        assert!(verify_aggregate_proof(&avk, &pvk, &instances(&proofs), &agg).unwrap());
+    }
+}
+
+#[test]
+fn too_many_proofs() {
+    let key = AggregationKey::<E>::setup(2, &mut ark_std::test_rng());
+    let (_, proofs) = proofs(3);
+    assert!(aggregate_proofs(&key, &proofs).is_err());
+}
+
+#[test]
+fn rejects_a_flipped_proof() {
+    let key = AggregationKey::<E>::setup(4, &mut ark_std::test_rng());
+    let (pvk, mut proofs) = proofs(4);
+    let c = proofs[2].0.c;
+    proofs[2].0.c = (c.into_projective() + c.into_projective()).into_affine();
+    let agg = aggregate_proofs(&key, &proofs).unwrap();
+    assert!(
+        !verify_aggregate_proof(&key.verifying_key(), &pvk, &instances(&proofs), &agg).unwrap()
+    );
+}
+
+#[test]
+fn rejects_a_wrong_public_input() {
+    let key = AggregationKey::<E>::setup(4, &mut ark_std::test_rng());
+    let (pvk, proofs) = proofs(4);
+    let agg = aggregate_proofs(&key, &proofs).unwrap();
+    let mut inputs = instances(&proofs);
+    inputs[1][0] += Fr::from(1u8);
+    assert!(!verify_aggregate_proof(&key.verifying_key(), &pvk, &inputs, &agg).unwrap());
+}
+
+#[test]
+fn rejects_a_tampered_aggregate() {
+    let key = AggregationKey::<E>::setup(4, &mut ark_std::test_rng());
+    let (pvk, proofs) = proofs(4);
+    let mut agg = aggregate_proofs(&key, &proofs).unwrap();
+    agg.z_c = agg.z_c.mul(2u64).into_affine();
+    assert!(
+        !verify_aggregate_proof(&key.verifying_key(), &pvk, &instances(&proofs), &agg).unwrap()
+    );
+}
+
+#[test]
+fn rejects_a_wrong_srs() {
+    let rng = &mut ark_std::test_rng();
+    let key = AggregationKey::<E>::setup(4, rng);
+    let other = AggregationKey::<E>::setup(4, rng);
+    let (pvk, proofs) = proofs(4);
+    let agg = aggregate_proofs(&key, &proofs).unwrap();
+    let inputs = instances(&proofs);
+    assert!(verify_aggregate_proof(&key.verifying_key(), &pvk, &inputs, &agg).unwrap());
+    assert!(!verify_aggregate_proof(&other.verifying_key(), &pvk, &inputs, &agg).unwrap());
+}
+
+#[test]
+fn aggregates_collaborative_proofs() {
+    run_parties(2, || {
+        mpc_test_prove_and_verify::<E, AdditivePairingShare<E>, _, _>(
+            3,
+            &mut SharedRng::new(b"groth16"),
+            &mut seed::rng(b"groth16"),
+        )
+    });
+}
@@ -0,0 +1,46 @@
+//! The collaborative Groth16 prover on BW6-761, the outer curve for proofs over BLS12-377, with
+//! every party run in-process.
+use ark_bw6_761::BW6_761;
+use ark_ec::PairingEngine;
+use ark_ff::UniformRand;
+use mpc_algebra::{
+    AdditivePairingShare, GszPairingShare, MpcField, PairingShare, Reveal, SpdzPairingShare,
+};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+type E = BW6_761;
+
+fn prove_and_verify<S: PairingShare<E>>(n: usize) {
+    let verified = run_parties(n, || {
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let a = <E as PairingEngine>::Fr::rand(rng);
+        let b = <E as PairingEngine>::Fr::rand(rng);
+        let circuit = MySillyCircuit {
+            a: Some(MpcField::<_, S::FrShare>::king_share(a, rng)),
+            b: Some(MpcField::<_, S::FrShare>::king_share(b, rng)),
+        };
+        let proof = prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default()).unwrap().0;
+        let proof = reveal_proof::<E, S>(proof);
+        verify(&pk.vk, &proof, &[a * b]).unwrap() && !verify(&pk.vk, &proof, &[a]).unwrap()
+    });
+    assert_eq!(verified, vec![true; n]);
+}
+
+#[test]
+fn hbc() {
+    prove_and_verify::<AdditivePairingShare<E>>(2);
+}
+
+#[test]
+fn spdz() {
+    prove_and_verify::<SpdzPairingShare<E>>(2);
+}
+
+#[test]
+fn gsz() {
+    prove_and_verify::<GszPairingShare<E>>(3);
+}
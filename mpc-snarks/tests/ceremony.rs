@@ -0,0 +1,64 @@
+//! Keys from a trusted setup run by the parties together.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::UniformRand;
+use ark_groth16::create_random_proof;
+use mpc_algebra::{
+    AdditivePairingShare, GszPairingShare, MpcField, PairingShare, Reveal, SpdzPairingShare,
+};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+type E = Bls12_377;
+
+/// Generate keys collaboratively, then prove with them, both collaboratively and locally.
+fn setup_and_prove<S: PairingShare<E>>(n: usize) {
+    let outs = run_parties(n, || {
+        S::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let pk = setup_collaborative::<E, S, _>(MySillyCircuit { a: None, b: None }).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let a = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let b = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let c = (a * b).reveal();
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let proof = reveal_proof::<E, S>(
+            prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default()).unwrap().0,
+        );
+        S::FrShare::deinit_protocol();
+        (pk, proof, c)
+    });
+    let (pk, _, _) = &outs[0];
+    for (pk2, proof, c) in &outs {
+        assert_eq!(pk2, pk);
+        assert!(verify(&pk.vk, proof, &[*c]).unwrap());
+        assert!(!verify(&pk.vk, proof, &[*c + Fr::from(1u8)]).unwrap());
+    }
+
+    let rng = &mut ark_std::test_rng();
+    let (a, b) = (Fr::rand(rng), Fr::rand(rng));
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = create_random_proof(circuit, pk, rng).unwrap();
+    assert!(verify(&pk.vk, &proof, &[a * b]).unwrap());
+}
+
+#[test]
+fn additive() {
+    setup_and_prove::<AdditivePairingShare<E>>(2);
+}
+
+#[test]
+fn spdz() {
+    setup_and_prove::<SpdzPairingShare<E>>(2);
+}
+
+#[test]
+fn gsz20() {
+    setup_and_prove::<GszPairingShare<E>>(3);
+}
@@ -0,0 +1,133 @@
+//! Checking a shared witness against its constraints before proving, with every party run
+//! in-process.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::{Field, One, UniformRand};
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+};
+use mpc_algebra::{
+    AdditivePairingShare, GszPairingShare, MpcField, PairingShare, Reveal, SpdzPairingShare,
+};
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+type E = Bls12_377;
+
+/// `x_i y_i = z_i`, for each `i`, and the sum of the `z_i` as the public input.
+#[derive(Clone)]
+struct Products<F: Field> {
+    xyz: Vec<Option<(F, F, F)>>,
+}
+
+impl<F: Field> ConstraintSynthesizer<F> for Products<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let sum = cs.new_input_variable(|| {
+            let mut sum = F::zero();
+            for xyz in &self.xyz {
+                sum += xyz.ok_or(SynthesisError::AssignmentMissing)?.2;
+            }
+            Ok(sum)
+        })?;
+        let mut zs = lc!();
+        for xyz in &self.xyz {
+            let get = |f: fn(&(F, F, F)) -> F| xyz.map(|t| f(&t));
+            let x =
+                cs.new_witness_variable(|| get(|t| t.0).ok_or(SynthesisError::AssignmentMissing))?;
+            let y =
+                cs.new_witness_variable(|| get(|t| t.1).ok_or(SynthesisError::AssignmentMissing))?;
+            let z =
+                cs.new_witness_variable(|| get(|t| t.2).ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce_constraint(lc!() + x, lc!() + y, lc!() + z)?;
+            zs = zs + z;
+        }
+        cs.enforce_constraint(zs, lc!() + Variable::One, lc!() + sum)?;
+        Ok(())
+    }
+}
+
+const N: usize = 8;
+
+/// Prove `Products`, with `z_i` off by one for each `i` in `wrong`, and check the outcome.
+fn check<S: PairingShare<E>>(n: usize, wrong: &[usize]) {
+    let rng = &mut ark_std::test_rng();
+    let xyz: Vec<_> = (0..N)
+        .map(|i| {
+            let (x, y) = (Fr::rand(rng), Fr::rand(rng));
+            let z = if wrong.contains(&i) {
+                x * y + Fr::one()
+            } else {
+                x * y
+            };
+            (x, y, z)
+        })
+        .collect();
+    let outs = run_parties(n, || {
+        S::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(Products { xyz: vec![None; N] }, rng).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let mut share = |f| MpcField::<Fr, S::FrShare>::king_share(f, rng);
+        let shared = xyz
+            .iter()
+            .map(|&(x, y, z)| Some((share(x), share(y), share(z))))
+            .collect();
+        let public_input = xyz.iter().map(|t| t.2).sum::<Fr>();
+        let circuit = Products { xyz: shared };
+        let options = ProveOptions::default().check();
+        let out = match prove_collaborative(&mpc_pk, circuit, rng, options) {
+            Ok((proof, _)) => {
+                let proof = reveal_proof::<E, S>(proof);
+                Ok(verify(&pk.vk, &proof, &[public_input]).unwrap())
+            }
+            Err(ProveError::Unsatisfied(u)) => Err(u),
+            Err(e) => panic!("{}", e),
+        };
+        S::FrShare::deinit_protocol();
+        out
+    });
+    let expected = if wrong.is_empty() {
+        Ok(true)
+    } else {
+        Err(UnsatisfiedConstraints {
+            indices: wrong.to_vec(),
+            num_constraints: N + 1,
+        })
+    };
+    assert_eq!(outs, vec![expected; n]);
+}
+
+#[test]
+fn additive() {
+    check::<AdditivePairingShare<E>>(2, &[]);
+    check::<AdditivePairingShare<E>>(2, &[3]);
+}
+
+#[test]
+fn spdz() {
+    check::<SpdzPairingShare<E>>(2, &[]);
+    check::<SpdzPairingShare<E>>(2, &[0, 5]);
+}
+
+#[test]
+fn gsz20() {
+    check::<GszPairingShare<E>>(3, &[]);
+    check::<GszPairingShare<E>>(3, &[1, 2, 7]);
+}
+
+/// The check alone, on a constraint system synthesized in setup mode, without an assignment.
+#[test]
+fn missing_assignment() {
+    use ark_relations::r1cs::{ConstraintSystem, SynthesisMode};
+    type F = MpcField<Fr, mpc_algebra::AdditiveFieldShare<Fr>>;
+    let cs = ConstraintSystem::<F>::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    Products::<F> { xyz: vec![None; 2] }
+        .generate_constraints(cs.clone())
+        .unwrap();
+    cs.finalize();
+    assert!(matches!(
+        debug_check_constraints(&cs),
+        Err(CheckError::Synthesis(SynthesisError::AssignmentMissing))
+    ));
+}
@@ -0,0 +1,106 @@
+//! Checkpointed collaborative proofs, resumed after a party is interrupted.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::UniformRand;
+use mpc_algebra::{
+    AdditivePairingShare, GszPairingShare, MpcField, PairingShare, Reveal, SpdzPairingShare,
+};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+type E = Bls12_377;
+
+/// The phases of the prover: randomness, witness map, and the five MSMs.
+const PHASES: usize = 7;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mpc-checkpoint-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Prove with a fresh checkpoint, then again, resuming it after party 0 lost every phase from
+/// `lost` on. The resumed proof is the same if the scheme's randomness all comes from the
+/// prover's rng (GSZ20 draws its own).
+fn resume<S: PairingShare<E>>(n: usize, lost: usize, same_randomness: bool, dir: &Path) {
+    let ok = run_parties(n, || {
+        S::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let a = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let b = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let c = (a * b).reveal();
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let dir = Checkpoint::party_dir(dir, Net::party_id());
+
+        let mut checkpoint = Checkpoint::create(&dir, "silly").unwrap();
+        let proof = prove_collaborative(
+            &mpc_pk,
+            circuit.clone(),
+            &mut ark_std::test_rng(),
+            ProveOptions::default().checkpoint(&mut checkpoint),
+        );
+        let first = reveal_proof::<E, S>(proof.unwrap().0);
+
+        if Net::party_id() == 0 {
+            for i in lost..PHASES {
+                fs::remove_file(dir.join(format!("phase.{}", i))).unwrap();
+            }
+        }
+        let mut checkpoint = Checkpoint::resume(&dir, "silly").unwrap();
+        assert_eq!(checkpoint.resumed_phases(), lost);
+        let proof = prove_collaborative(
+            &mpc_pk,
+            circuit,
+            &mut ark_std::test_rng(),
+            ProveOptions::default().checkpoint(&mut checkpoint),
+        );
+        let resumed = reveal_proof::<E, S>(proof.unwrap().0);
+        // and the interrupted phases are saved again
+        assert!(dir.join(format!("phase.{}", PHASES - 1)).is_file());
+        S::FrShare::deinit_protocol();
+        verify(&pk.vk, &first, &[c]).unwrap()
+            && verify(&pk.vk, &resumed, &[c]).unwrap()
+            && (first == resumed || !same_randomness)
+    });
+    assert_eq!(ok, vec![true; n]);
+}
+
+#[test]
+fn resumes() {
+    let dir = temp_dir("resumes");
+    for lost in 0..=PHASES {
+        resume::<AdditivePairingShare<E>>(2, lost, true, &dir);
+    }
+    resume::<SpdzPairingShare<E>>(2, 3, true, &dir);
+    resume::<GszPairingShare<E>>(3, 1, false, &dir);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn refuses_other_proofs() {
+    let dir = temp_dir("refuses");
+    run_parties(2, || {
+        let dir = Checkpoint::party_dir(&dir, Net::party_id());
+        let label = format!("proof {}", Net::party_id());
+        Checkpoint::create(&dir, &label).unwrap();
+        assert!(matches!(
+            Checkpoint::resume(&dir, "another proof"),
+            Err(CheckpointError::WrongLabel { .. })
+        ));
+        // each party's checkpoint is of a different proof
+        assert!(matches!(
+            Checkpoint::resume(&dir, &label),
+            Err(CheckpointError::Mismatch)
+        ));
+    });
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,128 @@
+//! The collaborative Groth16 prover against arkworks' own, with every party run in-process.
+//!
+//! Groth16 proving is deterministic given the blinding `r` and `s`, so with the same blinding, the
+//! opened collaborative proof must be the very proof that arkworks makes from the plain witness;
+//! any divergence in the MPC algebra shows up as a difference. With blinding of their own, both
+//! proofs must verify under the same key.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ed_on_bls12_377::{EdwardsParameters, Fr as EdFr};
+use ark_ff::UniformRand;
+use ark_groth16::{create_proof, create_random_proof};
+use ark_relations::r1cs::ConstraintSynthesizer;
+use mpc_algebra::{
+    channel, seed, AdditivePairingShare, GszPairingShare, MpcField, MpcPairingEngine, PairingShare,
+    Reveal, SpdzPairingShare,
+};
+use mpc_snarks::demo::*;
+use mpc_snarks::groth::prover;
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+type E = Bls12_377;
+type P = EdwardsParameters;
+
+/// Prove `circuit`, with public `inputs`, both plainly and by `n` parties, with the witness
+/// lifted by `share`, and check that the proofs agree.
+fn check<S, C, M>(n: usize, circuit: C, inputs: &[Fr], share: impl Fn(&C) -> M + Sync)
+where
+    S: PairingShare<E>,
+    C: ConstraintSynthesizer<Fr> + Clone + Sync,
+    M: ConstraintSynthesizer<MpcField<Fr, S::FrShare>> + Clone,
+{
+    let agreed = run_parties(n, || {
+        S::FrShare::init_protocol();
+        // every party draws the same key and blinding, before any is shared
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(circuit.clone(), rng).unwrap();
+        let (r, s) = (Fr::rand(rng), Fr::rand(rng));
+        let plain = create_proof::<E, _>(circuit.clone(), &pk, r, s).unwrap();
+        let random = create_random_proof::<E, _, _>(circuit.clone(), &pk, rng).unwrap();
+
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let lifted = share(&circuit);
+        let r = MpcField::<Fr, S::FrShare>::king_share(r, rng);
+        let s = MpcField::<Fr, S::FrShare>::king_share(s, rng);
+        let blinded = channel::without_cheating(|| {
+            prover::create_proof::<MpcPairingEngine<E, S>, _>(lifted.clone(), &mpc_pk, r, s)
+        });
+        let blinded = reveal_proof::<E, S>(blinded.unwrap());
+        let collaborative = prove_collaborative(
+            &mpc_pk,
+            lifted,
+            &mut seed::rng(b"prover"),
+            ProveOptions::default(),
+        );
+        let collaborative = reveal_proof::<E, S>(collaborative.unwrap().0);
+        S::FrShare::deinit_protocol();
+
+        (
+            blinded == plain,
+            verify(&pk.vk, &plain, inputs).unwrap()
+                && verify(&pk.vk, &random, inputs).unwrap()
+                && verify(&pk.vk, &collaborative, inputs).unwrap()
+                && collaborative != random,
+        )
+    });
+    // (the same proof with the same blinding, all verified)
+    assert_eq!(agreed, vec![(true, true); n]);
+}
+
+/// The small circuits, with shares of type `S`.
+fn check_all<S: PairingShare<E>>(n: usize) {
+    let rng = &mut ark_std::test_rng();
+    let (a, b) = (Fr::rand(rng), Fr::rand(rng));
+    check::<S, _, _>(
+        n,
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        &[a * b],
+        |c| {
+            let rng = &mut ark_std::test_rng();
+            MySillyCircuit {
+                a: Some(MpcField::king_share(c.a.unwrap(), rng)),
+                b: Some(MpcField::king_share(c.b.unwrap(), rng)),
+            }
+        },
+    );
+
+    let values: Vec<Fr> = (0..8u64).map(Fr::from).collect();
+    let tree = MerkleTree::new(&values);
+    let membership = MembershipCircuit::new(&tree, Some((5, values[5])));
+    let inputs = membership.public_inputs();
+    check::<S, _, _>(n, membership, &inputs, |c| {
+        c.share::<S::FrShare, _>(1, &mut ark_std::test_rng())
+    });
+}
+
+#[test]
+fn additive() {
+    check_all::<AdditivePairingShare<E>>(2);
+    check_all::<AdditivePairingShare<E>>(3);
+}
+
+#[test]
+fn spdz() {
+    check_all::<SpdzPairingShare<E>>(2);
+}
+
+#[test]
+fn gsz20() {
+    check_all::<GszPairingShare<E>>(3);
+}
+
+/// A larger circuit, with curve arithmetic, for one scheme only, as it is slow to prove.
+#[test]
+fn commitment() {
+    type S = AdditivePairingShare<E>;
+    let blinding = EdFr::rand(&mut ark_std::test_rng());
+    let value = 1 << 20;
+    let circuit =
+        CommitmentCircuit::<P>::new(commit::<P>(value, &blinding), Some((value, blinding)));
+    let inputs = circuit.public_inputs();
+    check::<S, _, _>(2, circuit, &inputs, |c| {
+        c.share::<<S as PairingShare<E>>::FrShare, _>(0, &mut ark_std::test_rng())
+    });
+}
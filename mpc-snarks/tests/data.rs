@@ -0,0 +1,183 @@
+//! Reading tables, and ingesting them under a column policy.
+use ark_bls12_377::{Fr, G1Projective};
+use ark_std::test_rng;
+use mpc_algebra::fixed::FixedPoint;
+use mpc_algebra::{AdditiveFieldShare, Reveal};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_snarks::data::*;
+use mpc_test_utils::run_parties;
+
+type Data = Dataset<Fr, AdditiveFieldShare<Fr>>;
+
+fn table(header: &[&str], rows: &[&[&str]]) -> Table {
+    let strings = |r: &[&str]| r.iter().map(|s| s.to_string()).collect();
+    Table::new(strings(header), rows.iter().map(|r| strings(r)).collect())
+}
+
+fn csv_error(text: &str) -> (usize, String) {
+    match Table::from_csv_reader(text.as_bytes()) {
+        Err(DataError::Csv { line, msg }) => (line, msg),
+        r => panic!("expected a CSV error, got {:?}", r),
+    }
+}
+
+#[test]
+fn reads_csv() {
+    let text = "name,x\r\n\"Smith, J\",1.5\n\n\"say \"\"hi\"\"\",\"2\nrows\"\n";
+    let t = Table::from_csv_reader(text.as_bytes()).unwrap();
+    assert_eq!(
+        t,
+        table(
+            &["name", "x"],
+            &[&["Smith, J", "1.5"], &["say \"hi\"", "2\nrows"]]
+        )
+    );
+    // without a final newline
+    let t = Table::from_csv_reader("a\n1".as_bytes()).unwrap();
+    assert_eq!(t.column("a"), Some(vec!["1"]));
+    assert_eq!(t.column("b"), None);
+}
+
+#[test]
+fn rejects_malformed_csv() {
+    assert_eq!(
+        csv_error("a,b\n1,2\n3\n"),
+        (3, "1 fields, but 2 columns".into())
+    );
+    // lines are counted through quoted newlines
+    assert_eq!(
+        csv_error("a\n\"x\ny\"\n1,2\n"),
+        (4, "2 fields, but 1 columns".into())
+    );
+    assert_eq!(csv_error("a\n\"open\n"), (2, "unterminated quote".into()));
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn reads_parquet() {
+    use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use std::sync::Arc;
+
+    let schema = "message t { REQUIRED BINARY name (UTF8); REQUIRED DOUBLE x; OPTIONAL INT64 y; }";
+    let path = std::env::temp_dir().join(format!("mpc-data-{}.parquet", std::process::id()));
+    let file = std::fs::File::create(&path).unwrap();
+    let props = Arc::new(WriterProperties::builder().build());
+    let schema = Arc::new(parse_message_type(schema).unwrap());
+    let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    let mut group = writer.next_row_group().unwrap();
+    let mut column = group.next_column().unwrap().unwrap();
+    let names = [ByteArray::from("a"), ByteArray::from("b, c")];
+    column
+        .typed::<ByteArrayType>()
+        .write_batch(&names, None, None)
+        .unwrap();
+    column.close().unwrap();
+    let mut column = group.next_column().unwrap().unwrap();
+    column
+        .typed::<DoubleType>()
+        .write_batch(&[0.1, 3.0], None, None)
+        .unwrap();
+    column.close().unwrap();
+    let mut column = group.next_column().unwrap().unwrap();
+    column
+        .typed::<Int64Type>()
+        .write_batch(&[-7], Some(&[1, 0]), None)
+        .unwrap();
+    column.close().unwrap();
+    group.close().unwrap();
+    writer.close().unwrap();
+
+    let t = Table::read_parquet(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        t.unwrap(),
+        table(
+            &["name", "x", "y"],
+            &[&["a", "0.1", "-7"], &["b, c", "3", ""]]
+        )
+    );
+}
+
+#[test]
+fn ingests_public_and_shared_columns() {
+    let results = run_parties(2, || {
+        let x = if Net::party_id() == 1 { "2.5" } else { "" };
+        let t = table(&["p", "x", "secret"], &[&["1", x, "9"]]);
+        let policy = Policy::new()
+            .column("p", ColumnPolicy::Public)
+            .column("x", ColumnPolicy::SharedBy(1));
+        let data = Data::ingest(&t, &policy, &FixedPoint::new(8), &mut test_rng()).unwrap();
+        let names: Vec<String> = data.column_names().map(String::from).collect();
+        (names, data.witness(&["p", "x"]).unwrap().reveal())
+    });
+    let encoding = FixedPoint::new(8);
+    let expected: Vec<Fr> = vec![
+        encoding.encode_decimal("1").unwrap(),
+        encoding.encode_decimal("2.5").unwrap(),
+    ];
+    for r in results {
+        assert_eq!(r, (vec!["p".to_owned(), "x".to_owned()], expected.clone()));
+    }
+}
+
+#[test]
+fn every_party_fails_when_an_owner_cannot_share() {
+    let results = run_parties(3, || {
+        let x = if Net::party_id() == 1 { "oops" } else { "" };
+        let t = table(&["x"], &[&[x]]);
+        let policy = Policy::new().column("x", ColumnPolicy::SharedBy(1));
+        Data::ingest(&t, &policy, &FixedPoint::new(8), &mut test_rng()).map(|_| ())
+    });
+    for (party, r) in results.into_iter().enumerate() {
+        match (party, r) {
+            (1, Err(DataError::Encode { column, row: 0, .. })) => assert_eq!(column, "x"),
+            (_, Err(DataError::OwnerFailed { column, owner: 1 })) => assert_eq!(column, "x"),
+            r => panic!("unexpected {:?}", r),
+        }
+    }
+}
+
+#[test]
+fn every_party_fails_when_an_owner_cannot_commit() {
+    let results = run_parties(2, || {
+        let t = table(&["x"], &[&["1"], &["2"]]);
+        let policy = Policy::new().column("x", ColumnPolicy::SharedBy(0));
+        let encoding = FixedPoint::new(8);
+        let mut data = Data::ingest(&t, &policy, &encoding, &mut test_rng()).unwrap();
+        let key = PedersenKey::<G1Projective>::new(b"test", 1);
+        data.commit_columns(&t, &policy, &encoding, &key, &mut test_rng())
+            .map(|_| ())
+    });
+    assert!(matches!(results[0], Err(DataError::ColumnTooLong(2))));
+    assert!(matches!(
+        results[1],
+        Err(DataError::OwnerFailed { owner: 0, .. })
+    ));
+}
+
+#[test]
+fn samples_hidden_masks() {
+    let results = run_parties(2, || {
+        let rng = &mut test_rng();
+        [0.0, 0.25, 0.5, 1.0]
+            .iter()
+            .map(|rate| sample_mask::<Fr, AdditiveFieldShare<Fr>, _>(400, *rate, rng).reveal())
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(results[0], results[1]);
+    for (mask, expected) in results[0].iter().zip(&[0, 100, 200, 400]) {
+        assert!(mask
+            .iter()
+            .all(|b| *b == Fr::from(0u8) || *b == Fr::from(1u8)));
+        let sampled = mask.iter().filter(|b| **b == Fr::from(1u8)).count() as i64;
+        assert!(
+            (sampled - expected).abs() <= 40,
+            "{} sampled of {}",
+            sampled,
+            expected
+        );
+    }
+}
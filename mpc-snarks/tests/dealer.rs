@@ -0,0 +1,52 @@
+//! `proof dealer`, with each party in a process of its own.
+#![cfg(feature = "sled")]
+use mpc_algebra::share::dealer;
+use mpc_algebra::share::store::{Material, PreprocessingStore, SledStore};
+use std::net::TcpListener;
+use std::process::{Command, Stdio};
+
+#[test]
+fn deals_to_every_party() {
+    let dir = std::env::temp_dir().join(format!("proof-dealer-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let listeners: Vec<_> = (0..2).map(|_| TcpListener::bind("127.0.0.1:0").unwrap()).collect();
+    let hosts: String = listeners
+        .iter()
+        .map(|l| format!("{}\n", l.local_addr().unwrap()))
+        .collect();
+    drop(listeners);
+    let hosts_file = dir.join("hosts");
+    std::fs::write(&hosts_file, hosts).unwrap();
+
+    let parties: Vec<_> = (0..2)
+        .map(|party| {
+            Command::new(env!("CARGO_BIN_EXE_proof"))
+                .arg("dealer")
+                .arg("--hosts")
+                .arg(&hosts_file)
+                .args(&["--party", &party.to_string(), "--alg", "spdz", "--out"])
+                .arg(&dir)
+                .args(&["--triples", "5", "--bits", "3"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    for (party, child) in parties.into_iter().enumerate() {
+        let output = child.wait_with_output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{}{}", stdout, stderr);
+        assert!(stderr.contains("insecure for production"), "{}", stderr);
+        assert!(stdout.contains("\"insecure_for_production\":true"), "{}", stdout);
+        assert!(stdout.contains("\"triples\":5"), "{}", stdout);
+
+        let store = SledStore::open(dir.join(format!("party{}", party))).unwrap();
+        assert!(dealer::is_dealt(&store).unwrap());
+        assert!(store.metadata(dealer::MAC_KEY_SHARE).unwrap().is_some());
+        assert_eq!(store.remaining(Material::Triples).unwrap(), 5);
+        assert_eq!(store.remaining(Material::Bits).unwrap(), 3);
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,118 @@
+//! The demo's example circuits, in plain circuits and in collaborative proofs.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ed_on_bls12_377::{EdwardsParameters, Fr as EdFr};
+use ark_ff::{One, UniformRand};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use mpc_algebra::SpdzPairingShare;
+use mpc_algebra::{AdditivePairingShare, GszPairingShare, PairingShare, Reveal};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_snarks::demo::*;
+
+type P = EdwardsParameters;
+type E = Bls12_377;
+
+fn satisfied<C: ConstraintSynthesizer<Fr>>(circuit: C) -> bool {
+    let cs = ConstraintSystem::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    cs.is_satisfied().unwrap()
+}
+
+fn tree() -> MerkleTree<Fr> {
+    let values: Vec<Fr> = (0..8u64).map(Fr::from).collect();
+    MerkleTree::new(&values)
+}
+
+#[test]
+fn circuits() {
+    let (a, b) = (Fr::from(6u64), Fr::from(7u64));
+    assert!(satisfied(MultiplicationCircuit {
+        a: Some(a),
+        b: Some(b)
+    }));
+
+    let blinding = EdFr::rand(&mut ark_std::test_rng());
+    let c = commit::<P>(1 << 40, &blinding);
+    assert!(satisfied(CommitmentCircuit::new(
+        c,
+        Some((1 << 40, blinding))
+    )));
+    assert!(!satisfied(CommitmentCircuit::new(
+        c,
+        Some((1 << 41, blinding))
+    )));
+    // a value too wide for the circuit's bits
+    let wide = commit::<P>(0, &(blinding + EdFr::from(1u128 << 64)));
+    assert!(!satisfied(CommitmentCircuit::new(
+        wide,
+        Some((0, blinding))
+    )));
+
+    let tree = tree();
+    assert!(satisfied(MembershipCircuit::new(
+        &tree,
+        Some((5, Fr::from(5u64)))
+    )));
+    assert!(!satisfied(MembershipCircuit::new(
+        &tree,
+        Some((5, Fr::from(4u64)))
+    )));
+    let mut circuit = MembershipCircuit::new(&tree, Some((2, Fr::from(2u64))));
+    circuit.directions[0] = Some(Fr::one());
+    assert!(!satisfied(circuit));
+}
+
+/// Every example, proved by `n` parties with shares `S`.
+fn prove_examples<S: PairingShare<E>>(n: usize) {
+    let blinding = EdFr::rand(&mut ark_std::test_rng());
+    let c = commit::<P>(42, &blinding);
+    let results = run_local(n, || {
+        S::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let me = Net::party_id();
+
+        let plain = MultiplicationCircuit {
+            a: (me == 0).then_some(Fr::from(6u64)),
+            b: (me == 1).then_some(Fr::from(7u64)),
+        };
+        let circuit = plain.share::<S::FrShare, _>(rng);
+        let product = (circuit.a.unwrap() * circuit.b.unwrap()).reveal();
+        let blank = MultiplicationCircuit { a: None, b: None };
+        let multiplication = prove::<E, S, _, _, _>(blank, circuit, vec![product], rng).unwrap();
+
+        let opening = (me == 0).then_some((42, blinding));
+        let plain = CommitmentCircuit::<P>::new(c, opening);
+        let circuit = plain.share::<S::FrShare, _>(0, rng);
+        let blank = CommitmentCircuit::<P>::new(c, None);
+        let commitment = prove::<E, S, _, _, _>(blank, circuit, plain.public_inputs(), rng);
+
+        let tree = tree();
+        let leaf = (me == 1).then_some((3, Fr::from(3u64)));
+        let plain = MembershipCircuit::new(&tree, leaf);
+        let circuit = plain.share::<S::FrShare, _>(1, rng);
+        let blank = MembershipCircuit::new(&tree, None);
+        let membership = prove::<E, S, _, _, _>(blank, circuit, plain.public_inputs(), rng);
+        S::FrShare::deinit_protocol();
+
+        product == Fr::from(42u64)
+            && multiplication.verified
+            && commitment.unwrap().verified
+            && membership.unwrap().verified
+    })
+    .unwrap();
+    assert_eq!(results, vec![true; n]);
+}
+
+#[test]
+fn additive() {
+    prove_examples::<AdditivePairingShare<E>>(2);
+}
+
+#[test]
+fn spdz() {
+    prove_examples::<SpdzPairingShare<E>>(3);
+}
+
+#[test]
+fn gsz() {
+    prove_examples::<GszPairingShare<E>>(3);
+}
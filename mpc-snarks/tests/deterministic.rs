@@ -0,0 +1,79 @@
+//! Seeded proving is reproducible, and matches the golden proofs in `tests/golden`.
+//!
+//! After a change that is meant to alter proofs, regenerate them with `UPDATE_GOLDEN=1`.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::UniformRand;
+use ark_serialize::CanonicalSerialize;
+use mpc_algebra::{seed, AdditivePairingShare, GszPairingShare, MpcField, PairingShare, Reveal};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+type E = Bls12_377;
+
+/// The opened proof, serialized, with the session's randomness derived from `s`.
+fn seeded_proof<S: PairingShare<E>>(n: usize, s: u8) -> Vec<u8> {
+    let proofs = run_parties(n, || {
+        seed::set_seed(Some([s; 32]));
+        S::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let a = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let b = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let prover_rng = &mut seed::rng(b"prover");
+        let proof = reveal_proof::<E, S>(
+            prove_collaborative(&mpc_pk, circuit, prover_rng, ProveOptions::default()).unwrap().0,
+        );
+        S::FrShare::deinit_protocol();
+        seed::set_seed(None);
+        let mut bytes = Vec::new();
+        proof.serialize(&mut bytes).unwrap();
+        bytes
+    });
+    assert!(proofs.iter().all(|p| p == &proofs[0]));
+    proofs.into_iter().next().unwrap()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for b in bytes {
+        write!(out, "{:02x}", b).unwrap();
+    }
+    out.push('\n');
+    out
+}
+
+fn check_golden(name: &str, proof: &[u8]) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+        .iter()
+        .collect();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, hex(proof)).unwrap();
+    }
+    let golden = fs::read_to_string(&path).unwrap();
+    assert_eq!(golden, hex(proof), "proof differs from {}", path.display());
+}
+
+#[test]
+fn additive() {
+    let p = seeded_proof::<AdditivePairingShare<E>>(2, 1);
+    assert_eq!(p, seeded_proof::<AdditivePairingShare<E>>(2, 1));
+    assert_ne!(p, seeded_proof::<AdditivePairingShare<E>>(2, 2));
+    check_golden("groth16-additive-2.hex", &p);
+}
+
+#[test]
+fn gsz20() {
+    let p = seeded_proof::<GszPairingShare<E>>(3, 1);
+    assert_eq!(p, seeded_proof::<GszPairingShare<E>>(3, 1));
+    check_golden("groth16-gsz20-3.hex", &p);
+}
@@ -0,0 +1,84 @@
+//! Debug exports of the collaborative prover's intermediate values.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::UniformRand;
+use mpc_algebra::share::envelope::{self, PayloadKind};
+use mpc_algebra::{AdditivePairingShare, MpcField, PairingShare, Persisted, Reveal};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+use std::fs;
+
+type E = Bls12_377;
+type S = AdditivePairingShare<E>;
+type MFr = MpcField<Fr, <S as PairingShare<E>>::FrShare>;
+
+const EXPORTED: [(&str, &str); 16] = [
+    ("randomness", "scalars"),
+    ("witness_map", "scalars"),
+    ("qap_a", "scalars"),
+    ("qap_b", "scalars"),
+    ("qap_a_on_coset", "scalars"),
+    ("qap_b_on_coset", "scalars"),
+    ("qap_c", "scalars"),
+    ("qap_c_on_coset", "scalars"),
+    ("qap_h_on_coset", "scalars"),
+    ("h", "g1"),
+    ("witness", "scalars"),
+    ("l", "g1"),
+    ("assignment", "scalars"),
+    ("a", "g1"),
+    ("b_g1", "g1"),
+    ("b_g2", "g2"),
+];
+
+#[test]
+fn exports() {
+    let dir = std::env::temp_dir().join(format!("mpc-export-{}", std::process::id()));
+    let ok = run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+        let (a_pub, b_pub) = (Fr::rand(rng), Fr::rand(rng));
+        let a = MFr::king_share(a_pub, rng);
+        let b = MFr::king_share(b_pub, rng);
+        let c = (a * b).reveal();
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let dir = Checkpoint::party_dir(&dir, Net::party_id());
+        // a stale export is replaced
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("index"), "99.stale\tscalars\t1\n").unwrap();
+        fs::write(dir.join("99.stale"), "").unwrap();
+
+        let mut export = DebugExport::create(&dir).unwrap();
+        let (proof, _) = prove_collaborative(
+            &share_proving_key::<E, S>(&pk),
+            circuit,
+            rng,
+            ProveOptions::default().export(&mut export),
+        )
+        .unwrap();
+        let verified = verify(&pk.vk, &reveal_proof::<E, S>(proof), &[c]).unwrap();
+
+        let index = fs::read_to_string(dir.join("index")).unwrap();
+        let lines: Vec<Vec<&str>> = index.lines().map(|l| l.split('\t').collect()).collect();
+        assert_eq!(lines.len(), EXPORTED.len());
+        assert_eq!(export.exported(), EXPORTED.len());
+        for (i, (line, (name, kind))) in lines.iter().zip(&EXPORTED).enumerate() {
+            assert_eq!(line[0], format!("{:02}.{}", i, name));
+            assert_eq!(line[1], *kind);
+        }
+        assert!(!dir.join("99.stale").exists());
+
+        // the shares of the assignment open to it
+        let Persisted(assignment): Persisted<Vec<MFr>> =
+            envelope::load(PayloadKind::Shares, dir.join("12.assignment")).unwrap();
+        assert_eq!(lines[12][2], "3");
+        verified && assignment.reveal() == vec![c, a_pub, b_pub]
+    });
+    assert_eq!(ok, vec![true; 2]);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,277 @@
+//! The collaborative Groth16 prover, with every party run in-process.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::UniformRand;
+use mpc_algebra::budget::{self, RevealBudget};
+use mpc_algebra::channel::{self, PointEncoding};
+use mpc_algebra::{
+    AdditivePairingShare, GszPairingShare, MpcField, PairingShare, Reveal, SpdzPairingShare,
+};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+type E = Bls12_377;
+
+/// Prove knowledge of a factorisation `c = a * b`, with `a` and `b` shared, and check the opened
+/// proof.
+fn prove_and_verify<S: PairingShare<E>>(n: usize) {
+    let verified = run_parties(n, || {
+        S::FrShare::init_protocol();
+        // c, and the three elements of the proof
+        budget::set_reveal_budget(Some(RevealBudget::abort_after(4)));
+        // every party draws the same keys and inputs
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+
+        let a = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let b = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let c = (a * b).reveal();
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let proof = reveal_proof::<E, S>(
+            prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default()).unwrap().0,
+        );
+        let ok = verify(&pk.vk, &proof, &[c]).unwrap();
+        let wrong = verify(&pk.vk, &proof, &[c + Fr::from(1u8)]).unwrap();
+        assert_eq!(budget::revealed(), 4);
+        budget::set_reveal_budget(None);
+        S::FrShare::deinit_protocol();
+        ok && !wrong
+    });
+    assert_eq!(verified, vec![true; n]);
+}
+
+#[test]
+fn additive() {
+    prove_and_verify::<AdditivePairingShare<E>>(2);
+    prove_and_verify::<AdditivePairingShare<E>>(3);
+}
+
+#[test]
+fn spdz() {
+    prove_and_verify::<SpdzPairingShare<E>>(2);
+    prove_and_verify::<SpdzPairingShare<E>>(3);
+}
+
+#[test]
+fn gsz20() {
+    prove_and_verify::<GszPairingShare<E>>(3);
+}
+
+/// Proving with fixed-base tables gives a valid proof, and the same as without if the scheme's
+/// randomness all comes from the prover's rng (GSZ20 draws its own).
+fn prove_with_tables<S: PairingShare<E>>(n: usize, same_randomness: bool) {
+    let same = run_parties(n, || {
+        S::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let tables = proving_key_tables(&mpc_pk, Some(3));
+        let a = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let b = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let plain = prove_collaborative(
+            &mpc_pk,
+            circuit.clone(),
+            &mut ark_std::test_rng(),
+            ProveOptions::default(),
+        );
+        let tabled = prove_collaborative(
+            &mpc_pk,
+            circuit,
+            &mut ark_std::test_rng(),
+            ProveOptions::default().tables(&tables),
+        );
+        let (plain, tabled) = (
+            reveal_proof::<E, S>(plain.unwrap().0),
+            reveal_proof::<E, S>(tabled.unwrap().0),
+        );
+        S::FrShare::deinit_protocol();
+        let c = (a * b).reveal();
+        (
+            plain == tabled || !same_randomness,
+            verify(&pk.vk, &plain, &[c]).unwrap() && verify(&pk.vk, &tabled, &[c]).unwrap(),
+        )
+    });
+    assert_eq!(same, vec![(true, true); n]);
+}
+
+#[test]
+fn tables() {
+    prove_with_tables::<AdditivePairingShare<E>>(2, true);
+    prove_with_tables::<SpdzPairingShare<E>>(2, true);
+    prove_with_tables::<GszPairingShare<E>>(3, false);
+}
+
+/// A prover context makes the same proofs as proving without one, again and again.
+fn prove_with_context<S: PairingShare<E>>(n: usize, same_randomness: bool) {
+    let same = run_parties(n, || {
+        S::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let context = prover_context(mpc_pk.clone(), Some(3)).unwrap();
+        let mut ok = true;
+        for _ in 0..2 {
+            let a = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+            let b = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+            let circuit = MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            };
+            let plain = prove_collaborative(
+                &mpc_pk,
+                circuit.clone(),
+                &mut ark_std::test_rng(),
+                ProveOptions::default(),
+            );
+            let cached = prove_collaborative(
+                &mpc_pk,
+                circuit,
+                &mut ark_std::test_rng(),
+                ProveOptions::default().context(&context),
+            );
+            let (plain, cached) = (
+                reveal_proof::<E, S>(plain.unwrap().0),
+                reveal_proof::<E, S>(cached.unwrap().0),
+            );
+            let c = (a * b).reveal();
+            ok &= plain == cached || !same_randomness;
+            ok &= verify(&pk.vk, &cached, &[c]).unwrap();
+        }
+        S::FrShare::deinit_protocol();
+        ok
+    });
+    assert_eq!(same, vec![true; n]);
+}
+
+#[test]
+fn context() {
+    prove_with_context::<AdditivePairingShare<E>>(2, true);
+    prove_with_context::<SpdzPairingShare<E>>(2, true);
+    prove_with_context::<GszPairingShare<E>>(3, false);
+}
+
+#[test]
+#[should_panic(expected = "the prover context is for another key")]
+fn context_for_another_key() {
+    type S = AdditivePairingShare<E>;
+    run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        let mut keys = (0..2).map(|_| {
+            let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+            share_proving_key::<E, S>(&pk)
+        });
+        let (mpc_pk, other) = (keys.next().unwrap(), keys.next().unwrap());
+        let context = prover_context(other, Some(3)).unwrap();
+        let a = MpcField::<Fr, <S as PairingShare<E>>::FrShare>::king_share(Fr::rand(rng), rng);
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(a),
+        };
+        prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default().context(&context))
+    });
+}
+
+/// With points exchanged uncompressed, the proof is the same, and it is written out in the
+/// session's encoding.
+#[test]
+fn uncompressed() {
+    type S = AdditivePairingShare<E>;
+    let outs = run_parties(2, || {
+        let proofs: Vec<_> = [PointEncoding::Compressed, PointEncoding::Uncompressed]
+            .iter()
+            .map(|&e| {
+                channel::set_point_encoding(Some(e));
+                let rng = &mut ark_std::test_rng();
+                let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+                let mpc_pk = share_proving_key::<E, S>(&pk);
+                let a =
+                    MpcField::<Fr, <S as PairingShare<E>>::FrShare>::king_share(Fr::rand(rng), rng);
+                let b =
+                    MpcField::<Fr, <S as PairingShare<E>>::FrShare>::king_share(Fr::rand(rng), rng);
+                let circuit = MySillyCircuit {
+                    a: Some(a),
+                    b: Some(b),
+                };
+                let proof = reveal_proof::<E, S>(
+                    prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default()).unwrap().0,
+                );
+                (proof, ProofFormat::session())
+            })
+            .collect();
+        channel::set_point_encoding(None);
+        proofs
+    });
+    for proofs in outs {
+        let (compressed, uncompressed) = (&proofs[0], &proofs[1]);
+        assert_eq!(compressed.0, uncompressed.0);
+        let proof = &compressed.0;
+        assert_eq!(compressed.1.encode(proof).len(), 192);
+        assert_eq!(uncompressed.1.encode(proof).len(), 384);
+        for &order in &[ByteOrder::LittleEndian, ByteOrder::BigEndian] {
+            for &points in &[PointEncoding::Compressed, PointEncoding::Uncompressed] {
+                let format = ProofFormat { points, order };
+                let bytes = format.encode(proof);
+                assert_eq!(&format.decode::<E>(&bytes).unwrap(), proof);
+            }
+        }
+        let big = ProofFormat {
+            order: ByteOrder::BigEndian,
+            ..compressed.1
+        };
+        // the first coordinate, a's x, reversed
+        assert_eq!(
+            big.encode(proof)[..48].iter().rev().collect::<Vec<_>>(),
+            compressed.1.encode(proof)[..48].iter().collect::<Vec<_>>()
+        );
+    }
+}
+
+/// A 32-byte big-endian word, from hex.
+fn word(hex: &str) -> Vec<u8> {
+    let hex = format!("{:0>64}", hex);
+    (0..32)
+        .map(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap())
+        .collect()
+}
+
+/// Big-endian uncompressed proofs are laid out as the EVM's pairing precompile takes points, with
+/// G2 coordinates imaginary part first: here the generators, as EIP-197 gives them.
+#[test]
+fn evm_encoding() {
+    use ark_bn254::{Bn254, G1Affine, G2Affine};
+    use ark_ec::AffineCurve;
+    let g1 = G1Affine::prime_subgroup_generator();
+    let proof = ark_groth16::Proof::<Bn254> {
+        a: g1,
+        b: G2Affine::prime_subgroup_generator(),
+        c: -g1,
+    };
+    let format = ProofFormat {
+        points: PointEncoding::Uncompressed,
+        order: ByteOrder::BigEndian,
+    };
+    let expected: Vec<u8> = [
+        "1",
+        "2",
+        "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2",
+        "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed",
+        "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b",
+        "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+        "1",
+        // the base field's modulus, less 2
+        "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45",
+    ]
+    .iter()
+    .flat_map(|w| word(w))
+    .collect();
+    assert_eq!(format.encode(&proof), expected);
+    assert_eq!(format.decode::<Bn254>(&expected).unwrap(), proof);
+}
@@ -0,0 +1,422 @@
+//! Parameters read from snarkjs's `.ptau` and `.zkey` files, written here from known secrets.
+use ark_bls12_377::{Bls12_377, Fq, Fr, G1Projective, G2Affine, G2Projective};
+use ark_bn254::{Bn254, Fr as BnFr, G1Affine as BnG1};
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, FpParameters, One, PrimeField, UniformRand};
+use ark_groth16::create_random_proof;
+use ark_groth16::r1cs_to_qap::R1CStoQAP;
+use ark_mnt4_753::MNT4_753;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, SynthesisMode,
+};
+use ark_serialize::CanonicalSerialize;
+use mpc_algebra::{AdditivePairingShare, MpcField, PairingShare, Reveal, SpdzPairingShare};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+type E = Bls12_377;
+
+/// Bytes per point, as snarkjs writes them.
+const G1: usize = 96;
+const G2: usize = 192;
+
+/// `x`, as snarkjs writes it: `x R`, little-endian.
+fn mont<F: PrimeField>(x: F) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let r = F::from_repr(F::Params::R).unwrap();
+    (x * r).into_repr().serialize(&mut bytes).unwrap();
+    bytes
+}
+
+/// `p`, as snarkjs writes it: each coordinate as above, in arkworks' order, and the identity as
+/// zeros.
+fn point<F: PrimeField, G: AffineCurve>(p: G) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    p.serialize_uncompressed(&mut bytes).unwrap();
+    if p.is_zero() {
+        return vec![0; bytes.len()];
+    }
+    // the flags, in the last coordinate's spare top bits
+    *bytes.last_mut().unwrap() &= 0x3f;
+    bytes
+        .chunks(F::zero().serialized_size())
+        .flat_map(|x| mont(F::deserialize(x).unwrap()))
+        .collect()
+}
+
+fn g1(p: G1Projective) -> Vec<u8> {
+    point::<Fq, _>(p.into_affine())
+}
+
+fn g2(p: G2Projective) -> Vec<u8> {
+    point::<Fq, _>(p.into_affine())
+}
+
+fn modulus<F: PrimeField>() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    F::Params::MODULUS.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+fn file(magic: &[u8; 4], sections: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    let mut bytes = magic.to_vec();
+    bytes.extend(&1u32.to_le_bytes());
+    bytes.extend(&(sections.len() as u32).to_le_bytes());
+    for (section, data) in sections {
+        bytes.extend(&section.to_le_bytes());
+        bytes.extend(&(data.len() as u64).to_le_bytes());
+        bytes.extend(data);
+    }
+    bytes
+}
+
+/// `x G1` of `E`, as snarkjs writes it.
+fn gen1<E: PairingEngine>(x: E::Fr) -> Vec<u8> {
+    point::<E::Fq, _>(
+        E::G1Affine::prime_subgroup_generator()
+            .mul(x.into_repr())
+            .into_affine(),
+    )
+}
+
+/// `x G2` of `E`, as snarkjs writes it.
+fn gen2<E: PairingEngine>(x: E::Fr) -> Vec<u8> {
+    point::<E::Fq, _>(
+        E::G2Affine::prime_subgroup_generator()
+            .mul(x.into_repr())
+            .into_affine(),
+    )
+}
+
+fn g1_gen(x: Fr) -> G1Projective {
+    G1Projective::prime_subgroup_generator().mul(x.into_repr())
+}
+
+fn g2_gen(x: Fr) -> G2Projective {
+    G2Projective::prime_subgroup_generator().mul(x.into_repr())
+}
+
+/// The sections of a `.ptau` file of `2^power` powers of `τ`, with `α` and `β`.
+fn ptau(power: u32, tau: Fr, alpha: Fr, beta: Fr) -> Vec<(u32, Vec<u8>)> {
+    let n = 1usize << power;
+    let pow = |i: usize| tau.pow([i as u64]);
+    let header = [
+        48u32.to_le_bytes().to_vec(),
+        modulus::<Fq>(),
+        power.to_le_bytes().to_vec(),
+        power.to_le_bytes().to_vec(),
+    ]
+    .concat();
+    vec![
+        (1, header),
+        (2, (0..2 * n - 1).flat_map(|i| g1(g1_gen(pow(i)))).collect()),
+        (3, (0..n).flat_map(|i| g2(g2_gen(pow(i)))).collect()),
+        (4, (0..n).flat_map(|i| g1(g1_gen(alpha * pow(i)))).collect()),
+        (5, (0..n).flat_map(|i| g1(g1_gen(beta * pow(i)))).collect()),
+        (6, g2(g2_gen(beta))),
+    ]
+}
+
+struct Waste<F> {
+    tau: F,
+    alpha: F,
+    beta: F,
+    delta: F,
+}
+
+/// The sections of a `.zkey` file for the silly circuit, with `γ = 1`, as snarkjs makes them, but
+/// with the prover's roots of unity.
+fn zkey<E: PairingEngine>(w: &Waste<E::Fr>) -> Vec<(u32, Vec<u8>)> {
+    let cs = ConstraintSystem::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::Constraints);
+    cs.set_mode(SynthesisMode::Setup);
+    MySillyCircuit::<E::Fr> { a: None, b: None }
+        .generate_constraints(cs.clone())
+        .unwrap();
+    cs.finalize();
+    let n_public = cs.num_instance_variables() - 1;
+    let (a, b, c, _, _, n) = R1CStoQAP::instance_map_with_evaluation::<
+        E::Fr,
+        GeneralEvaluationDomain<E::Fr>,
+    >(cs, &w.tau)
+    .unwrap();
+    let n_vars = a.len();
+    let abc: Vec<_> = (0..n_vars)
+        .map(|i| w.beta * a[i] + w.alpha * b[i] + c[i])
+        .collect();
+    let delta_inv = w.delta.inverse().unwrap();
+    let lagrange = GeneralEvaluationDomain::<E::Fr>::new(2 * n)
+        .unwrap()
+        .evaluate_all_lagrange_coefficients(w.tau);
+    let header = [
+        (modulus::<E::Fq>().len() as u32).to_le_bytes().to_vec(),
+        modulus::<E::Fq>(),
+        (modulus::<E::Fr>().len() as u32).to_le_bytes().to_vec(),
+        modulus::<E::Fr>(),
+        (n_vars as u32).to_le_bytes().to_vec(),
+        (n_public as u32).to_le_bytes().to_vec(),
+        (n as u32).to_le_bytes().to_vec(),
+        gen1::<E>(w.alpha),
+        gen1::<E>(w.beta),
+        gen2::<E>(w.beta),
+        gen2::<E>(E::Fr::one()),
+        gen1::<E>(w.delta),
+        gen2::<E>(w.delta),
+    ]
+    .concat();
+    let g1s = |xs: &[E::Fr]| xs.iter().flat_map(|x| gen1::<E>(*x)).collect::<Vec<_>>();
+    let l: Vec<_> = abc[n_public + 1..].iter().map(|x| *x * delta_inv).collect();
+    let h: Vec<_> = (0..n).map(|i| lagrange[2 * i + 1] * delta_inv).collect();
+    vec![
+        (1, 1u32.to_le_bytes().to_vec()),
+        (2, header),
+        (3, g1s(&abc[..n_public + 1])),
+        (5, g1s(&a)),
+        (6, g1s(&b)),
+        (7, b.iter().flat_map(|x| gen2::<E>(*x)).collect()),
+        (8, g1s(&l)),
+        (9, g1s(&h)),
+    ]
+}
+
+fn waste<F: PrimeField>() -> Waste<F> {
+    let rng = &mut ark_std::test_rng();
+    Waste {
+        tau: F::rand(rng),
+        alpha: F::rand(rng),
+        beta: F::rand(rng),
+        delta: F::rand(rng),
+    }
+}
+
+fn prove_and_verify<E: PairingEngine>(pk: &ark_groth16::ProvingKey<E>) -> bool {
+    let rng = &mut ark_std::test_rng();
+    let (a, b) = (E::Fr::rand(rng), E::Fr::rand(rng));
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = create_random_proof(circuit, pk, rng).unwrap();
+    verify(&pk.vk, &proof, &[a * b]).unwrap() && !verify(&pk.vk, &proof, &[a]).unwrap()
+}
+
+fn read<E: PairingEngine>(
+    sections: &[(u32, Vec<u8>)],
+) -> Result<ark_groth16::ProvingKey<E>, ImportError> {
+    read_zkey::<E, _, _>(&file(b"zkey", sections)[..], &mut ark_std::test_rng())
+}
+
+#[test]
+fn zkey_keys_prove() {
+    let w = waste::<BnFr>();
+    let pk = read::<Bn254>(&zkey::<Bn254>(&w)).unwrap();
+    assert!(prove_and_verify(&pk));
+
+    // the H-query converted to powers of τ
+    let n = pk.h_query.len() + 1;
+    let zt = w.tau.pow([n as u64]) - BnFr::one();
+    let delta_inv = w.delta.inverse().unwrap();
+    for (i, h) in pk.h_query.iter().enumerate() {
+        let x = zt * delta_inv * w.tau.pow([i as u64]);
+        assert_eq!(
+            *h,
+            BnG1::prime_subgroup_generator()
+                .mul(x.into_repr())
+                .into_affine()
+        );
+    }
+}
+
+/// snarkjs's roots of unity in BLS12-377's scalar field are not the prover's, so a well-formed
+/// `.zkey` reads to the very end, and no further.
+#[test]
+fn zkey_roots() {
+    assert!(matches!(
+        read::<E>(&zkey::<E>(&waste())),
+        Err(ImportError::Roots)
+    ));
+}
+
+#[test]
+fn bad_zkeys() {
+    let w = waste::<Fr>();
+    let rng = &mut ark_std::test_rng();
+    let sections = zkey::<E>(&w);
+    let at = |s: u32| sections.iter().position(|(t, _)| *t == s).unwrap();
+
+    let ptau_file = file(b"ptau", &ptau(3, w.tau, w.alpha, w.beta));
+    assert!(matches!(
+        read_zkey::<E, _, _>(&ptau_file[..], rng),
+        Err(ImportError::NotA(_))
+    ));
+    let zkey_file = file(b"zkey", &sections);
+    assert!(matches!(
+        read_zkey::<MNT4_753, _, _>(&zkey_file[..], rng),
+        Err(ImportError::Curve)
+    ));
+    assert!(matches!(
+        read_zkey::<E, _, _>(&zkey_file[..zkey_file.len() - 1], rng),
+        Err(ImportError::Io(_))
+    ));
+
+    let mut groth = sections.clone();
+    groth[at(1)].1 = 2u32.to_le_bytes().to_vec();
+    assert!(matches!(read::<E>(&groth), Err(ImportError::Protocol(2))));
+
+    let mut short = sections.clone();
+    short[at(9)].1.truncate(G1);
+    assert!(matches!(
+        read::<E>(&short),
+        Err(ImportError::SectionSize { section: 9, .. })
+    ));
+
+    let mut missing = sections.clone();
+    missing.remove(at(8));
+    assert!(matches!(
+        read::<E>(&missing),
+        Err(ImportError::MissingSection(8))
+    ));
+
+    // y + 1, off the curve
+    let mut off_curve = sections.clone();
+    let a1 = g1_gen(Fr::from(3u8)).into_affine();
+    let off = [mont(a1.x), mont(a1.y + Fq::one())].concat();
+    off_curve[at(5)].1[G1..2 * G1].copy_from_slice(&off);
+    assert!(matches!(
+        read::<E>(&off_curve),
+        Err(ImportError::Point {
+            section: 5,
+            index: 1
+        })
+    ));
+
+    // δ in G2 not the one in G1
+    let mut delta = sections.clone();
+    let header = &mut delta[at(2)].1;
+    let len = header.len();
+    header[len - G2..].copy_from_slice(&g2(g2_gen(w.delta.double())));
+    assert!(matches!(
+        read::<E>(&delta),
+        Err(ImportError::Inconsistent(_))
+    ));
+
+    let mut b = sections.clone();
+    b[at(7)].1[..G2].copy_from_slice(&g2(g2_gen(Fr::from(5u8))));
+    assert!(matches!(read::<E>(&b), Err(ImportError::Inconsistent(_))));
+}
+
+#[test]
+fn checks_arkworks_keys() {
+    let rng = &mut ark_std::test_rng();
+    let mut pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+    check_proving_key(&pk, rng).unwrap();
+    pk.b_g2_query[1] = G2Affine::prime_subgroup_generator();
+    assert!(check_proving_key(&pk, rng).is_err());
+    pk.b_g2_query.pop();
+    assert!(check_proving_key(&pk, rng).is_err());
+}
+
+fn powers_of_tau() -> PowersOfTau<E> {
+    let w = waste::<Fr>();
+    let bytes = file(b"ptau", &ptau(3, w.tau, w.alpha, w.beta));
+    PowersOfTau::read(&bytes[..], &mut ark_std::test_rng()).unwrap()
+}
+
+#[test]
+fn bad_ptaus() {
+    let w = waste::<Fr>();
+    let rng = &mut ark_std::test_rng();
+    let sections = ptau(3, w.tau, w.alpha, w.beta);
+    let read = |sections: &[(u32, Vec<u8>)]| {
+        PowersOfTau::<E>::read(&file(b"ptau", sections)[..], &mut ark_std::test_rng())
+    };
+    assert_eq!(powers_of_tau().max_domain_size(), 8);
+    assert!(matches!(
+        PowersOfTau::<MNT4_753>::read(&file(b"ptau", &sections)[..], rng),
+        Err(ImportError::Curve)
+    ));
+
+    // τ^3 and τ^4 swapped
+    let mut swapped = sections.clone();
+    let tau_g1 = &mut swapped[1].1;
+    let (three, four) = (
+        tau_g1[3 * G1..4 * G1].to_vec(),
+        tau_g1[4 * G1..5 * G1].to_vec(),
+    );
+    tau_g1[3 * G1..4 * G1].copy_from_slice(&four);
+    tau_g1[4 * G1..5 * G1].copy_from_slice(&three);
+    assert!(matches!(read(&swapped), Err(ImportError::Inconsistent(_))));
+
+    let mut beta = sections.clone();
+    beta[5].1 = g2(g2_gen(w.alpha));
+    assert!(matches!(read(&beta), Err(ImportError::Inconsistent(_))));
+
+    let mut generator = sections.clone();
+    generator[1].1[..G1].copy_from_slice(&g1(g1_gen(Fr::from(2u8))));
+    assert!(matches!(
+        read(&generator),
+        Err(ImportError::Inconsistent(_))
+    ));
+
+    let mut short = sections;
+    short[2].1.truncate(G2);
+    assert!(matches!(
+        read(&short),
+        Err(ImportError::SectionSize { section: 3, .. })
+    ));
+}
+
+/// Keys from powers of tau, with `δ` drawn by the parties, then a collaborative proof with them.
+fn setup_from_powers_and_prove<S: PairingShare<E>>(n: usize) {
+    let powers = powers_of_tau();
+    let outs = run_parties(n, move || {
+        S::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let pk = setup_from_powers_of_tau::<E, S, _>(MySillyCircuit { a: None, b: None }, &powers)
+            .unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let a = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let b = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let c = (a * b).reveal();
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let proof = reveal_proof::<E, S>(
+            prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default()).unwrap().0,
+        );
+        S::FrShare::deinit_protocol();
+        (pk, proof, c)
+    });
+    let (pk, _, _) = &outs[0];
+    check_proving_key(pk, &mut ark_std::test_rng()).unwrap();
+    assert!(prove_and_verify(pk));
+    for (pk2, proof, c) in &outs {
+        assert_eq!(pk2, pk);
+        assert!(verify(&pk.vk, proof, &[*c]).unwrap());
+    }
+}
+
+#[test]
+fn additive_from_powers() {
+    setup_from_powers_and_prove::<AdditivePairingShare<E>>(2);
+}
+
+#[test]
+fn spdz_from_powers() {
+    setup_from_powers_and_prove::<SpdzPairingShare<E>>(2);
+}
+
+#[test]
+fn too_few_powers() {
+    let powers = powers_of_tau();
+    let outs = run_parties(2, move || {
+        let circuit = MySillyCircuit::<Fr> { a: None, b: None };
+        let mut few = powers.clone();
+        few.tau_g2.truncate(4);
+        setup_from_powers_of_tau::<E, AdditivePairingShare<E>, _>(circuit, &few).is_err()
+    });
+    assert_eq!(outs, vec![true; 2]);
+}
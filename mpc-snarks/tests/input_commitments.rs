@@ -0,0 +1,66 @@
+//! Commitments to the parties' input shares, made before proving.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::UniformRand;
+use mpc_algebra::channel::MpcSerNet;
+use mpc_algebra::{
+    AdditivePairingShare, GszPairingShare, MpcField, PairingShare, Reveal, SpdzPairingShare,
+};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+type E = Bls12_377;
+
+/// Prove with input commitments, then have every party open its commitment to the others, as an
+/// audit would, and check the openings, and a tampered one.
+fn commit_and_audit<S: PairingShare<E>>(n: usize) {
+    let outs = run_parties(n, || {
+        S::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let a = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let b = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let c = (a * b).reveal();
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let mut inputs = InputCommitments::default();
+        let options = ProveOptions::default().commit_inputs(&mut inputs);
+        let (proof, _) = prove_collaborative(&mpc_pk, circuit, rng, options).unwrap();
+        let verified = verify(&pk.vk, &reveal_proof::<E, S>(proof), &[c]).unwrap();
+
+        let (shares, rand) = inputs.opening();
+        let openings = Net::broadcast(&(shares.to_vec(), rand.to_vec()));
+        let honest = openings
+            .iter()
+            .enumerate()
+            .all(|(p, (shares, rand))| inputs.check(p, shares, rand));
+        let (shares, rand) = &openings[(Net::party_id() + 1) % n];
+        let mut swapped = shares.clone();
+        swapped.swap(0, 1);
+        let caught = !inputs.check((Net::party_id() + 1) % n, &swapped, rand)
+            && !inputs.check(Net::party_id(), shares, rand)
+            && !inputs.check(n, shares, rand);
+        S::FrShare::deinit_protocol();
+        (inputs.commitments.len() == n, verified, honest, caught)
+    });
+    assert_eq!(outs, vec![(true, true, true, true); n]);
+}
+
+#[test]
+fn additive() {
+    commit_and_audit::<AdditivePairingShare<E>>(2);
+}
+
+#[test]
+fn spdz() {
+    commit_and_audit::<SpdzPairingShare<E>>(2);
+}
+
+#[test]
+fn gsz20() {
+    commit_and_audit::<GszPairingShare<E>>(3);
+}
@@ -0,0 +1,113 @@
+//! The collaborative Groth16 prover on the MNT4-753/MNT6-753 cycle, whose scalar fields have
+//! smaller two-adicity, with every party run in-process.
+//!
+//! The scalar field of MNT6-753 has two-adicity 15, so circuits with more than `2^15` constraints
+//! need mixed-radix (`2^a * 5^b`) domains there.
+use ark_ec::PairingEngine;
+use ark_ff::{Field, PrimeField, UniformRand};
+use ark_mnt4_753::MNT4_753;
+use ark_mnt6_753::MNT6_753;
+use ark_poly::{EvaluationDomain, MixedRadixEvaluationDomain};
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError},
+};
+use mpc_algebra::{AdditivePairingShare, MpcField, PairingShare, Reveal, SpdzPairingShare};
+use mpc_snarks::groth::r1cs_to_qap::R1CStoQAP;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+/// `y = x^(2^n)`, with `y` public.
+#[derive(Clone)]
+struct Squarings<F> {
+    x: Option<F>,
+    n: usize,
+}
+
+impl<F: Field> ConstraintSynthesizer<F> for Squarings<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let mut x = self.x;
+        let mut var = cs.new_witness_variable(|| x.ok_or(SynthesisError::AssignmentMissing))?;
+        for i in 0..self.n {
+            x = x.map(|x| x.square());
+            let next = if i + 1 == self.n {
+                cs.new_input_variable(|| x.ok_or(SynthesisError::AssignmentMissing))?
+            } else {
+                cs.new_witness_variable(|| x.ok_or(SynthesisError::AssignmentMissing))?
+            };
+            cs.enforce_constraint(lc!() + var, lc!() + var, lc!() + next)?;
+            var = next;
+        }
+        Ok(())
+    }
+}
+
+fn prove_and_verify<E: PairingEngine, S: PairingShare<E>>(n: usize) {
+    let verified = run_parties(n, || {
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(Squarings { x: None, n: 4 }, rng).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let x = E::Fr::rand(rng);
+        let shared = MpcField::<E::Fr, S::FrShare>::king_share(x, rng);
+        let proof = prove_collaborative(
+            &mpc_pk,
+            Squarings {
+                x: Some(shared),
+                n: 4,
+            },
+            rng,
+            ProveOptions::default(),
+        )
+        .unwrap()
+        .0;
+        let proof = reveal_proof::<E, S>(proof);
+        let y = (0..4).fold(x, |x, _| x.square());
+        verify(&pk.vk, &proof, &[y]).unwrap() && !verify(&pk.vk, &proof, &[x]).unwrap()
+    });
+    assert_eq!(verified, vec![true; n]);
+}
+
+#[test]
+fn mnt4_753() {
+    prove_and_verify::<MNT4_753, AdditivePairingShare<MNT4_753>>(2);
+}
+
+#[test]
+fn mnt6_753() {
+    prove_and_verify::<MNT6_753, AdditivePairingShare<MNT6_753>>(2);
+    prove_and_verify::<MNT6_753, SpdzPairingShare<MNT6_753>>(2);
+}
+
+fn mixed_radix_h<F: PrimeField>(x: F) -> Vec<F> {
+    let cs = ConstraintSystem::new_ref();
+    // 8 constraints and 2 inputs, including the constant
+    Squarings { x: Some(x), n: 8 }
+        .generate_constraints(cs.clone())
+        .unwrap();
+    cs.finalize();
+    R1CStoQAP::witness_map::<F, MixedRadixEvaluationDomain<F>>(cs).unwrap()
+}
+
+/// The witness map over a domain of size `2 * 5`, computed on shares, is that computed in the
+/// clear.
+#[test]
+fn mixed_radix_witness_map() {
+    type Fr = <MNT6_753 as PairingEngine>::Fr;
+    type S = <AdditivePairingShare<MNT6_753> as PairingShare<MNT6_753>>::FrShare;
+    type MpcFr = MpcField<Fr, S>;
+    let x = Fr::rand(&mut ark_std::test_rng());
+    let expected = mixed_radix_h(x);
+    assert_eq!(expected.len(), 10);
+    assert_eq!(
+        MixedRadixEvaluationDomain::<Fr>::new(10).unwrap().size(),
+        10
+    );
+
+    let outs = run_parties(2, move || {
+        let rng = &mut ark_std::test_rng();
+        mixed_radix_h(MpcFr::king_share(x, rng)).reveal()
+    });
+    for out in outs {
+        assert_eq!(out, expected);
+    }
+}
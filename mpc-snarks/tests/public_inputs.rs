@@ -0,0 +1,146 @@
+//! Public inputs that are partly public from the start and partly shared until revealed.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::{Field, One, UniformRand};
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use mpc_algebra::budget::{self, RevealBudget};
+use mpc_algebra::cost;
+use mpc_algebra::share::blame;
+use mpc_algebra::{
+    AdditivePairingShare, GszPairingShare, MpcField, PairingShare, Reveal, SpdzPairingShare,
+};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+type E = Bls12_377;
+
+/// `share`, but with one added to its value and not to its MAC.
+fn corrupt<S: CanonicalSerialize + CanonicalDeserialize>(share: &S) -> S {
+    let mut bytes = Vec::new();
+    share.serialize(&mut bytes).unwrap();
+    let (val, mac) = <(Fr, Fr)>::deserialize(&bytes[..]).unwrap();
+    let mut corrupted = Vec::new();
+    (val + Fr::one(), mac).serialize(&mut corrupted).unwrap();
+    S::deserialize(&corrupted[..]).unwrap()
+}
+
+/// `a (b + k) = c`, with the instance `k`, then `c`.
+#[derive(Clone)]
+struct OffsetProduct<F: Field> {
+    k: Option<F>,
+    a: Option<F>,
+    b: Option<F>,
+}
+
+impl<F: Field> ConstraintSynthesizer<F> for OffsetProduct<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let missing = || SynthesisError::AssignmentMissing;
+        let k = cs.new_input_variable(|| self.k.ok_or_else(missing))?;
+        let c = cs.new_input_variable(|| {
+            Ok(self.a.ok_or_else(missing)?
+                * (self.b.ok_or_else(missing)? + self.k.ok_or_else(missing)?))
+        })?;
+        let a = cs.new_witness_variable(|| self.a.ok_or_else(missing))?;
+        let b = cs.new_witness_variable(|| self.b.ok_or_else(missing))?;
+        cs.enforce_constraint(lc!() + a, lc!() + b + k, lc!() + c)
+    }
+}
+
+/// Prove with shares `S`, and check the proof against the inputs: `k` public, `c` shared.
+fn prove_and_verify<S: PairingShare<E>>() -> (bool, bool) {
+    S::FrShare::init_protocol();
+    let rng = &mut ark_std::test_rng();
+    let blank = OffsetProduct {
+        k: None,
+        a: None,
+        b: None,
+    };
+    let pk = setup::<E, _, _>(blank, rng).unwrap();
+    let k = Fr::from(7u8);
+    let a = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+    let b = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+    let c = a * (b + MpcField::Public(k));
+    let circuit = OffsetProduct {
+        k: Some(MpcField::Public(k)),
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = prove_collaborative(
+        &share_proving_key::<E, S>(&pk),
+        circuit,
+        rng,
+        ProveOptions::default(),
+    )
+    .unwrap()
+    .0;
+    let proof = reveal_proof::<E, S>(proof);
+
+    let inputs = PublicInputs::<E, S>::new().public(k).shared(c);
+    assert_eq!(inputs.len(), 2);
+    assert!(!inputs.is_shared(0) && inputs.is_shared(1));
+    let verified = inputs.verify(&pk.vk, &proof).unwrap();
+    // in the wrong order, they do not verify
+    let swapped = PublicInputs::<E, S>::new().shared(c).public(k);
+    let wrong = swapped.verify(&pk.vk, &proof).unwrap();
+    S::FrShare::deinit_protocol();
+    (verified, wrong)
+}
+
+#[test]
+fn mixed_inputs() {
+    for outcome in run_parties(2, prove_and_verify::<AdditivePairingShare<E>>) {
+        assert_eq!(outcome, (true, false));
+    }
+    for outcome in run_parties(2, prove_and_verify::<SpdzPairingShare<E>>) {
+        assert_eq!(outcome, (true, false));
+    }
+    for outcome in run_parties(3, prove_and_verify::<GszPairingShare<E>>) {
+        assert_eq!(outcome, (true, false));
+    }
+}
+
+#[test]
+fn catches_a_cheater_before_verifying() {
+    type S = SpdzPairingShare<E>;
+    type FrShare = <S as PairingShare<E>>::FrShare;
+    let outcomes = run_parties(2, || {
+        blame::set_identifiable_abort(true);
+        let rng = &mut ark_std::test_rng();
+        let c = match MpcField::<Fr, FrShare>::king_share(Fr::rand(rng), rng) {
+            MpcField::Shared(s) if Net::party_id() == 1 => MpcField::Shared(corrupt(&s)),
+            c => c,
+        };
+        let revealed = PublicInputs::<E, S>::new()
+            .public(Fr::from(1u8))
+            .shared(c)
+            .reveal();
+        blame::set_identifiable_abort(false);
+        revealed
+    });
+    for outcome in outcomes {
+        assert_eq!(outcome.unwrap_err().culprits, vec![1]);
+    }
+}
+
+#[test]
+fn charges_the_shared_inputs() {
+    type S = AdditivePairingShare<E>;
+    type FrShare = <S as PairingShare<E>>::FrShare;
+    let counts = run_parties(2, || {
+        let rng = &mut ark_std::test_rng();
+        budget::set_reveal_budget(Some(RevealBudget::abort_after(10)));
+        let shared = (0..3).map(|_| MpcField::<Fr, FrShare>::king_share(Fr::rand(rng), rng));
+        PublicInputs::<E, S>::new()
+            .public(Fr::from(1u8))
+            .shared_all(shared)
+            .reveal()
+            .unwrap();
+        (budget::revealed(), cost::costs().openings)
+    });
+    // the public input is free
+    assert_eq!(counts[0], (3, 3));
+}
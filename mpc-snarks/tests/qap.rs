@@ -0,0 +1,126 @@
+//! The QAP witness map over shared assignments, with every party run in-process, against the
+//! plain arkworks reduction.
+use ark_bls12_377::Fr;
+use ark_ff::{Field, UniformRand};
+use ark_groth16::r1cs_to_qap::R1CStoQAP;
+use ark_poly::polynomial::univariate::DensePolynomial;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, Polynomial, UVPolynomial};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, OptimizationGoal,
+};
+use mpc_algebra::{
+    AdditiveFieldShare, FieldShare, GszFieldShare, MpcField, Reveal, SpdzFieldShare,
+};
+use mpc_snarks::qap::{self, Qap, QapDomain};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_test_utils::run_parties;
+
+/// `circuit`'s constraint system, synthesized as the prover does.
+fn synthesize<F: Field, C: ConstraintSynthesizer<F>>(circuit: C) -> ConstraintSystemRef<F> {
+    let cs = ConstraintSystem::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::Constraints);
+    circuit.generate_constraints(cs.clone()).unwrap();
+    cs.finalize();
+    cs
+}
+
+fn plain(a: Fr, b: Fr) -> Qap<Fr> {
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let qap =
+        qap::witness_map::<Fr, GeneralEvaluationDomain<Fr>>(synthesize(circuit.clone())).unwrap();
+    let h = R1CStoQAP::witness_map::<Fr, GeneralEvaluationDomain<Fr>>(synthesize(circuit.clone()));
+    assert_eq!(qap.h, h.unwrap());
+    let domain = QapDomain::<Fr, GeneralEvaluationDomain<Fr>>::new(qap.h.len()).unwrap();
+    assert_eq!(
+        qap::quotient_in(&domain, synthesize(circuit)).unwrap(),
+        qap.h
+    );
+    qap
+}
+
+#[test]
+fn plain_polynomials_satisfy_the_qap() {
+    let rng = &mut ark_std::test_rng();
+    let (a, b) = (Fr::rand(rng), Fr::rand(rng));
+    let qap = plain(a, b);
+    let domain = GeneralEvaluationDomain::<Fr>::new(qap.a.len()).unwrap();
+    assert_eq!(domain.size(), qap.a.len());
+    // on the domain, the six constraints each multiply a by b, and a is followed by the instance
+    // variables, 1 and ab
+    let on_domain = |p: &[Fr]| domain.fft(p);
+    assert_eq!(
+        &on_domain(&qap.a)[..8],
+        &[a, a, a, a, a, a, Fr::from(1u8), a * b]
+    );
+    assert_eq!(&on_domain(&qap.b)[..6], &[b; 6]);
+    assert_eq!(&on_domain(&qap.c)[..6], &[a * b; 6]);
+
+    let poly = |p: &[Fr]| DensePolynomial::from_coefficients_slice(p);
+    let x = Fr::rand(rng);
+    let (pa, pb, pc, ph) = (poly(&qap.a), poly(&qap.b), poly(&qap.c), poly(&qap.h));
+    assert_eq!(
+        pa.evaluate(&x) * pb.evaluate(&x) - pc.evaluate(&x),
+        ph.evaluate(&x) * domain.evaluate_vanishing_polynomial(x)
+    );
+}
+
+fn shared<S: FieldShare<Fr>>(n: usize) {
+    let rng = &mut ark_std::test_rng();
+    let (a, b) = (Fr::rand(rng), Fr::rand(rng));
+    let expected = plain(a, b);
+    let outs = run_parties(n, || {
+        type M<S> = MpcField<Fr, S>;
+        let rng = &mut ark_std::test_rng();
+        let circuit = MySillyCircuit {
+            a: Some(M::<S>::king_share(a, rng)),
+            b: Some(M::<S>::king_share(b, rng)),
+        };
+        let qap =
+            qap::witness_map::<M<S>, GeneralEvaluationDomain<M<S>>>(synthesize(circuit)).unwrap();
+        let h = qap::quotient::<M<S>, GeneralEvaluationDomain<M<S>>>(synthesize(MySillyCircuit {
+            a: Some(M::<S>::king_share(a, rng)),
+            b: Some(M::<S>::king_share(b, rng)),
+        }))
+        .unwrap();
+        // through the cached coset powers too, which the `parallel` feature scales across threads
+        let domain = QapDomain::<M<S>, GeneralEvaluationDomain<M<S>>>::new(h.len()).unwrap();
+        let cached = qap::quotient_in(
+            &domain,
+            synthesize(MySillyCircuit {
+                a: Some(M::<S>::king_share(a, rng)),
+                b: Some(M::<S>::king_share(b, rng)),
+            }),
+        )
+        .unwrap();
+        assert_eq!(cached.reveal(), h.clone().reveal());
+        let qap = Qap {
+            a: qap.a.reveal(),
+            b: qap.b.reveal(),
+            c: qap.c.reveal(),
+            h: qap.h.reveal(),
+        };
+        (qap, h.reveal())
+    });
+    for (qap, h) in outs {
+        assert_eq!(qap, expected);
+        assert_eq!(h, expected.h);
+    }
+}
+
+#[test]
+fn additive() {
+    shared::<AdditiveFieldShare<Fr>>(2);
+}
+
+#[test]
+fn spdz() {
+    shared::<SpdzFieldShare<Fr>>(2);
+}
+
+#[test]
+fn gsz() {
+    shared::<GszFieldShare<Fr>>(3);
+}
@@ -0,0 +1,103 @@
+//! The Groth16 verifier circuits for MNT4-753 and BLS12-377 proofs, and collaborative proofs of
+//! circuits whose whole witness one party holds.
+//!
+//! Proving a verifier circuit itself, over MNT6-753 or BW6-761, takes minutes even in a release
+//! build: see `examples/recursion.rs`.
+use ark_bls12_377::{Bls12_377, Fr as BlsFr};
+use ark_ec::PairingEngine;
+use ark_ff::{One, UniformRand};
+use ark_groth16::{create_random_proof, Proof, VerifyingKey};
+use ark_mnt4_753::{Fr, MNT4_753};
+use ark_mnt6_753::Fr as OuterFr;
+use ark_r1cs_std::pairing::{bls12, mnt4, PairingVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use mpc_algebra::{AdditivePairingShare, GszPairingShare, PairingShare, SpdzPairingShare};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_snarks::recursion::{pack_inputs, SharedAssignment, VerifierCircuit};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+/// A proof over `E` that the public `c` is a product, with its verifying key and `c`.
+fn inner_proof<E: PairingEngine>() -> (VerifyingKey<E>, Proof<E>, E::Fr) {
+    let rng = &mut ark_std::test_rng();
+    let pk = setup::<E, _, _>(MySillyCircuit::<E::Fr> { a: None, b: None }, rng).unwrap();
+    let (a, b) = (E::Fr::rand(rng), E::Fr::rand(rng));
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = create_random_proof(circuit, &pk, rng).unwrap();
+    (pk.vk, proof, a * b)
+}
+
+/// Whether the verifier circuit for `vk` is satisfied by `proof` of `input`.
+fn satisfied<E: PairingEngine, PV: PairingVar<E>>(
+    vk: &VerifyingKey<E>,
+    proof: &Proof<E>,
+    input: E::Fr,
+) -> bool {
+    let cs = ConstraintSystem::<E::Fq>::new_ref();
+    VerifierCircuit::<E, PV>::new(vk.clone())
+        .with_proof(proof.clone(), vec![input])
+        .generate_constraints(cs.clone())
+        .unwrap();
+    let inputs: Vec<E::Fq> = pack_inputs(&[input]);
+    assert_eq!(cs.borrow().unwrap().instance_assignment[1..], inputs[..]);
+    cs.is_satisfied().unwrap()
+}
+
+#[test]
+fn verifier_circuit_accepts_only_the_proven_inputs() {
+    let (vk, proof, c) = inner_proof::<MNT4_753>();
+    type PV = mnt4::PairingVar<ark_mnt4_753::Parameters>;
+    assert!(satisfied::<_, PV>(&vk, &proof, c));
+    assert!(!satisfied::<_, PV>(&vk, &proof, c + Fr::one()));
+
+    // 753 bits take two elements of a 753-bit field, the second with just the top bit
+    let inputs: Vec<OuterFr> = pack_inputs(&[-Fr::one()]);
+    assert_eq!(inputs.len(), 2);
+    assert_eq!(inputs[1], OuterFr::one());
+}
+
+/// The BLS12-377 verifier, over the scalar field of BW6-761, which wraps proofs over BLS12-377.
+#[test]
+fn bls12_377_verifier_circuit() {
+    let (vk, proof, c) = inner_proof::<Bls12_377>();
+    type PV = bls12::PairingVar<ark_bls12_377::Parameters>;
+    assert!(satisfied::<_, PV>(&vk, &proof, c));
+    assert!(!satisfied::<_, PV>(&vk, &proof, c + BlsFr::one()));
+    // 253 bits fit in one element
+    assert_eq!(pack_inputs::<_, ark_bw6_761::Fr>(&[c]).len(), 1);
+}
+
+/// Party 1 knows `a` and `b`, and the parties prove together that `ab = c`.
+fn prove_for_party_one<S: PairingShare<Bls12_377>>(n: usize) {
+    let rng = &mut ark_std::test_rng();
+    let pk = setup::<Bls12_377, _, _>(MySillyCircuit::<BlsFr> { a: None, b: None }, rng).unwrap();
+    let (a, b) = (BlsFr::rand(rng), BlsFr::rand(rng));
+    let verified = run_parties(n, || {
+        let rng = &mut ark_std::test_rng();
+        let circuit = match Net::party_id() {
+            1 => MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            _ => MySillyCircuit { a: None, b: None },
+        };
+        let shared = SharedAssignment::share(circuit, 1, vec![a * b], rng).unwrap();
+        assert_eq!(shared.num_constraints(), 6);
+        let mpc_pk = share_proving_key::<Bls12_377, S>(&pk);
+        let proof = prove_collaborative(&mpc_pk, shared, rng, ProveOptions::default()).unwrap().0;
+        let proof = reveal_proof::<Bls12_377, S>(proof);
+        verify(&pk.vk, &proof, &[a * b]).unwrap() && !verify(&pk.vk, &proof, &[a]).unwrap()
+    });
+    assert_eq!(verified, vec![true; n]);
+}
+
+#[test]
+fn proves_a_witness_held_by_one_party() {
+    prove_for_party_one::<AdditivePairingShare<Bls12_377>>(2);
+    prove_for_party_one::<SpdzPairingShare<Bls12_377>>(2);
+    prove_for_party_one::<GszPairingShare<Bls12_377>>(3);
+}
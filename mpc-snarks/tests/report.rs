@@ -0,0 +1,78 @@
+//! Reports of what a collaborative proof cost each party.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::UniformRand;
+use mpc_algebra::{
+    AdditivePairingShare, GszPairingShare, MpcField, PairingShare, Reveal, SpdzPairingShare,
+};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+type E = Bls12_377;
+
+const PHASES: [&str; 8] = [
+    "randomness",
+    "synthesis",
+    "witness map",
+    "h",
+    "l",
+    "a",
+    "b_g1",
+    "b_g2",
+];
+
+/// Prove, and check the report's phases, and that the proof communicated, and drew triples if
+/// `triples`.
+fn report<S: PairingShare<E>>(n: usize, triples: bool) {
+    let reports = run_parties(n, || {
+        S::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let a = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let b = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let c = (a * b).reveal();
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let (proof, report) =
+            prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default()).unwrap();
+        assert!(verify(&pk.vk, &reveal_proof::<E, S>(proof), &[c]).unwrap());
+        S::FrShare::deinit_protocol();
+        report
+    });
+    for report in reports {
+        let phases: Vec<_> = report
+            .phase_timings
+            .iter()
+            .map(|(p, _)| p.as_str())
+            .collect();
+        assert_eq!(phases, PHASES);
+        assert!(report.bytes_sent > 0 && report.bytes_received > 0);
+        assert_eq!(report.triples_consumed > 0, triples);
+        // the prover opens nothing; the proof is opened after
+        assert_eq!(report.openings, 0);
+        let json = report.to_json();
+        assert!(json.starts_with("{\"phase_timings\":[{\"phase\":\"randomness\",\"seconds\":"));
+        assert!(json.ends_with(&format!(
+            "\"bytes_sent\":{},\"bytes_received\":{},\"triples_consumed\":{},\"openings\":0}}",
+            report.bytes_sent, report.bytes_received, report.triples_consumed
+        )));
+    }
+}
+
+#[test]
+fn additive() {
+    report::<AdditivePairingShare<E>>(2, true);
+}
+
+#[test]
+fn spdz() {
+    report::<SpdzPairingShare<E>>(2, true);
+}
+
+#[test]
+fn gsz20() {
+    report::<GszPairingShare<E>>(3, false);
+}
@@ -0,0 +1,100 @@
+//! `proof run-local`, which runs each party of a proof in a process of its own.
+use std::process::Command;
+
+fn run_local(args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_proof"))
+        .args(&["-c", "squaring", "--computation-size", "4", "run-local"])
+        .args(args)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    (output.status.success(), stdout)
+}
+
+#[test]
+fn proves_with_every_party() {
+    let (ok, stdout) = run_local(&["--parties", "3", "--alg", "spdz", "--", "--batch"]);
+    assert!(ok, "{}", stdout);
+    for party in 0..3 {
+        assert!(stdout.contains(&format!("[party {}] Stats:", party)));
+        assert!(stdout.contains(&format!("  party {}: finished in", party)));
+    }
+    assert!(stdout.contains("3 parties, spdz shares:"));
+    assert!(stdout.contains("Slowest timed section: "));
+}
+
+#[test]
+fn proves_with_shares_chosen_at_run_time() {
+    for (alg, parties) in &[("hbc", "2"), ("gsz", "3")] {
+        let (ok, stdout) = run_local(&["--parties", parties, "--alg", alg, "--", "--dyn-shares"]);
+        assert!(ok, "{}", stdout);
+        assert!(!stdout.contains(": failed"), "{}", stdout);
+    }
+}
+
+#[test]
+fn fails_with_a_party() {
+    let (ok, stdout) = run_local(&["--alg", "hbc", "--", "--no-such-option"]);
+    assert!(!ok);
+    // whichever fails first, the other is killed, rather than left waiting for it
+    assert!(stdout.contains(": failed (exit status: 1)"), "{}", stdout);
+    assert!(!stdout.contains(": finished"), "{}", stdout);
+}
+
+#[test]
+fn resumes_only_with_the_seed_of_the_checkpoint() {
+    let dir = std::env::temp_dir().join(format!("run-local-seed-{}", std::process::id()));
+    let dir = dir.to_str().unwrap();
+    let prove = |seed: Option<&str>, resume: bool| {
+        let mut args = vec!["--parties", "2", "--alg", "hbc", "--", "--checkpoint", dir];
+        if resume {
+            args.push("--resume");
+        }
+        let mut command = Command::new(env!("CARGO_BIN_EXE_proof"));
+        command.args(&["-c", "squaring", "--computation-size", "4"]);
+        if let Some(seed) = seed {
+            command.args(&["--seed", seed]);
+        }
+        let output = command.arg("run-local").args(&args).output().unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        (output.status.success(), stdout + &stderr)
+    };
+    let (ok, out) = prove(Some("7"), false);
+    assert!(ok, "{}", out);
+    let (ok, out) = prove(None, true);
+    assert!(!ok);
+    assert!(out.contains("--resume needs the --seed"), "{}", out);
+    let (ok, out) = prove(Some("8"), true);
+    assert!(!ok);
+    assert!(out.contains("but \"groth16 squaring 4 seed 8\" was expected"), "{}", out);
+    let (ok, out) = prove(Some("7"), true);
+    assert!(ok, "{}", out);
+    assert!(out.contains("Resuming after"), "{}", out);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn refuses_groth16_flags_with_other_proof_systems() {
+    let dir = std::env::temp_dir().join(format!("refused-{}", std::process::id()));
+    let dir = dir.to_str().unwrap();
+    for flags in &[
+        &["--report", "json"][..],
+        &["--checkpoint", dir, "--resume"],
+        &["--debug-export", dir],
+    ] {
+        for system in &["marlin", "plonk"] {
+            let output = Command::new(env!("CARGO_BIN_EXE_proof"))
+                .args(["-p", system, "mpc", "--hosts", "data/2", "--party", "0"])
+                .args(["--alg", "hbc"])
+                .args(*flags)
+                .output()
+                .unwrap();
+            let stderr = String::from_utf8(output.stderr).unwrap();
+            assert_eq!(output.status.code(), Some(1), "{}", stderr);
+            let expected = format!("{} is only supported with -p groth16", flags[0]);
+            assert!(stderr.contains(&expected), "{}", stderr);
+            assert!(stderr.contains(&format!("not -p {}", system)), "{}", stderr);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Schnorr signature verification, in plain circuits and in collaborative proofs.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ed_on_bls12_377::{EdwardsParameters, Fr as EdFr};
+use ark_ff::{One, UniformRand};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use mpc_algebra::{AdditivePairingShare, PairingShare, Reveal, SpdzPairingShare};
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use mpc_snarks::schnorr::{self, public_key, sign, SchnorrCircuit, SchnorrInstance};
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+type P = EdwardsParameters;
+type E = Bls12_377;
+
+/// A signed message, and its instance.
+fn signed(key_is_public: bool) -> (EdFr, Vec<Fr>, SchnorrInstance<P>) {
+    let rng = &mut ark_std::test_rng();
+    let secret = EdFr::rand(rng);
+    let message: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+    let signature = sign::<P, _>(&secret, &message, rng);
+    let instance = SchnorrInstance::new(&public_key(&secret), key_is_public, &message, signature);
+    (secret, message, instance)
+}
+
+fn satisfied(circuit: SchnorrCircuit<P>) -> bool {
+    let cs = ConstraintSystem::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    cs.is_satisfied().unwrap()
+}
+
+#[test]
+fn signatures() {
+    let (secret, mut message, instance) = signed(true);
+    let key = public_key::<P>(&secret);
+    assert!(schnorr::verify(&key, &message, &instance.signature));
+    message[0] += Fr::one();
+    assert!(!schnorr::verify(&key, &message, &instance.signature));
+}
+
+#[test]
+fn circuit() {
+    for &key_is_public in &[true, false] {
+        let (secret, message, instance) = signed(key_is_public);
+        let key = public_key::<P>(&secret);
+        assert!(satisfied(SchnorrCircuit::new(
+            &instance,
+            Some((&key, &message))
+        )));
+
+        // another message, with the instance's hash
+        let mut other = message.clone();
+        other[1] += Fr::one();
+        assert!(!satisfied(SchnorrCircuit::new(
+            &instance,
+            Some((&key, &other))
+        )));
+
+        // a signature on another message
+        let mut forged = instance.clone();
+        forged.signature.s += EdFr::one();
+        assert!(!satisfied(SchnorrCircuit::new(
+            &forged,
+            Some((&key, &message))
+        )));
+
+        // bits of another challenge
+        let mut circuit = SchnorrCircuit::new(&instance, Some((&key, &message)));
+        circuit.challenge_bits[0] = circuit.challenge_bits[0].map(|b| Fr::one() - b);
+        assert!(!satisfied(circuit));
+    }
+}
+
+/// Party 1 holds the signed message, and the parties prove that it is signed.
+fn prove_signed<S: PairingShare<E>>(key_is_public: bool) {
+    let verified = run_parties(2, move || {
+        S::FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let (secret, message, instance) = signed(key_is_public);
+        let pk = setup::<E, _, _>(SchnorrCircuit::new(&instance, None), rng).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+
+        let key = public_key::<P>(&secret);
+        let witness = if Net::party_id() == 1 {
+            Some((&key, &message[..]))
+        } else {
+            None
+        };
+        let circuit = SchnorrCircuit::new(&instance, witness).share::<S::FrShare, _>(1, rng);
+        let proof = reveal_proof::<E, S>(
+            prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default()).unwrap().0,
+        );
+        S::FrShare::deinit_protocol();
+        let inputs = instance.public_inputs();
+        let mut wrong = inputs.clone();
+        *wrong.last_mut().unwrap() += Fr::one();
+        verify(&pk.vk, &proof, &inputs).unwrap() && !verify(&pk.vk, &proof, &wrong).unwrap()
+    });
+    assert_eq!(verified, vec![true; 2]);
+}
+
+#[test]
+fn additive() {
+    prove_signed::<AdditivePairingShare<E>>(true);
+    prove_signed::<AdditivePairingShare<E>>(false);
+}
+
+#[test]
+fn spdz() {
+    prove_signed::<SpdzPairingShare<E>>(false);
+}
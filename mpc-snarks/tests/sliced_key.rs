@@ -0,0 +1,102 @@
+//! Proving keys sliced into a file per query.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::UniformRand;
+use ark_groth16::ProvingKey;
+use mpc_algebra::{AdditivePairingShare, MpcField, PairingShare, Reveal, SpdzPairingShare};
+use mpc_snarks::groth::sliced_key::Query;
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+
+use std::fs;
+use std::path::PathBuf;
+
+type E = Bls12_377;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mpc-sliced-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn key() -> ProvingKey<E> {
+    setup::<E, _, _>(
+        MySillyCircuit { a: None, b: None },
+        &mut ark_std::test_rng(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn slices() {
+    let dir = temp_dir("slices");
+    let pk = key();
+    SlicedKey::write(&pk, &dir).unwrap();
+    let key = SlicedKey::<E>::open(&dir).unwrap();
+    assert_eq!(key.head().vk, pk.vk);
+    assert_eq!(key.head().a_query, pk.a_query[..1]);
+    assert_eq!(key.load_g1(Query::A).unwrap(), pk.a_query[1..]);
+    assert_eq!(key.load_g1(Query::BG1).unwrap(), pk.b_g1_query[1..]);
+    assert_eq!(key.load_b_g2().unwrap(), pk.b_g2_query[1..]);
+    assert_eq!(key.load_g1(Query::H).unwrap(), pk.h_query);
+    assert_eq!(key.load_g1(Query::L).unwrap(), pk.l_query);
+    assert_eq!(key.len(Query::H), pk.h_query.len());
+
+    // a query of another key
+    let other_dir = temp_dir("slices-other");
+    let mut other = pk.clone();
+    other.delta_g1 = other.beta_g1;
+    SlicedKey::write(&other, &other_dir).unwrap();
+    fs::copy(other_dir.join("h_query"), dir.join("h_query")).unwrap();
+    assert!(matches!(
+        SlicedKey::<E>::open(&dir),
+        Err(SliceError::WrongKey(Query::H))
+    ));
+    fs::remove_file(dir.join("h_query")).unwrap();
+    assert!(matches!(SlicedKey::<E>::open(&dir), Err(SliceError::Io(_))));
+    fs::remove_dir_all(&dir).unwrap();
+    fs::remove_dir_all(&other_dir).unwrap();
+}
+
+/// Proving with a sliced key gives the same proof as with the whole key.
+fn prove_sliced<S: PairingShare<E>>(n: usize) {
+    let dir = temp_dir("prove");
+    SlicedKey::write(&key(), &dir).unwrap();
+    let ok = run_parties(n, || {
+        let pk = key();
+        let sliced = SlicedKey::<E>::open(&dir).unwrap();
+        let rng = &mut ark_std::test_rng();
+        let a = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let b = MpcField::<Fr, S::FrShare>::king_share(Fr::rand(rng), rng);
+        let c = (a * b).reveal();
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let whole = prove_collaborative(
+            &share_proving_key::<E, S>(&pk),
+            circuit.clone(),
+            &mut ark_std::test_rng(),
+            ProveOptions::default(),
+        );
+        let sliced = prove_collaborative(
+            &share_proving_key::<E, S>(sliced.head()),
+            circuit,
+            &mut ark_std::test_rng(),
+            ProveOptions::default().sliced(&sliced),
+        );
+        let (whole, sliced) = (
+            reveal_proof::<E, S>(whole.unwrap().0),
+            reveal_proof::<E, S>(sliced.unwrap().0),
+        );
+        whole == sliced && verify(&pk.vk, &sliced, &[c]).unwrap()
+    });
+    assert_eq!(ok, vec![true; n]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn proves() {
+    prove_sliced::<AdditivePairingShare<E>>(2);
+    prove_sliced::<SpdzPairingShare<E>>(2);
+}
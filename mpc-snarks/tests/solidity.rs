@@ -0,0 +1,126 @@
+//! A Solidity verifier for revealed proofs, checked by doing what its precompile calls do.
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_ff::{BigInteger256, PrimeField, UniformRand, Zero};
+use mpc_algebra::{AdditivePairingShare, MpcField, PairingShare, Reveal};
+use mpc_snarks::silly::MySillyCircuit;
+use mpc_snarks::*;
+use mpc_test_utils::run_parties;
+use sha3::{Digest, Keccak256};
+
+use std::convert::TryInto;
+
+type E = Bn254;
+type S = AdditivePairingShare<E>;
+type FrShare = <S as PairingShare<E>>::FrShare;
+
+/// A big-endian `uint256` word.
+fn word(bytes: &[u8]) -> BigInteger256 {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.rchunks(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    BigInteger256(limbs)
+}
+
+/// The value of the contract's constant `name`.
+fn constant(contract: &str, name: &str) -> Fq {
+    let line = format!("uint256 constant {} = 0x", name);
+    let at = contract.find(&line).unwrap() + line.len();
+    let hex = &contract[at..at + 64];
+    let bytes: Vec<u8> = (0..32)
+        .map(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap())
+        .collect();
+    Fq::from_repr(word(&bytes)).unwrap()
+}
+
+fn g1(x: Fq, y: Fq) -> G1Affine {
+    if x.is_zero() && y.is_zero() {
+        return G1Affine::zero();
+    }
+    let p = G1Affine::new(x, y, false);
+    assert!(p.is_on_curve() && p.is_in_correct_subgroup_assuming_on_curve());
+    p
+}
+
+/// A G2 point from its coordinates, imaginary parts first.
+fn g2(x1: Fq, x0: Fq, y1: Fq, y0: Fq) -> G2Affine {
+    let p = G2Affine::new(Fq2::new(x0, x1), Fq2::new(y0, y1), false);
+    assert!(p.is_on_curve() && p.is_in_correct_subgroup_assuming_on_curve());
+    p
+}
+
+/// What the contract's `verifyProof` computes from `calldata`, with its precompiles.
+fn evm_verify(contract: &str, calldata: &[u8], n_public: usize) -> bool {
+    let signature = format!(
+        "verifyProof(uint256[2],uint256[2][2],uint256[2],uint256[{}])",
+        n_public
+    );
+    assert_eq!(calldata[..4], Keccak256::digest(signature.as_bytes())[..4]);
+    let words: Vec<Fq> = calldata[4..4 + 8 * 32]
+        .chunks(32)
+        .map(|w| Fq::from_repr(word(w)).unwrap())
+        .collect();
+    let inputs: Vec<Fr> = calldata[4 + 8 * 32..]
+        .chunks(32)
+        .map(|w| Fr::from_repr(word(w)).unwrap())
+        .collect();
+    assert_eq!(inputs.len(), n_public);
+    let c = |name: &str| constant(contract, name);
+    let c1 = |name: &str| g1(c(&format!("{}_X", name)), c(&format!("{}_Y", name)));
+    let c2 = |name: &str| {
+        let c = |part: &str| c(&format!("{}_{}", name, part));
+        g2(c("X1"), c("X0"), c("Y1"), c("Y0"))
+    };
+
+    let a = g1(words[0], words[1]);
+    let b = g2(words[2], words[3], words[4], words[5]);
+    let c = g1(words[6], words[7]);
+    let mut x = c1("IC0").into_projective();
+    for (i, input) in inputs.iter().enumerate() {
+        x += c1(&format!("IC{}", i + 1)).mul(input.into_repr());
+    }
+    E::pairing(a, b)
+        == E::pairing(c1("ALPHA"), c2("BETA"))
+            * E::pairing(x, c2("GAMMA"))
+            * E::pairing(c, c2("DELTA"))
+}
+
+#[test]
+fn verifies_collaborative_proofs() {
+    let outs = run_parties(2, || {
+        FrShare::init_protocol();
+        let rng = &mut ark_std::test_rng();
+        let pk = setup::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+        let mpc_pk = share_proving_key::<E, S>(&pk);
+        let a = MpcField::<Fr, FrShare>::king_share(Fr::rand(rng), rng);
+        let b = MpcField::<Fr, FrShare>::king_share(Fr::rand(rng), rng);
+        let c = (a * b).reveal();
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let proof = reveal_proof::<E, S>(
+            prove_collaborative(&mpc_pk, circuit, rng, ProveOptions::default()).unwrap().0,
+        );
+        FrShare::deinit_protocol();
+        (pk.vk, proof, c)
+    });
+    let (vk, proof, c) = &outs[0];
+    let contract = solidity_verifier(vk);
+    assert!(contract.contains("uint256[1] calldata input"));
+    for (_, proof2, c2) in &outs {
+        assert_eq!(
+            solidity_calldata(proof2, &[*c2]),
+            solidity_calldata(proof, &[*c])
+        );
+    }
+
+    let calldata = solidity_calldata(proof, &[*c]);
+    assert_eq!(calldata.len(), 4 + 9 * 32);
+    // the selector of snarkjs's verifiers too, for one public input
+    assert_eq!(calldata[..4], [0x43, 0x75, 0x3b, 0x4d]);
+    assert!(evm_verify(&contract, &calldata, 1));
+    let wrong = solidity_calldata(proof, &[*c + Fr::from(1u8)]);
+    assert!(!evm_verify(&contract, &wrong, 1));
+}
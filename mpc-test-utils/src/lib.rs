@@ -0,0 +1,93 @@
+//! Run every party of a protocol in one process, for tests.
+//!
+//! [run_parties] plays `n` parties, each on its own thread and in its own in-memory
+//! [MpcSession], so a test can exercise a protocol end to end under `cargo test`:
+//!
+//! ```
+//! use mpc_net::{MpcMultiNet as Net, MpcNet};
+//! use mpc_test_utils::run_parties;
+//!
+//! let ids = run_parties(3, || {
+//!     let all = Net::broadcast_bytes(&[Net::party_id() as u8]);
+//!     assert_eq!(all, vec![vec![0], vec![1], vec![2]]);
+//!     Net::party_id()
+//! });
+//! assert_eq!(ids, vec![0, 1, 2]);
+//! ```
+//!
+//! Inside the closure, [mpc_net::MpcMultiNet] refers to that party's session, as does anything
+//! built on it. State that a protocol keeps per process, rather than per session, is shared by
+//! all the parties, and breaks this.
+use mpc_net::{MpcMultiNet, MpcSession, NetConfig, NetError};
+
+use std::any::Any;
+use std::panic;
+use std::thread;
+use std::time::Duration;
+
+/// Run `f` as each of `n` parties, connected in memory, and return the parties' results in order.
+///
+/// If any party panics, so does this, once all have finished; the others abort rather than wait on
+/// it. The panic reported is the first that isn't just a party aborting because another one
+/// failed.
+pub fn run_parties<T, F>(n: usize, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn() -> T + Sync,
+{
+    run_parties_with(n, test_config(), f)
+}
+
+/// As [run_parties], with the sessions configured by `config`.
+pub fn run_parties_with<T, F>(n: usize, config: NetConfig, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn() -> T + Sync,
+{
+    assert!(n > 0, "no parties to run");
+    MpcMultiNet::abort_on_panic();
+    let sessions = MpcSession::in_memory(n, config);
+    let f = &f;
+    let results: Vec<thread::Result<T>> = thread::scope(|s| {
+        let handles: Vec<_> = sessions
+            .into_iter()
+            .enumerate()
+            .map(|(i, session)| {
+                // the session moves in, so a party that panics hangs up on the others
+                thread::Builder::new()
+                    .name(format!("party-{}", i))
+                    .spawn_scoped(s, move || session.run(f))
+                    .expect("Failed to spawn party thread")
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join()).collect()
+    });
+    let mut first_abort = None;
+    let mut out = Vec::with_capacity(n);
+    for r in results {
+        match r {
+            Ok(t) => out.push(t),
+            Err(payload) if is_abort(&payload) => {
+                first_abort.get_or_insert(payload);
+            }
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+    if let Some(payload) = first_abort {
+        panic::resume_unwind(payload);
+    }
+    out
+}
+
+/// The default configuration, but with a shorter I/O timeout, so a test that deadlocks fails
+/// rather than hangs.
+pub fn test_config() -> NetConfig {
+    NetConfig {
+        io_timeout: Some(Duration::from_secs(60)),
+        ..NetConfig::default()
+    }
+}
+
+fn is_abort(payload: &Box<dyn Any + Send>) -> bool {
+    payload.is::<NetError>()
+}
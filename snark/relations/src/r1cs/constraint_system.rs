@@ -1,6 +1,6 @@
 #[cfg(feature = "std")]
 use crate::r1cs::ConstraintTrace;
-use crate::r1cs::{LcIndex, LinearCombination, Matrix, SynthesisError, Variable};
+use crate::r1cs::{CsrMatrix, LcIndex, LinearCombination, Matrix, SynthesisError, Variable};
 use ark_ff::Field;
 use ark_std::{
     any::{Any, TypeId},
@@ -110,19 +110,26 @@ pub enum OptimizationGoal {
 impl<F: Field> ConstraintSystem<F> {
     #[inline]
     fn make_row(&self, l: &LinearCombination<F>) -> Vec<(F, usize)> {
+        self.row_entries(l).collect()
+    }
+
+    /// The non-zero entries of the row of `l`, with their columns.
+    #[inline]
+    fn row_entries<'a>(
+        &self,
+        l: &'a LinearCombination<F>,
+    ) -> impl Iterator<Item = (F, usize)> + 'a {
         let num_input = self.num_instance_variables;
-        l.0.iter()
-            .filter_map(|(coeff, var)| {
-                if coeff.is_zero() {
-                    None
-                } else {
-                    Some((
-                        *coeff,
-                        var.get_index_unchecked(num_input).expect("no symbolic LCs"),
-                    ))
-                }
-            })
-            .collect()
+        l.0.iter().filter_map(move |(coeff, var)| {
+            if coeff.is_zero() {
+                None
+            } else {
+                Some((
+                    *coeff,
+                    var.get_index_unchecked(num_input).expect("no symbolic LCs"),
+                ))
+            }
+        })
     }
 
     /// Construct an empty `ConstraintSystem`.
@@ -566,6 +573,33 @@ impl<F: Field> ConstraintSystem<F> {
         }
     }
 
+    /// One of the matrices of [ConstraintSystem::to_matrices], packed
+    /// straight into CSR form, without the row-major copies of it and of the
+    /// other two. Like `to_matrices`, this must be called after all symbolic
+    /// LCs have been inlined.
+    pub fn to_csr_matrix(&self, which: ConstraintMatrix) -> Option<CsrMatrix<F>> {
+        if let SynthesisMode::Prove {
+            construct_matrices: false,
+        } = self.mode
+        {
+            return None;
+        }
+        let constraints = match which {
+            ConstraintMatrix::A => &self.a_constraints,
+            ConstraintMatrix::B => &self.b_constraints,
+            ConstraintMatrix::C => &self.c_constraints,
+        };
+        let lc = |index| self.lc_map.get(index).unwrap();
+        let num_non_zero = constraints
+            .iter()
+            .map(|index| self.row_entries(lc(index)).count())
+            .sum();
+        Some(CsrMatrix::from_rows(
+            constraints.iter().map(|index| self.row_entries(lc(index))),
+            num_non_zero,
+        ))
+    }
+
     fn eval_lc(&self, lc: LcIndex) -> Option<F> {
         let lc = self.lc_map.get(&lc)?;
         let mut acc = F::zero();
@@ -575,6 +609,57 @@ impl<F: Field> ConstraintSystem<F> {
         Some(acc)
     }
 
+    /// The matrices `A`, `B` and `C` in CSR form, with the assignment to
+    /// their columns: the instance variables, the witness variables, and
+    /// then one column for each symbolic LC that the constraints still use,
+    /// since they may be checked before their LCs are inlined.
+    fn assigned_csr_matrices(&self) -> crate::r1cs::Result<(Vec<CsrMatrix<F>>, Vec<F>)> {
+        let mut z: Vec<F> = self
+            .instance_assignment
+            .iter()
+            .chain(&self.witness_assignment)
+            .copied()
+            .collect();
+        if z.len() != self.num_instance_variables + self.num_witness_variables {
+            return Err(SynthesisError::AssignmentMissing);
+        }
+        let constraints = [&self.a_constraints, &self.b_constraints, &self.c_constraints];
+        let lc = |index| self.lc_map.get(index).unwrap();
+        let mut symbolic = BTreeMap::new();
+        for index in constraints.iter().flat_map(|c| c.iter()) {
+            for (_, var) in lc(index).iter() {
+                if let Variable::SymbolicLc(i) = var {
+                    if !symbolic.contains_key(i) {
+                        let value = self
+                            .assigned_value(*var)
+                            .ok_or(SynthesisError::AssignmentMissing)?;
+                        symbolic.insert(*i, z.len());
+                        z.push(value);
+                    }
+                }
+            }
+        }
+        let num_input = self.num_instance_variables;
+        let row = |index| {
+            lc(index).iter().filter_map(|(coeff, var)| {
+                let col = match var {
+                    _ if coeff.is_zero() => None,
+                    Variable::SymbolicLc(i) => Some(symbolic[i]),
+                    var => var.get_index_unchecked(num_input),
+                };
+                col.map(|col| (*coeff, col))
+            })
+        };
+        let matrices = constraints
+            .iter()
+            .map(|c| {
+                let num_non_zero = c.iter().map(|index| lc(index).len()).sum();
+                CsrMatrix::from_rows(c.iter().map(row), num_non_zero)
+            })
+            .collect();
+        Ok((matrices, z))
+    }
+
     /// If `self` is satisfied, outputs `Ok(true)`.
     /// If `self` is unsatisfied, outputs `Ok(false)`.
     /// If `self.is_in_setup_mode()`, outputs `Err(())`.
@@ -586,41 +671,35 @@ impl<F: Field> ConstraintSystem<F> {
     /// If `self` is unsatisfied, outputs `Some(i)`, where `i` is the index of
     /// the first unsatisfied constraint. If `self.is_in_setup_mode()`, outputs
     /// `Err(())`.
+    ///
+    /// The products `Az`, `Bz` and `Cz` are computed with [CsrMatrix]'s
+    /// kernel, which the `parallel` feature runs on rayon's pool.
     pub fn which_is_unsatisfied(&self) -> crate::r1cs::Result<Option<String>> {
         if self.is_in_setup_mode() {
-            Err(SynthesisError::AssignmentMissing)
-        } else {
-            for i in 0..self.num_constraints {
-                let a = self
-                    .eval_lc(self.a_constraints[i])
-                    .ok_or(SynthesisError::AssignmentMissing)?;
-                let b = self
-                    .eval_lc(self.b_constraints[i])
-                    .ok_or(SynthesisError::AssignmentMissing)?;
-                let c = self
-                    .eval_lc(self.c_constraints[i])
-                    .ok_or(SynthesisError::AssignmentMissing)?;
-                if a * b != c {
-                    let trace;
-                    #[cfg(feature = "std")]
-                    {
-                        trace = self.constraint_traces[i].as_ref().map_or_else(
-                            || {
-                                eprintln!("Constraint trace requires enabling `ConstraintLayer`");
-                                format!("{}", i)
-                            },
-                            |t| format!("{}", t),
-                        );
-                    }
-                    #[cfg(not(feature = "std"))]
-                    {
-                        trace = format!("{}", i);
-                    }
-                    return Ok(Some(trace));
-                }
-            }
-            Ok(None)
+            return Err(SynthesisError::AssignmentMissing);
         }
+        let (matrices, z) = self.assigned_csr_matrices()?;
+        let values: Vec<Vec<F>> = matrices.iter().map(|m| m.mul_vector(&z)).collect();
+        let (a, b, c) = (&values[0], &values[1], &values[2]);
+        let unsatisfied = (0..a.len()).find(|&i| a[i] * b[i] != c[i]);
+        Ok(unsatisfied.map(|i| {
+            let trace;
+            #[cfg(feature = "std")]
+            {
+                trace = self.constraint_traces[i].as_ref().map_or_else(
+                    || {
+                        eprintln!("Constraint trace requires enabling `ConstraintLayer`");
+                        format!("{}", i)
+                    },
+                    |t| format!("{}", t),
+                );
+            }
+            #[cfg(not(feature = "std"))]
+            {
+                trace = format!("{}", i);
+            }
+            trace
+        }))
     }
 
     /// Obtain the assignment corresponding to the `Variable` `v`.
@@ -674,6 +753,17 @@ pub struct ConstraintMatrices<F: Field> {
     pub c: Matrix<F>,
 }
 
+/// One of the A, B and C matrices of a Rank-One `ConstraintSystem`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintMatrix {
+    /// The A matrix.
+    A,
+    /// The B matrix.
+    B,
+    /// The C matrix.
+    C,
+}
+
 /// A shared reference to a constraint system that can be stored in high level
 /// variables.
 #[derive(Debug, Clone)]
@@ -932,6 +1022,13 @@ impl<F: Field> ConstraintSystemRef<F> {
         self.inner().and_then(|cs| cs.borrow().to_matrices())
     }
 
+    /// One of the matrices of [ConstraintSystemRef::to_matrices], in CSR form;
+    /// see [ConstraintSystem::to_csr_matrix].
+    #[inline]
+    pub fn to_csr_matrix(&self, which: ConstraintMatrix) -> Option<CsrMatrix<F>> {
+        self.inner().and_then(|cs| cs.borrow().to_csr_matrix(which))
+    }
+
     /// If `self` is satisfied, outputs `Ok(true)`.
     /// If `self` is unsatisfied, outputs `Ok(false)`.
     /// If `self.is_in_setup_mode()` or if `self == None`, outputs `Err(())`.
@@ -1041,6 +1138,41 @@ mod tests {
         assert_eq!(matrices.a[2], vec![(Fr::one(), 0)]);
         assert_eq!(matrices.b[2], vec![(two, 1), (two, 2)]);
         assert_eq!(matrices.c[2], vec![(two, 1), (two, 2)]);
+
+        for (which, m) in [
+            (ConstraintMatrix::A, &matrices.a),
+            (ConstraintMatrix::B, &matrices.b),
+            (ConstraintMatrix::C, &matrices.c),
+        ] {
+            assert_eq!(cs.to_csr_matrix(which), Some(CsrMatrix::from_matrix(m)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn satisfaction() -> crate::r1cs::Result<()> {
+        let satisfied = |c_value: u64| -> crate::r1cs::Result<_> {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let a = cs.new_input_variable(|| Ok(Fr::from(2u64)))?;
+            let b = cs.new_witness_variable(|| Ok(Fr::from(3u64)))?;
+            let c = cs.new_witness_variable(|| Ok(Fr::from(c_value)))?;
+            cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + (Fr::from(6u64), Variable::One))?;
+            let d = cs.new_lc(lc!() + a + b)?;
+            let e = cs.new_lc(lc!() + d + d)?;
+            cs.enforce_constraint(lc!() + e, lc!() + Variable::One, lc!() + c)?;
+            // before the LCs are inlined, and after
+            let before = cs.which_is_unsatisfied()?;
+            cs.inline_all_lcs();
+            assert_eq!(cs.which_is_unsatisfied()?, before);
+            Ok(before)
+        };
+        assert_eq!(satisfied(10)?, None);
+        assert_eq!(satisfied(11)?, Some("1".into()));
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+        cs.new_witness_variable(|| Ok(Fr::one()))?;
+        assert!(cs.is_satisfied().is_err());
         Ok(())
     }
 }
@@ -0,0 +1,146 @@
+//! Compressed sparse row (CSR) matrices, for fast matrix-vector products.
+
+use ark_ff::{One, Zero};
+use ark_std::vec::Vec;
+use core::ops::{AddAssign, Mul};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::Matrix;
+
+/// Rows are processed in blocks of this many, so that each task writes a contiguous run of the
+/// output and reads a contiguous run of the matrix.
+const ROW_BLOCK: usize = 256;
+
+/// A sparse matrix in compressed sparse row form.
+///
+/// The entries of row `i` are `coeffs[row_starts[i]..row_starts[i + 1]]`, in the columns
+/// `cols[row_starts[i]..row_starts[i + 1]]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsrMatrix<F> {
+    row_starts: Vec<usize>,
+    cols: Vec<usize>,
+    coeffs: Vec<F>,
+}
+
+impl<F: Clone> CsrMatrix<F> {
+    /// Pack a row-major [Matrix] into CSR form.
+    pub fn from_matrix(m: &Matrix<F>) -> Self {
+        let nnz = m.iter().map(|row| row.len()).sum();
+        Self::from_rows(m.iter().map(|row| row.iter().cloned()), nnz)
+    }
+}
+
+impl<F> CsrMatrix<F> {
+    /// Pack the rows, each given by its entries and their columns, into CSR form, with room for
+    /// `num_non_zero` entries in all.
+    pub fn from_rows<R>(rows: impl IntoIterator<Item = R>, num_non_zero: usize) -> Self
+    where
+        R: IntoIterator<Item = (F, usize)>,
+    {
+        let rows = rows.into_iter();
+        let mut row_starts = Vec::with_capacity(rows.size_hint().0 + 1);
+        let mut cols = Vec::with_capacity(num_non_zero);
+        let mut coeffs = Vec::with_capacity(num_non_zero);
+        row_starts.push(0);
+        for row in rows {
+            for (coeff, col) in row {
+                cols.push(col);
+                coeffs.push(coeff);
+            }
+            row_starts.push(cols.len());
+        }
+        Self {
+            row_starts,
+            cols,
+            coeffs,
+        }
+    }
+
+    /// The number of rows.
+    pub fn num_rows(&self) -> usize {
+        self.row_starts.len() - 1
+    }
+
+    /// The number of non-zero entries.
+    pub fn num_non_zero(&self) -> usize {
+        self.coeffs.len()
+    }
+
+    /// Compute `self * z` into `out[..self.num_rows()]`, leaving the rest of `out` untouched.
+    ///
+    /// Coefficients equal to one are added without multiplying.
+    pub fn mul_vector_into<'a, Z, R>(&'a self, z: &'a [Z], out: &mut [R])
+    where
+        F: One + PartialEq + Send + Sync,
+        Z: Send + Sync + Mul<&'a F, Output = Z> + Copy,
+        R: Zero + Send + Sync + AddAssign<Z>,
+    {
+        let n = self.num_rows();
+        assert!(out.len() >= n, "output shorter than the matrix");
+        cfg_chunks_mut!(out[..n], ROW_BLOCK)
+            .enumerate()
+            .for_each(|(block, out)| {
+                let first_row = block * ROW_BLOCK;
+                for (i, o) in out.iter_mut().enumerate() {
+                    let row = first_row + i;
+                    let (start, end) = (self.row_starts[row], self.row_starts[row + 1]);
+                    let mut sum = R::zero();
+                    for (coeff, col) in self.coeffs[start..end].iter().zip(&self.cols[start..end]) {
+                        let val = z[*col];
+                        if coeff.is_one() {
+                            sum += val;
+                        } else {
+                            sum += val * coeff;
+                        }
+                    }
+                    *o = sum;
+                }
+            });
+    }
+
+    /// Compute `self * z`.
+    pub fn mul_vector<'a, Z, R>(&'a self, z: &'a [Z]) -> Vec<R>
+    where
+        F: One + PartialEq + Send + Sync,
+        Z: Send + Sync + Mul<&'a F, Output = Z> + Copy,
+        R: Zero + Clone + Send + Sync + AddAssign<Z>,
+    {
+        let mut out = vec![R::zero(); self.num_rows()];
+        self.mul_vector_into(z, &mut out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn matches_dense_product() {
+        let two = Fr::one() + Fr::one();
+        let m: Matrix<Fr> = vec![
+            vec![(Fr::one(), 0), (two, 2)],
+            vec![],
+            vec![(two, 1), (Fr::one(), 1)],
+        ];
+        let z = vec![Fr::from(3u64), Fr::from(5u64), Fr::from(7u64)];
+        let csr = CsrMatrix::from_matrix(&m);
+        assert_eq!(csr.num_rows(), 3);
+        assert_eq!(csr.num_non_zero(), 4);
+        let out: Vec<Fr> = csr.mul_vector(&z);
+        assert_eq!(out, vec![Fr::from(17u64), Fr::zero(), Fr::from(15u64)]);
+    }
+
+    #[test]
+    fn spans_row_blocks() {
+        let m: Matrix<Fr> = (0..3 * ROW_BLOCK + 1).map(|i| vec![(Fr::one(), i)]).collect();
+        let z: Vec<Fr> = (0..m.len() as u64).map(Fr::from).collect();
+        let mut out = vec![Fr::one(); m.len() + 2];
+        CsrMatrix::from_matrix(&m).mul_vector_into(&z, &mut out);
+        assert_eq!(&out[..m.len()], &z[..]);
+        assert_eq!(&out[m.len()..], &[Fr::one(), Fr::one()]);
+    }
+}
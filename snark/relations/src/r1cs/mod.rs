@@ -8,6 +8,7 @@ pub type Result<T> = core::result::Result<T, SynthesisError>;
 #[macro_use]
 mod impl_lc;
 mod constraint_system;
+mod csr;
 mod error;
 #[cfg(feature = "std")]
 mod trace;
@@ -19,9 +20,10 @@ pub use tracing::info_span;
 
 pub use ark_ff::{Field, ToConstraintField};
 pub use constraint_system::{
-    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, Namespace,
+    ConstraintMatrices, ConstraintMatrix, ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, Namespace,
     OptimizationGoal, SynthesisMode,
 };
+pub use csr::CsrMatrix;
 pub use error::SynthesisError;
 
 use core::cmp::Ordering;
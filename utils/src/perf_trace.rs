@@ -35,6 +35,43 @@
 //! in the output.
 pub use self::inner::*;
 
+#[cfg(feature = "std")]
+pub use self::hook::*;
+
+/// Profilers can follow the timers: once a [SpanHook] is set, every `start_timer` calls its
+/// `enter` with the timer's message, and every `end_timer` calls its `exit`.
+#[cfg(feature = "std")]
+mod hook {
+    use std::sync::OnceLock;
+
+    #[derive(Clone, Copy, Debug)]
+    pub struct SpanHook {
+        pub enter: fn(&str),
+        pub exit: fn(),
+    }
+
+    static HOOK: OnceLock<SpanHook> = OnceLock::new();
+
+    /// Set the hook; only the first one set takes effect. Returns whether this one did.
+    pub fn set_span_hook(hook: SpanHook) -> bool {
+        HOOK.set(hook).is_ok()
+    }
+
+    #[doc(hidden)]
+    pub fn enter_span(msg: &str) {
+        if let Some(h) = HOOK.get() {
+            (h.enter)(msg)
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn exit_span() {
+        if let Some(h) = HOOK.get() {
+            (h.exit)()
+        }
+    }
+}
+
 #[macro_use]
 #[cfg(feature = "print-trace")]
 pub mod inner {
@@ -71,6 +108,7 @@ pub mod inner {
 
             $crate::perf_trace::println!("{}{:8} {}", indent, start_info, msg);
             NUM_INDENT.fetch_add(1, Ordering::Relaxed);
+            $crate::perf_trace::enter_span(&msg.to_string());
             $crate::perf_trace::TimerInfo {
                 msg: msg.to_string(),
                 time: Instant::now(),
@@ -91,6 +129,7 @@ pub mod inner {
 
             let time = $time.time;
             let final_time = time.elapsed();
+            $crate::perf_trace::exit_span();
             let final_time = {
                 let secs = final_time.as_secs();
                 let millis = final_time.subsec_millis();